@@ -27,7 +27,7 @@ pub fn parameters(field: &syn::Field, attr_id: &'static str) -> (Vec<syn::Ident>
     let mut exprs = Vec::with_capacity(params.len());
 
     for p in params {
-        if p.ident == "ty" {
+        if p.ident == "ty" || p.ident == "depends_on" || p.ident == "top_level_parent" {
             continue;
         }
 
@@ -38,10 +38,71 @@ pub fn parameters(field: &syn::Field, attr_id: &'static str) -> (Vec<syn::Ident>
     (names, exprs)
 }
 
+/// Returns the value of the `top_level_parent` parameter of a control. Ex: `top_level_parent: true`
+pub fn top_level_parent(field: &syn::Field) -> bool {
+    let member = match field.ident.as_ref() {
+        Some(m) => m,
+        None => unreachable!()
+    };
+
+    let attr = match field.attrs.iter().find(|attr| attr.path.get_ident().map(|id| id == "nwg_control").unwrap_or(false)) {
+        Some(attr) => attr,
+        None => unreachable!()
+    };
+
+    let params: Parameters = match syn::parse2(attr.tokens.clone()) {
+        Ok(a) => a,
+        Err(e) => panic!("Failed to parse field #{}: {}", member, e)
+    };
+
+    match params.params.iter().find(|p| p.ident == "top_level_parent").map(|p| &p.e) {
+        Some(syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Bool(b), .. })) => b.value,
+        Some(_) => panic!("The top_level_parent value of field {} must be `true` or `false`", member),
+        None => false
+    }
+}
+
+/// Returns the fields listed in the `depends_on` parameter of the attribute `attr_id`. Ex: `depends_on: [window, font]`
+pub fn dependencies(field: &syn::Field, attr_id: &'static str) -> Vec<String> {
+    let member = match field.ident.as_ref() {
+        Some(m) => m,
+        None => unreachable!()
+    };
+
+    let attr = match field.attrs.iter().find(|attr| attr.path.get_ident().map(|id| id == attr_id).unwrap_or(false)) {
+        Some(attr) => attr,
+        None => unreachable!()
+    };
+
+    let params: Parameters = match syn::parse2(attr.tokens.clone()) {
+        Ok(a) => a,
+        Err(e) => panic!("Failed to parse field #{}: {}", member, e)
+    };
+
+    let depends_on = match params.params.iter().find(|p| p.ident == "depends_on") {
+        Some(p) => &p.e,
+        None => { return Vec::new(); }
+    };
+
+    let elems: Vec<&syn::Expr> = match depends_on {
+        syn::Expr::Array(a) => a.elems.iter().collect(),
+        syn::Expr::Path(_) => vec![depends_on],
+        _ => panic!("The depends_on value of field {} must be a list of fields. Ex: `depends_on: [window, font]`", member)
+    };
+
+    elems.into_iter()
+        .map(|e| match e {
+            syn::Expr::Path(p) if p.path.get_ident().is_some() => p.path.get_ident().unwrap().to_string(),
+            _ => panic!("The depends_on value of field {} must be a list of fields. Ex: `depends_on: [window, font]`", member)
+        })
+        .collect()
+}
+
 pub fn expand_flags(member_name: &syn::Ident, ty: &syn::Ident, flags: syn::Expr) -> syn::Expr {
     let flags_type = format!("{}Flags", ty);
     
     let flags_value = match &flags {
+        syn::Expr::Verbatim(_) => { return flags; },
         syn::Expr::Lit(expr_lit) => match &expr_lit.lit {
             syn::Lit::Str(value) => value,
             other => panic!("Compressed flags must str, got {:?} for control {}", other, member_name)
@@ -4,6 +4,7 @@ use syn::punctuated::Punctuated;
 use syn::parse::{Parse, ParseStream, ParseBuffer};
 use quote::{ToTokens};
 use std::collections::HashMap;
+use crate::shared::cfg_attributes;
 
 
 /// A callback function definition
@@ -95,13 +96,14 @@ impl Parse for CallbackDefinitions {
 struct EventCallback {
     member: syn::Expr,
     path: syn::Path,
-    args: Punctuated<syn::Expr, Token![,]>
+    args: Punctuated<syn::Expr, Token![,]>,
+    cfg: Vec<syn::Attribute>,
 }
 
 /// Wrapper over a basic event dispatcher
 pub struct ControlEvents {
     partial: bool,
-    handles: Vec<syn::Ident>,
+    handles: Vec<(syn::Ident, Vec<syn::Attribute>)>,
     callbacks: HashMap<syn::Pat, Vec<EventCallback>>,
     partials_callbacks: Vec<pm2::TokenStream>,
     callback_args_cache: HashMap<usize, syn::Expr>,
@@ -132,12 +134,13 @@ impl ControlEvents {
         let member = field.ident.as_ref().expect("Cannot find member name when generating control");
 
         if top_level_window(field) {
-            self.handles.push(member.clone());
+            self.handles.push((member.clone(), cfg_attributes(field)));
         }
     }
 
-    pub fn add_partial(&mut self, id: &syn::Ident) {
+    pub fn add_partial(&mut self, id: &syn::Ident, cfg: &[syn::Attribute]) {
         self.partials_callbacks.push(quote! {
+            #(#cfg)*
             evt_ui.#id.process_event(_evt, &_evt_data, _handle);
         })
     }
@@ -167,7 +170,8 @@ impl ControlEvents {
                 let callback = EventCallback {
                     member: Self::parse_member(&callback_def.field_name, &member),
                     path: cb_fn.path.clone(),
-                    args: map_callback_args(&member, &cb_fn.args, &self.callback_args_cache)
+                    args: map_callback_args(&member, &cb_fn.args, &self.callback_args_cache),
+                    cfg: cfg_attributes(field),
                 };
 
                 evt_callbacks.push(callback);
@@ -189,7 +193,8 @@ impl ControlEvents {
 impl ToTokens for ControlEvents {
 
     fn to_tokens(&self, tokens: &mut pm2::TokenStream) {
-        let handles = &self.handles;
+        let handles = self.handles.iter().map(|(id, _)| id);
+        let handles_cfg = self.handles.iter().map(|(_, cfg)| cfg);
 
         let mut pats: Vec<&syn::Pat> = Vec::with_capacity(self.callbacks.len());
         let partial_callbacks = &self.partials_callbacks;
@@ -204,7 +209,7 @@ impl ToTokens for ControlEvents {
             quote! {
                 let evt_ui = self;
 
-                #( #partial_callbacks );*
+                #( #partial_callbacks )*
 
                 match _evt { 
                     #( #pats => #callbacks ),*
@@ -213,13 +218,18 @@ impl ToTokens for ControlEvents {
             }
         } else {
             quote! {
-                let window_handles: &[&ControlHandle] = &[#(&ui.#handles.handle),*];
+                let mut window_handles: Vec<&ControlHandle> = Vec::new();
+                #(
+                    #(#handles_cfg)*
+                    window_handles.push(&ui.#handles.handle);
+                )*
+
                 for handle in window_handles.iter() {
                     let evt_ui = Rc::downgrade(&inner);
                     let handle_events = move |_evt, _evt_data, _handle| {
 
                         if let Some(evt_ui) = evt_ui.upgrade() {
-                            #( #partial_callbacks );*
+                            #( #partial_callbacks )*
                             match _evt { 
                                 #( #pats => #callbacks ),*
                                 _ => {}
@@ -246,6 +256,29 @@ impl<'a> ToTokens for EventCallbackCol<'a> {
     fn to_tokens(&self, tokens: &mut pm2::TokenStream) {
         let cb = &self.0;
 
+        // The callbacks of the fields behind a `cfg` attribute are checked in separate statements, so that they can be removed
+        if cb.iter().any(|c| !c.cfg.is_empty()) {
+            let mut members_callbacks: HashMap<&syn::Expr, MemberCallbacks> = HashMap::new();
+            for c in cb.iter() {
+                let mc = members_callbacks.entry(&c.member).or_insert((&c.cfg, Vec::new()));
+                mc.1.push((&c.path, &c.args));
+            }
+
+            let members = members_callbacks.keys();
+            let cfg = members_callbacks.values().map(|(cfg, _)| cfg);
+            let values = members_callbacks.values().map(|(_, c)| PathArgs(c));
+
+            let tk = quote!{{
+                #(
+                    #(#cfg)*
+                    if &_handle == &#members { #values }
+                )*
+            }};
+
+            tk.to_tokens(tokens);
+            return;
+        }
+
         let tk = match cb.len() {
             0 => quote!{ {} },
             1 => {
@@ -283,6 +316,9 @@ impl<'a> ToTokens for EventCallbackCol<'a> {
 }
 
 
+/// The `cfg` attributes of a member and its callbacks
+type MemberCallbacks<'a> = (&'a [syn::Attribute], Vec<(&'a syn::Path, &'a Args)>);
+
 type Args = Punctuated<syn::Expr, Token![,]>;
 struct PathArgs<'a> (&'a [(&'a syn::Path, &'a Args)]);
 
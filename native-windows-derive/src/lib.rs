@@ -152,10 +152,56 @@ nwg::TextInput::builder()
     .build(&mut data.text_edit);
 ```
 
+## Automatic parent
+
+A control without a `parent` parameter is parented to the nearest container declared before it (a window, a tab container, a tab,
+a message window or an extern canvas). Set `top_level_parent: true` to use the nearest top level window instead (a window, a message window
+or an extern canvas). This is useful for the invisible controls of a background application, ex: a timer or a tray notification that must
+be parented to a `MessageWindow` declared before the visible windows.
+
+```
+#[nwg_control]
+host: nwg::MessageWindow,
+
+#[nwg_control(interval: 1000, top_level_parent: true)]
+timer: nwg::Timer,
+```
+
+## Raw expressions
+
+Builder values are parsed as rust expressions, so closures can be passed to the builders that accept them. 
+A value that cannot be parsed in an attribute (ex: a top level comparison with generics) can be wrapped in `expr!( ... )`.
+The tokens of a raw expression are passed to the builder unchanged. A raw `flags` value is not expanded and a raw `parent` value is not detected.
+
+```
+#[nwg_control(
+    validator: |text: &str| !text.is_empty(),
+    items: expr!(load_items::<String, u32>("items.txt", 10)),
+    flags: expr!(nwg::TextInputFlags::VISIBLE | nwg::TextInputFlags::TAB_STOP)
+)]
+input: MyInput,
+```
+
+Closures are built before the ui struct exists, so they cannot borrow `data`. Use the `OnInit` event to bind the behaviors that need the ui.
+
 ## Resources
 
 Use the `nwg_resource` to generate a resource from a struct field. It works the exact same way as `nwg_controls`. 
-Resources are always instanced before the controls.
+Resources are instanced before the controls, unless they have dependencies.
+
+## Build order
+
+Resources are built first, in the field order, followed by the controls (sorted so that a parent is built before its children).
+If a resource or a control needs another field that would be built later, list this field in the `depends_on` parameter.
+`depends_on` is accepted by `nwg_control` and `nwg_resource`, and the item is moved after its dependencies.
+
+```
+#[nwg_control]
+window: nwg::Window,
+
+#[nwg_resource(parent: &data.window, depends_on: [window])]
+shortcuts: MyShortcuts,
+```
 
 ## Events
 
@@ -219,6 +265,21 @@ Under the hood, both these attribute work the same way as `nwg_control`. `nwg_la
 
 NWD cannot guess the parent of layout items.
 
+## Optional fields
+
+The `cfg` attributes of a field are copied on the code generated for the field: its builder, its events, its layout item and its partial.
+A field can be removed by a feature of the application without breaking `build_ui`. The fields that use an optional field
+(ex: the children of an optional container) must have the same `cfg` attribute.
+Auto parent detection does not skip the optional containers, so the controls that follow an optional container should have an explicit `parent`.
+
+```
+#[nwg_control(text: "Export")]
+#[nwg_layout_item(layout: layout, col: 0, row: 1)]
+#[nwg_events( OnButtonClick: [App::export] )]
+#[cfg(feature = "export")]
+export_button: nwg::Button,
+```
+
 ## Partials
 
 Use the `nwg_partial` attribute to instance a partial from a struct field:
@@ -262,8 +323,7 @@ pub fn derive_ui(input: pm::TokenStream) -> pm::TokenStream {
     let generic_names = quote! { #lt #generic_names #gt }; // <'a, T, C>
 
     let ui = NwgUi::build(&ui_data, false);
-    let controls = ui.controls();
-    let resources = ui.resources();
+    let build_order = ui.build_order();
     let partials = ui.partials();
     let layouts = ui.layouts();
     let events = ui.events();
@@ -293,8 +353,7 @@ pub fn derive_ui(input: pm::TokenStream) -> pm::TokenStream {
 
             impl #generics NativeUi<#ui_struct_name #generic_names> for #struct_name #generic_names #where_clause {
                 fn build_ui(mut data: Self) -> Result<#ui_struct_name #generic_names, NwgError> {
-                    #resources
-                    #controls
+                    #build_order
                     #partials
 
                     let inner = Rc::new(data);
@@ -390,8 +449,7 @@ pub fn derive_partial(input: pm::TokenStream) -> pm::TokenStream {
 
     let ui_data = parse_ui_data(&base).expect("NWG derive can only be implemented on structs");
     let ui = NwgUi::build(&ui_data, true);
-    let controls = ui.controls();
-    let resources = ui.resources();
+    let build_order = ui.build_order();
     let partials = ui.partials();
     let layouts = ui.layouts();
     let events = ui.events();
@@ -417,8 +475,7 @@ pub fn derive_partial(input: pm::TokenStream) -> pm::TokenStream {
                     let parent = _parent.map(|p| p.into());
                     let parent_ref = parent.as_ref();
                     
-                    #resources
-                    #controls
+                    #build_order
                     #partials
 
                     let ui = data;
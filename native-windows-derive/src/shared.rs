@@ -14,11 +14,24 @@ impl Parse for Param {
         Ok(Param {
             ident: input.parse()?,
             sep: input.parse()?,
-            e: input.parse()?,
+            e: parse_value(input)?,
         })
     }
 }
 
+/// Parse a parameter value. `expr!( ... )` is a raw expression: its tokens are passed to the builder without being parsed.
+fn parse_value(input: ParseStream) -> syn::Result<syn::Expr> {
+    let raw = input.peek(syn::Ident) && input.peek2(Token![!]) &&
+        input.fork().parse::<syn::Ident>().map(|id| id == "expr").unwrap_or(false);
+
+    if raw {
+        let raw_expr: syn::Macro = input.parse()?;
+        Ok(syn::Expr::Verbatim(raw_expr.tokens))
+    } else {
+        input.parse()
+    }
+}
+
 #[derive(Debug)]
 pub struct Parameters {
     pub params: Punctuated<Param, Token![,]>
@@ -46,3 +59,13 @@ impl Parse for Parameters {
         Ok(parameters)
     }
 }
+
+
+/// Returns the `cfg` attributes of a field. The code generated for the field is put behind the same attributes,
+/// so that the field can be removed by a feature.
+pub fn cfg_attributes(field: &syn::Field) -> Vec<syn::Attribute> {
+    field.attrs.iter()
+        .filter(|attr| attr.path.is_ident("cfg"))
+        .cloned()
+        .collect()
+}
@@ -1,7 +1,7 @@
 use quote::{ToTokens};
 use crate::layouts::{LayoutChild, FlexboxLayoutChild, GridLayoutChild, layout_parameters};
 use crate::events::ControlEvents;
-use crate::shared::Parameters;
+use crate::shared::{Parameters, cfg_attributes};
 
 const TOP_LEVEL: &'static [&'static str] = &[
     "Window", "MessageWindow", "ExternCanvas"
@@ -12,6 +12,7 @@ const AUTO_PARENT: &'static [&'static str] = &[
 ];
 
 
+
 struct NwgControl<'a> {
     id: &'a syn::Ident,
     parent_id: Option<String>,
@@ -23,6 +24,11 @@ struct NwgControl<'a> {
 
     names: Vec<syn::Ident>,
     values: Vec<syn::Expr>,
+    depends_on: Vec<String>,
+    cfg: Vec<syn::Attribute>,
+
+    // If the automatic parent is the nearest top level window instead of the nearest container
+    top_level_parent: bool,
 
     // First value if the parent order, second value is the insert order
     weight: [u16; 2],
@@ -95,6 +101,7 @@ impl<'a> NwgControl<'a> {
                 self.parent_id = Some(id.to_string());
                 syn::parse_str(&format!("&data.{}", id)).unwrap()
             },
+            syn::Expr::Verbatim(_) => { return; },
             _ => { panic!("Bad expression type for parent of field {}", self.id); }
         };
         
@@ -109,6 +116,8 @@ struct NwgResource<'a> {
     ty: syn::Ident,
     names: Vec<syn::Ident>,
     values: Vec<syn::Expr>,
+    depends_on: Vec<String>,
+    cfg: Vec<syn::Attribute>,
 }
 
 impl<'a> NwgResource<'a> {
@@ -165,6 +174,7 @@ struct NwgLayout<'a> {
     ty: &'a syn::Ident,
     names: Vec<syn::Ident>,
     values: Vec<syn::Expr>,
+    cfg: Vec<syn::Attribute>,
 }
 
 impl<'a> NwgLayout<'a> {
@@ -201,6 +211,7 @@ impl<'a> NwgLayout<'a> {
                 let id = &p.path.segments.last().unwrap().ident;
                 syn::parse_str(&format!("&ui.{}", id)).unwrap()
             },
+            syn::Expr::Verbatim(_) => { return; },
             _ => { panic!("Bad expression type for parent of field {}", self.id); }
         };
         
@@ -213,6 +224,7 @@ struct NwgPartial<'a> {
     id: &'a syn::Ident,
     ty: &'a syn::Ident,
     parent: Option<syn::Ident>,
+    cfg: Vec<syn::Attribute>,
 }
 
 
@@ -264,90 +276,79 @@ impl<'a> NwgPartial<'a> {
 }
 
 
-pub struct NwgUiControls<'a>(&'a NwgUi<'a>);
+/// A control or a resource of the ui, in the order they are built. Holds the index of the item in `NwgUi`.
+#[derive(Copy, Clone)]
+enum BuildItem {
+    Resource(usize),
+    Control(usize),
+}
 
-impl<'a> ToTokens for NwgUiControls<'a> {
+struct ControlGen<'b> {
+    item: &'b NwgControl<'b>
+}
 
+impl<'b> ToTokens for ControlGen<'b> {
     fn to_tokens(&self, tokens: &mut pm2::TokenStream) {
+        let item = &self.item;
+        let ty = &item.ty;
+        let member = item.id;
+        let names = &item.names;
+        let values = &item.values;
+        let cfg = &item.cfg;
+        let control_tk = quote! {
+            #(#cfg)*
+            #ty::builder()
+                #(.#names(#values))*
+                .build(&mut data.#member)?;
+        };
 
-        struct ControlGen<'b> {
-            item: &'b NwgControl<'b>
-        }
-
-        impl<'b> ToTokens for ControlGen<'b> {
-            fn to_tokens(&self, tokens: &mut pm2::TokenStream) {
-                let item = &self.item;
-                let ty = &item.ty;
-                let member = item.id;
-                let names = &item.names;
-                let values = &item.values;
-                let control_tk = quote! {
-                    #ty::builder()
-                        #(.#names(#values))*
-                        .build(&mut data.#member)?;
-                };
-
-                control_tk.to_tokens(tokens);
-            }
-        }
+        control_tk.to_tokens(tokens);
+    }
+}
 
-        let ui = &self.0;
-        let controls: Vec<ControlGen> = ui.controls.iter()
-            .map(|item| ControlGen { item })
-            .collect();
+struct ResourceGen<'b> {
+    item: &'b NwgResource<'b>
+}
 
-        let controls_tk = quote! {
-            #(#controls)*
+impl<'b> ToTokens for ResourceGen<'b> {
+    fn to_tokens(&self, tokens: &mut pm2::TokenStream) {
+        let item = &self.item;
+        let ty = &item.ty;
+        let member = item.id;
+        let names = &item.names;
+        let values = &item.values;
+        let cfg = &item.cfg;
+        let resource_tk = quote! {
+            #(#cfg)*
+            #ty::builder()
+                #(.#names(#values))*
+                .build(&mut data.#member)?;
         };
 
-        controls_tk.to_tokens(tokens);
+        resource_tk.to_tokens(tokens);
     }
-
 }
 
-pub struct NwgUiResources<'a>(&'a NwgUi<'a>);
 
-impl<'a> ToTokens for NwgUiResources<'a> {
+/// Builds the resources and the controls. By default, the resources are built first, followed by the controls
+/// sorted by parent. The `depends_on` parameter moves an item after its dependencies.
+pub struct NwgUiBuildOrder<'a>(&'a NwgUi<'a>);
 
-    fn to_tokens(&self, tokens: &mut pm2::TokenStream) {
-        
-        struct ResourceGen<'b> {
-            item: &'b NwgResource<'b>
-        }
-
-        impl<'b> ToTokens for ResourceGen<'b> {
-            fn to_tokens(&self, tokens: &mut pm2::TokenStream) {
-                let item = &self.item;
-                let ty = &item.ty;
-                let member = item.id;
-                let names = &item.names;
-                let values = &item.values;
-                let resource_tk = quote! {
-                    #ty::builder()
-                        #(.#names(#values))*
-                        .build(&mut data.#member)?;
-                };
+impl<'a> ToTokens for NwgUiBuildOrder<'a> {
 
-                resource_tk.to_tokens(tokens);
+    fn to_tokens(&self, tokens: &mut pm2::TokenStream) {
+        let ui = &self.0;
+        for item in ui.build_order.iter() {
+            match *item {
+                BuildItem::Resource(i) => ResourceGen { item: &ui.resources[i] }.to_tokens(tokens),
+                BuildItem::Control(i) => ControlGen { item: &ui.controls[i] }.to_tokens(tokens),
             }
         }
-
-        let ui = &self.0;
-        let resources: Vec<ResourceGen> = ui.resources.iter()
-            .map(|item| ResourceGen { item })
-            .collect();
-
-        let resources_tk = quote! {
-            #(#resources)*
-        };
-
-        resources_tk.to_tokens(tokens);
     }
 
 }
 
 
-
 pub struct NwgUiEvents<'a>(&'a NwgUi<'a>);
 
 impl<'a> ToTokens for NwgUiEvents<'a> {
@@ -401,13 +402,24 @@ impl<'a> ToTokens for NwgUiLayouts<'a> {
                 let id = &self.layout.id;
                 let names = &self.layout.names;
                 let values = &self.layout.values;
+                let cfg = &self.layout.cfg;
                 let children = &self.children;
+                let children_cfg = self.children.iter().map(|c| &c.0.cfg);
 
+                // The children are added one statement at a time so that they can be behind a `cfg` attribute
                 let layout_tk = quote! {
-                    #ty::builder()
-                        #(.#names(#values))*
-                        #(.#children)*
-                        .build(&ui.#id)?;
+                    #(#cfg)*
+                    {
+                        let layout_builder = #ty::builder()
+                            #(.#names(#values))*;
+
+                        #(
+                            #(#children_cfg)*
+                            let layout_builder = layout_builder.#children;
+                        )*
+
+                        layout_builder.build(&ui.#id)?;
+                    }
                 };
                 layout_tk.to_tokens(tokens);
             }
@@ -450,13 +462,16 @@ impl<'a> ToTokens for NwgUiPartials<'a> {
                 let ty = &i.ty;
                 let id = &i.id;
                 let parent = &i.parent;
+                let cfg = &i.cfg;
 
                 let partial_tk = if parent.is_none() {
                     quote! {
+                        #(#cfg)*
                         #ty::build_partial::<&Window>(&mut data.#id, None)?;
                     }
                 } else {
                     quote! {
+                        #(#cfg)*
                         #ty::build_partial(&mut data.#id, Some(&data.#parent))?;
                     }
                 };
@@ -483,6 +498,7 @@ impl<'a> ToTokens for NwgUiPartials<'a> {
 pub struct NwgUi<'a> {
     controls: Vec<NwgControl<'a>>,
     resources: Vec<NwgResource<'a>>,
+    build_order: Vec<BuildItem>,
     layouts: Vec<NwgLayout<'a>>,
     partials: Vec<NwgPartial<'a>>,
     events: ControlEvents,
@@ -511,6 +527,8 @@ impl<'a> NwgUi<'a> {
                 let id = field.ident.as_ref().unwrap();
                 let ty = NwgControl::parse_type(field);
                 let (names, values) = crate::controls::parameters(field, "nwg_control");
+                let depends_on = crate::controls::dependencies(field, "nwg_control");
+                let top_level_parent = crate::controls::top_level_parent(field);
 
                 let f = NwgControl {
                     id,
@@ -520,6 +538,9 @@ impl<'a> NwgUi<'a> {
                     layout_index: 0,
                     names,
                     values,
+                    depends_on,
+                    cfg: cfg_attributes(field),
+                    top_level_parent,
                     weight: [0, field_pos as u16],
                 };
 
@@ -533,12 +554,15 @@ impl<'a> NwgUi<'a> {
                 let id = field.ident.as_ref().unwrap();
                 let ty = NwgResource::parse_type(field);
                 let (names, values) = crate::controls::parameters(field, "nwg_resource");
+                let depends_on = crate::controls::dependencies(field, "nwg_resource");
                 
                 let f = NwgResource {
                     id,
                     ty,
                     names,
                     values,
+                    depends_on,
+                    cfg: cfg_attributes(field),
                 };
 
                 resources.push(f);
@@ -551,6 +575,7 @@ impl<'a> NwgUi<'a> {
 
                 let layout = NwgLayout {
                     id, ty, names, values,
+                    cfg: cfg_attributes(field),
                 };
 
                 layouts.push(layout);
@@ -561,9 +586,10 @@ impl<'a> NwgUi<'a> {
                     id: field.ident.as_ref().unwrap(),
                     ty: NwgPartial::parse_type(field),
                     parent: NwgPartial::parse_parent(field),
+                    cfg: cfg_attributes(field),
                 };
 
-                events.add_partial(&partial.id);
+                events.add_partial(&partial.id, &partial.cfg);
                 events.parse(field);
 
                 partials.push(partial);
@@ -609,9 +635,10 @@ impl<'a> NwgUi<'a> {
                 controls[i].expand_parent();
             } else {
                 // Rewind the controls set the parent to the nearest control that supports children
+                let candidates = if controls[i].top_level_parent { TOP_LEVEL } else { AUTO_PARENT };
                 let parent = controls[0..i]
                     .iter().rev()
-                    .find(|i| AUTO_PARENT.iter().any(|top| i.ty == top) );
+                    .find(|i| candidates.iter().any(|top| i.ty == top) );
             
                 if let Some(parent) = parent {
                     let parent_id = Some(parent.id.to_string());
@@ -658,15 +685,64 @@ impl<'a> NwgUi<'a> {
             a.cmp(&b)
         });
 
-        NwgUi { controls, resources, layouts, partials, events }
+        let build_order = Self::sort_build_order(&resources, &controls);
+
+        NwgUi { controls, resources, build_order, layouts, partials, events }
     }
 
-    pub fn controls(&self) -> NwgUiControls {
-        NwgUiControls(self)
+    /// Order the resources and the controls so that every item is built after its dependencies (the fields in `depends_on` and the parent of the controls).
+    /// Items without dependencies keep their default order.
+    fn sort_build_order(resources: &[NwgResource], controls: &[NwgControl]) -> Vec<BuildItem> {
+        let mut pending: Vec<BuildItem> = (0..resources.len()).map(BuildItem::Resource)
+            .chain((0..controls.len()).map(BuildItem::Control))
+            .collect();
+
+        let id = |item: &BuildItem| match *item {
+            BuildItem::Resource(i) => resources[i].id.to_string(),
+            BuildItem::Control(i) => controls[i].id.to_string(),
+        };
+
+        let ids: Vec<String> = pending.iter().map(&id).collect();
+
+        let dependencies = |item: &BuildItem| -> Vec<String> {
+            let (member, depends_on, parent) = match *item {
+                BuildItem::Resource(i) => (resources[i].id, &resources[i].depends_on, None),
+                BuildItem::Control(i) => (controls[i].id, &controls[i].depends_on, controls[i].parent_id.as_ref()),
+            };
+
+            if let Some(unknown) = depends_on.iter().find(|d| !ids.contains(*d)) {
+                panic!("Field {} depends on {}, but {} is not a control or a resource", member, unknown, unknown);
+            }
+
+            depends_on.iter()
+                .chain(parent.into_iter().filter(|p| ids.contains(*p)))
+                .cloned()
+                .collect()
+        };
+
+        let mut order: Vec<BuildItem> = Vec::with_capacity(pending.len());
+        let mut built: Vec<String> = Vec::with_capacity(pending.len());
+
+        while !pending.is_empty() {
+            let next = pending.iter().position(|item| dependencies(item).iter().all(|d| built.contains(d)));
+            match next {
+                Some(i) => {
+                    let item = pending.remove(i);
+                    built.push(id(&item));
+                    order.push(item);
+                },
+                None => {
+                    let fields: Vec<String> = pending.iter().map(&id).collect();
+                    panic!("Circular dependency between the fields: {}", fields.join(", "));
+                }
+            }
+        }
+
+        order
     }
 
-    pub fn resources(&self) -> NwgUiResources {
-        NwgUiResources(self)
+    pub fn build_order(&self) -> NwgUiBuildOrder {
+        NwgUiBuildOrder(self)
     }
 
     pub fn events(&self) -> NwgUiEvents {
@@ -682,3 +758,163 @@ impl<'a> NwgUi<'a> {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_order(source: &str) -> Vec<String> {
+        let input: syn::DeriveInput = syn::parse_str(source).unwrap();
+        let data = match &input.data {
+            syn::Data::Struct(s) => s,
+            _ => unreachable!()
+        };
+
+        let ui = NwgUi::build(data, false);
+        ui.build_order.iter()
+            .map(|item| match *item {
+                BuildItem::Resource(i) => ui.resources[i].id.to_string(),
+                BuildItem::Control(i) => ui.controls[i].id.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn default_order_builds_resources_first() {
+        let order = build_order("struct App {
+            #[nwg_control]
+            window: Window,
+
+            #[nwg_control]
+            button: Button,
+
+            #[nwg_resource]
+            font: Font,
+        }");
+
+        assert_eq!(order, vec!["font", "window", "button"]);
+    }
+
+    #[test]
+    fn depends_on_moves_after_dependencies() {
+        let order = build_order("struct App {
+            #[nwg_resource(depends_on: [window])]
+            icon: Icon,
+
+            #[nwg_control]
+            window: Window,
+
+            #[nwg_control]
+            button: Button,
+        }");
+
+        assert_eq!(order, vec!["window", "icon", "button"]);
+    }
+
+    #[test]
+    fn children_are_built_after_their_parent() {
+        let order = build_order("struct App {
+            #[nwg_control]
+            window: Window,
+
+            #[nwg_control(parent: window, depends_on: [label])]
+            button: Button,
+
+            #[nwg_control(parent: window)]
+            label: Label,
+        }");
+
+        assert_eq!(order, vec!["window", "label", "button"]);
+    }
+
+    fn parents(source: &str) -> Vec<(String, Option<String>)> {
+        let input: syn::DeriveInput = syn::parse_str(source).unwrap();
+        let data = match &input.data {
+            syn::Data::Struct(s) => s,
+            _ => unreachable!()
+        };
+
+        let ui = NwgUi::build(data, false);
+        ui.controls.iter().map(|c| (c.id.to_string(), c.parent_id.clone())).collect()
+    }
+
+    fn parent_of<'a>(parents: &'a [(String, Option<String>)], id: &str) -> Option<&'a str> {
+        parents.iter().find(|(c, _)| c == id).and_then(|(_, p)| p.as_deref())
+    }
+
+    #[test]
+    fn auto_parent_is_the_nearest_container() {
+        let parents = parents("struct App {
+            #[nwg_control]
+            host: MessageWindow,
+
+            #[nwg_control]
+            window: Window,
+
+            #[nwg_control]
+            tabs: TabsContainer,
+
+            #[nwg_control]
+            tab: Tab,
+
+            #[nwg_control]
+            timer: Timer,
+
+            #[nwg_control]
+            button: Button,
+        }");
+
+        assert_eq!(parent_of(&parents, "tabs"), Some("window"));
+        assert_eq!(parent_of(&parents, "tab"), Some("tabs"));
+        assert_eq!(parent_of(&parents, "timer"), Some("tab"));
+        assert_eq!(parent_of(&parents, "button"), Some("tab"));
+    }
+
+    #[test]
+    fn top_level_parent_skips_the_containers() {
+        let parents = parents("struct App {
+            #[nwg_control]
+            host: MessageWindow,
+
+            #[nwg_control(top_level_parent: true)]
+            tray: TrayNotification,
+
+            #[nwg_control]
+            window: Window,
+
+            #[nwg_control]
+            tabs: TabsContainer,
+
+            #[nwg_control(interval: 1000, top_level_parent: true)]
+            timer: Timer,
+
+            #[nwg_control(top_level_parent: false)]
+            notice: Notice,
+        }");
+
+        assert_eq!(parent_of(&parents, "tray"), Some("host"));
+        assert_eq!(parent_of(&parents, "timer"), Some("window"));
+        assert_eq!(parent_of(&parents, "notice"), Some("tabs"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Circular dependency")]
+    fn circular_dependencies_panic() {
+        build_order("struct App {
+            #[nwg_resource(depends_on: [b])]
+            a: Font,
+
+            #[nwg_resource(depends_on: [a])]
+            b: Font,
+        }");
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a control or a resource")]
+    fn unknown_dependency_panics() {
+        build_order("struct App {
+            #[nwg_resource(depends_on: [missing])]
+            font: Font,
+        }");
+    }
+}
@@ -0,0 +1,298 @@
+/*!
+    Futures integrated with the event loop of native-windows-gui.
+
+    `spawn_local` runs a future on the GUI thread. The future is polled by the event loop (`dispatch_thread_events`), so an event handler
+    can start a task and return right away. The task can then `.await` the result of a dialog (`FileDialog::run_async`, `ColorDialog::run_async`)
+    or of a long operation running on another thread (`spawn_blocking`) while the other windows keep processing their events.
+
+    The tasks are not `Send`: they can hold the controls of the application (ex: an `Rc` of the application struct).
+    A modal dialog opened by a task runs its own message loop, which also polls the other tasks. A dialog opened while another dialog
+    is still open must be closed first, like the dialogs opened from the event handlers.
+
+    Requires the `asyncio` feature.
+
+    ```rust
+    use native_windows_gui as nwg;
+    use std::rc::Rc;
+
+    struct App {
+        window: nwg::Window,
+        dialog: nwg::FileDialog,
+        status: nwg::StatusBar,
+    }
+
+    fn open_file(app: &Rc<App>) {
+        let app = app.clone();
+        nwg::asyncio::spawn_local(async move {
+            if !app.dialog.run_async(Some(&app.window)).await {
+                return;
+            }
+
+            let path = app.dialog.get_selected_item().unwrap();
+            let size = nwg::asyncio::spawn_blocking(move || std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)).await;
+            app.status.set_text(0, &format!("{} bytes", size));
+        });
+    }
+    ```
+*/
+use winapi::shared::windef::HWND;
+use winapi::shared::minwindef::WPARAM;
+use crate::win32::window::create_message_window;
+use crate::win32::window_helper as wh;
+use crate::bind_raw_event_handler_inner;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+
+/// The id of the raw handler that polls the tasks in the executor window
+const EXECUTOR_HANDLER_ID: usize = 0x1000;
+
+
+struct Task {
+    /// The future of the task. `None` while the task is polled.
+    future: RefCell<Option<Pin<Box<dyn Future<Output = ()>>>>>,
+
+    /// If the task was woken while it was polled (ex: by a task that ran in the message loop of a dialog)
+    woken: Cell<bool>,
+}
+
+struct Executor {
+    window: HWND,
+    tasks: HashMap<usize, Rc<Task>>,
+    next_id: usize,
+}
+
+thread_local! {
+    /// The executor of the GUI thread. Created by the first call to `spawn_local`.
+    static EXECUTOR: RefCell<Option<Executor>> = RefCell::new(None);
+}
+
+/// Wakes a task by posting its id to the executor window. The message can be posted from any thread.
+struct TaskWaker {
+    window: usize,
+    id: usize,
+}
+
+impl Wake for TaskWaker {
+
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        wh::post_message(self.window as HWND, wh::NWG_ASYNC_WAKE, self.id as WPARAM, 0);
+    }
+
+}
+
+
+/**
+    Run `future` on the current thread. The future is first polled by the event loop, after the current event handler returns.
+    The task is dropped when the future completes.
+
+    Panics if `nwg::init` was not called.
+*/
+pub fn spawn_local<F>(future: F)
+    where F: Future<Output = ()> + 'static
+{
+    EXECUTOR.with(|executor| {
+        let mut executor = executor.borrow_mut();
+        if executor.is_none() {
+            *executor = Some(build_executor());
+        }
+
+        let executor = executor.as_mut().unwrap();
+        let id = executor.next_id;
+        executor.next_id += 1;
+
+        let task = Task {
+            future: RefCell::new(Some(Box::pin(future))),
+            woken: Cell::new(false),
+        };
+
+        executor.tasks.insert(id, Rc::new(task));
+        wh::post_message(executor.window, wh::NWG_ASYNC_WAKE, id as WPARAM, 0);
+    });
+}
+
+fn build_executor() -> Executor {
+    let window = create_message_window().expect("nwg::init must be called before nwg::asyncio::spawn_local");
+
+    bind_raw_event_handler_inner(&window, EXECUTOR_HANDLER_ID, |_hwnd, msg, w, _| {
+        match msg {
+            wh::NWG_ASYNC_WAKE => {
+                poll_task(w as usize);
+                Some(0)
+            },
+            _ => None
+        }
+    }).unwrap();
+
+    Executor {
+        window: window.hwnd().unwrap(),
+        tasks: HashMap::new(),
+        next_id: 0,
+    }
+}
+
+/// Poll the task `id`. The executor is not borrowed while the task runs, so the task can spawn other tasks.
+fn poll_task(id: usize) {
+    let found = EXECUTOR.with(|executor| {
+        executor.borrow().as_ref()
+            .and_then(|e| e.tasks.get(&id).map(|task| (task.clone(), e.window)))
+    });
+
+    let (task, window) = match found {
+        Some(found) => found,
+        None => { return; }
+    };
+
+    let mut future = match task.future.borrow_mut().take() {
+        Some(future) => future,
+        None => {
+            // The task is polled further down the stack. It is polled again when that poll returns.
+            task.woken.set(true);
+            return;
+        }
+    };
+
+    let waker = Waker::from(Arc::new(TaskWaker { window: window as usize, id }));
+    let mut context = Context::from_waker(&waker);
+
+    match future.as_mut().poll(&mut context) {
+        Poll::Ready(()) => {
+            EXECUTOR.with(|executor| {
+                if let Some(executor) = executor.borrow_mut().as_mut() {
+                    executor.tasks.remove(&id);
+                }
+            });
+        },
+        Poll::Pending => {
+            *task.future.borrow_mut() = Some(future);
+            if task.woken.replace(false) {
+                waker.wake();
+            }
+        }
+    }
+}
+
+
+/**
+    A future that runs a blocking function of the GUI thread (ex: a modal dialog) when it is polled. See `deferred`.
+*/
+pub struct Deferred<F> {
+    function: Option<F>,
+}
+
+impl<F> Unpin for Deferred<F> {}
+
+impl<T, F: FnOnce() -> T> Future for Deferred<F> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context) -> Poll<T> {
+        let function = self.function.take().expect("Deferred polled after completion");
+        Poll::Ready(function())
+    }
+}
+
+/**
+    Returns a future that calls `function` when it is awaited. Use it to run a blocking function of the GUI thread
+    (ex: `nwg::modal_info_message`) from a task: the function is called by the event loop and not by the event handler that spawned the task.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    async fn confirm(window: &nwg::Window) -> bool {
+        let choice = nwg::asyncio::deferred(|| nwg::modal_message(window, &nwg::MessageParams {
+            title: "Confirm",
+            content: "Overwrite the file?",
+            buttons: nwg::MessageButtons::YesNo,
+            icons: nwg::MessageIcons::Question
+        })).await;
+
+        choice == nwg::MessageChoice::Yes
+    }
+    ```
+*/
+pub fn deferred<T, F: FnOnce() -> T>(function: F) -> Deferred<F> {
+    Deferred { function: Some(function) }
+}
+
+
+struct BackgroundState {
+    done: bool,
+    waker: Option<Waker>,
+}
+
+/// Marks the background operation as done and wakes the task, even if the operation panicked
+struct BackgroundDone(Arc<Mutex<BackgroundState>>);
+
+impl Drop for BackgroundDone {
+    fn drop(&mut self) {
+        let waker = {
+            let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+            state.done = true;
+            state.waker.take()
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/**
+    A future that resolves to the result of an operation running on another thread. See `spawn_blocking`.
+*/
+pub struct Background<T> {
+    state: Arc<Mutex<BackgroundState>>,
+    thread: Option<thread::JoinHandle<T>>,
+}
+
+impl<T> Unpin for Background<T> {}
+
+impl<T> Future for Background<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<T> {
+        {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            if !state.done {
+                state.waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+        }
+
+        let thread = self.thread.take().expect("Background polled after completion");
+        match thread.join() {
+            Ok(value) => Poll::Ready(value),
+            Err(panic) => std::panic::resume_unwind(panic)
+        }
+    }
+}
+
+/**
+    Run `function` on a new thread and returns a future that resolves to its result. The GUI thread keeps processing its events
+    while the function runs. If the function panics, the panic is resumed in the task that awaits the result.
+
+    `function` must not use the controls of the application: they can only be used from the GUI thread.
+*/
+pub fn spawn_blocking<T, F>(function: F) -> Background<T>
+    where T: Send + 'static,
+          F: FnOnce() -> T + Send + 'static
+{
+    let state = Arc::new(Mutex::new(BackgroundState { done: false, waker: None }));
+    let done = BackgroundDone(state.clone());
+
+    let thread = thread::spawn(move || {
+        let _done = done;
+        function()
+    });
+
+    Background { state, thread: Some(thread) }
+}
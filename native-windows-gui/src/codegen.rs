@@ -0,0 +1,435 @@
+/*!
+    Generate the source of a native-windows-derive UI struct from a description of the controls. Used by the gui designers
+    to save the designed interface as idiomatic NWG code.
+
+    The description (`UiDefinition`) can be written by hand, or read from the controls created at runtime with
+    `create_control` (see `ControlDefinition::from_control`).
+*/
+use crate::{ControlType, ControlProps, DynControl, NwgError};
+use std::fmt::Write;
+
+const RUST_KEYWORDS: &'static [&'static str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn", "for", "if", "impl", "in",
+    "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true",
+    "type", "unsafe", "use", "where", "while",
+];
+
+
+/**
+    The description of a UI struct
+
+    * `name`:     The name of the struct
+    * `controls`: The controls, in creation order. A parent must be defined before its children.
+    * `layouts`:  The grid layouts. Each layout is generated after its parent control.
+*/
+#[derive(Clone, Debug, Default)]
+pub struct UiDefinition {
+    pub name: String,
+    pub controls: Vec<ControlDefinition>,
+    pub layouts: Vec<GridLayoutDefinition>,
+}
+
+/**
+    The description of a control
+
+    * `name`:        The name of the struct field
+    * `ty`:          The control type
+    * `parent`:      The name of the parent control. Required for every control type except `ControlType::Window`.
+    * `props`:       The text, size, position and state of the control. Size and position are ignored for controls in a layout.
+    * `events`:      The events handled by the control
+    * `layout_item`: The position of the control in a grid layout
+*/
+#[derive(Clone, Debug)]
+pub struct ControlDefinition {
+    pub name: String,
+    pub ty: ControlType,
+    pub parent: Option<String>,
+    pub props: ControlProps,
+    pub events: Vec<EventDefinition>,
+    pub layout_item: Option<GridItemDefinition>,
+}
+
+/**
+    An event handled by a control
+
+    * `event`:     The name of the `Event` variant (ex: "OnButtonClick")
+    * `callbacks`: The callbacks, with the native-windows-derive syntax (ex: "MyApp::say_hello" or "MyApp::on_click(SELF, CTRL)")
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventDefinition {
+    pub event: String,
+    pub callbacks: Vec<String>,
+}
+
+/**
+    The description of a grid layout
+
+    * `name`:    The name of the struct field
+    * `parent`:  The name of the parent control
+    * `spacing`: The spacing between the children. Uses the `GridLayout` default if `None`.
+    * `margin`:  The top, right, bottom, left margins. Uses the `GridLayout` default if `None`.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GridLayoutDefinition {
+    pub name: String,
+    pub parent: String,
+    pub spacing: Option<u32>,
+    pub margin: Option<[u32; 4]>,
+}
+
+/// The position of a control in a grid layout
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GridItemDefinition {
+    pub layout: String,
+    pub col: u32,
+    pub row: u32,
+    pub col_span: u32,
+    pub row_span: u32,
+}
+
+impl UiDefinition {
+
+    pub fn new<S: Into<String>>(name: S) -> UiDefinition {
+        UiDefinition {
+            name: name.into(),
+            controls: Vec::new(),
+            layouts: Vec::new(),
+        }
+    }
+
+    /// Return the control named `name`
+    pub fn control(&self, name: &str) -> Option<&ControlDefinition> {
+        self.controls.iter().find(|c| c.name == name)
+    }
+
+    /// Return the control named `name`
+    pub fn control_mut(&mut self, name: &str) -> Option<&mut ControlDefinition> {
+        self.controls.iter_mut().find(|c| c.name == name)
+    }
+
+    /**
+        Check that the definition can be generated: the names must be unique rust identifiers, the parents must be
+        containers defined before their children, and the layouts must exist.
+    */
+    pub fn validate(&self) -> Result<(), NwgError> {
+        check_identifier(&self.name)?;
+
+        let mut names: Vec<&str> = Vec::with_capacity(self.controls.len() + self.layouts.len());
+        for name in self.controls.iter().map(|c| &c.name).chain(self.layouts.iter().map(|l| &l.name)) {
+            check_identifier(name)?;
+            if names.contains(&name.as_str()) {
+                return Err(NwgError::codegen(format!("The name {:?} is used more than once", name)));
+            }
+            names.push(name);
+        }
+
+        for (i, control) in self.controls.iter().enumerate() {
+            match (&control.parent, control.ty) {
+                (Some(_), ControlType::Window) => {
+                    return Err(NwgError::codegen(format!("The window {:?} cannot have a parent", control.name)));
+                },
+                (None, ControlType::Window) => {},
+                (None, _) => {
+                    return Err(NwgError::codegen(format!("The control {:?} requires a parent", control.name)));
+                },
+                (Some(parent), _) => {
+                    let valid = self.controls[0..i].iter().any(|c| &c.name == parent && c.ty.is_container());
+                    if !valid {
+                        return Err(NwgError::codegen(format!("The parent of {:?} must be a container defined before it", control.name)));
+                    }
+                }
+            }
+
+            if let Some(item) = control.layout_item.as_ref() {
+                if !self.layouts.iter().any(|l| l.name == item.layout) {
+                    return Err(NwgError::codegen(format!("The layout {:?} of {:?} does not exist", item.layout, control.name)));
+                }
+            }
+
+            flags(control)?;
+        }
+
+        for layout in self.layouts.iter() {
+            if !self.controls.iter().any(|c| c.name == layout.parent && c.ty.is_container()) {
+                return Err(NwgError::codegen(format!("The parent of the layout {:?} must be a container", layout.name)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+        Generate the source of the UI struct. The struct derives `NwgUi`, so the generated file must import `native_windows_derive::NwgUi`
+        and `native_windows_gui` as `nwg`. The callbacks of the events are not generated.
+
+        Returns an error if the definition is not valid (see `validate`).
+    */
+    pub fn generate(&self) -> Result<String, NwgError> {
+        self.validate()?;
+
+        let mut out = String::with_capacity(1000);
+        out.push_str("#[derive(Default, NwgUi)]\n");
+        drop(writeln!(out, "pub struct {} {{", self.name));
+
+        let mut auto_parent: Option<&str> = None;
+        let mut first = true;
+        for control in self.controls.iter() {
+            if !first {
+                out.push('\n');
+            }
+            first = false;
+
+            self.write_control(&mut out, control, auto_parent)?;
+
+            if control.ty == ControlType::Window {
+                auto_parent = Some(&control.name);
+            }
+
+            for layout in self.layouts.iter().filter(|l| l.parent == control.name) {
+                out.push('\n');
+                write_layout(&mut out, layout);
+            }
+        }
+
+        out.push_str("}\n");
+
+        Ok(out)
+    }
+
+    fn write_control(&self, out: &mut String, control: &ControlDefinition, auto_parent: Option<&str>) -> Result<(), NwgError> {
+        let props = &control.props;
+        let mut params: Vec<String> = Vec::new();
+
+        match control.ty {
+            ControlType::Window if !props.text.is_empty() => params.push(format!("title: {:?}", props.text)),
+            ty if has_text(ty) && !props.text.is_empty() => params.push(format!("text: {:?}", props.text)),
+            _ => {}
+        }
+
+        if control.layout_item.is_none() {
+            params.push(format!("size: ({}, {})", props.size.0, props.size.1));
+            params.push(format!("position: ({}, {})", props.position.0, props.position.1));
+        }
+
+        // Controls of a window are detected automatically by native-windows-derive. The other parents must be explicit.
+        if let Some(parent) = control.parent.as_ref() {
+            if Some(parent.as_str()) != auto_parent {
+                params.push(format!("parent: {}", parent));
+            }
+        }
+
+        if let Some(flags) = flags(control)? {
+            params.push(format!("flags: {:?}", flags));
+        }
+
+        match params.is_empty() {
+            true => out.push_str("    #[nwg_control]\n"),
+            false => { drop(writeln!(out, "    #[nwg_control({})]", params.join(", "))); }
+        }
+
+        if let Some(item) = control.layout_item.as_ref() {
+            let mut params = format!("layout: {}, col: {}, row: {}", item.layout, item.col, item.row);
+            if item.col_span > 1 {
+                drop(write!(params, ", col_span: {}", item.col_span));
+            }
+            if item.row_span > 1 {
+                drop(write!(params, ", row_span: {}", item.row_span));
+            }
+
+            drop(writeln!(out, "    #[nwg_layout_item({})]", params));
+        }
+
+        let events: Vec<String> = control.events.iter()
+            .filter(|e| !e.callbacks.is_empty())
+            .map(|e| format!("{}: [{}]", e.event, e.callbacks.join(", ")))
+            .collect();
+
+        if !events.is_empty() {
+            drop(writeln!(out, "    #[nwg_events( {} )]", events.join(", ")));
+        }
+
+        drop(writeln!(out, "    {}: {},", control.name, type_path(control.ty)));
+
+        Ok(())
+    }
+
+}
+
+impl ControlDefinition {
+
+    pub fn new<S: Into<String>>(name: S, ty: ControlType, parent: Option<&str>) -> ControlDefinition {
+        ControlDefinition {
+            name: name.into(),
+            ty,
+            parent: parent.map(|p| p.to_string()),
+            props: Default::default(),
+            events: Vec::new(),
+            layout_item: None,
+        }
+    }
+
+    /// Create a definition from the current text, size, position and state of a control created with `create_control`
+    pub fn from_control<S: Into<String>>(name: S, control: &DynControl, parent: Option<&str>) -> ControlDefinition {
+        let (width, height) = control.size();
+        let props = ControlProps {
+            text: control.text(),
+            size: (width as i32, height as i32),
+            position: control.position(),
+            enabled: control.enabled(),
+            visible: control.visible(),
+        };
+
+        ControlDefinition {
+            props,
+            ..ControlDefinition::new(name, control.control_type(), parent)
+        }
+    }
+
+    /// Add a callback to an event of the control
+    pub fn add_event<S1: Into<String>, S2: Into<String>>(&mut self, event: S1, callback: S2) {
+        let event = event.into();
+        let callback = callback.into();
+
+        match self.events.iter_mut().find(|e| e.event == event) {
+            Some(e) => e.callbacks.push(callback),
+            None => self.events.push(EventDefinition { event, callbacks: vec![callback] }),
+        }
+    }
+
+}
+
+impl GridLayoutDefinition {
+
+    pub fn new<S1: Into<String>, S2: Into<String>>(name: S1, parent: S2) -> GridLayoutDefinition {
+        GridLayoutDefinition {
+            name: name.into(),
+            parent: parent.into(),
+            spacing: None,
+            margin: None,
+        }
+    }
+
+}
+
+impl GridItemDefinition {
+
+    pub fn new<S: Into<String>>(layout: S, col: u32, row: u32) -> GridItemDefinition {
+        GridItemDefinition {
+            layout: layout.into(),
+            col,
+            row,
+            col_span: 1,
+            row_span: 1,
+        }
+    }
+
+}
+
+fn write_layout(out: &mut String, layout: &GridLayoutDefinition) {
+    let mut params = format!("parent: {}", layout.parent);
+    if let Some(spacing) = layout.spacing {
+        drop(write!(params, ", spacing: {}", spacing));
+    }
+    if let Some([top, right, bottom, left]) = layout.margin {
+        drop(write!(params, ", margin: [{}, {}, {}, {}]", top, right, bottom, left));
+    }
+
+    drop(writeln!(out, "    #[nwg_layout({})]", params));
+    drop(writeln!(out, "    {}: nwg::GridLayout,", layout.name));
+}
+
+fn type_path(ty: ControlType) -> String {
+    match ty {
+        #[cfg(feature = "combobox")]
+        ControlType::ComboBox => "nwg::ComboBox<String>".to_string(),
+
+        #[cfg(feature = "listbox")]
+        ControlType::ListBox => "nwg::ListBox<String>".to_string(),
+
+        ty => format!("nwg::{}", ty.name()),
+    }
+}
+
+/// Return true if the builder of the control type accepts a text
+fn has_text(ty: ControlType) -> bool {
+    match ty {
+        ControlType::Button | ControlType::CheckBox | ControlType::RadioButton | ControlType::TextInput | ControlType::Label => true,
+        #[cfg(feature = "textbox")] ControlType::TextBox => true,
+        #[cfg(feature = "rich-textbox")] ControlType::RichTextBox => true,
+        #[cfg(feature = "rich-textbox")] ControlType::RichLabel => true,
+        _ => false
+    }
+}
+
+/// Return the default flags of the builder of the control type, and true if the type has a `NONE` flag
+fn default_flags(ty: ControlType) -> (&'static [&'static str], bool) {
+    match ty {
+        ControlType::Window => (&["MAIN_WINDOW", "VISIBLE"], false),
+        ControlType::Button => (&["VISIBLE", "TAB_STOP", "NOTIFY"], true),
+        ControlType::CheckBox => (&["VISIBLE", "TAB_STOP"], true),
+        ControlType::RadioButton => (&["VISIBLE"], false),
+        ControlType::TextInput => (&["VISIBLE"], false),
+        ControlType::Label => (&["VISIBLE"], true),
+        ControlType::ImageFrame => (&["VISIBLE"], false),
+        #[cfg(feature = "textbox")] ControlType::TextBox => (&["VISIBLE", "VSCROLL", "HSCROLL", "AUTOVSCROLL", "AUTOHSCROLL", "TAB_STOP"], false),
+        #[cfg(feature = "rich-textbox")] ControlType::RichTextBox => (&["VISIBLE", "VSCROLL", "HSCROLL", "AUTOVSCROLL", "AUTOHSCROLL", "TAB_STOP", "SAVE_SELECTION"], false),
+        #[cfg(feature = "rich-textbox")] ControlType::RichLabel => (&["VISIBLE"], true),
+        #[cfg(feature = "trackbar")] ControlType::TrackBar => (&["VISIBLE", "AUTO_TICK", "TAB_STOP"], false),
+        #[cfg(feature = "combobox")] ControlType::ComboBox => (&["VISIBLE", "TAB_STOP"], true),
+        #[cfg(feature = "listbox")] ControlType::ListBox => (&["VISIBLE", "TAB_STOP"], true),
+        #[cfg(feature = "datetime-picker")] ControlType::DatePicker => (&["VISIBLE", "TAB_STOP"], false),
+        #[cfg(feature = "progress-bar")] ControlType::ProgressBar => (&["VISIBLE"], false),
+        #[cfg(feature = "tree-view")] ControlType::TreeView => (&["VISIBLE", "TAB_STOP", "ALWAYS_SHOW_SELECTION"], false),
+        #[cfg(feature = "list-view")] ControlType::ListView => (&["VISIBLE", "TAB_STOP", "ALWAYS_SHOW_SELECTION"], false),
+        #[cfg(feature = "number-select")] ControlType::NumberSelect => (&["VISIBLE"], true),
+        #[cfg(feature = "frame")] ControlType::Frame => (&["VISIBLE", "BORDER"], true),
+        #[cfg(feature = "scroll-bar")] ControlType::ScrollBar => (&["VISIBLE", "TAB_STOP", "VERTICAL"], true),
+    }
+}
+
+/**
+    Return the compressed flags of a control (ex: "VISIBLE|DISABLED"), or `None` if the control uses the default flags.
+    The flags keep the defaults of the builder so only the visibility and the state of the control change.
+*/
+fn flags(control: &ControlDefinition) -> Result<Option<String>, NwgError> {
+    let props = &control.props;
+    if props.visible && props.enabled {
+        return Ok(None);
+    }
+
+    #[cfg(feature = "trackbar")]
+    {
+        if control.ty == ControlType::TrackBar && !props.enabled {
+            return Err(NwgError::codegen(format!("The track bar {:?} cannot be disabled when it is created", control.name)));
+        }
+    }
+
+    let (defaults, has_none) = default_flags(control.ty);
+    let mut flags: Vec<&str> = defaults.iter()
+        .cloned()
+        .filter(|&f| f != "VISIBLE" || props.visible)
+        .collect();
+
+    if !props.enabled {
+        flags.push("DISABLED");
+    }
+
+    match (flags.is_empty(), has_none) {
+        (false, _) => Ok(Some(flags.join("|"))),
+        (true, true) => Ok(Some("NONE".to_string())),
+        (true, false) => Err(NwgError::codegen(format!("The {} {:?} cannot be hidden when it is created", control.ty.name(), control.name))),
+    }
+}
+
+fn check_identifier(name: &str) -> Result<(), NwgError> {
+    let mut chars = name.chars();
+    let valid = match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => chars.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+        _ => false
+    };
+
+    match valid && name != "_" && !RUST_KEYWORDS.contains(&name) {
+        true => Ok(()),
+        false => Err(NwgError::codegen(format!("{:?} is not a valid field name", name)))
+    }
+}
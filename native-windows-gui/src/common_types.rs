@@ -18,6 +18,98 @@ pub enum VTextAlign {
     Bottom
 }
 
+/**
+    How a background bitmap is drawn in a window or a frame
+
+    * Tile: The bitmap is repeated from the top left corner
+    * Center: The bitmap is drawn at its size in the center
+    * Stretch: The bitmap is stretched over the whole client area
+    * Fit: The bitmap is scaled to the largest size that fits in the client area without changing its aspect ratio, and centered
+*/
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum BackgroundMode {
+    Tile,
+    Center,
+    Stretch,
+    Fit
+}
+
+pub mod easing {
+    //! Easing functions for the animations. The functions take the progress of an animation between 0.0 and 1.0
+    //! (see `AnimationTick::progress`) and return the eased progress. The eased progress can be out of the
+    //! `[0.0, 1.0]` range for the functions that overshoot, such as `spring`.
+
+    /// An easing function that can be stored in the state of an animation
+    #[derive(PartialEq, Debug, Clone, Copy)]
+    pub enum Easing {
+        Linear,
+        CubicIn,
+        CubicOut,
+        CubicInOut,
+        Spring,
+    }
+
+    impl Easing {
+        /// Applies the easing function to the progress `t`
+        pub fn ease(&self, t: f32) -> f32 {
+            match self {
+                Easing::Linear => linear(t),
+                Easing::CubicIn => cubic_in(t),
+                Easing::CubicOut => cubic_out(t),
+                Easing::CubicInOut => cubic_in_out(t),
+                Easing::Spring => spring(t),
+            }
+        }
+    }
+
+    /// Constant speed
+    pub fn linear(t: f32) -> f32 {
+        t.max(0.0).min(1.0)
+    }
+
+    /// Starts slowly and accelerates
+    pub fn cubic_in(t: f32) -> f32 {
+        let t = linear(t);
+        t * t * t
+    }
+
+    /// Starts quickly and decelerates
+    pub fn cubic_out(t: f32) -> f32 {
+        let t = 1.0 - linear(t);
+        1.0 - t * t * t
+    }
+
+    /// Accelerates until the middle of the animation, then decelerates
+    pub fn cubic_in_out(t: f32) -> f32 {
+        let t = linear(t);
+        match t < 0.5 {
+            true => 4.0 * t * t * t,
+            false => {
+                let t = -2.0 * t + 2.0;
+                1.0 - t * t * t / 2.0
+            }
+        }
+    }
+
+    /// Overshoots the target and oscillates around it before settling, like a damped spring
+    pub fn spring(t: f32) -> f32 {
+        const DAMPING: f32 = 6.0;
+        const FREQUENCY: f32 = 4.5 * ::std::f32::consts::PI;
+
+        match t {
+            t if t <= 0.0 => 0.0,
+            t if t >= 1.0 => 1.0,
+            t => 1.0 - (-DAMPING * t).exp() * (FREQUENCY * t).cos()
+        }
+    }
+
+    /// Interpolates between `from` and `to` with the eased progress `t`. Use it to compute a position or a size.
+    pub fn lerp(from: i32, to: i32, t: f32) -> i32 {
+        from + ((to - from) as f32 * t).round() as i32
+    }
+
+}
+
 pub mod keys {
     //! Windows virtual key code
     
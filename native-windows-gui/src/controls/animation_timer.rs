@@ -1,5 +1,5 @@
 use crate::controls::ControlHandle;
-use crate::NwgError;
+use crate::{NwgError, AnimationTick};
 use crate::win32::window_helper as wh;
 use std::{thread, time::{Duration, Instant}, sync::{Mutex, Arc}};
 
@@ -35,7 +35,7 @@ lazy_static! {
                         None => { continue; }
                     };
 
-                    if timer.last_tick.elapsed() > timer.interval {
+                    if timer.last_tick.elapsed() > timer.interval && timer.frame_ready() {
                         AnimationThread::timer_tick(id as u32, timer.hwnd);
                         timer.last_tick = Instant::now();
                         timer.current_tick += 1;
@@ -52,6 +52,7 @@ lazy_static! {
                     if let Some(lf) = timer.lifetime {
                         if timer.birthtime.elapsed() > lf {
                             AnimationThread::timer_stop(id as u32, timer.hwnd);
+                            AnimationThread::animation_end(id as u32, timer.hwnd);
                             timer.active = false;
                         }
                     }
@@ -74,10 +75,31 @@ struct InnerTimer {
     birthtime: Instant,
     max_tick: Option<u64>,
     current_tick: u64,
+    max_fps: Option<u32>,
+    last_frame: Option<Instant>,
     active: bool,
     hwnd: usize,
 }
 
+impl InnerTimer {
+
+    /// With a max fps, a tick is only sent once the frame time elapsed since the last tick handled by the application
+    fn frame_ready(&self) -> bool {
+        match (self.max_fps, self.last_frame) {
+            (Some(fps), Some(last_frame)) if fps > 0 => last_frame.elapsed() >= Duration::from_secs(1) / fps,
+            _ => true
+        }
+    }
+
+    fn progress(&self, elapsed: Duration) -> Option<f32> {
+        self.lifetime.map(|lifetime| match lifetime.as_secs_f32() {
+            l if l > 0.0 => (elapsed.as_secs_f32() / l).min(1.0),
+            _ => 1.0
+        })
+    }
+
+}
+
 struct AnimationThread {
     timers: Vec<Option<InnerTimer>>,
 }
@@ -110,6 +132,7 @@ impl AnimationThread {
             t.active = true;
             t.birthtime = Instant::now();
             t.current_tick = 0;
+            t.last_frame = None;
         }
     }
 
@@ -130,6 +153,13 @@ impl AnimationThread {
         }
     }
 
+    fn set_max_fps(id: u32, max_fps: Option<u32>) {
+        let mut state = THREAD_STATE.lock().unwrap();
+        if let Some(Some(t)) = state.timers.get_mut(id as usize) {
+            t.max_fps = max_fps;
+        }
+    }
+
     fn stop_timer(id: u32) {
         let mut state = THREAD_STATE.lock().unwrap();
         if let Some(Some(t)) = state.timers.get_mut(id as usize) {
@@ -156,6 +186,12 @@ impl AnimationThread {
         }
     }
 
+    pub fn animation_end(id: u32, hwnd: usize) {
+        unsafe {
+            SendNotifyMessageW(hwnd as HWND, wh::NWG_ANIMATION_END, id as WPARAM, hwnd as LPARAM);
+        }
+    }
+
 }
 
 
@@ -166,19 +202,22 @@ Timers are mosty used to handle animations OR to create a timeout. To sync multi
 AnimationTimer is controlled from a singletion running in another thread. All instance of AnimationTimer will live on that thread.
 
 A timer still requires a top level window parent. If the top level window parent is destroyed, the timer becomes invalid.
+The parent can be a `MessageWindow`, so timers also work in applications without a visible window.
 
 AnimationTimer replaces the default winapi timer. Please, for the love of god, do not use the default timer.
 
 **Builder parameters:**
-    * `parent`:     **Required.** The timer parent container that will receive the timer event. Should be a top level window
+    * `parent`:     **Required.** The timer parent container that will receive the timer event. Should be a top level window or a message window
     * `interval`:   The timer tick interval as a rust Duration. Minimum is 1 ms
     * `lifetime`:   The timer should automatically stop after the selected Duration. Defaults to `None`.
     * `max_tick`:   The timer should automatically stop after sending X amount of OnTImerTick events. Defaults to `None`.
+    * `max_fps`:    The maximum number of ticks per second. Ticks are skipped while the application is still handling the previous one. Defaults to `None`.
     * `active`:     If the timer should start right away. Default to `false`
 
 **Control events:**
-    * `OnTimerTick`: When the timer ticks
+    * `OnTimerTick`: When the timer ticks. Sends the timing of the tick in a `EventData::OnAnimationTick`.
     * `OnTimerStop`: When the timer stops itself (due to max_tick_count or lifetime being reached, not user actions)
+    * `OnAnimationEnd`: When the lifetime of the timer is reached. Sent after `OnTimerStop` with a progress of 1.0.
 
 ```
 use native_windows_gui as nwg;
@@ -193,6 +232,14 @@ fn build_timer(parent: &nwg::Window)  {
         .lifetime(Some(Duration::from_millis(3000)))
         .build(&mut timer);
 }
+
+/// Moves a button from 0 to 200 pixels in the lifetime of the timer
+fn animate(button: &nwg::Button, data: &nwg::EventData) {
+    if let Some(progress) = data.on_animation_tick().progress {
+        let x = nwg::easing::lerp(0, 200, nwg::easing::cubic_in_out(progress));
+        button.set_position(x, 10);
+    }
+}
 ```
 */
 #[derive(Default, PartialEq, Eq)]
@@ -208,6 +255,7 @@ impl AnimationTimer {
             interval: Duration::from_millis(1000/60),
             max_tick: None,
             lifetime: None,
+            max_fps: None,
             active: false,
         }
     }
@@ -260,6 +308,14 @@ impl AnimationTimer {
         AnimationThread::update_timer(id, None, None, Some(max_tick));
     }
 
+    /// Sets the maximum number of ticks per second of this timer. With a max fps, a tick is not sent until
+    /// the application handled the previous one, so the ticks do not pile up when the application cannot keep up.
+    pub fn set_max_fps(&self, max_fps: Option<u32>) {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        let (_, id) = self.handle.timer().expect(BAD_HANDLE);
+        AnimationThread::set_max_fps(id, max_fps);
+    }
+
 }
 
 impl Drop for AnimationTimer {
@@ -280,6 +336,7 @@ pub struct AnimationTimerBuilder {
     interval: Duration,
     max_tick: Option<u64>,
     lifetime: Option<Duration>,
+    max_fps: Option<u32>,
     active: bool
 }
 
@@ -305,6 +362,11 @@ impl AnimationTimerBuilder {
         self
     }
 
+    pub fn max_fps(mut self, max_fps: Option<u32>) -> AnimationTimerBuilder {
+        self.max_fps = max_fps;
+        self
+    }
+
     pub fn active(mut self, active: bool) -> AnimationTimerBuilder {
         self.active = active;
         self
@@ -330,6 +392,8 @@ impl AnimationTimerBuilder {
             birthtime: Instant::now(),
             max_tick: self.max_tick,
             current_tick: 0,
+            max_fps: self.max_fps,
+            last_frame: None,
             active: self.active,
             hwnd: parent as usize,
         };
@@ -344,3 +408,30 @@ impl AnimationTimerBuilder {
     }
 
 }
+
+/// Returns the timing of a tick of the timer `id` and marks the tick as handled. Called when `OnTimerTick` is dispatched.
+pub(crate) fn animation_tick(id: u32) -> AnimationTick {
+    let now = Instant::now();
+    let mut state = THREAD_STATE.lock().unwrap();
+
+    match state.timers.get_mut(id as usize) {
+        Some(Some(t)) => {
+            let elapsed = now.duration_since(t.birthtime);
+            let delta = now.duration_since(t.last_frame.unwrap_or(t.birthtime));
+            t.last_frame = Some(now);
+
+            AnimationTick { elapsed, delta, tick: t.current_tick, progress: t.progress(elapsed) }
+        },
+        _ => AnimationTick { elapsed: Duration::from_millis(0), delta: Duration::from_millis(0), tick: 0, progress: None }
+    }
+}
+
+/// Returns the timing of the end of the animation of the timer `id`. Called when `OnAnimationEnd` is dispatched.
+pub(crate) fn animation_end(id: u32) -> AnimationTick {
+    let mut tick = animation_tick(id);
+    if let Some(progress) = tick.progress.as_mut() {
+        *progress = 1.0;
+    }
+
+    tick
+}
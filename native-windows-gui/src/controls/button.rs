@@ -1,4 +1,6 @@
 use winapi::um::winuser::{WS_DISABLED, BS_ICON, BS_BITMAP, BS_NOTIFY, WS_VISIBLE, WS_TABSTOP, WS_CHILD};
+use winapi::um::commctrl::{BS_COMMANDLINK, BUTTON_IMAGELIST};
+use winapi::shared::windef::{HWND, RECT};
 use crate::win32::{
     base_helper::check_hwnd,  
     window_helper as wh,
@@ -6,6 +8,11 @@ use crate::win32::{
 };
 use crate::{NwgError, Font, Bitmap, Icon};
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
+use std::mem;
+
+#[cfg(feature="image-list")]
+use crate::ImageList;
 
 const NOT_BOUND: &'static str = "Button is not yet bound to a winapi object";
 const BAD_HANDLE: &'static str = "INTERNAL ERROR: Button handle is not HWND!";
@@ -22,6 +29,7 @@ bitflags! {
         * ICON:     The button will display a icon image with no text. Must have a icon or else it will only show text.
         * NOTIFY:   Enable the `OnButtonDoubleClick` event
         * TAB_STOP: The control can be selected using tab navigation
+        * COMMAND_LINK: The button is a command link: a flat button with a green arrow, a large text and an optional note line under the text (see `Button::set_note`)
     */
     pub struct ButtonFlags: u32 {
         const NONE = 0;
@@ -31,12 +39,30 @@ bitflags! {
         const BITMAP = BS_BITMAP;
         const NOTIFY = BS_NOTIFY;
         const TAB_STOP = WS_TABSTOP;
+        const COMMAND_LINK = BS_COMMANDLINK;
     }
 }
 
+/// The position of the image list of a button relative to the button text. See `Button::set_image_align`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ButtonImageAlign {
+    Left,
+    Right,
+    Top,
+    Bottom,
+
+    /// The image is drawn at the center of the button, over the text. Use it for buttons without text.
+    Center,
+}
+
 /**
 A push button is a rectangle containing an application-defined text label.
 Use `ImageButton` if you need to have a button that ONLY contains an icon or a bitmap.
+Use `SplitButton` for a button with a drop down arrow.
+
+A button with a bitmap or an icon always draws its image on the left of its text. For a button with an image and a text,
+use an image list instead (see `Button::set_image_list`): the image list controls the position and the margins of the image,
+and can hold a different image for the hot, pressed and disabled states of the button.
 
 Button is not behind any features.
 
@@ -52,12 +78,23 @@ Button is not behind any features.
   * `bitmap`:   A bitmap to display next to the button text. If this value is set, icon is ignored.
   * `icon`:     An icon to display next to the button text
   * `focus`:    The control receive focus after being created
+  * `elevation_required`: Display the UAC shield icon next to the button text. See `Button::set_elevation_required`
+  * `default_button`: The button is clicked when the user presses Enter in the window. See `Button::set_default`
+  * `cancel_button`:  The button is clicked when the user presses Escape in the window. See `Button::set_cancel`
+  * `note`:     The note line of a command link button. See `Button::set_note`
+  * `image_list`:   An image list holding the images of the button states. See `Button::set_image_list`. Requires the `image-list` feature.
+  * `image_align`:  The position of the image list relative to the text. Defaults to `ButtonImageAlign::Left`.
+  * `image_margin`: The margins around the image list image: top, right, bottom, left. Defaults to `[0, 0, 0, 0]`.
+  * `text_margin`:  The margins around the button text: top, right, bottom, left. Uses the system margins if not set.
 
 **Control events:**
   * `OnButtonClick`: When the button is clicked once by the user
   * `OnButtonDoubleClick`: When the button is clicked twice rapidly by the user
   * `MousePress(_)`: Generic mouse press events on the button
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
 
 ```rust
@@ -92,10 +129,203 @@ impl Button {
             parent: None,
             bitmap: None,
             icon: None,
-            focus: false
+            focus: false,
+            elevation_required: false,
+            default_button: false,
+            cancel_button: false,
+            note: None,
+            #[cfg(feature="image-list")]
+            image_list: None,
+            image_align: ButtonImageAlign::Left,
+            image_margin: [0, 0, 0, 0],
+            text_margin: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
+    /**
+        Display the UAC shield icon next to the button text. Use it on buttons that run an action requiring administrator
+        privileges (see `relaunch_elevated`). The shield replaces the button image.
+    */
+    pub fn set_elevation_required(&self, required: bool) {
+        use winapi::um::commctrl::BCM_SETSHIELD;
+        use winapi::shared::minwindef::LPARAM;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        wh::send_message(handle, BCM_SETSHIELD, 0, required as LPARAM);
+    }
+
+    /**
+        Make the button the default button of its top level window: the button raises `OnButtonClick` when the user presses Enter,
+        and it is drawn with a thick border. The window does not raise `OnKeyEnter` while it has a default button.
+        A window has one default button; setting a new one replaces the old one.
+
+        If the keyboard focus is on another button, Enter clicks the focused button.
+    */
+    pub fn set_default(&self, default: bool) {
+        use crate::win32::dialog_buttons::{DialogButton, set_dialog_button};
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        set_dialog_button(handle, DialogButton::Default, default);
+    }
+
+    /// Returns true if the button is the default button of its top level window
+    pub fn is_default(&self) -> bool {
+        use crate::win32::dialog_buttons::{DialogButton, is_dialog_button};
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        is_dialog_button(handle, DialogButton::Default)
+    }
+
+    /**
+        Make the button the cancel button of its top level window: the button raises `OnButtonClick` when the user presses Escape.
+        The window does not raise `OnKeyEsc` while it has a cancel button. A window has one cancel button; setting a new one replaces the old one.
+    */
+    pub fn set_cancel(&self, cancel: bool) {
+        use crate::win32::dialog_buttons::{DialogButton, set_dialog_button};
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        set_dialog_button(handle, DialogButton::Cancel, cancel);
+    }
+
+    /// Returns true if the button is the cancel button of its top level window
+    pub fn is_cancel(&self) -> bool {
+        use crate::win32::dialog_buttons::{DialogButton, is_dialog_button};
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        is_dialog_button(handle, DialogButton::Cancel)
+    }
+
+    /**
+        Sets the note line displayed under the text of a command link button (see `ButtonFlags::COMMAND_LINK`).
+        Does nothing on the other buttons. Requires Windows Vista or later.
+    */
+    pub fn set_note(&self, note: &str) {
+        use winapi::um::commctrl::BCM_SETNOTE;
+        use winapi::shared::minwindef::LPARAM;
+        use crate::win32::base_helper::to_utf16;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let note = to_utf16(note);
+        wh::send_message(handle, BCM_SETNOTE, 0, note.as_ptr() as LPARAM);
+    }
+
+    /// Returns the note line of a command link button. Returns an empty string for the other buttons.
+    pub fn note(&self) -> String {
+        use winapi::um::commctrl::{BCM_GETNOTE, BCM_GETNOTELENGTH};
+        use winapi::shared::minwindef::{WPARAM, LPARAM, DWORD};
+        use crate::win32::base_helper::from_utf16;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let length = wh::send_message(handle, BCM_GETNOTELENGTH, 0, 0) as usize;
+        if length == 0 {
+            return String::new();
+        }
+
+        let mut buffer: Vec<u16> = vec![0; length + 1];
+        let mut size = buffer.len() as DWORD;
+        wh::send_message(handle, BCM_GETNOTE, &mut size as *mut DWORD as WPARAM, buffer.as_mut_ptr() as LPARAM);
+
+        from_utf16(&buffer)
+    }
+
+    /**
+        Sets the image list of the button. Set `list` to `None` to remove the image list.
+
+        If the list has a single image, the image is used in every state of the button. Otherwise the images are used in this order:
+        normal, hot (under the mouse), pressed, disabled, default button and stylus hot. The states without an image use the normal image.
+
+        The image list is not copied and must outlive the button. The image list replaces the bitmap or the icon of the button.
+        The position and the margins of the image are kept (see `set_image_align` and `set_image_margin`).
+    */
+    #[cfg(feature="image-list")]
+    pub fn set_image_list(&self, list: Option<&ImageList>) {
+        use std::ptr;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let mut info = get_button_image_list(handle);
+        info.himl = list.map(|l| l.handle).unwrap_or(ptr::null_mut());
+        set_button_image_list(handle, &info);
+    }
+
+    /// Returns the image list of the button or None if there is none.
+    /// The returned image list is not owned
+    #[cfg(feature="image-list")]
+    pub fn image_list(&self) -> Option<ImageList> {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let info = get_button_image_list(handle);
+        if info.himl.is_null() {
+            None
+        } else {
+            Some(ImageList { handle: info.himl, owned: false })
+        }
+    }
+
+    /// Returns the position of the image list relative to the button text
+    pub fn image_align(&self) -> ButtonImageAlign {
+        use winapi::um::commctrl::{BUTTON_IMAGELIST_ALIGN_RIGHT, BUTTON_IMAGELIST_ALIGN_TOP, BUTTON_IMAGELIST_ALIGN_BOTTOM, BUTTON_IMAGELIST_ALIGN_CENTER};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        match get_button_image_list(handle).uAlign {
+            BUTTON_IMAGELIST_ALIGN_RIGHT => ButtonImageAlign::Right,
+            BUTTON_IMAGELIST_ALIGN_TOP => ButtonImageAlign::Top,
+            BUTTON_IMAGELIST_ALIGN_BOTTOM => ButtonImageAlign::Bottom,
+            BUTTON_IMAGELIST_ALIGN_CENTER => ButtonImageAlign::Center,
+            _ => ButtonImageAlign::Left
+        }
+    }
+
+    /// Sets the position of the image list relative to the button text. Set the image list first: the position is not kept if the button has no image list.
+    pub fn set_image_align(&self, align: ButtonImageAlign) {
+        use winapi::um::commctrl::{BUTTON_IMAGELIST_ALIGN_LEFT, BUTTON_IMAGELIST_ALIGN_RIGHT, BUTTON_IMAGELIST_ALIGN_TOP, BUTTON_IMAGELIST_ALIGN_BOTTOM, BUTTON_IMAGELIST_ALIGN_CENTER};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let mut info = get_button_image_list(handle);
+        info.uAlign = match align {
+            ButtonImageAlign::Left => BUTTON_IMAGELIST_ALIGN_LEFT,
+            ButtonImageAlign::Right => BUTTON_IMAGELIST_ALIGN_RIGHT,
+            ButtonImageAlign::Top => BUTTON_IMAGELIST_ALIGN_TOP,
+            ButtonImageAlign::Bottom => BUTTON_IMAGELIST_ALIGN_BOTTOM,
+            ButtonImageAlign::Center => BUTTON_IMAGELIST_ALIGN_CENTER,
+        };
+
+        set_button_image_list(handle, &info);
+    }
+
+    /// Returns the margins around the image list image. The four values are in this order: top, right, bottom, left.
+    pub fn image_margin(&self) -> [u32; 4] {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        rect_to_margin(&get_button_image_list(handle).margin)
+    }
+
+    /// Sets the margins around the image list image. The four values are in this order: top, right, bottom, left.
+    pub fn set_image_margin(&self, margin: [u32; 4]) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let mut info = get_button_image_list(handle);
+        info.margin = margin_to_rect(margin);
+        set_button_image_list(handle, &info);
+    }
+
+    /// Returns the margins around the button text. The four values are in this order: top, right, bottom, left.
+    pub fn text_margin(&self) -> [u32; 4] {
+        use winapi::um::commctrl::BCM_GETTEXTMARGIN;
+        use winapi::shared::minwindef::LPARAM;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let mut rect: RECT = unsafe { mem::zeroed() };
+        wh::send_message(handle, BCM_GETTEXTMARGIN, 0, &mut rect as *mut RECT as LPARAM);
+
+        rect_to_margin(&rect)
+    }
+
+    /// Sets the margins around the button text. The four values are in this order: top, right, bottom, left.
+    pub fn set_text_margin(&self, margin: [u32; 4]) {
+        use winapi::um::commctrl::BCM_SETTEXTMARGIN;
+        use winapi::shared::minwindef::LPARAM;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let rect = margin_to_rect(margin);
+        wh::send_message(handle, BCM_SETTEXTMARGIN, 0, &rect as *const RECT as LPARAM);
+    }
+
     /// Simulate a user click
     pub fn click(&self) {
         use winapi::um::winuser::BM_CLICK;
@@ -277,6 +507,17 @@ pub struct ButtonBuilder<'a> {
     icon: Option<&'a Icon>,
     parent: Option<ControlHandle>,
     focus: bool,
+    elevation_required: bool,
+    default_button: bool,
+    cancel_button: bool,
+    note: Option<&'a str>,
+    #[cfg(feature="image-list")]
+    image_list: Option<&'a ImageList>,
+    image_align: ButtonImageAlign,
+    image_margin: [u32; 4],
+    text_margin: Option<[u32; 4]>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a> ButtonBuilder<'a> {
@@ -331,13 +572,59 @@ impl<'a> ButtonBuilder<'a> {
         self
     }
 
+    pub fn elevation_required(mut self, required: bool) -> ButtonBuilder<'a> {
+        self.elevation_required = required;
+        self
+    }
+
+    pub fn default_button(mut self, default: bool) -> ButtonBuilder<'a> {
+        self.default_button = default;
+        self
+    }
+
+    pub fn cancel_button(mut self, cancel: bool) -> ButtonBuilder<'a> {
+        self.cancel_button = cancel;
+        self
+    }
+
+    pub fn note(mut self, note: Option<&'a str>) -> ButtonBuilder<'a> {
+        self.note = note;
+        self
+    }
+
+    #[cfg(feature="image-list")]
+    pub fn image_list(mut self, list: Option<&'a ImageList>) -> ButtonBuilder<'a> {
+        self.image_list = list;
+        self
+    }
+
+    pub fn image_align(mut self, align: ButtonImageAlign) -> ButtonBuilder<'a> {
+        self.image_align = align;
+        self
+    }
+
+    pub fn image_margin(mut self, margin: [u32; 4]) -> ButtonBuilder<'a> {
+        self.image_margin = margin;
+        self
+    }
+
+    pub fn text_margin(mut self, margin: [u32; 4]) -> ButtonBuilder<'a> {
+        self.text_margin = Some(margin);
+        self
+    }
+
     pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> ButtonBuilder<'a> {
         self.parent = Some(p.into());
         self
     }
 
+    pub fn name(mut self, name: &str) -> ButtonBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut Button) -> Result<(), NwgError> {
-        let flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
 
         let parent = match self.parent {
             Some(p) => Ok(p),
@@ -348,6 +635,7 @@ impl<'a> ButtonBuilder<'a> {
         *out = Button::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -372,6 +660,36 @@ impl<'a> ButtonBuilder<'a> {
             out.set_icon(self.icon);
         }
 
+        builder_set_image_list(&self, out);
+
+        if self.image_align != ButtonImageAlign::Left {
+            out.set_image_align(self.image_align);
+        }
+
+        if self.image_margin != [0, 0, 0, 0] {
+            out.set_image_margin(self.image_margin);
+        }
+
+        if let Some(margin) = self.text_margin {
+            out.set_text_margin(margin);
+        }
+
+        if self.elevation_required {
+            out.set_elevation_required(true);
+        }
+
+        if let Some(note) = self.note {
+            out.set_note(note);
+        }
+
+        if self.default_button {
+            out.set_default(true);
+        }
+
+        if self.cancel_button {
+            out.set_cancel(true);
+        }
+
         if self.focus {
             out.set_focus();
         }
@@ -380,3 +698,39 @@ impl<'a> ButtonBuilder<'a> {
     }
 
 }
+
+#[cfg(feature="image-list")]
+fn builder_set_image_list(builder: &ButtonBuilder, out: &Button) {
+    if builder.image_list.is_some() {
+        out.set_image_list(builder.image_list);
+    }
+}
+
+#[cfg(not(feature="image-list"))]
+fn builder_set_image_list(_builder: &ButtonBuilder, _out: &Button) {
+}
+
+fn get_button_image_list(handle: HWND) -> BUTTON_IMAGELIST {
+    use winapi::um::commctrl::BCM_GETIMAGELIST;
+    use winapi::shared::minwindef::LPARAM;
+
+    let mut info: BUTTON_IMAGELIST = unsafe { mem::zeroed() };
+    wh::send_message(handle, BCM_GETIMAGELIST, 0, &mut info as *mut BUTTON_IMAGELIST as LPARAM);
+    info
+}
+
+fn set_button_image_list(handle: HWND, info: &BUTTON_IMAGELIST) {
+    use winapi::um::commctrl::BCM_SETIMAGELIST;
+    use winapi::shared::minwindef::LPARAM;
+
+    wh::send_message(handle, BCM_SETIMAGELIST, 0, info as *const BUTTON_IMAGELIST as LPARAM);
+}
+
+fn margin_to_rect(margin: [u32; 4]) -> RECT {
+    let [top, right, bottom, left] = margin;
+    RECT { left: left as i32, top: top as i32, right: right as i32, bottom: bottom as i32 }
+}
+
+fn rect_to_margin(rect: &RECT) -> [u32; 4] {
+    [rect.top.max(0) as u32, rect.right.max(0) as u32, rect.bottom.max(0) as u32, rect.left.max(0) as u32]
+}
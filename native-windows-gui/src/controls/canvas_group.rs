@@ -0,0 +1,319 @@
+/*!
+    Input tracking for applications with one or more `ExternCanvas`
+*/
+use winapi::shared::windef::POINT;
+use super::ControlHandle;
+use crate::{Event, EventData, MousePressEvent};
+use std::cell::RefCell;
+
+
+/// The mouse button held while dragging in a canvas
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DragButton {
+    Left,
+    Right,
+}
+
+/// The state of the keyboard modifiers when an input was received
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl Modifiers {
+
+    fn current() -> Modifiers {
+        use winapi::um::winuser::{GetKeyState, VK_SHIFT, VK_CONTROL, VK_MENU};
+
+        let down = |key| unsafe { GetKeyState(key) < 0 };
+        Modifiers {
+            shift: down(VK_SHIFT),
+            ctrl: down(VK_CONTROL),
+            alt: down(VK_MENU),
+        }
+    }
+
+}
+
+/// The input state of a single canvas in a `CanvasGroup`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CanvasIoState {
+    /// The last position of the cursor in the canvas client area
+    pub position: (i32, i32),
+
+    /// The button being dragged, if any
+    pub dragging: Option<DragButton>,
+
+    /// The state of the keyboard modifiers at the last input
+    pub modifiers: Modifiers,
+}
+
+/// An input translated by `CanvasGroup::process_event`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CanvasInput {
+    /// A drag started at the selected position
+    DragBegin(DragButton, (i32, i32)),
+
+    /// The cursor moved while a button was held. Holds the movement since the last input.
+    Drag(DragButton, (i32, i32)),
+
+    /// The button was released
+    DragEnd(DragButton),
+
+    /// The cursor moved without any button held. Holds the new position.
+    Hover((i32, i32)),
+
+    /// The mouse wheel was rotated. Holds the wheel delta (a multiple of 120 for most mice).
+    Wheel(i32),
+}
+
+/**
+    Tracks the mouse state (dragging, last position, modifiers) of several `ExternCanvas` in the same window.
+
+    Without `CanvasGroup`, each canvas needs its own drag flags and last cursor position. Instead, add the canvas to a group and
+    forward the events to `process_event`. The events of the canvas that are not in the group are ignored.
+    The `CanvasInput` returned can be applied directly to an `OrbitCamera`.
+
+    Requires the `extern-canvas` feature.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    struct App {
+        left: nwg::ExternCanvas,
+        right: nwg::ExternCanvas,
+        group: nwg::CanvasGroup,
+        cameras: [std::cell::RefCell<nwg::OrbitCamera>; 2],
+    }
+
+    impl App {
+        fn init(&self) {
+            self.group.add(&self.left);
+            self.group.add(&self.right);
+        }
+
+        fn canvas_events(&self, evt: nwg::Event, evt_data: &nwg::EventData, handle: nwg::ControlHandle) {
+            if let Some(input) = self.group.process_event(evt, evt_data, handle) {
+                let index = if handle == self.left.handle { 0 } else { 1 };
+                if self.cameras[index].borrow_mut().apply(&input) {
+                    // Redraw the canvas
+                }
+            }
+        }
+    }
+    ```
+*/
+#[derive(Default)]
+pub struct CanvasGroup {
+    canvas: RefCell<Vec<(ControlHandle, CanvasIoState)>>,
+}
+
+impl CanvasGroup {
+
+    pub fn new() -> CanvasGroup {
+        Default::default()
+    }
+
+    /// Add a canvas to the group. Does nothing if the canvas is already in the group.
+    pub fn add<C: Into<ControlHandle>>(&self, canvas: C) {
+        let handle = canvas.into();
+        if handle.hwnd().is_none() {
+            panic!("CanvasGroup can only track window controls");
+        }
+
+        let mut canvas = self.canvas.borrow_mut();
+        if !canvas.iter().any(|(h, _)| *h == handle) {
+            canvas.push((handle, Default::default()));
+        }
+    }
+
+    /// Remove a canvas from the group
+    pub fn remove<C: Into<ControlHandle>>(&self, canvas: C) {
+        let handle = canvas.into();
+        self.canvas.borrow_mut().retain(|(h, _)| *h != handle);
+    }
+
+    /// Return true if the canvas is in the group
+    pub fn contains<C: Into<ControlHandle>>(&self, canvas: C) -> bool {
+        let handle = canvas.into();
+        self.canvas.borrow().iter().any(|(h, _)| *h == handle)
+    }
+
+    /// Return the input state of a canvas, or `None` if the canvas is not in the group
+    pub fn state<C: Into<ControlHandle>>(&self, canvas: C) -> Option<CanvasIoState> {
+        let handle = canvas.into();
+        self.canvas.borrow().iter().find(|(h, _)| *h == handle).map(|(_, s)| *s)
+    }
+
+    /**
+        Update the state of the canvas that raised the event.
+        Return the translated input, or `None` if the event is not a mouse event or if the canvas is not in the group.
+    */
+    pub fn process_event(&self, evt: Event, evt_data: &EventData, handle: ControlHandle) -> Option<CanvasInput> {
+        let mut canvas = self.canvas.borrow_mut();
+        let (handle, state) = canvas.iter_mut().find(|(h, _)| *h == handle)?;
+        let position = cursor_position(handle);
+
+        let input = match (evt, evt_data) {
+            (Event::OnMousePress(press), _) => {
+                let (button, down) = match press {
+                    MousePressEvent::MousePressLeftDown => (DragButton::Left, true),
+                    MousePressEvent::MousePressLeftUp => (DragButton::Left, false),
+                    MousePressEvent::MousePressRightDown => (DragButton::Right, true),
+                    MousePressEvent::MousePressRightUp => (DragButton::Right, false),
+                };
+
+                match (down, state.dragging) {
+                    (true, _) => {
+                        state.dragging = Some(button);
+                        Some(CanvasInput::DragBegin(button, position))
+                    },
+                    (false, Some(dragging)) if dragging == button => {
+                        state.dragging = None;
+                        Some(CanvasInput::DragEnd(button))
+                    },
+                    (false, _) => None
+                }
+            },
+            (Event::OnMouseMove, _) => {
+                let (x, y) = state.position;
+                match state.dragging {
+                    Some(button) => Some(CanvasInput::Drag(button, (position.0 - x, position.1 - y))),
+                    None => Some(CanvasInput::Hover(position))
+                }
+            },
+            (Event::OnMouseWheel, EventData::OnMouseWheel(delta)) => Some(CanvasInput::Wheel(*delta)),
+            _ => { return None; }
+        };
+
+        state.position = position;
+        state.modifiers = Modifiers::current();
+
+        input
+    }
+
+}
+
+/// Return the cursor position in the client area of a canvas
+fn cursor_position(handle: &ControlHandle) -> (i32, i32) {
+    use winapi::um::winuser::{GetCursorPos, ScreenToClient};
+
+    let mut p = POINT { x: 0, y: 0 };
+    unsafe {
+        GetCursorPos(&mut p);
+        ScreenToClient(handle.hwnd().unwrap(), &mut p);
+    }
+
+    (p.x as i32, p.y as i32)
+}
+
+
+/**
+    A camera that orbits around a target point. Left drag rotates the camera, right drag pans the target and
+    the mouse wheel zooms in and out. Use `apply` with the inputs returned by `CanvasGroup::process_event`.
+
+    The camera only computes its position. Use `eye` and `target` to build the view matrix
+    with the math library of the application (ex: `look_at_rh(eye, target, [0.0, 1.0, 0.0])`).
+
+    Requires the `extern-canvas` feature.
+*/
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OrbitCamera {
+    /// The point the camera looks at
+    pub target: [f32; 3],
+
+    /// The rotation around the vertical axis, in radians
+    pub yaw: f32,
+
+    /// The rotation above the horizontal plane, in radians. Clamped to avoid flipping over the poles.
+    pub pitch: f32,
+
+    /// The distance between the camera and the target
+    pub distance: f32,
+
+    /// Radians per pixel when rotating
+    pub rotate_speed: f32,
+
+    /// Fraction of the distance per pixel when panning
+    pub pan_speed: f32,
+
+    /// Fraction of the distance per wheel notch when zooming
+    pub zoom_speed: f32,
+
+    /// The smallest and largest distance allowed
+    pub distance_range: (f32, f32),
+}
+
+impl Default for OrbitCamera {
+    fn default() -> OrbitCamera {
+        OrbitCamera {
+            target: [0.0, 0.0, 0.0],
+            yaw: 0.0,
+            pitch: 0.0,
+            distance: 4.0,
+            rotate_speed: 0.004,
+            pan_speed: 0.002,
+            zoom_speed: 0.1,
+            distance_range: (0.1, 1000.0),
+        }
+    }
+}
+
+impl OrbitCamera {
+
+    const MAX_PITCH: f32 = 1.5533; // 89 degrees
+
+    /// Update the camera with a canvas input. Return true if the camera changed.
+    pub fn apply(&mut self, input: &CanvasInput) -> bool {
+        match *input {
+            CanvasInput::Drag(DragButton::Left, (dx, dy)) => self.rotate(dx as f32, dy as f32),
+            CanvasInput::Drag(DragButton::Right, (dx, dy)) => self.pan(dx as f32, dy as f32),
+            CanvasInput::Wheel(delta) => self.zoom(delta as f32 / 120.0),
+            _ => { return false; }
+        }
+
+        true
+    }
+
+    /// Rotate the camera around the target. `dx` and `dy` are in pixels.
+    pub fn rotate(&mut self, dx: f32, dy: f32) {
+        self.yaw -= dx * self.rotate_speed;
+        self.pitch = (self.pitch + dy * self.rotate_speed).max(-Self::MAX_PITCH).min(Self::MAX_PITCH);
+    }
+
+    /// Move the target in the camera plane. `dx` and `dy` are in pixels.
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+
+        let right = [cos_yaw, 0.0, -sin_yaw];
+        let up = [-sin_pitch * sin_yaw, cos_pitch, -sin_pitch * cos_yaw];
+        let scale = self.distance * self.pan_speed;
+
+        for i in 0..3 {
+            self.target[i] += (up[i] * dy - right[i] * dx) * scale;
+        }
+    }
+
+    /// Move the camera toward the target. Positive `notches` zoom in.
+    pub fn zoom(&mut self, notches: f32) {
+        let (min, max) = self.distance_range;
+        self.distance = (self.distance * (1.0 - self.zoom_speed).powf(notches)).max(min).min(max);
+    }
+
+    /// Return the position of the camera
+    pub fn eye(&self) -> [f32; 3] {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let [x, y, z] = self.target;
+
+        [
+            x + self.distance * cos_pitch * sin_yaw,
+            y + self.distance * sin_pitch,
+            z + self.distance * cos_pitch * cos_yaw,
+        ]
+    }
+
+}
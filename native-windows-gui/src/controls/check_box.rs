@@ -6,6 +6,7 @@ use winapi::shared::windef::HBRUSH;
 use crate::win32::{base_helper::check_hwnd, window_helper as wh};
 use crate::{Font, NwgError, RawEventHandler};
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
 use std::cell::RefCell;
 
 const NOT_BOUND: &'static str = "CheckBox is not yet bound to a winapi object";
@@ -67,8 +68,13 @@ CheckBox is not behind any features.
   * `OnButtonDoubleClick`: When the checkbox is clicked twice rapidly by the user
   * `MousePress(_)`: Generic mouse press events on the checkbox
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
 
+Use a `CheckBoxGroup` to link a tristate parent check box to a group of children check boxes.
+
 
 ```rust
 use native_windows_gui as nwg;
@@ -104,6 +110,8 @@ impl CheckBox {
             ex_flags: 0,
             font: None,
             parent: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -329,7 +337,9 @@ pub struct CheckBoxBuilder<'a> {
     flags: Option<CheckBoxFlags>,
     ex_flags: u32,
     font: Option<&'a Font>,
-    parent: Option<ControlHandle>
+    parent: Option<ControlHandle>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a> CheckBoxBuilder<'a> {
@@ -389,8 +399,13 @@ impl<'a> CheckBoxBuilder<'a> {
         self
     }
 
+    pub fn name(mut self, name: &str) -> CheckBoxBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut CheckBox) -> Result<(), NwgError> {
-        let mut flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let mut flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
         if flags & BS_AUTO3STATE == 0 {
             flags |= BS_AUTOCHECKBOX;
         }
@@ -404,6 +419,7 @@ impl<'a> CheckBoxBuilder<'a> {
         *out = CheckBox::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -0,0 +1,343 @@
+use winapi::shared::windef::HWND;
+use winapi::shared::minwindef::{WPARAM, LPARAM};
+use crate::win32::window_helper as wh;
+use crate::win32::window::bind_raw_event_handler_inner;
+use crate::{NwgError, RawEventHandler, unbind_raw_event_handler};
+use super::{ControlHandle, CheckBox, CheckBoxState};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::ptr;
+
+
+/// What happens when the parent check box of a `CheckBoxGroup` is clicked while some of the children are checked
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PartialCheckPolicy {
+    /// Check all the children
+    CheckAll,
+
+    /// Uncheck all the children
+    UncheckAll,
+}
+
+struct CheckBoxGroupInner {
+    parent: HWND,
+    children: Vec<HWND>,
+    policy: PartialCheckPolicy,
+}
+
+impl CheckBoxGroupInner {
+
+    /// Returns `Checked` if all the children are checked, `Unchecked` if none are, and `Indeterminate` otherwise
+    fn children_state(&self) -> CheckBoxState {
+        let mut states = self.children.iter().map(|&c| check_state(c));
+        let first = match states.next() {
+            Some(state) => state,
+            None => { return CheckBoxState::Unchecked; }
+        };
+
+        match first != CheckBoxState::Indeterminate && states.all(|s| s == first) {
+            true => first,
+            false => CheckBoxState::Indeterminate
+        }
+    }
+
+    /// Set the state of the parent check box from the state of the children
+    fn sync_parent(&self) {
+        set_check_state(self.parent, self.children_state());
+    }
+
+    fn set_children(&self, state: CheckBoxState) {
+        for &child in self.children.iter() {
+            set_check_state(child, state);
+        }
+    }
+
+    /// Apply a click on `control` if it is the parent or a child of the group. Returns true if the group was changed.
+    fn clicked(&self, control: HWND) -> bool {
+        if control == self.parent {
+            let state = match (self.children_state(), self.policy) {
+                (CheckBoxState::Checked, _) => CheckBoxState::Unchecked,
+                (CheckBoxState::Unchecked, _) => CheckBoxState::Checked,
+                (CheckBoxState::Indeterminate, PartialCheckPolicy::CheckAll) => CheckBoxState::Checked,
+                (CheckBoxState::Indeterminate, PartialCheckPolicy::UncheckAll) => CheckBoxState::Unchecked,
+            };
+
+            self.set_children(state);
+        } else if !self.children.contains(&control) {
+            return false;
+        }
+
+        self.sync_parent();
+        true
+    }
+
+    fn notify_changed(&self) {
+        let window = wh::get_window_parent(self.parent);
+        wh::post_message(window, wh::NWG_CHECK_GROUP_CHANGED, 0, self.parent as LPARAM);
+    }
+
+}
+
+/**
+    Links a parent check box to a group of children check boxes. The parent check box shows if all the children are checked,
+    if none are, or if some are (the indeterminate state). Clicking the parent check box checks or unchecks all the children.
+
+    When the user changes the group, `OnCheckGroupChanged` is raised on the parent check box, after the `OnButtonClick` event
+    of the check box that was clicked. The states set by the application (ex: with `CheckBox::set_check_state`) do not raise the event;
+    call `update` to refresh the parent check box after changing the children.
+
+    The parent check box is made tristate when the group is built. A check box should only be in one group.
+
+    **Builder parameters:**
+      * `parent_check`: **Required.** The parent check box.
+      * `child`:        Add a child check box to the group.
+      * `children`:     Add a list of children check boxes to the group.
+      * `policy`:       What happens when the parent is clicked while some of the children are checked. Defaults to `PartialCheckPolicy::CheckAll`.
+
+    **Control events:**
+      * `OnCheckGroupChanged`: When the user changes the state of the group. The handle is the handle of the parent check box.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn build_group(group: &mut nwg::CheckBoxGroup, all: &nwg::CheckBox, a: &nwg::CheckBox, b: &nwg::CheckBox) -> Result<(), nwg::NwgError> {
+        nwg::CheckBoxGroup::builder()
+            .parent_check(all)
+            .children(&[a, b])
+            .build(group)
+    }
+    ```
+*/
+pub struct CheckBoxGroup {
+    inner: Rc<RefCell<CheckBoxGroupInner>>,
+    handlers: RefCell<Vec<RawEventHandler>>,
+}
+
+impl CheckBoxGroup {
+
+    pub fn builder() -> CheckBoxGroupBuilder {
+        CheckBoxGroupBuilder {
+            parent: None,
+            children: Vec::new(),
+            policy: PartialCheckPolicy::CheckAll,
+        }
+    }
+
+    /// Returns the handle of the parent check box
+    pub fn parent_check(&self) -> ControlHandle {
+        ControlHandle::Hwnd(self.inner.borrow().parent)
+    }
+
+    /// Returns the combined state of the children: `Checked` if all are checked, `Unchecked` if none are, `Indeterminate` otherwise
+    pub fn state(&self) -> CheckBoxState {
+        self.inner.borrow().children_state()
+    }
+
+    /// Check or uncheck all the children and update the parent check box. Does not raise `OnCheckGroupChanged`.
+    pub fn set_all(&self, checked: bool) {
+        let state = match checked {
+            true => CheckBoxState::Checked,
+            false => CheckBoxState::Unchecked
+        };
+
+        let inner = self.inner.borrow();
+        inner.set_children(state);
+        inner.sync_parent();
+    }
+
+    /// Update the parent check box from the state of the children. Call this after changing the state of the children from the code.
+    pub fn update(&self) {
+        self.inner.borrow().sync_parent();
+    }
+
+    /// Returns what happens when the parent is clicked while some of the children are checked
+    pub fn policy(&self) -> PartialCheckPolicy {
+        self.inner.borrow().policy
+    }
+
+    /// Sets what happens when the parent is clicked while some of the children are checked
+    pub fn set_policy(&self, policy: PartialCheckPolicy) {
+        self.inner.borrow_mut().policy = policy;
+    }
+
+    /// Add a child check box to the group and update the parent check box
+    pub fn add_child(&self, child: &CheckBox) -> Result<(), NwgError> {
+        let handle = child.handle.hwnd().ok_or(NwgError::control_create("CheckBoxGroup child is not bound"))?;
+
+        {
+            let mut inner = self.inner.borrow_mut();
+            if inner.parent.is_null() {
+                panic!("CheckBoxGroup is not yet bound to a parent check box");
+            }
+
+            if !inner.children.contains(&handle) {
+                inner.children.push(handle);
+            }
+
+            inner.sync_parent();
+        }
+
+        self.bind_handlers()
+    }
+
+    /// Bind a click handler to the windows that hold the check boxes of the group. Remove the old handlers.
+    fn bind_handlers(&self) -> Result<(), NwgError> {
+        use winapi::um::winuser::{WM_COMMAND, BN_CLICKED};
+        use winapi::shared::minwindef::HIWORD;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CHECK_GROUP_ID: AtomicUsize = AtomicUsize::new(0xBFFF);
+
+        self.unbind_handlers();
+
+        let windows = {
+            let inner = self.inner.borrow();
+            let mut windows: Vec<HWND> = Vec::new();
+            for &control in Some(&inner.parent).into_iter().chain(inner.children.iter()) {
+                let window = wh::get_window_parent(control);
+                if !windows.contains(&window) {
+                    windows.push(window);
+                }
+            }
+
+            windows
+        };
+
+        let handler_id = CHECK_GROUP_ID.fetch_add(1, Ordering::SeqCst);
+        let mut handlers = self.handlers.borrow_mut();
+        for window in windows {
+            let group = self.inner.clone();
+            let cb = move |_hwnd, msg, w: WPARAM, l: LPARAM| {
+                if msg == WM_COMMAND && HIWORD(w as u32) == BN_CLICKED {
+                    if let Ok(inner) = group.try_borrow() {
+                        if inner.clicked(l as HWND) {
+                            inner.notify_changed();
+                        }
+                    }
+                }
+
+                None
+            };
+
+            handlers.push(bind_raw_event_handler_inner(&ControlHandle::Hwnd(window), handler_id, cb)?);
+        }
+
+        Ok(())
+    }
+
+    fn unbind_handlers(&self) {
+        for handler in self.handlers.borrow_mut().drain(..) {
+            drop(unbind_raw_event_handler(&handler));
+        }
+    }
+
+}
+
+impl Default for CheckBoxGroup {
+
+    fn default() -> CheckBoxGroup {
+        let inner = CheckBoxGroupInner {
+            parent: ptr::null_mut(),
+            children: Vec::new(),
+            policy: PartialCheckPolicy::CheckAll,
+        };
+
+        CheckBoxGroup {
+            inner: Rc::new(RefCell::new(inner)),
+            handlers: RefCell::new(Vec::new()),
+        }
+    }
+
+}
+
+impl Drop for CheckBoxGroup {
+    fn drop(&mut self) {
+        self.unbind_handlers();
+    }
+}
+
+
+pub struct CheckBoxGroupBuilder {
+    parent: Option<ControlHandle>,
+    children: Vec<ControlHandle>,
+    policy: PartialCheckPolicy,
+}
+
+impl CheckBoxGroupBuilder {
+
+    pub fn parent_check(mut self, parent: &CheckBox) -> CheckBoxGroupBuilder {
+        self.parent = Some(parent.handle);
+        self
+    }
+
+    pub fn child(mut self, child: &CheckBox) -> CheckBoxGroupBuilder {
+        self.children.push(child.handle);
+        self
+    }
+
+    pub fn children(mut self, children: &[&CheckBox]) -> CheckBoxGroupBuilder {
+        self.children.extend(children.iter().map(|c| c.handle));
+        self
+    }
+
+    pub fn policy(mut self, policy: PartialCheckPolicy) -> CheckBoxGroupBuilder {
+        self.policy = policy;
+        self
+    }
+
+    pub fn build(self, out: &mut CheckBoxGroup) -> Result<(), NwgError> {
+        use winapi::um::winuser::{GWL_STYLE, BM_SETSTYLE, BS_TYPEMASK, BS_AUTO3STATE};
+
+        let parent = match self.parent.and_then(|p| p.hwnd()) {
+            Some(p) => p,
+            None => { return Err(NwgError::no_parent("CheckBoxGroup")); }
+        };
+
+        let mut children = Vec::with_capacity(self.children.len());
+        for child in self.children {
+            match child.hwnd() {
+                Some(c) => if !children.contains(&c) { children.push(c); },
+                None => { return Err(NwgError::control_create("CheckBoxGroup child is not bound")); }
+            }
+        }
+
+        *out = CheckBoxGroup::default();
+
+        // Only change the button type, the other styles (ex: WS_VISIBLE, WS_TABSTOP) are kept
+        let style = wh::get_window_long(parent, GWL_STYLE) as u32;
+        wh::send_message(parent, BM_SETSTYLE, ((style & !BS_TYPEMASK) | BS_AUTO3STATE) as WPARAM, 1);
+
+        {
+            let mut inner = out.inner.borrow_mut();
+            inner.parent = parent;
+            inner.children = children;
+            inner.policy = self.policy;
+            inner.sync_parent();
+        }
+
+        out.bind_handlers()
+    }
+
+}
+
+fn check_state(handle: HWND) -> CheckBoxState {
+    use winapi::um::winuser::{BM_GETCHECK, BST_CHECKED, BST_INDETERMINATE};
+
+    match wh::send_message(handle, BM_GETCHECK, 0, 0) as usize {
+        BST_CHECKED => CheckBoxState::Checked,
+        BST_INDETERMINATE => CheckBoxState::Indeterminate,
+        _ => CheckBoxState::Unchecked
+    }
+}
+
+fn set_check_state(handle: HWND, state: CheckBoxState) {
+    use winapi::um::winuser::{BM_SETCHECK, BST_CHECKED, BST_INDETERMINATE, BST_UNCHECKED};
+
+    let state = match state {
+        CheckBoxState::Unchecked => BST_UNCHECKED,
+        CheckBoxState::Checked => BST_CHECKED,
+        CheckBoxState::Indeterminate => BST_INDETERMINATE,
+    };
+
+    wh::send_message(handle, BM_SETCHECK, state as WPARAM, 0);
+}
@@ -5,6 +5,7 @@ use crate::win32::base_helper::{check_hwnd, to_utf16, from_utf16};
 use crate::win32::window_helper as wh;
 use crate::{Font, NwgError, VTextAlign, RawEventHandler, unbind_raw_event_handler};
 use super::{ControlHandle, ControlBase};
+use super::control_builder::BuilderState;
 use std::cell::{Ref, RefMut, RefCell};
 use std::fmt::Display;
 use std::mem;
@@ -47,6 +48,7 @@ Requires the `combobox` feature.
   * `collection`:     The default collection of the combobox
   * `selected_index`: The default selected index. None means no values are selected.  
   * `focus`:          The control receive focus after being created
+  * `placeholder_text`: Text displayed in the combobox when no value is selected
 
 **Control events:**
   * `OnComboBoxClosed`: When the combobox dropdown is closed
@@ -54,6 +56,9 @@ Requires the `combobox` feature.
   * `OnComboxBoxSelection`: When a new value in a combobox is choosen
   * `MousePress(_)`: Generic mouse press events on the checkbox
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
 
 
@@ -91,7 +96,10 @@ impl<D: Display+Default> ComboBox<D> {
             font: None,
             collection: None,
             selected_index: None,
-            parent: None
+            placeholder_text: None,
+            parent: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -369,6 +377,28 @@ impl<D: Display+Default> ComboBox<D> {
         unsafe { wh::set_window_position(handle, x, y) }
     }
 
+    /// Return the placeholder text displayed in the combobox when no value is selected.
+    /// The string returned will be as long as the user specified, however it might be longer
+    /// or shorter than the actual placeholder text.
+    pub fn placeholder_text<'a>(&self, text_length: usize) -> String {
+        use winapi::um::commctrl::CB_GETCUEBANNER;
+        use winapi::um::winnt::WCHAR;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let mut placeholder_text: Vec<WCHAR> = vec![0; text_length];
+        wh::send_message(handle, CB_GETCUEBANNER, placeholder_text.as_mut_ptr() as WPARAM, placeholder_text.len() as LPARAM);
+        from_utf16(&placeholder_text)
+    }
+
+    /// Set the placeholder text displayed in the combobox when no value is selected
+    pub fn set_placeholder_text<'a>(&self, v: Option<&'a str>) {
+        use winapi::um::commctrl::CB_SETCUEBANNER;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let text = to_utf16(v.unwrap_or(""));
+        wh::send_message(handle, CB_SETCUEBANNER, 0, text.as_ptr() as LPARAM);
+    }
+
     /// Get read-only access to the inner collection of the combobox
     /// This call refcell.borrow under the hood. Be sure to drop the value before
     /// calling other combobox methods
@@ -535,7 +565,10 @@ pub struct ComboBoxBuilder<'a, D: Display+Default> {
     font: Option<&'a Font>,
     collection: Option<Vec<D>>,
     selected_index: Option<usize>,
-    parent: Option<ControlHandle>
+    placeholder_text: Option<&'a str>,
+    parent: Option<ControlHandle>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a, D: Display+Default> ComboBoxBuilder<'a, D> {
@@ -580,6 +613,11 @@ impl<'a, D: Display+Default> ComboBoxBuilder<'a, D> {
         self
     }
 
+    pub fn placeholder_text(mut self, placeholder_text: Option<&'a str>) -> ComboBoxBuilder<'a, D> {
+        self.placeholder_text = placeholder_text;
+        self
+    }
+
     pub fn enabled(mut self, e: bool) -> ComboBoxBuilder<'a, D> {
         self.enabled = e;
         self
@@ -595,8 +633,13 @@ impl<'a, D: Display+Default> ComboBoxBuilder<'a, D> {
         self
     }
 
+    pub fn name(mut self, name: &str) -> ComboBoxBuilder<'a, D> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut ComboBox<D>) -> Result<(), NwgError> {
-        let flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
 
         let parent = match self.parent {
             Some(p) => Ok(p),
@@ -607,6 +650,7 @@ impl<'a, D: Display+Default> ComboBoxBuilder<'a, D> {
         *out = ComboBox::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -630,6 +674,10 @@ impl<'a, D: Display+Default> ComboBoxBuilder<'a, D> {
             out.set_selection(self.selected_index);
         }
 
+        if self.placeholder_text.is_some() {
+            out.set_placeholder_text(self.placeholder_text);
+        }
+
         out.set_enabled(self.enabled);
 
         if self.focus {
@@ -2,6 +2,7 @@ use winapi::shared::minwindef::DWORD;
 use winapi::shared::windef::{HWND};
 use super::ControlHandle;
 use crate::win32::window::{build_hwnd_control, build_timer, build_notice};
+use crate::win32::custom_control::{CustomControl, register_custom_control};
 use crate::{NwgError};
 
 #[cfg(feature = "menu")] use crate::win32::menu::build_hmenu_control;
@@ -73,6 +74,37 @@ impl ControlBase {
             ty: TRAY
         }
     }
+
+    /**
+        Sets the order in which the Tab key moves the focus between `controls`. The controls must have the same parent.
+        The first control keeps its place in the tab order of the parent and the other controls are moved right after it,
+        so the controls that are not in `controls` keep their order.
+
+        By default, the tab order is the order in which the controls were created. Call this function again
+        after controls are added or moved by a layout change. Only the controls with a tab stop are focused with
+        the Tab key (see `ControlHandle::set_tab_stop`).
+
+        This function will panic if a control is not a window-like control.
+
+        ```rust
+        use native_windows_gui as nwg;
+
+        fn login_order(user: &nwg::TextInput, password: &nwg::TextInput, login: &nwg::Button) {
+            nwg::ControlBase::set_tab_order(&[&user.handle, &password.handle, &login.handle]);
+        }
+        ```
+    */
+    pub fn set_tab_order(controls: &[&ControlHandle]) {
+        use winapi::um::winuser::{SetWindowPos, SWP_NOMOVE, SWP_NOSIZE, SWP_NOACTIVATE, SWP_NOOWNERZORDER};
+
+        let handles: Vec<HWND> = controls.iter()
+            .map(|c| c.hwnd().expect("Only the window-like controls have a tab order"))
+            .collect();
+
+        for pair in handles.windows(2) {
+            unsafe { SetWindowPos(pair[1], pair[0], 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE | SWP_NOOWNERZORDER); }
+        }
+    }
 }
 
 
@@ -86,7 +118,9 @@ pub struct HwndBuilder {
     forced_flags: DWORD,
     flags: Option<DWORD>,
     ex_flags: Option<DWORD>,
-    parent: Option<HWND>
+    parent: Option<HWND>,
+    name: Option<String>,
+    register: Option<fn() -> Result<(), NwgError>>,
 }
 
 impl HwndBuilder {
@@ -96,6 +130,13 @@ impl HwndBuilder {
         self
     }
 
+    /// Use the window class of a custom control. The control is registered when the window is built. See `CustomControl`.
+    pub fn custom_control<C: CustomControl>(mut self) -> HwndBuilder {
+        self.class_name = C::class_name().to_string();
+        self.register = Some(register_custom_control::<C>);
+        self
+    }
+
     pub fn text<'a>(mut self, text: &'a str) -> HwndBuilder {
         self.text = Some(text.to_string());
         self
@@ -134,7 +175,17 @@ impl HwndBuilder {
         self
     }
 
+    /// Set the name of the control. See `set_control_name`.
+    pub fn name(mut self, name: Option<&str>) -> HwndBuilder {
+        self.name = name.map(|n| n.to_string());
+        self
+    }
+
     pub fn build(self) -> Result<ControlHandle, NwgError> {
+        if let Some(register) = self.register {
+            register()?;
+        }
+
         let handle = unsafe { build_hwnd_control(
             &self.class_name,
             self.text.as_ref().map(|v| v as &str),
@@ -146,6 +197,10 @@ impl HwndBuilder {
             self.parent
         )? };
 
+        if let Some(name) = self.name.as_ref() {
+            crate::win32::control_names::set_control_name(&handle, name);
+        }
+
         Ok(handle)
     }
 }
@@ -159,6 +214,7 @@ pub struct HmenuBuilder {
     item: bool,
     separator: bool,
     popup: bool,
+    position: Option<u32>,
     parent_menu: Option<HMENU>,
     parent_window: Option<HWND>,
 }
@@ -190,6 +246,12 @@ impl HmenuBuilder {
         self
     }
 
+    /// Insert the menu at the selected position in the parent. If `None`, the menu is appended.
+    pub fn position(mut self, p: Option<u32>) -> HmenuBuilder {
+        self.position = p;
+        self
+    }
+
     /// Set the parent of the menu. Can be a window or another menu.
     pub fn parent(mut self, parent: ControlHandle) -> HmenuBuilder {
         match parent {
@@ -208,6 +270,7 @@ impl HmenuBuilder {
             self.item,
             self.separator,
             self.popup,
+            self.position,
             self.parent_menu,
             self.parent_window
         )? };
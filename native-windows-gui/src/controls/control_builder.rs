@@ -0,0 +1,222 @@
+/*!
+    A common interface over the builders of the window-like controls. Generic code (factories, designers, interfaces built from a
+    configuration file) uses `ControlBuilder` to set the common parameters of a control without knowing its type.
+*/
+use crate::NwgError;
+use super::*;
+
+#[allow(unused)]
+use std::fmt::Display;
+
+
+/**
+    The parameters shared by the builders of the window-like controls, and a `finish` method that returns the built control.
+
+    The methods of `ControlBuilder` have the same name as the methods of the builders. The methods of the builders are still used
+    when the type of the builder is known, so the trait does not change the existing code.
+
+    The `enabled` and `visible` states are set before the control is created: they change the `DISABLED` and the `VISIBLE` flags
+    of the builder, or the `enabled` parameter of the builders that have one. A hidden control is never shown.
+
+    The controls that are not placed by the application do not implement the trait: `StatusBar` (docked at the bottom of its parent),
+    `Tab` (fills its `TabsContainer`), `DesignSurface` and `RubberBandSelector` (drawn over another control), `Eyedropper` (no window)
+    and `WindowThumbnail` (drawn by the desktop window manager).
+
+    ```rust
+    use native_windows_gui as nwg;
+    use nwg::ControlBuilder;
+
+    fn build_at<B: ControlBuilder>(builder: B, parent: &nwg::Window, pos: (i32, i32)) -> Result<B::Control, nwg::NwgError> {
+        builder
+            .parent(parent.handle)
+            .position(pos)
+            .size((100, 25))
+            .enabled(false)
+            .finish()
+    }
+
+    fn build_form(parent: &nwg::Window) -> Result<(nwg::Button, nwg::TextInput), nwg::NwgError> {
+        let button = build_at(nwg::Button::builder().text("Ok"), parent, (10, 10))?;
+        let input = build_at(nwg::TextInput::builder(), parent, (10, 40))?;
+        Ok((button, input))
+    }
+    ```
+*/
+pub trait ControlBuilder: Sized {
+    /// The control created by the builder
+    type Control: Default;
+
+    /// Sets the parent of the control
+    fn parent(self, parent: ControlHandle) -> Self;
+
+    /// Sets the position of the control in its parent, in logical pixels
+    fn position(self, pos: (i32, i32)) -> Self;
+
+    /// Sets the size of the control, in logical pixels
+    fn size(self, size: (i32, i32)) -> Self;
+
+    /// Creates the control enabled or disabled
+    fn enabled(self, enabled: bool) -> Self;
+
+    /// Creates the control visible or hidden
+    fn visible(self, visible: bool) -> Self;
+
+    /// Builds the control and returns it
+    fn finish(self) -> Result<Self::Control, NwgError>;
+}
+
+/// The states set with `ControlBuilder`. They are merged in the flags of the control before it is created.
+#[derive(Copy, Clone, Default, Debug)]
+pub(crate) struct BuilderState {
+    enabled: Option<bool>,
+    visible: Option<bool>,
+}
+
+impl BuilderState {
+
+    /// Returns `flags` with the `WS_DISABLED` and the `WS_VISIBLE` styles set by `enabled` and `visible`
+    pub(crate) fn flags(&self, mut flags: u32) -> u32 {
+        use winapi::um::winuser::{WS_DISABLED, WS_VISIBLE};
+
+        match self.enabled {
+            Some(true) => { flags &= !WS_DISABLED; },
+            Some(false) => { flags |= WS_DISABLED; },
+            None => {}
+        }
+
+        match self.visible {
+            Some(true) => { flags |= WS_VISIBLE; },
+            Some(false) => { flags &= !WS_VISIBLE; },
+            None => {}
+        }
+
+        flags
+    }
+
+}
+
+macro_rules! control_builder {
+    ([$($gen:tt)*] $builder:ty => $control:ty) => {
+        control_builder!(@impl [$($gen)*] $builder => $control, parent, parent, state);
+    };
+
+    ([$($gen:tt)*] $builder:ty => $control:ty, optional parent) => {
+        control_builder!(@impl [$($gen)*] $builder => $control, parent, Some(parent), state);
+    };
+
+    // The builder has its own `enabled` parameter
+    ([$($gen:tt)*] $builder:ty => $control:ty, enabled) => {
+        control_builder!(@impl [$($gen)*] $builder => $control, parent, parent, builder);
+    };
+
+    (@enabled state) => {
+        fn enabled(mut self, enabled: bool) -> Self {
+            self.state.enabled = Some(enabled);
+            self
+        }
+    };
+
+    (@enabled builder) => {
+        fn enabled(self, enabled: bool) -> Self { self.enabled(enabled) }
+    };
+
+    (@impl [$($gen:tt)*] $builder:ty => $control:ty, $p:ident, $parent:expr, $enabled:ident) => {
+        impl<$($gen)*> ControlBuilder for $builder {
+            type Control = $control;
+
+            fn parent(self, $p: ControlHandle) -> Self { self.parent($parent) }
+
+            fn position(self, pos: (i32, i32)) -> Self { self.position(pos) }
+
+            fn size(self, size: (i32, i32)) -> Self { self.size(size) }
+
+            control_builder!(@enabled $enabled);
+
+            fn visible(mut self, visible: bool) -> Self {
+                self.state.visible = Some(visible);
+                self
+            }
+
+            fn finish(self) -> Result<$control, NwgError> {
+                let mut control: $control = Default::default();
+                self.build(&mut control)?;
+                Ok(control)
+            }
+        }
+    };
+}
+
+control_builder!(['a] WindowBuilder<'a> => Window, optional parent);
+control_builder!(['a] ButtonBuilder<'a> => Button, enabled);
+control_builder!(['a] SplitButtonBuilder<'a> => SplitButton, enabled);
+control_builder!(['a] CheckBoxBuilder<'a> => CheckBox, enabled);
+control_builder!(['a] RadioButtonBuilder<'a> => RadioButton);
+control_builder!(['a] TextInputBuilder<'a> => TextInput);
+control_builder!(['a] LabelBuilder<'a> => Label);
+control_builder!(['a] ImageFrameBuilder<'a> => ImageFrame);
+
+#[cfg(feature = "textbox")]
+control_builder!(['a] TextBoxBuilder<'a> => TextBox);
+
+#[cfg(feature = "rich-textbox")]
+control_builder!(['a] RichTextBoxBuilder<'a> => RichTextBox);
+
+#[cfg(feature = "rich-textbox")]
+control_builder!(['a] RichLabelBuilder<'a> => RichLabel);
+
+#[cfg(feature = "log-view")]
+control_builder!(['a] LogViewBuilder<'a> => LogView);
+
+#[cfg(feature = "trackbar")]
+control_builder!([] TrackBarBuilder => TrackBar);
+
+#[cfg(feature = "combobox")]
+control_builder!(['a, D: Display+Default] ComboBoxBuilder<'a, D> => ComboBox<D>, enabled);
+
+#[cfg(feature = "listbox")]
+control_builder!(['a, D: Display+Default] ListBoxBuilder<'a, D> => ListBox<D>, enabled);
+
+#[cfg(feature = "datetime-picker")]
+control_builder!(['a] DatePickerBuilder<'a> => DatePicker);
+
+#[cfg(feature = "date-range-picker")]
+control_builder!(['a] DateRangePickerBuilder<'a> => DateRangePicker, enabled);
+
+#[cfg(feature = "progress-bar")]
+control_builder!([] ProgressBarBuilder => ProgressBar);
+
+#[cfg(feature = "tabs")]
+control_builder!(['a] TabsContainerBuilder<'a> => TabsContainer);
+
+#[cfg(feature = "tree-view")]
+control_builder!(['a] TreeViewBuilder<'a> => TreeView, enabled);
+
+#[cfg(feature = "list-view")]
+control_builder!([] ListViewBuilder => ListView);
+
+#[cfg(feature = "number-select")]
+control_builder!(['a] NumberSelectBuilder<'a> => NumberSelect, enabled);
+
+#[cfg(feature = "extern-canvas")]
+control_builder!(['a] ExternCanvasBuilder<'a> => ExternCanvas, optional parent);
+
+#[cfg(feature = "extern-canvas")]
+control_builder!(['a] RulerBuilder<'a> => Ruler);
+
+#[cfg(feature = "zoom-view")]
+control_builder!([] ZoomViewBuilder => ZoomView);
+
+#[cfg(feature = "frame")]
+control_builder!([] FrameBuilder => Frame, enabled);
+
+#[cfg(feature = "group-box")]
+control_builder!(['a] GroupBoxBuilder<'a> => GroupBox, enabled);
+
+#[cfg(feature = "scroll-bar")]
+control_builder!([] ScrollBarBuilder => ScrollBar, enabled);
+
+#[cfg(feature = "plotting")]
+control_builder!([] PlottersBuilder => Plotters);
+
+#[cfg(feature = "printing")]
+control_builder!(['a] PrintPreviewBuilder<'a> => PrintPreview);
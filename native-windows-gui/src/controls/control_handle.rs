@@ -1,5 +1,6 @@
 use winapi::shared::windef::{HWND, HMENU};
 use crate::win32::window_helper as wh;
+use crate::geometry::{Rect, LogicalRect, PhysicalRect, control_dpi};
 
 
 /**
@@ -102,6 +103,100 @@ impl ControlHandle {
         }
     }
 
+    /// Returns true if the user can move the focus to the control with the Tab key.
+    /// Returns false if the handle is not a window-like control.
+    pub fn tab_stop(&self) -> bool {
+        use winapi::um::winuser::WS_TABSTOP;
+
+        match self.hwnd() {
+            Some(h) => wh::get_style(h) & WS_TABSTOP == WS_TABSTOP,
+            None => false
+        }
+    }
+
+    /**
+        Sets if the user can move the focus to the control with the Tab key. Does nothing if the handle is not a window-like control.
+        The control can still receive the focus with the mouse or with `set_focus`.
+    */
+    pub fn set_tab_stop(&self, tab_stop: bool) {
+        use winapi::um::winuser::WS_TABSTOP;
+
+        if let Some(h) = self.hwnd() {
+            let style = wh::get_style(h);
+            match tab_stop {
+                true => wh::set_style(h, style | WS_TABSTOP),
+                false => wh::set_style(h, style & !WS_TABSTOP)
+            }
+        }
+    }
+
+    /**
+        Returns the position of the control in the client area of its parent (or on the desktop for a top level window)
+        and the size of its client area, in logical pixels. Returns `None` if the handle is not a window-like control.
+    */
+    pub fn rect(&self) -> Option<LogicalRect> {
+        let rect = self.physical_rect()?;
+        Some(rect.to_logical(control_dpi(*self)))
+    }
+
+    /// Same as `rect`, in physical pixels
+    pub fn physical_rect(&self) -> Option<PhysicalRect> {
+        let handle = self.hwnd()?;
+        unsafe {
+            let (x, y) = wh::get_window_physical_position(handle);
+            let (w, h) = wh::get_window_physical_size(handle);
+            Some(PhysicalRect::new(x, y, w, h))
+        }
+    }
+
+    /**
+        Moves and resizes the control, in logical pixels. The size is the size of the client area for a top level window.
+        Does nothing if the handle is not a window-like control.
+
+        The control is moved and resized with a single call, so an animation does not redraw the control twice per frame:
+
+        ```rust
+        use native_windows_gui as nwg;
+
+        // Called in the `OnTimerTick` handler of an `AnimationTimer` with the progress of `EventData::on_animation_tick`
+        fn slide_in(panel: &nwg::Frame, start: nwg::LogicalRect, end: nwg::LogicalRect, progress: f32) {
+            let t = nwg::easing::cubic_out(progress);
+            panel.handle.set_rect(start.lerp(end, t));
+        }
+        ```
+    */
+    pub fn set_rect(&self, rect: LogicalRect) {
+        if self.hwnd().is_some() {
+            self.set_physical_rect(rect.to_physical(control_dpi(*self)));
+        }
+    }
+
+    /// Same as `set_rect`, in physical pixels
+    pub fn set_physical_rect(&self, rect: PhysicalRect) {
+        use winapi::um::winuser::WS_CHILD;
+
+        if let Some(handle) = self.hwnd() {
+            let top_level = wh::get_style(handle) & WS_CHILD == 0;
+            let Rect { origin, size } = rect;
+            unsafe { wh::set_window_physical_rect(handle, origin.x, origin.y, size.width, size.height, top_level); }
+        }
+    }
+
+    /// Returns true if the control is visible. Returns false if the handle is not a window-like control.
+    pub fn visible(&self) -> bool {
+        match self.hwnd() {
+            Some(h) => unsafe { wh::get_window_visibility(h) },
+            None => false
+        }
+    }
+
+    /// Shows or hides the control. Does nothing if the handle is not a window-like control.
+    pub fn set_visible(&self, visible: bool) {
+        if let Some(h) = self.hwnd() {
+            unsafe { wh::set_window_visibility(h, visible); }
+        }
+    }
+
 }
 
 
@@ -0,0 +1,546 @@
+/*!
+    A light reflection layer over the controls properties. Each supported control exposes its properties as typed key/value pairs
+    that can be read and written at runtime. This is used to implement generic property editors and to save/load controls state.
+*/
+use winapi::shared::windef::HWND;
+use crate::win32::{base_helper::check_hwnd, window_helper as wh};
+use crate::NwgError;
+use super::*;
+use std::fmt;
+
+const NOT_BOUND: &'static str = "Control is not yet bound to a winapi object";
+const BAD_HANDLE: &'static str = "INTERNAL ERROR: Control handle is not HWND!";
+
+
+/// The type of a control property
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PropertyKind {
+    Bool,
+    Int,
+    Text,
+    Size,
+    Position,
+
+    /// A text value that must be one of the listed values
+    Choice(&'static [&'static str]),
+}
+
+/// The value of a control property
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PropertyValue {
+    Bool(bool),
+    Int(i64),
+    Text(String),
+    Size(u32, u32),
+    Position(i32, i32),
+}
+
+impl PropertyValue {
+
+    /**
+        Parse a value previously formatted with `to_string` (ex: `"true"`, `"12"`, `"100x25"`, `"10,-5"`)
+        Returns `None` if `value` cannot be parsed as `kind`.
+    */
+    pub fn parse(kind: PropertyKind, value: &str) -> Option<PropertyValue> {
+        fn pair<T: std::str::FromStr>(value: &str, sep: char) -> Option<(T, T)> {
+            let mut it = value.splitn(2, sep);
+            let a = it.next()?.trim().parse().ok()?;
+            let b = it.next()?.trim().parse().ok()?;
+            Some((a, b))
+        }
+
+        match kind {
+            PropertyKind::Bool => value.trim().parse().ok().map(PropertyValue::Bool),
+            PropertyKind::Int => value.trim().parse().ok().map(PropertyValue::Int),
+            PropertyKind::Text => Some(PropertyValue::Text(value.to_string())),
+            PropertyKind::Size => pair(value, 'x').map(|(w, h)| PropertyValue::Size(w, h)),
+            PropertyKind::Position => pair(value, ',').map(|(x, y)| PropertyValue::Position(x, y)),
+            PropertyKind::Choice(values) => values.iter()
+                .find(|&&v| v == value)
+                .map(|v| PropertyValue::Text(v.to_string())),
+        }
+    }
+
+}
+
+impl fmt::Display for PropertyValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PropertyValue::Bool(v) => write!(f, "{}", v),
+            PropertyValue::Int(v) => write!(f, "{}", v),
+            PropertyValue::Text(v) => write!(f, "{}", v),
+            PropertyValue::Size(w, h) => write!(f, "{}x{}", w, h),
+            PropertyValue::Position(x, y) => write!(f, "{},{}", x, y),
+        }
+    }
+}
+
+/// Describe a property exposed by a control
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PropertyInfo {
+    pub name: &'static str,
+    pub kind: PropertyKind,
+}
+
+const fn info(name: &'static str, kind: PropertyKind) -> PropertyInfo {
+    PropertyInfo { name, kind }
+}
+
+const COMMON_PROPERTIES: &'static [PropertyInfo] = &[
+    info("text", PropertyKind::Text),
+    info("size", PropertyKind::Size),
+    info("position", PropertyKind::Position),
+    info("enabled", PropertyKind::Bool),
+    info("visible", PropertyKind::Bool),
+];
+
+
+/**
+    Runtime access to the properties of a control.
+
+    Properties names match the name of the builder methods (ex: `text`, `size`, `check_state`).
+    Setting a property with the wrong type or an unknown name returns a `NwgError::PropertyError`.
+
+```rust
+use native_windows_gui as nwg;
+use nwg::ControlProperties;
+
+fn save_and_restore(button: &nwg::Button, other: &nwg::Button) -> Result<(), nwg::NwgError> {
+    let saved = nwg::serialize_properties(button);
+    nwg::deserialize_properties(other, &saved)
+}
+```
+*/
+pub trait ControlProperties {
+
+    /// Returns the properties exposed by the control
+    fn properties(&self) -> Vec<PropertyInfo>;
+
+    /// Returns the value of the property `name`, or `None` if the control does not have this property
+    fn property(&self, name: &str) -> Option<PropertyValue>;
+
+    /// Sets the value of the property `name`
+    fn set_property(&self, name: &str, value: PropertyValue) -> Result<(), NwgError>;
+
+    /// Returns the name and the value of every property of the control
+    fn property_values(&self) -> Vec<(&'static str, PropertyValue)> {
+        self.properties()
+            .iter()
+            .filter_map(|p| self.property(p.name).map(|v| (p.name, v)))
+            .collect()
+    }
+
+}
+
+/**
+    Serialize the properties of a control into a string. Each property is written on its own line as `name=value`.
+    Line breaks in text values are escaped.
+*/
+pub fn serialize_properties(control: &dyn ControlProperties) -> String {
+    let mut out = String::new();
+    for (name, value) in control.property_values() {
+        let value = escape(&value.to_string());
+        out.push_str(name);
+        out.push('=');
+        out.push_str(&value);
+        out.push('\n');
+    }
+
+    out
+}
+
+/**
+    Sets the properties of a control from a string generated by `serialize_properties`.
+    Unknown properties are ignored. Returns an error if a value cannot be parsed.
+*/
+pub fn deserialize_properties(control: &dyn ControlProperties, data: &str) -> Result<(), NwgError> {
+    let properties = control.properties();
+
+    for line in data.lines().filter(|l| !l.is_empty()) {
+        let mut it = line.splitn(2, '=');
+        let name = it.next().unwrap_or("");
+        let raw = it.next().unwrap_or("");
+
+        let info = match properties.iter().find(|p| p.name == name) {
+            Some(info) => info,
+            None => { continue; }
+        };
+
+        let raw = unescape(raw);
+        let value = PropertyValue::parse(info.kind, &raw)
+            .ok_or_else(|| NwgError::property(format!("Bad value {:?} for property {:?}", raw, name)))?;
+
+        control.set_property(name, value)?;
+    }
+
+    Ok(())
+}
+
+/// Escapes the backslashes and the line breaks of a value, so it fits on one line. See `unescape`.
+pub(crate) fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+/// Reverts `escape`. An unknown escape sequence is replaced by the escaped character and a trailing backslash is kept.
+pub(crate) fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('r') => out.push('\r'),
+                Some(c) => out.push(c),
+                None => out.push('\\'),
+            },
+            c => out.push(c)
+        }
+    }
+
+    out
+}
+
+fn bad_value(name: &str, value: &PropertyValue) -> NwgError {
+    NwgError::property(format!("Bad value {:?} for property {:?}", value, name))
+}
+
+fn unknown_property(name: &str) -> NwgError {
+    NwgError::property(format!("Unknown property {:?}", name))
+}
+
+fn common_property(handle: HWND, name: &str) -> Option<PropertyValue> {
+    unsafe {
+        match name {
+            "text" => Some(PropertyValue::Text(wh::get_window_text(handle))),
+            "size" => { let (w, h) = wh::get_window_size(handle); Some(PropertyValue::Size(w, h)) },
+            "position" => { let (x, y) = wh::get_window_position(handle); Some(PropertyValue::Position(x, y)) },
+            "enabled" => Some(PropertyValue::Bool(wh::get_window_enabled(handle))),
+            "visible" => Some(PropertyValue::Bool(wh::get_window_visibility(handle))),
+            _ => None
+        }
+    }
+}
+
+/// Sets one of the common properties. `properties` are all the properties of the control, so that a bad value
+/// for a control specific property that was not handled by the caller is not reported as an unknown property.
+fn set_common_property(handle: HWND, properties: &[PropertyInfo], name: &str, value: PropertyValue, fix_size: bool) -> Result<(), NwgError> {
+    unsafe {
+        match (name, value) {
+            ("text", PropertyValue::Text(t)) => wh::set_window_text(handle, &t),
+            ("size", PropertyValue::Size(w, h)) => wh::set_window_size(handle, w, h, fix_size),
+            ("position", PropertyValue::Position(x, y)) => wh::set_window_position(handle, x, y),
+            ("enabled", PropertyValue::Bool(v)) => wh::set_window_enabled(handle, v),
+            ("visible", PropertyValue::Bool(v)) => wh::set_window_visibility(handle, v),
+            (name, value) => match properties.iter().any(|p| p.name == name) {
+                true => { return Err(bad_value(name, &value)); },
+                false => { return Err(unknown_property(name)); }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Implements `ControlProperties` for controls that only have the common properties
+macro_rules! common_properties {
+    ($control:ty) => {
+        impl ControlProperties for $control {
+            fn properties(&self) -> Vec<PropertyInfo> {
+                COMMON_PROPERTIES.to_vec()
+            }
+
+            fn property(&self, name: &str) -> Option<PropertyValue> {
+                let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+                common_property(handle, name)
+            }
+
+            fn set_property(&self, name: &str, value: PropertyValue) -> Result<(), NwgError> {
+                let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+                set_common_property(handle, COMMON_PROPERTIES, name, value, false)
+            }
+        }
+    };
+}
+
+common_properties!(Button);
+common_properties!(Label);
+common_properties!(ImageFrame);
+
+#[cfg(feature = "textbox")]
+common_properties!(TextBox);
+
+#[cfg(feature = "frame")]
+common_properties!(Frame);
+
+impl ControlProperties for Window {
+    fn properties(&self) -> Vec<PropertyInfo> {
+        COMMON_PROPERTIES.to_vec()
+    }
+
+    fn property(&self, name: &str) -> Option<PropertyValue> {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        common_property(handle, name)
+    }
+
+    fn set_property(&self, name: &str, value: PropertyValue) -> Result<(), NwgError> {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        set_common_property(handle, COMMON_PROPERTIES, name, value, true)
+    }
+}
+
+const CHECK_STATES: &'static [&'static str] = &["Checked", "Unchecked", "Indeterminate"];
+
+impl ControlProperties for CheckBox {
+    fn properties(&self) -> Vec<PropertyInfo> {
+        let mut p = COMMON_PROPERTIES.to_vec();
+        p.push(info("check_state", PropertyKind::Choice(CHECK_STATES)));
+        p.push(info("tristate", PropertyKind::Bool));
+        p
+    }
+
+    fn property(&self, name: &str) -> Option<PropertyValue> {
+        match name {
+            "check_state" => {
+                let state = match self.check_state() {
+                    CheckBoxState::Checked => CHECK_STATES[0],
+                    CheckBoxState::Unchecked => CHECK_STATES[1],
+                    CheckBoxState::Indeterminate => CHECK_STATES[2],
+                };
+                Some(PropertyValue::Text(state.to_string()))
+            },
+            "tristate" => Some(PropertyValue::Bool(self.tristate())),
+            name => {
+                let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+                common_property(handle, name)
+            }
+        }
+    }
+
+    fn set_property(&self, name: &str, value: PropertyValue) -> Result<(), NwgError> {
+        match (name, value) {
+            ("check_state", PropertyValue::Text(state)) => match state.as_str() {
+                "Checked" => self.set_check_state(CheckBoxState::Checked),
+                "Unchecked" => self.set_check_state(CheckBoxState::Unchecked),
+                "Indeterminate" => self.set_check_state(CheckBoxState::Indeterminate),
+                _ => { return Err(bad_value(name, &PropertyValue::Text(state.clone()))); }
+            },
+            ("tristate", PropertyValue::Bool(v)) => self.set_tristate(v),
+            (name, value) => {
+                let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+                return set_common_property(handle, &self.properties(), name, value, false);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ControlProperties for RadioButton {
+    fn properties(&self) -> Vec<PropertyInfo> {
+        let mut p = COMMON_PROPERTIES.to_vec();
+        p.push(info("check_state", PropertyKind::Choice(&CHECK_STATES[0..2])));
+        p
+    }
+
+    fn property(&self, name: &str) -> Option<PropertyValue> {
+        match name {
+            "check_state" => {
+                let state = match self.check_state() {
+                    RadioButtonState::Checked => CHECK_STATES[0],
+                    RadioButtonState::Unchecked => CHECK_STATES[1],
+                };
+                Some(PropertyValue::Text(state.to_string()))
+            },
+            name => {
+                let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+                common_property(handle, name)
+            }
+        }
+    }
+
+    fn set_property(&self, name: &str, value: PropertyValue) -> Result<(), NwgError> {
+        match (name, value) {
+            ("check_state", PropertyValue::Text(state)) => match state.as_str() {
+                "Checked" => self.set_check_state(RadioButtonState::Checked),
+                "Unchecked" => self.set_check_state(RadioButtonState::Unchecked),
+                _ => { return Err(bad_value(name, &PropertyValue::Text(state.clone()))); }
+            },
+            (name, value) => {
+                let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+                return set_common_property(handle, &self.properties(), name, value, false);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ControlProperties for TextInput {
+    fn properties(&self) -> Vec<PropertyInfo> {
+        let mut p = COMMON_PROPERTIES.to_vec();
+        p.push(info("readonly", PropertyKind::Bool));
+        p.push(info("limit", PropertyKind::Int));
+        p.push(info("password", PropertyKind::Text));
+        p
+    }
+
+    fn property(&self, name: &str) -> Option<PropertyValue> {
+        match name {
+            "readonly" => Some(PropertyValue::Bool(self.readonly())),
+            "limit" => Some(PropertyValue::Int(self.limit() as i64)),
+            "password" => Some(PropertyValue::Text(self.password_char().map(|c| c.to_string()).unwrap_or_default())),
+            name => {
+                let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+                common_property(handle, name)
+            }
+        }
+    }
+
+    fn set_property(&self, name: &str, value: PropertyValue) -> Result<(), NwgError> {
+        match (name, value) {
+            ("readonly", PropertyValue::Bool(v)) => self.set_readonly(v),
+            ("limit", PropertyValue::Int(v)) if v >= 0 => self.set_limit(v as usize),
+            ("password", PropertyValue::Text(v)) => self.set_password_char(v.chars().next()),
+            (name, value) => {
+                let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+                return set_common_property(handle, &self.properties(), name, value, false);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "trackbar")]
+impl ControlProperties for TrackBar {
+    fn properties(&self) -> Vec<PropertyInfo> {
+        let mut p = COMMON_PROPERTIES[1..].to_vec();
+        p.push(info("pos", PropertyKind::Int));
+        p.push(info("range_min", PropertyKind::Int));
+        p.push(info("range_max", PropertyKind::Int));
+        p
+    }
+
+    fn property(&self, name: &str) -> Option<PropertyValue> {
+        match name {
+            "pos" => Some(PropertyValue::Int(self.pos() as i64)),
+            "range_min" => Some(PropertyValue::Int(self.range_min() as i64)),
+            "range_max" => Some(PropertyValue::Int(self.range_max() as i64)),
+            "text" => None,
+            name => {
+                let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+                common_property(handle, name)
+            }
+        }
+    }
+
+    fn set_property(&self, name: &str, value: PropertyValue) -> Result<(), NwgError> {
+        match (name, value) {
+            ("pos", PropertyValue::Int(v)) if v >= 0 => self.set_pos(v as usize),
+            ("range_min", PropertyValue::Int(v)) if v >= 0 => self.set_range_min(v as usize),
+            ("range_max", PropertyValue::Int(v)) if v >= 0 => self.set_range_max(v as usize),
+            ("text", _) => { return Err(unknown_property(name)); },
+            (name, value) => {
+                let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+                return set_common_property(handle, &self.properties(), name, value, false);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "progress-bar")]
+impl ControlProperties for ProgressBar {
+    fn properties(&self) -> Vec<PropertyInfo> {
+        let mut p = COMMON_PROPERTIES[1..].to_vec();
+        p.push(info("pos", PropertyKind::Int));
+        p.push(info("step", PropertyKind::Int));
+        p
+    }
+
+    fn property(&self, name: &str) -> Option<PropertyValue> {
+        match name {
+            "pos" => Some(PropertyValue::Int(self.pos() as i64)),
+            "step" => Some(PropertyValue::Int(self.step() as i64)),
+            "text" => None,
+            name => {
+                let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+                common_property(handle, name)
+            }
+        }
+    }
+
+    fn set_property(&self, name: &str, value: PropertyValue) -> Result<(), NwgError> {
+        match (name, value) {
+            ("pos", PropertyValue::Int(v)) if v >= 0 => self.set_pos(v as u32),
+            ("step", PropertyValue::Int(v)) if v >= 0 => self.set_step(v as u32),
+            ("text", _) => { return Err(unknown_property(name)); },
+            (name, value) => {
+                let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+                return set_common_property(handle, &self.properties(), name, value, false);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Forwards the properties to the inner control. Controls without a specific implementation only expose the common properties.
+impl ControlProperties for DynControl {
+    fn properties(&self) -> Vec<PropertyInfo> {
+        match self.reflect() {
+            Some(c) => c.properties(),
+            None => COMMON_PROPERTIES.to_vec()
+        }
+    }
+
+    fn property(&self, name: &str) -> Option<PropertyValue> {
+        match self.reflect() {
+            Some(c) => c.property(name),
+            None => {
+                let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+                common_property(handle, name)
+            }
+        }
+    }
+
+    fn set_property(&self, name: &str, value: PropertyValue) -> Result<(), NwgError> {
+        match self.reflect() {
+            Some(c) => c.set_property(name, value),
+            None => {
+                let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+                set_common_property(handle, COMMON_PROPERTIES, name, value, false)
+            }
+        }
+    }
+}
+
+impl DynControl {
+
+    fn reflect(&self) -> Option<&dyn ControlProperties> {
+        macro_rules! try_type {
+            ($ty:ty) => {
+                if let Some(c) = self.downcast_ref::<$ty>() {
+                    return Some(c as &dyn ControlProperties);
+                }
+            };
+        }
+
+        try_type!(Window);
+        try_type!(Button);
+        try_type!(CheckBox);
+        try_type!(RadioButton);
+        try_type!(TextInput);
+        try_type!(Label);
+        try_type!(ImageFrame);
+
+        #[cfg(feature = "textbox")] try_type!(TextBox);
+        #[cfg(feature = "frame")] try_type!(Frame);
+        #[cfg(feature = "trackbar")] try_type!(TrackBar);
+        #[cfg(feature = "progress-bar")] try_type!(ProgressBar);
+
+        None
+    }
+
+}
@@ -1,8 +1,12 @@
 use winapi::um::winuser::{WS_VISIBLE, WS_DISABLED, WS_TABSTOP};
+use winapi::um::commctrl::{DTS_TIMEFORMAT, DTS_UPDOWN};
+use winapi::um::minwinbase::SYSTEMTIME;
+use std::time::{SystemTime, UNIX_EPOCH, Duration};
 use crate::win32::window_helper as wh;
 use crate::win32::base_helper::{to_utf16, check_hwnd};
 use crate::{Font, NwgError};
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
 
 const NOT_BOUND: &'static str = "DatePicker is not yet bound to a winapi object";
 const BAD_HANDLE: &'static str = "INTERNAL ERROR: DatePicker handle is not HWND!";
@@ -17,11 +21,15 @@ bitflags! {
         * VISIBLE:  The date picker is immediatly visible after creation
         * DISABLED: The date picker cannot be interacted with by the user. It also has a grayed out look.
         * TAB_STOP: The control can be selected using tab navigation
+        * TIME:     The control displays and edits the time instead of the date. Implies `UP_DOWN`.
+        * UP_DOWN:  Replace the calendar dropdown by an up-down control to edit the value
     */
     pub struct DatePickerFlags: u32 {
         const VISIBLE = WS_VISIBLE;
         const DISABLED = WS_DISABLED;
         const TAB_STOP = WS_TABSTOP;
+        const TIME = DTS_TIMEFORMAT;
+        const UP_DOWN = DTS_UPDOWN;
     }
 }
 
@@ -36,6 +44,149 @@ pub struct DatePickerValue {
     pub day: u16
 }
 
+/**
+    A date and time struct that can be passed to a date time picker control.
+    Fields are self explanatory. `month` and `day` are 1-based.
+
+    `DateTimeValue` can be converted from and into a `std::time::SystemTime`. The value is assumed to be in UTC.
+    With the `datetime-chrono` feature, it can also be converted from and into a `chrono::NaiveDateTime`.
+*/
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct DateTimeValue {
+    pub year: u16,
+    pub month: u16,
+    pub day: u16,
+    pub hour: u16,
+    pub minute: u16,
+    pub second: u16,
+    pub milliseconds: u16,
+}
+
+impl DateTimeValue {
+
+    /// Return the date part of the value
+    pub fn date(&self) -> DatePickerValue {
+        DatePickerValue { year: self.year, month: self.month, day: self.day }
+    }
+
+    /// Return the time part of the value as `(hour, minute, second, milliseconds)`
+    pub fn time(&self) -> (u16, u16, u16, u16) {
+        (self.hour, self.minute, self.second, self.milliseconds)
+    }
+
+    fn from_system(st: &SYSTEMTIME) -> DateTimeValue {
+        DateTimeValue {
+            year: st.wYear, month: st.wMonth, day: st.wDay,
+            hour: st.wHour, minute: st.wMinute, second: st.wSecond, milliseconds: st.wMilliseconds
+        }
+    }
+
+    fn to_system(&self) -> SYSTEMTIME {
+        SYSTEMTIME {
+            wYear: self.year, wMonth: self.month, wDayOfWeek: 0, wDay: self.day,
+            wHour: self.hour, wMinute: self.minute, wSecond: self.second, wMilliseconds: self.milliseconds
+        }
+    }
+
+}
+
+impl From<DatePickerValue> for DateTimeValue {
+    fn from(date: DatePickerValue) -> DateTimeValue {
+        DateTimeValue { year: date.year, month: date.month, day: date.day, hour: 0, minute: 0, second: 0, milliseconds: 0 }
+    }
+}
+
+impl From<SystemTime> for DateTimeValue {
+    fn from(time: SystemTime) -> DateTimeValue {
+        let millis = match time.duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_millis() as i64,
+            Err(e) => -(e.duration().as_millis() as i64),
+        };
+
+        let days = millis.div_euclid(86_400_000);
+        let day_millis = millis.rem_euclid(86_400_000);
+        let (year, month, day) = civil_from_days(days);
+
+        DateTimeValue {
+            year: year as u16,
+            month: month as u16,
+            day: day as u16,
+            hour: (day_millis / 3_600_000) as u16,
+            minute: ((day_millis / 60_000) % 60) as u16,
+            second: ((day_millis / 1000) % 60) as u16,
+            milliseconds: (day_millis % 1000) as u16,
+        }
+    }
+}
+
+impl From<DateTimeValue> for SystemTime {
+    fn from(value: DateTimeValue) -> SystemTime {
+        let days = days_from_civil(value.year as i64, value.month as i64, value.day as i64);
+        let millis = days * 86_400_000
+            + (value.hour as i64) * 3_600_000
+            + (value.minute as i64) * 60_000
+            + (value.second as i64) * 1000
+            + (value.milliseconds as i64);
+
+        match millis >= 0 {
+            true => UNIX_EPOCH + Duration::from_millis(millis as u64),
+            false => UNIX_EPOCH - Duration::from_millis((-millis) as u64),
+        }
+    }
+}
+
+#[cfg(feature = "datetime-chrono")]
+impl From<chrono::NaiveDateTime> for DateTimeValue {
+    fn from(value: chrono::NaiveDateTime) -> DateTimeValue {
+        use chrono::{Datelike, Timelike};
+
+        DateTimeValue {
+            year: value.year() as u16,
+            month: value.month() as u16,
+            day: value.day() as u16,
+            hour: value.hour() as u16,
+            minute: value.minute() as u16,
+            second: value.second() as u16,
+            milliseconds: (value.nanosecond() / 1_000_000).min(999) as u16,
+        }
+    }
+}
+
+#[cfg(feature = "datetime-chrono")]
+impl DateTimeValue {
+
+    /// Convert the value into a `chrono::NaiveDateTime`. Return `None` if the value is not a valid date or time.
+    pub fn to_naive_date_time(&self) -> Option<chrono::NaiveDateTime> {
+        chrono::NaiveDate::from_ymd_opt(self.year as i32, self.month as u32, self.day as u32)
+            .and_then(|d| d.and_hms_milli_opt(self.hour as u32, self.minute as u32, self.second as u32, self.milliseconds as u32))
+    }
+
+}
+
+/// Number of days since 1970-01-01 of a date in the proleptic gregorian calendar
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Inverse of `days_from_civil`
+pub(crate) fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let doe = days - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
 
 
 /**
@@ -53,6 +204,7 @@ Requires the `datetime-picker` feature.
   * `ex_flags`: A combination of win32 window extended flags. Unlike `flags`, ex_flags must be used straight from winapi
   * `font`:     The font used for the dtp text
   * `date`:     The default date as a `DatePickerValue` value
+  * `datetime`: The default date and time as a `DateTimeValue` value. Overrides `date`.
   * `format`:   The format of the date. See the `set_format` method.
   * `range`:    The accepted range of dates. The value is inclusive.
   * `focus`:    The control receive focus after being created
@@ -61,8 +213,12 @@ Requires the `datetime-picker` feature.
   * `OnDatePickerClosed`: When the datepicker dropdown is closed
   * `OnDatePickerDropdown`: When the datepicker dropdown is opened
   * `OnDatePickerChanged`: When a new value in a datepicker is choosen
+  * `OnDatePickerTimeChanged`: When the time part of the value is changed by the user. Sent after `OnDatePickerChanged`
   * `MousePress(_)`: Generic mouse press events on the checkbox
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
 
 ```rust
@@ -81,6 +237,15 @@ fn build_dtp(date: &mut nwg::DatePicker, window: &nwg::Window) {
         .parent(window)
         .build(date);
 }
+
+fn build_time_picker(time: &mut nwg::DatePicker, window: &nwg::Window) {
+    nwg::DatePicker::builder()
+        .flags(nwg::DatePickerFlags::VISIBLE | nwg::DatePickerFlags::TIME)
+        .datetime(Some(std::time::SystemTime::now().into()))
+        .format(Some("HH':'mm':'ss"))
+        .parent(window)
+        .build(time);
+}
 ```
 */
 #[derive(Default, PartialEq, Eq)]
@@ -100,8 +265,11 @@ impl DatePicker {
             font: None,
             parent: None,
             date: None,
+            datetime: None,
             format: None,
-            range: None
+            range: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -151,6 +319,22 @@ impl DatePicker {
         wh::send_message(handle, DTM_SETFORMATW, 0, format_ptr);
     }
 
+    /**
+        Sets the date format of the control using the short date format (ex: `yyyy-MM-dd`) or the
+        long date format (ex: `dddd, MMMM d, yyyy`) of a locale.
+        
+        Requires the `winnls` feature.
+    */
+    #[cfg(feature="winnls")]
+    pub fn set_locale_format(&self, locale: &crate::Locale, long: bool) {
+        let format = match long {
+            true => locale.long_date(),
+            false => locale.short_date()
+        };
+
+        self.set_format(Some(&format));
+    }
+
     /**
         Return the check state of the checkbox of the control.  
         If the date time picker is not optional, return false.
@@ -183,57 +367,65 @@ impl DatePicker {
         Note: use `get_text` to get the text value of the control.
     */
     pub fn value(&self) -> Option<DatePickerValue> {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { dtp_value(handle) }
+    }
+
+    /**
+        Set the time set in the control in a `PickerDate` structure.  
+        If `None` is passed, this clears the checkbox.
+    */
+    pub fn set_value(&self, date: Option<DatePickerValue>) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { dtp_set_value(handle, date); }
+    }
+
+    /**
+        Return the date and time set in the control in a `DateTimeValue` structure.  
+        Return None if `optional` was set and the checkbox is not checked.
+    */
+    pub fn datetime_value(&self) -> Option<DateTimeValue> {
         use winapi::um::commctrl::{GDT_VALID, DTM_GETSYSTEMTIME};
-        use winapi::um::minwinbase::SYSTEMTIME;
+        use winapi::shared::minwindef::LPARAM;
         use std::mem;
 
         let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
 
         let mut syst: SYSTEMTIME = unsafe{ mem::zeroed() };
 
-        let r = unsafe{ wh::send_message(handle, DTM_GETSYSTEMTIME, 0, mem::transmute(&mut syst)) };
+        let r = wh::send_message(handle, DTM_GETSYSTEMTIME, 0, &mut syst as *mut SYSTEMTIME as LPARAM);
         match r {
-            GDT_VALID => Some(DatePickerValue {
-                year: syst.wYear,
-                month: syst.wMonth,
-                day: syst.wDay
-            }),
+            GDT_VALID => Some(DateTimeValue::from_system(&syst)),
             _ => None
         }
     }
 
     /**
-        Set the time set in the control in a `PickerDate` structure.  
+        Set the date and time set in the control in a `DateTimeValue` structure.  
         If `None` is passed, this clears the checkbox.
     */
-    pub fn set_value(&self, date: Option<DatePickerValue>) {
+    pub fn set_datetime_value(&self, value: Option<DateTimeValue>) {
         use winapi::um::commctrl::{DTM_SETSYSTEMTIME, GDT_VALID, GDT_NONE};
         use winapi::shared::minwindef::{WPARAM, LPARAM};
-        use winapi::um::minwinbase::SYSTEMTIME;
 
         let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
 
-        match date {
-            Some(date) => {
-                let syst: SYSTEMTIME = SYSTEMTIME{ 
-                    wYear: date.year, 
-                    wMonth: date.month, 
-                    wDay: date.day, 
-                    wDayOfWeek:0, wHour:0, wMinute:0, wSecond:0, wMilliseconds: 0 
-                };
-
+        match value {
+            Some(value) => {
+                let syst = value.to_system();
                 wh::send_message(handle, DTM_SETSYSTEMTIME, GDT_VALID as WPARAM, &syst as *const SYSTEMTIME as LPARAM);
             },
             None => { 
                 wh::send_message(handle, DTM_SETSYSTEMTIME, GDT_NONE as WPARAM, 0); 
             }
         };
+
+        unsafe { dtp_store_time_key(handle, value.map(|v| dtp_time_key(&v.to_system()))); }
     }
 
     /// Gets the current minimum and maximum allowable system times for a date and time picker control.
     pub fn range(&self) -> [DatePickerValue; 2] {
         use winapi::um::commctrl::DTM_GETRANGE;
-        use winapi::um::minwinbase::SYSTEMTIME;
         use winapi::shared::minwindef::{LPARAM};
         use std::mem;
 
@@ -254,7 +446,6 @@ impl DatePicker {
     pub fn set_range(&self, r: &[DatePickerValue; 2]) {
         use winapi::um::commctrl::DTM_SETRANGE;
         use winapi::um::commctrl::{GDTR_MIN, GDTR_MAX};
-        use winapi::um::minwinbase::SYSTEMTIME;
         use winapi::shared::minwindef::{LPARAM};
 
         let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
@@ -368,6 +559,10 @@ impl DatePicker {
 
 impl Drop for DatePicker {
     fn drop(&mut self) {
+        if let Some(handle) = self.handle.hwnd() {
+            unsafe { dtp_remove_time_key(handle); }
+        }
+
         self.handle.destroy();
     }
 }
@@ -381,8 +576,11 @@ pub struct DatePickerBuilder<'a> {
     focus: bool,
     parent: Option<ControlHandle>,
     date: Option<DatePickerValue>,
+    datetime: Option<DateTimeValue>,
     format: Option<&'a str>,
-    range: Option<[DatePickerValue; 2]>
+    range: Option<[DatePickerValue; 2]>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a> DatePickerBuilder<'a> {
@@ -422,6 +620,11 @@ impl<'a> DatePickerBuilder<'a> {
         self
     }
 
+    pub fn datetime(mut self, datetime: Option<DateTimeValue>) -> DatePickerBuilder<'a> {
+        self.datetime = datetime;
+        self
+    }
+
     pub fn format(mut self, format: Option<&'a str>) -> DatePickerBuilder<'a> {
         self.format = format;
         self
@@ -437,8 +640,13 @@ impl<'a> DatePickerBuilder<'a> {
         self
     }
 
+    pub fn name(mut self, name: &str) -> DatePickerBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut DatePicker) -> Result<(), NwgError> {
-        let flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
 
         let parent = match self.parent {
             Some(p) => Ok(p),
@@ -448,6 +656,7 @@ impl<'a> DatePickerBuilder<'a> {
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -463,8 +672,13 @@ impl<'a> DatePickerBuilder<'a> {
             out.set_font(Font::global_default().as_ref());
         }
 
-        if self.date.is_some() {
+        if self.datetime.is_some() {
+            out.set_datetime_value(self.datetime);
+        } else if self.date.is_some() {
             out.set_value(self.date)
+        } else {
+            let value = out.datetime_value();
+            unsafe { dtp_store_time_key(out.handle.hwnd().unwrap(), value.map(|v| dtp_time_key(&v.to_system()))); }
         }
 
         if self.range.is_some() {
@@ -488,6 +702,82 @@ impl<'a> DatePickerBuilder<'a> {
 use winapi::um::commctrl::DATETIMEPICKERINFO;
 use winapi::shared::windef::HWND;
 
+/// Return the date of a date picker control. Shared with the `DateRangePicker` control.
+pub(crate) unsafe fn dtp_value(handle: HWND) -> Option<DatePickerValue> {
+    use winapi::um::commctrl::{GDT_VALID, DTM_GETSYSTEMTIME};
+    use std::mem;
+
+    let mut syst: SYSTEMTIME = mem::zeroed();
+
+    let r = wh::send_message(handle, DTM_GETSYSTEMTIME, 0, mem::transmute(&mut syst));
+    match r {
+        GDT_VALID => Some(DatePickerValue {
+            year: syst.wYear,
+            month: syst.wMonth,
+            day: syst.wDay
+        }),
+        _ => None
+    }
+}
+
+/// Set the date of a date picker control. Shared with the `DateRangePicker` control.
+pub(crate) unsafe fn dtp_set_value(handle: HWND, date: Option<DatePickerValue>) {
+    use winapi::um::commctrl::{DTM_SETSYSTEMTIME, GDT_VALID, GDT_NONE};
+    use winapi::shared::minwindef::{WPARAM, LPARAM};
+
+    match date {
+        Some(date) => {
+            let syst: SYSTEMTIME = SYSTEMTIME{ 
+                wYear: date.year, 
+                wMonth: date.month, 
+                wDay: date.day, 
+                wDayOfWeek:0, wHour:0, wMinute:0, wSecond:0, wMilliseconds: 0 
+            };
+
+            wh::send_message(handle, DTM_SETSYSTEMTIME, GDT_VALID as WPARAM, &syst as *const SYSTEMTIME as LPARAM);
+        },
+        None => { 
+            wh::send_message(handle, DTM_SETSYSTEMTIME, GDT_NONE as WPARAM, 0); 
+        }
+    };
+
+    dtp_store_time_key(handle, date.map(|_| 0));
+}
+
+const TIME_KEY_PROP: &'static str = "NWG_DTP_TIME";
+
+/// Pack the time part of a SYSTEMTIME into a single value. Used to detect time changes in `OnDatePickerTimeChanged`.
+pub(crate) fn dtp_time_key(st: &SYSTEMTIME) -> usize {
+    (st.wHour as usize) * 3_600_000 + (st.wMinute as usize) * 60_000 + (st.wSecond as usize) * 1000 + (st.wMilliseconds as usize)
+}
+
+/// Store the last known time of the control in a window property. `None` clears the stored value.
+pub(crate) unsafe fn dtp_store_time_key(handle: HWND, key: Option<usize>) {
+    use winapi::um::winuser::SetPropW;
+
+    let name = to_utf16(TIME_KEY_PROP);
+    let value = key.map(|k| k + 1).unwrap_or(0);
+    SetPropW(handle, name.as_ptr(), value as _);
+}
+
+/// Return the last known time of the control
+pub(crate) unsafe fn dtp_load_time_key(handle: HWND) -> Option<usize> {
+    use winapi::um::winuser::GetPropW;
+
+    let name = to_utf16(TIME_KEY_PROP);
+    match GetPropW(handle, name.as_ptr()) as usize {
+        0 => None,
+        v => Some(v - 1)
+    }
+}
+
+unsafe fn dtp_remove_time_key(handle: HWND) {
+    use winapi::um::winuser::RemovePropW;
+
+    let name = to_utf16(TIME_KEY_PROP);
+    RemovePropW(handle, name.as_ptr());
+}
+
 unsafe fn get_dtp_info(handle: HWND) -> DATETIMEPICKERINFO {
     use winapi::um::commctrl::DTM_GETDATETIMEPICKERINFO;
     use winapi::shared::minwindef::DWORD;
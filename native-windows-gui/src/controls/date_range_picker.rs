@@ -0,0 +1,582 @@
+use winapi::shared::windef::HWND;
+use winapi::um::winuser::{WS_VISIBLE, WS_DISABLED, WS_TABSTOP, WS_EX_CONTROLPARENT};
+use std::fmt;
+use std::rc::Rc;
+
+use crate::win32::window_helper as wh;
+use crate::win32::window::nwg_class;
+use crate::win32::base_helper::check_hwnd;
+use crate::{NwgError, Font, RawEventHandler, bind_raw_event_handler_inner, unbind_raw_event_handler};
+use super::{ControlBase, ControlHandle, ComboBox, ComboBoxFlags, DatePicker, DatePickerFlags, DatePickerValue};
+use super::control_builder::BuilderState;
+use super::date_picker::{dtp_value, dtp_set_value, days_from_civil, civil_from_days};
+
+const NOT_BOUND: &'static str = "DateRangePicker is not yet bound to a winapi object";
+const BAD_HANDLE: &'static str = "INTERNAL ERROR: DateRangePicker handle is not HWND!";
+
+const CHILDREN_SPACING: i32 = 5;
+
+
+bitflags! {
+    /**
+        The DateRangePicker flags
+
+        * NONE:     No flags. Equivalent to a invisible blank DateRangePicker.
+        * VISIBLE:  The DateRangePicker is immediatly visible after creation
+        * DISABLED: The DateRangePicker cannot be interacted with by the user. It also has a grayed out look.
+        * TAB_STOP: The controls can be selected using tab navigation.
+    */
+    pub struct DateRangePickerFlags: u32 {
+        const NONE = 0;
+        const VISIBLE = WS_VISIBLE;
+        const DISABLED = WS_DISABLED;
+        const TAB_STOP = WS_TABSTOP;
+    }
+}
+
+/// The predefined ranges that can be selected in a DateRangePicker. Ranges are computed from the current local date.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DateRangePreset {
+    /// The range was picked manually by the user
+    Custom,
+    Today,
+    Yesterday,
+    Last7Days,
+    Last30Days,
+    ThisMonth,
+    LastMonth,
+    ThisYear,
+}
+
+impl DateRangePreset {
+
+    /// Return every preset, in the order they are displayed by default
+    pub fn all() -> Vec<DateRangePreset> {
+        use DateRangePreset::*;
+        vec![Custom, Today, Yesterday, Last7Days, Last30Days, ThisMonth, LastMonth, ThisYear]
+    }
+
+    /// Return the inclusive range of the preset relative to `today`. Return `None` for `Custom`.
+    pub fn range(&self, today: DatePickerValue) -> Option<[DatePickerValue; 2]> {
+        use DateRangePreset::*;
+
+        let t = days_from_civil(today.year as i64, today.month as i64, today.day as i64);
+        let first_of_month = days_from_civil(today.year as i64, today.month as i64, 1);
+
+        let (start, end) = match self {
+            Custom => { return None; },
+            Today => (t, t),
+            Yesterday => (t - 1, t - 1),
+            Last7Days => (t - 6, t),
+            Last30Days => (t - 29, t),
+            ThisMonth => (first_of_month, t),
+            LastMonth => {
+                let last_day = first_of_month - 1;
+                let (year, month, _) = civil_from_days(last_day);
+                (days_from_civil(year, month, 1), last_day)
+            },
+            ThisYear => (days_from_civil(today.year as i64, 1, 1), t),
+        };
+
+        Some([date_from_days(start), date_from_days(end)])
+    }
+
+}
+
+impl Default for DateRangePreset {
+    fn default() -> DateRangePreset {
+        DateRangePreset::Custom
+    }
+}
+
+impl fmt::Display for DateRangePreset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use DateRangePreset::*;
+
+        let text = match self {
+            Custom => "Custom",
+            Today => "Today",
+            Yesterday => "Yesterday",
+            Last7Days => "Last 7 days",
+            Last30Days => "Last 30 days",
+            ThisMonth => "This month",
+            LastMonth => "Last month",
+            ThisYear => "This year",
+        };
+
+        write!(f, "{}", text)
+    }
+}
+
+
+/**
+A DateRangePicker is a composite control used to select a range of dates. It is made of a combobox listing
+quick presets (ex: "Last 7 days") followed by two date pickers: the start and the end of the range.
+
+The control makes sure that the start date is never after the end date. If the user moves the start date past the end date,
+the end date is moved to the start date (and vice versa). Editing one of the dates selects the `Custom` preset.
+
+Requires the `date-range-picker` feature.
+
+**Builder parameters:**
+  * `parent`:       **Required.** The date range picker parent container.
+  * `size`:         The date range picker size.
+  * `position`:     The date range picker position.
+  * `enabled`:      If the date range picker can be used by the user. It also has a grayed out look if disabled.
+  * `flags`:        A combination of the DateRangePickerFlags values.
+  * `font`:         The font used for the date range picker text
+  * `range`:        The default range. Overrides `preset`.
+  * `preset`:       The default preset. Defaults to `Today`.
+  * `presets`:      The presets displayed in the combobox. `Custom` is always added if it is missing.
+  * `preset_width`: The width of the presets combobox
+
+**Control events:**
+  * `OnDateRangeChanged`: When the range is changed by the user, either by picking a preset or by editing a date
+  * `OnDatePickerChanged`: Sent by the inner date pickers
+  * `OnComboxBoxSelection`: Sent by the inner presets combobox
+
+```rust
+use native_windows_gui as nwg;
+fn build_range(range: &mut nwg::DateRangePicker, window: &nwg::Window) {
+    nwg::DateRangePicker::builder()
+        .size((400, 25))
+        .preset(Some(nwg::DateRangePreset::Last7Days))
+        .parent(window)
+        .build(range);
+}
+```
+
+*/
+#[derive(Default)]
+pub struct DateRangePicker {
+    pub handle: ControlHandle,
+    presets: ComboBox<DateRangePreset>,
+    start: DatePicker,
+    end: DatePicker,
+    preset_width: i32,
+    handler: Option<RawEventHandler>
+}
+
+impl DateRangePicker {
+
+    pub fn builder<'a>() -> DateRangePickerBuilder<'a> {
+        DateRangePickerBuilder {
+            size: (400, 25),
+            position: (0, 0),
+            enabled: true,
+            flags: None,
+            font: None,
+            range: None,
+            preset: None,
+            presets: DateRangePreset::all(),
+            preset_width: 120,
+            parent: None,
+            name: None,
+            state: Default::default(),
+        }
+    }
+
+    /// Return the selected range as `[start, end]`. Both values are inclusive.
+    /// Return `None` if the user cleared one of the dates.
+    pub fn range(&self) -> Option<[DatePickerValue; 2]> {
+        match (self.start.value(), self.end.value()) {
+            (Some(start), Some(end)) => Some([start, end]),
+            _ => None
+        }
+    }
+
+    /// Sets the selected range. If `start` is after `end`, the two values are swapped.
+    /// This also selects the `Custom` preset.
+    pub fn set_range(&self, range: &[DatePickerValue; 2]) {
+        let [mut start, mut end] = *range;
+        if date_key(&start) > date_key(&end) {
+            std::mem::swap(&mut start, &mut end);
+        }
+
+        self.start.set_value(Some(start));
+        self.end.set_value(Some(end));
+        self.select_preset(DateRangePreset::Custom);
+    }
+
+    /// Return the selected preset
+    pub fn preset(&self) -> DateRangePreset {
+        let index = self.presets.selection();
+        let presets = self.presets.collection();
+        index.and_then(|i| presets.get(i).copied()).unwrap_or(DateRangePreset::Custom)
+    }
+
+    /// Selects a preset and update the range using the current local date.
+    /// Does nothing if the preset is not in the presets list of the control.
+    pub fn set_preset(&self, preset: DateRangePreset) {
+        if let Some(range) = preset.range(today()) {
+            self.start.set_value(Some(range[0]));
+            self.end.set_value(Some(range[1]));
+        }
+
+        self.select_preset(preset);
+    }
+
+    /// Return the font of the control
+    pub fn font(&self) -> Option<Font> {
+        self.start.font()
+    }
+
+    /// Sets the font of the control
+    pub fn set_font(&self, font: Option<&Font>) {
+        self.presets.set_font(font);
+        self.start.set_font(font);
+        self.end.set_font(font);
+    }
+
+    /// Return true if the control currently has the keyboard focus
+    pub fn focus(&self) -> bool {
+        self.presets.focus() || self.start.focus() || self.end.focus()
+    }
+
+    /// Sets the keyboard focus on the presets combobox
+    pub fn set_focus(&self) {
+        self.presets.set_focus();
+    }
+
+    /// Return true if the control user can interact with the control, return false otherwise
+    pub fn enabled(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_enabled(handle) }
+    }
+
+    /// Enable or disable the control
+    pub fn set_enabled(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_enabled(handle, v) }
+        self.presets.set_enabled(v);
+        self.start.set_enabled(v);
+        self.end.set_enabled(v);
+    }
+
+    /// Return true if the control is visible to the user. Will return true even if the
+    /// control is outside of the parent client view (ex: at the position (10000, 10000))
+    pub fn visible(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_visibility(handle) }
+    }
+
+    /// Show or hide the control to the user
+    pub fn set_visible(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_visibility(handle, v) }
+    }
+
+    /// Return the size of the date range picker in the parent window
+    pub fn size(&self) -> (u32, u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_size(handle) }
+    }
+
+    /// Set the size of the date range picker in the parent window. The inner controls are resized.
+    pub fn set_size(&self, x: u32, y: u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_size(handle, x, y, false) }
+        self.layout(x as i32, y as i32);
+    }
+
+    /// Return the position of the date range picker in the parent window
+    pub fn position(&self) -> (i32, i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_position(handle) }
+    }
+
+    /// Set the position of the date range picker in the parent window
+    pub fn set_position(&self, x: i32, y: i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_position(handle, x, y) }
+    }
+
+    /// Winapi class name used during control creation
+    pub fn class_name(&self) -> &'static str {
+        nwg_class("NativeWindowsGuiWindow")
+    }
+
+    /// Winapi base flags used during window creation
+    pub fn flags(&self) -> u32 {
+        WS_VISIBLE | WS_TABSTOP
+    }
+
+    /// Winapi flags required by the control
+    pub fn forced_flags(&self) -> u32 {
+        use winapi::um::winuser::{WS_CHILD, WS_CLIPCHILDREN};
+        WS_CHILD | WS_CLIPCHILDREN
+    }
+
+    fn select_preset(&self, preset: DateRangePreset) {
+        let index = self.presets.collection().iter().position(|&p| p == preset);
+        self.presets.set_selection(index);
+    }
+
+    fn layout(&self, w: i32, h: i32) {
+        let (presets_w, picker_w) = children_width(w, self.preset_width);
+        let start_x = presets_w + CHILDREN_SPACING;
+
+        self.presets.set_size(presets_w as u32, h as u32);
+        self.start.set_position(start_x, 0);
+        self.start.set_size(picker_w as u32, h as u32);
+        self.end.set_position(start_x + picker_w + CHILDREN_SPACING, 0);
+        self.end.set_size(picker_w as u32, h as u32);
+    }
+
+}
+
+impl Drop for DateRangePicker {
+    fn drop(&mut self) {
+        if let Some(h) = self.handler.as_ref() {
+            drop(unbind_raw_event_handler(h));
+        }
+
+        self.handle.destroy();
+    }
+}
+
+pub struct DateRangePickerBuilder<'a> {
+    size: (i32, i32),
+    position: (i32, i32),
+    enabled: bool,
+    flags: Option<DateRangePickerFlags>,
+    font: Option<&'a Font>,
+    range: Option<[DatePickerValue; 2]>,
+    preset: Option<DateRangePreset>,
+    presets: Vec<DateRangePreset>,
+    preset_width: i32,
+    parent: Option<ControlHandle>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
+}
+
+impl<'a> DateRangePickerBuilder<'a> {
+
+    pub fn flags(mut self, flags: DateRangePickerFlags) -> DateRangePickerBuilder<'a> {
+        self.flags = Some(flags);
+        self
+    }
+
+    pub fn size(mut self, size: (i32, i32)) -> DateRangePickerBuilder<'a> {
+        self.size = size;
+        self
+    }
+
+    pub fn position(mut self, pos: (i32, i32)) -> DateRangePickerBuilder<'a> {
+        self.position = pos;
+        self
+    }
+
+    pub fn enabled(mut self, e: bool) -> DateRangePickerBuilder<'a> {
+        self.enabled = e;
+        self
+    }
+
+    pub fn font(mut self, font: Option<&'a Font>) -> DateRangePickerBuilder<'a> {
+        self.font = font;
+        self
+    }
+
+    pub fn range(mut self, range: Option<[DatePickerValue; 2]>) -> DateRangePickerBuilder<'a> {
+        self.range = range;
+        self
+    }
+
+    pub fn preset(mut self, preset: Option<DateRangePreset>) -> DateRangePickerBuilder<'a> {
+        self.preset = preset;
+        self
+    }
+
+    pub fn presets(mut self, presets: Vec<DateRangePreset>) -> DateRangePickerBuilder<'a> {
+        self.presets = presets;
+        self
+    }
+
+    pub fn preset_width(mut self, width: i32) -> DateRangePickerBuilder<'a> {
+        self.preset_width = width;
+        self
+    }
+
+    pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> DateRangePickerBuilder<'a> {
+        self.parent = Some(p.into());
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> DateRangePickerBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn build(self, out: &mut DateRangePicker) -> Result<(), NwgError> {
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
+        let (combo_flags, picker_flags) = if flags & WS_TABSTOP == WS_TABSTOP {
+            (ComboBoxFlags::VISIBLE | ComboBoxFlags::TAB_STOP, DatePickerFlags::VISIBLE | DatePickerFlags::TAB_STOP)
+        } else {
+            (ComboBoxFlags::VISIBLE, DatePickerFlags::VISIBLE)
+        };
+
+        let parent = match self.parent {
+            Some(p) => Ok(p),
+            None => Err(NwgError::no_parent("DateRangePicker"))
+        }?;
+
+        if out.handler.is_some() {
+            unbind_raw_event_handler(out.handler.as_ref().unwrap())?;
+        }
+
+        *out = DateRangePicker::default();
+        out.preset_width = self.preset_width;
+
+        let mut presets = self.presets;
+        if !presets.contains(&DateRangePreset::Custom) {
+            presets.insert(0, DateRangePreset::Custom);
+        }
+
+        let (w, h) = self.size;
+        let (presets_w, picker_w) = children_width(w, self.preset_width);
+        let start_x = presets_w + CHILDREN_SPACING;
+
+        out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
+            .class_name(out.class_name())
+            .forced_flags(out.forced_flags())
+            .ex_flags(WS_EX_CONTROLPARENT)
+            .flags(flags)
+            .size(self.size)
+            .position(self.position)
+            .parent(Some(parent))
+            .build()?;
+
+        ComboBox::builder()
+            .collection(presets.clone())
+            .size((presets_w, h))
+            .parent(&out.handle)
+            .flags(combo_flags)
+            .build(&mut out.presets)?;
+
+        DatePicker::builder()
+            .size((picker_w, h))
+            .position((start_x, 0))
+            .parent(&out.handle)
+            .flags(picker_flags)
+            .build(&mut out.start)?;
+
+        DatePicker::builder()
+            .size((picker_w, h))
+            .position((start_x + picker_w + CHILDREN_SPACING, 0))
+            .parent(&out.handle)
+            .flags(picker_flags)
+            .build(&mut out.end)?;
+
+        if self.font.is_some() {
+            out.set_font(self.font);
+        } else {
+            out.set_font(Font::global_default().as_ref());
+        }
+
+        match (self.range, self.preset) {
+            (Some(range), _) => out.set_range(&range),
+            (None, Some(preset)) => out.set_preset(preset),
+            (None, None) => out.set_preset(DateRangePreset::Today),
+        }
+
+        let handler_presets = Rc::new(presets);
+        let base_handle = out.handle.hwnd().unwrap();
+        let combo_handle = out.presets.handle.hwnd().unwrap();
+        let start_handle = out.start.handle.hwnd().unwrap();
+        let end_handle = out.end.handle.hwnd().unwrap();
+
+        let handler = bind_raw_event_handler_inner(&out.handle, 0x4546, move |_hwnd, msg, w, l| {
+            use winapi::um::winuser::{WM_COMMAND, WM_NOTIFY, CB_GETCURSEL, CB_SETCURSEL, CBN_SELCHANGE};
+            use winapi::um::commctrl::DTN_DATETIMECHANGE;
+            use winapi::shared::minwindef::{HIWORD, WPARAM};
+            use winapi::um::winuser::NMHDR;
+
+            match msg {
+                WM_COMMAND => {
+                    let message = HIWORD(w as u32) as u16;
+                    if message != CBN_SELCHANGE || l as HWND != combo_handle {
+                        return None;
+                    }
+
+                    let index = wh::send_message(combo_handle, CB_GETCURSEL, 0, 0);
+                    if index < 0 {
+                        return None;
+                    }
+
+                    if let Some(range) = handler_presets.get(index as usize).and_then(|p| p.range(today())) {
+                        unsafe {
+                            dtp_set_value(start_handle, Some(range[0]));
+                            dtp_set_value(end_handle, Some(range[1]));
+                        }
+
+                        wh::send_message(base_handle, wh::NWG_DATE_RANGE_CHANGED, 0, 0);
+                    }
+                },
+                WM_NOTIFY => {
+                    let nmhdr = unsafe { &*(l as *const NMHDR) };
+                    let from = nmhdr.hwndFrom;
+                    if nmhdr.code != DTN_DATETIMECHANGE || (from != start_handle && from != end_handle) {
+                        return None;
+                    }
+
+                    unsafe {
+                        if let (Some(start), Some(end)) = (dtp_value(start_handle), dtp_value(end_handle)) {
+                            if date_key(&start) > date_key(&end) {
+                                match from == start_handle {
+                                    true => dtp_set_value(end_handle, Some(start)),
+                                    false => dtp_set_value(start_handle, Some(end)),
+                                }
+                            }
+                        }
+                    }
+
+                    let custom = handler_presets.iter().position(|&p| p == DateRangePreset::Custom).unwrap_or(0);
+                    wh::send_message(combo_handle, CB_SETCURSEL, custom as WPARAM, 0);
+                    wh::send_message(base_handle, wh::NWG_DATE_RANGE_CHANGED, 0, 0);
+                },
+                _ => {}
+            }
+
+            None
+        });
+
+        out.handler = Some(handler.unwrap());
+
+        if !self.enabled {
+            out.set_enabled(self.enabled);
+        }
+
+        Ok(())
+    }
+
+}
+
+/// Split the width of the control between the presets combobox and the two date pickers
+fn children_width(w: i32, preset_width: i32) -> (i32, i32) {
+    let presets_w = i32::min(preset_width, w);
+    let picker_w = (w - presets_w - CHILDREN_SPACING * 2) / 2;
+    (presets_w, i32::max(picker_w, 0))
+}
+
+fn date_key(date: &DatePickerValue) -> (u16, u16, u16) {
+    (date.year, date.month, date.day)
+}
+
+fn date_from_days(days: i64) -> DatePickerValue {
+    let (year, month, day) = civil_from_days(days);
+    DatePickerValue { year: year as u16, month: month as u16, day: day as u16 }
+}
+
+/// The current local date
+fn today() -> DatePickerValue {
+    use winapi::um::sysinfoapi::GetLocalTime;
+    use winapi::um::minwinbase::SYSTEMTIME;
+    use std::mem;
+
+    unsafe {
+        let mut st: SYSTEMTIME = mem::zeroed();
+        GetLocalTime(&mut st);
+        DatePickerValue { year: st.wYear, month: st.wMonth, day: st.wDay }
+    }
+}
@@ -0,0 +1,773 @@
+use winapi::shared::windef::{HWND, POINT, RECT};
+use winapi::shared::minwindef::{WPARAM, LPARAM};
+use super::ControlHandle;
+use crate::win32::{window_helper as wh, high_dpi};
+use crate::{NwgError, RawEventHandler, unbind_raw_event_handler, snap_to_grid};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::{ptr, mem};
+
+const NOT_BOUND: &'static str = "DesignSurface is not yet bound to a winapi object";
+
+/// The id of the raw event handler of the parent
+const SURFACE_ID: usize = 0x4453;
+
+/// The id of the raw event handlers of the children
+const SURFACE_CHILD_ID: usize = 0x4454;
+
+/// The size of the resize grips, in pixels
+const GRIP_SIZE: i32 = 7;
+
+/// The smallest size of a control resized by the user, in logical pixels
+const MIN_SIZE: i32 = 4;
+
+/// Sent in the wparam of `NWG_DESIGN_SURFACE`
+pub(crate) const DESIGN_SELECTION_CHANGED: WPARAM = 0;
+pub(crate) const DESIGN_GEOMETRY_CHANGED: WPARAM = 1;
+
+
+/// The part of the selection under the mouse. `(0, 0)` moves the selection, any other value resizes the control from this side.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Grip(i32, i32);
+
+const MOVE: Grip = Grip(0, 0);
+
+#[derive(Copy, Clone, Debug)]
+struct Drag {
+    grip: Grip,
+
+    /// The cursor position when the button was pressed, in logical pixels
+    start: (i32, i32),
+
+    /// True once the cursor moved further than the drag threshold
+    started: bool,
+}
+
+/// The state shared with the raw event handlers
+struct SurfaceState {
+    parent: HWND,
+    enabled: Cell<bool>,
+
+    /// The direct children of the parent, from the top of the z-order to the bottom
+    children: RefCell<Vec<HWND>>,
+
+    /// The selected children. The first one is the reference of the alignment commands.
+    selection: RefCell<Vec<HWND>>,
+    grid: Cell<(u32, u32)>,
+    show_grid: Cell<bool>,
+    drag: Cell<Option<Drag>>,
+
+    /// The geometry of the selection when the drag started
+    origin: RefCell<Vec<(HWND, [i32; 4])>>,
+}
+
+impl SurfaceState {
+
+    fn selection_changed(&self) {
+        self.repaint();
+        wh::send_message(self.parent, wh::NWG_DESIGN_SURFACE, DESIGN_SELECTION_CHANGED, 0);
+    }
+
+    /// Raise `OnDesignGeometryChanged` with the new geometry of the controls
+    fn geometry_changed(&self, handles: &[HWND]) {
+        let changes: Vec<(ControlHandle, [i32; 4])> = handles.iter()
+            .map(|&h| (ControlHandle::Hwnd(h), control_rect(h)))
+            .collect();
+
+        wh::send_message(self.parent, wh::NWG_DESIGN_SURFACE, DESIGN_GEOMETRY_CHANGED, &changes as *const _ as LPARAM);
+    }
+
+    fn repaint(&self) {
+        use winapi::um::winuser::InvalidateRect;
+        unsafe { InvalidateRect(self.parent, ptr::null(), 1); }
+    }
+
+    /// Return the visible child under a point, in logical pixels
+    fn child_at(&self, (x, y): (i32, i32)) -> Option<HWND> {
+        self.children.borrow().iter()
+            .cloned()
+            .filter(|&h| unsafe { wh::get_window_visibility(h) })
+            .find(|&h| {
+                let [cx, cy, cw, ch] = control_rect(h);
+                x >= cx && x < cx + cw && y >= cy && y < cy + ch
+            })
+    }
+
+    /// Return the resize grip under a point, in logical pixels. Only a single selected control can be resized.
+    fn grip_at(&self, point: (i32, i32)) -> Option<Grip> {
+        let selection = self.selection.borrow();
+        if selection.len() != 1 {
+            return None;
+        }
+
+        let (x, y) = logical_to_physical(self.parent, point);
+        grips(physical_rect(selection[0])).iter()
+            .find(|(_, r)| x >= r.left && x < r.right && y >= r.top && y < r.bottom)
+            .map(|&(grip, _)| grip)
+    }
+
+    /// Save the geometry of the selection before a drag
+    fn save_origin(&self) {
+        *self.origin.borrow_mut() = self.selection.borrow().iter().map(|&h| (h, control_rect(h))).collect();
+    }
+
+    /// Move or resize the selection from the saved geometry
+    fn apply(&self, grip: Grip, (dx, dy): (i32, i32)) {
+        let origin = self.origin.borrow();
+        let grid = self.grid.get();
+
+        if grip == MOVE {
+            // The reference control is snapped to the grid and the others keep their distance to it
+            let [rx, ry, _, _] = match origin.first() {
+                Some(&(_, r)) => r,
+                None => { return; }
+            };
+
+            let (sx, sy) = snap_to_grid((rx + dx, ry + dy), grid);
+            for &(handle, [x, y, w, h]) in origin.iter() {
+                set_control_rect(handle, [x + sx - rx, y + sy - ry, w, h]);
+            }
+        } else if let Some(&(handle, [x, y, w, h])) = origin.first() {
+            let Grip(gx, gy) = grip;
+            let (mut left, mut top, mut right, mut bottom) = (x, y, x + w, y + h);
+
+            let (sx, sy) = snap_to_grid((match gx { -1 => x + dx, _ => right + dx }, match gy { -1 => y + dy, _ => bottom + dy }), grid);
+            match gx {
+                -1 => { left = sx.min(right - MIN_SIZE); },
+                1 => { right = sx.max(left + MIN_SIZE); },
+                _ => {}
+            }
+            match gy {
+                -1 => { top = sy.min(bottom - MIN_SIZE); },
+                1 => { bottom = sy.max(top + MIN_SIZE); },
+                _ => {}
+            }
+
+            set_control_rect(handle, [left, top, right - left, bottom - top]);
+        }
+
+        self.repaint();
+    }
+
+    /// Put the selection back where it was before the drag
+    fn restore_origin(&self) {
+        for &(handle, rect) in self.origin.borrow().iter() {
+            set_control_rect(handle, rect);
+        }
+
+        self.repaint();
+    }
+
+}
+
+/**
+A design surface turns a parent window into a form designer canvas, the core of a drag and drop GUI editor.
+
+While the surface is enabled, the direct children of the parent do not receive mouse input anymore. Instead the user can:
+  * Click a control to select it. Hold Ctrl or Shift to add or remove a control from the selection.
+  * Drag the selected controls to move them. Press Escape during the drag to cancel it.
+  * Drag one of the eight grips around a single selected control to resize it.
+  * Use the arrow keys to move the selection by one pixel, Ctrl+arrows to move it by a grid cell, and Shift+arrows to resize it.
+
+The moved and resized controls are snapped to the grid (if a grid is set). The grid can also be drawn on the parent.
+`OnDesignGeometryChanged` is raised with the new geometry of the controls at the end of each move or resize.
+Use the alignment functions (ex: `align_controls`) on `selection` to implement the alignment commands of the designer.
+
+The surface paints the parent window itself while it is enabled: `OnPaint` is not raised by the parent in design mode.
+Call `refresh` after controls are added or removed from the parent.
+
+Requires the `design-surface` feature.
+
+**Builder parameters:**
+  * `parent`:    **Required.** The window containing the controls to design.
+  * `enabled`:   If the design mode is enabled right away. Defaults to `true`.
+  * `grid`:      The size of a grid cell, in logical pixels. `(0, 0)` disables the snapping. Defaults to `(8, 8)`.
+  * `show_grid`: If the grid is drawn on the parent. Defaults to `true`.
+
+**Control events:**
+  * `OnDesignSelectionChanged`: When the selected controls changed. Raised by the parent.
+  * `OnDesignGeometryChanged`:  When the user moved or resized controls. Read the new geometry with `EventData::on_design_geometry_changed`. Raised by the parent.
+
+```rust
+use native_windows_gui as nwg;
+
+fn build_surface(surface: &mut nwg::DesignSurface, form: &nwg::Frame) {
+    nwg::DesignSurface::builder()
+        .grid((10, 10))
+        .parent(form)
+        .build(surface)
+        .unwrap();
+}
+
+fn geometry_changed(data: &nwg::EventData) {
+    for (control, [x, y, width, height]) in data.on_design_geometry_changed() {
+        println!("{:?} moved to ({}, {}) with a size of {}x{}", control, x, y, width, height);
+    }
+}
+```
+*/
+#[derive(Default)]
+pub struct DesignSurface {
+    pub handle: ControlHandle,
+    state: Option<Rc<SurfaceState>>,
+    handler0: RefCell<Option<RawEventHandler>>,
+    child_handlers: RefCell<Vec<RawEventHandler>>,
+}
+
+impl DesignSurface {
+
+    pub fn builder() -> DesignSurfaceBuilder {
+        DesignSurfaceBuilder {
+            parent: None,
+            enabled: true,
+            grid: (8, 8),
+            show_grid: true,
+        }
+    }
+
+    /// Return true if the design mode is enabled
+    pub fn enabled(&self) -> bool {
+        self.state().enabled.get()
+    }
+
+    /// Enable or disable the design mode. Disabling the design mode clears the selection and gives the mouse input back to the controls.
+    pub fn set_enabled(&self, enabled: bool) {
+        use winapi::um::winuser::SetFocus;
+
+        let state = self.state();
+        if state.enabled.get() == enabled {
+            return;
+        }
+
+        state.enabled.set(enabled);
+        self.unbind_children();
+
+        if enabled {
+            self.bind_children();
+            unsafe { SetFocus(state.parent); }
+            state.repaint();
+        } else {
+            state.drag.set(None);
+            let had_selection = !state.selection.borrow().is_empty();
+            state.selection.borrow_mut().clear();
+
+            match had_selection {
+                true => state.selection_changed(),
+                false => state.repaint()
+            }
+        }
+    }
+
+    /// Read the children of the parent again. Must be called after controls are added or removed from the parent.
+    pub fn refresh(&self) {
+        let state = self.state();
+        self.unbind_children();
+
+        if state.enabled.get() {
+            self.bind_children();
+        }
+
+        let children = state.children.borrow();
+        let len = state.selection.borrow().len();
+        state.selection.borrow_mut().retain(|h| children.contains(h));
+        if len != state.selection.borrow().len() {
+            drop(children);
+            state.selection_changed();
+        }
+    }
+
+    /// Return the selected controls. The first control is the reference of the alignment commands.
+    pub fn selection(&self) -> Vec<ControlHandle> {
+        self.state().selection.borrow().iter().map(|&h| ControlHandle::Hwnd(h)).collect()
+    }
+
+    /// Return the selected controls and their geometry (`[x, y, width, height]` in the client area of the parent)
+    pub fn selection_rects(&self) -> Vec<(ControlHandle, [i32; 4])> {
+        self.state().selection.borrow().iter().map(|&h| (ControlHandle::Hwnd(h), control_rect(h))).collect()
+    }
+
+    /// Select controls. The controls that are not children of the parent are ignored. Does not raise `OnDesignSelectionChanged`.
+    pub fn set_selection(&self, controls: &[ControlHandle]) {
+        let state = self.state();
+        {
+            let children = state.children.borrow();
+            let mut selection = state.selection.borrow_mut();
+            selection.clear();
+
+            for handle in controls.iter().filter_map(|c| c.hwnd()) {
+                if children.contains(&handle) && !selection.contains(&handle) {
+                    selection.push(handle);
+                }
+            }
+        }
+
+        state.repaint();
+    }
+
+    /// Clear the selection. Does not raise `OnDesignSelectionChanged`.
+    pub fn clear_selection(&self) {
+        self.set_selection(&[]);
+    }
+
+    /// Return the size of a grid cell, in logical pixels
+    pub fn grid(&self) -> (u32, u32) {
+        self.state().grid.get()
+    }
+
+    /// Set the size of a grid cell, in logical pixels. `(0, 0)` disables the snapping.
+    pub fn set_grid(&self, grid: (u32, u32)) {
+        let state = self.state();
+        state.grid.set(grid);
+        state.repaint();
+    }
+
+    /// Return true if the grid is drawn on the parent
+    pub fn show_grid(&self) -> bool {
+        self.state().show_grid.get()
+    }
+
+    /// Set if the grid is drawn on the parent
+    pub fn set_show_grid(&self, show: bool) {
+        let state = self.state();
+        state.show_grid.set(show);
+        state.repaint();
+    }
+
+    fn state(&self) -> &SurfaceState {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.state.as_ref().unwrap()
+    }
+
+    /// Read the children of the parent and make them transparent to the mouse
+    fn bind_children(&self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::{GetWindow, WM_NCHITTEST, HTTRANSPARENT, GW_CHILD, GW_HWNDNEXT};
+
+        let state = self.state();
+        let mut children = state.children.borrow_mut();
+        let mut handlers = self.child_handlers.borrow_mut();
+        children.clear();
+
+        let mut child = unsafe { GetWindow(state.parent, GW_CHILD) };
+        while !child.is_null() {
+            children.push(child);
+
+            let handler = bind_raw_event_handler_inner(&ControlHandle::Hwnd(child), SURFACE_CHILD_ID, |_hwnd, msg, _w, _l| {
+                match msg {
+                    WM_NCHITTEST => Some(HTTRANSPARENT),
+                    _ => None
+                }
+            });
+
+            if let Ok(handler) = handler {
+                handlers.push(handler);
+            }
+
+            child = unsafe { GetWindow(child, GW_HWNDNEXT) };
+        }
+    }
+
+    fn unbind_children(&self) {
+        for handler in self.child_handlers.borrow_mut().drain(..) {
+            drop(unbind_raw_event_handler(&handler));
+        }
+    }
+
+    /// Handle the input of the parent and paint the grid and the selection
+    fn bind(&self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::{WM_PAINT, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MOUSEMOVE, WM_CAPTURECHANGED, WM_KEYDOWN, WM_SETCURSOR,
+            WM_GETDLGCODE, DLGC_WANTALLKEYS, MK_CONTROL, MK_SHIFT, VK_ESCAPE, VK_LEFT, VK_RIGHT, VK_UP, VK_DOWN, VK_CONTROL, VK_SHIFT,
+            SetCapture, ReleaseCapture, GetCapture, SetFocus, GetKeyState, GetSystemMetrics, SM_CXDRAG, SM_CYDRAG};
+        use winapi::shared::minwindef::{LOWORD, HIWORD};
+
+        let state = self.state.clone().unwrap();
+        let handler = bind_raw_event_handler_inner(&self.handle, SURFACE_ID, move |hwnd, msg, w, l| {
+            if !state.enabled.get() {
+                return None;
+            }
+
+            let cursor = || physical_to_logical(hwnd, (LOWORD(l as u32) as i16 as i32, HIWORD(l as u32) as i16 as i32));
+
+            match msg {
+                WM_PAINT => {
+                    unsafe { paint_surface(hwnd, &state); }
+                    return Some(0);
+                },
+                WM_GETDLGCODE => {
+                    return Some(DLGC_WANTALLKEYS);
+                },
+                WM_SETCURSOR => {
+                    let point = cursor_position(hwnd);
+                    let grip = state.drag.get().map(|d| d.grip).or_else(|| state.grip_at(point));
+                    let over_selection = state.child_at(point).map(|h| state.selection.borrow().contains(&h)).unwrap_or(false);
+
+                    match (grip, over_selection) {
+                        (Some(grip), _) if grip != MOVE => { set_cursor(grip); return Some(1); },
+                        (Some(_), _) | (None, true) => { set_cursor(MOVE); return Some(1); },
+                        _ => {}
+                    }
+                },
+                WM_LBUTTONDOWN => {
+                    let point = cursor();
+                    unsafe { SetFocus(hwnd); }
+
+                    let grip = match state.grip_at(point) {
+                        Some(grip) => Some(grip),
+                        None => match state.child_at(point) {
+                            Some(child) => {
+                                let toggle = w & (MK_CONTROL | MK_SHIFT) != 0;
+                                let selected = state.selection.borrow().contains(&child);
+                                match (toggle, selected) {
+                                    (true, true) => { state.selection.borrow_mut().retain(|&h| h != child); },
+                                    (true, false) => { state.selection.borrow_mut().push(child); },
+                                    (false, false) => { *state.selection.borrow_mut() = vec![child]; },
+                                    (false, true) => {}
+                                }
+
+                                if toggle || !selected {
+                                    state.selection_changed();
+                                }
+
+                                match state.selection.borrow().contains(&child) {
+                                    true => Some(MOVE),
+                                    false => None
+                                }
+                            },
+                            None => {
+                                if !state.selection.borrow().is_empty() {
+                                    state.selection.borrow_mut().clear();
+                                    state.selection_changed();
+                                }
+                                None
+                            }
+                        }
+                    };
+
+                    if let Some(grip) = grip {
+                        state.save_origin();
+                        state.drag.set(Some(Drag { grip, start: point, started: false }));
+                        unsafe { SetCapture(hwnd); }
+                    }
+
+                    return Some(0);
+                },
+                WM_MOUSEMOVE => if let Some(drag) = state.drag.get() {
+                    let (x, y) = cursor();
+                    let (dx, dy) = (x - drag.start.0, y - drag.start.1);
+
+                    let started = drag.started || unsafe {
+                        let (tx, ty) = physical_to_logical(hwnd, (GetSystemMetrics(SM_CXDRAG), GetSystemMetrics(SM_CYDRAG)));
+                        dx.abs() >= tx || dy.abs() >= ty
+                    };
+
+                    if started {
+                        state.drag.set(Some(Drag { started: true, ..drag }));
+                        state.apply(drag.grip, (dx, dy));
+                    }
+
+                    return Some(0);
+                },
+                WM_LBUTTONUP => if let Some(drag) = state.drag.get() {
+                    // Cleared first because `ReleaseCapture` sends `WM_CAPTURECHANGED` to the parent
+                    state.drag.set(None);
+                    unsafe {
+                        if GetCapture() == hwnd {
+                            ReleaseCapture();
+                        }
+                    }
+
+                    if drag.started {
+                        let handles: Vec<HWND> = state.origin.borrow().iter().map(|&(h, _)| h).collect();
+                        state.geometry_changed(&handles);
+                    }
+
+                    return Some(0);
+                },
+                WM_CAPTURECHANGED => if let Some(drag) = state.drag.get() {
+                    state.drag.set(None);
+                    if drag.started {
+                        state.restore_origin();
+                    }
+                },
+                WM_KEYDOWN => {
+                    let key = w as i32;
+                    if key == VK_ESCAPE {
+                        if state.drag.get().is_some() {
+                            unsafe { ReleaseCapture(); }
+                        }
+                        return Some(0);
+                    }
+
+                    let direction = match key {
+                        VK_LEFT => (-1, 0),
+                        VK_RIGHT => (1, 0),
+                        VK_UP => (0, -1),
+                        VK_DOWN => (0, 1),
+                        _ => { return None; }
+                    };
+
+                    if state.drag.get().is_some() || state.selection.borrow().is_empty() {
+                        return Some(0);
+                    }
+
+                    let (control, shift) = unsafe { (GetKeyState(VK_CONTROL) < 0, GetKeyState(VK_SHIFT) < 0) };
+                    let (gx, gy) = state.grid.get();
+
+                    let selection = state.selection.borrow().clone();
+                    for &handle in selection.iter() {
+                        let [x, y, w, h] = control_rect(handle);
+                        let rect = match (shift, control) {
+                            (true, _) => [x, y, (w + direction.0).max(MIN_SIZE), (h + direction.1).max(MIN_SIZE)],
+                            (false, true) => [next_grid_line(x, direction.0, gx), next_grid_line(y, direction.1, gy), w, h],
+                            (false, false) => [x + direction.0, y + direction.1, w, h],
+                        };
+
+                        set_control_rect(handle, rect);
+                    }
+
+                    state.repaint();
+                    state.geometry_changed(&selection);
+                    return Some(0);
+                },
+                _ => {}
+            }
+
+            None
+        });
+
+        *self.handler0.borrow_mut() = Some(handler.unwrap());
+    }
+
+}
+
+impl PartialEq for DesignSurface {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for DesignSurface {}
+
+impl Drop for DesignSurface {
+    fn drop(&mut self) {
+        self.unbind_children();
+
+        if let Some(h) = self.handler0.borrow_mut().take() {
+            drop(unbind_raw_event_handler(&h));
+        }
+
+        if let Some(state) = self.state.take() {
+            state.repaint();
+        }
+    }
+}
+
+pub struct DesignSurfaceBuilder {
+    parent: Option<ControlHandle>,
+    enabled: bool,
+    grid: (u32, u32),
+    show_grid: bool,
+}
+
+impl DesignSurfaceBuilder {
+
+    pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> DesignSurfaceBuilder {
+        self.parent = Some(p.into());
+        self
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> DesignSurfaceBuilder {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn grid(mut self, grid: (u32, u32)) -> DesignSurfaceBuilder {
+        self.grid = grid;
+        self
+    }
+
+    pub fn show_grid(mut self, show: bool) -> DesignSurfaceBuilder {
+        self.show_grid = show;
+        self
+    }
+
+    pub fn build(self, out: &mut DesignSurface) -> Result<(), NwgError> {
+        let parent = match self.parent {
+            Some(p) => match p.hwnd() {
+                Some(handle) => Ok(handle),
+                None => Err(NwgError::control_create("Wrong parent type"))
+            },
+            None => Err(NwgError::no_parent("DesignSurface"))
+        }?;
+
+        *out = Default::default();
+        out.handle = ControlHandle::Hwnd(parent);
+        out.state = Some(Rc::new(SurfaceState {
+            parent,
+            enabled: Cell::new(false),
+            children: RefCell::new(Vec::new()),
+            selection: RefCell::new(Vec::new()),
+            grid: Cell::new(self.grid),
+            show_grid: Cell::new(self.show_grid),
+            drag: Cell::new(None),
+            origin: RefCell::new(Vec::new()),
+        }));
+
+        out.bind();
+        out.set_enabled(self.enabled);
+
+        Ok(())
+    }
+
+}
+
+fn physical_to_logical(hwnd: HWND, (x, y): (i32, i32)) -> (i32, i32) {
+    unsafe { high_dpi::physical_to_logical_for(hwnd, x, y) }
+}
+
+fn logical_to_physical(hwnd: HWND, (x, y): (i32, i32)) -> (i32, i32) {
+    unsafe { high_dpi::logical_to_physical_for(hwnd, x, y) }
+}
+
+/// Return the outer rectangle of a control in the client area of its parent, in physical pixels
+fn physical_rect(handle: HWND) -> RECT {
+    use winapi::um::winuser::{GetWindowRect, MapWindowPoints, GetParent};
+
+    unsafe {
+        let mut r: RECT = mem::zeroed();
+        GetWindowRect(handle, &mut r);
+        MapWindowPoints(ptr::null_mut(), GetParent(handle), &mut r as *mut RECT as *mut POINT, 2);
+        r
+    }
+}
+
+/// Return the outer geometry (`[x, y, width, height]`) of a control in the client area of its parent, in logical pixels
+fn control_rect(handle: HWND) -> [i32; 4] {
+    let r = physical_rect(handle);
+    let (x, y) = physical_to_logical(handle, (r.left, r.top));
+    let (w, h) = physical_to_logical(handle, (r.right - r.left, r.bottom - r.top));
+    [x, y, w, h]
+}
+
+/// Move and resize a control. The geometry is the outer rectangle in logical pixels.
+fn set_control_rect(handle: HWND, [x, y, w, h]: [i32; 4]) {
+    use winapi::um::winuser::{SetWindowPos, SWP_NOZORDER, SWP_NOACTIVATE, SWP_NOOWNERZORDER};
+
+    let (x, y) = logical_to_physical(handle, (x, y));
+    let (w, h) = logical_to_physical(handle, (w, h));
+    unsafe { SetWindowPos(handle, ptr::null_mut(), x, y, w, h, SWP_NOZORDER|SWP_NOACTIVATE|SWP_NOOWNERZORDER); }
+}
+
+/// Return the cursor position in the client area of a window, in logical pixels
+fn cursor_position(hwnd: HWND) -> (i32, i32) {
+    use winapi::um::winuser::{GetCursorPos, ScreenToClient};
+
+    let mut p = POINT { x: 0, y: 0 };
+    unsafe {
+        GetCursorPos(&mut p);
+        ScreenToClient(hwnd, &mut p);
+    }
+
+    physical_to_logical(hwnd, (p.x, p.y))
+}
+
+/// Return the next grid line from `value` in `direction` (-1, 0 or 1). A pitch of 0 moves by one pixel.
+fn next_grid_line(value: i32, direction: i32, pitch: u32) -> i32 {
+    let p = pitch.max(1) as i32;
+    match direction {
+        1 => (value.div_euclid(p) + 1) * p,
+        -1 => (value - 1).div_euclid(p) * p,
+        _ => value
+    }
+}
+
+fn set_cursor(grip: Grip) {
+    use winapi::um::winuser::{SetCursor, LoadCursorW, IDC_SIZEALL, IDC_SIZEWE, IDC_SIZENS, IDC_SIZENWSE, IDC_SIZENESW};
+
+    let cursor = match grip {
+        Grip(0, 0) => IDC_SIZEALL,
+        Grip(_, 0) => IDC_SIZEWE,
+        Grip(0, _) => IDC_SIZENS,
+        Grip(x, y) if x == y => IDC_SIZENWSE,
+        _ => IDC_SIZENESW,
+    };
+
+    unsafe { SetCursor(LoadCursorW(ptr::null_mut(), cursor)); }
+}
+
+/// Return the eight resize grips around a control, in physical pixels. The grips are drawn outside of the control
+/// because the parent cannot paint over its children.
+fn grips(r: RECT) -> Vec<(Grip, RECT)> {
+    let mut grips = Vec::with_capacity(8);
+    let (center_x, center_y) = ((r.left + r.right) / 2, (r.top + r.bottom) / 2);
+
+    for &gy in [-1, 0, 1].iter() {
+        for &gx in [-1, 0, 1].iter() {
+            if gx == 0 && gy == 0 {
+                continue;
+            }
+
+            let x = match gx { -1 => r.left - GRIP_SIZE, 0 => center_x - GRIP_SIZE / 2, _ => r.right };
+            let y = match gy { -1 => r.top - GRIP_SIZE, 0 => center_y - GRIP_SIZE / 2, _ => r.bottom };
+            grips.push((Grip(gx, gy), RECT { left: x, top: y, right: x + GRIP_SIZE, bottom: y + GRIP_SIZE }));
+        }
+    }
+
+    grips
+}
+
+/// Paint the grid and the selection over the background of the parent
+unsafe fn paint_surface(hwnd: HWND, state: &SurfaceState) {
+    use winapi::um::winuser::{BeginPaint, EndPaint, FillRect, FrameRect, DrawFocusRect, GetSysColorBrush, PAINTSTRUCT,
+        COLOR_BTNSHADOW, COLOR_HIGHLIGHT, COLOR_WINDOW};
+
+    let mut paint: PAINTSTRUCT = mem::zeroed();
+    let dc = BeginPaint(hwnd, &mut paint);
+
+    let (gx, gy) = state.grid.get();
+    if state.show_grid.get() && gx > 1 && gy > 1 {
+        let brush = GetSysColorBrush(COLOR_BTNSHADOW);
+        let area = paint.rcPaint;
+        let (first_x, first_y) = physical_to_logical(hwnd, (area.left, area.top));
+        let (gx, gy) = (gx as i32, gy as i32);
+
+        let mut y = first_y.div_euclid(gy) * gy;
+        loop {
+            let (_, py) = logical_to_physical(hwnd, (0, y));
+            if py >= area.bottom { break; }
+
+            let mut x = first_x.div_euclid(gx) * gx;
+            loop {
+                let (px, _) = logical_to_physical(hwnd, (x, 0));
+                if px >= area.right { break; }
+
+                FillRect(dc, &RECT { left: px, top: py, right: px + 1, bottom: py + 1 }, brush);
+                x += gx;
+            }
+
+            y += gy;
+        }
+    }
+
+    let selection = state.selection.borrow();
+    let highlight = GetSysColorBrush(COLOR_HIGHLIGHT);
+    for (i, &handle) in selection.iter().enumerate() {
+        let r = physical_rect(handle);
+        let outline = RECT { left: r.left - 2, top: r.top - 2, right: r.right + 2, bottom: r.bottom + 2 };
+        DrawFocusRect(dc, &outline);
+
+        // The reference of the alignment commands has filled grips
+        for (_, grip) in grips(r) {
+            match i == 0 {
+                true => FillRect(dc, &grip, highlight),
+                false => {
+                    FillRect(dc, &grip, GetSysColorBrush(COLOR_WINDOW));
+                    FrameRect(dc, &grip, highlight)
+                }
+            };
+        }
+    }
+
+    EndPaint(hwnd, &paint);
+}
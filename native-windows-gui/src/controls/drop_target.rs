@@ -0,0 +1,752 @@
+use winapi::shared::minwindef::{DWORD, ULONG, HGLOBAL, BOOL, WPARAM, LPARAM};
+use winapi::shared::windef::{HWND, POINT, POINTL};
+use winapi::shared::guiddef::{REFIID, IsEqualIID};
+use winapi::shared::winerror::{S_OK, E_NOINTERFACE};
+use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use winapi::um::oleidl::{IDropTarget, IDropTargetVtbl, DROPEFFECT_NONE, DROPEFFECT_COPY, DROPEFFECT_MOVE, DROPEFFECT_LINK};
+use winapi::um::objidl::{IDataObject, FORMATETC, STGMEDIUM, TYMED_HGLOBAL};
+use winapi::um::winnt::HRESULT;
+use winapi::um::winuser::{CF_HDROP, CF_UNICODETEXT};
+use winapi::ctypes::c_void;
+use winapi::Interface;
+use super::control_handle::ControlHandle;
+use crate::win32::window_helper as wh;
+use crate::{bind_raw_event_handler_inner, unbind_raw_event_handler, RawEventHandler, DropFiles, NwgError};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::{ptr, mem, fmt};
+
+const NOT_BOUND: &'static str = "DropTarget is not yet bound to a winapi object";
+
+/// The id of the raw handler that revokes the drop target when its control is destroyed
+const DROP_TARGET_HANDLER_ID: usize = 0xDFFF;
+
+/// The steps of a drag and drop operation sent with `NWG_DRAG_DROP`
+pub(crate) const DRAG_ENTER: WPARAM = 0;
+pub(crate) const DRAG_OVER: WPARAM = 1;
+pub(crate) const DRAG_LEAVE: WPARAM = 2;
+pub(crate) const DROP_FILES: WPARAM = 3;
+pub(crate) const DROP_TEXT: WPARAM = 4;
+
+/// The alt key in the drag and drop keys. `oleidl::MK_ALT` has the wrong value in winapi.
+const MK_ALT: DWORD = 0x20;
+
+
+bitflags! {
+    /**
+        The keyboard modifiers and the mouse buttons held during a drag and drop operation
+    */
+    pub struct DragKeys: u32 {
+        const LEFT_BUTTON = 0x01;
+        const RIGHT_BUTTON = 0x02;
+        const SHIFT = 0x04;
+        const CONTROL = 0x08;
+        const MIDDLE_BUTTON = 0x10;
+        const ALT = 0x20;
+    }
+}
+
+/**
+    The operation done with the data dropped on a control. It also sets the cursor displayed during the drag.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DropEffect {
+    /// The control does not accept the data
+    None,
+
+    /// The data is copied into the control
+    Copy,
+
+    /// The data is moved into the control. The source deletes its own data.
+    Move,
+
+    /// The control keeps a link to the source data
+    Link,
+}
+
+impl DropEffect {
+
+    fn bits(self) -> DWORD {
+        match self {
+            DropEffect::None => DROPEFFECT_NONE,
+            DropEffect::Copy => DROPEFFECT_COPY,
+            DropEffect::Move => DROPEFFECT_MOVE,
+            DropEffect::Link => DROPEFFECT_LINK,
+        }
+    }
+
+}
+
+impl Default for DropEffect {
+    fn default() -> DropEffect {
+        DropEffect::None
+    }
+}
+
+
+/**
+    The state of a drag and drop operation over a control with a `DropTarget`. Sent with `OnDragEnter` and `OnDragOver`.
+
+    The effect can be changed during the event to refuse the drop or to select another operation.
+    An effect that is not allowed by the source of the drag refuses the drop.
+*/
+#[derive(Clone)]
+pub struct DragData {
+    point: [i32; 2],
+    keys: DragKeys,
+    files: bool,
+    text: bool,
+    allowed: DWORD,
+    effect: *const Cell<DropEffect>,
+}
+
+impl DragData {
+
+    /// Returns the position of the mouse pointer in the control
+    pub fn point(&self) -> [i32; 2] {
+        self.point
+    }
+
+    /// Returns the keyboard modifiers and the mouse buttons held by the user
+    pub fn keys(&self) -> DragKeys {
+        self.keys
+    }
+
+    /// Returns true if the dragged data contains files accepted by the drop target
+    pub fn has_files(&self) -> bool {
+        self.files
+    }
+
+    /// Returns true if the dragged data contains text accepted by the drop target
+    pub fn has_text(&self) -> bool {
+        self.text
+    }
+
+    /// Returns true if the source of the drag allows `effect`
+    pub fn is_allowed(&self, effect: DropEffect) -> bool {
+        self.allowed & effect.bits() != 0
+    }
+
+    /// Returns the effect of a drop at the current position
+    pub fn effect(&self) -> DropEffect {
+        unsafe { (*self.effect).get() }
+    }
+
+    /// Sets the effect of a drop at the current position. Use `DropEffect::None` to refuse the drop.
+    pub fn set_effect(&self, effect: DropEffect) {
+        unsafe { (*self.effect).set(effect); }
+    }
+
+}
+
+impl fmt::Debug for DragData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,
+            "DragData {{ point: {:?}, keys: {:?}, files: {:?}, text: {:?}, effect: {:?} }}",
+            self.point, self.keys, self.files, self.text, self.effect()
+        )
+    }
+}
+
+/**
+    The text dropped on a control with a `DropTarget`. Sent with `OnTextDrop`.
+*/
+#[derive(Clone, Debug)]
+pub struct DropText {
+    text: String,
+    point: [i32; 2],
+    keys: DragKeys,
+}
+
+impl DropText {
+
+    /// Returns the dropped text
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the position of the mouse pointer in the control when the text was dropped
+    pub fn point(&self) -> [i32; 2] {
+        self.point
+    }
+
+    /// Returns the keyboard modifiers and the mouse buttons held when the text was dropped
+    pub fn keys(&self) -> DragKeys {
+        self.keys
+    }
+
+}
+
+/// The files dropped on a control with a `DropTarget`. The global memory is owned by the source of the drag.
+pub(crate) struct DroppedFiles {
+    drop: HGLOBAL,
+    point: [i32; 2],
+    keys: DWORD,
+}
+
+/// The header of a `CF_HDROP` global memory (`DROPFILES`)
+#[repr(C)]
+struct DropFilesHeader {
+    files: DWORD,
+    pt: POINT,
+    nc: BOOL,
+    wide: BOOL,
+}
+
+impl DroppedFiles {
+
+    /**
+        Copy the dropped files in a `DropFiles`. Each event handler receives its own copy because `DropFiles` frees its memory.
+        The drop point is written in the copy so that `DropFiles::point` returns the position in the control.
+    */
+    pub(crate) fn to_drop_files(&self) -> DropFiles {
+        use winapi::um::winbase::{GlobalAlloc, GlobalFree, GlobalSize, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+
+        unsafe {
+            let empty = DropFiles { drop: ptr::null_mut(), keys: self.keys };
+
+            let size = GlobalSize(self.drop);
+            if size < mem::size_of::<DropFilesHeader>() {
+                return empty;
+            }
+
+            let copy = GlobalAlloc(GMEM_MOVEABLE, size);
+            if copy.is_null() {
+                return empty;
+            }
+
+            let src = GlobalLock(self.drop) as *const u8;
+            let dst = GlobalLock(copy) as *mut u8;
+            let copied = !src.is_null() && !dst.is_null();
+            if copied {
+                ptr::copy_nonoverlapping(src, dst, size);
+
+                let header = &mut *(dst as *mut DropFilesHeader);
+                header.pt = POINT { x: self.point[0], y: self.point[1] };
+                header.nc = 0;
+            }
+
+            GlobalUnlock(self.drop);
+            GlobalUnlock(copy);
+
+            match copied {
+                true => DropFiles { drop: copy as _, keys: self.keys },
+                false => {
+                    GlobalFree(copy);
+                    empty
+                }
+            }
+        }
+    }
+
+}
+
+
+/**
+A drop target accepts the files and the text dragged from the other applications (ex: the files dragged from the explorer)
+into a control. Unlike the `accept_files` parameter of `Window`, the drop target works with any control, it can accept text,
+and it raises events while the data is dragged over the control.
+
+The events are raised by the control of the drop target. The positions are in the client area of the control.
+During `OnDragEnter` and `OnDragOver`, `EventData::on_drag` returns the dragged data and the effect of a drop at the current position.
+By default, the effect is `Copy` (`Move` when shift is held, `Link` when control and shift are held).
+The effect can be changed from the event to refuse the drop or to highlight the area under the pointer.
+
+A control can only have one drop target. The drop target is revoked when it is dropped or when its control is destroyed.
+
+Requires the `drag-drop` feature.
+
+**Builder parameters:**
+  * `control`:      **Required.** The control that accepts the dropped data
+  * `accept_files`: Accept the dropped files. Defaults to true.
+  * `accept_text`:  Accept the dropped text. Defaults to true.
+  * `effect`:       The default effect of a drop when no modifier is held. Defaults to `DropEffect::Copy`.
+
+**Control events:**
+  * `OnDragEnter`: When data accepted by the drop target is dragged into the control
+  * `OnDragOver`: When the data is dragged over the control, or when the modifiers change
+  * `OnDragLeave`: When the data leaves the control, or when the drop is cancelled or refused
+  * `OnFileDrop`: When files are dropped on the control. See `EventData::on_file_drop`
+  * `OnTextDrop`: When text is dropped on the control. See `EventData::on_text_drop`
+
+```rust
+use native_windows_gui as nwg;
+
+fn build_drop_target(target: &mut nwg::DropTarget, list: &nwg::ListBox<String>) {
+    nwg::DropTarget::builder()
+        .control(list)
+        .accept_text(false)
+        .build(target)
+        .expect("Failed to build the drop target");
+}
+
+fn drag_over(data: &nwg::EventData) {
+    let drag = data.on_drag();
+    if drag.keys().contains(nwg::DragKeys::ALT) {
+        drag.set_effect(nwg::DropEffect::None);
+    }
+}
+```
+*/
+pub struct DropTarget {
+    pub handle: ControlHandle,
+    state: Rc<DropTargetState>,
+    target: *mut DropTargetHandler,
+    handler: Option<RawEventHandler>,
+}
+
+impl DropTarget {
+
+    pub fn builder() -> DropTargetBuilder {
+        DropTargetBuilder {
+            control: None,
+            accept_files: true,
+            accept_text: true,
+            effect: DropEffect::Copy,
+        }
+    }
+
+    /// Returns true if the drop target accepts the dropped files
+    pub fn accept_files(&self) -> bool {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.state.accept_files.get()
+    }
+
+    /// Sets if the drop target accepts the dropped files. Applies to the next drag.
+    pub fn set_accept_files(&self, accept: bool) {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.state.accept_files.set(accept);
+    }
+
+    /// Returns true if the drop target accepts the dropped text
+    pub fn accept_text(&self) -> bool {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.state.accept_text.get()
+    }
+
+    /// Sets if the drop target accepts the dropped text. Applies to the next drag.
+    pub fn set_accept_text(&self, accept: bool) {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.state.accept_text.set(accept);
+    }
+
+    /// Returns the default effect of a drop when no modifier is held
+    pub fn effect(&self) -> DropEffect {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.state.default_effect.get()
+    }
+
+    /// Sets the default effect of a drop when no modifier is held
+    pub fn set_effect(&self, effect: DropEffect) {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.state.default_effect.set(effect);
+    }
+
+}
+
+impl Default for DropTarget {
+    fn default() -> DropTarget {
+        DropTarget {
+            handle: ControlHandle::NoHandle,
+            state: Rc::new(DropTargetState::default()),
+            target: ptr::null_mut(),
+            handler: None,
+        }
+    }
+}
+
+impl PartialEq for DropTarget {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for DropTarget {}
+
+impl Drop for DropTarget {
+    fn drop(&mut self) {
+        use winapi::um::ole2::RevokeDragDrop;
+
+        if let Some(handler) = self.handler.take() {
+            drop(unbind_raw_event_handler(&handler));
+        }
+
+        if !self.target.is_null() {
+            unsafe {
+                if let Some(hwnd) = self.handle.hwnd() {
+                    RevokeDragDrop(hwnd);
+                }
+
+                target_release(self.target as *mut IUnknown);
+            }
+        }
+    }
+}
+
+pub struct DropTargetBuilder {
+    control: Option<ControlHandle>,
+    accept_files: bool,
+    accept_text: bool,
+    effect: DropEffect,
+}
+
+impl DropTargetBuilder {
+
+    pub fn control<C: Into<ControlHandle>>(mut self, c: C) -> DropTargetBuilder {
+        self.control = Some(c.into());
+        self
+    }
+
+    pub fn accept_files(mut self, accept: bool) -> DropTargetBuilder {
+        self.accept_files = accept;
+        self
+    }
+
+    pub fn accept_text(mut self, accept: bool) -> DropTargetBuilder {
+        self.accept_text = accept;
+        self
+    }
+
+    pub fn effect(mut self, effect: DropEffect) -> DropTargetBuilder {
+        self.effect = effect;
+        self
+    }
+
+    pub fn build(self, out: &mut DropTarget) -> Result<(), NwgError> {
+        use winapi::um::ole2::{OleInitialize, RegisterDragDrop, RevokeDragDrop};
+        use winapi::um::winuser::WM_DESTROY;
+
+        let hwnd = match self.control {
+            None => { return Err(NwgError::control_create("No control for DropTarget")); },
+            Some(c) => match c.hwnd() {
+                Some(h) => h,
+                None => { return Err(NwgError::control_create("Wrong control type for DropTarget")); }
+            }
+        };
+
+        *out = Default::default();
+
+        // Drag and drop requires OLE. `nwg::init` only initializes COM.
+        if unsafe { OleInitialize(ptr::null_mut()) } < 0 {
+            return Err(NwgError::control_create("OleInitialize failed"));
+        }
+
+        let state = Rc::new(DropTargetState {
+            accept_files: Cell::new(self.accept_files),
+            accept_text: Cell::new(self.accept_text),
+            default_effect: Cell::new(self.effect),
+            ..Default::default()
+        });
+
+        let target = Box::into_raw(Box::new(DropTargetHandler {
+            vtbl: &TARGET_VTBL,
+            refs: AtomicU32::new(1),
+            hwnd,
+            state: state.clone(),
+        }));
+
+        if unsafe { RegisterDragDrop(hwnd, target as *mut IDropTarget) } != S_OK {
+            unsafe { target_release(target as *mut IUnknown); }
+            return Err(NwgError::control_create("RegisterDragDrop failed. The control may already have a drop target."));
+        }
+
+        let handler = bind_raw_event_handler_inner(&ControlHandle::Hwnd(hwnd), DROP_TARGET_HANDLER_ID, |hwnd, msg, _w, _l| {
+            if msg == WM_DESTROY {
+                unsafe { RevokeDragDrop(hwnd); }
+            }
+
+            None
+        });
+
+        out.handle = ControlHandle::Hwnd(hwnd);
+        out.state = state;
+        out.target = target;
+        out.handler = handler.ok();
+
+        Ok(())
+    }
+
+}
+
+
+/// The options of a drop target and the state of the current drag
+#[derive(Default)]
+struct DropTargetState {
+    accept_files: Cell<bool>,
+    accept_text: Cell<bool>,
+    default_effect: Cell<DropEffect>,
+
+    /// If the dragged data contains accepted files or text
+    files: Cell<bool>,
+    text: Cell<bool>,
+
+    /// The effect of a drop at the current position, after the events were handled
+    effect: Cell<DropEffect>,
+}
+
+/*
+    A minimal `IDropTarget` implementation that raises the drag and drop events in the control of the drop target.
+*/
+#[repr(C)]
+struct DropTargetHandler {
+    vtbl: *const IDropTargetVtbl,
+    refs: AtomicU32,
+    hwnd: HWND,
+    state: Rc<DropTargetState>,
+}
+
+impl DropTargetHandler {
+
+    /// Raise `OnDragEnter` or `OnDragOver` and returns the effect selected by the event handlers
+    fn drag(&self, step: WPARAM, keys: DWORD, pt: POINTL, allowed: DWORD) -> DWORD {
+        let state = &self.state;
+        let effect = match state.files.get() || state.text.get() {
+            true => preferred_effect(state.default_effect.get(), keys, allowed),
+            false => DropEffect::None
+        };
+
+        state.effect.set(effect);
+
+        let data = DragData {
+            point: client_point(self.hwnd, pt),
+            keys: DragKeys::from_bits_truncate(keys),
+            files: state.files.get(),
+            text: state.text.get(),
+            allowed,
+            effect: &state.effect,
+        };
+
+        wh::send_message(self.hwnd, wh::NWG_DRAG_DROP, step, &data as *const DragData as LPARAM);
+
+        let effect = state.effect.get();
+        match effect.bits() & allowed {
+            0 => {
+                state.effect.set(DropEffect::None);
+                DROPEFFECT_NONE
+            },
+            bits => bits
+        }
+    }
+
+}
+
+static TARGET_VTBL: IDropTargetVtbl = IDropTargetVtbl {
+    parent: IUnknownVtbl {
+        QueryInterface: target_query_interface,
+        AddRef: target_add_ref,
+        Release: target_release,
+    },
+    DragEnter: target_drag_enter,
+    DragOver: target_drag_over,
+    DragLeave: target_drag_leave,
+    Drop: target_drop,
+};
+
+unsafe extern "system" fn target_query_interface(this: *mut IUnknown, riid: REFIID, out: *mut *mut c_void) -> HRESULT {
+    let riid = &*riid;
+    if IsEqualIID(riid, &IUnknown::uuidof()) || IsEqualIID(riid, &IDropTarget::uuidof()) {
+        target_add_ref(this);
+        *out = this as *mut c_void;
+        S_OK
+    } else {
+        *out = ptr::null_mut();
+        E_NOINTERFACE
+    }
+}
+
+unsafe extern "system" fn target_add_ref(this: *mut IUnknown) -> ULONG {
+    let handler = &*(this as *mut DropTargetHandler);
+    handler.refs.fetch_add(1, Ordering::SeqCst) + 1
+}
+
+unsafe extern "system" fn target_release(this: *mut IUnknown) -> ULONG {
+    let refs = {
+        let handler = &*(this as *mut DropTargetHandler);
+        handler.refs.fetch_sub(1, Ordering::SeqCst) - 1
+    };
+
+    if refs == 0 {
+        drop(Box::from_raw(this as *mut DropTargetHandler));
+    }
+
+    refs
+}
+
+unsafe extern "system" fn target_drag_enter(this: *mut IDropTarget, data: *const IDataObject, keys: DWORD, pt: *const POINTL, effect: *mut DWORD) -> HRESULT {
+    let handler = &*(this as *mut DropTargetHandler);
+    let state = &handler.state;
+    state.files.set(state.accept_files.get() && has_format(data, CF_HDROP));
+    state.text.set(state.accept_text.get() && has_format(data, CF_UNICODETEXT));
+
+    *effect = handler.drag(DRAG_ENTER, keys, *pt, *effect);
+    S_OK
+}
+
+unsafe extern "system" fn target_drag_over(this: *mut IDropTarget, keys: DWORD, pt: *const POINTL, effect: *mut DWORD) -> HRESULT {
+    let handler = &*(this as *mut DropTargetHandler);
+    *effect = handler.drag(DRAG_OVER, keys, *pt, *effect);
+    S_OK
+}
+
+unsafe extern "system" fn target_drag_leave(this: *mut IDropTarget) -> HRESULT {
+    let handler = &*(this as *mut DropTargetHandler);
+    wh::send_message(handler.hwnd, wh::NWG_DRAG_DROP, DRAG_LEAVE, 0);
+    S_OK
+}
+
+unsafe extern "system" fn target_drop(this: *mut IDropTarget, data: *const IDataObject, keys: DWORD, pt: *const POINTL, effect: *mut DWORD) -> HRESULT {
+    let handler = &*(this as *mut DropTargetHandler);
+    let state = &handler.state;
+
+    // The effect was selected by the last `OnDragOver`
+    let bits = state.effect.get().bits() & *effect;
+    if bits == 0 {
+        *effect = DROPEFFECT_NONE;
+        wh::send_message(handler.hwnd, wh::NWG_DRAG_DROP, DRAG_LEAVE, 0);
+        return S_OK;
+    }
+
+    // Files are preferred when the data contains both files and text
+    let files = state.files.get();
+    let medium = match files {
+        true => get_global(data, CF_HDROP),
+        false => get_global(data, CF_UNICODETEXT)
+    };
+
+    let medium = match medium {
+        Some(medium) => medium,
+        None => {
+            *effect = DROPEFFECT_NONE;
+            wh::send_message(handler.hwnd, wh::NWG_DRAG_DROP, DRAG_LEAVE, 0);
+            return S_OK;
+        }
+    };
+
+    let point = client_point(handler.hwnd, *pt);
+    if files {
+        let files = DroppedFiles { drop: medium_global(&medium), point, keys };
+        wh::send_message(handler.hwnd, wh::NWG_DRAG_DROP, DROP_FILES, &files as *const DroppedFiles as LPARAM);
+        release_medium(medium);
+    } else {
+        let text = DropText { text: read_text(medium_global(&medium)), point, keys: DragKeys::from_bits_truncate(keys) };
+        release_medium(medium);
+        wh::send_message(handler.hwnd, wh::NWG_DRAG_DROP, DROP_TEXT, &text as *const DropText as LPARAM);
+    }
+
+    *effect = bits;
+    S_OK
+}
+
+/**
+    Returns the effect of a drop when `keys` are held. The default effect is used when no modifier is held.
+    If the source does not allow the effect, the first effect allowed is used instead.
+*/
+fn preferred_effect(default: DropEffect, keys: DWORD, allowed: DWORD) -> DropEffect {
+    use winapi::um::winuser::{MK_CONTROL, MK_SHIFT};
+
+    let control = keys & (MK_CONTROL as DWORD) != 0;
+    let shift = keys & (MK_SHIFT as DWORD) != 0;
+    let effect = match (control, shift) {
+        (true, true) => DropEffect::Link,
+        (true, false) => DropEffect::Copy,
+        (false, true) => DropEffect::Move,
+        (false, false) => default,
+    };
+
+    if effect == DropEffect::None {
+        return effect;
+    }
+
+    [effect, DropEffect::Copy, DropEffect::Move, DropEffect::Link].iter()
+        .cloned()
+        .find(|e| e.bits() & allowed != 0)
+        .unwrap_or(DropEffect::None)
+}
+
+fn client_point(hwnd: HWND, pt: POINTL) -> [i32; 2] {
+    use winapi::um::winuser::ScreenToClient;
+
+    let mut point = POINT { x: pt.x, y: pt.y };
+    unsafe { ScreenToClient(hwnd, &mut point); }
+    [point.x, point.y]
+}
+
+/// Returns the keys held by the user, in the format of the drag and drop keys. Used by the `WM_DROPFILES` message.
+pub(crate) fn current_drag_keys() -> DWORD {
+    use winapi::um::winuser::{GetKeyState, VK_LBUTTON, VK_RBUTTON, VK_MBUTTON, VK_SHIFT, VK_CONTROL, VK_MENU};
+
+    let keys = [(VK_LBUTTON, 0x01), (VK_RBUTTON, 0x02), (VK_SHIFT, 0x04), (VK_CONTROL, 0x08), (VK_MBUTTON, 0x10), (VK_MENU, MK_ALT)];
+    keys.iter()
+        .filter(|(vk, _)| unsafe { GetKeyState(*vk) } < 0)
+        .fold(0, |acc, (_, bit)| acc | bit)
+}
+
+fn format(clip_format: u32) -> FORMATETC {
+    use winapi::shared::wtypes::DVASPECT_CONTENT;
+
+    FORMATETC {
+        cfFormat: clip_format as _,
+        ptd: ptr::null(),
+        dwAspect: DVASPECT_CONTENT,
+        lindex: -1,
+        tymed: TYMED_HGLOBAL,
+    }
+}
+
+unsafe fn has_format(data: *const IDataObject, f: u32) -> bool {
+    !data.is_null() && (&*data).QueryGetData(&format(f)) == S_OK
+}
+
+/// Returns the data of `data` in the format `f`, in a global memory
+unsafe fn get_global(data: *const IDataObject, f: u32) -> Option<STGMEDIUM> {
+    if data.is_null() {
+        return None;
+    }
+
+    let mut medium: STGMEDIUM = mem::zeroed();
+    if (&*data).GetData(&format(f), &mut medium) != S_OK {
+        return None;
+    }
+
+    match medium.tymed == TYMED_HGLOBAL {
+        true => Some(medium),
+        false => {
+            release_medium(medium);
+            None
+        }
+    }
+}
+
+/// winapi declares the union of `STGMEDIUM` as a pointer. The first member of the union, the global memory handle, is stored in its place.
+fn medium_global(medium: &STGMEDIUM) -> HGLOBAL {
+    medium.u as HGLOBAL
+}
+
+/// Release a medium returned by `IDataObject::GetData` (`ReleaseStgMedium` for a global memory)
+unsafe fn release_medium(medium: STGMEDIUM) {
+    use winapi::um::winbase::GlobalFree;
+
+    if !medium.pUnkForRelease.is_null() {
+        (&*medium.pUnkForRelease).Release();
+    } else if medium.tymed == TYMED_HGLOBAL {
+        GlobalFree(medium_global(&medium));
+    }
+}
+
+unsafe fn read_text(global: HGLOBAL) -> String {
+    use winapi::um::winbase::{GlobalSize, GlobalLock, GlobalUnlock};
+    use crate::win32::base_helper::from_utf16;
+    use std::slice;
+
+    let len = GlobalSize(global) / 2;
+    let text = GlobalLock(global) as *const u16;
+    if text.is_null() {
+        return String::new();
+    }
+
+    let value = from_utf16(slice::from_raw_parts(text, len));
+    GlobalUnlock(global);
+
+    value
+}
@@ -0,0 +1,404 @@
+/*!
+    Runtime control creation. Used by tools that must instance controls without knowing their type at compile time (ex: a gui designer).
+*/
+use std::any::Any;
+use crate::win32::{base_helper::check_hwnd, window_helper as wh};
+use crate::NwgError;
+use super::*;
+
+const NOT_BOUND: &'static str = "DynControl is not yet bound to a winapi object";
+const BAD_HANDLE: &'static str = "INTERNAL ERROR: DynControl handle is not HWND!";
+
+
+/**
+    The type of control that can be created by `create_control`.
+    Variants are only available if the matching control feature is enabled.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ControlType {
+    Window,
+    Button,
+    CheckBox,
+    RadioButton,
+    TextInput,
+    Label,
+    ImageFrame,
+
+    #[cfg(feature = "textbox")]
+    TextBox,
+
+    #[cfg(feature = "rich-textbox")]
+    RichTextBox,
+
+    #[cfg(feature = "rich-textbox")]
+    RichLabel,
+
+    #[cfg(feature = "trackbar")]
+    TrackBar,
+
+    #[cfg(feature = "combobox")]
+    ComboBox,
+
+    #[cfg(feature = "listbox")]
+    ListBox,
+
+    #[cfg(feature = "datetime-picker")]
+    DatePicker,
+
+    #[cfg(feature = "progress-bar")]
+    ProgressBar,
+
+    #[cfg(feature = "tree-view")]
+    TreeView,
+
+    #[cfg(feature = "list-view")]
+    ListView,
+
+    #[cfg(feature = "number-select")]
+    NumberSelect,
+
+    #[cfg(feature = "frame")]
+    Frame,
+
+    #[cfg(feature = "scroll-bar")]
+    ScrollBar,
+}
+
+impl ControlType {
+
+    /// Returns the name of the control type. This is the same as the name of the rust struct.
+    pub fn name(&self) -> &'static str {
+        use ControlType::*;
+
+        match self {
+            Window => "Window",
+            Button => "Button",
+            CheckBox => "CheckBox",
+            RadioButton => "RadioButton",
+            TextInput => "TextInput",
+            Label => "Label",
+            ImageFrame => "ImageFrame",
+            #[cfg(feature = "textbox")] TextBox => "TextBox",
+            #[cfg(feature = "rich-textbox")] RichTextBox => "RichTextBox",
+            #[cfg(feature = "rich-textbox")] RichLabel => "RichLabel",
+            #[cfg(feature = "trackbar")] TrackBar => "TrackBar",
+            #[cfg(feature = "combobox")] ComboBox => "ComboBox",
+            #[cfg(feature = "listbox")] ListBox => "ListBox",
+            #[cfg(feature = "datetime-picker")] DatePicker => "DatePicker",
+            #[cfg(feature = "progress-bar")] ProgressBar => "ProgressBar",
+            #[cfg(feature = "tree-view")] TreeView => "TreeView",
+            #[cfg(feature = "list-view")] ListView => "ListView",
+            #[cfg(feature = "number-select")] NumberSelect => "NumberSelect",
+            #[cfg(feature = "frame")] Frame => "Frame",
+            #[cfg(feature = "scroll-bar")] ScrollBar => "ScrollBar",
+        }
+    }
+
+    /// Returns the control type matching `name`. See `ControlType::name`.
+    pub fn from_name(name: &str) -> Option<ControlType> {
+        ControlType::all().iter().find(|ty| ty.name() == name).copied()
+    }
+
+    /// Returns true if the control type can hold children controls
+    pub fn is_container(&self) -> bool {
+        match self {
+            ControlType::Window => true,
+            #[cfg(feature = "frame")] ControlType::Frame => true,
+            _ => false
+        }
+    }
+
+    /// Returns every control type available with the current features
+    pub fn all() -> &'static [ControlType] {
+        use ControlType::*;
+
+        &[
+            Window, Button, CheckBox, RadioButton, TextInput, Label, ImageFrame,
+            #[cfg(feature = "textbox")] TextBox,
+            #[cfg(feature = "rich-textbox")] RichTextBox,
+            #[cfg(feature = "rich-textbox")] RichLabel,
+            #[cfg(feature = "trackbar")] TrackBar,
+            #[cfg(feature = "combobox")] ComboBox,
+            #[cfg(feature = "listbox")] ListBox,
+            #[cfg(feature = "datetime-picker")] DatePicker,
+            #[cfg(feature = "progress-bar")] ProgressBar,
+            #[cfg(feature = "tree-view")] TreeView,
+            #[cfg(feature = "list-view")] ListView,
+            #[cfg(feature = "number-select")] NumberSelect,
+            #[cfg(feature = "frame")] Frame,
+            #[cfg(feature = "scroll-bar")] ScrollBar,
+        ]
+    }
+
+}
+
+
+/**
+    The properties used by `create_control`. Properties that do not apply to a control type are ignored
+    (ex: `text` on a `ProgressBar`).
+
+    * `text`:     The control text. For a window, this is the title.
+    * `size`:     The control size
+    * `position`: The control position
+    * `enabled`:  If the control can be used by the user
+    * `visible`:  If the control is visible after being created
+*/
+#[derive(Clone, Debug)]
+pub struct ControlProps {
+    pub text: String,
+    pub size: (i32, i32),
+    pub position: (i32, i32),
+    pub enabled: bool,
+    pub visible: bool,
+}
+
+impl Default for ControlProps {
+    fn default() -> ControlProps {
+        ControlProps {
+            text: String::new(),
+            size: (100, 25),
+            position: (0, 0),
+            enabled: true,
+            visible: true,
+        }
+    }
+}
+
+
+/**
+    A type erased control created by `create_control`. The control is freed when the `DynControl` is dropped.
+
+    `DynControl` exposes the operations shared by every window control. Use `downcast_ref` to access the
+    control specific methods.
+
+```rust
+use native_windows_gui as nwg;
+
+fn add_button(window: &nwg::Window) -> Result<nwg::DynControl, nwg::NwgError> {
+    let props = nwg::ControlProps { text: "Hello".into(), ..Default::default() };
+    let control = nwg::create_control(nwg::ControlType::Button, Some(&window.handle), &props)?;
+
+    if let Some(button) = control.downcast_ref::<nwg::Button>() {
+        button.click();
+    }
+
+    Ok(control)
+}
+```
+*/
+pub struct DynControl {
+    pub handle: ControlHandle,
+    ty: ControlType,
+    control: Box<dyn Any>,
+}
+
+impl DynControl {
+
+    /// Returns the type of the inner control
+    pub fn control_type(&self) -> ControlType {
+        self.ty
+    }
+
+    /// Returns a reference to the inner control if it is of type `T`
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.control.downcast_ref::<T>()
+    }
+
+    /// Returns a mutable reference to the inner control if it is of type `T`
+    pub fn downcast_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.control.downcast_mut::<T>()
+    }
+
+    /// Consumes the `DynControl` and returns the inner control if it is of type `T`.
+    /// Returns the `DynControl` unchanged otherwise.
+    pub fn downcast<T: Any>(self) -> Result<Box<T>, DynControl> {
+        let DynControl { handle, ty, control } = self;
+        control.downcast::<T>()
+            .map_err(|control| DynControl { handle, ty, control })
+    }
+
+    /// Returns the control text. For a window, this is the title.
+    pub fn text(&self) -> String {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_text(handle) }
+    }
+
+    /// Sets the control text. For a window, this is the title.
+    pub fn set_text<'a>(&self, v: &'a str) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_text(handle, v) }
+    }
+
+    /// Returns true if the control user can interact with the control, return false otherwise
+    pub fn enabled(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_enabled(handle) }
+    }
+
+    /// Enable or disable the control
+    pub fn set_enabled(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_enabled(handle, v) }
+    }
+
+    /// Returns true if the control is visible to the user.
+    pub fn visible(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_visibility(handle) }
+    }
+
+    /// Show or hide the control to the user
+    pub fn set_visible(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_visibility(handle, v) }
+    }
+
+    /// Returns the size of the control
+    pub fn size(&self) -> (u32, u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_size(handle) }
+    }
+
+    /// Sets the size of the control. Windows size is fixed to exclude the window borders.
+    pub fn set_size(&self, x: u32, y: u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_size(handle, x, y, self.ty == ControlType::Window) }
+    }
+
+    /// Returns the position of the control in its parent
+    pub fn position(&self) -> (i32, i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_position(handle) }
+    }
+
+    /// Sets the position of the control in its parent
+    pub fn set_position(&self, x: i32, y: i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_position(handle, x, y) }
+    }
+
+    /// Returns true if the control currently has the keyboard focus
+    pub fn focus(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_focus(handle) }
+    }
+
+    /// Sets the keyboard focus on the control
+    pub fn set_focus(&self) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_focus(handle); }
+    }
+
+}
+
+impl PartialEq<ControlHandle> for DynControl {
+    fn eq(&self, other: &ControlHandle) -> bool {
+        self.handle == *other
+    }
+}
+
+impl PartialEq<DynControl> for ControlHandle {
+    fn eq(&self, other: &DynControl) -> bool {
+        *self == other.handle
+    }
+}
+
+impl From<&DynControl> for ControlHandle {
+    fn from(control: &DynControl) -> Self { control.handle }
+}
+
+
+/**
+    Creates a new control of type `ty` using `props`.
+
+    `parent` is required for every control type except `ControlType::Window`.
+    The created control uses the default flags of its type.
+*/
+pub fn create_control(ty: ControlType, parent: Option<&ControlHandle>, props: &ControlProps) -> Result<DynControl, NwgError> {
+    let parent = parent.copied();
+
+    macro_rules! build_child {
+        ($ty:ident, $builder:expr) => {{
+            let parent = parent.ok_or_else(|| NwgError::no_parent(stringify!($ty)))?;
+            let mut control = $ty::default();
+            $builder
+                .size(props.size)
+                .position(props.position)
+                .parent(parent)
+                .build(&mut control)?;
+
+            (control.handle, Box::new(control) as Box<dyn Any>)
+        }};
+    }
+
+    let (handle, control) = match ty {
+        ControlType::Window => {
+            let mut control = Window::default();
+            Window::builder()
+                .title(&props.text)
+                .size(props.size)
+                .position(props.position)
+                .parent(parent)
+                .build(&mut control)?;
+
+            (control.handle, Box::new(control) as Box<dyn Any>)
+        },
+        ControlType::Button => build_child!(Button, Button::builder().text(&props.text)),
+        ControlType::CheckBox => build_child!(CheckBox, CheckBox::builder().text(&props.text)),
+        ControlType::RadioButton => build_child!(RadioButton, RadioButton::builder().text(&props.text)),
+        ControlType::TextInput => build_child!(TextInput, TextInput::builder().text(&props.text)),
+        ControlType::Label => build_child!(Label, Label::builder().text(&props.text)),
+        ControlType::ImageFrame => build_child!(ImageFrame, ImageFrame::builder()),
+
+        #[cfg(feature = "textbox")]
+        ControlType::TextBox => build_child!(TextBox, TextBox::builder().text(&props.text)),
+
+        #[cfg(feature = "rich-textbox")]
+        ControlType::RichTextBox => build_child!(RichTextBox, RichTextBox::builder().text(&props.text)),
+
+        #[cfg(feature = "rich-textbox")]
+        ControlType::RichLabel => build_child!(RichLabel, RichLabel::builder().text(&props.text)),
+
+        #[cfg(feature = "trackbar")]
+        ControlType::TrackBar => build_child!(TrackBar, TrackBar::builder()),
+
+        #[cfg(feature = "combobox")]
+        ControlType::ComboBox => {
+            type ComboBox = super::ComboBox<String>;
+            build_child!(ComboBox, ComboBox::builder())
+        },
+
+        #[cfg(feature = "listbox")]
+        ControlType::ListBox => {
+            type ListBox = super::ListBox<String>;
+            build_child!(ListBox, ListBox::builder())
+        },
+
+        #[cfg(feature = "datetime-picker")]
+        ControlType::DatePicker => build_child!(DatePicker, DatePicker::builder()),
+
+        #[cfg(feature = "progress-bar")]
+        ControlType::ProgressBar => build_child!(ProgressBar, ProgressBar::builder()),
+
+        #[cfg(feature = "tree-view")]
+        ControlType::TreeView => build_child!(TreeView, TreeView::builder()),
+
+        #[cfg(feature = "list-view")]
+        ControlType::ListView => build_child!(ListView, ListView::builder()),
+
+        #[cfg(feature = "number-select")]
+        ControlType::NumberSelect => build_child!(NumberSelect, NumberSelect::builder()),
+
+        #[cfg(feature = "frame")]
+        ControlType::Frame => build_child!(Frame, Frame::builder()),
+
+        #[cfg(feature = "scroll-bar")]
+        ControlType::ScrollBar => build_child!(ScrollBar, ScrollBar::builder()),
+    };
+
+    let control = DynControl { handle, ty, control };
+    control.set_enabled(props.enabled);
+    control.set_visible(props.visible);
+
+    Ok(control)
+}
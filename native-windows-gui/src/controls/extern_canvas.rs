@@ -3,12 +3,19 @@ WS_MINIMIZEBOX, WS_MAXIMIZEBOX, WS_SYSMENU, WS_THICKFRAME, WS_CLIPCHILDREN, WS_C
 
 use crate::win32::base_helper::check_hwnd;
 use crate::win32::window_helper as wh;
-use crate::{NwgError, Icon};
+use crate::win32::window::nwg_class;
+use crate::win32::extern_canvas::{PresentThread, compositor_refresh_rate};
+use crate::{NwgError, Icon, RawEventHandler, unbind_raw_event_handler};
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
+use std::cell::RefCell;
 
 const NOT_BOUND: &'static str = "ExternCanvas is not yet bound to a winapi object";
 const BAD_HANDLE: &'static str = "INTERNAL ERROR: ExternCanvas handle is not HWND!";
 
+/// The raw event handler id used to acknowledge the present events
+const PRESENT_READY_ID: usize = 0x7453;
+
 
 bitflags! {
 
@@ -65,11 +72,15 @@ bitflags! {
       * `position`: The default position of the window in the desktop
       * `icon`: The window icon
       * `parent`: Logical parent of the window, unlike children controls, this is NOT required.
+      * `present_events`: If the canvas should receive `OnPresentReady` events. Defaults to false.
 
     **Control events:**
       * `OnInit`: The window was created
       * `MousePress(_)`: Generic mouse press events on the button
       * `OnMouseMove`: Generic mouse mouse event
+      * `OnMouseEnter`: When the mouse enters the control
+      * `OnMouseLeave`: When the mouse leaves the control
+      * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
       * `OnMouseWheel`: Generic mouse wheel event
       * `OnPaint`: Generic on paint event
       * `OnKeyPress`: Generic key press
@@ -81,11 +92,21 @@ bitflags! {
       * `OnWindowMinimize`: When the window is minimized
       * `OnMove`: When the window is moved by the user
       * `OnMinMaxInfo`: When the size or position of the window is about to change and the size of the windows must be restricted
+      * `OnPresentReady`: When the desktop compositor is ready for a new frame. Only sent if the present events are enabled.
+
+    **Present events:**
+
+    The present events are an alternative to `AnimationTimer` for canvas that render continuously.
+    Instead of a fixed interval, `OnPresentReady` is raised once per vertical blank of the desktop compositor, so
+    the frames are rendered in sync with the display. If the event handler takes longer than a frame, the next events are skipped
+    instead of being queued. The refresh rate of the compositor can be read with `refresh_rate`.
 
 */
 #[derive(Default)]
 pub struct ExternCanvas {
-    pub handle: ControlHandle
+    pub handle: ControlHandle,
+    present_thread: RefCell<Option<PresentThread>>,
+    present_handler: RefCell<Option<RawEventHandler>>,
 }
 
 impl ExternCanvas {
@@ -98,10 +119,50 @@ impl ExternCanvas {
             flags: None,
             ex_flags: 0,
             icon: None,
-            parent: None
+            parent: None,
+            present_events: false,
+            name: None,
+            state: Default::default(),
         }
     }
 
+    /// Return true if the canvas receives `OnPresentReady` events
+    pub fn present_events(&self) -> bool {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.present_thread.borrow().is_some()
+    }
+
+    /// Enable or disable the `OnPresentReady` events
+    pub fn set_present_events(&self, enabled: bool) {
+        use crate::bind_raw_event_handler_inner;
+        use crate::win32::window_helper::NWG_PRESENT_READY;
+        use std::sync::atomic::Ordering;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        self.stop_present_events();
+        if !enabled {
+            return;
+        }
+
+        let thread = PresentThread::start(handle);
+        let pending = thread.pending();
+        let handler = bind_raw_event_handler_inner(&self.handle, PRESENT_READY_ID, move |_hwnd, msg, _w, _l| {
+            if msg == NWG_PRESENT_READY {
+                pending.store(false, Ordering::SeqCst);
+            }
+            None
+        });
+
+        *self.present_handler.borrow_mut() = Some(handler.unwrap());
+        *self.present_thread.borrow_mut() = Some(thread);
+    }
+
+    /// Return the refresh rate of the desktop compositor in hertz. This is the rate of the `OnPresentReady` events.
+    pub fn refresh_rate(&self) -> f64 {
+        compositor_refresh_rate()
+    }
+
     /// Invalidate the whole drawing region. For canvas that are children control, this should be called in the paint event.
     pub fn invalidate(&self) {
         use winapi::um::winuser::InvalidateRect;
@@ -221,7 +282,7 @@ impl ExternCanvas {
 
     /// Winapi class name used during control creation
     pub fn class_name(&self) -> &'static str {
-        "NWG_EXTERN_CANVAS"
+        nwg_class("NWG_EXTERN_CANVAS")
     }
 
     // Winapi base flags used during window creation
@@ -233,10 +294,19 @@ impl ExternCanvas {
     pub fn forced_flags(&self) -> u32 {
         WS_CLIPCHILDREN | WS_CLIPSIBLINGS 
     }
+
+    fn stop_present_events(&self) {
+        drop(self.present_thread.borrow_mut().take());
+
+        if let Some(h) = self.present_handler.borrow_mut().take() {
+            drop(unbind_raw_event_handler(&h));
+        }
+    }
 }
 
 impl Drop for ExternCanvas {
     fn drop(&mut self) {
+        self.stop_present_events();
         self.handle.destroy();
     }
 }
@@ -272,7 +342,10 @@ pub struct ExternCanvasBuilder<'a> {
     flags: Option<ExternCanvasFlags>,
     ex_flags: u32,
     icon: Option<&'a Icon>,
-    parent: Option<ControlHandle>
+    parent: Option<ControlHandle>,
+    present_events: bool,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a> ExternCanvasBuilder<'a> {
@@ -312,10 +385,20 @@ impl<'a> ExternCanvasBuilder<'a> {
         self
     }
 
+    pub fn present_events(mut self, present_events: bool) -> ExternCanvasBuilder<'a> {
+        self.present_events = present_events;
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> ExternCanvasBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut ExternCanvas) -> Result<(), NwgError> {
         use winapi::um::winuser::{WS_CHILD};
 
-        let mut flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let mut flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
 
         // Remove window flags if a parent is set
         if self.parent.is_some() {
@@ -326,6 +409,7 @@ impl<'a> ExternCanvasBuilder<'a> {
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -340,6 +424,10 @@ impl<'a> ExternCanvasBuilder<'a> {
             out.set_icon(self.icon);
         }
 
+        if self.present_events {
+            out.set_present_events(true);
+        }
+
         Ok(())
     }
 
@@ -0,0 +1,356 @@
+use winapi::shared::windef::{HWND, HDC, POINT, RECT};
+use winapi::shared::minwindef::{WPARAM, LPARAM};
+use winapi::um::winuser::{WS_POPUP, WS_BORDER, WS_EX_TOPMOST, WS_EX_TOOLWINDOW};
+use super::{ControlBase, ControlHandle};
+use crate::win32::{window_helper as wh, window::{build_notice, nwg_class}};
+use crate::{NwgError, RawEventHandler, unbind_raw_event_handler};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::{ptr, mem};
+
+const NOT_BOUND: &'static str = "Eyedropper is not yet bound to a winapi object";
+const BAD_HANDLE: &'static str = "INTERNAL ERROR: Eyedropper handle is not Notice!";
+
+/// The id of the timer that moves the loupe and the id of the raw event handler of the loupe
+const EYEDROPPER_ID: usize = 0x4579;
+
+/// The distance between the cursor and the loupe, in pixels
+const LOUPE_OFFSET: i32 = 24;
+
+/// Sent in the lparam of `NWG_COLOR_PICKED` when the pick was cancelled
+pub(crate) const COLOR_PICK_CANCELLED: LPARAM = -1;
+
+
+/// The state shared with the loupe window
+struct EyedropperState {
+    parent: HWND,
+    id: u32,
+    zoom: i32,
+    active: Cell<bool>,
+}
+
+/**
+An eyedropper lets the user pick the color of any pixel on the screen.
+
+When `start` is called, the cursor becomes a crosshair and a loupe displaying a magnified view of the pixels
+under the cursor follows the mouse. A left click picks the color under the cursor and raises `OnColorPicked`.
+A right click, the escape key, or `stop` cancels the pick and raises `OnColorPickCancelled`.
+
+An eyedropper must have a parent window that receives the events.
+
+Requires the `eyedropper` feature.
+
+**Builder parameters:**
+  * `parent`:     **Required.** The eyedropper parent container that will receive the events. Should be a top level window
+  * `zoom`:       The magnification of the loupe. Defaults to 8.
+  * `loupe_size`: The size of the loupe in pixels. Defaults to 120.
+
+**Control events:**
+  * `OnColorPicked`: When the user picked a color. The color can be read with `EventData::on_color_picked`
+  * `OnColorPickCancelled`: When the pick was cancelled
+
+```rust
+use native_windows_gui as nwg;
+
+fn build_eyedropper(eyedropper: &mut nwg::Eyedropper, window: &nwg::Window) {
+    nwg::Eyedropper::builder()
+        .parent(window)
+        .build(eyedropper);
+}
+
+fn events(evt: nwg::Event, evt_data: &nwg::EventData) {
+    if evt == nwg::Event::OnColorPicked {
+        let [r, g, b] = evt_data.on_color_picked();
+        println!("#{:02X}{:02X}{:02X}", r, g, b);
+    }
+}
+```
+*/
+#[derive(Default)]
+pub struct Eyedropper {
+    pub handle: ControlHandle,
+    loupe: ControlHandle,
+    state: Option<Rc<EyedropperState>>,
+    handler0: RefCell<Option<RawEventHandler>>,
+}
+
+impl Eyedropper {
+
+    pub fn builder() -> EyedropperBuilder {
+        EyedropperBuilder {
+            parent: None,
+            zoom: 8,
+            loupe_size: 120,
+        }
+    }
+
+    /// Return the color of a pixel of the screen. `x` and `y` are in screen coordinates.
+    pub fn screen_color(x: i32, y: i32) -> [u8; 3] {
+        use winapi::um::winuser::{GetDC, ReleaseDC};
+        use winapi::um::wingdi::{GetPixel, GetRValue, GetGValue, GetBValue};
+
+        unsafe {
+            let screen = GetDC(ptr::null_mut());
+            let color = GetPixel(screen, x, y);
+            ReleaseDC(ptr::null_mut(), screen);
+            [GetRValue(color), GetGValue(color), GetBValue(color)]
+        }
+    }
+
+    /// Start picking a color. Does nothing if a pick is already active.
+    pub fn start(&self) {
+        use winapi::um::winuser::{SetCapture, SetTimer, SetCursor, LoadCursorW, ShowWindow, SetForegroundWindow, IDC_CROSS, SW_SHOW};
+
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.handle.notice().expect(BAD_HANDLE);
+
+        let state = self.state.as_ref().unwrap();
+        if state.active.get() {
+            return;
+        }
+
+        let loupe = self.loupe.hwnd().unwrap();
+        state.active.set(true);
+
+        unsafe {
+            move_loupe(loupe);
+            // The loupe takes the keyboard focus to receive the escape key
+            ShowWindow(loupe, SW_SHOW);
+            SetForegroundWindow(loupe);
+            wh::set_focus(loupe);
+            SetCapture(loupe);
+            SetCursor(LoadCursorW(ptr::null_mut(), IDC_CROSS));
+            SetTimer(loupe, EYEDROPPER_ID, 16, None);
+        }
+    }
+
+    /// Cancel the current pick. Raises `OnColorPickCancelled` if a pick was active.
+    pub fn stop(&self) {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.handle.notice().expect(BAD_HANDLE);
+
+        let state = self.state.as_ref().unwrap();
+        if state.active.get() {
+            finish(self.loupe.hwnd().unwrap(), state, COLOR_PICK_CANCELLED);
+        }
+    }
+
+    /// Return true if the user is currently picking a color
+    pub fn active(&self) -> bool {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.state.as_ref().map(|s| s.active.get()).unwrap_or(false)
+    }
+
+    /// Handle the messages of the loupe window
+    fn bind_loupe(&self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::{WM_TIMER, WM_PAINT, WM_LBUTTONDOWN, WM_RBUTTONDOWN, WM_KEYDOWN, WM_CAPTURECHANGED, WM_SETCURSOR,
+            VK_ESCAPE, SetCursor, LoadCursorW, IDC_CROSS};
+        use winapi::um::wingdi::RGB;
+
+        let state = self.state.clone().unwrap();
+
+        let handler = bind_raw_event_handler_inner(&self.loupe, EYEDROPPER_ID, move |hwnd, msg, w, _l| {
+            match msg {
+                WM_PAINT => {
+                    unsafe { paint_loupe(hwnd, state.zoom); }
+                    return Some(0);
+                },
+                WM_SETCURSOR => {
+                    unsafe { SetCursor(LoadCursorW(ptr::null_mut(), IDC_CROSS)); }
+                    return Some(1);
+                },
+                _ => {}
+            }
+
+            if !state.active.get() {
+                return None;
+            }
+
+            match msg {
+                WM_TIMER if w == EYEDROPPER_ID => {
+                    unsafe { move_loupe(hwnd); }
+                    Some(0)
+                },
+                WM_LBUTTONDOWN => {
+                    let (x, y) = cursor_position();
+                    let [r, g, b] = Eyedropper::screen_color(x, y);
+                    finish(hwnd, &state, RGB(r, g, b) as LPARAM);
+                    Some(0)
+                },
+                WM_RBUTTONDOWN | WM_CAPTURECHANGED => {
+                    finish(hwnd, &state, COLOR_PICK_CANCELLED);
+                    Some(0)
+                },
+                WM_KEYDOWN if w == VK_ESCAPE as WPARAM => {
+                    finish(hwnd, &state, COLOR_PICK_CANCELLED);
+                    Some(0)
+                },
+                _ => None
+            }
+        });
+
+        *self.handler0.borrow_mut() = Some(handler.unwrap());
+    }
+
+}
+
+impl PartialEq for Eyedropper {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for Eyedropper {}
+
+impl Drop for Eyedropper {
+    fn drop(&mut self) {
+        if let Some(h) = self.handler0.borrow_mut().take() {
+            drop(unbind_raw_event_handler(&h));
+        }
+
+        self.loupe.destroy();
+        self.handle.destroy();
+    }
+}
+
+pub struct EyedropperBuilder {
+    parent: Option<ControlHandle>,
+    zoom: u32,
+    loupe_size: u32,
+}
+
+impl EyedropperBuilder {
+
+    pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> EyedropperBuilder {
+        self.parent = Some(p.into());
+        self
+    }
+
+    pub fn zoom(mut self, zoom: u32) -> EyedropperBuilder {
+        self.zoom = zoom;
+        self
+    }
+
+    pub fn loupe_size(mut self, size: u32) -> EyedropperBuilder {
+        self.loupe_size = size;
+        self
+    }
+
+    pub fn build(self, out: &mut Eyedropper) -> Result<(), NwgError> {
+        let parent = match self.parent {
+            Some(p) => match p.hwnd() {
+                Some(handle) => Ok(handle),
+                None => Err(NwgError::control_create("Wrong parent type"))
+            },
+            None => Err(NwgError::no_parent("Eyedropper"))
+        }?;
+
+        *out = Default::default();
+
+        let size = self.loupe_size as i32;
+        out.loupe = ControlBase::build_hwnd()
+            .class_name(nwg_class("NativeWindowsGuiWindow"))
+            .flags(WS_POPUP | WS_BORDER)
+            .ex_flags(WS_EX_TOPMOST | WS_EX_TOOLWINDOW)
+            .size((size, size))
+            .position((0, 0))
+            .parent(None)
+            .build()?;
+
+        out.handle = build_notice(parent);
+
+        let (_, id) = out.handle.notice().unwrap();
+        out.state = Some(Rc::new(EyedropperState {
+            parent,
+            id,
+            zoom: self.zoom.max(1) as i32,
+            active: Cell::new(false),
+        }));
+
+        out.bind_loupe();
+
+        Ok(())
+    }
+
+}
+
+fn cursor_position() -> (i32, i32) {
+    use winapi::um::winuser::GetCursorPos;
+
+    let mut p = POINT { x: 0, y: 0 };
+    unsafe { GetCursorPos(&mut p); }
+    (p.x, p.y)
+}
+
+/// End the pick and send the result to the parent window
+fn finish(loupe: HWND, state: &EyedropperState, result: LPARAM) {
+    use winapi::um::winuser::{KillTimer, ReleaseCapture, ShowWindow, SW_HIDE};
+
+    // Cleared first because `ReleaseCapture` sends `WM_CAPTURECHANGED` to the loupe
+    state.active.set(false);
+
+    unsafe {
+        KillTimer(loupe, EYEDROPPER_ID);
+        ReleaseCapture();
+        ShowWindow(loupe, SW_HIDE);
+    }
+
+    wh::post_message(state.parent, wh::NWG_COLOR_PICKED, state.id as WPARAM, result);
+}
+
+/// Move the loupe next to the cursor. The loupe is moved to the other side of the cursor near the edges of the monitor.
+unsafe fn move_loupe(loupe: HWND) {
+    use winapi::um::winuser::{MonitorFromPoint, GetMonitorInfoW, MONITORINFO, MONITOR_DEFAULTTONEAREST, InvalidateRect,
+        SetWindowPos, GetWindowRect, SWP_NOSIZE, SWP_NOACTIVATE, SWP_NOZORDER};
+
+    let (x, y) = cursor_position();
+
+    let mut rect: RECT = mem::zeroed();
+    GetWindowRect(loupe, &mut rect);
+    let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+
+    let mut info: MONITORINFO = mem::zeroed();
+    info.cbSize = mem::size_of::<MONITORINFO>() as u32;
+    GetMonitorInfoW(MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONEAREST), &mut info);
+    let work = info.rcWork;
+
+    let mut loupe_x = x + LOUPE_OFFSET;
+    if loupe_x + width > work.right {
+        loupe_x = x - LOUPE_OFFSET - width;
+    }
+
+    let mut loupe_y = y + LOUPE_OFFSET;
+    if loupe_y + height > work.bottom {
+        loupe_y = y - LOUPE_OFFSET - height;
+    }
+
+    SetWindowPos(loupe, ptr::null_mut(), loupe_x, loupe_y, 0, 0, SWP_NOSIZE | SWP_NOACTIVATE | SWP_NOZORDER);
+    InvalidateRect(loupe, ptr::null(), 0);
+}
+
+/// Draw the magnified pixels under the cursor and a frame around the pixel that will be picked
+unsafe fn paint_loupe(loupe: HWND, zoom: i32) {
+    use winapi::um::winuser::{BeginPaint, EndPaint, GetClientRect, GetDC, ReleaseDC, FrameRect, PAINTSTRUCT};
+    use winapi::um::wingdi::{StretchBlt, GetStockObject, SRCCOPY, BLACK_BRUSH};
+
+    let mut paint: PAINTSTRUCT = mem::zeroed();
+    let dc: HDC = BeginPaint(loupe, &mut paint);
+
+    let mut client: RECT = mem::zeroed();
+    GetClientRect(loupe, &mut client);
+
+    // Always sample an odd number of pixels so that the picked pixel is in the center
+    let pixels = (client.right / zoom) | 1;
+    let (x, y) = cursor_position();
+
+    let screen = GetDC(ptr::null_mut());
+    StretchBlt(dc, 0, 0, pixels * zoom, pixels * zoom, screen, x - pixels / 2, y - pixels / 2, pixels, pixels, SRCCOPY);
+    ReleaseDC(ptr::null_mut(), screen);
+
+    let center = (pixels / 2) * zoom;
+    let frame = RECT { left: center - 1, top: center - 1, right: center + zoom + 1, bottom: center + zoom + 1 };
+    FrameRect(dc, &frame, GetStockObject(BLACK_BRUSH as i32) as _);
+
+    EndPaint(loupe, &paint);
+}
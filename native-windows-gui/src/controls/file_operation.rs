@@ -0,0 +1,292 @@
+use winapi::shared::minwindef::{UINT, WORD, WPARAM, LPARAM};
+use winapi::um::shellapi::{FOF_ALLOWUNDO, FOF_NOCONFIRMATION, FOF_NOCONFIRMMKDIR, FOF_RENAMEONCOLLISION, FOF_SILENT, FOF_NOERRORUI,
+  FOF_FILESONLY, FOF_SIMPLEPROGRESS, FOF_WANTNUKEWARNING, FO_COPY, FO_MOVE, FO_DELETE, FO_RENAME};
+use super::control_handle::ControlHandle;
+use crate::win32::{window_helper as wh, window::build_notice};
+use crate::NwgError;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+use std::cell::Cell;
+use std::thread;
+
+const NOT_BOUND: &'static str = "FileOperation is not yet bound to a winapi object";
+const BAD_HANDLE: &'static str = "INTERNAL ERROR: FileOperation handle is not Notice!";
+
+/// Sent in the lparam of `NWG_FILE_OPERATION_DONE` when the operation was aborted by the user
+pub(crate) const FILE_OPERATION_ABORTED: LPARAM = -1;
+
+
+bitflags! {
+    /**
+        The file operation flags
+
+        * NONE:                No flags
+        * ALLOW_UNDO:          Register the operation in the undo history of the shell. Deleted files are moved to the recycle bin.
+        * NO_CONFIRMATION:     Answer "Yes to all" to every confirmation dialog
+        * NO_CONFIRM_MKDIR:    Do not ask for confirmation before creating a missing destination folder
+        * RENAME_ON_COLLISION: Give a new name to the files that already exist in the destination (ex: "file - Copy.txt")
+        * SILENT:              Do not display the progress dialog
+        * NO_ERROR_UI:         Do not display the errors to the user
+        * FILES_ONLY:          Only process the files when a wildcard is used (ex: "*.*")
+        * SIMPLE_PROGRESS:     Display a progress dialog without the file names
+        * WANT_NUKE_WARNING:   Warn the user if the files are deleted permanently instead of moved to the recycle bin
+    */
+    pub struct FileOperationFlags: u32 {
+        const NONE = 0;
+        const ALLOW_UNDO = FOF_ALLOWUNDO as u32;
+        const NO_CONFIRMATION = FOF_NOCONFIRMATION as u32;
+        const NO_CONFIRM_MKDIR = FOF_NOCONFIRMMKDIR as u32;
+        const RENAME_ON_COLLISION = FOF_RENAMEONCOLLISION as u32;
+        const SILENT = FOF_SILENT as u32;
+        const NO_ERROR_UI = FOF_NOERRORUI as u32;
+        const FILES_ONLY = FOF_FILESONLY as u32;
+        const SIMPLE_PROGRESS = FOF_SIMPLEPROGRESS as u32;
+        const WANT_NUKE_WARNING = FOF_WANTNUKEWARNING as u32;
+    }
+}
+
+/**
+An invisible component that copies, moves, deletes and renames files with the Windows shell, and raises `OnFileOperationComplete` when done.
+
+Operations behave like in the Windows explorer: the standard progress dialog, confirmation dialogs and file conflict dialogs are displayed
+(unless disabled with the flags). With the `ALLOW_UNDO` flag (the default), deleted files are moved to the recycle bin and the operation
+can be undone from the explorer.
+
+Each operation runs in its own thread, so the GUI is not blocked. The result of the operation is sent with the event and can be read
+with `EventData::on_file_operation_complete`. Relative paths start at the current directory.
+
+A file operation must have a parent window. The parent window is the owner of the dialogs.
+
+Requires the `file-operation` feature.
+
+**Builder parameters:**
+    * `parent`:     **Required.** The file operation parent container that will receive the events. Should be a top level window
+    * `flags`:      A combination of the FileOperationFlags values. Defaults to `ALLOW_UNDO`
+
+**Control events:**
+    * `OnFileOperationComplete`: When an operation is done
+
+## Example
+
+```rust
+use native_windows_gui as nwg;
+
+fn build_file_operation(op: &mut nwg::FileOperation, window: &nwg::Window) {
+    nwg::FileOperation::builder()
+        .flags(nwg::FileOperationFlags::ALLOW_UNDO | nwg::FileOperationFlags::RENAME_ON_COLLISION)
+        .parent(window)
+        .build(op);
+}
+
+fn backup(op: &nwg::FileOperation) {
+    op.copy(&["C:\\data\\report.docx", "C:\\data\\budget.xlsx"], "D:\\backup").unwrap();
+}
+
+fn on_event(evt: nwg::Event, evt_data: &nwg::EventData) {
+    if evt == nwg::Event::OnFileOperationComplete {
+        match evt_data.on_file_operation_complete() {
+            nwg::FileOperationResult::Completed => println!("Done"),
+            nwg::FileOperationResult::Aborted => println!("Cancelled"),
+            nwg::FileOperationResult::Failed(code) => println!("Error {}", code),
+        }
+    }
+}
+```
+*/
+pub struct FileOperation {
+    pub handle: ControlHandle,
+    flags: Cell<u32>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl FileOperation {
+
+    pub fn builder() -> FileOperationBuilder {
+        FileOperationBuilder {
+            parent: None,
+            flags: None,
+        }
+    }
+
+    /// Return the flags used by the next operations
+    pub fn flags(&self) -> FileOperationFlags {
+        FileOperationFlags::from_bits_truncate(self.flags.get())
+    }
+
+    /// Set the flags used by the next operations
+    pub fn set_flags(&self, flags: FileOperationFlags) {
+        self.flags.set(flags.bits());
+    }
+
+    /// Return the number of operations that are not completed yet
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    /// Copy the files or folders of `from` into the folder `to`. The folder is created if it does not exist.
+    pub fn copy<P1: AsRef<Path>, P2: AsRef<Path>>(&self, from: &[P1], to: P2) -> Result<(), NwgError> {
+        self.start(FO_COPY, from, Some(to.as_ref()))
+    }
+
+    /// Move the files or folders of `from` into the folder `to`. The folder is created if it does not exist.
+    pub fn move_to<P1: AsRef<Path>, P2: AsRef<Path>>(&self, from: &[P1], to: P2) -> Result<(), NwgError> {
+        self.start(FO_MOVE, from, Some(to.as_ref()))
+    }
+
+    /// Delete the files or folders of `paths`. They are moved to the recycle bin if the `ALLOW_UNDO` flag is set.
+    pub fn delete<P: AsRef<Path>>(&self, paths: &[P]) -> Result<(), NwgError> {
+        self.start(FO_DELETE, paths, None)
+    }
+
+    /// Rename the file or folder `from` to `to`. `to` can be a file name or a full path in the same folder.
+    pub fn rename<P1: AsRef<Path>, P2: AsRef<Path>>(&self, from: P1, to: P2) -> Result<(), NwgError> {
+        let from = from.as_ref();
+        let to = to.as_ref();
+        let to = match to.parent() {
+            Some(p) if p.as_os_str().len() > 0 => to.to_path_buf(),
+            _ => from.with_file_name(to),
+        };
+
+        self.start(FO_RENAME, &[from], Some(to.as_path()))
+    }
+
+    fn start<P: AsRef<Path>>(&self, func: WORD, from: &[P], to: Option<&Path>) -> Result<(), NwgError> {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        let (hwnd, id) = self.handle.notice().expect(BAD_HANDLE);
+
+        if from.is_empty() {
+            return Err(NwgError::control_create("FileOperation requires at least one source path"));
+        }
+
+        let from_paths: Vec<PathBuf> = from.iter().map(|p| absolute(p.as_ref())).collect();
+        let from = path_list(&from_paths);
+        let to = to.map(|p| path_list(&[absolute(p)]));
+        let flags = self.flags.get() as WORD;
+        let pending = self.pending.clone();
+        let hwnd = hwnd as usize;
+
+        pending.fetch_add(1, Ordering::SeqCst);
+
+        let started = thread::Builder::new()
+            .name("nwg-file-operation".to_string())
+            .spawn(move || {
+                let result = unsafe { run_operation(hwnd as _, func, &from, to.as_ref().map(|t| t.as_slice()), flags) };
+                pending.fetch_sub(1, Ordering::SeqCst);
+                wh::post_message(hwnd as _, wh::NWG_FILE_OPERATION_DONE, id as WPARAM, result);
+            });
+
+        if started.is_err() {
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+            return Err(NwgError::control_create("Failed to start the file operation thread"));
+        }
+
+        Ok(())
+    }
+
+}
+
+impl Default for FileOperation {
+    fn default() -> FileOperation {
+        FileOperation {
+            handle: ControlHandle::NoHandle,
+            flags: Cell::new(FileOperationFlags::ALLOW_UNDO.bits()),
+            pending: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+impl PartialEq for FileOperation {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for FileOperation {}
+
+impl Drop for FileOperation {
+    fn drop(&mut self) {
+        self.handle.destroy();
+    }
+}
+
+pub struct FileOperationBuilder {
+    parent: Option<ControlHandle>,
+    flags: Option<FileOperationFlags>,
+}
+
+impl FileOperationBuilder {
+
+    pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> FileOperationBuilder {
+        self.parent = Some(p.into());
+        self
+    }
+
+    pub fn flags(mut self, flags: FileOperationFlags) -> FileOperationBuilder {
+        self.flags = Some(flags);
+        self
+    }
+
+    pub fn build(self, out: &mut FileOperation) -> Result<(), NwgError> {
+        let parent = match self.parent {
+            Some(p) => match p.hwnd() {
+                Some(handle) => Ok(handle),
+                None => Err(NwgError::control_create("Wrong parent type"))
+            },
+            None => Err(NwgError::no_parent("FileOperation"))
+        }?;
+
+        *out = Default::default();
+        out.handle = build_notice(parent);
+
+        if let Some(flags) = self.flags {
+            out.set_flags(flags);
+        }
+
+        Ok(())
+    }
+
+}
+
+/// Run the operation and return the lparam sent with `NWG_FILE_OPERATION_DONE`
+unsafe fn run_operation(hwnd: winapi::shared::windef::HWND, func: WORD, from: &[u16], to: Option<&[u16]>, flags: WORD) -> LPARAM {
+    use winapi::um::shellapi::{SHFILEOPSTRUCTW, SHFileOperationW};
+    use std::ptr;
+
+    let mut op = SHFILEOPSTRUCTW {
+        hwnd,
+        wFunc: func as UINT,
+        pFrom: from.as_ptr(),
+        pTo: to.map(|t| t.as_ptr()).unwrap_or(ptr::null()),
+        fFlags: flags,
+        fAnyOperationsAborted: 0,
+        hNameMappings: ptr::null_mut(),
+        lpszProgressTitle: ptr::null(),
+    };
+
+    match SHFileOperationW(&mut op) {
+        0 if op.fAnyOperationsAborted != 0 => FILE_OPERATION_ABORTED,
+        code => code as LPARAM
+    }
+}
+
+/// Make `path` absolute. The shell does not accept relative paths.
+fn absolute(path: &Path) -> PathBuf {
+    match path.is_absolute() {
+        true => path.to_path_buf(),
+        false => std::env::current_dir().map(|d| d.join(path)).unwrap_or(path.to_path_buf())
+    }
+}
+
+/// Encode a list of paths as a double null terminated string
+fn path_list(paths: &[PathBuf]) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+
+    let mut list = Vec::new();
+    for path in paths {
+        list.extend(path.as_os_str().encode_wide());
+        list.push(0);
+    }
+
+    list.push(0);
+    list
+}
@@ -0,0 +1,647 @@
+use winapi::shared::windef::HWND;
+use winapi::um::winuser::WS_EX_CONTROLPARENT;
+use std::cell::{Cell, RefCell};
+use std::path::{Path, PathBuf, Component};
+use std::fs;
+
+use crate::win32::window_helper as wh;
+use crate::win32::paths::{format_file_size_kb, format_file_time};
+use crate::{NwgError, PartialUi, Event, EventData};
+use super::{ControlHandle, Frame, FrameFlags, TreeView, TreeItem, TreeInsert, TreeItemAction, ExpandState, ListView, ListViewStyle,
+  ListViewFlags, ListViewExFlags, ListViewColumnFlags, InsertListViewColumn, TextInput, Button, ButtonFlags};
+
+const BREADCRUMB_HEIGHT: i32 = 25;
+const FILENAME_HEIGHT: i32 = 25;
+const SPACING: i32 = 5;
+
+/// Text of the placeholder child added to folders that were not loaded yet
+const PLACEHOLDER: &'static str = "";
+
+
+/// An item displayed in the file list
+struct FileEntry {
+    name: String,
+    folder: bool,
+}
+
+
+/**
+A FilePicker is a ready-made `PartialUi` to pick files inside a window, without opening a modal `FileDialog`.
+It is made of a path breadcrumb, a tree of the folders of the computer, a list of the files in the selected folder (with their size,
+modification date and type) and a box to type a file name.
+
+The controls are children of `frame`. Position `frame` like any other control (ex: in a `GridLayout`); the inner controls are resized with it.
+Like any partial, the parent UI must forward its events to `process_event`.
+
+When the user double clicks a file or presses enter in the file name box, the picker raises `OnFilePicked` on `frame`.
+`selected_file` then returns the path of the file. Typing the path of a folder in the file name box opens the folder.
+
+`set_filter` limits the files displayed to a list of wildcard patterns (ex: "*.txt;*.rs"). Folders are always displayed.
+
+Requires the `file-picker` feature.
+
+```rust
+use native_windows_gui as nwg;
+
+#[derive(Default)]
+pub struct OpenPanel {
+    picker: nwg::FilePicker,
+}
+
+fn build_panel(data: &mut OpenPanel, window: &nwg::Window) -> Result<(), nwg::NwgError> {
+    use nwg::PartialUi;
+
+    nwg::FilePicker::build_partial(&mut data.picker, Some(window))?;
+    data.picker.set_filter("*.txt;*.md");
+    data.picker.frame.set_size(600, 400);
+    Ok(())
+}
+
+fn on_event(data: &OpenPanel, evt: nwg::Event, evt_data: &nwg::EventData, handle: nwg::ControlHandle) {
+    use nwg::PartialUi;
+
+    data.picker.process_event(evt, evt_data, handle);
+    if evt == nwg::Event::OnFilePicked && handle == data.picker.frame.handle {
+        println!("{:?}", data.picker.selected_file());
+    }
+}
+```
+*/
+#[derive(Default)]
+pub struct FilePicker {
+    pub frame: Frame,
+    pub folders: TreeView,
+    pub files: ListView,
+    pub filename: TextInput,
+    breadcrumb: RefCell<Vec<(Button, PathBuf)>>,
+    breadcrumb_len: Cell<usize>,
+    folder: RefCell<Option<PathBuf>>,
+    entries: RefCell<Vec<FileEntry>>,
+    filter: RefCell<Vec<String>>,
+    tree_width: Cell<Option<u32>>,
+}
+
+impl FilePicker {
+
+    /// Return the folder displayed in the file list
+    pub fn folder(&self) -> Option<PathBuf> {
+        self.folder.borrow().clone()
+    }
+
+    /**
+        Display the content of `path` and select it in the folder tree. Relative paths start at the current directory.
+        Returns an error if the folder cannot be read.
+    */
+    pub fn set_folder<P: AsRef<Path>>(&self, path: P) -> Result<(), NwgError> {
+        let path = normalize(path.as_ref());
+        self.show_folder(&path)?;
+
+        if let Some(item) = self.expand_to(&path) {
+            self.folders.select_item(&item);
+            self.folders.ensure_visible(&item);
+        }
+
+        Ok(())
+    }
+
+    /// Reload the content of the current folder
+    pub fn refresh(&self) {
+        if let Some(folder) = self.folder() {
+            if self.show_folder(&folder).is_err() {
+                self.clear_files();
+            }
+        }
+    }
+
+    /// Return the file name typed or selected by the user, joined to the current folder.
+    /// Returns `None` if the file name box is empty.
+    pub fn selected_file(&self) -> Option<PathBuf> {
+        let name = self.filename.text();
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let folder = self.folder().unwrap_or_default();
+        Some(folder.join(name))
+    }
+
+    /// Return the patterns used to filter the files, separated by ';'
+    pub fn filter(&self) -> String {
+        self.filter.borrow().join(";")
+    }
+
+    /**
+        Only display the files that match one of the wildcard patterns of `filter`. The patterns are separated by ';'
+        and support `*` and `?` (ex: "*.txt;report_??.csv"). An empty filter displays every file.
+    */
+    pub fn set_filter(&self, filter: &str) {
+        *self.filter.borrow_mut() = filter.split(';')
+            .map(|p| p.trim().to_lowercase())
+            .filter(|p| !p.is_empty())
+            .collect();
+
+        self.refresh();
+    }
+
+    /// Return the width of the folder tree. `None` if the tree takes a third of the picker.
+    pub fn tree_width(&self) -> Option<u32> {
+        self.tree_width.get()
+    }
+
+    /// Set the width of the folder tree. `None` to use a third of the picker.
+    pub fn set_tree_width(&self, width: Option<u32>) {
+        self.tree_width.set(width);
+        self.layout();
+    }
+
+    /// Resize the inner controls to fit `frame`. Called automatically when `frame` is resized.
+    pub fn layout(&self) {
+        if self.frame.handle.blank() {
+            return;
+        }
+
+        let (w, h) = self.frame.size();
+        let (w, h) = (w as i32, h as i32);
+
+        self.layout_breadcrumb(w);
+
+        let body_y = BREADCRUMB_HEIGHT + SPACING;
+        let body_h = i32::max(h - body_y - SPACING - FILENAME_HEIGHT, 0);
+        let tree_w = match self.tree_width.get() {
+            Some(tw) => i32::min(tw as i32, w),
+            None => w / 3,
+        };
+        let files_x = tree_w + SPACING;
+
+        self.folders.set_position(0, body_y);
+        self.folders.set_size(tree_w as u32, body_h as u32);
+        self.files.set_position(files_x, body_y);
+        self.files.set_size(i32::max(w - files_x, 0) as u32, body_h as u32);
+        self.filename.set_position(0, body_y + body_h + SPACING);
+        self.filename.set_size(w as u32, FILENAME_HEIGHT as u32);
+    }
+
+    fn show_folder(&self, path: &Path) -> Result<(), NwgError> {
+        let read = fs::read_dir(path)
+            .map_err(|e| NwgError::file_dialog(format!("Failed to read {:?}: {}", path, e)))?;
+
+        let mut entries: Vec<(FileEntry, Option<fs::Metadata>)> = Vec::new();
+        {
+            let filter = self.filter.borrow();
+            for entry in read.filter_map(|e| e.ok()) {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let meta = entry.metadata().ok();
+                let folder = meta.as_ref().map(|m| m.is_dir()).unwrap_or(false);
+                if !folder && !filter.is_empty() && !filter.iter().any(|p| wildcard_match(p, &name.to_lowercase())) {
+                    continue;
+                }
+
+                entries.push((FileEntry { name, folder }, meta));
+            }
+        }
+
+        entries.sort_by(|(a, _), (b, _)| {
+            b.folder.cmp(&a.folder).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        });
+
+        self.files.set_redraw(false);
+        self.files.clear();
+
+        for (entry, meta) in entries.iter() {
+            let size = match (entry.folder, meta) {
+                (false, Some(m)) => format_file_size_kb(m.len()),
+                _ => String::new(),
+            };
+
+            let modified = meta.as_ref()
+                .and_then(|m| m.modified().ok())
+                .map(format_file_time)
+                .unwrap_or_default();
+
+            let kind = match entry.folder {
+                true => "Folder".to_string(),
+                false => match Path::new(&entry.name).extension() {
+                    Some(ext) => format!("{} File", ext.to_string_lossy().to_uppercase()),
+                    None => "File".to_string(),
+                }
+            };
+
+            self.files.insert_items_row(None, &[entry.name.clone(), size, modified, kind]);
+        }
+
+        self.files.set_redraw(true);
+
+        *self.entries.borrow_mut() = entries.into_iter().map(|(e, _)| e).collect();
+        *self.folder.borrow_mut() = Some(path.to_path_buf());
+
+        self.update_breadcrumb(path);
+
+        Ok(())
+    }
+
+    fn clear_files(&self) {
+        self.files.clear();
+        self.entries.borrow_mut().clear();
+    }
+
+    /// Open the folder or pick the file at `row` of the file list
+    fn activate_row(&self, row: usize) {
+        let entry = self.entries.borrow().get(row).map(|e| (e.name.clone(), e.folder));
+        let folder = self.folder();
+
+        match (entry, folder) {
+            (Some((name, true)), Some(folder)) => { self.set_folder(folder.join(name)).ok(); },
+            (Some((name, false)), Some(_)) => {
+                self.filename.set_text(&name);
+                self.pick();
+            },
+            _ => {}
+        }
+    }
+
+    /// Open the folder or pick the file typed in the file name box
+    fn activate_filename(&self) {
+        let path = match self.selected_file() {
+            Some(p) => p,
+            None => { return; }
+        };
+
+        if path.is_dir() {
+            if self.set_folder(&path).is_ok() {
+                self.filename.set_text("");
+            }
+        } else {
+            self.pick();
+        }
+    }
+
+    fn pick(&self) {
+        if let Some(hwnd) = self.frame.handle.hwnd() {
+            wh::post_message(hwnd, wh::NWG_FILE_PICKED, 0, 0);
+        }
+    }
+
+    //
+    // Folder tree
+    //
+
+    fn add_folder_item(&self, name: &str, parent: Option<&TreeItem>) {
+        let position = match parent.is_some() {
+            true => TreeInsert::Last,
+            false => TreeInsert::Root,
+        };
+
+        let item = self.folders.insert_item(name, parent, position);
+        self.folders.insert_item(PLACEHOLDER, Some(&item), TreeInsert::Last);
+    }
+
+    /// Replace the placeholder child of `item` by its sub folders
+    fn load_children(&self, item: &TreeItem) {
+        let placeholder = match self.folders.first_child(item) {
+            Some(child) if self.folders.item_text(&child).as_ref().map(|t| t.as_str()) == Some(PLACEHOLDER) => child,
+            _ => { return; }
+        };
+
+        self.folders.remove_item(&placeholder);
+
+        let path = match self.item_path(item) {
+            Some(p) => p,
+            None => { return; }
+        };
+
+        let mut names: Vec<String> = match fs::read_dir(&path) {
+            Ok(read) => read.filter_map(|e| e.ok())
+                .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .collect(),
+            Err(_) => Vec::new()
+        };
+
+        names.sort_by_key(|n| n.to_lowercase());
+
+        for name in names.iter() {
+            self.add_folder_item(name, Some(item));
+        }
+    }
+
+    /// Rebuild the path of a tree item from the text of its parents
+    fn item_path(&self, item: &TreeItem) -> Option<PathBuf> {
+        let mut names = Vec::new();
+        let mut current = Some(TreeItem { handle: item.handle });
+        while let Some(i) = current {
+            names.push(self.folders.item_text(&i)?);
+            current = self.folders.parent(&i);
+        }
+
+        Some(names.iter().rev().collect())
+    }
+
+    fn find_child(&self, parent: Option<&TreeItem>, name: &str) -> Option<TreeItem> {
+        let name = name.to_lowercase();
+        let mut current = match parent {
+            Some(p) => self.folders.first_child(p),
+            None => self.folders.root(),
+        };
+
+        while let Some(item) = current {
+            if self.folders.item_text(&item).map(|t| t.to_lowercase()) == Some(name.clone()) {
+                return Some(item);
+            }
+
+            current = self.folders.next_sibling(&item);
+        }
+
+        None
+    }
+
+    /// Expand the folder tree up to `path` and return the item of the folder
+    fn expand_to(&self, path: &Path) -> Option<TreeItem> {
+        let mut item: Option<TreeItem> = None;
+
+        for component in path.components() {
+            let name = match component {
+                Component::Prefix(p) => format!("{}\\", p.as_os_str().to_string_lossy()),
+                Component::Normal(n) => n.to_string_lossy().into_owned(),
+                _ => { continue; }
+            };
+
+            if let Some(parent) = item.as_ref() {
+                self.load_children(parent);
+                self.folders.set_expand_state(parent, ExpandState::Expand);
+            }
+
+            item = Some(self.find_child(item.as_ref(), &name)?);
+        }
+
+        item
+    }
+
+    //
+    // Breadcrumb
+    //
+
+    /// Display one button per folder of `path`. Buttons are reused because one of them may be the source of the current event.
+    fn update_breadcrumb(&self, path: &Path) {
+        let mut folders: Vec<PathBuf> = path.ancestors().map(|p| p.to_path_buf()).collect();
+        folders.reverse();
+
+        let mut breadcrumb = self.breadcrumb.borrow_mut();
+        for (index, folder) in folders.into_iter().enumerate() {
+            let text = match folder.file_name() {
+                Some(name) => name.to_string_lossy().into_owned(),
+                None => folder.to_string_lossy().trim_end_matches('\\').to_string(),
+            };
+
+            if index >= breadcrumb.len() {
+                let mut button = Button::default();
+                let built = Button::builder()
+                    .text(&text)
+                    .flags(ButtonFlags::TAB_STOP)
+                    .parent(&self.frame)
+                    .build(&mut button);
+
+                if built.is_err() {
+                    break;
+                }
+
+                breadcrumb.push((button, folder));
+            } else {
+                breadcrumb[index].0.set_text(&text);
+                breadcrumb[index].1 = folder;
+            }
+
+            self.breadcrumb_len.set(index + 1);
+        }
+
+        drop(breadcrumb);
+
+        let (w, _) = self.frame.size();
+        self.layout_breadcrumb(w as i32);
+    }
+
+    /// Place the breadcrumb buttons. The first folders are hidden if the path does not fit.
+    fn layout_breadcrumb(&self, width: i32) {
+        let breadcrumb = self.breadcrumb.borrow();
+        let len = self.breadcrumb_len.get();
+
+        let widths: Vec<i32> = breadcrumb.iter().take(len)
+            .map(|(b, _)| text_width(b.handle.hwnd().unwrap(), &b.text()) + 16)
+            .collect();
+
+        let mut first = len;
+        let mut total = 0;
+        while first > 0 && (first == len || total + widths[first - 1] <= width) {
+            total += widths[first - 1] + 2;
+            first -= 1;
+        }
+
+        let mut x = 0;
+        for (index, (button, _)) in breadcrumb.iter().enumerate() {
+            let visible = index >= first && index < len;
+            button.set_visible(visible);
+
+            if visible {
+                button.set_position(x, 0);
+                button.set_size(widths[index] as u32, BREADCRUMB_HEIGHT as u32);
+                x += widths[index] + 2;
+            }
+        }
+    }
+
+    fn breadcrumb_folder(&self, handle: ControlHandle) -> Option<PathBuf> {
+        let breadcrumb = self.breadcrumb.borrow();
+        breadcrumb.iter()
+            .take(self.breadcrumb_len.get())
+            .find(|(b, _)| b.handle == handle)
+            .map(|(_, f)| f.clone())
+    }
+
+}
+
+impl PartialUi for FilePicker {
+
+    fn build_partial<W: Into<ControlHandle>>(data: &mut Self, parent: Option<W>) -> Result<(), NwgError> {
+        use winapi::um::commctrl::TVS_EDITLABELS;
+
+        let parent = parent.ok_or(NwgError::no_parent("FilePicker"))?;
+
+        Frame::builder()
+            .flags(FrameFlags::VISIBLE)
+            .ex_flags(WS_EX_CONTROLPARENT)
+            .size((600, 400))
+            .parent(parent)
+            .build(&mut data.frame)?;
+
+        TreeView::builder()
+            .parent(&data.frame)
+            .build(&mut data.folders)?;
+
+        // Folder names cannot be edited in the tree
+        let tree_handle = data.folders.handle.hwnd().unwrap();
+        wh::set_style(tree_handle, wh::get_style(tree_handle) & !TVS_EDITLABELS);
+
+        ListView::builder()
+            .list_style(ListViewStyle::Detailed)
+            .flags(ListViewFlags::VISIBLE | ListViewFlags::TAB_STOP | ListViewFlags::SINGLE_SELECTION | ListViewFlags::ALWAYS_SHOW_SELECTION)
+            .ex_flags(ListViewExFlags::FULL_ROW_SELECT)
+            .parent(&data.frame)
+            .build(&mut data.files)?;
+
+        let columns = [("Name", 220, ListViewColumnFlags::LEFT), ("Size", 80, ListViewColumnFlags::RIGHT), ("Modified", 120, ListViewColumnFlags::LEFT), ("Type", 90, ListViewColumnFlags::LEFT)];
+        for &(text, width, fmt) in columns.iter() {
+            data.files.insert_column(InsertListViewColumn {
+                index: None,
+                fmt: Some(fmt),
+                width: Some(width),
+                text: Some(text.to_string()),
+            });
+        }
+
+        TextInput::builder()
+            .parent(&data.frame)
+            .build(&mut data.filename)?;
+
+        for drive in logical_drives() {
+            data.add_folder_item(&drive, None);
+        }
+
+        data.layout();
+
+        if let Ok(dir) = std::env::current_dir() {
+            data.set_folder(dir).ok();
+        }
+
+        Ok(())
+    }
+
+    fn process_event(&self, evt: Event, evt_data: &EventData, handle: ControlHandle) {
+        match evt {
+            Event::OnResize if handle == self.frame.handle => {
+                self.layout();
+            },
+            Event::OnTreeItemExpanded if handle == self.folders.handle => {
+                if let (item, TreeItemAction::Expand(ExpandState::Expand)) = evt_data.on_tree_item_update() {
+                    self.load_children(item);
+                }
+            },
+            Event::OnTreeItemSelectionChanged if handle == self.folders.handle => {
+                let (_, new) = evt_data.on_tree_item_selection_changed();
+                let path = self.item_path(new);
+                if path.is_some() && path != self.folder() {
+                    if self.show_folder(path.as_ref().unwrap()).is_err() {
+                        self.clear_files();
+                    }
+                }
+            },
+            Event::OnListViewItemChanged if handle == self.files.handle => {
+                let (row, _, selected) = evt_data.on_list_view_item_changed();
+                let entries = self.entries.borrow();
+                match entries.get(row) {
+                    Some(entry) if selected && !entry.folder => self.filename.set_text(&entry.name),
+                    _ => {}
+                }
+            },
+            Event::OnListViewItemActivated if handle == self.files.handle => {
+                let (row, _) = evt_data.on_list_view_item_index();
+                self.activate_row(row);
+            },
+            Event::OnButtonClick => {
+                if let Some(folder) = self.breadcrumb_folder(handle) {
+                    self.set_folder(folder).ok();
+                }
+            },
+            Event::OnKeyEnter if self.filename.focus() => {
+                self.activate_filename();
+            },
+            _ => {}
+        }
+    }
+
+}
+
+
+/// Make `path` absolute and remove the `.` and `..` components
+fn normalize(path: &Path) -> PathBuf {
+    let path = match path.is_absolute() {
+        true => path.to_path_buf(),
+        false => std::env::current_dir().map(|d| d.join(path)).unwrap_or(path.to_path_buf()),
+    };
+
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => { out.pop(); },
+            c => out.push(c.as_os_str()),
+        }
+    }
+
+    out
+}
+
+/// Match `name` against a pattern that may contain `*` (any number of characters) and `?` (exactly one character)
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((sp, sn)) = star {
+            p = sp + 1;
+            n = sn + 1;
+            star = Some((sp, sn + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Return the root of every drive of the computer (ex: "C:\")
+fn logical_drives() -> Vec<String> {
+    use winapi::um::fileapi::GetLogicalDriveStringsW;
+
+    let mut buffer = [0u16; 256];
+    let len = unsafe { GetLogicalDriveStringsW(buffer.len() as u32, buffer.as_mut_ptr()) } as usize;
+    if len == 0 || len > buffer.len() {
+        return Vec::new();
+    }
+
+    buffer[..len].split(|&c| c == 0)
+        .filter(|d| !d.is_empty())
+        .map(String::from_utf16_lossy)
+        .collect()
+}
+
+/// Return the logical width of `text` drawn with the font of `hwnd`
+fn text_width(hwnd: HWND, text: &str) -> i32 {
+    use winapi::um::winuser::{GetDC, ReleaseDC, WM_GETFONT};
+    use winapi::um::wingdi::{SelectObject, GetTextExtentPoint32W};
+    use winapi::shared::windef::{SIZE, HFONT};
+    use crate::win32::base_helper::to_utf16;
+    use crate::win32::high_dpi::physical_to_logical_for;
+
+    let text = to_utf16(text);
+    let mut size = SIZE { cx: 0, cy: 0 };
+
+    unsafe {
+        let font = wh::send_message(hwnd, WM_GETFONT, 0, 0) as HFONT;
+        let dc = GetDC(hwnd);
+        let old = SelectObject(dc, font as _);
+        GetTextExtentPoint32W(dc, text.as_ptr(), (text.len() - 1) as i32, &mut size);
+        SelectObject(dc, old);
+        ReleaseDC(hwnd, dc);
+
+        physical_to_logical_for(hwnd, size.cx, size.cy).0
+    }
+}
@@ -1,12 +1,24 @@
-use winapi::um::winuser::{WS_VISIBLE, WS_DISABLED, WS_BORDER, WS_CHILD, WS_CLIPCHILDREN, WS_EX_CONTROLPARENT};
+use winapi::um::winuser::{WS_VISIBLE, WS_DISABLED, WS_BORDER, WS_CHILD, WS_CLIPCHILDREN, WS_EX_CONTROLPARENT, WS_EX_CLIENTEDGE, WS_EX_DLGMODALFRAME};
+use winapi::shared::windef::{HWND, HBRUSH, HBITMAP, RECT};
 use crate::win32::window_helper as wh;
+use crate::win32::window::nwg_class;
 use crate::win32::base_helper::check_hwnd;
-use crate::NwgError;
+use crate::{NwgError, Bitmap, BackgroundMode, RawEventHandler, unbind_raw_event_handler};
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::{mem, ptr};
 
 const NOT_BOUND: &'static str = "Frame is not yet bound to a winapi object";
 const BAD_HANDLE: &'static str = "INTERNAL ERROR: Frame handle is not HWND!";
 
+/// Id of the raw event handler that implements the frame border, background, padding and scrolling
+const FRAME_HANDLER_ID: usize = 0x4652;
+
+/// Distance scrolled by the scrollbar arrows and by one notch of the mouse wheel, in logical pixels
+const SCROLL_LINE: i32 = 20;
+
 
 bitflags! {
     /**
@@ -15,7 +27,7 @@ bitflags! {
         * NONE:     No flags. Equivalent to a invisible frame without borders.
         * VISIBLE:  The frame is immediatly visible after creation
         * DISABLED: The frame chidlren cannot be interacted with by the user.
-        * BORDER:   The frame has a thin black border
+        * BORDER:   The frame has a thin black border. Same as `FrameBorder::Simple`.
     */
     pub struct FrameFlags: u32 {
         const NONE = 0;
@@ -25,27 +37,100 @@ bitflags! {
     }
 }
 
+/// The border style of a frame
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameBorder {
+    /// No border
+    None,
+
+    /// A thin black border
+    Simple,
+
+    /// A sunken 3D border
+    Sunken,
+
+    /// A raised 3D border
+    Raised,
+
+    /// A thin border that uses the color of the current visual style (the same border as a text input)
+    Themed,
+}
+
+/// State shared between the frame and its event handler
+struct FrameState {
+    border: Cell<FrameBorder>,
+    background: Cell<Option<[u8; 3]>>,
+    brush: Cell<HBRUSH>,
+    bitmap: Cell<Option<(HBITMAP, BackgroundMode)>>,
+    padding: Cell<[u32; 4]>,
+    scrollable: Cell<bool>,
+    offset: Cell<(i32, i32)>,
+}
+
+impl Default for FrameState {
+    fn default() -> FrameState {
+        FrameState {
+            border: Cell::new(FrameBorder::Simple),
+            background: Cell::new(None),
+            brush: Cell::new(ptr::null_mut()),
+            bitmap: Cell::new(None),
+            padding: Cell::new([0, 0, 0, 0]),
+            scrollable: Cell::new(false),
+            offset: Cell::new((0, 0)),
+        }
+    }
+}
+
 /**
 A frame is a rectangle containing children controls. Frame is implemented as a custom control.
 
-Requires the `frame` feature. 
+The padding of a frame is excluded from its client area. Because layouts use the client area of their parent, the children of a frame
+are positioned inside the padding without any special configuration.
+
+If the frame is scrollable, scrollbars are displayed when its children exceed the client area. The scroll range is updated when the frame is
+resized. Call `update_scroll` after moving, resizing, adding or removing children.
+
+Requires the `frame` feature.
 
 **Builder parameters:**
-  * `parent`:   **Required.** The frame parent container.
-  * `size`:     The frame size.
-  * `position`: The frame position.
-  * `enabled`:  If the frame children can be used by the user.
-  * `flags`:    A combination of the FrameFlags values.
-  * `ex_flags`: A combination of win32 window extended flags. Unlike `flags`, ex_flags must be used straight from winapi
+  * `parent`:           **Required.** The frame parent container.
+  * `size`:             The frame size.
+  * `position`:         The frame position.
+  * `enabled`:          If the frame children can be used by the user.
+  * `flags`:            A combination of the FrameFlags values.
+  * `ex_flags`:         A combination of win32 window extended flags. Unlike `flags`, ex_flags must be used straight from winapi
+  * `border`:           The border style of the frame. Overrides the `BORDER` flag.
+  * `background_color`: The background color of the frame. Defaults to the window background color.
+  * `background_bitmap`: A bitmap drawn in the background of the frame and the mode used to draw it. See `Frame::set_background_bitmap`.
+  * `padding`:          The space between the border and the children, in this order: top, right, bottom, left.
+  * `scrollable`:       If the frame displays scrollbars when its children exceed the client area.
 
 **Control events:**
   * `MousePress(_)`: Generic mouse press events on the button
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
+
+```rust
+use native_windows_gui as nwg;
+
+fn build_frame(frame: &mut nwg::Frame, window: &nwg::Window) {
+    nwg::Frame::builder()
+        .border(nwg::FrameBorder::Themed)
+        .background_color(Some([240, 240, 250]))
+        .padding([10, 10, 10, 10])
+        .scrollable(true)
+        .parent(window)
+        .build(frame);
+}
+```
 */
-#[derive(Default, PartialEq, Eq)]
 pub struct Frame {
-    pub handle: ControlHandle
+    pub handle: ControlHandle,
+    state: Rc<FrameState>,
+    handler0: RefCell<Option<RawEventHandler>>,
 }
 
 impl Frame {
@@ -57,7 +142,14 @@ impl Frame {
             enabled: true,
             flags: None,
             ex_flags: 0,
+            border: None,
+            background_color: None,
+            background_bitmap: None,
+            padding: [0, 0, 0, 0],
+            scrollable: false,
             parent: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -85,7 +177,7 @@ impl Frame {
         unsafe { wh::set_window_enabled(handle, v) }
     }
 
-    /// Returns true if the control is visible to the user. Will return true even if the 
+    /// Returns true if the control is visible to the user. Will return true even if the
     /// control is outside of the parent client view (ex: at the position (10000, 10000))
     pub fn visible(&self) -> bool {
         let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
@@ -122,9 +214,133 @@ impl Frame {
         unsafe { wh::set_window_position(handle, x, y) }
     }
 
+    /// Returns the border style of the frame
+    pub fn border(&self) -> FrameBorder {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.border.get()
+    }
+
+    /// Sets the border style of the frame
+    pub fn set_border(&self, border: FrameBorder) {
+        use winapi::um::winuser::GWL_EXSTYLE;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.border.set(border);
+
+        let (style, ex_style) = border_styles(border);
+        let old_style = wh::get_style(handle) & !WS_BORDER;
+        let old_ex_style = wh::get_window_long(handle, GWL_EXSTYLE) as u32 & !(WS_EX_CLIENTEDGE | WS_EX_DLGMODALFRAME);
+
+        wh::set_style(handle, old_style | style);
+        wh::set_window_long(handle, GWL_EXSTYLE, (old_ex_style | ex_style) as usize);
+
+        unsafe { frame_changed(handle); }
+    }
+
+    /// Returns the background color of the frame, or `None` if the frame uses the default background
+    pub fn background_color(&self) -> Option<[u8; 3]> {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.background.get()
+    }
+
+    /// Sets the background color of the frame. `None` restores the default background.
+    pub fn set_background_color(&self, color: Option<[u8; 3]>) {
+        use winapi::um::wingdi::{CreateSolidBrush, DeleteObject, RGB};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let old = self.state.brush.replace(ptr::null_mut());
+        if !old.is_null() {
+            unsafe { DeleteObject(old as _); }
+        }
+
+        if let Some(c) = color {
+            self.state.brush.set(unsafe { CreateSolidBrush(RGB(c[0], c[1], c[2])) });
+        }
+
+        self.state.background.set(color);
+
+        unsafe { redraw(handle); }
+    }
+
+    /// Returns the mode of the background bitmap, or `None` if the frame does not have a background bitmap
+    pub fn background_bitmap_mode(&self) -> Option<BackgroundMode> {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.bitmap.get().map(|(_, mode)| mode)
+    }
+
+    /**
+        Sets a bitmap drawn in the background of the frame, behind the children controls. `None` removes the background bitmap.
+        The area not covered by the bitmap is filled with the background color.
+
+        The bitmap is not copied, it must not be freed while the frame uses it.
+    */
+    pub fn set_background_bitmap(&self, bitmap: Option<&Bitmap>, mode: BackgroundMode) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.bitmap.set(bitmap.map(|b| (b.handle as HBITMAP, mode)));
+        unsafe { redraw(handle); }
+    }
+
+    /// Returns the padding of the frame, in this order: top, right, bottom, left.
+    pub fn padding(&self) -> [u32; 4] {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.padding.get()
+    }
+
+    /// Sets the padding of the frame, in this order: top, right, bottom, left.
+    /// The layouts of the frame are updated because the client area is resized.
+    pub fn set_padding(&self, padding: [u32; 4]) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.padding.set(padding);
+        unsafe { frame_changed(handle); }
+    }
+
+    /// Returns true if the frame displays scrollbars when its children exceed the client area
+    pub fn scrollable(&self) -> bool {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.scrollable.get()
+    }
+
+    /// Enables or disables the scrollbars of the frame. Disabling the scrollbars scrolls back to the origin.
+    pub fn set_scrollable(&self, scrollable: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.scrollable.set(scrollable);
+        unsafe { update_scroll(handle, &self.state); }
+    }
+
+    /// Updates the scrollbars of the frame. Must be called when the children of the frame are moved, resized, added or removed.
+    pub fn update_scroll(&self) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { update_scroll(handle, &self.state); }
+    }
+
+    /// Returns the scroll position of the frame, in logical pixels
+    pub fn scroll_position(&self) -> (i32, i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let (x, y) = self.state.offset.get();
+        unsafe { crate::win32::high_dpi::physical_to_logical_for(handle, x, y) }
+    }
+
+    /// Scrolls the frame to a position, in logical pixels. The position is clamped to the scroll range.
+    pub fn set_scroll_position(&self, x: i32, y: i32) {
+        use winapi::um::winuser::{SB_HORZ, SB_VERT};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        if !self.state.scrollable.get() {
+            return;
+        }
+
+        let (x, y) = unsafe { crate::win32::high_dpi::logical_to_physical_for(handle, x, y) };
+        unsafe {
+            let x = clamp_scroll(handle, SB_HORZ as i32, x);
+            let y = clamp_scroll(handle, SB_VERT as i32, y);
+            scroll_to(handle, &self.state, x, y);
+        }
+    }
+
     /// Winapi class name used during control creation
     pub fn class_name(&self) -> &'static str {
-        "NWG_FRAME"
+        nwg_class("NWG_FRAME")
     }
 
     /// Winapi base flags used during window creation
@@ -137,10 +353,132 @@ impl Frame {
         WS_CHILD | WS_CLIPCHILDREN
     }
 
+    /// Hook the messages that implement the background, the padding, the themed border and the scrollbars
+    fn hook_frame(&self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::{WM_NCCALCSIZE, WM_NCPAINT, WM_ERASEBKGND, WM_SIZE, WM_HSCROLL, WM_VSCROLL, WM_MOUSEWHEEL,
+          SB_HORZ, SB_VERT, NCCALCSIZE_PARAMS, GET_WHEEL_DELTA_WPARAM, COLOR_WINDOW, GetClientRect, FillRect, InvalidateRect};
+        use winapi::um::commctrl::DefSubclassProc;
+        use winapi::shared::minwindef::{LOWORD, LRESULT};
+        use winapi::shared::windef::HDC;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let state = self.state.clone();
+
+        let handler = bind_raw_event_handler_inner(&self.handle, FRAME_HANDLER_ID, move |hwnd, msg, w, l| {
+            unsafe {
+                match msg {
+                    WM_NCCALCSIZE => {
+                        // The default size includes the border and the scrollbars
+                        let result = DefSubclassProc(hwnd, msg, w, l);
+                        let rect = match w == 0 {
+                            true => &mut *(l as *mut RECT),
+                            false => &mut (*(l as *mut NCCALCSIZE_PARAMS)).rgrc[0]
+                        };
+
+                        let [top, right, bottom, left] = physical_padding(hwnd, &state);
+                        rect.top += top;
+                        rect.right = (rect.right - right).max(rect.left);
+                        rect.bottom = (rect.bottom - bottom).max(rect.top);
+                        rect.left = (rect.left + left).min(rect.right);
+
+                        Some(result)
+                    },
+                    WM_NCPAINT => {
+                        DefSubclassProc(hwnd, msg, w, l);
+                        paint_non_client(hwnd, &state);
+                        Some(0)
+                    },
+                    WM_ERASEBKGND => {
+                        let brush = state.brush.get();
+                        if let Some((bitmap, mode)) = state.bitmap.get() {
+                            let fill = if brush.is_null() { COLOR_WINDOW as HBRUSH } else { brush };
+                            wh::draw_background_bitmap(hwnd, w as HDC, bitmap, mode, fill);
+                            return Some(1);
+                        }
+
+                        if brush.is_null() {
+                            return None;
+                        }
+
+                        let mut r: RECT = mem::zeroed();
+                        GetClientRect(hwnd, &mut r);
+                        FillRect(w as HDC, &r, brush);
+                        Some(1)
+                    },
+                    WM_SIZE => {
+                        update_scroll(hwnd, &state);
+
+                        // The position or the size of the background bitmap depends on the client area
+                        if state.bitmap.get().map(|(_, mode)| mode != BackgroundMode::Tile).unwrap_or(false) {
+                            InvalidateRect(hwnd, ptr::null(), 1);
+                        }
+
+                        None
+                    },
+                    WM_HSCROLL if l == 0 => {
+                        scroll_event(hwnd, &state, SB_HORZ as i32, LOWORD(w as u32) as isize);
+                        Some(0)
+                    },
+                    WM_VSCROLL if l == 0 => {
+                        scroll_event(hwnd, &state, SB_VERT as i32, LOWORD(w as u32) as isize);
+                        Some(0)
+                    },
+                    WM_MOUSEWHEEL if state.scrollable.get() => {
+                        let (_, line) = crate::win32::high_dpi::logical_to_physical_for(hwnd, 0, SCROLL_LINE);
+                        let delta = GET_WHEEL_DELTA_WPARAM(w) as i32;
+                        let (x, y) = state.offset.get();
+                        let y = clamp_scroll(hwnd, SB_VERT as i32, y - (delta * line * 3) / 120);
+                        scroll_to(hwnd, &state, x, y);
+                        Some(0 as LRESULT)
+                    },
+                    _ => None
+                }
+            }
+        });
+
+        *self.handler0.borrow_mut() = Some(handler.unwrap());
+
+        unsafe {
+            frame_changed(handle);
+            update_scroll(handle, &self.state);
+        }
+    }
+
+}
+
+impl Default for Frame {
+    fn default() -> Frame {
+        Frame {
+            handle: ControlHandle::NoHandle,
+            state: Default::default(),
+            handler0: RefCell::new(None),
+        }
+    }
+}
+
+impl PartialEq for Frame {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
 }
 
+impl Eq for Frame {}
+
 impl Drop for Frame {
     fn drop(&mut self) {
+        use winapi::um::wingdi::DeleteObject;
+
+        let handler = self.handler0.borrow();
+        if let Some(h) = handler.as_ref() {
+            drop(unbind_raw_event_handler(h));
+        }
+
+        let brush = self.state.brush.replace(ptr::null_mut());
+        if !brush.is_null() {
+            unsafe { DeleteObject(brush as _); }
+        }
+
         self.handle.destroy();
     }
 }
@@ -150,7 +488,14 @@ pub struct FrameBuilder {
     enabled: bool,
     flags: Option<FrameFlags>,
     ex_flags: u32,
-    parent: Option<ControlHandle>
+    border: Option<FrameBorder>,
+    background_color: Option<[u8; 3]>,
+    background_bitmap: Option<(HBITMAP, BackgroundMode)>,
+    padding: [u32; 4],
+    scrollable: bool,
+    parent: Option<ControlHandle>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl FrameBuilder {
@@ -180,34 +525,311 @@ impl FrameBuilder {
         self
     }
 
+    pub fn border(mut self, border: FrameBorder) -> FrameBuilder {
+        self.border = Some(border);
+        self
+    }
+
+    pub fn background_color(mut self, color: Option<[u8; 3]>) -> FrameBuilder {
+        self.background_color = color;
+        self
+    }
+
+    pub fn background_bitmap(mut self, bitmap: Option<&Bitmap>, mode: BackgroundMode) -> FrameBuilder {
+        self.background_bitmap = bitmap.map(|b| (b.handle as HBITMAP, mode));
+        self
+    }
+
+    pub fn padding(mut self, padding: [u32; 4]) -> FrameBuilder {
+        self.padding = padding;
+        self
+    }
+
+    pub fn scrollable(mut self, scrollable: bool) -> FrameBuilder {
+        self.scrollable = scrollable;
+        self
+    }
+
     pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> FrameBuilder {
         self.parent = Some(p.into());
         self
     }
 
+    pub fn name(mut self, name: &str) -> FrameBuilder {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut Frame) -> Result<(), NwgError> {
-        let flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
 
         let parent = match self.parent {
             Some(p) => Ok(p),
             None => Err(NwgError::no_parent("Frame"))
         }?;
 
+        let border = match self.border {
+            Some(b) => b,
+            None if flags & WS_BORDER == WS_BORDER => FrameBorder::Simple,
+            None => FrameBorder::None
+        };
+
+        let (border_style, border_ex_style) = border_styles(border);
+
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
-            .flags(flags)
-            .ex_flags(WS_EX_CONTROLPARENT | self.ex_flags)
+            .flags((flags & !WS_BORDER) | border_style)
+            .ex_flags(WS_EX_CONTROLPARENT | border_ex_style | self.ex_flags)
             .size(self.size)
             .position(self.position)
             .parent(Some(parent))
             .build()?;
 
+        out.state.border.set(border);
+        out.state.bitmap.set(self.background_bitmap);
+        out.state.padding.set(self.padding);
+        out.state.scrollable.set(self.scrollable);
+
+        if self.background_color.is_some() {
+            out.set_background_color(self.background_color);
+        }
+
+        out.hook_frame();
         out.set_enabled(self.enabled);
 
         Ok(())
     }
 
 }
+
+/// Return the window style and the extended window style of a border
+fn border_styles(border: FrameBorder) -> (u32, u32) {
+    match border {
+        FrameBorder::None => (0, 0),
+        FrameBorder::Simple => (WS_BORDER, 0),
+        FrameBorder::Sunken | FrameBorder::Themed => (0, WS_EX_CLIENTEDGE),
+        FrameBorder::Raised => (0, WS_EX_DLGMODALFRAME),
+    }
+}
+
+/// Return the padding in physical pixels, in this order: top, right, bottom, left
+fn physical_padding(hwnd: HWND, state: &FrameState) -> [i32; 4] {
+    use crate::win32::high_dpi::logical_to_physical_for;
+
+    let [top, right, bottom, left] = state.padding.get();
+    let (left, top) = unsafe { logical_to_physical_for(hwnd, left as i32, top as i32) };
+    let (right, bottom) = unsafe { logical_to_physical_for(hwnd, right as i32, bottom as i32) };
+
+    [top, right, bottom, left]
+}
+
+/// Recompute the non client area of the frame
+unsafe fn frame_changed(hwnd: HWND) {
+    use winapi::um::winuser::{SetWindowPos, SWP_NOOWNERZORDER, SWP_NOSIZE, SWP_NOMOVE, SWP_NOZORDER, SWP_FRAMECHANGED};
+    SetWindowPos(hwnd, ptr::null_mut(), 0, 0, 0, 0, SWP_NOOWNERZORDER | SWP_NOZORDER | SWP_NOSIZE | SWP_NOMOVE | SWP_FRAMECHANGED);
+}
+
+/// Redraw the frame, including the non client area
+unsafe fn redraw(hwnd: HWND) {
+    use winapi::um::winuser::{RedrawWindow, RDW_INVALIDATE, RDW_ERASE, RDW_FRAME};
+    RedrawWindow(hwnd, ptr::null(), ptr::null_mut(), RDW_INVALIDATE | RDW_ERASE | RDW_FRAME);
+}
+
+/// Paint the padding and the themed border. The other borders and the scrollbars are painted by the system.
+unsafe fn paint_non_client(hwnd: HWND, state: &FrameState) {
+    use winapi::um::winuser::{GetWindowDC, ReleaseDC, GetWindowRect, GetClientRect, ClientToScreen, FillRect, FrameRect, GetSystemMetrics, SM_CXEDGE, SM_CYEDGE, COLOR_WINDOW};
+    use winapi::um::wingdi::{ExcludeClipRect, CreateSolidBrush, DeleteObject};
+    use winapi::shared::windef::POINT;
+
+    let brush = match state.brush.get().is_null() {
+        true => COLOR_WINDOW as HBRUSH,
+        false => state.brush.get()
+    };
+
+    let mut window: RECT = mem::zeroed();
+    let mut client: RECT = mem::zeroed();
+    GetWindowRect(hwnd, &mut window);
+    GetClientRect(hwnd, &mut client);
+
+    // Client area in window coordinates
+    let mut origin = POINT { x: 0, y: 0 };
+    ClientToScreen(hwnd, &mut origin);
+    let client = RECT {
+        left: origin.x - window.left,
+        top: origin.y - window.top,
+        right: origin.x - window.left + client.right,
+        bottom: origin.y - window.top + client.bottom,
+    };
+
+    let [top, right, bottom, left] = physical_padding(hwnd, state);
+    let padded = RECT { left: client.left - left, top: client.top - top, right: client.right + right, bottom: client.bottom + bottom };
+
+    let dc = GetWindowDC(hwnd);
+
+    ExcludeClipRect(dc, client.left, client.top, client.right, client.bottom);
+    FillRect(dc, &padded, brush);
+
+    if state.border.get() == FrameBorder::Themed {
+        let outer = RECT { left: 0, top: 0, right: window.right - window.left, bottom: window.bottom - window.top };
+        let (edge_x, edge_y) = (GetSystemMetrics(SM_CXEDGE), GetSystemMetrics(SM_CYEDGE));
+        ExcludeClipRect(dc, outer.left + edge_x, outer.top + edge_y, outer.right - edge_x, outer.bottom - edge_y);
+        FillRect(dc, &outer, brush);
+
+        let border_brush = CreateSolidBrush(themed_border_color(hwnd));
+        FrameRect(dc, &outer, border_brush);
+        DeleteObject(border_brush as _);
+    }
+
+    ReleaseDC(hwnd, dc);
+}
+
+/// Return the border color of the text inputs in the current visual style
+unsafe fn themed_border_color(hwnd: HWND) -> u32 {
+    use winapi::um::uxtheme::{OpenThemeData, CloseThemeData, GetThemeColor};
+    use winapi::um::winuser::{GetSysColor, COLOR_WINDOWFRAME};
+    use winapi::shared::winerror::S_OK;
+    use crate::win32::base_helper::to_utf16;
+
+    const EP_EDITTEXT: i32 = 1;
+    const ETS_NORMAL: i32 = 1;
+    const TMT_BORDERCOLOR: i32 = 3801;
+
+    let class = to_utf16("EDIT");
+    let theme = OpenThemeData(hwnd, class.as_ptr());
+    if theme.is_null() {
+        return GetSysColor(COLOR_WINDOWFRAME);
+    }
+
+    let mut color = 0;
+    let result = GetThemeColor(theme, EP_EDITTEXT, ETS_NORMAL, TMT_BORDERCOLOR, &mut color);
+    CloseThemeData(theme);
+
+    match result {
+        S_OK => color,
+        _ => GetSysColor(COLOR_WINDOWFRAME)
+    }
+}
+
+/// Update the scroll range from the size of the children
+unsafe fn update_scroll(hwnd: HWND, state: &FrameState) {
+    use winapi::um::winuser::{GetWindow, GetWindowRect, GetClientRect, MapWindowPoints, IsWindowVisible, ShowScrollBar, SetScrollInfo,
+      SCROLLINFO, SIF_RANGE, SIF_PAGE, SB_HORZ, SB_VERT, SB_BOTH, GW_CHILD, GW_HWNDNEXT};
+    use winapi::shared::windef::POINT;
+
+    if !state.scrollable.get() {
+        let (x, y) = state.offset.get();
+        if x != 0 || y != 0 {
+            scroll_to(hwnd, state, 0, 0);
+        }
+
+        ShowScrollBar(hwnd, SB_BOTH as i32, 0);
+        return;
+    }
+
+    // The size of the content is the bottom right corner of the children at the scroll origin
+    let (offset_x, offset_y) = state.offset.get();
+    let (mut width, mut height) = (0, 0);
+    let mut child = GetWindow(hwnd, GW_CHILD);
+    while !child.is_null() {
+        if IsWindowVisible(child) != 0 {
+            let mut r: RECT = mem::zeroed();
+            GetWindowRect(child, &mut r);
+            MapWindowPoints(ptr::null_mut(), hwnd, &mut r as *mut RECT as *mut POINT, 2);
+            width = width.max(r.right + offset_x);
+            height = height.max(r.bottom + offset_y);
+        }
+
+        child = GetWindow(child, GW_HWNDNEXT);
+    }
+
+    let mut client: RECT = mem::zeroed();
+    GetClientRect(hwnd, &mut client);
+
+    for &(bar, content, page) in [(SB_HORZ, width, client.right), (SB_VERT, height, client.bottom)].iter() {
+        let mut si: SCROLLINFO = mem::zeroed();
+        si.cbSize = mem::size_of::<SCROLLINFO>() as u32;
+        si.fMask = SIF_RANGE | SIF_PAGE;
+        si.nMin = 0;
+        si.nMax = (content - 1).max(0);
+        si.nPage = page.max(0) as u32;
+        SetScrollInfo(hwnd, bar as i32, &si, 1);
+    }
+
+    // The range might have shrunk below the current position
+    let (x, y) = state.offset.get();
+    let x = clamp_scroll(hwnd, SB_HORZ as i32, x);
+    let y = clamp_scroll(hwnd, SB_VERT as i32, y);
+    scroll_to(hwnd, state, x, y);
+}
+
+/// Clamp a scroll position to the range of a scrollbar
+unsafe fn clamp_scroll(hwnd: HWND, bar: i32, pos: i32) -> i32 {
+    use winapi::um::winuser::{GetScrollInfo, SCROLLINFO, SIF_RANGE, SIF_PAGE};
+
+    let mut si: SCROLLINFO = mem::zeroed();
+    si.cbSize = mem::size_of::<SCROLLINFO>() as u32;
+    si.fMask = SIF_RANGE | SIF_PAGE;
+    if GetScrollInfo(hwnd, bar, &mut si) == 0 {
+        return 0;
+    }
+
+    let max = (si.nMax - si.nPage as i32 + 1).max(si.nMin);
+    pos.max(si.nMin).min(max)
+}
+
+/// Move the children of the frame to a new scroll position
+unsafe fn scroll_to(hwnd: HWND, state: &FrameState, x: i32, y: i32) {
+    use winapi::um::winuser::{ScrollWindowEx, SetScrollPos, InvalidateRect, SB_HORZ, SB_VERT, SW_SCROLLCHILDREN, SW_INVALIDATE, SW_ERASE};
+
+    let (old_x, old_y) = state.offset.get();
+    if old_x == x && old_y == y {
+        return;
+    }
+
+    state.offset.set((x, y));
+    ScrollWindowEx(hwnd, old_x - x, old_y - y, ptr::null(), ptr::null(), ptr::null_mut(), ptr::null_mut(), SW_SCROLLCHILDREN | SW_INVALIDATE | SW_ERASE);
+
+    // The background bitmap does not move with the children
+    if state.bitmap.get().is_some() {
+        InvalidateRect(hwnd, ptr::null(), 1);
+    }
+
+    if state.scrollable.get() {
+        SetScrollPos(hwnd, SB_HORZ as i32, x, 1);
+        SetScrollPos(hwnd, SB_VERT as i32, y, 1);
+    }
+}
+
+/// Handle a WM_HSCROLL or a WM_VSCROLL message sent by the scrollbars of the frame
+unsafe fn scroll_event(hwnd: HWND, state: &FrameState, bar: i32, event: isize) {
+    use winapi::um::winuser::{GetScrollInfo, SCROLLINFO, SIF_ALL, SB_HORZ, SB_TOP, SB_BOTTOM, SB_LINEUP, SB_LINEDOWN,
+      SB_PAGEUP, SB_PAGEDOWN, SB_THUMBTRACK, SB_THUMBPOSITION};
+
+    let mut si: SCROLLINFO = mem::zeroed();
+    si.cbSize = mem::size_of::<SCROLLINFO>() as u32;
+    si.fMask = SIF_ALL;
+    GetScrollInfo(hwnd, bar, &mut si);
+
+    let (_, line) = crate::win32::high_dpi::logical_to_physical_for(hwnd, 0, SCROLL_LINE);
+    let pos = match event {
+        SB_TOP => si.nMin,
+        SB_BOTTOM => si.nMax,
+        SB_LINEUP => si.nPos - line,
+        SB_LINEDOWN => si.nPos + line,
+        SB_PAGEUP => si.nPos - si.nPage as i32,
+        SB_PAGEDOWN => si.nPos + si.nPage as i32,
+        SB_THUMBTRACK | SB_THUMBPOSITION => si.nTrackPos,
+        _ => { return; }
+    };
+
+    let pos = clamp_scroll(hwnd, bar, pos);
+    let (x, y) = state.offset.get();
+    match bar == SB_HORZ as i32 {
+        true => scroll_to(hwnd, state, pos, y),
+        false => scroll_to(hwnd, state, x, pos),
+    }
+}
@@ -0,0 +1,641 @@
+use winapi::um::winuser::{WS_VISIBLE, WS_DISABLED, WS_CHILD, WS_CLIPCHILDREN, WS_EX_CONTROLPARENT};
+use winapi::shared::windef::{HWND, HFONT, RECT};
+use crate::win32::window_helper as wh;
+use crate::win32::window::nwg_class;
+use crate::win32::base_helper::check_hwnd;
+use crate::{Font, NwgError, RawEventHandler, unbind_raw_event_handler};
+use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::{mem, ptr};
+
+const NOT_BOUND: &'static str = "GroupBox is not yet bound to a winapi object";
+const BAD_HANDLE: &'static str = "INTERNAL ERROR: GroupBox handle is not HWND!";
+
+/// Id of the raw event handler that implements the group box title, border and insets
+const GROUP_BOX_HANDLER_ID: usize = 0x4742;
+
+/// Distance between the left side of the border and the title, in logical pixels
+const TITLE_OFFSET: i32 = 8;
+
+/// Space around the title text that interrupts the border, in logical pixels
+const TITLE_MARGIN: i32 = 2;
+
+/// Visual style part and states of the group boxes
+const BP_GROUPBOX: i32 = 4;
+const GBS_NORMAL: i32 = 1;
+const GBS_DISABLED: i32 = 2;
+
+/// Visual style property of the title color
+const TMT_TEXTCOLOR: i32 = 3803;
+
+
+bitflags! {
+    /**
+        The group box flags
+
+        * NONE:     No flags. Equivalent to a invisible group box.
+        * VISIBLE:  The group box is immediatly visible after creation
+        * DISABLED: The group box chidlren cannot be interacted with by the user. The title also has a grayed out look.
+    */
+    pub struct GroupBoxFlags: u32 {
+        const NONE = 0;
+        const VISIBLE = WS_VISIBLE;
+        const DISABLED = WS_DISABLED;
+    }
+}
+
+/// State shared between the group box and its event handler
+struct GroupBoxState {
+    font: Cell<HFONT>,
+    title_color: Cell<Option<[u8; 3]>>,
+    padding: Cell<[u32; 4]>,
+}
+
+impl Default for GroupBoxState {
+    fn default() -> GroupBoxState {
+        GroupBoxState {
+            font: Cell::new(ptr::null_mut()),
+            title_color: Cell::new(None),
+            padding: Cell::new([0, 0, 0, 0]),
+        }
+    }
+}
+
+/**
+A group box is a titled border around a group of related controls. Unlike the group box style of the system buttons,
+GroupBox is a container: the controls of the group are children of the group box. GroupBox is implemented as a custom control.
+
+The border, the title and the padding of the group box are excluded from its client area, and the top of the client area is
+moved down by the height of the title. Because layouts use the client area of their parent, a layout can be bound directly
+to the group box and its children are positioned inside the border without any special configuration.
+
+The border uses the current visual style. By default the title uses the font of the control and the title color of the visual style.
+
+Requires the `group-box` feature.
+
+**Builder parameters:**
+  * `parent`:           **Required.** The group box parent container.
+  * `text`:             The title of the group box. A `&` underlines the next character.
+  * `size`:             The group box size.
+  * `position`:         The group box position.
+  * `enabled`:          If the group box children can be used by the user.
+  * `flags`:            A combination of the GroupBoxFlags values.
+  * `ex_flags`:         A combination of win32 window extended flags. Unlike `flags`, ex_flags must be used straight from winapi
+  * `font`:             The font used for the title.
+  * `title_color`:      The color of the title. Defaults to the title color of the visual style.
+  * `padding`:          The space between the border and the children, in this order: top, right, bottom, left.
+
+**Control events:**
+  * `MousePress(_)`: Generic mouse press events on the group box
+  * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
+  * `OnMouseWheel`: Generic mouse wheel event
+
+```rust
+use native_windows_gui as nwg;
+
+fn build_group_box(group: &mut nwg::GroupBox, layout: &nwg::GridLayout, name: &nwg::TextInput, window: &nwg::Window, font: &nwg::Font) {
+    nwg::GroupBox::builder()
+        .text("&Identity")
+        .font(Some(font))
+        .title_color(Some([0, 51, 153]))
+        .padding([5, 5, 5, 5])
+        .parent(window)
+        .build(group);
+
+    nwg::GridLayout::builder()
+        .parent(group)
+        .child(0, 0, name)
+        .build(layout);
+}
+```
+*/
+pub struct GroupBox {
+    pub handle: ControlHandle,
+    state: Rc<GroupBoxState>,
+    handler0: RefCell<Option<RawEventHandler>>,
+}
+
+impl GroupBox {
+
+    pub fn builder<'a>() -> GroupBoxBuilder<'a> {
+        GroupBoxBuilder {
+            text: "",
+            size: (200, 100),
+            position: (0, 0),
+            enabled: true,
+            flags: None,
+            ex_flags: 0,
+            font: None,
+            title_color: None,
+            padding: [0, 0, 0, 0],
+            parent: None,
+            name: None,
+            state: Default::default(),
+        }
+    }
+
+    /// Return the font of the title
+    pub fn font(&self) -> Option<Font> {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let font_handle = wh::get_window_font(handle);
+        if font_handle.is_null() {
+            None
+        } else {
+            Some(Font { handle: font_handle })
+        }
+    }
+
+    /// Set the font of the title. The layouts of the group box are updated because the height of the title changes.
+    pub fn set_font(&self, font: Option<&Font>) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_font(handle, font.map(|f| f.handle), true); }
+    }
+
+    /// Return the title of the group box
+    pub fn text(&self) -> String {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_text(handle) }
+    }
+
+    /// Set the title of the group box
+    pub fn set_text<'a>(&self, v: &'a str) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_text(handle, v) }
+    }
+
+    /// Return the color of the title, or `None` if the title uses the color of the visual style
+    pub fn title_color(&self) -> Option<[u8; 3]> {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.title_color.get()
+    }
+
+    /// Set the color of the title. `None` restores the color of the visual style.
+    pub fn set_title_color(&self, color: Option<[u8; 3]>) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.title_color.set(color);
+        unsafe { redraw(handle); }
+    }
+
+    /// Returns the padding of the group box, in this order: top, right, bottom, left.
+    pub fn padding(&self) -> [u32; 4] {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.padding.get()
+    }
+
+    /// Sets the padding of the group box, in this order: top, right, bottom, left.
+    /// The layouts of the group box are updated because the client area is resized.
+    pub fn set_padding(&self, padding: [u32; 4]) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.padding.set(padding);
+        unsafe { frame_changed(handle); }
+    }
+
+    /// Returns true if the control user can interact with the control, return false otherwise
+    pub fn enabled(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_enabled(handle) }
+    }
+
+    /// Enable or disable the control. The title of a disabled group box is grayed out.
+    pub fn set_enabled(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe {
+            wh::set_window_enabled(handle, v);
+            redraw(handle);
+        }
+    }
+
+    /// Returns true if the control is visible to the user. Will return true even if the
+    /// control is outside of the parent client view (ex: at the position (10000, 10000))
+    pub fn visible(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_visibility(handle) }
+    }
+
+    /// Show or hide the control to the user
+    pub fn set_visible(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_visibility(handle, v) }
+    }
+
+    /// Returns the size of the group box in the parent window
+    pub fn size(&self) -> (u32, u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_size(handle) }
+    }
+
+    /// Sets the size of the group box in the parent window
+    pub fn set_size(&self, x: u32, y: u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_size(handle, x, y, false) }
+    }
+
+    /// Returns the position of the group box in the parent window
+    pub fn position(&self) -> (i32, i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_position(handle) }
+    }
+
+    /// Sets the position of the group box in the parent window
+    pub fn set_position(&self, x: i32, y: i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_position(handle, x, y) }
+    }
+
+    /// Winapi class name used during control creation
+    pub fn class_name(&self) -> &'static str {
+        nwg_class("NWG_FRAME")
+    }
+
+    /// Winapi base flags used during window creation
+    pub fn flags(&self) -> u32 {
+        WS_VISIBLE
+    }
+
+    /// Winapi flags required by the control
+    pub fn forced_flags(&self) -> u32 {
+        WS_CHILD | WS_CLIPCHILDREN
+    }
+
+    /// Hook the messages that implement the font, the title, the border and the client area insets
+    fn hook_group_box(&self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::{WM_NCCALCSIZE, WM_NCPAINT, WM_SETFONT, WM_GETFONT, WM_SETTEXT, WM_ENABLE, WM_SIZE, WM_THEMECHANGED, NCCALCSIZE_PARAMS};
+        use winapi::um::commctrl::DefSubclassProc;
+        use winapi::shared::minwindef::LRESULT;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let state = self.state.clone();
+
+        let handler = bind_raw_event_handler_inner(&self.handle, GROUP_BOX_HANDLER_ID, move |hwnd, msg, w, l| {
+            unsafe {
+                match msg {
+                    WM_NCCALCSIZE => {
+                        let result = DefSubclassProc(hwnd, msg, w, l);
+                        let rect = match w == 0 {
+                            true => &mut *(l as *mut RECT),
+                            false => &mut (*(l as *mut NCCALCSIZE_PARAMS)).rgrc[0]
+                        };
+
+                        let [top, right, bottom, left] = insets(hwnd, &state);
+                        rect.top += top;
+                        rect.right = (rect.right - right).max(rect.left);
+                        rect.bottom = (rect.bottom - bottom).max(rect.top);
+                        rect.left = (rect.left + left).min(rect.right);
+
+                        Some(result)
+                    },
+                    WM_NCPAINT => {
+                        DefSubclassProc(hwnd, msg, w, l);
+                        paint_non_client(hwnd, &state);
+                        Some(0)
+                    },
+                    WM_SETFONT => {
+                        state.font.set(w as HFONT);
+                        frame_changed(hwnd);
+                        if l != 0 {
+                            redraw(hwnd);
+                        }
+
+                        Some(0)
+                    },
+                    WM_GETFONT => {
+                        Some(state.font.get() as LRESULT)
+                    },
+                    WM_SETTEXT | WM_ENABLE => {
+                        let result = DefSubclassProc(hwnd, msg, w, l);
+                        redraw(hwnd);
+                        Some(result)
+                    },
+                    WM_SIZE | WM_THEMECHANGED => {
+                        redraw(hwnd);
+                        None
+                    },
+                    _ => None
+                }
+            }
+        });
+
+        *self.handler0.borrow_mut() = Some(handler.unwrap());
+
+        unsafe { frame_changed(handle); }
+    }
+
+}
+
+impl Default for GroupBox {
+    fn default() -> GroupBox {
+        GroupBox {
+            handle: ControlHandle::NoHandle,
+            state: Default::default(),
+            handler0: RefCell::new(None),
+        }
+    }
+}
+
+impl PartialEq for GroupBox {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for GroupBox {}
+
+impl Drop for GroupBox {
+    fn drop(&mut self) {
+        let handler = self.handler0.borrow();
+        if let Some(h) = handler.as_ref() {
+            drop(unbind_raw_event_handler(h));
+        }
+
+        self.handle.destroy();
+    }
+}
+
+pub struct GroupBoxBuilder<'a> {
+    text: &'a str,
+    size: (i32, i32),
+    position: (i32, i32),
+    enabled: bool,
+    flags: Option<GroupBoxFlags>,
+    ex_flags: u32,
+    font: Option<&'a Font>,
+    title_color: Option<[u8; 3]>,
+    padding: [u32; 4],
+    parent: Option<ControlHandle>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
+}
+
+impl<'a> GroupBoxBuilder<'a> {
+
+    pub fn flags(mut self, flags: GroupBoxFlags) -> GroupBoxBuilder<'a> {
+        self.flags = Some(flags);
+        self
+    }
+
+    pub fn ex_flags(mut self, flags: u32) -> GroupBoxBuilder<'a> {
+        self.ex_flags = flags;
+        self
+    }
+
+    pub fn text(mut self, text: &'a str) -> GroupBoxBuilder<'a> {
+        self.text = text;
+        self
+    }
+
+    pub fn size(mut self, size: (i32, i32)) -> GroupBoxBuilder<'a> {
+        self.size = size;
+        self
+    }
+
+    pub fn position(mut self, pos: (i32, i32)) -> GroupBoxBuilder<'a> {
+        self.position = pos;
+        self
+    }
+
+    pub fn enabled(mut self, e: bool) -> GroupBoxBuilder<'a> {
+        self.enabled = e;
+        self
+    }
+
+    pub fn font(mut self, font: Option<&'a Font>) -> GroupBoxBuilder<'a> {
+        self.font = font;
+        self
+    }
+
+    pub fn title_color(mut self, color: Option<[u8; 3]>) -> GroupBoxBuilder<'a> {
+        self.title_color = color;
+        self
+    }
+
+    pub fn padding(mut self, padding: [u32; 4]) -> GroupBoxBuilder<'a> {
+        self.padding = padding;
+        self
+    }
+
+    pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> GroupBoxBuilder<'a> {
+        self.parent = Some(p.into());
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> GroupBoxBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn build(self, out: &mut GroupBox) -> Result<(), NwgError> {
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
+
+        let parent = match self.parent {
+            Some(p) => Ok(p),
+            None => Err(NwgError::no_parent("GroupBox"))
+        }?;
+
+        *out = Default::default();
+
+        out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
+            .class_name(out.class_name())
+            .forced_flags(out.forced_flags())
+            .flags(flags)
+            .ex_flags(WS_EX_CONTROLPARENT | self.ex_flags)
+            .size(self.size)
+            .position(self.position)
+            .text(self.text)
+            .parent(Some(parent))
+            .build()?;
+
+        out.state.title_color.set(self.title_color);
+        out.state.padding.set(self.padding);
+
+        out.hook_group_box();
+
+        if self.font.is_some() {
+            out.set_font(self.font);
+        } else {
+            out.set_font(Font::global_default().as_ref());
+        }
+
+        out.set_enabled(self.enabled);
+
+        Ok(())
+    }
+
+}
+
+/// Return the font used to draw the title. The group boxes without a font use the default GUI font.
+unsafe fn title_font(state: &GroupBoxState) -> HFONT {
+    use winapi::um::wingdi::{GetStockObject, DEFAULT_GUI_FONT};
+
+    match state.font.get().is_null() {
+        true => GetStockObject(DEFAULT_GUI_FONT as i32) as HFONT,
+        false => state.font.get()
+    }
+}
+
+/// Return the height of the title in physical pixels
+unsafe fn title_height(hwnd: HWND, state: &GroupBoxState) -> i32 {
+    use winapi::um::winuser::{GetDC, ReleaseDC};
+    use winapi::um::wingdi::{SelectObject, GetTextMetricsW, TEXTMETRICW};
+
+    let dc = GetDC(hwnd);
+    let old = SelectObject(dc, title_font(state) as _);
+
+    let mut metrics: TEXTMETRICW = mem::zeroed();
+    GetTextMetricsW(dc, &mut metrics);
+
+    SelectObject(dc, old);
+    ReleaseDC(hwnd, dc);
+
+    metrics.tmHeight
+}
+
+/// Return the size of the border in physical pixels
+unsafe fn border_size() -> (i32, i32) {
+    use winapi::um::winuser::{GetSystemMetrics, SM_CXEDGE, SM_CYEDGE};
+    (GetSystemMetrics(SM_CXEDGE), GetSystemMetrics(SM_CYEDGE))
+}
+
+/// Return the space between the window rect and the client area in physical pixels, in this order: top, right, bottom, left
+unsafe fn insets(hwnd: HWND, state: &GroupBoxState) -> [i32; 4] {
+    use crate::win32::high_dpi::logical_to_physical_for;
+
+    let [top, right, bottom, left] = state.padding.get();
+    let (left, top) = logical_to_physical_for(hwnd, left as i32, top as i32);
+    let (right, bottom) = logical_to_physical_for(hwnd, right as i32, bottom as i32);
+    let (border_x, border_y) = border_size();
+
+    [title_height(hwnd, state).max(border_y) + top, border_x + right, border_y + bottom, border_x + left]
+}
+
+/// Recompute the non client area of the group box
+unsafe fn frame_changed(hwnd: HWND) {
+    use winapi::um::winuser::{SetWindowPos, SWP_NOOWNERZORDER, SWP_NOSIZE, SWP_NOMOVE, SWP_NOZORDER, SWP_FRAMECHANGED};
+    SetWindowPos(hwnd, ptr::null_mut(), 0, 0, 0, 0, SWP_NOOWNERZORDER | SWP_NOZORDER | SWP_NOSIZE | SWP_NOMOVE | SWP_FRAMECHANGED);
+}
+
+/// Redraw the group box, including the non client area
+unsafe fn redraw(hwnd: HWND) {
+    use winapi::um::winuser::{RedrawWindow, RDW_INVALIDATE, RDW_ERASE, RDW_FRAME};
+    RedrawWindow(hwnd, ptr::null(), ptr::null_mut(), RDW_INVALIDATE | RDW_ERASE | RDW_FRAME);
+}
+
+/// Paint the border, the title and the padding
+unsafe fn paint_non_client(hwnd: HWND, state: &GroupBoxState) {
+    use winapi::um::winuser::{GetWindowDC, ReleaseDC, GetWindowRect, GetClientRect, ClientToScreen, FillRect, DrawEdge, DrawTextW, IsWindowEnabled,
+      EDGE_ETCHED, BF_RECT, DT_SINGLELINE, DT_LEFT, DT_TOP, DT_CALCRECT, COLOR_WINDOW};
+    use winapi::um::uxtheme::{OpenThemeData, CloseThemeData, DrawThemeBackground};
+    use winapi::um::wingdi::{ExcludeClipRect, SaveDC, RestoreDC, SelectObject, SetTextColor, SetBkMode, TRANSPARENT, RGB};
+    use winapi::shared::windef::{POINT, HBRUSH};
+    use crate::win32::base_helper::to_utf16;
+    use crate::win32::high_dpi::logical_to_physical_for;
+
+    let mut window: RECT = mem::zeroed();
+    let mut client: RECT = mem::zeroed();
+    GetWindowRect(hwnd, &mut window);
+    GetClientRect(hwnd, &mut client);
+
+    // Client area in window coordinates
+    let mut origin = POINT { x: 0, y: 0 };
+    ClientToScreen(hwnd, &mut origin);
+    let client = RECT {
+        left: origin.x - window.left,
+        top: origin.y - window.top,
+        right: origin.x - window.left + client.right,
+        bottom: origin.y - window.top + client.bottom,
+    };
+
+    let outer = RECT { left: 0, top: 0, right: window.right - window.left, bottom: window.bottom - window.top };
+    let enabled = IsWindowEnabled(hwnd) != 0;
+    let dc = GetWindowDC(hwnd);
+
+    ExcludeClipRect(dc, client.left, client.top, client.right, client.bottom);
+    FillRect(dc, &outer, COLOR_WINDOW as HBRUSH);
+
+    // Measure the title
+    let font = title_font(state);
+    let old_font = SelectObject(dc, font as _);
+    let text = to_utf16(&wh::get_window_text(hwnd));
+    let (offset, margin) = logical_to_physical_for(hwnd, TITLE_OFFSET, TITLE_MARGIN);
+    let mut title = RECT { left: offset + margin, top: 0, right: offset + margin, bottom: 0 };
+    DrawTextW(dc, text.as_ptr(), -1, &mut title, DT_SINGLELINE | DT_LEFT | DT_TOP | DT_CALCRECT);
+    title.right = title.right.min(outer.right - offset - margin);
+
+    // The border goes through the middle of the title
+    let height = title_height(hwnd, state);
+    let mut border = RECT { left: 0, top: height / 2, right: outer.right, bottom: outer.bottom };
+
+    // The border is not drawn behind the title
+    let has_title = text.len() > 1;
+    SaveDC(dc);
+    if has_title {
+        ExcludeClipRect(dc, title.left - margin, 0, title.right + margin, height);
+    }
+
+    let class = to_utf16("BUTTON");
+    let theme = OpenThemeData(hwnd, class.as_ptr());
+    match theme.is_null() {
+        true => { DrawEdge(dc, &mut border, EDGE_ETCHED, BF_RECT); },
+        false => {
+            let part_state = if enabled { GBS_NORMAL } else { GBS_DISABLED };
+            DrawThemeBackground(theme, dc, BP_GROUPBOX, part_state, &border, ptr::null());
+        }
+    }
+
+    RestoreDC(dc, -1);
+
+    if has_title {
+        let [r, g, b] = title_color(theme, state, enabled);
+        SetTextColor(dc, RGB(r, g, b));
+        SetBkMode(dc, TRANSPARENT as i32);
+
+        DrawTextW(dc, text.as_ptr(), -1, &mut title, DT_SINGLELINE | DT_LEFT | DT_TOP);
+    }
+
+    if !theme.is_null() {
+        CloseThemeData(theme);
+    }
+
+    SelectObject(dc, old_font);
+    ReleaseDC(hwnd, dc);
+}
+
+/// Return the color of the title: the color set by the user, or the group box text color of the visual style
+unsafe fn title_color(theme: winapi::um::uxtheme::HTHEME, state: &GroupBoxState, enabled: bool) -> [u8; 3] {
+    use winapi::um::uxtheme::GetThemeColor;
+    use winapi::um::winuser::{GetSysColor, COLOR_WINDOWTEXT, COLOR_GRAYTEXT};
+    use winapi::um::wingdi::{GetRValue, GetGValue, GetBValue};
+    use winapi::shared::winerror::S_OK;
+
+    let to_rgb = |c: u32| [GetRValue(c), GetGValue(c), GetBValue(c)];
+
+    if !enabled {
+        return match theme.is_null() {
+            true => to_rgb(GetSysColor(COLOR_GRAYTEXT)),
+            false => {
+                let mut color = 0;
+                match GetThemeColor(theme, BP_GROUPBOX, GBS_DISABLED, TMT_TEXTCOLOR, &mut color) {
+                    S_OK => to_rgb(color),
+                    _ => to_rgb(GetSysColor(COLOR_GRAYTEXT))
+                }
+            }
+        };
+    }
+
+    if let Some(color) = state.title_color.get() {
+        return color;
+    }
+
+    let mut color = 0;
+    match !theme.is_null() && GetThemeColor(theme, BP_GROUPBOX, GBS_NORMAL, TMT_TEXTCOLOR, &mut color) == S_OK {
+        true => to_rgb(color),
+        false => to_rgb(GetSysColor(COLOR_WINDOWTEXT))
+    }
+}
@@ -1,4 +1,4 @@
-use super::{ControlHandle, Window, Button, CheckBox, RadioButton, TextInput, Label, ImageFrame};
+use super::{ControlHandle, Window, Button, SplitButton, CheckBox, RadioButton, TextInput, Label, ImageFrame};
 use std::convert::From;
 
 #[allow(unused)]
@@ -75,8 +75,49 @@ macro_rules! subclass_control {
     }
 }
 
+/**
+Implements the conversions to `ControlHandle` and the comparisons with `ControlHandle` for a custom control.
+The control must have a `handle: ControlHandle` field. See `CustomControl`.
+
+```rust
+#[macro_use] extern crate native_windows_gui as nwg;
+
+#[derive(Default)]
+pub struct Chart {
+    pub handle: nwg::ControlHandle,
+}
+
+custom_control_handle!(Chart);
+```
+*/
+#[macro_export]
+macro_rules! custom_control_handle {
+    ($ty:ident) => {
+        impl From<&$ty> for $crate::ControlHandle {
+            fn from(control: &$ty) -> Self { control.handle }
+        }
+
+        impl From<&mut $ty> for $crate::ControlHandle {
+            fn from(control: &mut $ty) -> Self { control.handle }
+        }
+
+        impl PartialEq<$crate::ControlHandle> for $ty {
+            fn eq(&self, other: &$crate::ControlHandle) -> bool {
+                self.handle == *other
+            }
+        }
+
+        impl PartialEq<$ty> for $crate::ControlHandle {
+            fn eq(&self, other: &$ty) -> bool {
+                *self == other.handle
+            }
+        }
+    }
+}
+
 handles!(Window);
 handles!(Button);
+handles!(SplitButton);
 handles!(ImageFrame);
 handles!(Label);
 handles!(CheckBox);
@@ -221,6 +262,48 @@ use super::Notice;
 #[cfg(feature = "notice")]
 handles!(Notice);
 
+#[cfg(feature = "network-listener")]
+use super::NetworkListener;
+
+#[cfg(feature = "network-listener")]
+handles!(NetworkListener);
+
+#[cfg(feature = "process-watcher")]
+use super::ProcessWatcher;
+
+#[cfg(feature = "process-watcher")]
+handles!(ProcessWatcher);
+
+#[cfg(feature = "idle-watcher")]
+use super::IdleWatcher;
+
+#[cfg(feature = "idle-watcher")]
+handles!(IdleWatcher);
+
+#[cfg(feature = "file-operation")]
+use super::FileOperation;
+
+#[cfg(feature = "file-operation")]
+handles!(FileOperation);
+
+#[cfg(feature = "zoom-view")]
+use super::ZoomView;
+
+#[cfg(feature = "zoom-view")]
+handles!(ZoomView);
+
+#[cfg(feature = "eyedropper")]
+use super::Eyedropper;
+
+#[cfg(feature = "eyedropper")]
+handles!(Eyedropper);
+
+#[cfg(feature = "printing")]
+use super::PrintPreview;
+
+#[cfg(feature = "printing")]
+handles!(PrintPreview);
+
 #[cfg(feature = "list-view")]
 use super::ListView;
 
@@ -233,6 +316,12 @@ use super::ExternCanvas;
 #[cfg(feature = "extern-canvas")]
 handles!(ExternCanvas);
 
+#[cfg(feature = "extern-canvas")]
+use super::Ruler;
+
+#[cfg(feature = "extern-canvas")]
+handles!(Ruler);
+
 
 #[cfg(feature = "frame")]
 use super::Frame;
@@ -240,6 +329,12 @@ use super::Frame;
 #[cfg(feature = "frame")]
 handles!(Frame);
 
+#[cfg(feature = "group-box")]
+use super::GroupBox;
+
+#[cfg(feature = "group-box")]
+handles!(GroupBox);
+
 
 #[cfg(feature = "rich-textbox")]
 use super::RichTextBox;
@@ -253,12 +348,24 @@ use super::RichLabel;
 #[cfg(feature = "rich-textbox")]
 handles!(RichLabel);
 
+#[cfg(feature = "log-view")]
+use super::LogView;
+
+#[cfg(feature = "log-view")]
+handles!(LogView);
+
 #[cfg(feature = "scroll-bar")]
 use super::ScrollBar;
 
 #[cfg(feature = "scroll-bar")]
 handles!(ScrollBar);
 
+#[cfg(feature = "date-range-picker")]
+use super::DateRangePicker;
+
+#[cfg(feature = "date-range-picker")]
+handles!(DateRangePicker);
+
 #[cfg(feature = "number-select")]
 use super::NumberSelect;
 
@@ -0,0 +1,199 @@
+use winapi::um::winnt::HANDLE;
+use winapi::shared::minwindef::{WPARAM, LPARAM};
+use winapi::shared::windef::HWND;
+use super::control_handle::ControlHandle;
+use crate::win32::{window_helper as wh, window::build_notice, idle::last_input_elapsed};
+use crate::NwgError;
+use std::sync::{Arc, atomic::{AtomicBool, AtomicU64, Ordering}};
+use std::time::Duration;
+use std::{ptr, thread};
+
+const NOT_BOUND: &'static str = "IdleWatcher is not yet bound to a winapi object";
+
+/// The time between two checks of the user activity, in milliseconds
+const POLL_INTERVAL: u32 = 500;
+
+/// The default idle threshold: 5 minutes
+const DEFAULT_THRESHOLD: u64 = 5 * 60 * 1000;
+
+
+/**
+An invisible component that raises `OnUserIdle` when the user did not use the keyboard or the mouse for a selected duration,
+and `OnUserReturned` when the user is back. Useful to lock an application or to pause a refresh while the user is away.
+
+The input of the whole session is considered, not only the input sent to the application.
+The activity is checked in a background thread twice per second. The events are raised on the GUI thread.
+The idle time of the user when `OnUserIdle` was raised can be read with `EventData::on_user_idle`.
+
+An idle watcher must have a parent window. The watcher stops when it is dropped.
+
+Requires the `idle-watcher` feature.
+
+**Builder parameters:**
+    * `parent`:     **Required.** The watcher parent container that will receive the events. Should be a top level window
+    * `threshold`:  The time without input after which the user is idle. Defaults to 5 minutes.
+
+**Control events:**
+    * `OnUserIdle`:     When the user did not send any input for the threshold duration
+    * `OnUserReturned`: When the user sent an input after `OnUserIdle` was raised
+
+## Example
+
+```rust
+use native_windows_gui as nwg;
+use std::time::Duration;
+
+fn build_watcher(watcher: &mut nwg::IdleWatcher, window: &nwg::Window) {
+    nwg::IdleWatcher::builder()
+        .threshold(Duration::from_secs(10 * 60))
+        .parent(window)
+        .build(watcher);
+}
+```
+*/
+pub struct IdleWatcher {
+    pub handle: ControlHandle,
+    threshold: Arc<AtomicU64>,
+    idle: Arc<AtomicBool>,
+    stop_event: HANDLE,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl IdleWatcher {
+
+    pub fn builder() -> IdleWatcherBuilder {
+        IdleWatcherBuilder {
+            parent: None,
+            threshold: Duration::from_millis(DEFAULT_THRESHOLD),
+        }
+    }
+
+    /// Return the time without input after which the user is idle
+    pub fn threshold(&self) -> Duration {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        Duration::from_millis(self.threshold.load(Ordering::SeqCst))
+    }
+
+    /// Set the time without input after which the user is idle. Applies on the next check.
+    pub fn set_threshold(&self, threshold: Duration) {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.threshold.store(threshold.as_millis() as u64, Ordering::SeqCst);
+    }
+
+    /// Return true if `OnUserIdle` was raised and the user did not return yet
+    pub fn is_idle(&self) -> bool {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.idle.load(Ordering::SeqCst)
+    }
+
+}
+
+impl Default for IdleWatcher {
+    fn default() -> IdleWatcher {
+        IdleWatcher {
+            handle: ControlHandle::NoHandle,
+            threshold: Arc::new(AtomicU64::new(DEFAULT_THRESHOLD)),
+            idle: Arc::new(AtomicBool::new(false)),
+            stop_event: ptr::null_mut(),
+            thread: None,
+        }
+    }
+}
+
+impl PartialEq for IdleWatcher {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for IdleWatcher {}
+
+impl Drop for IdleWatcher {
+    fn drop(&mut self) {
+        use winapi::um::synchapi::SetEvent;
+        use winapi::um::handleapi::CloseHandle;
+
+        if !self.stop_event.is_null() {
+            unsafe { SetEvent(self.stop_event); }
+
+            if let Some(thread) = self.thread.take() {
+                drop(thread.join());
+            }
+
+            unsafe { CloseHandle(self.stop_event); }
+        }
+
+        self.handle.destroy();
+    }
+}
+
+pub struct IdleWatcherBuilder {
+    parent: Option<ControlHandle>,
+    threshold: Duration,
+}
+
+impl IdleWatcherBuilder {
+
+    pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> IdleWatcherBuilder {
+        self.parent = Some(p.into());
+        self
+    }
+
+    pub fn threshold(mut self, threshold: Duration) -> IdleWatcherBuilder {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn build(self, out: &mut IdleWatcher) -> Result<(), NwgError> {
+        use winapi::um::synchapi::CreateEventW;
+
+        let parent = match self.parent {
+            Some(p) => match p.hwnd() {
+                Some(handle) => Ok(handle),
+                None => Err(NwgError::control_create("Wrong parent type"))
+            },
+            None => Err(NwgError::no_parent("IdleWatcher"))
+        }?;
+
+        let stop_event = unsafe { CreateEventW(ptr::null_mut(), 1, 0, ptr::null()) };
+        if stop_event.is_null() {
+            return Err(NwgError::control_create("Failed to create the IdleWatcher stop event"));
+        }
+
+        *out = Default::default();
+        out.handle = build_notice(parent);
+        out.stop_event = stop_event;
+        out.threshold.store(self.threshold.as_millis() as u64, Ordering::SeqCst);
+
+        let (_, id) = out.handle.notice().unwrap();
+        let (threshold, idle) = (out.threshold.clone(), out.idle.clone());
+        let (hwnd, stop_event) = (parent as usize, stop_event as usize);
+        out.thread = Some(thread::spawn(move || unsafe { watch_input(hwnd, id, stop_event, threshold, idle) }));
+
+        Ok(())
+    }
+
+}
+
+/// The watcher thread. Checks the last input time until the stop event is set.
+unsafe fn watch_input(hwnd: usize, id: u32, stop_event: usize, threshold: Arc<AtomicU64>, idle: Arc<AtomicBool>) {
+    use winapi::um::synchapi::WaitForSingleObject;
+    use winapi::shared::winerror::WAIT_TIMEOUT;
+
+    while WaitForSingleObject(stop_event as HANDLE, POLL_INTERVAL) == WAIT_TIMEOUT {
+        let elapsed = last_input_elapsed();
+        let limit = Duration::from_millis(threshold.load(Ordering::SeqCst));
+
+        match (idle.load(Ordering::SeqCst), elapsed >= limit) {
+            (false, true) => {
+                idle.store(true, Ordering::SeqCst);
+                wh::post_message(hwnd as HWND, wh::NWG_USER_IDLE, id as WPARAM, elapsed.as_millis() as LPARAM);
+            },
+            (true, false) => {
+                idle.store(false, Ordering::SeqCst);
+                wh::post_message(hwnd as HWND, wh::NWG_USER_RETURNED, id as WPARAM, 0);
+            },
+            _ => {}
+        }
+    }
+}
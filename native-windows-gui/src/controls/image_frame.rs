@@ -7,6 +7,7 @@ use crate::win32::{
     resources_helper as rh
 };
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
 use crate::{Bitmap, Icon, NwgError, RawEventHandler, unbind_raw_event_handler};
 use std::cell::RefCell;
 
@@ -41,6 +42,9 @@ ImageFrame is not behind any features.
   * `OnImageFrameDoubleClick`: When the image frame is clicked twice rapidly by the user
   * `MousePress(_)`: Generic mouse press events on the button
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
 
 ```rust
@@ -70,7 +74,9 @@ impl ImageFrame {
             bitmap: None,
             icon: None,
             parent: None,
-            background_color: None
+            background_color: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -251,6 +257,8 @@ pub struct ImageFrameBuilder<'a> {
     icon: Option<&'a Icon>,
     parent: Option<ControlHandle>,
     background_color: Option<[u8; 3]>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a> ImageFrameBuilder<'a> {
@@ -295,10 +303,15 @@ impl<'a> ImageFrameBuilder<'a> {
         self
     }
 
+    pub fn name(mut self, name: &str) -> ImageFrameBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut ImageFrame) -> Result<(), NwgError> {
         use winapi::um::winuser::{SS_BITMAP, SS_ICON};
 
-        let mut flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let mut flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
         if self.icon.is_some() {
             flags |= SS_ICON;
         } else {
@@ -313,6 +326,7 @@ impl<'a> ImageFrameBuilder<'a> {
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -0,0 +1,118 @@
+/*!
+    The incremental search (type-ahead) of the list view and of the tree view, when the application matches the items.
+    The characters typed in the control are added to a search prefix. The prefix is cleared when the user stops typing for a second.
+*/
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// The prefix is cleared when no character was typed for this long, like the native incremental search
+const SEARCH_TIMEOUT: Duration = Duration::from_millis(1000);
+
+
+/// The next search to run after a character was typed
+pub(crate) struct SearchStep {
+    /// The text to match against the items
+    pub text: String,
+
+    /// If the search starts after the current item instead of at the current item
+    pub next: bool,
+}
+
+/// The state of the incremental search of a control. `F` is the type of the matching function.
+pub(crate) struct IncrementalSearch<F: ?Sized> {
+    matcher: RefCell<Option<Rc<F>>>,
+    prefix: RefCell<String>,
+    last_key: Cell<Option<Instant>>,
+}
+
+impl<F: ?Sized> IncrementalSearch<F> {
+
+    /// Returns the matching function, if the application has set one
+    pub fn matcher(&self) -> Option<Rc<F>> {
+        self.matcher.borrow().clone()
+    }
+
+    pub fn set_matcher(&self, matcher: Option<Rc<F>>) {
+        *self.matcher.borrow_mut() = matcher;
+        self.reset();
+    }
+
+    /// Returns the current search prefix, or an empty string if the search has timed out
+    pub fn prefix(&self) -> String {
+        match self.last_key.get() {
+            Some(time) if time.elapsed() < SEARCH_TIMEOUT => self.prefix.borrow().clone(),
+            _ => String::new()
+        }
+    }
+
+    pub fn reset(&self) {
+        self.prefix.borrow_mut().clear();
+        self.last_key.set(None);
+    }
+
+    /**
+        Add a typed character to the search prefix. Returns `None` if the character does not start or continue a search
+        (the control characters, or a space that would start the prefix).
+
+        Typing the same character again cycles through the items that start with the character, like the native search.
+    */
+    pub fn push(&self, c: char) -> Option<SearchStep> {
+        if c.is_control() {
+            return None;
+        }
+
+        let mut prefix = self.prefix();
+        if prefix.is_empty() && c == ' ' {
+            return None;
+        }
+
+        prefix.push(c);
+        self.last_key.set(Some(Instant::now()));
+        *self.prefix.borrow_mut() = prefix.clone();
+
+        let mut chars = prefix.chars();
+        let first = chars.next().unwrap();
+        let step = match chars.all(|c2| c2 == first) {
+            true => SearchStep { text: first.to_string(), next: true },
+            false => SearchStep { text: prefix, next: false },
+        };
+
+        Some(step)
+    }
+
+}
+
+impl<F: ?Sized> Default for IncrementalSearch<F> {
+
+    fn default() -> IncrementalSearch<F> {
+        IncrementalSearch {
+            matcher: RefCell::new(None),
+            prefix: RefCell::new(String::new()),
+            last_key: Cell::new(None),
+        }
+    }
+
+}
+
+/**
+    Returns the index of the first item matched by `matches`, starting at `current` (or after `current` if `next` is true)
+    and wrapping around at the end of the `count` items.
+*/
+pub(crate) fn find_item<M>(count: usize, current: Option<usize>, next: bool, mut matches: M) -> Option<usize>
+    where M: FnMut(usize) -> bool
+{
+    if count == 0 {
+        return None;
+    }
+
+    let start = match (current, next) {
+        (Some(c), true) => c + 1,
+        (Some(c), false) => c,
+        (None, _) => 0,
+    };
+
+    (0..count)
+        .map(|offset| (start + offset) % count)
+        .find(|&index| matches(index))
+}
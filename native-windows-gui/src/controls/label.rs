@@ -8,6 +8,7 @@ use crate::win32::window_helper as wh;
 use crate::win32::base_helper::check_hwnd;
 use crate::{Font, NwgError, HTextAlign, VTextAlign, RawEventHandler, unbind_raw_event_handler};
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
 use std::cell::RefCell;
 
 const NOT_BOUND: &'static str = "Label is not yet bound to a winapi object";
@@ -47,12 +48,16 @@ Label is not behind any features.
   * `font`:             The font used for the label text
   * `background_color`: The background color of the label
   * `h_align`:          The horizontal aligment of the label
+  * `mnemonic`:         If a `&` in the text underlines the next character. Pressing the character moves the focus to the control after the label. Defaults to false.
 
 **Control events:**
   * `OnLabelClick`: When the user click the label
   * `OnLabelDoubleClick`: When the user double click a label
   * `MousePress(_)`: Generic mouse press events on the label
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
 
 
@@ -91,7 +96,10 @@ impl Label {
             parent: None,
             h_align: HTextAlign::Left,
             v_align: VTextAlign::Center,
-            background_color: None
+            background_color: None,
+            mnemonic: false,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -186,6 +194,31 @@ impl Label {
         unsafe { wh::set_window_text(handle, v) }
     }
 
+    /// Return true if a `&` in the text of the label is a mnemonic. See `nwg::text_mnemonic`.
+    pub fn mnemonic(&self) -> bool {
+        use winapi::um::winuser::SS_NOPREFIX;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        wh::get_style(handle) & SS_NOPREFIX == 0
+    }
+
+    /// Set if a `&` in the text of the label is a mnemonic. When it is, the next character is underlined and pressing it
+    /// moves the focus to the control after the label in the tab order. Otherwise the `&` is displayed.
+    pub fn set_mnemonic(&self, mnemonic: bool) {
+        use winapi::um::winuser::{InvalidateRect, GWL_STYLE, SS_NOPREFIX};
+        use std::ptr;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let style = wh::get_style(handle);
+        let style = match mnemonic {
+            true => style & !SS_NOPREFIX,
+            false => style | SS_NOPREFIX
+        };
+
+        wh::set_window_long(handle, GWL_STYLE, style as usize);
+        unsafe { InvalidateRect(handle, ptr::null(), 1); }
+    }
+
     /// Winapi class name used during control creation
     pub fn class_name(&self) -> &'static str {
         "STATIC"
@@ -397,7 +430,10 @@ pub struct LabelBuilder<'a> {
     font: Option<&'a Font>,
     h_align: HTextAlign,
     v_align: VTextAlign,
-    parent: Option<ControlHandle>
+    parent: Option<ControlHandle>,
+    mnemonic: bool,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a> LabelBuilder<'a> {
@@ -452,10 +488,24 @@ impl<'a> LabelBuilder<'a> {
         self
     }
 
+    pub fn mnemonic(mut self, mnemonic: bool) -> LabelBuilder<'a> {
+        self.mnemonic = mnemonic;
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> LabelBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut Label) -> Result<(), NwgError> {
-        use winapi::um::winuser::{SS_LEFT, SS_RIGHT, SS_CENTER};
+        use winapi::um::winuser::{SS_LEFT, SS_RIGHT, SS_CENTER, SS_NOPREFIX};
 
-        let mut flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let mut flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
+        match self.mnemonic {
+            true => { flags &= !SS_NOPREFIX; },
+            false => { flags |= SS_NOPREFIX; }
+        }
 
         match self.h_align {
             HTextAlign::Left => { flags |= SS_LEFT; },
@@ -472,6 +522,7 @@ impl<'a> LabelBuilder<'a> {
         *out = Label::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -5,6 +5,7 @@ use crate::win32::window_helper as wh;
 use crate::win32::base_helper::{to_utf16, from_utf16, check_hwnd};
 use crate::{Font, NwgError};
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
 use std::cell::{Ref, RefMut, RefCell};
 use std::fmt::Display;
 use std::ops::Range;
@@ -58,6 +59,9 @@ Requires the `list-box` feature.
   * `OnListBoxDoubleClick`: When a listbox item is clicked twice rapidly
   * `MousePress(_)`: Generic mouse press events on the listbox
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
 
 ```rust
@@ -94,7 +98,9 @@ impl<D: Display+Default> ListBox<D> {
             collection: None,
             selected_index: None,
             multi_selection: Vec::new(),
-            parent: None
+            parent: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -508,7 +514,9 @@ pub struct ListBoxBuilder<'a, D: Display+Default> {
     collection: Option<Vec<D>>,
     selected_index: Option<usize>,
     multi_selection: Vec<usize>,
-    parent: Option<ControlHandle>
+    parent: Option<ControlHandle>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a, D: Display+Default> ListBoxBuilder<'a, D> {
@@ -568,8 +576,13 @@ impl<'a, D: Display+Default> ListBoxBuilder<'a, D> {
         self
     }
 
+    pub fn name(mut self, name: &str) -> ListBoxBuilder<'a, D> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut ListBox<D>) -> Result<(), NwgError> {
-        let flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
 
         let parent = match self.parent {
             Some(p) => Ok(p),
@@ -579,6 +592,7 @@ impl<'a, D: Display+Default> ListBoxBuilder<'a, D> {
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
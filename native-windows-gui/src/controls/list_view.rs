@@ -1,4 +1,4 @@
-use winapi::shared::windef::{HBITMAP, HBRUSH};
+use winapi::shared::windef::{HWND, HBITMAP, HBRUSH};
 use winapi::um::winuser::{WS_VISIBLE, WS_DISABLED, WS_TABSTOP};
 use winapi::um::commctrl::{
     LVS_ICON, LVS_SMALLICON, LVS_LIST, LVS_REPORT, LVS_NOCOLUMNHEADER, LVCOLUMNW, LVCFMT_LEFT, LVCFMT_RIGHT, LVCFMT_CENTER, LVCFMT_JUSTIFYMASK,
@@ -7,10 +7,13 @@ use winapi::um::commctrl::{
     LVS_EX_HEADERDRAGDROP, LVS_EX_HEADERINALLVIEWS, LVM_GETHEADER, HDITEMW, HDI_FORMAT, HDM_GETITEMW, HDF_SORTUP, HDF_SORTDOWN, HDM_SETITEMW
 };
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
+use super::incremental_search::{IncrementalSearch, find_item};
 use crate::win32::window_helper as wh;
+use crate::win32::scrolling::bind_smooth_scrolling;
 use crate::win32::base_helper::{to_utf16, from_utf16, check_hwnd};
 use crate::{NwgError, RawEventHandler, unbind_raw_event_handler};
-use std::{mem, ptr, rc::Rc, cell::RefCell};
+use std::{mem, ptr, rc::Rc, cell::{Cell, RefCell}};
 
 #[cfg(feature="image-list")]
 use crate::ImageList;
@@ -195,6 +198,31 @@ pub enum ListViewColumnSortArrow {
     Down,
 }
 
+/// The content used to compute the width of a column in `ListView::autosize_column`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ListViewColumnAutoSize {
+    /// Fit the widest item of the column
+    Content,
+
+    /// Fit the widest item of the column or the header text, whichever is wider.
+    /// For the last column, fill the remaining width of the list view.
+    Header,
+}
+
+/// The clipboard formats written by `ListView::copy_selection_to_clipboard`
+#[cfg(feature="clipboard")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ListViewCopyFormat {
+    /// Tab separated values, one row per line
+    Text,
+
+    /// A HTML table
+    Html,
+
+    /// Both the text and the HTML table, like a spreadsheet. Applications that paste HTML keep the table.
+    TextAndHtml,
+}
+
 
 /// Represents a list view item parameters
 #[derive(Default, Clone, Debug)]
@@ -229,6 +257,82 @@ pub struct ListViewItem {
     pub image: i32,
 }
 
+/// Tracks when the user scrolls near the end of the list view. See `ListView::set_approaching_end_threshold`.
+#[derive(Default)]
+struct ListViewApproachingEnd {
+    threshold: Cell<Option<usize>>,
+
+    /// The number of items when `OnListViewApproachingEnd` was last raised. The event is raised once per item count.
+    raised_at: Cell<Option<usize>>,
+}
+
+impl ListViewApproachingEnd {
+
+    /// Returns true if the last visible item of the list view is within `threshold` of the end and the event was not raised for this item count
+    fn check(&self, handle: HWND) -> bool {
+        use winapi::um::commctrl::{LVM_GETITEMCOUNT, LVM_GETTOPINDEX, LVM_GETCOUNTPERPAGE};
+
+        let threshold = match self.threshold.get() {
+            Some(t) => t,
+            None => { return false; }
+        };
+
+        let len = wh::send_message(handle, LVM_GETITEMCOUNT, 0, 0) as usize;
+        let top = wh::send_message(handle, LVM_GETTOPINDEX, 0, 0) as usize;
+        let per_page = wh::send_message(handle, LVM_GETCOUNTPERPAGE, 0, 0) as usize;
+
+        if len == 0 || top + per_page + threshold < len || self.raised_at.get() == Some(len) {
+            return false;
+        }
+
+        self.raised_at.set(Some(len));
+        true
+    }
+
+}
+
+/// The tooltip that shows the text computed by the application for the cell under the mouse. See `ListView::set_cell_tooltip`.
+#[derive(Default)]
+struct ListViewCellTooltip {
+    text: RefCell<Option<Rc<dyn Fn(usize, usize) -> Option<String>>>>,
+    tooltip: Cell<Option<HWND>>,
+
+    /// The cell under the mouse, as `(row, column)`
+    cell: Cell<Option<(usize, usize)>>,
+
+    /// The text of the tooltip. It must live until the tooltip is shown.
+    buffer: RefCell<Vec<u16>>,
+}
+
+impl ListViewCellTooltip {
+
+    /// Returns the cell of the list view at the client position `x`, `y`
+    fn hit_test(handle: HWND, x: i32, y: i32) -> Option<(usize, usize)> {
+        use winapi::um::commctrl::{LVM_SUBITEMHITTEST, LVHITTESTINFO, LVHT_ONITEM};
+        use winapi::shared::windef::POINT;
+
+        let mut info: LVHITTESTINFO = unsafe { mem::zeroed() };
+        info.pt = POINT { x, y };
+
+        let index = wh::send_message(handle, LVM_SUBITEMHITTEST, 0, &mut info as *mut LVHITTESTINFO as _);
+        match index >= 0 && info.flags & LVHT_ONITEM != 0 {
+            true => Some((info.iItem as usize, info.iSubItem as usize)),
+            false => None
+        }
+    }
+
+    fn destroy(&self) {
+        use winapi::um::winuser::DestroyWindow;
+
+        if let Some(tooltip) = self.tooltip.take() {
+            unsafe { DestroyWindow(tooltip); }
+        }
+
+        self.cell.set(None);
+    }
+
+}
+
 struct ListViewDoubleBuffer {
     buffer: HBITMAP,
     size: [i32; 2],
@@ -255,10 +359,18 @@ Builder parameters:
   * `item_count`:       Number of item to preallocate
   * `list_style`:       The default style of the listview
   * `focus`:            The control receive focus after being created
+  * `truncated_tooltips`: If the full text of the truncated items is shown in a tooltip (defaults to true)
+  * `approaching_end_threshold`: Raise `OnListViewApproachingEnd` when the last visible item is this close to the end (defaults to None)
+  * `copy_shortcut`:    If Ctrl+C copies the selected rows to the clipboard as text and as a HTML table (defaults to true, requires the `clipboard` feature)
+  * `smooth_scrolling`: If the mouse wheel scrolls the list view smoothly (defaults to false). See `nwg::set_wheel_routing`.
+  * `cell_tooltip`:     A function that returns the tooltip text of a cell from its row and column (see `ListView::set_cell_tooltip`)
 
 **Control events:**
   * `MousePress(_)`:   Generic mouse press events on the tree view
   * `OnMouseMove`:     Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`:    Generic mouse wheel event
   * `OnKeyPress`:      Generic key press event
   * `OnKeyRelease`:    Generic key release event
@@ -273,13 +385,25 @@ Builder parameters:
   * `OnListViewItemChanged`: When an item is selected/unselected in the listview
   * `OnListViewFocus`: When the list view has received focus
   * `OnListViewFocusLost`: When the list view has lost focus
+  * `OnListViewColumnReordered`: When the user has dragged a column header to a new position (requires `ListViewExFlags::HEADER_DRAG_DROP`)
+  * `OnListViewColumnResized`: When the user has resized a column by dragging or double clicking a header divider
+  * `OnListViewApproachingEnd`: When the user scrolls near the last item of the list view (see `ListView::set_approaching_end_threshold`)
+  * `OnSearchPrefixChanged`: When the user types in the list view and the incremental search prefix changes (see `ListView::search_prefix`)
 
 */
 #[derive(Default)]
 pub struct ListView {
     pub handle: ControlHandle,
     double_buffer: Option<Rc<RefCell<ListViewDoubleBuffer>>>,
+    approaching_end: Rc<ListViewApproachingEnd>,
+    search: Rc<IncrementalSearch<dyn Fn(&str, usize) -> bool>>,
+    cell_tooltip: Rc<ListViewCellTooltip>,
     handler0: Option<RawEventHandler>,
+    handler1: Option<RawEventHandler>,
+    handler2: Option<RawEventHandler>,
+    handler3: Option<RawEventHandler>,
+    handler4: Option<RawEventHandler>,
+    handler5: Option<RawEventHandler>,
 }
 
 impl ListView {
@@ -297,7 +421,14 @@ impl ListView {
             ex_window_flags: 0,
             style: ListViewStyle::Simple,
             parent: None,
-            item_count: 0
+            item_count: 0,
+            truncated_tooltips: true,
+            approaching_end_threshold: None,
+            copy_shortcut: true,
+            smooth_scrolling: false,
+            cell_tooltip: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -554,6 +685,160 @@ impl ListView {
         }
     }
 
+    /// Returns true if the user can reorder the columns by dragging the headers
+    pub fn header_drag_drop(&self) -> bool {
+        use winapi::um::commctrl::LVM_GETEXTENDEDLISTVIEWSTYLE;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let ex_style = wh::send_message(handle, LVM_GETEXTENDEDLISTVIEWSTYLE, 0, 0) as u32;
+        ex_style & LVS_EX_HEADERDRAGDROP == LVS_EX_HEADERDRAGDROP
+    }
+
+    /// Allow or forbid the user to reorder the columns by dragging the headers. Raises `OnListViewColumnReordered` when a header is dropped.
+    pub fn set_header_drag_drop(&self, enable: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let value = match enable {
+            true => LVS_EX_HEADERDRAGDROP,
+            false => 0
+        };
+
+        wh::send_message(handle, LVM_SETEXTENDEDLISTVIEWSTYLE, LVS_EX_HEADERDRAGDROP as _, value as _);
+    }
+
+    /// Returns true if the full text of a truncated item is shown in a tooltip when the mouse hovers it
+    pub fn truncated_tooltips(&self) -> bool {
+        use winapi::um::commctrl::{LVM_GETEXTENDEDLISTVIEWSTYLE, LVS_EX_LABELTIP};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let ex_style = wh::send_message(handle, LVM_GETEXTENDEDLISTVIEWSTYLE, 0, 0) as u32;
+        ex_style & LVS_EX_LABELTIP == LVS_EX_LABELTIP
+    }
+
+    /**
+        Show or hide the full text of the truncated items in a tooltip when the mouse hovers them, like the file explorer.
+        In the report view, the tooltips are only shown for the first column. Enabled by default.
+    */
+    pub fn set_truncated_tooltips(&self, enable: bool) {
+        use winapi::um::commctrl::LVS_EX_LABELTIP;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let value = match enable {
+            true => LVS_EX_LABELTIP,
+            false => 0
+        };
+
+        wh::send_message(handle, LVM_SETEXTENDEDLISTVIEWSTYLE, LVS_EX_LABELTIP as _, value as _);
+    }
+
+    /**
+        Show a tooltip for each cell of the list view. When the mouse hovers a cell, `text` is called with the row and the column
+        of the cell and returns the text of the tooltip, or `None` to show no tooltip for this cell. The text can have multiple lines.
+
+        The text is computed each time the mouse enters a cell, so it can show the full content of a narrow column or details
+        that are not displayed in the list view. The tooltips of the truncated items (`set_truncated_tooltips`) are disabled.
+
+        Set `text` to `None` to remove the cell tooltips.
+    */
+    pub fn set_cell_tooltip<F>(&self, text: Option<F>)
+        where F: Fn(usize, usize) -> Option<String> + 'static
+    {
+        use winapi::um::winuser::{CreateWindowExW, WS_POPUP, WS_EX_TOPMOST};
+        use winapi::um::commctrl::{TTM_ADDTOOLW, TTM_SETMAXTIPWIDTH, TTTOOLINFOW, TTS_ALWAYSTIP, TTS_NOPREFIX, TTF_IDISHWND, TTF_SUBCLASS, LPSTR_TEXTCALLBACKW};
+        use winapi::um::libloaderapi::GetModuleHandleW;
+        use winapi::shared::windef::RECT;
+
+        // Width of the tooltip before the text wraps, so that the long texts are displayed on multiple lines
+        const MAX_TIP_WIDTH: isize = 500;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let text = match text {
+            Some(text) => text,
+            None => {
+                self.cell_tooltip.destroy();
+                *self.cell_tooltip.text.borrow_mut() = None;
+                return;
+            }
+        };
+
+        *self.cell_tooltip.text.borrow_mut() = Some(Rc::new(text));
+        if self.cell_tooltip.tooltip.get().is_some() {
+            return;
+        }
+
+        self.set_truncated_tooltips(false);
+
+        let tooltip = unsafe {
+            let class_name = to_utf16("tooltips_class32");
+            CreateWindowExW(
+                WS_EX_TOPMOST, class_name.as_ptr(), ptr::null(),
+                WS_POPUP | TTS_ALWAYSTIP | TTS_NOPREFIX,
+                0, 0, 0, 0,
+                handle, ptr::null_mut(), GetModuleHandleW(ptr::null_mut()), ptr::null_mut()
+            )
+        };
+
+        if tooltip.is_null() {
+            return;
+        }
+
+        let tool = TTTOOLINFOW {
+            cbSize: mem::size_of::<TTTOOLINFOW>() as u32,
+            uFlags: TTF_IDISHWND | TTF_SUBCLASS,
+            hwnd: handle,
+            uId: handle as usize,
+            rect: RECT { left: 0, top: 0, right: 0, bottom: 0 },
+            hinst: ptr::null_mut(),
+            lpszText: LPSTR_TEXTCALLBACKW,
+            lParam: 0,
+            lpReserved: ptr::null_mut()
+        };
+
+        wh::send_message(tooltip, TTM_ADDTOOLW, 0, &tool as *const TTTOOLINFOW as _);
+        wh::send_message(tooltip, TTM_SETMAXTIPWIDTH, 0, MAX_TIP_WIDTH);
+
+        self.cell_tooltip.cell.set(None);
+        self.cell_tooltip.tooltip.set(Some(tooltip));
+    }
+
+    /// Returns true if the list view shows a tooltip for each cell. See `set_cell_tooltip`.
+    pub fn cell_tooltip(&self) -> bool {
+        self.cell_tooltip.tooltip.get().is_some()
+    }
+
+    /**
+        Returns true if the text of an item is too long to fit in its column and is displayed with an ellipsis.
+        Only the report view and the list view are supported. Returns false if there is no item at the selected position.
+    */
+    pub fn item_text_truncated(&self, row_index: usize, column_index: usize) -> bool {
+        use winapi::um::commctrl::{LVM_GETSUBITEMRECT, LVM_GETSTRINGWIDTHW, LVIR_LABEL};
+        use winapi::shared::windef::RECT;
+
+        // Space left by the list view between the text and the border of the columns
+        const ITEM_PADDING: i32 = 4;
+        const SUBITEM_PADDING: i32 = 12;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let text = match self.item(row_index, column_index, 1024) {
+            Some(item) => to_utf16(&item.text),
+            None => { return false; }
+        };
+
+        let mut rect = RECT { left: LVIR_LABEL, top: column_index as i32, right: 0, bottom: 0 };
+        if wh::send_message(handle, LVM_GETSUBITEMRECT, row_index as _, &mut rect as *mut RECT as _) == 0 {
+            return false;
+        }
+
+        let text_width = wh::send_message(handle, LVM_GETSTRINGWIDTHW, 0, text.as_ptr() as _) as i32;
+        let padding = match column_index {
+            0 => ITEM_PADDING,
+            _ => SUBITEM_PADDING
+        };
+
+        text_width + padding > rect.right - rect.left
+    }
+
     /// Returns column sort indicator
     pub fn column_sort_arrow(&self, column_index: usize) -> Option<ListViewColumnSortArrow> {
         let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
@@ -614,6 +899,122 @@ impl ListView {
         wh::send_message(handle, LVM_GETCOLUMNWIDTH, 0, 0) as usize
     }
 
+    /// Resize a column to fit its content. Only works in the `Detailed` list style.
+    pub fn autosize_column(&self, column_index: usize, size: ListViewColumnAutoSize) {
+        use winapi::um::commctrl::{LVSCW_AUTOSIZE, LVSCW_AUTOSIZE_USEHEADER};
+
+        let width = match size {
+            ListViewColumnAutoSize::Content => LVSCW_AUTOSIZE,
+            ListViewColumnAutoSize::Header => LVSCW_AUTOSIZE_USEHEADER,
+        };
+
+        self.set_column_width(column_index, width as isize);
+    }
+
+    /// Resize every column of the list view to fit its content. See `autosize_column`.
+    pub fn autosize_columns(&self, size: ListViewColumnAutoSize) {
+        for index in 0..self.column_len() {
+            self.autosize_column(index, size);
+        }
+    }
+
+    /**
+        Returns the column indices in the order they are displayed, from left to right.
+        The order only differs from the insertion order if the user has dragged the headers around (see `ListViewExFlags::HEADER_DRAG_DROP`)
+        or if `set_column_order` was called.
+    */
+    pub fn column_order(&self) -> Vec<usize> {
+        use winapi::um::commctrl::LVM_GETCOLUMNORDERARRAY;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let count = self.column_len();
+        let mut order: Vec<i32> = vec![0; count];
+        let result = wh::send_message(handle, LVM_GETCOLUMNORDERARRAY, count as _, order.as_mut_ptr() as _);
+        match result {
+            0 => (0..count).collect(),
+            _ => order.into_iter().map(|i| i as usize).collect()
+        }
+    }
+
+    /**
+        Sets the display order of the columns, from left to right. `order` must contain every column index exactly once.
+        Returns `false` if the order is not valid or if the list view refused it.
+    */
+    pub fn set_column_order(&self, order: &[usize]) -> bool {
+        use winapi::um::commctrl::LVM_SETCOLUMNORDERARRAY;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let count = self.column_len();
+        let mut sorted = order.to_vec();
+        sorted.sort_unstable();
+        if sorted.len() != count || sorted.iter().enumerate().any(|(i, &index)| i != index) {
+            return false;
+        }
+
+        let mut order: Vec<i32> = order.iter().map(|&i| i as i32).collect();
+        let result = wh::send_message(handle, LVM_SETCOLUMNORDERARRAY, count as _, order.as_mut_ptr() as _) != 0;
+        if result {
+            // The list view does not repaint the items by itself after the columns are reordered
+            self.invalidate();
+        }
+
+        result
+    }
+
+    /**
+        Returns the order and the width of the columns as a compact string that can be saved in a config file
+        and restored with `restore_column_state`. The columns are listed in display order as `index:width`, separated by commas.
+        For example, `2:120,0:80,1:60` means the third column is displayed first and is 120 pixels wide.
+    */
+    pub fn column_state(&self) -> String {
+        use winapi::um::commctrl::LVM_GETCOLUMNWIDTH;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        self.column_order()
+            .into_iter()
+            .map(|index| {
+                let width = wh::send_message(handle, LVM_GETCOLUMNWIDTH, index, 0);
+                format!("{}:{}", index, width)
+            })
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    /**
+        Restores the order and the width of the columns from a string returned by `column_state`.
+        Returns `false` without changing the columns if the string is malformed or if it was saved with a different number of columns.
+    */
+    pub fn restore_column_state(&self, state: &str) -> bool {
+        let parse_column = |column: &str| -> Option<(usize, isize)> {
+            let mut parts = column.splitn(2, ':');
+            let index = parts.next()?.trim().parse().ok()?;
+            let width = parts.next()?.trim().parse().ok()?;
+            match width >= 0 {
+                true => Some((index, width)),
+                false => None
+            }
+        };
+
+        let columns = match state.split(',').map(parse_column).collect::<Option<Vec<(usize, isize)>>>() {
+            Some(columns) => columns,
+            None => { return false; }
+        };
+
+        let order: Vec<usize> = columns.iter().map(|&(index, _)| index).collect();
+        if !self.set_column_order(&order) {
+            return false;
+        }
+
+        for (index, width) in columns {
+            self.set_column_width(index, width);
+        }
+
+        true
+    }
+
     /// Select or unselect an item at `row_index`. Does nothing if the index is out of bounds.
     pub fn select_item(&self, row_index: usize, selected: bool) {
         use winapi::um::commctrl::{LVM_SETITEMW, LVIF_STATE, LVIS_SELECTED};
@@ -825,6 +1226,120 @@ impl ListView {
         }
     }
 
+    /**
+        Append rows at the end of the list view. Each row is a list of strings, one per column.
+        The control is not redrawn until all the rows are inserted, so a page of data can be appended while the user scrolls without flicker.
+        The scroll position and the selection are kept.
+    */
+    pub fn append_rows<R: AsRef<[S]>, S: AsRef<str>>(&self, rows: &[R]) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let start = self.len();
+
+        self.set_redraw(false);
+        self.set_item_count((start + rows.len()) as u32);
+
+        for (index, row) in rows.iter().enumerate() {
+            let items: Vec<&str> = row.as_ref().iter().map(|s| s.as_ref()).collect();
+            self.insert_items_row(Some((start + index) as i32), &items);
+        }
+
+        self.set_redraw(true);
+        self.invalidate();
+
+        // The new rows may not fill the list view, in which case the next page must be loaded right away
+        wh::post_message(handle, wh::NWG_LIST_VIEW_SCROLLED, 0, 0);
+    }
+
+    /**
+        Raise `OnListViewApproachingEnd` when the last visible item of the list view is within `threshold` items of the end.
+        Set `threshold` to `None` to disable the event.
+
+        The event is raised once until items are added to the list view or removed from it. Load the next page of data in the
+        event handler and append it with `append_rows`. The event is raised again right away if the new rows do not fill the list view.
+        Only the `Simple` and the `Detailed` list styles are supported.
+    */
+    pub fn set_approaching_end_threshold(&self, threshold: Option<usize>) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        self.approaching_end.threshold.set(threshold);
+        self.approaching_end.raised_at.set(None);
+
+        if threshold.is_some() {
+            wh::post_message(handle, wh::NWG_LIST_VIEW_SCROLLED, 0, 0);
+        }
+    }
+
+    /// Returns the threshold of `OnListViewApproachingEnd`, or `None` if the event is disabled
+    pub fn approaching_end_threshold(&self) -> Option<usize> {
+        self.approaching_end.threshold.get()
+    }
+
+    /**
+        Sets the function that matches the items with the text typed by the user (the incremental search, or type-ahead).
+        The function receives the search prefix and the index of a row, and returns true if the row matches the prefix.
+        The first matching row, starting at the focused row, is selected. Typing the same character again moves to the next matching row.
+
+        If `matcher` is `None`, the list view uses the native search, which matches the prefix with the text of the first column.
+
+        ```rust
+        use native_windows_gui as nwg;
+        use std::rc::Rc;
+
+        /// Search the second column of the list view
+        fn search_second_column(list: &Rc<nwg::ListView>) {
+            let list_ref = Rc::downgrade(list);
+            list.set_search_matcher(Some(move |prefix: &str, row: usize| {
+                let list = list_ref.upgrade().unwrap();
+                match list.item(row, 1, 260) {
+                    Some(item) => item.text.to_lowercase().starts_with(&prefix.to_lowercase()),
+                    None => false
+                }
+            }));
+        }
+        ```
+    */
+    pub fn set_search_matcher<F>(&self, matcher: Option<F>)
+        where F: Fn(&str, usize) -> bool + 'static
+    {
+        self.search.set_matcher(matcher.map(|f| Rc::new(f) as Rc<dyn Fn(&str, usize) -> bool>));
+    }
+
+    /**
+        Returns the text typed by the user in the current incremental search, or an empty string if there is no search in progress.
+        The prefix is cleared when the user stops typing for about a second. `OnSearchPrefixChanged` is raised when the prefix changes.
+    */
+    pub fn search_prefix(&self) -> String {
+        use winapi::um::commctrl::LVM_GETISEARCHSTRINGW;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        if self.search.matcher().is_some() {
+            return self.search.prefix();
+        }
+
+        let mut buffer = vec![0u16; 256];
+        let length = wh::send_message(handle, LVM_GETISEARCHSTRINGW, 0, buffer.as_mut_ptr() as _) as usize;
+        from_utf16(&buffer[..length.min(buffer.len())])
+    }
+
+    /**
+        Copy the selected rows to the clipboard. Returns `false` if no row is selected or if the clipboard could not be opened.
+
+        Only the visible columns are copied, in the order they are displayed. A column with a width of 0 is hidden.
+        In the `Detailed` style, the column headers are copied as the first row if the headers are shown.
+        In the other styles, only the first column is copied.
+
+        With `ListViewCopyFormat::TextAndHtml`, the clipboard receives the rows as tab separated values and as a HTML table at the same time.
+        Applications pick the format they understand (ex: a spreadsheet keeps the cells of the table, a text editor pastes the text).
+
+        Requires the `clipboard` feature.
+    */
+    #[cfg(feature="clipboard")]
+    pub fn copy_selection_to_clipboard(&self, format: ListViewCopyFormat) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        copy_selection(handle, format)
+    }
+
     /// Returns the current style of the list view
     pub fn list_style(&self) -> ListViewStyle {
         let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
@@ -1072,6 +1587,157 @@ impl ListView {
         self.double_buffer = Some(rc_double_buffer);
     }
 
+    /// Check if the user scrolled near the end of the list view after the list view is scrolled, resized or navigated with the keyboard.
+    /// The check is posted so that it runs after the list view has processed the message.
+    fn bind_approaching_end_handler(&mut self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::{WM_VSCROLL, WM_MOUSEWHEEL, WM_KEYDOWN, WM_SIZE};
+
+        let approaching_end = self.approaching_end.clone();
+        let handler = bind_raw_event_handler_inner(&self.handle, 0x021, move |hwnd, msg, _, _| {
+            match msg {
+                WM_VSCROLL | WM_MOUSEWHEEL | WM_KEYDOWN | WM_SIZE => {
+                    if approaching_end.threshold.get().is_some() {
+                        wh::post_message(hwnd, wh::NWG_LIST_VIEW_SCROLLED, 0, 0);
+                    }
+
+                    None
+                },
+                wh::NWG_LIST_VIEW_SCROLLED => {
+                    if approaching_end.check(hwnd) {
+                        wh::post_message(hwnd, wh::NWG_LIST_VIEW_END, 0, 0);
+                    }
+
+                    Some(0)
+                },
+                _ => None
+            }
+        }).unwrap();
+
+        self.handler1 = Some(handler);
+    }
+
+    /// Run the incremental search of the application when a character is typed, if there is a search matcher.
+    /// Otherwise the list view runs its native search. `OnSearchPrefixChanged` is posted so that it is raised after the search.
+    fn bind_search_handler(&mut self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::WM_CHAR;
+        use winapi::um::commctrl::{LVM_GETITEMCOUNT, LVM_GETNEXTITEM, LVNI_FOCUSED};
+        use std::char;
+
+        let search = self.search.clone();
+        let handler = bind_raw_event_handler_inner(&self.handle, 0x022, move |hwnd, msg, w, _| {
+            if msg != WM_CHAR {
+                return None;
+            }
+
+            let matcher = match search.matcher() {
+                Some(m) => m,
+                None => {
+                    wh::post_message(hwnd, wh::NWG_SEARCH_PREFIX, 0, 0);
+                    return None;
+                }
+            };
+
+            let step = match char::from_u32(w as u32).and_then(|c| search.push(c)) {
+                Some(step) => step,
+                None => { return None; }
+            };
+
+            let count = wh::send_message(hwnd, LVM_GETITEMCOUNT, 0, 0) as usize;
+            let focused = wh::send_message(hwnd, LVM_GETNEXTITEM, usize::max_value(), LVNI_FOCUSED);
+            let current = match focused < 0 {
+                true => None,
+                false => Some(focused as usize)
+            };
+
+            if let Some(row) = find_item(count, current, step.next, |row| matcher(&step.text, row)) {
+                select_search_result(hwnd, row);
+            }
+
+            wh::post_message(hwnd, wh::NWG_SEARCH_PREFIX, 0, 0);
+            Some(0)
+        }).unwrap();
+
+        self.handler2 = Some(handler);
+    }
+
+    /// Copy the selected rows as text and as a HTML table when the user presses Ctrl+C
+    #[cfg(feature="clipboard")]
+    fn bind_copy_handler(&mut self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::{GetKeyState, WM_KEYDOWN, VK_CONTROL, VK_MENU};
+
+        let handler = bind_raw_event_handler_inner(&self.handle, 0x023, move |hwnd, msg, w, _| {
+            if msg != WM_KEYDOWN || w != 'C' as usize {
+                return None;
+            }
+
+            let ctrl = unsafe { GetKeyState(VK_CONTROL) < 0 && GetKeyState(VK_MENU) >= 0 };
+            if ctrl {
+                copy_selection(hwnd, ListViewCopyFormat::TextAndHtml);
+            }
+
+            None
+        }).unwrap();
+
+        self.handler3 = Some(handler);
+    }
+
+    #[cfg(not(feature="clipboard"))]
+    fn bind_copy_handler(&mut self) {}
+
+    /// Track the cell under the mouse and answer the text requests of the cell tooltip.
+    /// The tooltip is reset when the mouse enters another cell so that it asks for the text of the new cell.
+    fn bind_cell_tooltip_handler(&mut self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::{WM_MOUSEMOVE, WM_NOTIFY, NMHDR};
+        use winapi::um::commctrl::{TTM_ACTIVATE, TTN_GETDISPINFOW, NMTTDISPINFOW};
+        use winapi::shared::minwindef::{LOWORD, HIWORD};
+
+        let cell_tooltip = self.cell_tooltip.clone();
+        let handler = bind_raw_event_handler_inner(&self.handle, 0x024, move |hwnd, msg, _, l| {
+            let tooltip = cell_tooltip.tooltip.get()?;
+
+            match msg {
+                WM_MOUSEMOVE => {
+                    let (x, y) = (LOWORD(l as u32) as i16 as i32, HIWORD(l as u32) as i16 as i32);
+                    let cell = ListViewCellTooltip::hit_test(hwnd, x, y);
+                    if cell != cell_tooltip.cell.get() {
+                        cell_tooltip.cell.set(cell);
+                        wh::send_message(tooltip, TTM_ACTIVATE, 0, 0);
+                        wh::send_message(tooltip, TTM_ACTIVATE, 1, 0);
+                    }
+
+                    None
+                },
+                WM_NOTIFY => {
+                    let nmhdr = unsafe { &*(l as *const NMHDR) };
+                    if nmhdr.hwndFrom != tooltip || nmhdr.code != TTN_GETDISPINFOW {
+                        return None;
+                    }
+
+                    // The function is cloned so that it can change the cell tooltip
+                    let text_fn = cell_tooltip.text.borrow().clone();
+                    let text = match (text_fn, cell_tooltip.cell.get()) {
+                        (Some(text_fn), Some((row, column))) => text_fn(row, column),
+                        _ => None
+                    };
+
+                    let mut buffer = cell_tooltip.buffer.borrow_mut();
+                    *buffer = to_utf16(text.as_deref().unwrap_or(""));
+
+                    let info = unsafe { &mut *(l as *mut NMTTDISPINFOW) };
+                    info.lpszText = buffer.as_mut_ptr();
+                    Some(0)
+                },
+                _ => None
+            }
+        }).unwrap();
+
+        self.handler4 = Some(handler);
+    }
+
 }
 
 impl Drop for ListView {
@@ -1090,6 +1756,28 @@ impl Drop for ListView {
             drop(unbind_raw_event_handler(h));
         }
 
+        if let Some(h) = self.handler1.as_ref() {
+            drop(unbind_raw_event_handler(h));
+        }
+
+        if let Some(h) = self.handler2.as_ref() {
+            drop(unbind_raw_event_handler(h));
+        }
+
+        if let Some(h) = self.handler3.as_ref() {
+            drop(unbind_raw_event_handler(h));
+        }
+
+        self.cell_tooltip.destroy();
+
+        if let Some(h) = self.handler4.as_ref() {
+            drop(unbind_raw_event_handler(h));
+        }
+
+        if let Some(h) = self.handler5.as_ref() {
+            drop(unbind_raw_event_handler(h));
+        }
+
         self.handle.destroy();
     }
 }
@@ -1106,7 +1794,14 @@ pub struct ListViewBuilder {
     ex_window_flags: u32,
     style: ListViewStyle,
     item_count: u32,
-    parent: Option<ControlHandle>
+    parent: Option<ControlHandle>,
+    truncated_tooltips: bool,
+    approaching_end_threshold: Option<usize>,
+    copy_shortcut: bool,
+    smooth_scrolling: bool,
+    cell_tooltip: Option<Box<dyn Fn(usize, usize) -> Option<String>>>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl ListViewBuilder {
@@ -1172,8 +1867,38 @@ impl ListViewBuilder {
         self
     }
 
+    pub fn truncated_tooltips(mut self, enable: bool) -> ListViewBuilder {
+        self.truncated_tooltips = enable;
+        self
+    }
+
+    pub fn approaching_end_threshold(mut self, threshold: Option<usize>) -> ListViewBuilder {
+        self.approaching_end_threshold = threshold;
+        self
+    }
+
+    pub fn copy_shortcut(mut self, enable: bool) -> ListViewBuilder {
+        self.copy_shortcut = enable;
+        self
+    }
+
+    pub fn smooth_scrolling(mut self, smooth: bool) -> ListViewBuilder {
+        self.smooth_scrolling = smooth;
+        self
+    }
+
+    pub fn cell_tooltip<F: Fn(usize, usize) -> Option<String> + 'static>(mut self, text: F) -> ListViewBuilder {
+        self.cell_tooltip = Some(Box::new(text));
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> ListViewBuilder {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut ListView) -> Result<(), NwgError> {
-        let mut flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let mut flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
         flags |= self.style.bits();
 
         let parent = match self.parent {
@@ -1184,6 +1909,7 @@ impl ListViewBuilder {
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -1198,6 +1924,17 @@ impl ListViewBuilder {
             out.set_double_buffered();
         }
 
+        out.bind_approaching_end_handler();
+        out.bind_search_handler();
+
+        if self.copy_shortcut {
+            out.bind_copy_handler();
+        }
+
+        if self.smooth_scrolling {
+            out.handler5 = Some(bind_smooth_scrolling(&out.handle, 0x025));
+        }
+
         if self.item_count > 0 {
             out.set_item_count(self.item_count);
         }
@@ -1211,6 +1948,17 @@ impl ListViewBuilder {
             wh::send_message(out.handle.hwnd().unwrap(), LVM_SETEXTENDEDLISTVIEWSTYLE, flags as _, flags as _);
         }
 
+        out.set_truncated_tooltips(self.truncated_tooltips);
+
+        out.bind_cell_tooltip_handler();
+        if let Some(text) = self.cell_tooltip {
+            out.set_cell_tooltip(Some(text));
+        }
+
+        if self.approaching_end_threshold.is_some() {
+            out.set_approaching_end_threshold(self.approaching_end_threshold);
+        }
+
         if let Some([r, g, b]) = self.background_color {
             out.set_background_color(r, g, b);
         }
@@ -1316,3 +2064,212 @@ fn build_list_view_image(row_index: usize, column_index: usize, state: u32, text
         selected: state & LVIS_SELECTED == LVIS_SELECTED,
     }
 }
+
+/// Select and focus the row found by the incremental search, and scroll the list view to show it. The other rows are unselected.
+fn select_search_result(handle: HWND, row_index: usize) {
+    use winapi::um::commctrl::{LVM_SETITEMSTATE, LVM_SETSELECTIONMARK, LVM_ENSUREVISIBLE, LVIS_SELECTED, LVIS_FOCUSED};
+
+    let mut item: LVITEMW = unsafe { mem::zeroed() };
+    item.stateMask = LVIS_SELECTED | LVIS_FOCUSED;
+    item.state = 0;
+    wh::send_message(handle, LVM_SETITEMSTATE, usize::max_value(), &mut item as *mut LVITEMW as _);
+
+    item.state = LVIS_SELECTED | LVIS_FOCUSED;
+    wh::send_message(handle, LVM_SETITEMSTATE, row_index, &mut item as *mut LVITEMW as _);
+    wh::send_message(handle, LVM_SETSELECTIONMARK, 0, row_index as _);
+    wh::send_message(handle, LVM_ENSUREVISIBLE, row_index, 0);
+}
+
+
+// Clipboard
+
+/// Copy the selected rows of the list view `handle` to the clipboard. See `ListView::copy_selection_to_clipboard`.
+#[cfg(feature="clipboard")]
+fn copy_selection(handle: HWND, format: ListViewCopyFormat) -> bool {
+    use crate::{Clipboard, ClipboardFormat};
+    use winapi::um::commctrl::{LVM_GETNEXTITEM, LVNI_SELECTED};
+    use winapi::um::winuser::OpenClipboard;
+
+    let mut rows = Vec::new();
+    let mut row = -1isize;
+    loop {
+        row = wh::send_message(handle, LVM_GETNEXTITEM, row as usize, LVNI_SELECTED as _);
+        if row < 0 {
+            break;
+        }
+
+        rows.push(row as usize);
+    }
+
+    if rows.is_empty() {
+        return false;
+    }
+
+    let columns = visible_columns(handle);
+    let headers = match wh::get_style(handle) & (0b11 | LVS_NOCOLUMNHEADER) == LVS_REPORT {
+        true => Some(columns.iter().map(|c| c.1.as_str()).collect::<Vec<_>>()),
+        false => None
+    };
+
+    let cells: Vec<Vec<String>> = rows.iter()
+        .map(|&row| columns.iter().map(|c| item_text(handle, row, c.0)).collect())
+        .collect();
+
+    if unsafe { OpenClipboard(handle) } == 0 {
+        return false;
+    }
+
+    Clipboard::empty();
+
+    if format != ListViewCopyFormat::Html {
+        let text = to_utf16(&tsv_table(headers.as_ref(), &cells));
+        unsafe { Clipboard::set_data(ClipboardFormat::UnicodeText, text.as_ptr(), text.len()); }
+    }
+
+    if format != ListViewCopyFormat::Text {
+        let html = html_clipboard_data(&html_table(headers.as_ref(), &cells));
+        unsafe { Clipboard::set_data(ClipboardFormat::Global("HTML Format"), html.as_ptr(), html.len()); }
+    }
+
+    Clipboard::close();
+
+    true
+}
+
+/// Returns the index and the header text of the visible columns, in the order they are displayed.
+/// Only the first column is shown outside of the `Detailed` style.
+#[cfg(feature="clipboard")]
+fn visible_columns(handle: HWND) -> Vec<(usize, String)> {
+    use winapi::um::commctrl::{LVM_GETCOLUMNW, LVM_GETCOLUMNORDERARRAY};
+
+    let mut columns = Vec::new();
+    let mut index = 0;
+    loop {
+        let mut text = vec![0u16; 260];
+        let mut col: LVCOLUMNW = unsafe { mem::zeroed() };
+        col.mask = LVCF_TEXT | LVCF_WIDTH;
+        col.pszText = text.as_mut_ptr();
+        col.cchTextMax = text.len() as _;
+
+        if wh::send_message(handle, LVM_GETCOLUMNW, index, &mut col as *mut LVCOLUMNW as _) == 0 {
+            break;
+        }
+
+        columns.push((index, from_utf16(&text), col.cx));
+        index += 1;
+    }
+
+    if wh::get_style(handle) & 0b11 != LVS_REPORT || columns.is_empty() {
+        let header = columns.into_iter().next().map(|c| c.1).unwrap_or_default();
+        return vec![(0, header)];
+    }
+
+    let mut order: Vec<i32> = vec![0; columns.len()];
+    if wh::send_message(handle, LVM_GETCOLUMNORDERARRAY, order.len(), order.as_mut_ptr() as _) == 0 {
+        order = (0..columns.len() as i32).collect();
+    }
+
+    order.into_iter()
+        .filter_map(|i| columns.get(i as usize))
+        .filter(|c| c.2 > 0)
+        .map(|c| (c.0, c.1.clone()))
+        .collect()
+}
+
+/// Returns the full text of an item. The buffer grows until the text fits.
+#[cfg(feature="clipboard")]
+fn item_text(handle: HWND, row_index: usize, column_index: usize) -> String {
+    use winapi::um::commctrl::LVM_GETITEMTEXTW;
+
+    let mut size = 256;
+    loop {
+        let mut buffer = vec![0u16; size];
+        let mut item: LVITEMW = unsafe { mem::zeroed() };
+        item.iSubItem = column_index as _;
+        item.pszText = buffer.as_mut_ptr();
+        item.cchTextMax = size as _;
+
+        let length = wh::send_message(handle, LVM_GETITEMTEXTW, row_index, &mut item as *mut LVITEMW as _) as usize;
+        if length + 1 < size {
+            return from_utf16(&buffer[..length]);
+        }
+
+        size *= 2;
+    }
+}
+
+/// The rows as tab separated values. Tabs and line breaks in the cells are replaced by spaces.
+#[cfg(feature="clipboard")]
+fn tsv_table(headers: Option<&Vec<&str>>, cells: &[Vec<String>]) -> String {
+    let clean = |text: &str| text.replace(|c: char| c == '\t' || c == '\r' || c == '\n', " ");
+
+    let mut lines = Vec::with_capacity(cells.len() + 1);
+    if let Some(headers) = headers {
+        lines.push(headers.iter().map(|h| clean(h)).collect::<Vec<_>>().join("\t"));
+    }
+
+    for row in cells {
+        lines.push(row.iter().map(|c| clean(c)).collect::<Vec<_>>().join("\t"));
+    }
+
+    lines.join("\r\n") + "\r\n"
+}
+
+/// The rows as a HTML table
+#[cfg(feature="clipboard")]
+fn html_table(headers: Option<&Vec<&str>>, cells: &[Vec<String>]) -> String {
+    let escape = |text: &str| text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>");
+
+    let mut html = String::from("<table>");
+    if let Some(headers) = headers {
+        html.push_str("<tr>");
+        for h in headers {
+            html.push_str(&format!("<th>{}</th>", escape(h)));
+        }
+        html.push_str("</tr>");
+    }
+
+    for row in cells {
+        html.push_str("<tr>");
+        for c in row {
+            html.push_str(&format!("<td>{}</td>", escape(c)));
+        }
+        html.push_str("</tr>");
+    }
+
+    html.push_str("</table>");
+    html
+}
+
+/// Wraps a HTML fragment in the "HTML Format" clipboard format: a header with the byte offsets of the document and of the fragment,
+/// followed by the utf-8 document. The data is null terminated.
+#[cfg(feature="clipboard")]
+fn html_clipboard_data(fragment: &str) -> Vec<u8> {
+    const START: &'static str = "<html><body>\r\n<!--StartFragment-->";
+    const END: &'static str = "<!--EndFragment-->\r\n</body></html>";
+
+    let header = |start_html: usize, end_html: usize, start_fragment: usize, end_fragment: usize| format!(
+        "Version:0.9\r\nStartHTML:{:010}\r\nEndHTML:{:010}\r\nStartFragment:{:010}\r\nEndFragment:{:010}\r\n",
+        start_html, end_html, start_fragment, end_fragment
+    );
+
+    let start_html = header(0, 0, 0, 0).len();
+    let start_fragment = start_html + START.len();
+    let end_fragment = start_fragment + fragment.len();
+    let end_html = end_fragment + END.len();
+
+    let mut data = header(start_html, end_html, start_fragment, end_fragment);
+    data.push_str(START);
+    data.push_str(fragment);
+    data.push_str(END);
+
+    let mut data = data.into_bytes();
+    data.push(0);
+    data
+}
@@ -0,0 +1,672 @@
+use winapi::shared::minwindef::{WPARAM, LPARAM};
+use winapi::shared::windef::HWND;
+use winapi::um::winuser::{WS_VISIBLE, WS_DISABLED, WS_TABSTOP};
+use crate::win32::window_helper as wh;
+use crate::win32::base_helper::{check_hwnd, to_utf16};
+use crate::win32::richedit as rich;
+use crate::{Font, NwgError, RawEventHandler, unbind_raw_event_handler};
+use super::{ControlBase, ControlHandle, CharFormat};
+use super::control_builder::BuilderState;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+const NOT_BOUND: &'static str = "LogView is not yet bound to a winapi object";
+const BAD_HANDLE: &'static str = "INTERNAL ERROR: LogView handle is not HWND!";
+
+const ES_SAVESEL: u32 = 32768;
+
+bitflags! {
+    /**
+        The log view flags
+
+        * VISIBLE:  The log view is immediatly visible after creation
+        * DISABLED: The log view cannot be interacted with by the user. It also has a grayed out look.
+        * TAB_STOP: The log view can be selected using tab navigation
+    */
+    pub struct LogViewFlags: u32 {
+        const VISIBLE = WS_VISIBLE;
+        const DISABLED = WS_DISABLED;
+        const TAB_STOP = WS_TABSTOP;
+    }
+}
+
+/// The severity of a log line. Each severity is displayed with its own color.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warning,
+    Error,
+}
+
+const DEFAULT_COLORS: [[u8; 3]; 5] = [
+    [128, 128, 128],
+    [80, 80, 160],
+    [0, 0, 0],
+    [190, 120, 0],
+    [200, 0, 0],
+];
+
+struct LogViewState {
+    lines: VecDeque<(LogLevel, String)>,
+    max_lines: usize,
+    colors: [[u8; 3]; 5],
+}
+
+type LogQueue = Arc<Mutex<Vec<(LogLevel, String)>>>;
+
+/**
+A read-only text view optimized for streaming logs. Built over a rich edit control.
+
+* The number of lines kept in the view is bounded. When the limit is reached, the oldest lines are removed.
+* Each line is colored according to its `LogLevel`.
+* New lines scroll the view to the end, unless the user scrolled up. Scrolling back to the end resumes the auto-scroll.
+* Lines can be appended from other threads using a `LogSender`.
+
+Requires the `log-view` feature.
+
+**Builder parameters:**
+  * `parent`:           **Required.** The log view parent container.
+  * `size`:             The log view size.
+  * `position`:         The log view position.
+  * `flags`:            A combination of the LogViewFlags values.
+  * `ex_flags`:         A combination of win32 window extended flags. Unlike `flags`, ex_flags must be used straight from winapi
+  * `font`:             The font used for the log view text
+  * `max_lines`:        The maximum number of lines kept in the view. Default `1000`
+  * `background_color`: The background color of the view
+  * `focus`:            The control receive focus after being created
+
+**Control events:**
+  * `OnMouseMove`:   Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
+  * `OnMouseWheel`:  Generic mouse wheel event
+  * `MousePress(_)`: Generic mouse press events on the log view
+  * `OnKeyPress`:    Generic key press event
+  * `OnKeyRelease`:  Generic key release event
+
+```rust
+use native_windows_gui as nwg;
+use std::thread;
+
+fn build_log_view(log: &mut nwg::LogView, window: &nwg::Window) {
+    nwg::LogView::builder()
+        .max_lines(500)
+        .parent(window)
+        .build(log);
+}
+
+fn log_from_thread(log: &nwg::LogView) {
+    log.log(nwg::LogLevel::Info, "Starting worker");
+
+    let sender = log.sender();
+    thread::spawn(move || {
+        sender.log(nwg::LogLevel::Warning, "Hello from the worker thread");
+    });
+}
+```
+*/
+#[derive(Default)]
+pub struct LogView {
+    pub handle: ControlHandle,
+    state: Rc<RefCell<Option<LogViewState>>>,
+    queue: LogQueue,
+    handler0: RefCell<Option<RawEventHandler>>,
+}
+
+impl LogView {
+
+    pub fn builder<'a>() -> LogViewBuilder<'a> {
+        LogViewBuilder {
+            size: (300, 200),
+            position: (0, 0),
+            flags: None,
+            ex_flags: 0,
+            font: None,
+            max_lines: 1000,
+            background_color: None,
+            focus: false,
+            parent: None,
+            name: None,
+            state: Default::default(),
+        }
+    }
+
+    /// Append a line to the view. The text can contain multiple lines.
+    pub fn log<'a>(&self, level: LogLevel, text: &'a str) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let mut state = self.state.borrow_mut();
+        let state = state.as_mut().expect(NOT_BOUND);
+        append_lines(handle, state, vec![(level, text.to_string())]);
+    }
+
+    /// Create a new `LogSender` that can append lines to this view from another thread
+    pub fn sender(&self) -> LogSender {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        LogSender {
+            hwnd: handle as usize,
+            queue: self.queue.clone(),
+        }
+    }
+
+    /// Remove every line from the view
+    pub fn clear(&self) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        if let Some(state) = self.state.borrow_mut().as_mut() {
+            state.lines.clear();
+        }
+
+        unsafe { wh::set_window_text(handle, ""); }
+    }
+
+    /// Return the number of lines in the view
+    pub fn len(&self) -> usize {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.borrow().as_ref().map(|s| s.lines.len()).unwrap_or(0)
+    }
+
+    /// Return the text of the lines in the view, oldest first
+    pub fn lines(&self) -> Vec<(LogLevel, String)> {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.borrow().as_ref().map(|s| s.lines.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    /// Return the maximum number of lines kept in the view
+    pub fn max_lines(&self) -> usize {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.borrow().as_ref().map(|s| s.max_lines).unwrap_or(0)
+    }
+
+    /// Set the maximum number of lines kept in the view. If the view has more lines, the oldest lines are removed.
+    pub fn set_max_lines(&self, max: usize) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let mut state = self.state.borrow_mut();
+        let state = state.as_mut().expect(NOT_BOUND);
+        state.max_lines = max.max(1);
+        append_lines(handle, state, Vec::new());
+    }
+
+    /// Return the color used to display the lines of a log level
+    pub fn level_color(&self, level: LogLevel) -> [u8; 3] {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.borrow().as_ref().map(|s| s.colors[level as usize]).unwrap_or(DEFAULT_COLORS[level as usize])
+    }
+
+    /// Set the color used to display the lines of a log level. Only affects new lines.
+    pub fn set_level_color(&self, level: LogLevel, color: [u8; 3]) {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        if let Some(state) = self.state.borrow_mut().as_mut() {
+            state.colors[level as usize] = color;
+        }
+    }
+
+    /// Return true if the auto-scroll is paused because the user scrolled up
+    pub fn paused(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { !scrolled_to_end(handle) }
+    }
+
+    /// Scroll to the last line of the view. This resumes the auto-scroll.
+    pub fn scroll_to_end(&self) {
+        use winapi::um::winuser::{WM_VSCROLL, SB_BOTTOM};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        wh::send_message(handle, WM_VSCROLL, SB_BOTTOM as WPARAM, 0);
+    }
+
+    /// Return the text selected by the user
+    pub fn selected_text(&self) -> String {
+        use winapi::um::winuser::EM_GETSEL;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let (mut start, mut end) = (0u32, 0u32);
+        wh::send_message(handle, EM_GETSEL, &mut start as *mut u32 as WPARAM, &mut end as *mut u32 as LPARAM);
+
+        let text: Vec<u16> = unsafe { wh::get_window_text(handle) }
+            .replace("\r\n", "\r")
+            .encode_utf16()
+            .collect();
+
+        let end = (end as usize).min(text.len());
+        let start = (start as usize).min(end);
+        String::from_utf16_lossy(&text[start..end]).replace("\r", "\r\n")
+    }
+
+    /// Copy the text selected by the user to the clipboard
+    pub fn copy(&self) {
+        use winapi::um::winuser::WM_COPY;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        wh::send_message(handle, WM_COPY, 0, 0);
+    }
+
+    /**
+        Search the next line containing `text`, starting after the line of the current selection and wrapping
+        around at the end of the view. If a match is found, the match is selected, scrolled into view and
+        the index of the line is returned.
+    */
+    pub fn find<'a>(&self, text: &'a str, case_sensitive: bool) -> Option<usize> {
+        use winapi::um::winuser::{EM_GETSEL, EM_SETSEL, EM_SCROLLCARET};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let state = self.state.borrow();
+        let state = state.as_ref().expect(NOT_BOUND);
+        if text.is_empty() || state.lines.is_empty() {
+            return None;
+        }
+
+        let (mut sel_start, mut sel_end) = (0u32, 0u32);
+        wh::send_message(handle, EM_GETSEL, &mut sel_start as *mut u32 as WPARAM, &mut sel_end as *mut u32 as LPARAM);
+
+        // Index of the line of the current selection
+        let mut offset = 0;
+        let mut current = None;
+        for (i, (_, line)) in state.lines.iter().enumerate() {
+            let next = offset + line.encode_utf16().count() + 1;
+            if (sel_start as usize) < next {
+                current = Some(i);
+                break;
+            }
+            offset = next;
+        }
+
+        let needle = match case_sensitive {
+            true => text.to_string(),
+            false => text.to_lowercase(),
+        };
+
+        let count = state.lines.len();
+        let first = match (current, sel_start != sel_end) {
+            (Some(i), true) => i + 1,
+            (Some(i), false) => i,
+            (None, _) => 0,
+        };
+
+        for i in (0..count).map(|i| (first + i) % count) {
+            let line = match case_sensitive {
+                true => state.lines[i].1.clone(),
+                false => state.lines[i].1.to_lowercase(),
+            };
+
+            if let Some(byte_index) = line.find(&needle) {
+                let line_offset: usize = state.lines.iter().take(i).map(|(_, l)| l.encode_utf16().count() + 1).sum();
+                let start = line_offset + line[..byte_index].encode_utf16().count();
+                let end = start + text.encode_utf16().count();
+                wh::send_message(handle, EM_SETSEL, start as WPARAM, end as LPARAM);
+                wh::send_message(handle, EM_SCROLLCARET, 0, 0);
+                return Some(i);
+            }
+        }
+
+        None
+    }
+
+    /// Set the font of the control
+    pub fn set_font(&self, font: Option<&Font>) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_font(handle, font.map(|f| f.handle), true); }
+    }
+
+    /// Sets the background color of the view
+    pub fn set_background_color(&self, color: [u8; 3]) {
+        use winapi::um::wingdi::RGB;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let color = RGB(color[0], color[1], color[2]);
+        wh::send_message(handle, rich::EM_SETBKGNDCOLOR, 0, color as _);
+    }
+
+    /// Return true if the control currently has the keyboard focus
+    pub fn focus(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_focus(handle) }
+    }
+
+    /// Set the keyboard focus on the log view
+    pub fn set_focus(&self) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_focus(handle); }
+    }
+
+    /// Return true if the control user can interact with the control, return false otherwise
+    pub fn enabled(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_enabled(handle) }
+    }
+
+    /// Enable or disable the control
+    pub fn set_enabled(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_enabled(handle, v) }
+    }
+
+    /// Return true if the control is visible to the user. Will return true even if the
+    /// control is outside of the parent client view (ex: at the position (10000, 10000))
+    pub fn visible(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_visibility(handle) }
+    }
+
+    /// Show or hide the control to the user
+    pub fn set_visible(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_visibility(handle, v) }
+    }
+
+    /// Return the size of the log view in the parent window
+    pub fn size(&self) -> (u32, u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_size(handle) }
+    }
+
+    /// Set the size of the log view in the parent window
+    pub fn set_size(&self, x: u32, y: u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_size(handle, x, y, false) }
+    }
+
+    /// Return the position of the log view in the parent window
+    pub fn position(&self) -> (i32, i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_position(handle) }
+    }
+
+    /// Set the position of the log view in the parent window
+    pub fn set_position(&self, x: i32, y: i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_position(handle, x, y) }
+    }
+
+    /// Winapi class name used during control creation
+    pub fn class_name(&self) -> &'static str {
+        "RICHEDIT50W"
+    }
+
+    /// Winapi base flags used during window creation
+    pub fn flags(&self) -> u32 {
+        WS_VISIBLE | WS_TABSTOP
+    }
+
+    /// Winapi flags required by the control
+    pub fn forced_flags(&self) -> u32 {
+        use winapi::um::winuser::{WS_BORDER, WS_CHILD, WS_VSCROLL, WS_HSCROLL, ES_MULTILINE, ES_READONLY, ES_AUTOVSCROLL, ES_AUTOHSCROLL};
+
+        WS_BORDER | WS_CHILD | WS_VSCROLL | WS_HSCROLL | ES_MULTILINE | ES_READONLY | ES_AUTOVSCROLL | ES_AUTOHSCROLL | ES_SAVESEL
+    }
+
+    /// Append the lines sent by the `LogSender` when the view receives `NWG_LOG_APPEND`
+    fn hook_log_sender(&self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::PostMessageW;
+
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.handle.hwnd().expect(BAD_HANDLE);
+
+        let state = self.state.clone();
+        let queue = self.queue.clone();
+        let handler = bind_raw_event_handler_inner(&self.handle, 0, move |hwnd, msg, _w, _l| {
+            if msg != wh::NWG_LOG_APPEND {
+                return None;
+            }
+
+            // The queue is only drained once the view can be updated. If the view is borrowed (ex: a handler
+            // of the view is running), the lines are appended after the handler returns.
+            match state.try_borrow_mut() {
+                Ok(mut state) => if let Some(state) = state.as_mut() {
+                    let lines = match queue.lock() {
+                        Ok(mut queue) => queue.drain(..).collect(),
+                        Err(_) => Vec::new()
+                    };
+
+                    append_lines(hwnd, state, lines);
+                },
+                Err(_) => unsafe { PostMessageW(hwnd, wh::NWG_LOG_APPEND, 0, 0); }
+            }
+
+            Some(0)
+        });
+
+        *self.handler0.borrow_mut() = Some(handler.unwrap());
+    }
+
+}
+
+impl Drop for LogView {
+    fn drop(&mut self) {
+        let handler = self.handler0.borrow();
+        if let Some(h) = handler.as_ref() {
+            drop(unbind_raw_event_handler(h));
+        }
+
+        self.handle.destroy();
+    }
+}
+
+impl PartialEq for LogView {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for LogView {}
+
+/// LogSender appends lines to its parent `LogView` from another thread
+#[derive(Clone)]
+pub struct LogSender {
+    hwnd: usize,
+    queue: LogQueue,
+}
+
+impl LogSender {
+    /// Queue a line and wake up the GUI thread of the parent `LogView`. The text can contain multiple lines.
+    pub fn log<S: Into<String>>(&self, level: LogLevel, text: S) {
+        use winapi::um::winuser::SendNotifyMessageW;
+
+        let wake = match self.queue.lock() {
+            Ok(mut queue) => {
+                queue.push((level, text.into()));
+                queue.len() == 1
+            },
+            Err(_) => false
+        };
+
+        // Only the first line of a batch needs to wake up the GUI thread
+        if wake {
+            unsafe { SendNotifyMessageW(self.hwnd as HWND, wh::NWG_LOG_APPEND, 0, 0); }
+        }
+    }
+}
+
+pub struct LogViewBuilder<'a> {
+    size: (i32, i32),
+    position: (i32, i32),
+    flags: Option<LogViewFlags>,
+    ex_flags: u32,
+    font: Option<&'a Font>,
+    max_lines: usize,
+    background_color: Option<[u8; 3]>,
+    focus: bool,
+    parent: Option<ControlHandle>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
+}
+
+impl<'a> LogViewBuilder<'a> {
+
+    pub fn flags(mut self, flags: LogViewFlags) -> LogViewBuilder<'a> {
+        self.flags = Some(flags);
+        self
+    }
+
+    pub fn ex_flags(mut self, flags: u32) -> LogViewBuilder<'a> {
+        self.ex_flags = flags;
+        self
+    }
+
+    pub fn size(mut self, size: (i32, i32)) -> LogViewBuilder<'a> {
+        self.size = size;
+        self
+    }
+
+    pub fn position(mut self, pos: (i32, i32)) -> LogViewBuilder<'a> {
+        self.position = pos;
+        self
+    }
+
+    pub fn font(mut self, font: Option<&'a Font>) -> LogViewBuilder<'a> {
+        self.font = font;
+        self
+    }
+
+    pub fn max_lines(mut self, max: usize) -> LogViewBuilder<'a> {
+        self.max_lines = max;
+        self
+    }
+
+    pub fn background_color(mut self, color: Option<[u8; 3]>) -> LogViewBuilder<'a> {
+        self.background_color = color;
+        self
+    }
+
+    pub fn focus(mut self, focus: bool) -> LogViewBuilder<'a> {
+        self.focus = focus;
+        self
+    }
+
+    pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> LogViewBuilder<'a> {
+        self.parent = Some(p.into());
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> LogViewBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn build(self, out: &mut LogView) -> Result<(), NwgError> {
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
+
+        let parent = match self.parent {
+            Some(p) => Ok(p),
+            None => Err(NwgError::no_parent("LogView"))
+        }?;
+
+        *out = Default::default();
+
+        out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
+            .class_name(out.class_name())
+            .forced_flags(out.forced_flags())
+            .flags(flags)
+            .ex_flags(self.ex_flags)
+            .size(self.size)
+            .position(self.position)
+            .parent(Some(parent))
+            .build()?;
+
+        *out.state.borrow_mut() = Some(LogViewState {
+            lines: VecDeque::new(),
+            max_lines: self.max_lines.max(1),
+            colors: DEFAULT_COLORS,
+        });
+
+        out.hook_log_sender();
+
+        if self.font.is_some() {
+            out.set_font(self.font);
+        } else {
+            out.set_font(Font::global_default().as_ref());
+        }
+
+        if let Some(color) = self.background_color {
+            out.set_background_color(color);
+        }
+
+        if self.focus {
+            out.set_focus();
+        }
+
+        Ok(())
+    }
+
+}
+
+
+unsafe fn scrolled_to_end(handle: HWND) -> bool {
+    use winapi::um::winuser::{GetScrollInfo, SCROLLINFO, SIF_ALL, SB_VERT};
+    use std::mem;
+
+    let mut info: SCROLLINFO = mem::zeroed();
+    info.cbSize = mem::size_of::<SCROLLINFO>() as u32;
+    info.fMask = SIF_ALL;
+    if GetScrollInfo(handle, SB_VERT as i32, &mut info) == 0 {
+        return true;
+    }
+
+    info.nPage == 0 || info.nPos + (info.nPage as i32) > info.nMax
+}
+
+/// Append `new_lines` at the end of the view, remove the lines over the limit, and
+/// keep the user selection and scroll position unless the view was scrolled to the end.
+fn append_lines(handle: HWND, state: &mut LogViewState, new_lines: Vec<(LogLevel, String)>) {
+    use winapi::um::winuser::{EM_GETSEL, EM_SETSEL, EM_REPLACESEL, EM_GETFIRSTVISIBLELINE, EM_LINESCROLL, WM_SETREDRAW, WM_VSCROLL, SB_BOTTOM, InvalidateRect};
+    use std::ptr;
+
+    let at_end = unsafe { scrolled_to_end(handle) };
+    let first_visible = wh::send_message(handle, EM_GETFIRSTVISIBLELINE, 0, 0) as i32;
+
+    let (mut sel_start, mut sel_end) = (0u32, 0u32);
+    wh::send_message(handle, EM_GETSEL, &mut sel_start as *mut u32 as WPARAM, &mut sel_end as *mut u32 as LPARAM);
+
+    wh::send_message(handle, WM_SETREDRAW, 0, 0);
+
+    for (level, text) in new_lines {
+        let color = state.colors[level as usize];
+        // `split` keeps the empty lines, including an empty message
+        for line in text.split('\n').map(|line| line.trim_end_matches('\r')) {
+            wh::send_message(handle, EM_SETSEL, -1isize as WPARAM, -1);
+            rich::set_char_format(handle, &CharFormat { text_color: Some(color), ..Default::default() });
+
+            let line_text = to_utf16(&format!("{}\r", line));
+            wh::send_message(handle, EM_REPLACESEL, 0, line_text.as_ptr() as LPARAM);
+
+            state.lines.push_back((level, line.to_string()));
+        }
+    }
+
+    // Remove the oldest lines
+    let (mut removed_chars, mut removed_lines) = (0u32, 0i32);
+    while state.lines.len() > state.max_lines {
+        if let Some((_, line)) = state.lines.pop_front() {
+            removed_chars += line.encode_utf16().count() as u32 + 1;
+            removed_lines += 1;
+        }
+    }
+
+    if removed_chars > 0 {
+        let empty = to_utf16("");
+        wh::send_message(handle, EM_SETSEL, 0, removed_chars as LPARAM);
+        wh::send_message(handle, EM_REPLACESEL, 0, empty.as_ptr() as LPARAM);
+    }
+
+    let sel_start = sel_start.saturating_sub(removed_chars);
+    let sel_end = sel_end.saturating_sub(removed_chars);
+    wh::send_message(handle, EM_SETSEL, sel_start as WPARAM, sel_end as LPARAM);
+
+    match at_end {
+        true => { wh::send_message(handle, WM_VSCROLL, SB_BOTTOM as WPARAM, 0); },
+        false => {
+            let target = (first_visible - removed_lines).max(0);
+            let current = wh::send_message(handle, EM_GETFIRSTVISIBLELINE, 0, 0) as i32;
+            wh::send_message(handle, EM_LINESCROLL, 0, (target - current) as LPARAM);
+        }
+    }
+
+    wh::send_message(handle, WM_SETREDRAW, 1, 0);
+    unsafe { InvalidateRect(handle, ptr::null(), 1); }
+}
@@ -1,6 +1,7 @@
 use crate::win32::menu as mh;
-use crate::NwgError;
+use crate::{NwgError, Bitmap};
 use super::{ControlBase, ControlHandle};
+use winapi::shared::windef::HMENU;
 use std::ptr;
 
 const NOT_BOUND: &'static str = "Menu/MenuItem is not yet bound to a winapi object";
@@ -63,8 +64,10 @@ bitflags! {
       - disabled: If the menu can be selected by the user
       - popup: The menu is a context menu
       - parent: A top level window, a menu or None. With a top level window, the menu is added to the menu bar if popup is set to false.
+      - insert_at: Insert the menu at this index in the parent instead of appending it.
 
     **Control events:**
+      - OnMenuOpening: Sent before a drop-down menu, submenu or popup menu is displayed. Use it to fill the menu lazily.
       - OnMenuOpen: Sent when a drop-down menu or submenu is about to become active.
       - OnMenuHover: When the user hovers the menu
       - OnMenuEnter: When the user enters the menu. Technically, when the user enters the menu modal loop.
@@ -80,6 +83,12 @@ bitflags! {
     To create an access key for a menu item, precede any character in the item's text string with an ampersand.
     For example, the text string "&Move" causes the system to underline the letter "M".
 
+    **Runtime edition**
+
+    The items of a menu can be edited after it is built with `insert_item_at`, `remove_item` and `clear`.
+    Combined with the `OnMenuOpening` event, this can be used to compute the content of a menu when it is opened (ex: a list of recent files).
+    Note that removing an item that opens a submenu destroys the submenu. Any `Menu` refering to it becomes invalid.
+
     ```rust
     use native_windows_gui as nwg;
 
@@ -104,10 +113,83 @@ impl Menu {
             text: "Menu",
             disabled: false,
             popup: false,
+            insert_at: None,
             parent: None
         }
     }
 
+    /// Return the number of items in the menu, separators and submenus included
+    pub fn item_count(&self) -> u32 {
+        unsafe { mh::menu_item_count(self.hmenu()) }
+    }
+
+    /**
+        Create a new menu item with the selected text and insert it at `index`. If `index` is out of bounds, the item is appended.
+        The item is removed from the menu when the returned `MenuItem` is dropped.
+    */
+    pub fn insert_item_at(&self, index: u32, text: &str) -> Result<MenuItem, NwgError> {
+        let mut item = MenuItem::default();
+        MenuItem::builder()
+            .text(text)
+            .insert_at(Some(index))
+            .parent(&self.handle)
+            .build(&mut item)?;
+
+        Ok(item)
+    }
+
+    /**
+        Remove the item at `index` from the menu. Return `false` if there is no item at this index.
+        If the item opens a submenu, the submenu is destroyed.
+    */
+    pub fn remove_item(&self, index: u32) -> bool {
+        unsafe { mh::delete_menu_item_at(self.hmenu(), index) }
+    }
+
+    /// Remove every item from the menu. Submenus of the menu are destroyed.
+    pub fn clear(&self) {
+        let menu = self.hmenu();
+        unsafe {
+            let count = mh::menu_item_count(menu);
+            for index in (0..count).rev() {
+                mh::delete_menu_item_at(menu, index);
+            }
+        }
+    }
+
+    /// Return the handle of the item at `index`. A `ControlHandle::Menu` for submenus and a `ControlHandle::MenuItem` for items and separators.
+    pub fn item(&self, index: u32) -> Option<ControlHandle> {
+        unsafe { mh::menu_item_at(self.hmenu(), index) }
+    }
+
+    /// Return the index of the menu item with the selected id. See `MenuItem::id`.
+    pub fn find_item_by_id(&self, id: u32) -> Option<u32> {
+        unsafe { mh::menu_item_index(self.hmenu(), id) }
+    }
+
+    /**
+        Return the index of the first item with the selected text.
+        Access key ampersands and the shortcut label after a tab are ignored, so "&Open\tCtrl+O" is matched by "Open".
+    */
+    pub fn find_item_by_text(&self, text: &str) -> Option<u32> {
+        let menu = self.hmenu();
+        let text = menu_label(text);
+        unsafe {
+            (0..mh::menu_item_count(menu))
+                .find(|&index| menu_label(&mh::menu_item_text(menu, index)) == text)
+        }
+    }
+
+    /// Return the text of the item at `index`
+    pub fn item_text(&self, index: u32) -> String {
+        unsafe { mh::menu_item_text(self.hmenu(), index) }
+    }
+
+    /// Set the text of the item at `index`
+    pub fn set_item_text(&self, index: u32, text: &str) {
+        unsafe { mh::set_menu_item_text(self.hmenu(), index, text) }
+    }
+
     /// Return true if the control user can interact with the control, return false otherwise
     pub fn enabled(&self) -> bool {
         if self.handle.blank() { panic!("{}", NOT_BOUND); }
@@ -163,6 +245,29 @@ impl Menu {
         self.popup_with_flags(x, y, PopupMenuFlags::empty())
     }
 
+    fn hmenu(&self) -> HMENU {
+        match self.handle {
+            ControlHandle::Menu(_, menu) | ControlHandle::PopMenu(_, menu) => menu,
+            ControlHandle::NoHandle => panic!("{}", NOT_BOUND),
+            _ => panic!("{}", BAD_HANDLE)
+        }
+    }
+
+}
+
+/// Strip the access key ampersands and the shortcut label from a menu text
+fn menu_label(text: &str) -> String {
+    let text = text.split('\t').next().unwrap_or("");
+    let mut label = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '&' => if let Some(next) = chars.next() { label.push(next); },
+            c => label.push(c)
+        }
+    }
+
+    label
 }
 
 impl Drop for Menu {
@@ -175,6 +280,7 @@ pub struct MenuBuilder<'a> {
     text: &'a str,
     disabled: bool,
     popup: bool,
+    insert_at: Option<u32>,
     parent: Option<ControlHandle>
 }
 
@@ -195,6 +301,11 @@ impl<'a> MenuBuilder<'a> {
         self
     }
 
+    pub fn insert_at(mut self, index: Option<u32>) -> MenuBuilder<'a> {
+        self.insert_at = index;
+        self
+    }
+
     pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> MenuBuilder<'a> {
         self.parent = Some(p.into());
         self
@@ -209,6 +320,7 @@ impl<'a> MenuBuilder<'a> {
             .text(self.text)
             .item(false)
             .popup(self.popup)
+            .position(self.insert_at)
             .parent(self.parent.unwrap())
             .build()?;
 
@@ -230,11 +342,18 @@ impl<'a> MenuBuilder<'a> {
       - text: The text of the menu, including access key and shortcut label
       - disabled: If the item can be selected by the user
       - check: If the item should have a check mark next to it.
+      - bitmap: A bitmap displayed next to the item text. The bitmap must outlive the menu item.
+      - check_bitmaps: The bitmaps displayed instead of the default check mark as `(checked, unchecked)`. The bitmaps must outlive the menu item.
+      - default_item: If the item is the default item of its menu. The default item is displayed in bold.
+      - owner_draw: If the item is painted by the application. See **Owner Draw** below.
       - parent: A top level window or a menu. With a top level window, the menu item is added to the menu bar.
+      - insert_at: Insert the item at this index in the parent instead of appending it.
 
    **Control events:**
       - OnMenuItemSelected: When a menu item is selected. This can be done by clicking or using the hot-key.
       - OnMenuHover: When the user hovers the menu
+      - OnMenuItemMeasure: When the size of an owner drawn item is needed. Sent to the window that owns the menu.
+      - OnMenuItemDraw: When an owner drawn item must be painted. Sent to the window that owns the menu.
 
 
     **Menu Access Keys**
@@ -268,6 +387,13 @@ impl<'a> MenuBuilder<'a> {
 
     **note:** This will only add a text label to the menu item, the keyboard handling must be done through other means.
 
+    **Owner Draw**
+
+    Owner drawn items are measured and painted by the application. This can be used for colored or multi-column items.
+    The `OnMenuItemMeasure` event must set the item size using `EventData::on_menu_item_measure`
+    and the `OnMenuItemDraw` event must paint the item into the device context returned by `EventData::on_menu_item_draw`.
+    Both events are sent to the window that owns the menu with the menu item handle.
+
     ```rust
     use native_windows_gui as nwg;
 
@@ -292,10 +418,47 @@ impl MenuItem {
             text: "Menu Item",
             disabled: false,
             check: false,
+            bitmap: None,
+            check_bitmaps: None,
+            default_item: false,
+            owner_draw: false,
+            insert_at: None,
             parent: None
         }
     }
 
+    /// Return the unique id of the menu item. Can be used with `Menu::find_item_by_id`.
+    pub fn id(&self) -> u32 {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        let (_parent_handle, id) = self.handle.hmenu_item().expect(BAD_HANDLE);
+        id
+    }
+
+    /// Return the text of the menu item, including the access key and the shortcut label
+    pub fn text(&self) -> String {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        let (parent_handle, id) = self.handle.hmenu_item().expect(BAD_HANDLE);
+
+        unsafe { 
+            match mh::menu_item_index(parent_handle, id) {
+                Some(index) => mh::menu_item_text(parent_handle, index),
+                None => String::new()
+            }
+        }
+    }
+
+    /// Set the text of the menu item
+    pub fn set_text(&self, text: &str) {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        let (parent_handle, id) = self.handle.hmenu_item().expect(BAD_HANDLE);
+
+        unsafe { 
+            if let Some(index) = mh::menu_item_index(parent_handle, id) {
+                mh::set_menu_item_text(parent_handle, index, text);
+            }
+        }
+    }
+
     /// Return true if the control user can interact with the control, return false otherwise
     pub fn enabled(&self) -> bool {
         if self.handle.blank() { panic!("{}", NOT_BOUND); }
@@ -328,6 +491,60 @@ impl MenuItem {
         unsafe { mh::menu_item_checked(parent_handle, id) }
     }
 
+    /// Sets the bitmap displayed next to the text of the menu item. If None, the bitmap is removed.
+    /// The bitmap is not copied and must outlive the menu item.
+    pub fn set_bitmap(&self, bitmap: Option<&Bitmap>) {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        let (parent_handle, id) = self.handle.hmenu_item().expect(BAD_HANDLE);
+
+        let bitmap = bitmap.map(|b| b.handle as _).unwrap_or(ptr::null_mut());
+        unsafe { mh::set_menu_item_bitmap(parent_handle, id, bitmap); }
+    }
+
+    /// Sets the bitmaps displayed instead of the default check mark when the item is checked or unchecked.
+    /// If None, the default check mark is used. The bitmaps are not copied and must outlive the menu item.
+    pub fn set_check_bitmaps(&self, checked: Option<&Bitmap>, unchecked: Option<&Bitmap>) {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        let (parent_handle, id) = self.handle.hmenu_item().expect(BAD_HANDLE);
+
+        let checked = checked.map(|b| b.handle as _).unwrap_or(ptr::null_mut());
+        let unchecked = unchecked.map(|b| b.handle as _).unwrap_or(ptr::null_mut());
+        unsafe { mh::set_menu_item_check_bitmaps(parent_handle, id, checked, unchecked); }
+    }
+
+    /// Returns true if the menu item is the default item of its menu
+    pub fn default_item(&self) -> bool {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        let (parent_handle, id) = self.handle.hmenu_item().expect(BAD_HANDLE);
+
+        unsafe { mh::menu_default_item(parent_handle) == Some(id) }
+    }
+
+    /// Sets the menu item as the default item of its menu. The default item is displayed in bold.
+    /// A menu can only have one default item.
+    pub fn set_default_item(&self, default: bool) {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        let (parent_handle, id) = self.handle.hmenu_item().expect(BAD_HANDLE);
+
+        unsafe { mh::set_menu_default_item(parent_handle, id, default); }
+    }
+
+    /// Returns true if the menu item is painted by the application
+    pub fn owner_draw(&self) -> bool {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        let (parent_handle, id) = self.handle.hmenu_item().expect(BAD_HANDLE);
+
+        unsafe { mh::menu_item_owner_draw(parent_handle, id) }
+    }
+
+    /// Sets if the menu item is painted by the application. See the **Owner Draw** section of the control documentation.
+    pub fn set_owner_draw(&self, owner_draw: bool) {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        let (parent_handle, id) = self.handle.hmenu_item().expect(BAD_HANDLE);
+
+        unsafe { mh::set_menu_item_owner_draw(parent_handle, id, owner_draw); }
+    }
+
 }
 
 impl Drop for MenuItem {
@@ -340,6 +557,11 @@ pub struct MenuItemBuilder<'a> {
     text: &'a str,
     disabled: bool,
     check: bool,
+    bitmap: Option<&'a Bitmap>,
+    check_bitmaps: Option<(Option<&'a Bitmap>, Option<&'a Bitmap>)>,
+    default_item: bool,
+    owner_draw: bool,
+    insert_at: Option<u32>,
     parent: Option<ControlHandle>
 }
 
@@ -360,6 +582,31 @@ impl<'a> MenuItemBuilder<'a> {
         self
     }
 
+    pub fn bitmap(mut self, bitmap: Option<&'a Bitmap>) -> MenuItemBuilder<'a> {
+        self.bitmap = bitmap;
+        self
+    }
+
+    pub fn check_bitmaps(mut self, bitmaps: (Option<&'a Bitmap>, Option<&'a Bitmap>)) -> MenuItemBuilder<'a> {
+        self.check_bitmaps = Some(bitmaps);
+        self
+    }
+
+    pub fn default_item(mut self, default: bool) -> MenuItemBuilder<'a> {
+        self.default_item = default;
+        self
+    }
+
+    pub fn owner_draw(mut self, owner_draw: bool) -> MenuItemBuilder<'a> {
+        self.owner_draw = owner_draw;
+        self
+    }
+
+    pub fn insert_at(mut self, index: Option<u32>) -> MenuItemBuilder<'a> {
+        self.insert_at = index;
+        self
+    }
+
     pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> MenuItemBuilder<'a> {
         self.parent = Some(p.into());
         self
@@ -373,6 +620,7 @@ impl<'a> MenuItemBuilder<'a> {
         item.handle = ControlBase::build_hmenu()
             .text(self.text)
             .item(true)
+            .position(self.insert_at)
             .parent(self.parent.unwrap())
             .build()?;
 
@@ -384,6 +632,22 @@ impl<'a> MenuItemBuilder<'a> {
             item.set_checked(true);
         }
 
+        if self.bitmap.is_some() {
+            item.set_bitmap(self.bitmap);
+        }
+
+        if let Some((checked, unchecked)) = self.check_bitmaps {
+            item.set_check_bitmaps(checked, unchecked);
+        }
+
+        if self.default_item {
+            item.set_default_item(true);
+        }
+
+        if self.owner_draw {
+            item.set_owner_draw(true);
+        }
+
         Ok(())
     }
 }
@@ -395,6 +659,7 @@ impl<'a> MenuItemBuilder<'a> {
 
     **Builder parameters:**
       - parent: A top level window or a menu. With a top level window, the menu item is added to the menu bar.
+      - insert_at: Insert the separator at this index in the parent instead of appending it.
 
    **Control events:**
       - OnMenuHover: When the user hovers the menu
@@ -418,6 +683,7 @@ impl MenuSeparator {
 
     pub fn builder() -> MenuSeparatorBuilder {
         MenuSeparatorBuilder {
+            insert_at: None,
             parent: None
         }
     }
@@ -425,11 +691,17 @@ impl MenuSeparator {
 }
 
 pub struct MenuSeparatorBuilder {
+    insert_at: Option<u32>,
     parent: Option<ControlHandle>
 }
 
 impl MenuSeparatorBuilder {
 
+    pub fn insert_at(mut self, index: Option<u32>) -> MenuSeparatorBuilder {
+        self.insert_at = index;
+        self
+    }
+
     pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> MenuSeparatorBuilder {
         self.parent = Some(p.into());
         self
@@ -442,6 +714,7 @@ impl MenuSeparatorBuilder {
 
         sep.handle = ControlBase::build_hmenu()
             .separator(true)
+            .position(self.insert_at)
             .parent(self.parent.unwrap())
             .build()?;
 
@@ -15,10 +15,17 @@
     ```
 
     When making a system-tray application (with TrayNotification), this is the recommended top level window type.
+    The invisible controls (`Timer`, `AnimationTimer`, `Notice`, `TrayNotification`) and the hotkeys of a `ShortcutMap` accept a message window
+    as parent. With the derive macro, use `top_level_parent: true` to parent these controls to the nearest top level window, a message window included.
 */
+use winapi::shared::guiddef::GUID;
 use super::ControlHandle;
 use crate::win32::window::create_message_window;
-use crate::NwgError;
+use crate::win32::base_helper::check_hwnd;
+use crate::{NwgError, DeviceNotification};
+
+const NOT_BOUND: &'static str = "MessageWindow is not yet bound to a winapi object";
+const BAD_HANDLE: &'static str = "INTERNAL ERROR: MessageWindow handle is not HWND!";
 
 /**
     A message only top level window. At least one top level window is required to make a NWG application.
@@ -35,6 +42,17 @@ impl MessageWindow {
         MessageWindowBuilder {}
     }
 
+    /**
+        Register the window for the `OnDeviceArrival` and `OnDeviceRemoval` events of the device interfaces of `interface_class`.
+        If `interface_class` is `None`, all the device interfaces are registered. The events are sent as long as the returned `DeviceNotification` is alive.
+        
+        Message only windows do not receive the volume notifications.
+    */
+    pub fn register_device_notification(&self, interface_class: Option<GUID>) -> Result<DeviceNotification, NwgError> {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        DeviceNotification::register(handle, interface_class)
+    }
+
 }
 
 impl Drop for MessageWindow {
@@ -3,6 +3,8 @@ mod control_base;
 mod window;
 mod button;
 mod check_box;
+mod check_box_group;
+mod split_button;
 mod radio_button;
 mod text_input;
 mod label;
@@ -17,6 +19,9 @@ mod rich_text_box;
 #[cfg(feature = "rich-textbox")]
 mod rich_label;
 
+#[cfg(feature = "log-view")]
+mod log_view;
+
 #[cfg(feature = "status-bar")]
 mod status_bar;
 
@@ -38,6 +43,15 @@ mod animation_timer;
 #[cfg(feature = "notice")]
 mod notice;
 
+#[cfg(feature = "network-listener")]
+mod network_listener;
+
+#[cfg(feature = "process-watcher")]
+mod process_watcher;
+
+#[cfg(feature = "idle-watcher")]
+mod idle_watcher;
+
 #[cfg(feature = "combobox")]
 mod combo_box;
 
@@ -47,6 +61,9 @@ mod list_box;
 #[cfg(feature = "datetime-picker")]
 mod date_picker;
 
+#[cfg(feature = "date-range-picker")]
+mod date_range_picker;
+
 #[cfg(feature = "progress-bar")]
 mod progress_bar;
 
@@ -68,32 +85,87 @@ mod message_window;
 #[cfg(feature = "list-view")]
 mod list_view;
 
+#[cfg(any(feature = "list-view", feature = "tree-view"))]
+mod incremental_search;
+
 #[cfg(feature = "number-select")]
 mod number_select;
 
 #[cfg(feature = "extern-canvas")]
 mod extern_canvas;
 
+#[cfg(feature = "extern-canvas")]
+mod canvas_group;
+
+#[cfg(feature = "extern-canvas")]
+mod viewport;
+
+#[cfg(feature = "extern-canvas")]
+mod ruler;
+
+#[cfg(feature = "rubber-band")]
+mod rubber_band;
+
+#[cfg(feature = "design-surface")]
+mod design_surface;
+
+#[cfg(feature = "drag-drop")]
+mod drop_target;
+
+#[cfg(feature = "window-thumbnail")]
+mod window_thumbnail;
+
+#[cfg(feature = "zoom-view")]
+mod zoom_view;
+
+#[cfg(feature = "eyedropper")]
+mod eyedropper;
+
+#[cfg(feature = "printing")]
+mod print_preview;
+
 #[cfg(feature = "frame")]
 mod frame;
 
+#[cfg(feature = "group-box")]
+mod group_box;
+
+#[cfg(feature = "file-picker")]
+mod file_picker;
+
+#[cfg(feature = "file-operation")]
+mod file_operation;
+
 #[cfg(feature = "scroll-bar")]
 mod scroll_bar;
 
 #[cfg(feature = "plotting")]
 mod plotters;
 
+#[cfg(feature = "dyn-control")]
+mod dyn_control;
+
+#[cfg(feature = "dyn-control")]
+mod control_properties;
+
+mod navigation_behavior;
+
+mod control_builder;
+
 mod handle_from_control;
 
 pub use control_handle::ControlHandle;
 pub use control_base::{ControlBase, HwndBuilder, TimerBuilder as BaseTimerBuilder, OtherBuilder};
-pub use window::{Window, WindowBuilder, WindowFlags};
-pub use button::{Button, ButtonBuilder, ButtonFlags};
+pub use window::{Window, WindowBuilder, WindowFlags, MessageFilterAction};
+pub use button::{Button, ButtonBuilder, ButtonFlags, ButtonImageAlign};
+pub use split_button::{SplitButton, SplitButtonBuilder, SplitButtonFlags};
 pub use check_box::{CheckBox, CheckBoxBuilder, CheckBoxState, CheckBoxFlags};
+pub use check_box_group::{CheckBoxGroup, CheckBoxGroupBuilder, PartialCheckPolicy};
 pub use radio_button::{RadioButton, RadioButtonBuilder, RadioButtonState, RadioButtonFlags};
 pub use text_input::{TextInput, TextInputBuilder, TextInputFlags};
 pub use label::{Label, LabelBuilder, LabelFlags};
 pub use image_frame::{ImageFrame, ImageFrameBuilder, ImageFrameFlags};
+pub use navigation_behavior::{NavigationBehavior, NavigationSelection, NavigationChange};
 
 #[cfg(feature = "textbox")]
 pub use text_box::{TextBox, TextBoxBuilder, TextBoxFlags};
@@ -104,8 +176,11 @@ pub use rich_text_box::*;
 #[cfg(feature = "rich-textbox")]
 pub use rich_label::*;
 
+#[cfg(feature = "log-view")]
+pub use log_view::{LogView, LogViewBuilder, LogViewFlags, LogLevel, LogSender};
+
 #[cfg(feature = "status-bar")]
-pub use status_bar::{StatusBar, StatusBarBuilder};
+pub use status_bar::{StatusBar, StatusBarBuilder, StatusBarPart};
 
 #[cfg(feature = "tooltip")]
 pub use tooltip::{Tooltip, TooltipBuilder, TooltipIcon};
@@ -127,9 +202,27 @@ pub use timer::{Timer, TimerBuilder};
 #[allow(deprecated)]
 pub use animation_timer::{AnimationTimer, AnimationTimerBuilder};
 
+#[cfg(feature = "animation-timer")]
+pub(crate) use animation_timer::{animation_tick, animation_end};
+
 #[cfg(feature = "notice")]
 pub use notice::{Notice, NoticeSender, NoticeBuilder};
 
+#[cfg(feature = "network-listener")]
+pub use network_listener::{NetworkListener, NetworkListenerBuilder};
+
+#[cfg(feature = "network-listener")]
+pub(crate) use network_listener::{NETWORK_ADDRESS_CHANGED, NETWORK_CONNECTED, NETWORK_DISCONNECTED};
+
+#[cfg(feature = "process-watcher")]
+pub use process_watcher::{ProcessWatcher, ProcessWatcherBuilder};
+
+#[cfg(feature = "idle-watcher")]
+pub use idle_watcher::{IdleWatcher, IdleWatcherBuilder};
+
+#[cfg(feature = "file-operation")]
+pub use file_operation::{FileOperation, FileOperationBuilder, FileOperationFlags};
+
 #[cfg(feature = "combobox")]
 pub use combo_box::{ComboBox, ComboBoxFlags, ComboBoxBuilder};
 
@@ -137,7 +230,13 @@ pub use combo_box::{ComboBox, ComboBoxFlags, ComboBoxBuilder};
 pub use list_box::{ListBox, ListBoxFlags, ListBoxBuilder};
 
 #[cfg(feature = "datetime-picker")]
-pub use date_picker::{DatePicker, DatePickerValue, DatePickerFlags, DatePickerBuilder};
+pub use date_picker::{DatePicker, DatePickerValue, DateTimeValue, DatePickerFlags, DatePickerBuilder};
+
+#[cfg(feature = "datetime-picker")]
+pub(crate) use date_picker::{dtp_time_key, dtp_store_time_key, dtp_load_time_key, days_from_civil, civil_from_days};
+
+#[cfg(feature = "date-range-picker")]
+pub use date_range_picker::{DateRangePicker, DateRangePickerBuilder, DateRangePickerFlags, DateRangePreset};
 
 #[cfg(feature = "progress-bar")]
 pub use progress_bar::{ProgressBar, ProgressBarState, ProgressBarFlags, ProgressBarBuilder};
@@ -158,7 +257,10 @@ pub use tray_notification::{TrayNotificationFlags, TrayNotification, TrayNotific
 pub use message_window::{MessageWindow, MessageWindowBuilder};
 
 #[cfg(feature = "list-view")]
-pub use list_view::{ListView, ListViewStyle, ListViewBuilder, ListViewFlags, ListViewExFlags, InsertListViewItem, ListViewItem, InsertListViewColumn, ListViewColumn, ListViewColumnSortArrow, ListViewColumnFlags};
+pub use list_view::{ListView, ListViewStyle, ListViewBuilder, ListViewFlags, ListViewExFlags, InsertListViewItem, ListViewItem, InsertListViewColumn, ListViewColumn, ListViewColumnSortArrow, ListViewColumnFlags, ListViewColumnAutoSize};
+
+#[cfg(all(feature="list-view", feature="clipboard"))]
+pub use list_view::ListViewCopyFormat;
 
 #[cfg(all(feature="list-view", feature="image-list"))]
 pub use list_view::ListViewImageListType;
@@ -169,8 +271,59 @@ pub use number_select::{NumberSelect, NumberSelectBuilder, NumberSelectFlags, Nu
 #[cfg(feature = "extern-canvas")]
 pub use extern_canvas::{ExternCanvas, ExternCanvasBuilder, ExternCanvasFlags};
 
+#[cfg(feature = "extern-canvas")]
+pub use canvas_group::{CanvasGroup, CanvasIoState, CanvasInput, DragButton, Modifiers, OrbitCamera};
+
+#[cfg(feature = "extern-canvas")]
+pub use viewport::{Viewport2D, Viewport2DBuilder};
+
+#[cfg(feature = "extern-canvas")]
+pub use ruler::{Ruler, RulerBuilder, RulerFlags, RulerOrientation};
+
+#[cfg(feature = "extern-canvas")]
+pub(crate) use ruler::RULER_GUIDES_CHANGED;
+
+#[cfg(feature = "rubber-band")]
+pub use rubber_band::{RubberBandSelector, RubberBandSelectorBuilder, SelectionMode, hit_test_point, hit_test_rect};
+
+#[cfg(feature = "rubber-band")]
+pub(crate) use rubber_band::{RUBBER_BAND_SELECT, RUBBER_BAND_CANCEL};
+
+#[cfg(feature = "design-surface")]
+pub use design_surface::{DesignSurface, DesignSurfaceBuilder};
+
+#[cfg(feature = "design-surface")]
+pub(crate) use design_surface::DESIGN_GEOMETRY_CHANGED;
+
+#[cfg(feature = "drag-drop")]
+pub use drop_target::{DropTarget, DropTargetBuilder, DragData, DropText, DragKeys, DropEffect};
+
+#[cfg(feature = "drag-drop")]
+pub(crate) use drop_target::{DroppedFiles, current_drag_keys, DRAG_ENTER, DRAG_OVER, DRAG_LEAVE, DROP_FILES, DROP_TEXT};
+
+#[cfg(feature = "window-thumbnail")]
+pub use window_thumbnail::{WindowThumbnail, WindowThumbnailBuilder};
+
+#[cfg(feature = "zoom-view")]
+pub use zoom_view::{ZoomView, ZoomViewBuilder, ZoomViewFlags};
+
+#[cfg(feature = "eyedropper")]
+pub use eyedropper::{Eyedropper, EyedropperBuilder};
+
+#[cfg(feature = "eyedropper")]
+pub(crate) use eyedropper::COLOR_PICK_CANCELLED;
+
+#[cfg(feature = "printing")]
+pub use print_preview::{PrintPreview, PrintPreviewBuilder, PrintPreviewFlags};
+
 #[cfg(feature = "frame")]
-pub use frame::{Frame, FrameBuilder, FrameFlags};
+pub use frame::{Frame, FrameBuilder, FrameFlags, FrameBorder};
+
+#[cfg(feature = "group-box")]
+pub use group_box::{GroupBox, GroupBoxBuilder, GroupBoxFlags};
+
+#[cfg(feature = "file-picker")]
+pub use file_picker::FilePicker;
 
 #[cfg(feature = "scroll-bar")]
 pub use scroll_bar::{ScrollBar, ScrollBarBuilder, ScrollBarFlags};
@@ -178,4 +331,18 @@ pub use scroll_bar::{ScrollBar, ScrollBarBuilder, ScrollBarFlags};
 #[cfg(feature = "plotting")]
 pub use self::plotters::{Plotters, PlottersBuilder, PlottersDrawingArea, PlottersBackend, PlottersError};
 
+#[cfg(feature = "plotting-pdf")]
+pub use self::plotters::PdfBackend;
+
+#[cfg(feature = "dyn-control")]
+pub use dyn_control::{ControlType, ControlProps, DynControl, create_control};
+
+#[cfg(feature = "dyn-control")]
+pub use control_properties::{ControlProperties, PropertyInfo, PropertyKind, PropertyValue, serialize_properties, deserialize_properties};
+
+#[cfg(feature = "dyn-control")]
+pub(crate) use control_properties::{escape, unescape};
+
+pub use control_builder::ControlBuilder;
+
 pub use handle_from_control::*;
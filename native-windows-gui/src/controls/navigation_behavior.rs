@@ -0,0 +1,375 @@
+/*!
+    Standard keyboard navigation for custom list-like controls
+*/
+use crate::{Event, EventData, keys};
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+
+/// How many items can be selected in a `NavigationBehavior`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NavigationSelection {
+    /// Only the focused item is selected
+    Single,
+
+    /// Shift extends the selection from the anchor, Ctrl moves the focus without changing the selection and Ctrl+Space toggles the focused item
+    Multiple,
+}
+
+/// A change of focus or selection returned by `NavigationBehavior`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct NavigationChange {
+    /// The focused item before the input
+    pub previous: Option<usize>,
+
+    /// The focused item after the input. The control should scroll it into view.
+    pub focus: Option<usize>,
+
+    /// True if the selected items changed
+    pub selection_changed: bool,
+}
+
+/**
+    Implements the standard keyboard navigation of list controls for custom controls (owner drawn lists, item grids in an `ExternCanvas`, etc).
+
+    The behavior tracks the focused item, the selection and the selection anchor of a control with `count` items laid out
+    in rows of `columns` items (1 for a list). Forward the events of the control to `process_event`:
+      * Up, Down, Left, Right: move the focus by one item (Left and Right are ignored in a list)
+      * Home, End: focus the first or the last item
+      * Page Up, Page Down: move the focus by `page_size` rows
+      * Typed characters: focus the next item starting with the typed text (type-ahead search). The search text is reset after one second without typing.
+      * Shift: extend the selection from the anchor (`Multiple` selection)
+      * Ctrl: move the focus without changing the selection, Ctrl+Space toggles the focused item, Ctrl+A selects everything (`Multiple` selection)
+
+    Mouse clicks can be forwarded to `click` to update the selection the same way.
+
+    The behavior does not draw anything. When the returned `NavigationChange` is not `None`, the control redraws the
+    items and scrolls the focused item into view.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    struct ItemGrid {
+        canvas: nwg::ExternCanvas,
+        items: Vec<String>,
+        navigation: nwg::NavigationBehavior,
+    }
+
+    impl ItemGrid {
+        fn init(&self) {
+            self.navigation.set_count(self.items.len());
+            self.navigation.set_columns(4);
+            self.navigation.set_selection_mode(nwg::NavigationSelection::Multiple);
+        }
+
+        fn events(&self, evt: nwg::Event, evt_data: &nwg::EventData) {
+            let label = |index: usize| self.items[index].clone();
+            if let Some(change) = self.navigation.process_event(evt, evt_data, label) {
+                // Redraw the grid and scroll the focused item into view
+                println!("{:?}", change.focus);
+            }
+        }
+    }
+    ```
+*/
+pub struct NavigationBehavior {
+    count: Cell<usize>,
+    columns: Cell<usize>,
+    page_size: Cell<usize>,
+    mode: Cell<NavigationSelection>,
+    focus: Cell<Option<usize>>,
+    anchor: Cell<Option<usize>>,
+    selection: RefCell<Vec<bool>>,
+    search: RefCell<String>,
+    last_search: Cell<Option<Instant>>,
+}
+
+impl NavigationBehavior {
+
+    /// Time without typing after which the type-ahead search text is reset
+    const SEARCH_TIMEOUT: Duration = Duration::from_millis(1000);
+
+    pub fn new() -> NavigationBehavior {
+        Default::default()
+    }
+
+    /// Return the number of items
+    pub fn count(&self) -> usize {
+        self.count.get()
+    }
+
+    /// Set the number of items. The focus, the anchor and the selection past the new count are cleared.
+    pub fn set_count(&self, count: usize) {
+        self.count.set(count);
+        self.selection.borrow_mut().resize(count, false);
+
+        if self.focus.get().map(|f| f >= count).unwrap_or(false) {
+            self.focus.set(None);
+        }
+
+        if self.anchor.get().map(|a| a >= count).unwrap_or(false) {
+            self.anchor.set(None);
+        }
+    }
+
+    /// Return the number of items per row
+    pub fn columns(&self) -> usize {
+        self.columns.get()
+    }
+
+    /// Set the number of items per row. Use 1 for a list.
+    pub fn set_columns(&self, columns: usize) {
+        self.columns.set(columns.max(1));
+    }
+
+    /// Return the number of rows moved by Page Up and Page Down
+    pub fn page_size(&self) -> usize {
+        self.page_size.get()
+    }
+
+    /// Set the number of rows moved by Page Up and Page Down. Usually the number of rows visible in the control.
+    pub fn set_page_size(&self, rows: usize) {
+        self.page_size.set(rows.max(1));
+    }
+
+    /// Return the selection mode
+    pub fn selection_mode(&self) -> NavigationSelection {
+        self.mode.get()
+    }
+
+    /// Set the selection mode. The selection is cleared.
+    pub fn set_selection_mode(&self, mode: NavigationSelection) {
+        self.mode.set(mode);
+        self.clear_selection();
+    }
+
+    /// Return the focused item
+    pub fn focus(&self) -> Option<usize> {
+        self.focus.get()
+    }
+
+    /// Focus an item without changing the selection. Values out of range clear the focus.
+    pub fn set_focus(&self, index: Option<usize>) {
+        self.focus.set(index.filter(|&i| i < self.count.get()));
+    }
+
+    /// Return the selection anchor. Extending the selection selects the items between the anchor and the focus.
+    pub fn anchor(&self) -> Option<usize> {
+        self.anchor.get()
+    }
+
+    /// Return true if the item is selected
+    pub fn selected(&self, index: usize) -> bool {
+        self.selection.borrow().get(index).cloned().unwrap_or(false)
+    }
+
+    /// Return the selected items in ascending order
+    pub fn selection(&self) -> Vec<usize> {
+        self.selection.borrow().iter()
+            .enumerate()
+            .filter(|(_, &s)| s)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Select or unselect an item. In `Single` mode, selecting an item unselects the others.
+    pub fn set_selected(&self, index: usize, selected: bool) {
+        if index >= self.count.get() {
+            return;
+        }
+
+        if selected && self.mode.get() == NavigationSelection::Single {
+            self.clear_selection();
+        }
+
+        self.selection.borrow_mut()[index] = selected;
+    }
+
+    /// Select every item. Does nothing in `Single` mode.
+    pub fn select_all(&self) {
+        if self.mode.get() == NavigationSelection::Multiple {
+            for s in self.selection.borrow_mut().iter_mut() {
+                *s = true;
+            }
+        }
+    }
+
+    /// Unselect every item
+    pub fn clear_selection(&self) {
+        for s in self.selection.borrow_mut().iter_mut() {
+            *s = false;
+        }
+    }
+
+    /**
+        Handle the keyboard events of the control (`OnKeyPress` and `OnChar`). `label` must return the text of an item and is only called for the type-ahead search.
+        Return the change of focus and selection, or `None` if the event was not handled.
+    */
+    pub fn process_event<F>(&self, evt: Event, evt_data: &EventData, label: F) -> Option<NavigationChange>
+        where F: Fn(usize) -> String
+    {
+        match (evt, evt_data) {
+            (Event::OnKeyPress, EventData::OnKey(key)) => self.key(*key),
+            (Event::OnChar, EventData::OnChar(c)) => self.type_ahead(*c, label),
+            _ => None
+        }
+    }
+
+    /// Handle a key press. `key` is a virtual key code. Return `None` if the key is not a navigation key.
+    pub fn key(&self, key: u32) -> Option<NavigationChange> {
+        let count = self.count.get();
+        if count == 0 {
+            return None;
+        }
+
+        let (shift, ctrl) = modifiers();
+        let columns = self.columns.get();
+        let page = self.page_size.get() * columns;
+        let current = self.focus.get();
+        let focus = current.unwrap_or(0);
+        let last = count - 1;
+
+        let target = match key {
+            keys::UP => focus.saturating_sub(columns),
+            keys::DOWN => (focus + columns).min(last),
+            keys::LEFT if columns > 1 => focus.saturating_sub(1),
+            keys::RIGHT if columns > 1 => (focus + 1).min(last),
+            keys::HOME => 0,
+            keys::END => last,
+            keys::PRIOR => focus.saturating_sub(page),
+            keys::NEXT => (focus + page).min(last),
+            keys::SPACE if ctrl && self.mode.get() == NavigationSelection::Multiple => {
+                let selected = !self.selected(focus);
+                self.selection.borrow_mut()[focus] = selected;
+                self.focus.set(Some(focus));
+                self.anchor.set(Some(focus));
+                return Some(NavigationChange { previous: current, focus: Some(focus), selection_changed: true });
+            },
+            keys::_A if ctrl && self.mode.get() == NavigationSelection::Multiple => {
+                self.select_all();
+                return Some(NavigationChange { previous: current, focus: current, selection_changed: true });
+            },
+            _ => { return None; }
+        };
+
+        // With no focused item, the first navigation key focuses the first item
+        let target = if current.is_none() { 0 } else { target };
+
+        Some(self.move_focus(target, shift, ctrl))
+    }
+
+    /// Handle a mouse click on an item. The modifiers are read from the keyboard state.
+    pub fn click(&self, index: usize) -> Option<NavigationChange> {
+        if index >= self.count.get() {
+            return None;
+        }
+
+        let (shift, ctrl) = modifiers();
+        if ctrl && !shift && self.mode.get() == NavigationSelection::Multiple {
+            let previous = self.focus.get();
+            let selected = !self.selected(index);
+            self.selection.borrow_mut()[index] = selected;
+            self.focus.set(Some(index));
+            self.anchor.set(Some(index));
+            return Some(NavigationChange { previous, focus: Some(index), selection_changed: true });
+        }
+
+        Some(self.move_focus(index, shift, false))
+    }
+
+    /// Handle a character typed by the user. Return `None` if no item starts with the search text.
+    fn type_ahead<F: Fn(usize) -> String>(&self, c: char, label: F) -> Option<NavigationChange> {
+        let count = self.count.get();
+        // Ctrl+Space also sends a space character
+        if count == 0 || c.is_control() || modifiers().1 {
+            return None;
+        }
+
+        let now = Instant::now();
+        let expired = self.last_search.get().map(|t| now.duration_since(t) > Self::SEARCH_TIMEOUT).unwrap_or(true);
+        self.last_search.set(Some(now));
+
+        let mut search = self.search.borrow_mut();
+        if expired {
+            search.clear();
+        }
+
+        search.extend(c.to_lowercase());
+
+        // A new search starts after the focused item so that typing the same letter cycles through the matching items.
+        // A longer search text starts at the focused item so that it is kept if it still matches.
+        let focus = self.focus.get();
+        let start = match (focus, search.chars().count()) {
+            (Some(f), 1) => f + 1,
+            (Some(f), _) => f,
+            (None, _) => 0,
+        };
+
+        let found = (0..count)
+            .map(|i| (start + i) % count)
+            .find(|&i| label(i).to_lowercase().starts_with(search.as_str()))?;
+
+        drop(search);
+
+        Some(self.move_focus(found, false, false))
+    }
+
+    /// Move the focus to `target` and update the selection
+    fn move_focus(&self, target: usize, extend: bool, keep_selection: bool) -> NavigationChange {
+        let previous = self.focus.get();
+        self.focus.set(Some(target));
+
+        let mode = self.mode.get();
+        if keep_selection && mode == NavigationSelection::Multiple {
+            return NavigationChange { previous, focus: Some(target), selection_changed: false };
+        }
+
+        let before = self.selection.borrow().clone();
+
+        match (mode, extend) {
+            (NavigationSelection::Multiple, true) => {
+                let anchor = self.anchor.get().unwrap_or(target);
+                let (first, last) = (anchor.min(target), anchor.max(target));
+                self.anchor.set(Some(anchor));
+                for (i, s) in self.selection.borrow_mut().iter_mut().enumerate() {
+                    *s = i >= first && i <= last;
+                }
+            },
+            _ => {
+                self.anchor.set(Some(target));
+                for (i, s) in self.selection.borrow_mut().iter_mut().enumerate() {
+                    *s = i == target;
+                }
+            }
+        }
+
+        let selection_changed = *self.selection.borrow() != before;
+        NavigationChange { previous, focus: Some(target), selection_changed }
+    }
+
+}
+
+impl Default for NavigationBehavior {
+    fn default() -> NavigationBehavior {
+        NavigationBehavior {
+            count: Cell::new(0),
+            columns: Cell::new(1),
+            page_size: Cell::new(10),
+            mode: Cell::new(NavigationSelection::Single),
+            focus: Cell::new(None),
+            anchor: Cell::new(None),
+            selection: RefCell::new(Vec::new()),
+            search: RefCell::new(String::new()),
+            last_search: Cell::new(None),
+        }
+    }
+}
+
+/// Return the state of the shift and the control keys
+fn modifiers() -> (bool, bool) {
+    use winapi::um::winuser::{GetKeyState, VK_SHIFT, VK_CONTROL};
+
+    unsafe {
+        (GetKeyState(VK_SHIFT) < 0, GetKeyState(VK_CONTROL) < 0)
+    }
+}
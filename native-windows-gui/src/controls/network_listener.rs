@@ -0,0 +1,206 @@
+use winapi::um::winnt::HANDLE;
+use winapi::shared::minwindef::{WPARAM, LPARAM};
+use winapi::shared::windef::HWND;
+use super::control_handle::ControlHandle;
+use crate::win32::{window_helper as wh, window::build_notice};
+use crate::NwgError;
+use std::{ptr, mem, thread};
+
+const NOT_BOUND: &'static str = "NetworkListener is not yet bound to a winapi object";
+const BAD_HANDLE: &'static str = "INTERNAL ERROR: NetworkListener handle is not Notice!";
+
+/// Values sent in the lparam of `NWG_NETWORK_CHANGE`
+pub(crate) const NETWORK_ADDRESS_CHANGED: LPARAM = 0;
+pub(crate) const NETWORK_CONNECTED: LPARAM = 1;
+pub(crate) const NETWORK_DISCONNECTED: LPARAM = 2;
+
+/**
+An invisible component that watches the network configuration of the system.
+
+The listener waits for the changes in a background thread and raises the events on the GUI thread.
+After every change of the ip address table, the listener checks if a route to the internet exists and
+raises `OnNetworkConnected` or `OnNetworkDisconnected` if the connectivity changed.
+
+A network listener must have a parent window. The listener stops when it is dropped.
+
+Requires the `network-listener` feature.
+
+**Builder parameters:**
+    * `parent`:     **Required.** The listener parent container that will receive the events. Should be a top level window
+
+**Control events:**
+    * `OnNetworkConnected`: When the system gained a route to the internet
+    * `OnNetworkDisconnected`: When the system lost its route to the internet
+    * `OnNetworkAddressChanged`: When an ip address of the system was added, removed or changed
+
+## Example
+
+```rust
+use native_windows_gui as nwg;
+fn build_listener(listener: &mut nwg::NetworkListener, window: &nwg::Window) {
+    nwg::NetworkListener::builder()
+        .parent(window)
+        .build(listener);
+}
+```
+*/
+pub struct NetworkListener {
+    pub handle: ControlHandle,
+    stop_event: HANDLE,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl NetworkListener {
+
+    pub fn builder() -> NetworkListenerBuilder {
+        NetworkListenerBuilder {
+            parent: None
+        }
+    }
+
+    /// Return true if the system currently has a route to the internet
+    pub fn connected(&self) -> bool {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.handle.notice().expect(BAD_HANDLE);
+        network_connected()
+    }
+
+}
+
+impl Default for NetworkListener {
+    fn default() -> NetworkListener {
+        NetworkListener {
+            handle: ControlHandle::NoHandle,
+            stop_event: ptr::null_mut(),
+            thread: None
+        }
+    }
+}
+
+impl PartialEq for NetworkListener {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for NetworkListener {}
+
+impl Drop for NetworkListener {
+    fn drop(&mut self) {
+        use winapi::um::synchapi::SetEvent;
+        use winapi::um::handleapi::CloseHandle;
+
+        if self.stop_event.is_null() {
+            return;
+        }
+
+        unsafe { SetEvent(self.stop_event); }
+
+        if let Some(thread) = self.thread.take() {
+            drop(thread.join());
+        }
+
+        unsafe { CloseHandle(self.stop_event); }
+
+        self.handle.destroy();
+    }
+}
+
+pub struct NetworkListenerBuilder {
+    parent: Option<ControlHandle>
+}
+
+impl NetworkListenerBuilder {
+
+    pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> NetworkListenerBuilder {
+        self.parent = Some(p.into());
+        self
+    }
+
+    pub fn build(self, out: &mut NetworkListener) -> Result<(), NwgError> {
+        use winapi::um::synchapi::CreateEventW;
+
+        let parent = match self.parent {
+            Some(p) => match p.hwnd() {
+                Some(handle) => Ok(handle),
+                None => Err(NwgError::control_create("Wrong parent type"))
+            },
+            None => Err(NwgError::no_parent("NetworkListener"))
+        }?;
+
+        *out = Default::default();
+
+        let stop_event = unsafe { CreateEventW(ptr::null_mut(), 1, 0, ptr::null()) };
+        if stop_event.is_null() {
+            return Err(NwgError::control_create("Failed to create the NetworkListener stop event"));
+        }
+
+        out.handle = build_notice(parent);
+        out.stop_event = stop_event;
+
+        let (_, id) = out.handle.notice().unwrap();
+        let (hwnd, stop_event) = (parent as usize, stop_event as usize);
+        out.thread = Some(thread::spawn(move || unsafe { listen(hwnd, id, stop_event) }));
+
+        Ok(())
+    }
+
+}
+
+/// Return true if a route exists to a public address
+fn network_connected() -> bool {
+    use winapi::um::iphlpapi::GetBestInterface;
+    use winapi::shared::winerror::NO_ERROR;
+
+    // 8.8.8.8. Any public address works, no packet is sent.
+    let public_address = 0x08080808;
+    let mut index = 0;
+    unsafe { GetBestInterface(public_address, &mut index) == NO_ERROR }
+}
+
+/// The listener thread. Waits for ip address changes until the stop event is set.
+unsafe fn listen(hwnd: usize, id: u32, stop_event: usize) {
+    use winapi::um::iphlpapi::{NotifyAddrChange, CancelIPChangeNotify};
+    use winapi::um::synchapi::{CreateEventW, WaitForMultipleObjects};
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::minwinbase::OVERLAPPED;
+    use winapi::um::winbase::{WAIT_OBJECT_0, INFINITE};
+    use winapi::shared::winerror::ERROR_IO_PENDING;
+
+    let change_event = CreateEventW(ptr::null_mut(), 0, 0, ptr::null());
+    if change_event.is_null() {
+        return;
+    }
+
+    let send = |value: LPARAM| {
+        wh::post_message(hwnd as HWND, wh::NWG_NETWORK_CHANGE, id as WPARAM, value);
+    };
+
+    let mut connected = network_connected();
+    let handles = [change_event, stop_event as HANDLE];
+
+    loop {
+        let mut overlapped: OVERLAPPED = mem::zeroed();
+        overlapped.hEvent = change_event;
+
+        let mut notify_handle = ptr::null_mut();
+        if NotifyAddrChange(&mut notify_handle, &mut overlapped) != ERROR_IO_PENDING {
+            break;
+        }
+
+        if WaitForMultipleObjects(2, handles.as_ptr(), 0, INFINITE) != WAIT_OBJECT_0 {
+            CancelIPChangeNotify(&mut overlapped);
+            break;
+        }
+
+        send(NETWORK_ADDRESS_CHANGED);
+
+        let now_connected = network_connected();
+        if now_connected != connected {
+            connected = now_connected;
+            send(if connected { NETWORK_CONNECTED } else { NETWORK_DISCONNECTED });
+        }
+    }
+
+    CloseHandle(change_event);
+}
@@ -1,19 +1,30 @@
 use super::control_handle::ControlHandle;
 use crate::win32::{window_helper as wh, window::build_notice};
 use crate::NwgError;
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
 
 
 const NOT_BOUND: &'static str = "Notice is not yet bound to a winapi object";
 const UNUSABLE_NOTICE: &'static str = "Notice parent window was freed";
 const BAD_HANDLE: &'static str = "INTERNAL ERROR: Notice handle is not Notice!";
 
+type NoticeValue = Box<dyn Any + Send>;
+
+lazy_static! {
+    /// The values sent with `NoticeSender::send`, by notice id. The queue of a notice lives as long as the notice.
+    static ref NOTICE_VALUES: Mutex<HashMap<u32, VecDeque<NoticeValue>>> = Mutex::new(HashMap::new());
+}
+
 /**
 An invisible component that can be triggered by other thread.
 
-A notice object does not send data between threads. Rust has already plenty of way to do this.
-The notice object only serve to "wake up" the GUI thread.
+The notice object serves to "wake up" the GUI thread. A background thread can also deliver values to the GUI thread
+with `NoticeSender::send`. The values are then read with `Notice::try_recv` in the `OnNotice` handler.
 
 A notice must have a parent window. If the parent is destroyed before the notice, the notice becomes invalid.
+The parent can be a `MessageWindow`, so a background application can be woken up without a visible window.
 
 Requires the `notice` feature. 
 
@@ -44,6 +55,25 @@ fn notice(noticer: &nwg::Notice) {
 
 ```
 
+```rust
+use native_windows_gui as nwg;
+use std::thread;
+
+fn compute(noticer: &nwg::Notice) {
+    let sender = noticer.sender();
+    thread::spawn(move || {
+        let result: u64 = (1..=20).product();
+        sender.send(result);
+    });
+}
+
+fn on_notice(noticer: &nwg::Notice, label: &nwg::Label) {
+    while let Some(result) = noticer.try_recv::<u64>() {
+        label.set_text(&format!("Result: {}", result));
+    }
+}
+```
+
 */
 #[derive(Default, PartialEq, Eq)]
 pub struct Notice {
@@ -107,10 +137,32 @@ impl Notice {
         }
     }
 
+    /**
+        Returns the oldest value of type `T` sent with `NoticeSender::send`, or `None` if there is no value of this type.
+        The values of the other types are kept in the queue. Each value sends one `OnNotice` event, so a handler
+        can read one value per event or empty the queue in a loop.
+    */
+    pub fn try_recv<T: Any + Send>(&self) -> Option<T> {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        let (_, id) = self.handle.notice().expect(BAD_HANDLE);
+
+        let mut values = NOTICE_VALUES.lock().unwrap();
+        let queue = values.get_mut(&id)?;
+        let index = queue.iter().position(|v| v.is::<T>())?;
+
+        queue.remove(index)
+            .and_then(|v| v.downcast::<T>().ok())
+            .map(|v| *v)
+    }
+
 }
 
 impl Drop for Notice {
     fn drop(&mut self) {
+        if let ControlHandle::Notice(_, id) = &self.handle {
+            NOTICE_VALUES.lock().unwrap().remove(id);
+        }
+
         self.handle.destroy();
     }
 }
@@ -136,6 +188,20 @@ impl NoticeSender {
         }
     }
 
+    /// Send a value to the parent `Notice` and wake up its thread. The value is read with `Notice::try_recv`.
+    /// The value is dropped if the parent `Notice` was dropped.
+    pub fn send<T: Any + Send>(&self, value: T) {
+        {
+            let mut values = NOTICE_VALUES.lock().unwrap();
+            match values.get_mut(&self.id) {
+                Some(queue) => queue.push_back(Box::new(value)),
+                None => { return; }
+            }
+        }
+
+        self.notice();
+    }
+
 }
 
 
@@ -160,6 +226,9 @@ impl NoticeBuilder {
         }?;
 
         out.handle = build_notice(parent);
+
+        let (_, id) = out.handle.notice().expect(BAD_HANDLE);
+        NOTICE_VALUES.lock().unwrap().insert(id, VecDeque::new());
         
         Ok(())
     }
@@ -3,9 +3,14 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use crate::win32::window_helper as wh;
+use crate::win32::window::nwg_class;
 use crate::win32::base_helper::check_hwnd;
 use crate::{NwgError, Font, RawEventHandler, bind_raw_event_handler_inner, unbind_raw_event_handler};
 use super::{ControlBase, ControlHandle, TextInput, Button, ButtonFlags, TextInputFlags};
+use super::control_builder::BuilderState;
+
+#[cfg(feature="winnls")]
+use crate::{Locale, locale_format_number};
 
 const NOT_BOUND: &'static str = "UpDown is not yet bound to a winapi object";
 const BAD_HANDLE: &'static str = "INTERNAL ERROR: UpDown handle is not HWND!";
@@ -44,6 +49,18 @@ impl NumberSelectData {
         }
     }
 
+    /// Format the value using the decimal separator and the digit grouping of `locale`.
+    /// Falls back to `formatted_value` if the locale cannot format the value.
+    #[cfg(feature="winnls")]
+    pub fn formatted_value_locale(&self, locale: &Locale) -> String {
+        let formatted = match self {
+            NumberSelectData::Int{ value, ..} => locale_format_number(*value as f64, Some(0), Some(locale)),
+            NumberSelectData::Float{ value, decimals, ..} => locale_format_number(*value, Some(*decimals as u32), Some(locale)),
+        };
+
+        formatted.unwrap_or_else(|_| self.formatted_value())
+    }
+
     pub fn decrease(&mut self) {
         match self {
             NumberSelectData::Int{ value, step, min, ..} => {
@@ -97,10 +114,14 @@ Requires the `number-select` feature.
   * `enabled`:  If the number select can be used by the user. It also has a grayed out look if disabled.
   * `flags`:    A combination of the NumberSelectFlags values.
   * `font`:     The font used for the number select text
+  * `locale`:   The locale used to format the value. Requires the `winnls` feature.
 
 **Control events:**
   * `MousePress(_)`: Generic mouse press events on the button
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
 
 ```rust
 use native_windows_gui as nwg;
@@ -120,7 +141,10 @@ pub struct NumberSelect {
     edit: TextInput,
     btn_up: Button,
     btn_down: Button,
-    handler: Option<RawEventHandler>
+    handler: Option<RawEventHandler>,
+
+    #[cfg(feature="winnls")]
+    locale: Rc<RefCell<Option<Locale>>>,
 }
 
 impl NumberSelect {
@@ -133,7 +157,12 @@ impl NumberSelect {
             enabled: true,
             flags: None,
             font: None,
-            parent: None
+            parent: None,
+
+            #[cfg(feature="winnls")]
+            locale: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -147,7 +176,23 @@ impl NumberSelect {
     /// See [NumberSelectData](enum.NumberSelectData.html) for the possible values
     pub fn set_data(&self, v: NumberSelectData) {
         *self.data.borrow_mut() = v;
-        self.edit.set_text(&v.formatted_value());
+        self.edit.set_text(&self.display_value(&v));
+    }
+
+    /// Returns the locale used to format the value. `None` if the value is not formatted using a locale.
+    #[cfg(feature="winnls")]
+    pub fn locale(&self) -> Option<Locale> {
+        self.locale.borrow().clone()
+    }
+
+    /// Sets the locale used to format the value. Also update the value display.
+    /// Use `None` to display the value without any locale formatting.
+    #[cfg(feature="winnls")]
+    pub fn set_locale(&self, locale: Option<&Locale>) {
+        *self.locale.borrow_mut() = locale.cloned();
+
+        let data = self.data.borrow().clone();
+        self.edit.set_text(&self.display_value(&data));
     }
 
     /// Returns the font of the control
@@ -230,7 +275,7 @@ impl NumberSelect {
 
     /// Winapi class name used during control creation
     pub fn class_name(&self) -> &'static str {
-        "NativeWindowsGuiWindow"
+        nwg_class("NativeWindowsGuiWindow")
     }
 
     /// Winapi base flags used during window creation
@@ -244,6 +289,17 @@ impl NumberSelect {
         WS_CHILD | WS_BORDER | WS_CLIPCHILDREN
     }
 
+    fn display_value(&self, data: &NumberSelectData) -> String {
+        #[cfg(feature="winnls")]
+        {
+            if let Some(locale) = self.locale.borrow().as_ref() {
+                return data.formatted_value_locale(locale);
+            }
+        }
+
+        data.formatted_value()
+    }
+
 }
 
 impl Drop for NumberSelect {
@@ -265,7 +321,12 @@ pub struct NumberSelectBuilder<'a> {
     enabled: bool,
     flags: Option<NumberSelectFlags>,
     font: Option<&'a Font>,
-    parent: Option<ControlHandle>
+    parent: Option<ControlHandle>,
+
+    #[cfg(feature="winnls")]
+    locale: Option<&'a Locale>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a> NumberSelectBuilder<'a> {
@@ -369,13 +430,24 @@ impl<'a> NumberSelectBuilder<'a> {
         self
     }
 
+    #[cfg(feature="winnls")]
+    pub fn locale(mut self, locale: Option<&'a Locale>) -> NumberSelectBuilder<'a> {
+        self.locale = locale;
+        self
+    }
+
     pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> NumberSelectBuilder<'a> {
         self.parent = Some(p.into());
         self
     }
 
+    pub fn name(mut self, name: &str) -> NumberSelectBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut NumberSelect) -> Result<(), NwgError> {
-        let flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
         let (btn_flags, text_flags) = if flags & WS_TABSTOP == WS_TABSTOP {
             (ButtonFlags::VISIBLE | ButtonFlags::TAB_STOP, TextInputFlags::VISIBLE | TextInputFlags::TAB_STOP)
         } else {
@@ -397,8 +469,14 @@ impl<'a> NumberSelectBuilder<'a> {
 
         *out = NumberSelect::default();
         *out.data.borrow_mut() = self.data;
+
+        #[cfg(feature="winnls")]
+        {
+            *out.locale.borrow_mut() = self.locale.cloned();
+        }
         
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .ex_flags(WS_EX_CONTROLPARENT)
@@ -409,7 +487,7 @@ impl<'a> NumberSelectBuilder<'a> {
             .build()?;
 
         TextInput::builder()
-            .text(&self.data.formatted_value())
+            .text(&out.display_value(&self.data))
             .size((w-19, h))
             .parent(&out.handle)
             .flags(text_flags)
@@ -448,6 +526,9 @@ impl<'a> NumberSelectBuilder<'a> {
         let minus_button = out.btn_down.handle.clone();
         let text_handle = out.edit.handle.clone();
 
+        #[cfg(feature="winnls")]
+        let handler_locale = out.locale.clone();
+
         let handler = bind_raw_event_handler_inner(&out.handle, 0x4545, move |_hwnd, msg, w, l| {
             use winapi::shared::windef::HWND;
             use winapi::um::winuser::{WM_COMMAND, BN_CLICKED};
@@ -457,21 +538,30 @@ impl<'a> NumberSelectBuilder<'a> {
                 WM_COMMAND => {
                     let handle = ControlHandle::Hwnd(l as HWND);
                     let message = HIWORD(w as u32) as u16;
-                    if message == BN_CLICKED && handle == plus_button {
-                        let mut data = handler_data.borrow_mut();
-                        data.increase();
+                    if message != BN_CLICKED {
+                        return None;
+                    }
 
-                        let handle = text_handle.hwnd().unwrap();
-                        let text = data.formatted_value();
-                        unsafe { wh::set_window_text(handle, &text); }
-                    } else if message == BN_CLICKED && handle == minus_button {
-                        let mut data = handler_data.borrow_mut();
+                    let mut data = handler_data.borrow_mut();
+                    if handle == plus_button {
+                        data.increase();
+                    } else if handle == minus_button {
                         data.decrease();
-
-                        let handle = text_handle.hwnd().unwrap();
-                        let text = data.formatted_value();
-                        unsafe { wh::set_window_text(handle, &text); }
+                    } else {
+                        return None;
                     }
+
+                    #[cfg(feature="winnls")]
+                    let text = match handler_locale.borrow().as_ref() {
+                        Some(locale) => data.formatted_value_locale(locale),
+                        None => data.formatted_value()
+                    };
+
+                    #[cfg(not(feature="winnls"))]
+                    let text = data.formatted_value();
+
+                    let handle = text_handle.hwnd().unwrap();
+                    unsafe { wh::set_window_text(handle, &text); }
                 },
                 
                 _ => {}
@@ -1,14 +1,19 @@
 use winapi::um::winuser::{WS_CHILD, WS_VISIBLE, WS_CLIPCHILDREN, WS_CLIPSIBLINGS};
 use crate::win32::base_helper::check_hwnd;
 use crate::win32::window_helper as wh;
+use crate::win32::window::nwg_class;
 use crate::NwgError;
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
 
 use plotters::prelude::DrawingArea;
 use plotters::coord::Shift;
 pub use crate::win32::plotters_d2d::{PlottersError, PlottersBackend};
 use std::ops::Deref;
 
+#[cfg(feature = "plotting-pdf")]
+pub use crate::win32::plotters_pdf::PdfBackend;
+
 const NOT_BOUND: &'static str = "Plotters control is not yet bound to a winapi object";
 const BAD_HANDLE: &'static str = "INTERNAL ERROR: Plotters control handle is not HWND!";
 
@@ -77,6 +82,8 @@ impl Plotters {
             position: (0, 0),
             ex_flags: 0,
             parent: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -87,6 +94,23 @@ impl Plotters {
         PlottersDrawingArea::new(self)
     }
 
+    /**
+        Export a chart to a PDF file at `path`, with a page the size of the control. `draw` receives a drawing area that writes to the document,
+        use the same drawing code as the control (ex: a function generic over the plotters `DrawingBackend`).
+
+        Requires the `plotting-pdf` feature.
+    */
+    #[cfg(feature = "plotting-pdf")]
+    pub fn export_pdf<P, F>(&self, path: P, draw: F) -> std::io::Result<()>
+        where P: AsRef<std::path::Path>,
+              F: FnOnce(&DrawingArea<&PdfBackend, Shift>)
+    {
+        let (width, height) = self.size();
+        let backend = PdfBackend::new(width, height);
+        draw(&(&backend).into());
+        backend.save(path)
+    }
+
     /// Return true if the control currently has the keyboard focus
     pub fn focus(&self) -> bool {
         let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
@@ -156,7 +180,7 @@ impl Plotters {
 
     /// Winapi class name used during control creation
     pub fn class_name(&self) -> &'static str {
-        "NWG_EXTERN_CANVAS"
+        nwg_class("NWG_EXTERN_CANVAS")
     }
 
     // Winapi base flags used during window creation
@@ -183,6 +207,8 @@ pub struct PlottersBuilder {
     size: (i32, i32),
     position: (i32, i32),
     ex_flags: u32,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl PlottersBuilder {
@@ -207,13 +233,19 @@ impl PlottersBuilder {
         self
     }
 
+    pub fn name(mut self, name: &str) -> PlottersBuilder {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut Plotters) -> Result<(), NwgError> {
         *out = Default::default();
         
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
-            .flags(out.flags())
+            .flags(self.state.flags(out.flags()))
             .ex_flags(self.ex_flags)
             .size(self.size)
             .position(self.position)
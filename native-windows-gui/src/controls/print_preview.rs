@@ -0,0 +1,665 @@
+use winapi::shared::windef::{HWND, HDC, POINT, RECT};
+use winapi::um::winuser::{WS_VISIBLE, WS_DISABLED, WS_CHILD, WS_TABSTOP};
+use crate::win32::{base_helper::check_hwnd, window_helper as wh};
+use crate::win32::window::nwg_class;
+use crate::resources::DocumentState;
+use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
+use crate::{NwgError, PrintDocument, RawEventHandler, unbind_raw_event_handler};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::{ptr, mem};
+
+const NOT_BOUND: &'static str = "PrintPreview is not yet bound to a winapi object";
+const BAD_HANDLE: &'static str = "INTERNAL ERROR: PrintPreview handle is not HWND!";
+
+/// The id of the raw event handler of the preview
+const PRINT_PREVIEW_ID: usize = 0x5050;
+
+/// The space around the page, in pixels
+const PAGE_SPACING: i32 = 16;
+
+/// The zoom multiplier applied by one step of the mouse wheel
+const ZOOM_STEP: f64 = 1.25;
+
+const MIN_ZOOM: f64 = 0.05;
+const MAX_ZOOM: f64 = 8.0;
+
+/// Hundredths of a millimeter in one inch
+const UNITS_PER_INCH: f64 = 2540.0;
+
+
+bitflags! {
+    /**
+        The print preview flags
+
+        * VISIBLE:  The preview is immediatly visible after creation
+        * DISABLED: The user cannot zoom, scroll or change the page
+        * TAB_STOP: The preview can be selected using tab navigation
+    */
+    pub struct PrintPreviewFlags: u32 {
+        const VISIBLE = WS_VISIBLE;
+        const DISABLED = WS_DISABLED;
+        const TAB_STOP = WS_TABSTOP;
+    }
+}
+
+/// The position of the page in the client area of the preview
+struct PageLayout {
+    /// The paper, in client coordinates
+    page: RECT,
+
+    /// The pixels per hundredth of a millimeter
+    scale: f64,
+
+    /// The largest scroll offset on each axis
+    max_scroll: (i32, i32),
+}
+
+/// The state shared with the raw event handler
+struct PreviewState {
+    document: Rc<DocumentState>,
+    page: Cell<usize>,
+
+    /// The zoom, where 1.0 displays the page at its real size. `None` fits the page in the control.
+    zoom: Cell<Option<f64>>,
+    show_margins: Cell<bool>,
+
+    /// The scroll offset of a page larger than the control, in pixels
+    scroll: Cell<(i32, i32)>,
+
+    /// The cursor position and the scroll offset when the user started to drag the page
+    drag: Cell<Option<(POINT, (i32, i32))>>,
+}
+
+impl PreviewState {
+
+    fn current_page(&self) -> usize {
+        self.page.get().min(self.document.page_count().saturating_sub(1))
+    }
+
+    /// Compute the position and the scale of the page in a client area, and clamp the scroll offset
+    fn layout(&self, client: &RECT, dpi: i32) -> PageLayout {
+        let (width, height) = self.document.page_size();
+        let (client_width, client_height) = (client.right - client.left, client.bottom - client.top);
+        let real_size = dpi as f64 / UNITS_PER_INCH;
+
+        let zoom = match self.zoom.get() {
+            Some(zoom) => zoom,
+            None => self.fit_zoom(client, dpi)
+        };
+
+        let scale = real_size * zoom;
+        let page_width = (width as f64 * scale).round() as i32;
+        let page_height = (height as f64 * scale).round() as i32;
+
+        let max_scroll = (
+            (page_width + PAGE_SPACING * 2 - client_width).max(0),
+            (page_height + PAGE_SPACING * 2 - client_height).max(0),
+        );
+
+        let (scroll_x, scroll_y) = self.scroll.get();
+        let scroll = (scroll_x.max(0).min(max_scroll.0), scroll_y.max(0).min(max_scroll.1));
+        self.scroll.set(scroll);
+
+        // A page smaller than the control is centered, a larger page is scrolled
+        let left = match max_scroll.0 {
+            0 => (client_width - page_width) / 2,
+            _ => PAGE_SPACING - scroll.0
+        };
+
+        let top = match max_scroll.1 {
+            0 => (client_height - page_height) / 2,
+            _ => PAGE_SPACING - scroll.1
+        };
+
+        PageLayout {
+            page: RECT { left, top, right: left + page_width, bottom: top + page_height },
+            scale,
+            max_scroll,
+        }
+    }
+
+    /// The zoom that fits the whole page in the client area
+    fn fit_zoom(&self, client: &RECT, dpi: i32) -> f64 {
+        let (width, height) = self.document.page_size();
+        let real_size = dpi as f64 / UNITS_PER_INCH;
+        let available_width = (client.right - client.left - PAGE_SPACING * 2).max(1) as f64;
+        let available_height = (client.bottom - client.top - PAGE_SPACING * 2).max(1) as f64;
+
+        let zoom = (available_width / (width as f64 * real_size)).min(available_height / (height as f64 * real_size));
+        zoom.max(MIN_ZOOM).min(MAX_ZOOM)
+    }
+
+}
+
+/**
+A print preview displays the pages of a `PrintDocument` before they are sent to the printer.
+The pages are drawn by the draw callback of the document, the same callback that draws the printed pages.
+
+The user can change the page with the Page Up, Page Down, Home and End keys, or with the mouse wheel when the page fits in the control.
+Ctrl + mouse wheel zooms in and out. A page larger than the control is scrolled with the mouse wheel or by dragging it.
+The margins of the document are displayed as a dotted rectangle if `show_margins` is set.
+
+Requires the `printing` feature.
+
+**Builder parameters:**
+  * `parent`:       **Required.** The preview parent container.
+  * `document`:     **Required.** The document displayed by the preview.
+  * `size`:         The preview size.
+  * `position`:     The preview position.
+  * `zoom`:         The initial zoom, where 1.0 displays the pages at their real size. Defaults to `None`, which fits the page in the control.
+  * `show_margins`: Display the margins of the document. Defaults to `true`.
+  * `flags`:        A combination of the PrintPreviewFlags values.
+  * `ex_flags`:     A combination of win32 window extended flags. Unlike `flags`, ex_flags must be used straight from winapi
+
+**Control events:**
+  * `OnPrintPreviewPageChanged`: When the user displayed another page
+
+```rust
+use native_windows_gui as nwg;
+
+fn build_preview(window: &nwg::Window, document: &nwg::PrintDocument, preview: &mut nwg::PrintPreview) {
+    nwg::PrintPreview::builder()
+        .document(document)
+        .size((400, 500))
+        .parent(window)
+        .build(preview)
+        .unwrap();
+}
+
+fn print_from_preview(preview: &nwg::PrintPreview) {
+    if let Err(e) = preview.print() {
+        nwg::error_message("Print", &format!("{}", e));
+    }
+}
+```
+*/
+#[derive(Default)]
+pub struct PrintPreview {
+    pub handle: ControlHandle,
+    state: Option<Rc<PreviewState>>,
+    handler0: RefCell<Option<RawEventHandler>>,
+}
+
+impl PrintPreview {
+
+    pub fn builder<'a>() -> PrintPreviewBuilder<'a> {
+        PrintPreviewBuilder {
+            size: (400, 500),
+            position: (0, 0),
+            zoom: None,
+            show_margins: true,
+            flags: None,
+            ex_flags: 0,
+            parent: None,
+            document: None,
+            name: None,
+            state: Default::default(),
+        }
+    }
+
+    /// Return the index of the displayed page, starting at 0
+    pub fn page(&self) -> usize {
+        self.state().current_page()
+    }
+
+    /// Display a page. The index is clamped to the pages of the document.
+    pub fn set_page(&self, page: usize) {
+        let state = self.state();
+        state.page.set(page.min(state.document.page_count().saturating_sub(1)));
+        state.scroll.set((0, 0));
+        self.repaint();
+    }
+
+    /// Return the number of pages of the document
+    pub fn page_count(&self) -> usize {
+        self.state().document.page_count()
+    }
+
+    /// Display the next page. Does nothing on the last page.
+    pub fn next_page(&self) {
+        self.set_page(self.page() + 1);
+    }
+
+    /// Display the previous page. Does nothing on the first page.
+    pub fn previous_page(&self) {
+        self.set_page(self.page().saturating_sub(1));
+    }
+
+    /// Return the displayed zoom, where 1.0 is the real size of the page. If the page fits the control, this is the zoom that fits the page.
+    pub fn zoom(&self) -> f64 {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let state = self.state();
+        match state.zoom.get() {
+            Some(zoom) => zoom,
+            None => unsafe { state.fit_zoom(&client_rect(handle), screen_dpi()) }
+        }
+    }
+
+    /// Set the zoom, where 1.0 is the real size of the page. The zoom is clamped between 0.05 and 8.0.
+    pub fn set_zoom(&self, zoom: f64) {
+        if zoom.is_finite() {
+            self.state().zoom.set(Some(zoom.max(MIN_ZOOM).min(MAX_ZOOM)));
+            self.repaint();
+        }
+    }
+
+    /// Fit the whole page in the control. The page stays fitted when the control is resized.
+    pub fn zoom_to_fit(&self) {
+        let state = self.state();
+        state.zoom.set(None);
+        state.scroll.set((0, 0));
+        self.repaint();
+    }
+
+    /// Return true if the page fits in the control
+    pub fn fits(&self) -> bool {
+        self.state().zoom.get().is_none()
+    }
+
+    /// Return true if the margins of the document are displayed
+    pub fn show_margins(&self) -> bool {
+        self.state().show_margins.get()
+    }
+
+    /// Display or hide the margins of the document
+    pub fn set_show_margins(&self, show: bool) {
+        self.state().show_margins.set(show);
+        self.repaint();
+    }
+
+    /**
+        Show the print dialog with the preview as owner, then print the pages selected by the user.
+        Returns `Ok(false)` if the user cancelled the dialog.
+    */
+    pub fn print(&self) -> Result<bool, NwgError> {
+        let state = self.state();
+        state.document.print_with_dialog(Some(self.handle))
+    }
+
+    /// Return true if the control user can interact with the control, return false otherwise
+    pub fn enabled(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_enabled(handle) }
+    }
+
+    /// Enable or disable the control
+    pub fn set_enabled(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_enabled(handle, v) }
+    }
+
+    /// Return true if the control is visible to the user. Will return true even if the
+    /// control is outside of the parent client view (ex: at the position (10000, 10000))
+    pub fn visible(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_visibility(handle) }
+    }
+
+    /// Show or hide the control to the user
+    pub fn set_visible(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_visibility(handle, v) }
+    }
+
+    /// Return the size of the preview in the parent window
+    pub fn size(&self) -> (u32, u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_size(handle) }
+    }
+
+    /// Set the size of the preview in the parent window
+    pub fn set_size(&self, x: u32, y: u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_size(handle, x, y, false) }
+    }
+
+    /// Return the position of the preview in the parent window
+    pub fn position(&self) -> (i32, i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_position(handle) }
+    }
+
+    /// Set the position of the preview in the parent window
+    pub fn set_position(&self, x: i32, y: i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_position(handle, x, y) }
+    }
+
+    /// Winapi class name used during control creation
+    pub fn class_name(&self) -> &'static str {
+        nwg_class("NativeWindowsGuiWindow")
+    }
+
+    /// Winapi base flags used during window creation
+    pub fn flags(&self) -> u32 {
+        WS_VISIBLE | WS_TABSTOP
+    }
+
+    /// Winapi flags required by the control
+    pub fn forced_flags(&self) -> u32 {
+        WS_CHILD
+    }
+
+    fn state(&self) -> &PreviewState {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.as_ref().unwrap()
+    }
+
+    fn repaint(&self) {
+        use winapi::um::winuser::InvalidateRect;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { InvalidateRect(handle, ptr::null(), 0); }
+    }
+
+    /// Paint the page, and handle the navigation, the zoom and the scrolling
+    fn bind(&self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::shared::minwindef::{LOWORD, HIWORD};
+        use winapi::um::winuser::{WM_PAINT, WM_ERASEBKGND, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MOUSEMOVE, WM_CAPTURECHANGED, WM_MOUSEWHEEL,
+            WM_KEYDOWN, WM_SIZE, WM_GETDLGCODE, DLGC_WANTARROWS, VK_PRIOR, VK_NEXT, VK_HOME, VK_END, VK_UP, VK_DOWN, MK_CONTROL,
+            GET_WHEEL_DELTA_WPARAM, SetCapture, ReleaseCapture, GetCapture, SetFocus, InvalidateRect};
+
+        let state = self.state.clone().unwrap();
+        let handler = bind_raw_event_handler_inner(&self.handle, PRINT_PREVIEW_ID, move |hwnd, msg, w, l| {
+            // Change the page and raise `OnPrintPreviewPageChanged` if it is not the displayed page
+            let show_page = |page: usize| {
+                let page = page.min(state.document.page_count().saturating_sub(1));
+                if page != state.current_page() {
+                    state.page.set(page);
+                    state.scroll.set((0, 0));
+                    unsafe { InvalidateRect(hwnd, ptr::null(), 0); }
+                    wh::send_message(hwnd, wh::NWG_PRINT_PREVIEW_PAGE, 0, 0);
+                }
+            };
+
+            // Scroll a page larger than the control. Returns false if the page fits vertically.
+            let scroll_by = |dy: i32| -> bool {
+                let layout = unsafe { state.layout(&client_rect(hwnd), screen_dpi()) };
+                let (x, y) = state.scroll.get();
+                let new_y = (y + dy).max(0).min(layout.max_scroll.1);
+                if new_y != y {
+                    state.scroll.set((x, new_y));
+                    unsafe { InvalidateRect(hwnd, ptr::null(), 0); }
+                }
+
+                layout.max_scroll.1 > 0
+            };
+
+            match msg {
+                WM_ERASEBKGND => { return Some(1); },
+                WM_PAINT => {
+                    unsafe { paint_preview(hwnd, &state); }
+                    return Some(0);
+                },
+                WM_SIZE => unsafe { InvalidateRect(hwnd, ptr::null(), 0); },
+                WM_GETDLGCODE => { return Some(DLGC_WANTARROWS as isize); },
+                WM_KEYDOWN => match w as i32 {
+                    VK_NEXT => show_page(state.current_page() + 1),
+                    VK_PRIOR => show_page(state.current_page().saturating_sub(1)),
+                    VK_HOME => show_page(0),
+                    VK_END => show_page(state.document.page_count().saturating_sub(1)),
+                    VK_DOWN => { scroll_by(PAGE_SPACING * 2); },
+                    VK_UP => { scroll_by(-PAGE_SPACING * 2); },
+                    _ => {}
+                },
+                WM_MOUSEWHEEL => {
+                    let delta = GET_WHEEL_DELTA_WPARAM(w) as i32;
+                    let keys = LOWORD(w as u32) as usize;
+
+                    if keys & MK_CONTROL == MK_CONTROL {
+                        let zoom = state.zoom.get().unwrap_or_else(|| unsafe { state.fit_zoom(&client_rect(hwnd), screen_dpi()) });
+                        let zoom = match delta > 0 { true => zoom * ZOOM_STEP, false => zoom / ZOOM_STEP };
+                        state.zoom.set(Some(zoom.max(MIN_ZOOM).min(MAX_ZOOM)));
+                        unsafe { InvalidateRect(hwnd, ptr::null(), 0); }
+                    } else if !scroll_by(-delta / 2) {
+                        // The wheel changes the page when there is nothing to scroll
+                        match delta < 0 {
+                            true => show_page(state.current_page() + 1),
+                            false => show_page(state.current_page().saturating_sub(1)),
+                        }
+                    }
+
+                    return Some(0);
+                },
+                WM_LBUTTONDOWN => {
+                    let cursor = POINT { x: LOWORD(l as u32) as i16 as i32, y: HIWORD(l as u32) as i16 as i32 };
+                    state.drag.set(Some((cursor, state.scroll.get())));
+                    unsafe {
+                        SetFocus(hwnd);
+                        SetCapture(hwnd);
+                    }
+                },
+                WM_MOUSEMOVE => if let Some((start, (x, y))) = state.drag.get() {
+                    let cursor = POINT { x: LOWORD(l as u32) as i16 as i32, y: HIWORD(l as u32) as i16 as i32 };
+                    state.scroll.set((x - (cursor.x - start.x), y - (cursor.y - start.y)));
+                    unsafe { InvalidateRect(hwnd, ptr::null(), 0); }
+                },
+                WM_LBUTTONUP => if state.drag.get().is_some() {
+                    // Cleared first because `ReleaseCapture` sends `WM_CAPTURECHANGED` to the preview
+                    state.drag.set(None);
+                    unsafe {
+                        if GetCapture() == hwnd {
+                            ReleaseCapture();
+                        }
+                    }
+                },
+                WM_CAPTURECHANGED => { state.drag.set(None); },
+                _ => {}
+            }
+
+            None
+        });
+
+        *self.handler0.borrow_mut() = Some(handler.unwrap());
+    }
+
+}
+
+impl PartialEq for PrintPreview {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for PrintPreview {}
+
+impl Drop for PrintPreview {
+    fn drop(&mut self) {
+        if let Some(h) = self.handler0.borrow_mut().take() {
+            drop(unbind_raw_event_handler(&h));
+        }
+
+        if let Some(state) = self.state.take() {
+            state.document.remove_preview(self.handle);
+        }
+
+        self.handle.destroy();
+    }
+}
+
+pub struct PrintPreviewBuilder<'a> {
+    size: (i32, i32),
+    position: (i32, i32),
+    zoom: Option<f64>,
+    show_margins: bool,
+    flags: Option<PrintPreviewFlags>,
+    ex_flags: u32,
+    parent: Option<ControlHandle>,
+    document: Option<&'a PrintDocument>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
+}
+
+impl<'a> PrintPreviewBuilder<'a> {
+
+    pub fn flags(mut self, flags: PrintPreviewFlags) -> PrintPreviewBuilder<'a> {
+        self.flags = Some(flags);
+        self
+    }
+
+    pub fn ex_flags(mut self, flags: u32) -> PrintPreviewBuilder<'a> {
+        self.ex_flags = flags;
+        self
+    }
+
+    pub fn size(mut self, size: (i32, i32)) -> PrintPreviewBuilder<'a> {
+        self.size = size;
+        self
+    }
+
+    pub fn position(mut self, pos: (i32, i32)) -> PrintPreviewBuilder<'a> {
+        self.position = pos;
+        self
+    }
+
+    pub fn zoom(mut self, zoom: Option<f64>) -> PrintPreviewBuilder<'a> {
+        self.zoom = zoom;
+        self
+    }
+
+    pub fn show_margins(mut self, show: bool) -> PrintPreviewBuilder<'a> {
+        self.show_margins = show;
+        self
+    }
+
+    pub fn document(mut self, document: &'a PrintDocument) -> PrintPreviewBuilder<'a> {
+        self.document = Some(document);
+        self
+    }
+
+    pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> PrintPreviewBuilder<'a> {
+        self.parent = Some(p.into());
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> PrintPreviewBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn build(self, out: &mut PrintPreview) -> Result<(), NwgError> {
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
+
+        let parent = match self.parent {
+            Some(p) => Ok(p),
+            None => Err(NwgError::no_parent("PrintPreview"))
+        }?;
+
+        let document = match self.document {
+            Some(d) => d.shared_state(),
+            None => { return Err(NwgError::control_create("A PrintPreview requires a PrintDocument")); }
+        };
+
+        *out = Default::default();
+
+        out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
+            .class_name(out.class_name())
+            .forced_flags(out.forced_flags())
+            .flags(flags)
+            .ex_flags(self.ex_flags)
+            .size(self.size)
+            .position(self.position)
+            .text("")
+            .parent(Some(parent))
+            .build()?;
+
+        document.add_preview(out.handle);
+
+        out.state = Some(Rc::new(PreviewState {
+            document,
+            page: Cell::new(0),
+            zoom: Cell::new(self.zoom.map(|z| z.max(MIN_ZOOM).min(MAX_ZOOM))),
+            show_margins: Cell::new(self.show_margins),
+            scroll: Cell::new((0, 0)),
+            drag: Cell::new(None),
+        }));
+
+        out.bind();
+
+        Ok(())
+    }
+
+}
+
+unsafe fn client_rect(hwnd: HWND) -> RECT {
+    use winapi::um::winuser::GetClientRect;
+
+    let mut client: RECT = mem::zeroed();
+    GetClientRect(hwnd, &mut client);
+    client
+}
+
+/// The dpi of the screen, used to display the pages at their real size with a zoom of 1.0
+unsafe fn screen_dpi() -> i32 {
+    use winapi::um::winuser::{GetDC, ReleaseDC};
+    use winapi::um::wingdi::{GetDeviceCaps, LOGPIXELSX};
+
+    let dc = GetDC(ptr::null_mut());
+    let dpi = GetDeviceCaps(dc, LOGPIXELSX);
+    ReleaseDC(ptr::null_mut(), dc);
+
+    dpi.max(1)
+}
+
+/// Draw the page and its margins in a memory bitmap, then copy it to the window
+unsafe fn paint_preview(hwnd: HWND, state: &PreviewState) {
+    use winapi::um::winuser::{BeginPaint, EndPaint, FillRect, GetSysColorBrush, PAINTSTRUCT, COLOR_APPWORKSPACE, COLOR_BTNSHADOW};
+    use winapi::um::wingdi::{CreateCompatibleDC, CreateCompatibleBitmap, SelectObject, DeleteObject, DeleteDC, BitBlt, GetStockObject,
+        CreatePen, Rectangle, SetBkMode, SRCCOPY, WHITE_BRUSH, NULL_BRUSH, PS_DOT, TRANSPARENT, RGB};
+
+    let mut paint: PAINTSTRUCT = mem::zeroed();
+    let dc = BeginPaint(hwnd, &mut paint);
+
+    let client = client_rect(hwnd);
+    let (width, height) = (client.right, client.bottom);
+
+    let memory: HDC = CreateCompatibleDC(dc);
+    let bitmap = CreateCompatibleBitmap(dc, width.max(1), height.max(1));
+    let old_bitmap = SelectObject(memory, bitmap as _);
+
+    FillRect(memory, &client, GetSysColorBrush(COLOR_APPWORKSPACE));
+
+    if state.document.page_count() > 0 {
+        let layout = state.layout(&client, screen_dpi());
+        let page = layout.page;
+
+        let shadow = RECT { left: page.left + 3, top: page.top + 3, right: page.right + 3, bottom: page.bottom + 3 };
+        FillRect(memory, &shadow, GetSysColorBrush(COLOR_BTNSHADOW));
+        FillRect(memory, &page, GetStockObject(WHITE_BRUSH as i32) as _);
+
+        state.document.render_page(memory, state.current_page(), &page, true);
+
+        if state.show_margins.get() {
+            let [left, top, right, bottom] = state.document.margins();
+            let scale = layout.scale;
+            let pen = CreatePen(PS_DOT as i32, 1, RGB(160, 160, 160));
+            let old_pen = SelectObject(memory, pen as _);
+            let old_brush = SelectObject(memory, GetStockObject(NULL_BRUSH as i32));
+            SetBkMode(memory, TRANSPARENT as i32);
+
+            Rectangle(
+                memory,
+                page.left + (left as f64 * scale).round() as i32,
+                page.top + (top as f64 * scale).round() as i32,
+                page.right - (right as f64 * scale).round() as i32,
+                page.bottom - (bottom as f64 * scale).round() as i32,
+            );
+
+            SelectObject(memory, old_brush);
+            SelectObject(memory, old_pen);
+            DeleteObject(pen as _);
+        }
+    }
+
+    BitBlt(dc, 0, 0, width, height, memory, 0, 0, SRCCOPY);
+
+    SelectObject(memory, old_bitmap);
+    DeleteObject(bitmap as _);
+    DeleteDC(memory);
+
+    EndPaint(hwnd, &paint);
+}
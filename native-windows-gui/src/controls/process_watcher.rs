@@ -0,0 +1,259 @@
+use winapi::um::winnt::{HANDLE, PVOID, BOOLEAN};
+use winapi::shared::minwindef::{DWORD, WPARAM, LPARAM};
+use winapi::shared::windef::HWND;
+use super::control_handle::ControlHandle;
+use crate::win32::{window_helper as wh, window::build_notice};
+use crate::NwgError;
+use std::os::windows::io::RawHandle;
+use std::cell::RefCell;
+use std::ptr;
+
+const NOT_BOUND: &'static str = "ProcessWatcher is not yet bound to a winapi object";
+const BAD_HANDLE: &'static str = "INTERNAL ERROR: ProcessWatcher handle is not Notice!";
+
+
+/// The data passed to the wait callback. Lives until the wait is unregistered.
+struct WaitContext {
+    hwnd: HWND,
+    id: u32,
+    process: HANDLE,
+}
+
+/// The process currently watched
+struct WatchState {
+    process: HANDLE,
+    wait: HANDLE,
+    context: Option<Box<WaitContext>>,
+}
+
+/**
+An invisible component that watches a process and raises `OnProcessExited` when it exits.
+
+The process is waited on by the system thread pool, so the watcher does not poll and does not spawn a thread.
+The event is raised on the GUI thread. The exit code of the process can be read with `EventData::on_process_exited`.
+
+A watcher watches a single process at a time. Watching a new process stops watching the previous one.
+The process can be selected by pid or by handle (ex: the raw handle of a `std::process::Child`). The handle is duplicated,
+so the original handle can be closed after the call.
+
+A process watcher must have a parent window. The watcher stops when it is dropped.
+
+Requires the `process-watcher` feature.
+
+**Builder parameters:**
+    * `parent`:     **Required.** The watcher parent container that will receive the events. Should be a top level window
+    * `pid`:        The id of the process to watch when the watcher is created
+
+**Control events:**
+    * `OnProcessExited`: When the watched process exited
+
+## Example
+
+```rust
+use native_windows_gui as nwg;
+use std::os::windows::io::AsRawHandle;
+
+fn launch(watcher: &nwg::ProcessWatcher) {
+    let child = std::process::Command::new("notepad.exe").spawn().unwrap();
+    watcher.watch_handle(child.as_raw_handle()).unwrap();
+}
+
+fn build_watcher(watcher: &mut nwg::ProcessWatcher, window: &nwg::Window) {
+    nwg::ProcessWatcher::builder()
+        .parent(window)
+        .build(watcher);
+}
+```
+*/
+pub struct ProcessWatcher {
+    pub handle: ControlHandle,
+    state: RefCell<Option<WatchState>>,
+}
+
+impl ProcessWatcher {
+
+    pub fn builder() -> ProcessWatcherBuilder {
+        ProcessWatcherBuilder {
+            parent: None,
+            pid: None,
+        }
+    }
+
+    /// Watch the process with the selected id
+    pub fn watch_pid(&self, pid: u32) -> Result<(), NwgError> {
+        use winapi::um::processthreadsapi::OpenProcess;
+        use winapi::um::winnt::{SYNCHRONIZE, PROCESS_QUERY_LIMITED_INFORMATION};
+
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+
+        let process = unsafe { OpenProcess(SYNCHRONIZE | PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+        if process.is_null() {
+            return Err(NwgError::control_create(format!("Failed to open the process {}", pid)));
+        }
+
+        self.watch(process)
+    }
+
+    /// Watch the process identified by `handle`. The handle is duplicated and can be closed after the call.
+    pub fn watch_handle(&self, handle: RawHandle) -> Result<(), NwgError> {
+        use winapi::um::handleapi::DuplicateHandle;
+        use winapi::um::processthreadsapi::GetCurrentProcess;
+        use winapi::um::winnt::DUPLICATE_SAME_ACCESS;
+
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+
+        let mut process = ptr::null_mut();
+        let duplicated = unsafe {
+            let current = GetCurrentProcess();
+            DuplicateHandle(current, handle as HANDLE, current, &mut process, 0, 0, DUPLICATE_SAME_ACCESS)
+        };
+
+        if duplicated == 0 {
+            return Err(NwgError::control_create("Failed to duplicate the process handle"));
+        }
+
+        self.watch(process)
+    }
+
+    /// Stop watching the current process. Does nothing if no process is watched.
+    pub fn stop(&self) {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        if let Some(state) = self.state.borrow_mut().take() {
+            unsafe { unwatch(state); }
+        }
+    }
+
+    /// Return the id of the watched process, or `None` if no process is watched
+    pub fn pid(&self) -> Option<u32> {
+        use winapi::um::processthreadsapi::GetProcessId;
+
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.state.borrow().as_ref().map(|s| unsafe { GetProcessId(s.process) })
+    }
+
+    /// Return true if a process is watched and it did not exit yet
+    pub fn running(&self) -> bool {
+        use winapi::um::synchapi::WaitForSingleObject;
+        use winapi::um::winbase::WAIT_OBJECT_0;
+
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        match self.state.borrow().as_ref() {
+            Some(s) => unsafe { WaitForSingleObject(s.process, 0) != WAIT_OBJECT_0 },
+            None => false
+        }
+    }
+
+    /// Register a wait on `process`. Takes ownership of the handle.
+    fn watch(&self, process: HANDLE) -> Result<(), NwgError> {
+        use winapi::um::winbase::{RegisterWaitForSingleObject, INFINITE};
+        use winapi::um::winnt::WT_EXECUTEONLYONCE;
+        use winapi::um::handleapi::CloseHandle;
+
+        self.stop();
+
+        let (hwnd, id) = self.handle.notice().expect(BAD_HANDLE);
+        let mut context = Box::new(WaitContext { hwnd, id, process });
+        let mut wait = ptr::null_mut();
+
+        let registered = unsafe {
+            RegisterWaitForSingleObject(&mut wait, process, Some(process_exited), &mut *context as *mut WaitContext as PVOID, INFINITE, WT_EXECUTEONLYONCE)
+        };
+
+        if registered == 0 {
+            unsafe { CloseHandle(process); }
+            return Err(NwgError::control_create("Failed to register the process wait"));
+        }
+
+        *self.state.borrow_mut() = Some(WatchState { process, wait, context: Some(context) });
+
+        Ok(())
+    }
+
+}
+
+impl Default for ProcessWatcher {
+    fn default() -> ProcessWatcher {
+        ProcessWatcher {
+            handle: ControlHandle::NoHandle,
+            state: RefCell::new(None),
+        }
+    }
+}
+
+impl PartialEq for ProcessWatcher {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for ProcessWatcher {}
+
+impl Drop for ProcessWatcher {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.borrow_mut().take() {
+            unsafe { unwatch(state); }
+        }
+
+        self.handle.destroy();
+    }
+}
+
+pub struct ProcessWatcherBuilder {
+    parent: Option<ControlHandle>,
+    pid: Option<u32>,
+}
+
+impl ProcessWatcherBuilder {
+
+    pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> ProcessWatcherBuilder {
+        self.parent = Some(p.into());
+        self
+    }
+
+    pub fn pid(mut self, pid: Option<u32>) -> ProcessWatcherBuilder {
+        self.pid = pid;
+        self
+    }
+
+    pub fn build(self, out: &mut ProcessWatcher) -> Result<(), NwgError> {
+        let parent = match self.parent {
+            Some(p) => match p.hwnd() {
+                Some(handle) => Ok(handle),
+                None => Err(NwgError::control_create("Wrong parent type"))
+            },
+            None => Err(NwgError::no_parent("ProcessWatcher"))
+        }?;
+
+        *out = Default::default();
+        out.handle = build_notice(parent);
+
+        if let Some(pid) = self.pid {
+            out.watch_pid(pid)?;
+        }
+
+        Ok(())
+    }
+
+}
+
+/// Unregister the wait and close the process handle. Blocks until a running callback returns.
+unsafe fn unwatch(mut state: WatchState) {
+    use winapi::um::threadpoollegacyapiset::UnregisterWaitEx;
+    use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+
+    UnregisterWaitEx(state.wait, INVALID_HANDLE_VALUE);
+    drop(state.context.take());
+    CloseHandle(state.process);
+}
+
+/// Called by the thread pool when the process exits
+unsafe extern "system" fn process_exited(context: PVOID, _timed_out: BOOLEAN) {
+    use winapi::um::processthreadsapi::GetExitCodeProcess;
+
+    let context = &*(context as *const WaitContext);
+
+    let mut exit_code: DWORD = 0;
+    GetExitCodeProcess(context.process, &mut exit_code);
+
+    wh::post_message(context.hwnd, wh::NWG_PROCESS_EXITED, context.id as WPARAM, exit_code as LPARAM);
+}
@@ -3,13 +3,17 @@ A push button is a rectangle containing an application-defined text label, an ic
 that indicates what the button does when the user selects it.
 */
 
+use winapi::shared::windef::HWND;
 use winapi::um::winuser::{WS_VISIBLE, WS_DISABLED};
 use winapi::um::commctrl::{PBS_MARQUEE, PBS_VERTICAL};
 use crate::win32::window_helper as wh;
 use crate::win32::base_helper::check_hwnd;
-use crate::NwgError;
+use crate::{NwgError, RawEventHandler, unbind_raw_event_handler};
 use super::{ControlHandle, ControlBase};
+use super::control_builder::BuilderState;
 use std::ops::Range;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 
 const NOT_BOUND: &'static str = "Progress bar is not yet bound to a winapi object";
 const BAD_HANDLE: &'static str = "INTERNAL ERROR: Progress bar handle is not HWND!";
@@ -32,6 +36,12 @@ pub enum ProgressBarState {
     Paused
 }
 
+#[derive(Copy, Clone, Default)]
+struct ProgressBarColors {
+    bar: Option<[u8; 3]>,
+    background: Option<[u8; 3]>,
+}
+
 /**
 A progress bar is a window that an application can use to indicate the progress of a lengthy operation.
 
@@ -50,10 +60,15 @@ Requires the `progress-bar` feature.
   * `ex_flags`:       A combination of win32 window extended flags. Unlike `flags`, ex_flags must be used straight from winapi
   * `marquee`:        Enable of disable the marquee animation (only used with the MARQUEE flags)
   * `marquee_update`: The update interval of the marquee mode
+  * `bar_color`:      The color of the bar in RGB format
+  * `background_color`: The color of the background of the progress bar in RGB format
 
 **Control events:**
   * `MousePress(_)`: Generic mouse press events on the progress bar
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
 
 ```rust
@@ -69,9 +84,11 @@ fn build_progress_bar(bar: &mut nwg::ProgressBar, window: &nwg::Window) {
 ```
 
 */
-#[derive(Default, PartialEq, Eq)]
+#[derive(Default)]
 pub struct ProgressBar {
-    pub handle: ControlHandle
+    pub handle: ControlHandle,
+    colors: Rc<Cell<ProgressBarColors>>,
+    handler0: RefCell<Option<RawEventHandler>>,
 }
 
 impl ProgressBar {
@@ -88,7 +105,11 @@ impl ProgressBar {
             range: 0..100,
             marquee_enable: false,
             marquee_update: 0,
-            parent: None
+            bar_color: None,
+            background_color: None,
+            parent: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -204,6 +225,48 @@ impl ProgressBar {
         wh::send_message(handle, PBM_SETMARQUEE, enable as WPARAM, update_interval as LPARAM);
     }
 
+    /// Returns the color of the bar in RGB format, or `None` if the bar uses the color of the system
+    pub fn bar_color(&self) -> Option<[u8; 3]> {
+        self.colors.get().bar
+    }
+
+    /**
+        Sets the color of the bar in RGB format, for example to show a success or a warning state. `None` restores the color of the system.
+        The visual styles ignore the colors of the progress bars, so a themed progress bar with a custom color is painted by native-windows-gui.
+        The marquee animation always uses the colors of the system when the progress bar is themed.
+    */
+    pub fn set_bar_color(&self, color: Option<[u8; 3]>) {
+        use winapi::um::commctrl::PBM_SETBARCOLOR;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        wh::send_message(handle, PBM_SETBARCOLOR, 0, color_ref(color) as _);
+
+        let mut colors = self.colors.get();
+        colors.bar = color;
+        self.colors.set(colors);
+
+        self.hook_custom_paint();
+    }
+
+    /// Returns the color of the background of the progress bar in RGB format, or `None` if the background uses the color of the system
+    pub fn background_color(&self) -> Option<[u8; 3]> {
+        self.colors.get().background
+    }
+
+    /// Sets the color of the background of the progress bar in RGB format. `None` restores the color of the system. See `set_bar_color`.
+    pub fn set_background_color(&self, color: Option<[u8; 3]>) {
+        use winapi::um::commctrl::PBM_SETBKCOLOR;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        wh::send_message(handle, PBM_SETBKCOLOR, 0, color_ref(color) as _);
+
+        let mut colors = self.colors.get();
+        colors.background = color;
+        self.colors.set(colors);
+
+        self.hook_custom_paint();
+    }
+
     /// Updates the flags of the progress bar.
     pub fn add_flags(&self, styles: ProgressBarFlags) {
         let styles = styles.bits() as u32;
@@ -302,14 +365,57 @@ impl ProgressBar {
         WS_CHILD
     }
 
+    /// Paints the progress bar when it has custom colors and the visual styles ignore them
+    fn hook_custom_paint(&self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::{InvalidateRect, WM_PAINT, WM_ERASEBKGND};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { InvalidateRect(handle, ::std::ptr::null(), 1); }
+
+        if self.handler0.borrow().is_some() {
+            return;
+        }
+
+        let colors = self.colors.clone();
+        let handler = bind_raw_event_handler_inner(&self.handle, 0, move |hwnd, msg, _w, _l| {
+            let colors = colors.get();
+            let custom = colors.bar.is_some() || colors.background.is_some();
+            if !custom || !needs_custom_paint(hwnd) {
+                return None;
+            }
+
+            match msg {
+                WM_PAINT => unsafe { paint_progress_bar(hwnd, colors); Some(0) },
+                WM_ERASEBKGND => Some(1),
+                _ => None
+            }
+        });
+
+        *self.handler0.borrow_mut() = Some(handler.unwrap());
+    }
+
 }
 
 impl Drop for ProgressBar {
     fn drop(&mut self) {
+        let handler = self.handler0.borrow();
+        if let Some(h) = handler.as_ref() {
+            drop(unbind_raw_event_handler(h));
+        }
+
         self.handle.destroy();
     }
 }
 
+impl PartialEq for ProgressBar {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for ProgressBar {}
+
 pub struct ProgressBarBuilder {
     size: (i32, i32),
     position: (i32, i32),
@@ -321,7 +427,11 @@ pub struct ProgressBarBuilder {
     range: Range<u32>,
     marquee_enable: bool,
     marquee_update: u32,
-    parent: Option<ControlHandle>
+    bar_color: Option<[u8; 3]>,
+    background_color: Option<[u8; 3]>,
+    parent: Option<ControlHandle>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl ProgressBarBuilder {
@@ -376,13 +486,28 @@ impl ProgressBarBuilder {
         self
     }
 
+    pub fn bar_color(mut self, color: Option<[u8; 3]>) -> ProgressBarBuilder {
+        self.bar_color = color;
+        self
+    }
+
+    pub fn background_color(mut self, color: Option<[u8; 3]>) -> ProgressBarBuilder {
+        self.background_color = color;
+        self
+    }
+
     pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> ProgressBarBuilder {
         self.parent = Some(p.into());
         self
     }
 
+    pub fn name(mut self, name: &str) -> ProgressBarBuilder {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut ProgressBar) -> Result<(), NwgError> {
-        let flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
 
         let parent = match self.parent {
             Some(p) => Ok(p),
@@ -392,6 +517,7 @@ impl ProgressBarBuilder {
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -407,7 +533,83 @@ impl ProgressBarBuilder {
         out.set_range(self.range);
         out.set_marquee(self.marquee_enable, self.marquee_update);
 
+        if self.bar_color.is_some() {
+            out.set_bar_color(self.bar_color);
+        }
+
+        if self.background_color.is_some() {
+            out.set_background_color(self.background_color);
+        }
+
         Ok(())
     }
 
 }
+
+fn color_ref(color: Option<[u8; 3]>) -> u32 {
+    use winapi::um::wingdi::RGB;
+    use winapi::um::commctrl::CLR_DEFAULT;
+
+    match color {
+        Some([r, g, b]) => RGB(r, g, b),
+        None => CLR_DEFAULT
+    }
+}
+
+/// The themed progress bars ignore `PBM_SETBARCOLOR` and `PBM_SETBKCOLOR`. The marquee animation is left to the system.
+fn needs_custom_paint(hwnd: HWND) -> bool {
+    use winapi::um::uxtheme::GetWindowTheme;
+
+    let marquee = wh::get_style(hwnd) & PBS_MARQUEE == PBS_MARQUEE;
+    !marquee && unsafe { !GetWindowTheme(hwnd).is_null() }
+}
+
+unsafe fn paint_progress_bar(hwnd: HWND, colors: ProgressBarColors) {
+    use winapi::um::winuser::{BeginPaint, EndPaint, FillRect, FrameRect, GetClientRect, SendMessageW, PAINTSTRUCT};
+    use winapi::um::commctrl::{PBM_GETPOS, PBM_GETRANGE, PBM_GETSTATE, PBST_ERROR, PBST_PAUSED};
+    use winapi::um::wingdi::{CreateSolidBrush, DeleteObject, RGB};
+    use winapi::shared::windef::RECT;
+    use std::mem;
+
+    let mut paint: PAINTSTRUCT = mem::zeroed();
+    BeginPaint(hwnd, &mut paint);
+
+    let mut r: RECT = mem::zeroed();
+    GetClientRect(hwnd, &mut r);
+
+    // Same colors as the default theme of Windows 10
+    let [r1, g1, b1] = colors.background.unwrap_or([230, 230, 230]);
+    let [r2, g2, b2] = colors.bar.unwrap_or_else(|| match SendMessageW(hwnd, PBM_GETSTATE, 0, 0) as i32 {
+        PBST_ERROR => [218, 38, 38],
+        PBST_PAUSED => [218, 203, 38],
+        _ => [6, 176, 37]
+    });
+
+    let background = CreateSolidBrush(RGB(r1, g1, b1));
+    let bar = CreateSolidBrush(RGB(r2, g2, b2));
+    let border = CreateSolidBrush(RGB(188, 188, 188));
+
+    FillRect(paint.hdc, &r, background);
+    FrameRect(paint.hdc, &r, border);
+
+    let low = SendMessageW(hwnd, PBM_GETRANGE, 1, 0) as i64;
+    let high = SendMessageW(hwnd, PBM_GETRANGE, 0, 0) as i64;
+    let pos = SendMessageW(hwnd, PBM_GETPOS, 0, 0) as i64;
+
+    let mut inner = RECT { left: r.left + 1, top: r.top + 1, right: r.right - 1, bottom: r.bottom - 1 };
+    if high > low && pos > low {
+        let progress = (pos.min(high) - low) as f64 / (high - low) as f64;
+        match wh::get_style(hwnd) & PBS_VERTICAL == PBS_VERTICAL {
+            true => { inner.top = inner.bottom - (((inner.bottom - inner.top) as f64) * progress) as i32; },
+            false => { inner.right = inner.left + (((inner.right - inner.left) as f64) * progress) as i32; }
+        }
+
+        FillRect(paint.hdc, &inner, bar);
+    }
+
+    DeleteObject(background as _);
+    DeleteObject(bar as _);
+    DeleteObject(border as _);
+
+    EndPaint(hwnd, &paint);
+}
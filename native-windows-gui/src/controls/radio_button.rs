@@ -7,6 +7,7 @@ use crate::win32::window_helper as wh;
 use crate::win32::base_helper::check_hwnd;
 use crate::{Font, NwgError, RawEventHandler, unbind_raw_event_handler};
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
 use std::cell::RefCell;
 
 const NOT_BOUND: &'static str = "RadioButton is not yet bound to a winapi object";
@@ -61,6 +62,9 @@ Note: Internally, radio buttons are `Button` and as such, they trigger the same
   * `OnButtonDoubleClick`: When the adio button is clicked twice rapidly by the user
   * `MousePress(_)`: Generic mouse press events on the adio button
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
 
 
@@ -128,7 +132,9 @@ impl RadioButton {
             flags: None,
             ex_flags: 0,
             font: None,
-            parent: None
+            parent: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -326,7 +332,9 @@ pub struct RadioButtonBuilder<'a> {
     flags: Option<RadioButtonFlags>,
     ex_flags: u32,
     font: Option<&'a Font>,
-    parent: Option<ControlHandle>
+    parent: Option<ControlHandle>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a> RadioButtonBuilder<'a> {
@@ -381,8 +389,13 @@ impl<'a> RadioButtonBuilder<'a> {
         self
     }
 
+    pub fn name(mut self, name: &str) -> RadioButtonBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut RadioButton) -> Result<(), NwgError> {
-        let flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
 
         let parent = match self.parent {
             Some(p) => Ok(p),
@@ -392,6 +405,7 @@ impl<'a> RadioButtonBuilder<'a> {
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
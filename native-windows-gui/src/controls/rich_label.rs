@@ -4,6 +4,7 @@ use crate::win32::base_helper::check_hwnd;
 use crate::win32::richedit as rich;
 use crate::{Font, NwgError, RawEventHandler, HTextAlign, unbind_raw_event_handler};
 use super::{ControlBase, ControlHandle, CharFormat, ParaFormat};
+use super::control_builder::BuilderState;
 
 use std::{rc::Rc, ops::Range, cell::RefCell};
 
@@ -59,6 +60,9 @@ Unlike the basic `Label`, this version supports:
 **Control events:**
   * `MousePress(_)`: Generic mouse press events on the label
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
 
 ** Example **
@@ -95,6 +99,8 @@ impl RichLabel {
             background_color: None,
             line_height: None,
             parent: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -378,6 +384,8 @@ pub struct RichLabelBuilder<'a> {
     background_color: Option<[u8; 3]>,
     line_height: Option<i32>,
     parent: Option<ControlHandle>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a> RichLabelBuilder<'a> {
@@ -432,10 +440,15 @@ impl<'a> RichLabelBuilder<'a> {
         self
     }
 
+    pub fn name(mut self, name: &str) -> RichLabelBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut RichLabel) -> Result<(), NwgError> {
         use winapi::um::winuser::{SS_LEFT, SS_RIGHT, SS_CENTER};
 
-        let mut flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let mut flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
         match self.h_align {
             HTextAlign::Left => { flags |= SS_LEFT; },
             HTextAlign::Right => { flags |= SS_RIGHT; },
@@ -452,6 +465,7 @@ impl<'a> RichLabelBuilder<'a> {
     
         *out.line_height.borrow_mut() = self.line_height;
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
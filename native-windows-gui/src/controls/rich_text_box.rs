@@ -5,7 +5,9 @@ use crate::win32::base_helper::check_hwnd;
 use crate::win32::richedit as rich;
 use crate::{Font, NwgError};
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
 use std::ops::Range;
+use std::io;
 use newline_converter::{unix2dos, dos2unix};
 
 const NOT_BOUND: &'static str = "RichTextBox is not yet bound to a winapi object";
@@ -231,6 +233,7 @@ The rich text box control supports the following rich text features:
 * Bullet point list
 * Paragraph with custom indent/offset
 * Custom line spacing
+* Loading and saving RTF documents (see `load_rtf` and `save_rtf`)
 
 
 See: https://docs.microsoft.com/en-us/windows/win32/controls/about-rich-edit-controls#rich-edit-version-41
@@ -251,6 +254,9 @@ Note: Use `\r\n` to input a new line not just `\n`.
 
 **Control events:**
   * `OnMouseMove`:   Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`:  Generic mouse wheel event
   * `MousePress(_)`: Generic mouse press events on the button
   * `OnKeyPress`:    Generic key press event
@@ -275,7 +281,9 @@ impl RichTextBox {
             readonly: false,
             focus: false,
             font: None,
-            parent: None
+            parent: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -528,6 +536,44 @@ impl RichTextBox {
         self.scroll_lastline();
     }
 
+    /**
+        Replace the content of the control with a RTF document. The formatting of the document is kept.
+        If `rtf` is not a valid RTF document, it is loaded as plain text.
+    */
+    pub fn load_rtf<'a>(&self, rtf: &'a str) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        if rich::stream_in(handle, rich::SF_RTF, &mut rtf.as_bytes()).is_err() {
+            self.set_text(rtf);
+        }
+    }
+
+    /// Returns the content of the control as a RTF document, with its formatting
+    pub fn save_rtf(&self) -> String {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let mut rtf = Vec::new();
+        rich::stream_out(handle, rich::SF_RTF, &mut rtf).expect("Writing to a Vec cannot fail");
+        String::from_utf8_lossy(&rtf).into_owned()
+    }
+
+    /**
+        Replace the content of the control with the RTF document read from `reader`, ex: a file opened with `File::open`.
+        Returns the errors of `reader`, or an `InvalidData` error if the document is not valid.
+    */
+    pub fn load_rtf_from<R: io::Read>(&self, reader: &mut R) -> io::Result<()> {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        rich::stream_in(handle, rich::SF_RTF, reader)
+    }
+
+    /**
+        Write the content of the control as a RTF document to `writer`, ex: a file created with `File::create`.
+        Returns the errors of `writer`.
+    */
+    pub fn save_rtf_to<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        rich::stream_out(handle, rich::SF_RTF, writer)
+    }
+
     /// Winapi class name used during control creation
     pub fn class_name(&self) -> &'static str {
         "RICHEDIT50W"
@@ -562,7 +608,9 @@ pub struct RichTextBoxBuilder<'a> {
     readonly: bool,
     focus: bool,
     font: Option<&'a Font>,
-    parent: Option<ControlHandle>
+    parent: Option<ControlHandle>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a> RichTextBoxBuilder<'a> {
@@ -617,8 +665,13 @@ impl<'a> RichTextBoxBuilder<'a> {
         self
     }
 
+    pub fn name(mut self, name: &str) -> RichTextBoxBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut RichTextBox) -> Result<(), NwgError> {
-        let flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
 
         let parent = match self.parent {
             Some(p) => Ok(p),
@@ -628,6 +681,7 @@ impl<'a> RichTextBoxBuilder<'a> {
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -0,0 +1,543 @@
+use winapi::shared::windef::{HWND, POINT, RECT, COLORREF};
+use winapi::shared::minwindef::{WPARAM, LPARAM};
+use winapi::um::winuser::{WS_POPUP, WS_EX_LAYERED, WS_EX_TRANSPARENT, WS_EX_TOOLWINDOW, WS_EX_NOACTIVATE};
+use super::{ControlBase, ControlHandle};
+use crate::win32::window_helper as wh;
+use crate::win32::window::nwg_class;
+use crate::{NwgError, RawEventHandler, unbind_raw_event_handler};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::{ptr, mem};
+
+const NOT_BOUND: &'static str = "RubberBandSelector is not yet bound to a winapi object";
+
+/// The id of the raw event handlers of the canvas and of the overlay, and of the auto scroll timer
+const RUBBER_BAND_ID: usize = 0x5242;
+
+/// The interval of the auto scroll timer, in milliseconds
+const AUTO_SCROLL_INTERVAL: u32 = 50;
+
+/// Sent in the wparam of `NWG_RUBBER_BAND`
+pub(crate) const RUBBER_BAND_CHANGED: WPARAM = 0;
+pub(crate) const RUBBER_BAND_SELECT: WPARAM = 1;
+pub(crate) const RUBBER_BAND_CANCEL: WPARAM = 2;
+
+
+/// How `hit_test_rect` selects the items
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// Select the items that touch the selection rectangle
+    Intersect,
+
+    /// Select the items that are fully inside the selection rectangle
+    Contain,
+}
+
+/// The state shared with the raw event handlers
+struct BandState {
+    canvas: HWND,
+    overlay: HWND,
+    color: COLORREF,
+    auto_scroll: bool,
+    scroll_margin: i32,
+    scroll_unit: i32,
+
+    /// The position where the left button was pressed, in content coordinates (client coordinates + scroll position)
+    anchor: Cell<(i32, i32)>,
+
+    /// The last position of the cursor, in client coordinates
+    cursor: Cell<(i32, i32)>,
+    pressed: Cell<bool>,
+    active: Cell<bool>,
+    rect: Cell<Option<[i32; 4]>>,
+}
+
+/**
+A rubber band selector lets the user select items in a custom canvas by dragging a rectangle with the left mouse button,
+like in the Windows explorer. Useful for designers, node editors or any canvas that draws its own items.
+
+The selector is attached to a window control (usually an `ExternCanvas` or a `Frame`). When the user drags the mouse over the canvas,
+a translucent rectangle is displayed over the canvas and `OnRubberBandChanged` is raised each time the rectangle changes.
+The selection ends with `OnRubberBandSelect` when the button is released, or with `OnRubberBandCancel` if the escape key is pressed.
+The events are raised by the canvas. The rectangle can be read with `rect`, in client coordinates of the canvas.
+
+A simple click does not start a selection: the cursor must move further than the system drag distance first.
+The mouse events of the canvas are still raised, so the application can handle the clicks as usual.
+
+If `auto_scroll` is enabled and the canvas has scroll bars, dragging the cursor near the edges of the canvas sends
+`WM_HSCROLL` and `WM_VSCROLL` line messages to the canvas. The start of the rectangle follows the content using the scroll positions
+of the canvas, multiplied by `scroll_unit`.
+
+Use `hit_test_point` and `hit_test_rect` to find the items under the cursor or inside the selection.
+
+Requires the `rubber-band` feature.
+
+**Builder parameters:**
+  * `canvas`:        **Required.** The window control where the rectangle is dragged.
+  * `color`:         The color of the rectangle. Defaults to the system highlight color.
+  * `opacity`:       The opacity of the rectangle, from 0 to 255. Defaults to 80.
+  * `auto_scroll`:   Scroll the canvas when the cursor is near its edges. Defaults to `true`.
+  * `scroll_margin`: The size of the area near the edges where the canvas is scrolled, in pixels. Defaults to 20.
+  * `scroll_unit`:   The number of pixels of a scroll position of the canvas. Defaults to 1.
+
+**Control events (raised by the canvas):**
+  * `OnRubberBandChanged`: When the selection rectangle changed
+  * `OnRubberBandSelect`:  When the user released the mouse button
+  * `OnRubberBandCancel`:  When the user pressed escape during the selection
+
+```rust
+use native_windows_gui as nwg;
+
+struct Designer {
+    canvas: nwg::ExternCanvas,
+    band: nwg::RubberBandSelector,
+    items: Vec<[i32; 4]>,
+}
+
+impl Designer {
+    fn build_band(&mut self) {
+        nwg::RubberBandSelector::builder()
+            .canvas(&self.canvas)
+            .build(&mut self.band)
+            .unwrap();
+    }
+
+    fn on_select(&self) {
+        if let Some(rect) = self.band.rect() {
+            let selected = nwg::hit_test_rect(&self.items, rect, nwg::SelectionMode::Intersect);
+            println!("{} items selected", selected.len());
+        }
+    }
+}
+```
+*/
+#[derive(Default)]
+pub struct RubberBandSelector {
+    pub handle: ControlHandle,
+    overlay: ControlHandle,
+    state: Option<Rc<BandState>>,
+    handler0: RefCell<Option<RawEventHandler>>,
+    handler1: RefCell<Option<RawEventHandler>>,
+}
+
+impl RubberBandSelector {
+
+    pub fn builder() -> RubberBandSelectorBuilder {
+        RubberBandSelectorBuilder {
+            canvas: None,
+            color: None,
+            opacity: 80,
+            auto_scroll: true,
+            scroll_margin: 20,
+            scroll_unit: 1,
+        }
+    }
+
+    /**
+        Return the selection rectangle as `[x, y, width, height]` in client coordinates of the canvas.
+        The rectangle is kept after `OnRubberBandSelect` until the next click in the canvas. Returns `None` if there is no selection.
+    */
+    pub fn rect(&self) -> Option<[i32; 4]> {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.state.as_ref().and_then(|s| s.rect.get())
+    }
+
+    /// Return true if the user is currently dragging a selection rectangle
+    pub fn active(&self) -> bool {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.state.as_ref().map(|s| s.active.get()).unwrap_or(false)
+    }
+
+    /// Cancel the current selection. Raises `OnRubberBandCancel` if a selection was active.
+    pub fn cancel(&self) {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+
+        let state = self.state.as_ref().unwrap();
+        if state.pressed.get() {
+            finish(state, RUBBER_BAND_CANCEL);
+        }
+    }
+
+    /// Handle the mouse and keyboard messages of the canvas, and the paint and timer messages of the overlay
+    fn bind(&self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::{WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MOUSEMOVE, WM_CAPTURECHANGED, WM_KEYDOWN, WM_PAINT, WM_TIMER,
+            VK_ESCAPE, SetCapture};
+
+        let state = self.state.clone().unwrap();
+        let canvas_handler = bind_raw_event_handler_inner(&self.handle, RUBBER_BAND_ID, move |_hwnd, msg, w, _l| {
+            match msg {
+                WM_LBUTTONDOWN => {
+                    let (x, y) = cursor_position(state.canvas);
+                    let (sx, sy) = scroll_offset(&state);
+                    state.anchor.set((x + sx, y + sy));
+                    state.cursor.set((x, y));
+                    state.pressed.set(true);
+                    state.active.set(false);
+                    unsafe { SetCapture(state.canvas); }
+                },
+                WM_MOUSEMOVE if state.pressed.get() => {
+                    state.cursor.set(cursor_position(state.canvas));
+                    if !state.active.get() && drag_started(&state) {
+                        begin(&state);
+                    }
+
+                    if state.active.get() {
+                        update(&state);
+                    }
+                },
+                WM_LBUTTONUP if state.pressed.get() => {
+                    finish(&state, RUBBER_BAND_SELECT);
+                },
+                WM_CAPTURECHANGED if state.pressed.get() => {
+                    finish(&state, RUBBER_BAND_CANCEL);
+                },
+                WM_KEYDOWN if state.pressed.get() && w == VK_ESCAPE as WPARAM => {
+                    finish(&state, RUBBER_BAND_CANCEL);
+                    return Some(0);
+                },
+                _ => {}
+            }
+
+            None
+        });
+
+        let state = self.state.clone().unwrap();
+        let overlay_handler = bind_raw_event_handler_inner(&self.overlay, RUBBER_BAND_ID, move |hwnd, msg, w, _l| {
+            match msg {
+                WM_PAINT => {
+                    unsafe { paint_overlay(hwnd, state.color); }
+                    Some(0)
+                },
+                WM_TIMER if w == RUBBER_BAND_ID => {
+                    if state.active.get() && auto_scroll(&state) {
+                        state.cursor.set(cursor_position(state.canvas));
+                        update(&state);
+                    }
+                    Some(0)
+                },
+                _ => None
+            }
+        });
+
+        *self.handler0.borrow_mut() = Some(canvas_handler.unwrap());
+        *self.handler1.borrow_mut() = Some(overlay_handler.unwrap());
+    }
+
+}
+
+impl PartialEq for RubberBandSelector {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle && self.overlay == other.overlay
+    }
+}
+
+impl Eq for RubberBandSelector {}
+
+impl Drop for RubberBandSelector {
+    fn drop(&mut self) {
+        if let Some(h) = self.handler0.borrow_mut().take() {
+            drop(unbind_raw_event_handler(&h));
+        }
+
+        if let Some(h) = self.handler1.borrow_mut().take() {
+            drop(unbind_raw_event_handler(&h));
+        }
+
+        // The canvas belongs to the application
+        self.overlay.destroy();
+    }
+}
+
+pub struct RubberBandSelectorBuilder {
+    canvas: Option<ControlHandle>,
+    color: Option<[u8; 3]>,
+    opacity: u8,
+    auto_scroll: bool,
+    scroll_margin: u32,
+    scroll_unit: u32,
+}
+
+impl RubberBandSelectorBuilder {
+
+    pub fn canvas<C: Into<ControlHandle>>(mut self, canvas: C) -> RubberBandSelectorBuilder {
+        self.canvas = Some(canvas.into());
+        self
+    }
+
+    pub fn color(mut self, color: Option<[u8; 3]>) -> RubberBandSelectorBuilder {
+        self.color = color;
+        self
+    }
+
+    pub fn opacity(mut self, opacity: u8) -> RubberBandSelectorBuilder {
+        self.opacity = opacity;
+        self
+    }
+
+    pub fn auto_scroll(mut self, auto_scroll: bool) -> RubberBandSelectorBuilder {
+        self.auto_scroll = auto_scroll;
+        self
+    }
+
+    pub fn scroll_margin(mut self, margin: u32) -> RubberBandSelectorBuilder {
+        self.scroll_margin = margin;
+        self
+    }
+
+    pub fn scroll_unit(mut self, unit: u32) -> RubberBandSelectorBuilder {
+        self.scroll_unit = unit;
+        self
+    }
+
+    pub fn build(self, out: &mut RubberBandSelector) -> Result<(), NwgError> {
+        use winapi::um::winuser::{SetLayeredWindowAttributes, GetSysColor, COLOR_HIGHLIGHT, LWA_ALPHA};
+        use winapi::um::wingdi::RGB;
+
+        let canvas = match self.canvas {
+            Some(c) => match c.hwnd() {
+                Some(handle) => Ok(handle),
+                None => Err(NwgError::control_create("RubberBandSelector canvas must be a window control"))
+            },
+            None => Err(NwgError::no_parent("RubberBandSelector"))
+        }?;
+
+        *out = Default::default();
+
+        // The overlay is owned by the canvas so that it stays above the application window without being topmost
+        out.overlay = ControlBase::build_hwnd()
+            .class_name(nwg_class("NativeWindowsGuiWindow"))
+            .flags(WS_POPUP)
+            .ex_flags(WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE)
+            .size((1, 1))
+            .position((0, 0))
+            .parent(Some(ControlHandle::Hwnd(canvas)))
+            .build()?;
+
+        let overlay = out.overlay.hwnd().unwrap();
+        unsafe { SetLayeredWindowAttributes(overlay, 0, self.opacity, LWA_ALPHA); }
+
+        let color = match self.color {
+            Some([r, g, b]) => RGB(r, g, b),
+            None => unsafe { GetSysColor(COLOR_HIGHLIGHT) }
+        };
+
+        out.handle = ControlHandle::Hwnd(canvas);
+        out.state = Some(Rc::new(BandState {
+            canvas,
+            overlay,
+            color,
+            auto_scroll: self.auto_scroll,
+            scroll_margin: self.scroll_margin as i32,
+            scroll_unit: self.scroll_unit.max(1) as i32,
+            anchor: Cell::new((0, 0)),
+            cursor: Cell::new((0, 0)),
+            pressed: Cell::new(false),
+            active: Cell::new(false),
+            rect: Cell::new(None),
+        }));
+
+        out.bind();
+
+        Ok(())
+    }
+
+}
+
+/**
+    Return the index of the item under `point`, or `None` if there is no item under the point.
+    Items are `[x, y, width, height]` rectangles drawn in the order of the slice, so the last items are on top and are tested first.
+*/
+pub fn hit_test_point(items: &[[i32; 4]], point: (i32, i32)) -> Option<usize> {
+    let (x, y) = point;
+    items.iter().rposition(|&[ix, iy, w, h]| x >= ix && x < ix + w && y >= iy && y < iy + h)
+}
+
+/**
+    Return the indices of the items selected by a `[x, y, width, height]` rectangle, in the order of the slice.
+    Items are `[x, y, width, height]` rectangles.
+*/
+pub fn hit_test_rect(items: &[[i32; 4]], rect: [i32; 4], mode: SelectionMode) -> Vec<usize> {
+    let [rx, ry, rw, rh] = rect;
+    let (right, bottom) = (rx + rw, ry + rh);
+
+    items.iter()
+        .enumerate()
+        .filter(|(_, item)| {
+            let [ix, iy, w, h] = **item;
+            match mode {
+                SelectionMode::Intersect => ix < right && ix + w > rx && iy < bottom && iy + h > ry,
+                SelectionMode::Contain => ix >= rx && iy >= ry && ix + w <= right && iy + h <= bottom,
+            }
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Return the cursor position in the client area of the canvas
+fn cursor_position(canvas: HWND) -> (i32, i32) {
+    use winapi::um::winuser::{GetCursorPos, ScreenToClient};
+
+    let mut p = POINT { x: 0, y: 0 };
+    unsafe {
+        GetCursorPos(&mut p);
+        ScreenToClient(canvas, &mut p);
+    }
+
+    (p.x, p.y)
+}
+
+/// Return the scroll position of the canvas in pixels. Canvas without scroll bars return 0.
+fn scroll_offset(state: &BandState) -> (i32, i32) {
+    use winapi::um::winuser::{GetScrollPos, SB_HORZ, SB_VERT};
+
+    unsafe {
+        let x = GetScrollPos(state.canvas, SB_HORZ as i32);
+        let y = GetScrollPos(state.canvas, SB_VERT as i32);
+        (x * state.scroll_unit, y * state.scroll_unit)
+    }
+}
+
+/// Return true if the cursor moved further than the system drag distance since the button was pressed
+fn drag_started(state: &BandState) -> bool {
+    use winapi::um::winuser::{GetSystemMetrics, SM_CXDRAG, SM_CYDRAG};
+
+    let (ax, ay) = state.anchor.get();
+    let (sx, sy) = scroll_offset(state);
+    let (x, y) = state.cursor.get();
+
+    let (dx, dy) = unsafe { (GetSystemMetrics(SM_CXDRAG), GetSystemMetrics(SM_CYDRAG)) };
+    (x - (ax - sx)).abs() > dx || (y - (ay - sy)).abs() > dy
+}
+
+fn begin(state: &BandState) {
+    use winapi::um::winuser::SetTimer;
+
+    state.active.set(true);
+
+    if state.auto_scroll {
+        unsafe { SetTimer(state.overlay, RUBBER_BAND_ID, AUTO_SCROLL_INTERVAL, None); }
+    }
+}
+
+/// Compute the selection rectangle, move the overlay over it and raise `OnRubberBandChanged`
+fn update(state: &BandState) {
+    use winapi::um::winuser::{GetClientRect, ClientToScreen, SetWindowPos, InvalidateRect, HWND_TOP, SWP_NOACTIVATE, SWP_SHOWWINDOW};
+
+    let (ax, ay) = state.anchor.get();
+    let (sx, sy) = scroll_offset(state);
+    let (ax, ay) = (ax - sx, ay - sy);
+    let (x, y) = state.cursor.get();
+
+    let rect = [ax.min(x), ay.min(y), (ax - x).abs(), (ay - y).abs()];
+    if state.rect.get() == Some(rect) {
+        return;
+    }
+
+    state.rect.set(Some(rect));
+
+    unsafe {
+        // The overlay only covers the visible part of the rectangle
+        let mut client: RECT = mem::zeroed();
+        GetClientRect(state.canvas, &mut client);
+
+        let [rx, ry, rw, rh] = rect;
+        let mut top_left = POINT { x: rx.max(client.left), y: ry.max(client.top) };
+        let mut bottom_right = POINT { x: (rx + rw).min(client.right), y: (ry + rh).min(client.bottom) };
+        ClientToScreen(state.canvas, &mut top_left);
+        ClientToScreen(state.canvas, &mut bottom_right);
+
+        let (width, height) = ((bottom_right.x - top_left.x).max(0), (bottom_right.y - top_left.y).max(0));
+        SetWindowPos(state.overlay, HWND_TOP, top_left.x, top_left.y, width, height, SWP_NOACTIVATE | SWP_SHOWWINDOW);
+        InvalidateRect(state.overlay, ptr::null(), 0);
+    }
+
+    wh::send_message(state.canvas, wh::NWG_RUBBER_BAND, RUBBER_BAND_CHANGED, 0);
+}
+
+/// End the selection. The events are only raised if the rectangle was displayed.
+fn finish(state: &BandState, result: WPARAM) {
+    use winapi::um::winuser::{KillTimer, ReleaseCapture, ShowWindow, GetCapture, SW_HIDE};
+
+    let active = state.active.get();
+
+    // Cleared first because `ReleaseCapture` sends `WM_CAPTURECHANGED` to the canvas
+    state.pressed.set(false);
+    state.active.set(false);
+
+    unsafe {
+        KillTimer(state.overlay, RUBBER_BAND_ID);
+        ShowWindow(state.overlay, SW_HIDE);
+
+        if GetCapture() == state.canvas {
+            ReleaseCapture();
+        }
+    }
+
+    if !active {
+        state.rect.set(None);
+        return;
+    }
+
+    if result == RUBBER_BAND_CANCEL {
+        state.rect.set(None);
+    }
+
+    wh::send_message(state.canvas, wh::NWG_RUBBER_BAND, result, 0);
+}
+
+/// Scroll the canvas if the cursor is near its edges. Return true if the canvas was scrolled.
+fn auto_scroll(state: &BandState) -> bool {
+    use winapi::um::winuser::{GetClientRect, GetWindowLongW, GWL_STYLE, WS_HSCROLL, WS_VSCROLL, WM_HSCROLL, WM_VSCROLL,
+        SB_LINEUP, SB_LINEDOWN, SB_LINELEFT, SB_LINERIGHT};
+
+    let (x, y) = cursor_position(state.canvas);
+    let margin = state.scroll_margin;
+    let before = scroll_offset(state);
+
+    unsafe {
+        let mut client: RECT = mem::zeroed();
+        GetClientRect(state.canvas, &mut client);
+        let style = GetWindowLongW(state.canvas, GWL_STYLE) as u32;
+
+        if style & WS_HSCROLL == WS_HSCROLL {
+            if x < client.left + margin {
+                wh::send_message(state.canvas, WM_HSCROLL, SB_LINELEFT as WPARAM, 0 as LPARAM);
+            } else if x > client.right - margin {
+                wh::send_message(state.canvas, WM_HSCROLL, SB_LINERIGHT as WPARAM, 0 as LPARAM);
+            }
+        }
+
+        if style & WS_VSCROLL == WS_VSCROLL {
+            if y < client.top + margin {
+                wh::send_message(state.canvas, WM_VSCROLL, SB_LINEUP as WPARAM, 0 as LPARAM);
+            } else if y > client.bottom - margin {
+                wh::send_message(state.canvas, WM_VSCROLL, SB_LINEDOWN as WPARAM, 0 as LPARAM);
+            }
+        }
+    }
+
+    scroll_offset(state) != before
+}
+
+/// Fill the overlay with the selection color and draw a frame around it
+unsafe fn paint_overlay(overlay: HWND, color: COLORREF) {
+    use winapi::um::winuser::{BeginPaint, EndPaint, GetClientRect, FillRect, FrameRect, PAINTSTRUCT};
+    use winapi::um::wingdi::{CreateSolidBrush, DeleteObject, RGB, GetRValue, GetGValue, GetBValue};
+
+    let mut paint: PAINTSTRUCT = mem::zeroed();
+    let dc = BeginPaint(overlay, &mut paint);
+
+    let mut client: RECT = mem::zeroed();
+    GetClientRect(overlay, &mut client);
+
+    // The frame is a darker shade of the fill color
+    let fill = CreateSolidBrush(color);
+    let frame = CreateSolidBrush(RGB(GetRValue(color) / 2, GetGValue(color) / 2, GetBValue(color) / 2));
+    FillRect(dc, &client, fill);
+    FrameRect(dc, &client, frame);
+    DeleteObject(fill as _);
+    DeleteObject(frame as _);
+
+    EndPaint(overlay, &paint);
+}
@@ -0,0 +1,806 @@
+use winapi::shared::windef::{HWND, HDC, POINT, RECT};
+use winapi::shared::minwindef::WPARAM;
+use winapi::um::winuser::{WS_VISIBLE, WS_DISABLED, WS_CHILD};
+use crate::win32::{base_helper::check_hwnd, window_helper as wh};
+use crate::win32::window::nwg_class;
+use super::{ControlBase, ControlHandle, Viewport2D};
+use super::control_builder::BuilderState;
+use super::viewport::ViewportState;
+use crate::{NwgError, RawEventHandler, unbind_raw_event_handler};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::{ptr, mem};
+
+const NOT_BOUND: &'static str = "Ruler is not yet bound to a winapi object";
+const BAD_HANDLE: &'static str = "INTERNAL ERROR: Ruler handle is not HWND!";
+
+/// The id of the raw event handler of the ruler. The handlers on the canvas add the orientation to this id.
+const RULER_ID: usize = 0x5255;
+
+/// The smallest distance between two labelled ticks, in pixels
+const MIN_MAJOR_SPACING: f64 = 60.0;
+
+/// The smallest distance between two ticks, in pixels
+const MIN_MINOR_SPACING: f64 = 5.0;
+
+/// The distance under which a guide is picked by the mouse or snapped to a tick, in pixels
+const GUIDE_DISTANCE: f64 = 4.0;
+
+/// Sent in the wparam of `NWG_RULER_GUIDE`
+pub(crate) const RULER_GUIDE_MOVED: WPARAM = 0;
+pub(crate) const RULER_GUIDES_CHANGED: WPARAM = 1;
+
+
+bitflags! {
+    /**
+        The ruler flags
+
+        * VISIBLE:  The ruler is immediatly visible after creation
+        * DISABLED: The guides cannot be dragged by the user
+    */
+    pub struct RulerFlags: u32 {
+        const VISIBLE = WS_VISIBLE;
+        const DISABLED = WS_DISABLED;
+    }
+}
+
+/// The side of the canvas where a ruler is placed
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RulerOrientation {
+    /// A ruler above the canvas. It measures the X axis and creates vertical guides.
+    Horizontal,
+
+    /// A ruler on the left of the canvas. It measures the Y axis and creates horizontal guides.
+    Vertical,
+}
+
+/// A guide being dragged by the user
+#[derive(Copy, Clone, Debug)]
+struct GuideDrag {
+    /// The index of the guide that is moved, or `None` for a new guide
+    index: Option<usize>,
+
+    /// The world position of the guide, or `None` if the cursor is outside the canvas
+    position: Option<f64>,
+}
+
+/// The state shared with the raw event handlers
+struct RulerState {
+    ruler: HWND,
+    vertical: bool,
+    viewport: Rc<ViewportState>,
+    unit_scale: Cell<f64>,
+    snap: Cell<bool>,
+    guides: RefCell<Vec<f64>>,
+    drag: Cell<Option<GuideDrag>>,
+
+    /// The cursor position along the axis, in client coordinates of the canvas
+    marker: Cell<Option<i32>>,
+}
+
+impl RulerState {
+
+    /// Return the distance between the origin of the canvas and the origin of the ruler along the axis
+    fn canvas_offset(&self) -> i32 {
+        use winapi::um::winuser::ClientToScreen;
+
+        let mut canvas = POINT { x: 0, y: 0 };
+        let mut ruler = POINT { x: 0, y: 0 };
+        unsafe {
+            ClientToScreen(self.viewport.canvas, &mut canvas);
+            ClientToScreen(self.ruler, &mut ruler);
+        }
+
+        match self.vertical {
+            true => canvas.y - ruler.y,
+            false => canvas.x - ruler.x,
+        }
+    }
+
+    /// Convert a world position to a client position of the ruler along the axis
+    fn world_to_ruler(&self, value: f64) -> f64 {
+        self.viewport.world_to_screen_axis(value, self.vertical) + self.canvas_offset() as f64
+    }
+
+    /// Return the displayed distance between two labelled ticks and the number of intervals between them
+    fn ticks(&self) -> (f64, u32) {
+        let pixels_per_unit = self.viewport.zoom.get() / self.unit_scale.get();
+
+        let mut magnitude = 10f64.powf((MIN_MAJOR_SPACING / pixels_per_unit).log10().floor());
+        loop {
+            for &m in [1.0, 2.0, 5.0].iter() {
+                let step = m * magnitude;
+                if step * pixels_per_unit >= MIN_MAJOR_SPACING {
+                    let spacing = step * pixels_per_unit;
+                    let subdivisions = [10, 5, 2].iter()
+                        .cloned()
+                        .find(|&s| spacing / s as f64 >= MIN_MINOR_SPACING && (m != 2.0 || s != 5))
+                        .unwrap_or(1);
+
+                    return (step, subdivisions);
+                }
+            }
+
+            magnitude *= 10.0;
+        }
+    }
+
+    /// Snap a world position to the closest tick if it is near enough
+    fn snap_to_ticks(&self, value: f64) -> f64 {
+        if !self.snap.get() {
+            return value;
+        }
+
+        let (step, subdivisions) = self.ticks();
+        let scale = self.unit_scale.get();
+        let minor = step / subdivisions as f64;
+
+        let snapped = ((value * scale) / minor).round() * minor / scale;
+        match ((snapped - value) * self.viewport.zoom.get()).abs() <= GUIDE_DISTANCE {
+            true => snapped,
+            false => value
+        }
+    }
+
+    /// Return the world position of the cursor along the axis, or `None` if the cursor is outside the canvas
+    fn cursor_world_position(&self) -> Option<f64> {
+        use winapi::um::winuser::{GetCursorPos, ScreenToClient, GetClientRect};
+
+        let canvas = self.viewport.canvas;
+        let mut p = POINT { x: 0, y: 0 };
+        let mut client: RECT = unsafe { mem::zeroed() };
+        unsafe {
+            GetCursorPos(&mut p);
+            ScreenToClient(canvas, &mut p);
+            GetClientRect(canvas, &mut client);
+        }
+
+        let inside = p.x >= client.left && p.x < client.right && p.y >= client.top && p.y < client.bottom;
+        if !inside {
+            return None;
+        }
+
+        let value = match self.vertical { true => p.y, false => p.x };
+        Some(self.snap_to_ticks(self.viewport.screen_to_world_axis(value as f64, self.vertical)))
+    }
+
+}
+
+/**
+A ruler displays the scale of a `Viewport2D` along one side of a canvas, like the rulers of a drawing application.
+The ticks and the labels follow the zoom and the offset of the viewport, and a marker follows the cursor over the canvas.
+
+Guidelines are created by dragging from the ruler into the canvas. Existing guides are displayed as markers on the ruler:
+drag a marker to move the guide, or drag it out of the canvas to remove it. While a guide is dragged, its position snaps to the
+closest tick (if `snap` is enabled) and `OnRulerGuideMoved` is raised. `OnRulerGuidesChanged` is raised when the drag ends.
+The canvas does not draw the guides by itself: use `guides` and `dragging_guide` to draw them with the rest of the drawing.
+
+Use `fit_to_canvas` to place the ruler along the canvas, and `snap_to_guides` to snap the items moved by the user to the guides.
+
+Requires the `extern-canvas` feature.
+
+**Builder parameters:**
+  * `parent`:      **Required.** The ruler parent container.
+  * `viewport`:    **Required.** The viewport tracked by the ruler.
+  * `orientation`: The side of the canvas where the ruler is placed. Defaults to `RulerOrientation::Horizontal`.
+  * `size`:        The ruler size.
+  * `position`:    The ruler position.
+  * `unit_scale`:  The displayed units per world unit (ex: 0.2645 to display millimeters for a world in 96 DPI pixels). Defaults to 1.0.
+  * `snap`:        Snap the dragged guides to the ticks. Defaults to `true`.
+  * `flags`:       A combination of the RulerFlags values.
+
+**Control events:**
+  * `OnRulerGuideMoved`:     When a guide is dragged by the user. Redraw the canvas with `dragging_guide`
+  * `OnRulerGuidesChanged`:  When a guide was added, moved or removed by the user
+
+```rust
+use native_windows_gui as nwg;
+
+fn build_rulers(window: &nwg::Window, canvas: &nwg::ExternCanvas, viewport: &nwg::Viewport2D, top: &mut nwg::Ruler, left: &mut nwg::Ruler) {
+    nwg::Ruler::builder()
+        .orientation(nwg::RulerOrientation::Horizontal)
+        .viewport(viewport)
+        .parent(window)
+        .build(top)
+        .unwrap();
+
+    nwg::Ruler::builder()
+        .orientation(nwg::RulerOrientation::Vertical)
+        .viewport(viewport)
+        .parent(window)
+        .build(left)
+        .unwrap();
+
+    top.fit_to_canvas(24);
+    left.fit_to_canvas(24);
+}
+```
+*/
+#[derive(Default)]
+pub struct Ruler {
+    pub handle: ControlHandle,
+    state: Option<Rc<RulerState>>,
+    handler0: RefCell<Option<RawEventHandler>>,
+    handler1: RefCell<Option<RawEventHandler>>,
+}
+
+impl Ruler {
+
+    pub fn builder<'a>() -> RulerBuilder<'a> {
+        RulerBuilder {
+            size: (300, 24),
+            position: (0, 0),
+            orientation: RulerOrientation::Horizontal,
+            unit_scale: 1.0,
+            snap: true,
+            flags: None,
+            ex_flags: 0,
+            parent: None,
+            viewport: None,
+            name: None,
+            state: Default::default(),
+        }
+    }
+
+    /// Return the side of the canvas where the ruler is placed
+    pub fn orientation(&self) -> RulerOrientation {
+        match self.state().vertical {
+            true => RulerOrientation::Vertical,
+            false => RulerOrientation::Horizontal,
+        }
+    }
+
+    /// Return the world position of the guides
+    pub fn guides(&self) -> Vec<f64> {
+        self.state().guides.borrow().clone()
+    }
+
+    /// Replace the guides. Positions are in world coordinates.
+    pub fn set_guides(&self, guides: &[f64]) {
+        let state = self.state();
+        *state.guides.borrow_mut() = guides.to_vec();
+        self.repaint();
+    }
+
+    /// Add a guide at a world position
+    pub fn add_guide(&self, position: f64) {
+        self.state().guides.borrow_mut().push(position);
+        self.repaint();
+    }
+
+    /// Remove all the guides
+    pub fn clear_guides(&self) {
+        self.state().guides.borrow_mut().clear();
+        self.repaint();
+    }
+
+    /**
+        Return the guide being dragged as `(index, position)`. `index` is `None` for a new guide.
+        `position` is `None` if the cursor is outside the canvas (the guide will be removed if the button is released there).
+        Returns `None` if no guide is dragged.
+    */
+    pub fn dragging_guide(&self) -> Option<(Option<usize>, Option<f64>)> {
+        self.state().drag.get().map(|d| (d.index, d.position))
+    }
+
+    /**
+        Snap a world position to the closest guide if it is within `distance` pixels of it.
+        Returns the position unchanged if no guide is close enough.
+    */
+    pub fn snap_to_guides(&self, position: f64, distance: u32) -> f64 {
+        let state = self.state();
+        let zoom = state.viewport.zoom.get();
+
+        state.guides.borrow().iter()
+            .map(|&g| (g, ((g - position) * zoom).abs()))
+            .filter(|&(_, d)| d <= distance as f64)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(g, _)| g)
+            .unwrap_or(position)
+    }
+
+    /// Return the displayed units per world unit
+    pub fn unit_scale(&self) -> f64 {
+        self.state().unit_scale.get()
+    }
+
+    /// Set the displayed units per world unit
+    pub fn set_unit_scale(&self, scale: f64) {
+        if scale > 0.0 {
+            self.state().unit_scale.set(scale);
+            self.repaint();
+        }
+    }
+
+    /// Return true if the dragged guides snap to the ticks
+    pub fn snap(&self) -> bool {
+        self.state().snap.get()
+    }
+
+    /// Set if the dragged guides snap to the ticks
+    pub fn set_snap(&self, snap: bool) {
+        self.state().snap.set(snap);
+    }
+
+    /**
+        Place the ruler along the canvas of the viewport: above it for a horizontal ruler, on its left for a vertical ruler.
+        `thickness` is the height (or the width) of the ruler. The ruler and the canvas must have the same parent. The canvas is not moved, so leave enough space around it.
+        Call this method again after the canvas is moved or resized.
+    */
+    pub fn fit_to_canvas(&self, thickness: u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let state = self.state();
+        let canvas = state.viewport.canvas;
+
+        unsafe {
+            let (x, y) = wh::get_window_position(canvas);
+            let (width, height) = wh::get_window_size(canvas);
+            let t = thickness as i32;
+
+            match state.vertical {
+                true => {
+                    wh::set_window_position(handle, x - t, y);
+                    wh::set_window_size(handle, thickness, height, false);
+                },
+                false => {
+                    wh::set_window_position(handle, x, y - t);
+                    wh::set_window_size(handle, width, thickness, false);
+                }
+            }
+        }
+
+        self.repaint();
+    }
+
+    /// Return true if the control user can interact with the control, return false otherwise
+    pub fn enabled(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_enabled(handle) }
+    }
+
+    /// Enable or disable the control
+    pub fn set_enabled(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_enabled(handle, v) }
+    }
+
+    /// Return true if the control is visible to the user. Will return true even if the
+    /// control is outside of the parent client view (ex: at the position (10000, 10000))
+    pub fn visible(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_visibility(handle) }
+    }
+
+    /// Show or hide the control to the user
+    pub fn set_visible(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_visibility(handle, v) }
+    }
+
+    /// Return the size of the ruler in the parent window
+    pub fn size(&self) -> (u32, u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_size(handle) }
+    }
+
+    /// Set the size of the ruler in the parent window
+    pub fn set_size(&self, x: u32, y: u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_size(handle, x, y, false) }
+    }
+
+    /// Return the position of the ruler in the parent window
+    pub fn position(&self) -> (i32, i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_position(handle) }
+    }
+
+    /// Set the position of the ruler in the parent window
+    pub fn set_position(&self, x: i32, y: i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_position(handle, x, y) }
+    }
+
+    /// Winapi class name used during control creation
+    pub fn class_name(&self) -> &'static str {
+        nwg_class("NativeWindowsGuiWindow")
+    }
+
+    /// Winapi base flags used during window creation
+    pub fn flags(&self) -> u32 {
+        WS_VISIBLE
+    }
+
+    /// Winapi flags required by the control
+    pub fn forced_flags(&self) -> u32 {
+        WS_CHILD
+    }
+
+    fn state(&self) -> &RulerState {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state.as_ref().unwrap()
+    }
+
+    fn repaint(&self) {
+        use winapi::um::winuser::InvalidateRect;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { InvalidateRect(handle, ptr::null(), 0); }
+    }
+
+    /// Paint the ruler, drag the guides, and follow the cursor over the canvas
+    fn bind(&self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::shared::minwindef::{LOWORD, HIWORD};
+        use winapi::um::winuser::{WM_PAINT, WM_ERASEBKGND, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MOUSEMOVE, WM_MOUSELEAVE, WM_CAPTURECHANGED,
+            WM_SETCURSOR, SetCapture, ReleaseCapture, GetCapture, SetCursor, LoadCursorW, InvalidateRect, IDC_SIZEWE, IDC_SIZENS,
+            TrackMouseEvent, TRACKMOUSEEVENT, TME_LEAVE};
+
+        let state = self.state.clone().unwrap();
+        let ruler_handler = bind_raw_event_handler_inner(&self.handle, RULER_ID, move |hwnd, msg, _w, _l| {
+            match msg {
+                WM_ERASEBKGND => { return Some(1); },
+                WM_PAINT => {
+                    unsafe { paint_ruler(hwnd, &state); }
+                    return Some(0);
+                },
+                WM_SETCURSOR if hovered_guide(&state).is_some() || state.drag.get().is_some() => {
+                    let cursor = match state.vertical { true => IDC_SIZENS, false => IDC_SIZEWE };
+                    unsafe { SetCursor(LoadCursorW(ptr::null_mut(), cursor)); }
+                    return Some(1);
+                },
+                WM_LBUTTONDOWN => {
+                    let index = hovered_guide(&state);
+                    let position = index.map(|i| state.guides.borrow()[i]);
+                    state.drag.set(Some(GuideDrag { index, position }));
+                    unsafe { SetCapture(hwnd); }
+                },
+                WM_MOUSEMOVE => if let Some(drag) = state.drag.get() {
+                    let position = state.cursor_world_position();
+                    if position != drag.position {
+                        state.drag.set(Some(GuideDrag { index: drag.index, position }));
+                        unsafe { InvalidateRect(hwnd, ptr::null(), 0); }
+                        wh::send_message(hwnd, wh::NWG_RULER_GUIDE, RULER_GUIDE_MOVED, 0);
+                    }
+                },
+                WM_LBUTTONUP => if let Some(drag) = state.drag.get() {
+                    // Cleared first because `ReleaseCapture` sends `WM_CAPTURECHANGED` to the ruler
+                    state.drag.set(None);
+                    unsafe {
+                        if GetCapture() == hwnd {
+                            ReleaseCapture();
+                        }
+                    }
+
+                    let changed = {
+                        let mut guides = state.guides.borrow_mut();
+                        match (drag.index, drag.position) {
+                            (Some(i), Some(p)) => { guides[i] = p; true },
+                            (Some(i), None) => { guides.remove(i); true },
+                            (None, Some(p)) => { guides.push(p); true },
+                            (None, None) => false,
+                        }
+                    };
+
+                    unsafe { InvalidateRect(hwnd, ptr::null(), 0); }
+                    if changed {
+                        wh::send_message(hwnd, wh::NWG_RULER_GUIDE, RULER_GUIDES_CHANGED, 0);
+                    }
+                },
+                WM_CAPTURECHANGED => if state.drag.get().is_some() {
+                    state.drag.set(None);
+                    unsafe { InvalidateRect(hwnd, ptr::null(), 0); }
+                    wh::send_message(hwnd, wh::NWG_RULER_GUIDE, RULER_GUIDE_MOVED, 0);
+                },
+                _ => {}
+            }
+
+            None
+        });
+
+        let state = self.state.clone().unwrap();
+        let canvas = ControlHandle::Hwnd(state.viewport.canvas);
+        let canvas_handler = bind_raw_event_handler_inner(&canvas, RULER_ID + 1 + state.vertical as usize, move |hwnd, msg, _w, l| {
+            match msg {
+                WM_MOUSEMOVE => {
+                    let (x, y) = (LOWORD(l as u32) as i16 as i32, HIWORD(l as u32) as i16 as i32);
+                    state.marker.set(Some(match state.vertical { true => y, false => x }));
+
+                    unsafe {
+                        let mut track = TRACKMOUSEEVENT { cbSize: mem::size_of::<TRACKMOUSEEVENT>() as u32, dwFlags: TME_LEAVE, hwndTrack: hwnd, dwHoverTime: 0 };
+                        TrackMouseEvent(&mut track);
+                        InvalidateRect(state.ruler, ptr::null(), 0);
+                    }
+                },
+                WM_MOUSELEAVE => {
+                    state.marker.set(None);
+                    unsafe { InvalidateRect(state.ruler, ptr::null(), 0); }
+                },
+                _ => {}
+            }
+
+            None
+        });
+
+        *self.handler0.borrow_mut() = Some(ruler_handler.unwrap());
+        *self.handler1.borrow_mut() = canvas_handler.ok();
+    }
+
+}
+
+impl PartialEq for Ruler {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for Ruler {}
+
+impl Drop for Ruler {
+    fn drop(&mut self) {
+        if let Some(h) = self.handler0.borrow_mut().take() {
+            drop(unbind_raw_event_handler(&h));
+        }
+
+        if let Some(h) = self.handler1.borrow_mut().take() {
+            drop(unbind_raw_event_handler(&h));
+        }
+
+        if let Some(state) = self.state.take() {
+            state.viewport.remove_listener(state.ruler);
+        }
+
+        self.handle.destroy();
+    }
+}
+
+pub struct RulerBuilder<'a> {
+    size: (i32, i32),
+    position: (i32, i32),
+    orientation: RulerOrientation,
+    unit_scale: f64,
+    snap: bool,
+    flags: Option<RulerFlags>,
+    ex_flags: u32,
+    parent: Option<ControlHandle>,
+    viewport: Option<&'a Viewport2D>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
+}
+
+impl<'a> RulerBuilder<'a> {
+
+    pub fn flags(mut self, flags: RulerFlags) -> RulerBuilder<'a> {
+        self.flags = Some(flags);
+        self
+    }
+
+    pub fn ex_flags(mut self, flags: u32) -> RulerBuilder<'a> {
+        self.ex_flags = flags;
+        self
+    }
+
+    pub fn size(mut self, size: (i32, i32)) -> RulerBuilder<'a> {
+        self.size = size;
+        self
+    }
+
+    pub fn position(mut self, pos: (i32, i32)) -> RulerBuilder<'a> {
+        self.position = pos;
+        self
+    }
+
+    pub fn orientation(mut self, orientation: RulerOrientation) -> RulerBuilder<'a> {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn unit_scale(mut self, scale: f64) -> RulerBuilder<'a> {
+        self.unit_scale = scale;
+        self
+    }
+
+    pub fn snap(mut self, snap: bool) -> RulerBuilder<'a> {
+        self.snap = snap;
+        self
+    }
+
+    pub fn viewport(mut self, viewport: &'a Viewport2D) -> RulerBuilder<'a> {
+        self.viewport = Some(viewport);
+        self
+    }
+
+    pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> RulerBuilder<'a> {
+        self.parent = Some(p.into());
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> RulerBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn build(self, out: &mut Ruler) -> Result<(), NwgError> {
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
+
+        let parent = match self.parent {
+            Some(p) => Ok(p),
+            None => Err(NwgError::no_parent("Ruler"))
+        }?;
+
+        let viewport = match self.viewport {
+            Some(v) => v.shared_state(),
+            None => { return Err(NwgError::control_create("A Ruler requires a Viewport2D")); }
+        };
+
+        if !(self.unit_scale > 0.0) {
+            return Err(NwgError::control_create("Ruler unit scale must be positive"));
+        }
+
+        *out = Default::default();
+
+        out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
+            .class_name(out.class_name())
+            .forced_flags(out.forced_flags())
+            .flags(flags)
+            .ex_flags(self.ex_flags)
+            .size(self.size)
+            .position(self.position)
+            .text("")
+            .parent(Some(parent))
+            .build()?;
+
+        let ruler = out.handle.hwnd().unwrap();
+        viewport.add_listener(ruler);
+
+        out.state = Some(Rc::new(RulerState {
+            ruler,
+            vertical: self.orientation == RulerOrientation::Vertical,
+            viewport,
+            unit_scale: Cell::new(self.unit_scale),
+            snap: Cell::new(self.snap),
+            guides: RefCell::new(Vec::new()),
+            drag: Cell::new(None),
+            marker: Cell::new(None),
+        }));
+
+        out.bind();
+
+        Ok(())
+    }
+
+}
+
+/// Return the index of the guide under the cursor of the ruler
+fn hovered_guide(state: &RulerState) -> Option<usize> {
+    use winapi::um::winuser::{GetCursorPos, ScreenToClient};
+
+    let mut p = POINT { x: 0, y: 0 };
+    unsafe {
+        GetCursorPos(&mut p);
+        ScreenToClient(state.ruler, &mut p);
+    }
+
+    let cursor = match state.vertical { true => p.y, false => p.x } as f64;
+    state.guides.borrow().iter()
+        .position(|&g| (state.world_to_ruler(g) - cursor).abs() <= GUIDE_DISTANCE)
+}
+
+/// Format the label of a tick with enough decimals for the tick step
+fn tick_label(value: f64, step: f64) -> String {
+    let decimals = match step >= 1.0 {
+        true => 0,
+        false => (-step.log10()).ceil() as usize
+    };
+
+    let value = match value.abs() < step / 2.0 { true => 0.0, false => value };
+    format!("{:.*}", decimals, value)
+}
+
+/// Draw the ruler in a memory bitmap, then copy it to the window
+unsafe fn paint_ruler(hwnd: HWND, state: &RulerState) {
+    use winapi::um::winuser::{BeginPaint, EndPaint, GetClientRect, FillRect, GetSysColorBrush, GetSysColor, PAINTSTRUCT,
+        COLOR_BTNFACE, COLOR_BTNSHADOW, COLOR_BTNTEXT, COLOR_HIGHLIGHT, COLOR_HOTLIGHT};
+    use winapi::um::wingdi::{CreateCompatibleDC, CreateCompatibleBitmap, SelectObject, DeleteObject, DeleteDC, BitBlt, SetBkMode,
+        SetTextColor, GetStockObject, GetObjectW, CreateFontIndirectW, TextOutW, LOGFONTW, SRCCOPY, TRANSPARENT, DEFAULT_GUI_FONT};
+
+    let mut paint: PAINTSTRUCT = mem::zeroed();
+    let dc = BeginPaint(hwnd, &mut paint);
+
+    let mut client: RECT = mem::zeroed();
+    GetClientRect(hwnd, &mut client);
+    let (width, height) = (client.right, client.bottom);
+
+    let memory: HDC = CreateCompatibleDC(dc);
+    let bitmap = CreateCompatibleBitmap(dc, width.max(1), height.max(1));
+    let old_bitmap = SelectObject(memory, bitmap as _);
+
+    FillRect(memory, &client, GetSysColorBrush(COLOR_BTNFACE));
+
+    let vertical = state.vertical;
+    let (length, thickness) = match vertical { true => (height, width), false => (width, height) };
+
+    // Fill a rectangle given along the axis (`a`) and across the axis (`c`). The edge facing the canvas is at `c = thickness`.
+    let fill = |a: i32, c: i32, a_len: i32, c_len: i32, brush| {
+        let rect = match vertical {
+            true => RECT { left: c, top: a, right: c + c_len, bottom: a + a_len },
+            false => RECT { left: a, top: c, right: a + a_len, bottom: c + c_len },
+        };
+        FillRect(memory, &rect, brush);
+    };
+
+    fill(0, thickness - 1, length, 1, GetSysColorBrush(COLOR_BTNSHADOW));
+
+    // Vertical rulers draw their labels rotated by 90 degrees
+    let mut log_font: LOGFONTW = mem::zeroed();
+    GetObjectW(GetStockObject(DEFAULT_GUI_FONT as i32), mem::size_of::<LOGFONTW>() as i32, &mut log_font as *mut LOGFONTW as _);
+    if vertical {
+        log_font.lfEscapement = 900;
+        log_font.lfOrientation = 900;
+    }
+    let font = CreateFontIndirectW(&log_font);
+    let old_font = SelectObject(memory, font as _);
+    SetBkMode(memory, TRANSPARENT as i32);
+    SetTextColor(memory, GetSysColor(COLOR_BTNTEXT));
+
+    let text_brush = GetSysColorBrush(COLOR_BTNTEXT);
+    let (step, subdivisions) = state.ticks();
+    let scale = state.unit_scale.get();
+    let minor = step / subdivisions as f64;
+    let first = (state.viewport.screen_to_world_axis(-(state.canvas_offset() as f64), vertical) * scale / minor).floor() as i64;
+    let last = (state.viewport.screen_to_world_axis((length - state.canvas_offset()) as f64, vertical) * scale / minor).ceil() as i64;
+
+    for i in first..=last {
+        let value = i as f64 * minor;
+        let position = state.world_to_ruler(value / scale).round() as i32;
+
+        let tick = match i.rem_euclid(subdivisions as i64) {
+            0 => thickness,
+            n if subdivisions % 2 == 0 && n == subdivisions as i64 / 2 => thickness / 2,
+            _ => thickness / 4,
+        };
+
+        fill(position, thickness - tick, 1, tick, text_brush);
+
+        if i.rem_euclid(subdivisions as i64) == 0 {
+            let label: Vec<u16> = tick_label(value, step).encode_utf16().collect();
+            match vertical {
+                true => TextOutW(memory, 1, position - 2, label.as_ptr(), label.len() as i32),
+                false => TextOutW(memory, position + 2, 1, label.as_ptr(), label.len() as i32),
+            };
+        }
+    }
+
+    // Guides and the guide being dragged
+    let guide_brush = GetSysColorBrush(COLOR_HOTLIGHT);
+    let drag = state.drag.get();
+    for (i, &guide) in state.guides.borrow().iter().enumerate() {
+        if drag.map(|d| d.index == Some(i)).unwrap_or(false) {
+            continue;
+        }
+
+        let position = state.world_to_ruler(guide).round() as i32;
+        fill(position - 1, 0, 3, thickness, guide_brush);
+    }
+
+    if let Some(GuideDrag { position: Some(guide), .. }) = drag {
+        let position = state.world_to_ruler(guide).round() as i32;
+        fill(position - 1, 0, 3, thickness, guide_brush);
+    }
+
+    // Cursor marker
+    if let Some(marker) = state.marker.get() {
+        fill(marker + state.canvas_offset(), 0, 1, thickness, GetSysColorBrush(COLOR_HIGHLIGHT));
+    }
+
+    BitBlt(dc, 0, 0, width, height, memory, 0, 0, SRCCOPY);
+
+    SelectObject(memory, old_font);
+    SelectObject(memory, old_bitmap);
+    DeleteObject(font as _);
+    DeleteObject(bitmap as _);
+    DeleteDC(memory);
+
+    EndPaint(hwnd, &paint);
+}
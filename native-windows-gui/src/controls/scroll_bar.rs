@@ -3,6 +3,7 @@ use crate::win32::window_helper as wh;
 use crate::win32::base_helper::check_hwnd;
 use crate::{NwgError, RawEventHandler};
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
 use std::{mem, cell::RefCell, ops::Range};
 
 const NOT_BOUND: &'static str = "Scroll bar is not yet bound to a winapi object";
@@ -51,6 +52,9 @@ Requires the `scroll-bar` feature.
   * `OnHorizontalScroll`: When the value of a scrollbar with the HORIZONTAL flags is changed
   * `MousePress(_)`: Generic mouse press events on the button
   * `OnMouseMove`: Generic mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
 
 ```rust
@@ -84,7 +88,9 @@ impl ScrollBar {
             parent: None,
             focus: false,
             range: None,
-            pos: None
+            pos: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -333,6 +339,8 @@ pub struct ScrollBarBuilder {
     focus: bool,
     range: Option<Range<usize>>,
     pos: Option<usize>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl ScrollBarBuilder {
@@ -382,8 +390,13 @@ impl ScrollBarBuilder {
         self
     }
 
+    pub fn name(mut self, name: &str) -> ScrollBarBuilder {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut ScrollBar) -> Result<(), NwgError> {
-        let flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
 
         let parent = match self.parent {
             Some(p) => Ok(p),
@@ -393,6 +406,7 @@ impl ScrollBarBuilder {
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -0,0 +1,447 @@
+use winapi::um::winuser::{WS_DISABLED, BS_NOTIFY, WS_VISIBLE, WS_TABSTOP, WS_CHILD};
+use winapi::um::commctrl::{BS_SPLITBUTTON, BS_DEFSPLITBUTTON};
+use crate::win32::{
+    base_helper::check_hwnd,
+    window_helper as wh
+};
+use crate::{NwgError, Font};
+use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
+
+#[cfg(feature = "menu")]
+use crate::{Menu, RawEventHandler, unbind_raw_event_handler};
+
+#[cfg(feature = "menu")]
+use std::cell::RefCell;
+
+const NOT_BOUND: &'static str = "SplitButton is not yet bound to a winapi object";
+const BAD_HANDLE: &'static str = "INTERNAL ERROR: SplitButton handle is not HWND!";
+
+
+bitflags! {
+    /**
+        The split button flags
+
+        * NONE:     No flags. Equivalent to a invisible blank button.
+        * VISIBLE:  The button is immediatly visible after creation
+        * DISABLED: The button cannot be interacted with by the user. It also has a grayed out look.
+        * DEFAULT:  The button is drawn as the default button of the window. See `SplitButton::set_default` to click it with Enter.
+        * NOTIFY:   Enable the `OnButtonDoubleClick` event
+        * TAB_STOP: The control can be selected using tab navigation
+    */
+    pub struct SplitButtonFlags: u32 {
+        const NONE = 0;
+        const VISIBLE = WS_VISIBLE;
+        const DISABLED = WS_DISABLED;
+        const DEFAULT = BS_DEFSPLITBUTTON;
+        const NOTIFY = BS_NOTIFY;
+        const TAB_STOP = WS_TABSTOP;
+    }
+}
+
+/**
+A split button is a push button with a drop down arrow on its right side. The main area of the button raises `OnButtonClick`.
+The arrow raises `OnSplitDropdown` and opens the menu attached to the button, if there is one (see `SplitButton::set_menu`).
+
+The attached menu must be a popup menu. Its items raise `OnMenuItemSelected` like the items of any other popup menu.
+
+Requires Windows Vista or later. SplitButton is not behind any features. Attaching a menu requires the `menu` feature.
+
+**Builder parameters:**
+  * `parent`:   **Required.** The button parent container.
+  * `text`:     The button text.
+  * `size`:     The button size.
+  * `position`: The button position.
+  * `enabled`:  If the button can be used by the user. It also has a grayed out look if disabled.
+  * `flags`:    A combination of the SplitButtonFlags values.
+  * `ex_flags`: A combination of win32 window extended flags. Unlike `flags`, ex_flags must be used straight from winapi
+  * `font`:     The font used for the button text
+  * `menu`:     A popup menu opened by the drop down arrow. Requires the `menu` feature.
+  * `focus`:    The control receive focus after being created
+
+**Control events:**
+  * `OnButtonClick`: When the main area of the button is clicked by the user
+  * `OnButtonDoubleClick`: When the main area of the button is clicked twice rapidly by the user
+  * `OnSplitDropdown`: When the drop down arrow of the button is clicked by the user
+  * `MousePress(_)`: Generic mouse press events on the button
+  * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
+  * `OnMouseWheel`: Generic mouse wheel event
+
+```rust
+use native_windows_gui as nwg;
+fn build_split_button(button: &mut nwg::SplitButton, window: &nwg::Window, menu: &nwg::Menu) {
+    nwg::SplitButton::builder()
+        .text("Save")
+        .menu(Some(menu))
+        .parent(window)
+        .build(button);
+}
+```
+
+*/
+#[derive(Default)]
+pub struct SplitButton {
+    pub handle: ControlHandle,
+
+    #[cfg(feature = "menu")]
+    menu_handler: RefCell<Option<RawEventHandler>>,
+}
+
+impl SplitButton {
+
+    pub fn builder<'a>() -> SplitButtonBuilder<'a> {
+        SplitButtonBuilder {
+            text: "Button",
+            size: (120, 25),
+            position: (0, 0),
+            enabled: true,
+            flags: None,
+            ex_flags: 0,
+            font: None,
+            #[cfg(feature = "menu")]
+            menu: None,
+            parent: None,
+            focus: false,
+            name: None,
+            state: Default::default(),
+        }
+    }
+
+    /**
+        Attach a popup menu to the drop down arrow of the button. The menu opens under the button when the arrow is clicked.
+        Set `menu` to `None` to remove the menu. `OnSplitDropdown` is raised with or without a menu.
+
+        The menu must outlive the button, or be removed from the button before it is dropped.
+        This function will panic if `menu` is not a popup menu.
+    */
+    #[cfg(feature = "menu")]
+    pub fn set_menu(&self, menu: Option<&Menu>) -> Result<(), NwgError> {
+        use crate::win32::window::bind_raw_event_handler_inner;
+        use winapi::um::winuser::{WM_NOTIFY, NMHDR, TrackPopupMenu, SetForegroundWindow, ClientToScreen, TPM_LEFTALIGN, TPM_TOPALIGN};
+        use winapi::um::commctrl::{BCN_DROPDOWN, NMBCDROPDOWN};
+        use winapi::shared::windef::{HWND, POINT};
+        use winapi::shared::minwindef::{WPARAM, LPARAM};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::ptr;
+
+        static SPLIT_BUTTON_ID: AtomicUsize = AtomicUsize::new(0xCFFF);
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        if let Some(handler) = self.menu_handler.borrow_mut().take() {
+            drop(unbind_raw_event_handler(&handler));
+        }
+
+        let (owner, hmenu) = match menu {
+            Some(menu) => menu.handle.pop_hmenu().expect("SplitButton menu must be a popup menu"),
+            None => { return Ok(()); }
+        };
+
+        let owner = owner as usize;
+        let hmenu = hmenu as usize;
+        let button = handle as usize;
+        let parent = ControlHandle::Hwnd(wh::get_window_parent(handle));
+        let cb = move |_hwnd, msg, _w: WPARAM, l: LPARAM| {
+            if msg != WM_NOTIFY {
+                return None;
+            }
+
+            let notif = unsafe { &*(l as *const NMHDR) };
+            if notif.hwndFrom as usize != button || notif.code != BCN_DROPDOWN {
+                return None;
+            }
+
+            unsafe {
+                let dropdown = &*(l as *const NMBCDROPDOWN);
+                let mut pt = POINT { x: dropdown.rcButton.left, y: dropdown.rcButton.bottom };
+                ClientToScreen(button as HWND, &mut pt);
+
+                SetForegroundWindow(owner as HWND);
+                TrackPopupMenu(hmenu as _, TPM_LEFTALIGN | TPM_TOPALIGN, pt.x, pt.y, 0, owner as HWND, ptr::null());
+            }
+
+            None
+        };
+
+        let handler = bind_raw_event_handler_inner(&parent, SPLIT_BUTTON_ID.fetch_add(1, Ordering::SeqCst), cb)?;
+        *self.menu_handler.borrow_mut() = Some(handler);
+
+        Ok(())
+    }
+
+    /**
+        Make the button the default button of its top level window: the button raises `OnButtonClick` when the user presses Enter.
+        See `Button::set_default`.
+    */
+    pub fn set_default(&self, default: bool) {
+        use crate::win32::dialog_buttons::{DialogButton, set_dialog_button};
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        set_dialog_button(handle, DialogButton::Default, default);
+    }
+
+    /// Returns true if the button is the default button of its top level window
+    pub fn is_default(&self) -> bool {
+        use crate::win32::dialog_buttons::{DialogButton, is_dialog_button};
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        is_dialog_button(handle, DialogButton::Default)
+    }
+
+    /// Simulate a user click on the main area of the button
+    pub fn click(&self) {
+        use winapi::um::winuser::BM_CLICK;
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        wh::send_message(handle, BM_CLICK, 0, 0);
+    }
+
+    /// Returns the font of the control
+    pub fn font(&self) -> Option<Font> {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let font_handle = wh::get_window_font(handle);
+        if font_handle.is_null() {
+            None
+        } else {
+            Some(Font { handle: font_handle })
+        }
+    }
+
+    /// Sets the font of the control
+    pub fn set_font(&self, font: Option<&Font>) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_font(handle, font.map(|f| f.handle), true); }
+    }
+
+    /// Returns true if the control currently has the keyboard focus
+    pub fn focus(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_focus(handle) }
+    }
+
+    /// Sets the keyboard focus on the button.
+    pub fn set_focus(&self) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_focus(handle); }
+    }
+
+    /// Returns true if the control user can interact with the control, return false otherwise
+    pub fn enabled(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_enabled(handle) }
+    }
+
+    /// Enable or disable the control
+    pub fn set_enabled(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_enabled(handle, v) }
+    }
+
+    /// Returns true if the control is visible to the user. Will return true even if the
+    /// control is outside of the parent client view (ex: at the position (10000, 10000))
+    pub fn visible(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_visibility(handle) }
+    }
+
+    /// Show or hide the control to the user
+    pub fn set_visible(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_visibility(handle, v) }
+    }
+
+    /// Returns the size of the button in the parent window
+    pub fn size(&self) -> (u32, u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_size(handle) }
+    }
+
+    /// Sets the size of the button in the parent window
+    pub fn set_size(&self, x: u32, y: u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_size(handle, x, y, false) }
+    }
+
+    /// Returns the position of the button in the parent window
+    pub fn position(&self) -> (i32, i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_position(handle) }
+    }
+
+    /// Sets the position of the button in the parent window
+    pub fn set_position(&self, x: i32, y: i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_position(handle, x, y) }
+    }
+
+    /// Returns the button label
+    pub fn text(&self) -> String {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_text(handle) }
+    }
+
+    /// Sets the button label
+    pub fn set_text<'a>(&self, v: &'a str) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_text(handle, v) }
+    }
+
+    /// Winapi class name used during control creation
+    pub fn class_name(&self) -> &'static str {
+        "BUTTON"
+    }
+
+    /// Winapi base flags used during window creation
+    pub fn flags(&self) -> u32 {
+        WS_VISIBLE | WS_TABSTOP
+    }
+
+    /// Winapi flags required by the control
+    pub fn forced_flags(&self) -> u32 {
+        WS_CHILD | BS_SPLITBUTTON
+    }
+
+}
+
+impl Drop for SplitButton {
+    fn drop(&mut self) {
+        #[cfg(feature = "menu")]
+        {
+            if let Some(handler) = self.menu_handler.borrow_mut().take() {
+                drop(unbind_raw_event_handler(&handler));
+            }
+        }
+
+        self.handle.destroy();
+    }
+}
+
+pub struct SplitButtonBuilder<'a> {
+    text: &'a str,
+    size: (i32, i32),
+    position: (i32, i32),
+    enabled: bool,
+    flags: Option<SplitButtonFlags>,
+    ex_flags: u32,
+    font: Option<&'a Font>,
+    #[cfg(feature = "menu")]
+    menu: Option<&'a Menu>,
+    parent: Option<ControlHandle>,
+    focus: bool,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
+}
+
+impl<'a> SplitButtonBuilder<'a> {
+
+    pub fn flags(mut self, flags: SplitButtonFlags) -> SplitButtonBuilder<'a> {
+        self.flags = Some(flags);
+        self
+    }
+
+    pub fn ex_flags(mut self, flags: u32) -> SplitButtonBuilder<'a> {
+        self.ex_flags = flags;
+        self
+    }
+
+    pub fn text(mut self, text: &'a str) -> SplitButtonBuilder<'a> {
+        self.text = text;
+        self
+    }
+
+    pub fn size(mut self, size: (i32, i32)) -> SplitButtonBuilder<'a> {
+        self.size = size;
+        self
+    }
+
+    pub fn position(mut self, pos: (i32, i32)) -> SplitButtonBuilder<'a> {
+        self.position = pos;
+        self
+    }
+
+    pub fn enabled(mut self, e: bool) -> SplitButtonBuilder<'a> {
+        self.enabled = e;
+        self
+    }
+
+    pub fn font(mut self, font: Option<&'a Font>) -> SplitButtonBuilder<'a> {
+        self.font = font;
+        self
+    }
+
+    #[cfg(feature = "menu")]
+    pub fn menu(mut self, menu: Option<&'a Menu>) -> SplitButtonBuilder<'a> {
+        self.menu = menu;
+        self
+    }
+
+    pub fn focus(mut self, focus: bool) -> SplitButtonBuilder<'a> {
+        self.focus = focus;
+        self
+    }
+
+    pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> SplitButtonBuilder<'a> {
+        self.parent = Some(p.into());
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> SplitButtonBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn build(self, out: &mut SplitButton) -> Result<(), NwgError> {
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
+
+        let parent = match self.parent {
+            Some(p) => Ok(p),
+            None => Err(NwgError::no_parent("SplitButton"))
+        }?;
+
+        // Drop the old object
+        *out = SplitButton::default();
+
+        out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
+            .class_name(out.class_name())
+            .forced_flags(out.forced_flags())
+            .flags(flags)
+            .ex_flags(self.ex_flags)
+            .size(self.size)
+            .position(self.position)
+            .text(self.text)
+            .parent(Some(parent))
+            .build()?;
+
+        if self.font.is_some() {
+            out.set_font(self.font);
+        } else {
+            out.set_font(Font::global_default().as_ref());
+        }
+
+        out.set_enabled(self.enabled);
+
+        #[cfg(feature = "menu")]
+        {
+            if self.menu.is_some() {
+                out.set_menu(self.menu)?;
+            }
+        }
+
+        if self.focus {
+            out.set_focus();
+        }
+
+        Ok(())
+    }
+
+}
+
+impl PartialEq for SplitButton {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for SplitButton {}
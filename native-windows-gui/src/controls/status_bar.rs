@@ -1,27 +1,67 @@
 use winapi::shared::minwindef::{WPARAM, LPARAM};
+use winapi::shared::windef::HWND;
+use winapi::um::commctrl::SBARS_SIZEGRIP;
 use crate::win32::window_helper as wh;
 use crate::win32::base_helper::check_hwnd;
+use crate::win32::high_dpi;
 use crate::{Font, NwgError, RawEventHandler, unbind_raw_event_handler};
 use super::{ControlHandle, ControlBase};
 use std::cell::RefCell;
+use std::rc::Rc;
 
 const NOT_BOUND: &'static str = "StatusBar is not yet bound to a winapi object";
 const BAD_HANDLE: &'static str = "INTERNAL ERROR: StatusBar handle is not HWND!";
 
+
+/**
+    The width of a part of a `StatusBar`. See `StatusBar::set_parts`.
+
+    The parts first get their minimum width. The remaining width of the status bar is then shared between the parts in proportion of their weight.
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StatusBarPart {
+    /// The minimum width of the part, in logical pixels
+    pub min_width: u32,
+
+    /// The share of the remaining width given to the part. A part with a weight of 0 keeps its minimum width.
+    pub weight: u32,
+}
+
+impl StatusBarPart {
+
+    /// A part that keeps the same width when the status bar is resized
+    pub fn fixed(width: u32) -> StatusBarPart {
+        StatusBarPart { min_width: width, weight: 0 }
+    }
+
+    /// A part that grows with the status bar, but is never smaller than `min_width`
+    pub fn proportional(weight: u32, min_width: u32) -> StatusBarPart {
+        StatusBarPart { min_width, weight }
+    }
+
+}
+
 /**
 A status bar is a horizontal window at the bottom of a parent window in which an application can display various kinds of status information.
 Status bar cannot stack, so there must be only one per window.
 
 Requires the `status-bar` feature. 
 
+The layouts of the parent window place their children above the status bar.
+
 **Builder parameters:**
-  * `parent`:   **Required.** The status bar parent container.
-  * `text`:     The status bar text.
-  * `font`:     The font used for the status bar text
+  * `parent`:    **Required.** The status bar parent container.
+  * `text`:      The status bar text.
+  * `font`:      The font used for the status bar text
+  * `parts`:     The widths of the parts of the status bar. See `StatusBar::set_parts`.
+  * `size_grip`: If the status bar shows a sizing grip at its right end (defaults to false)
 
 **Control events:**
   * `MousePress(_)`: Generic mouse press events on the status bar
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
 
 ```rust
@@ -39,6 +79,7 @@ fn build_status(status: &mut nwg::StatusBar, window: &nwg::Window, font: &nwg::F
 #[derive(Default)]
 pub struct StatusBar {
     pub handle: ControlHandle,
+    parts: Rc<RefCell<Vec<StatusBarPart>>>,
     handler0: RefCell<Option<RawEventHandler>>,
 }
 
@@ -49,10 +90,74 @@ impl StatusBar {
         StatusBarBuilder {
             text: "",
             font: None,
-            parent: None
+            parts: None,
+            size_grip: false,
+            parent: None,
+            name: None,
         }
     }
 
+    /**
+        Split the status bar in parts. The width of the parts is updated when the parent window is resized.
+        The text of the parts is set with `set_text`, using the index of the part. A status bar has at most 256 parts.
+        An empty slice leaves the status bar with a single part.
+
+        ```rust
+        use native_windows_gui as nwg;
+
+        fn split(status: &nwg::StatusBar) {
+            status.set_parts(&[
+                nwg::StatusBarPart::proportional(1, 100),
+                nwg::StatusBarPart::fixed(120),
+                nwg::StatusBarPart::fixed(80),
+            ]);
+        }
+        ```
+    */
+    pub fn set_parts(&self, parts: &[StatusBarPart]) {
+        use winapi::um::commctrl::SB_SETPARTS;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let parts = &parts[..parts.len().min(256)];
+        *self.parts.borrow_mut() = parts.to_vec();
+
+        match parts.is_empty() {
+            true => {
+                let right = [-1i32];
+                wh::send_message(handle, SB_SETPARTS, 1, right.as_ptr() as LPARAM);
+            },
+            false => update_parts(handle, parts)
+        }
+    }
+
+    /// Returns the widths of the parts set with `set_parts`
+    pub fn parts(&self) -> Vec<StatusBarPart> {
+        self.parts.borrow().clone()
+    }
+
+    /// Returns true if the status bar shows a sizing grip at its right end
+    pub fn size_grip(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        wh::get_style(handle) & SBARS_SIZEGRIP == SBARS_SIZEGRIP
+    }
+
+    /// Show or hide the sizing grip at the right end of the status bar
+    pub fn set_size_grip(&self, grip: bool) {
+        use winapi::um::winuser::{InvalidateRect, WM_SIZE};
+        use std::ptr;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let style = wh::get_style(handle);
+        let style = match grip {
+            true => style | SBARS_SIZEGRIP,
+            false => style & !SBARS_SIZEGRIP
+        };
+
+        wh::set_style(handle, style);
+        wh::send_message(handle, WM_SIZE, 0, 0);
+        unsafe { InvalidateRect(handle, ptr::null(), 1); }
+    }
+
     /// Set the minimum height of the statusbar (in pixels)
     pub fn set_min_height(&self, height: u32) {
         use winapi::um::commctrl::SB_SETMINHEIGHT;
@@ -126,7 +231,7 @@ impl StatusBar {
     }
 
     /// Status bar do not resize automatically. Instead, a resize message must be
-    /// manually sent by the parent window to trigger the resize action.
+    /// manually sent by the parent window to trigger the resize action. The parts of the status bar are also resized.
     pub fn hook_parent_resize(&self) {
         use winapi::um::winuser::WM_SIZE;
         use crate::bind_raw_event_handler_inner;
@@ -135,9 +240,16 @@ impl StatusBar {
         let handle = self.handle.hwnd().expect(BAD_HANDLE);
 
         let parent_handle = ControlHandle::Hwnd(wh::get_window_parent(handle));
+        let parts = self.parts.clone();
         let handler = bind_raw_event_handler_inner(&parent_handle, handle as usize, move |_hwnd, msg, _w, _l| {
             if msg == WM_SIZE {
                 wh::send_message(handle, WM_SIZE, 0, 0);
+
+                if let Ok(parts) = parts.try_borrow() {
+                    if !parts.is_empty() {
+                        update_parts(handle, &parts);
+                    }
+                }
             }
 
             None
@@ -161,7 +273,10 @@ impl Drop for StatusBar {
 pub struct StatusBarBuilder<'a> {
     text: &'a str,
     font: Option<&'a Font>,
-    parent: Option<ControlHandle>
+    parts: Option<&'a [StatusBarPart]>,
+    size_grip: bool,
+    parent: Option<ControlHandle>,
+    name: Option<String>,
 }
 
 impl<'a> StatusBarBuilder<'a> {
@@ -176,11 +291,26 @@ impl<'a> StatusBarBuilder<'a> {
         self
     }
 
+    pub fn parts(mut self, parts: &'a [StatusBarPart]) -> StatusBarBuilder<'a> {
+        self.parts = Some(parts);
+        self
+    }
+
+    pub fn size_grip(mut self, grip: bool) -> StatusBarBuilder<'a> {
+        self.size_grip = grip;
+        self
+    }
+
     pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> StatusBarBuilder<'a> {
         self.parent = Some(p.into());
         self
     }
 
+    pub fn name(mut self, name: &str) -> StatusBarBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut StatusBar) -> Result<(), NwgError> {
         let parent = match self.parent {
             Some(p) => Ok(p),
@@ -189,10 +319,16 @@ impl<'a> StatusBarBuilder<'a> {
 
         *out = Default::default();
 
+        let mut flags = out.flags();
+        if self.size_grip {
+            flags |= SBARS_SIZEGRIP;
+        }
+
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
-            .flags(out.flags())
+            .flags(flags)
             .parent(Some(parent))
             .build()?;
 
@@ -202,6 +338,10 @@ impl<'a> StatusBarBuilder<'a> {
             out.set_font(Font::global_default().as_ref());
         }
 
+        if let Some(parts) = self.parts {
+            out.set_parts(parts);
+        }
+
         out.set_text(0, self.text);
         out.hook_parent_resize();
 
@@ -215,3 +355,32 @@ impl PartialEq for StatusBar {
         self.handle == other.handle
     }
 }
+
+/// Set the right edges of the parts of the status bar from the width of the status bar. A last part with a weight extends to the right end.
+fn update_parts(handle: HWND, parts: &[StatusBarPart]) {
+    use winapi::um::commctrl::SB_SETPARTS;
+
+    let (width, _) = unsafe { wh::get_window_size(handle) };
+    let min_width: u32 = parts.iter().map(|p| p.min_width).sum();
+    let weights: u64 = parts.iter().map(|p| p.weight as u64).sum();
+    let extra = width.saturating_sub(min_width) as u64;
+
+    let mut right = 0u64;
+    let mut edges: Vec<i32> = parts.iter()
+        .map(|part| {
+            right += part.min_width as u64;
+            if weights > 0 {
+                right += extra * part.weight as u64 / weights;
+            }
+
+            let (x, _) = unsafe { high_dpi::logical_to_physical_for(handle, right.min(i32::max_value() as u64) as i32, 0) };
+            x
+        })
+        .collect();
+
+    if parts.last().map(|p| p.weight > 0).unwrap_or(false) {
+        *edges.last_mut().unwrap() = -1;
+    }
+
+    wh::send_message(handle, SB_SETPARTS, edges.len() as WPARAM, edges.as_ptr() as LPARAM);
+}
@@ -3,8 +3,10 @@ use winapi::shared::windef::HWND;
 use winapi::um::winnt::LPWSTR;
 use winapi::um::winuser::{EnumChildWindows, WS_VISIBLE, WS_DISABLED, WS_EX_CONTROLPARENT};
 use crate::win32::{base_helper::{to_utf16, check_hwnd}, window_helper as wh};
+use crate::win32::window::nwg_class;
 use crate::{NwgError, Font, RawEventHandler, unbind_raw_event_handler};
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
 use std::{mem, cell::RefCell};
 
 #[cfg(feature="image-list")]
@@ -46,6 +48,9 @@ Requires the `tabs` feature
   * `TabsContainerChanging`: The selected tab of a TabsContainer is about to be changed
   * `MousePress(_)`: Generic mouse press events on the button
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
 
 */
@@ -68,7 +73,9 @@ impl TabsContainer {
             ex_flags: 0,
 
             #[cfg(feature = "image-list")]
-            image_list: None
+            image_list: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -289,7 +296,7 @@ impl TabsContainer {
                     let size = l as u32;
                     let width = LOWORD(size) as i32;
                     let height = HIWORD(size) as i32;
-                    let (w, h) = crate::win32::high_dpi::physical_to_logical(width, height);
+                    let (w, h) = crate::win32::high_dpi::physical_to_logical_for(hwnd, width, height);
 
                     let mut data = ResizeDirectChildrenParams {
                         parent: hwnd,
@@ -363,7 +370,9 @@ pub struct TabsContainerBuilder<'a> {
     ex_flags: u32,
 
     #[cfg(feature = "image-list")]
-    image_list: Option<&'a ImageList>
+    image_list: Option<&'a ImageList>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a> TabsContainerBuilder<'a> {
@@ -404,8 +413,13 @@ impl<'a> TabsContainerBuilder<'a> {
         self
     }
 
+    pub fn name(mut self, name: &str) -> TabsContainerBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut TabsContainer) -> Result<(), NwgError> {
-        let flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
 
         let parent = match self.parent {
             Some(p) => Ok(p),
@@ -415,6 +429,7 @@ impl<'a> TabsContainerBuilder<'a> {
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -474,6 +489,7 @@ impl Tab {
 
             #[cfg(feature = "image-list")]
             image_index: None,
+            name: None,
         }
     }
 
@@ -567,7 +583,7 @@ impl Tab {
 
     /// Winapi class name used during control creation
     pub fn class_name(&self) -> &'static str {
-        "NWG_TAB"
+        nwg_class("NWG_TAB")
     }
 
     /// Winapi base flags used during window creation
@@ -661,6 +677,7 @@ pub struct TabBuilder<'a> {
 
     #[cfg(feature = "image-list")]
     image_index: Option<i32>,
+    name: Option<String>,
 }
 
 impl<'a> TabBuilder<'a> {
@@ -681,6 +698,11 @@ impl<'a> TabBuilder<'a> {
         self
     }
 
+    pub fn name(mut self, name: &str) -> TabBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut Tab) -> Result<(), NwgError> {
         use winapi::um::commctrl::WC_TABCONTROL;
 
@@ -704,6 +726,7 @@ impl<'a> TabBuilder<'a> {
         }?;
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .ex_flags(WS_EX_CONTROLPARENT)
@@ -755,7 +778,7 @@ unsafe extern "system" fn resize_direct_children(handle: HWND, params: LPARAM) -
 unsafe extern "system" fn count_children(handle: HWND, params: LPARAM) -> BOOL {
     use winapi::um::winuser::GWL_USERDATA;
 
-    if &wh::get_window_class_name(handle) == "NWG_TAB" {
+    if &wh::get_window_class_name(handle) == nwg_class("NWG_TAB") {
         let tab_index = (wh::get_window_long(handle, GWL_USERDATA)) as WPARAM;
         let count: &mut usize = ::std::mem::transmute(params);
         *count = usize::max(tab_index+1, *count);
@@ -1,8 +1,10 @@
 use winapi::shared::minwindef::{WPARAM, LPARAM};
 use winapi::um::winuser::{WS_VSCROLL, WS_HSCROLL, ES_AUTOVSCROLL, ES_AUTOHSCROLL, WS_VISIBLE, WS_DISABLED, WS_TABSTOP};
 use crate::win32::window_helper as wh;
-use crate::{Font, NwgError};
+use crate::win32::scrolling::bind_smooth_scrolling;
+use crate::{Font, NwgError, RawEventHandler, unbind_raw_event_handler};
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
 use std::ops::Range;
 use newline_converter::{dos2unix, unix2dos};
 
@@ -54,11 +56,15 @@ Note: Use `\r\n` to input a new line not just `\n`.
   * `limit`:    The maximum number of character that can be inserted in the control
   * `readonly`: If the textbox should allow user input or not
   * `focus`:    The control receive focus after being created
+  * `smooth_scrolling`: If the mouse wheel scrolls the text box smoothly (defaults to false). See `nwg::set_wheel_routing`.
 
 **Control events:**
   * `OnTextInput`: When a TextBox value is changed
   * `MousePress(_)`: Generic mouse press events on the button
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
   * `OnKeyPress`:    Generic key press event
   * `OnKeyRelease`:  Generic key release event
@@ -74,9 +80,10 @@ fn build_box(tbox: &mut nwg::TextBox, window: &nwg::Window, font: &nwg::Font) {
 }
 ```
 */
-#[derive(Default, PartialEq, Eq)]
+#[derive(Default)]
 pub struct TextBox {
-    pub handle: ControlHandle
+    pub handle: ControlHandle,
+    handler0: Option<RawEventHandler>,
 }
 
 impl TextBox {
@@ -90,9 +97,12 @@ impl TextBox {
             ex_flags: 0,
             limit: 0,
             readonly: false,
+            smooth_scrolling: false,
             focus: false,
             font: None,
-            parent: None
+            parent: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -374,6 +384,10 @@ impl TextBox {
 
 impl Drop for TextBox {
     fn drop(&mut self) {
+        if let Some(h) = self.handler0.as_ref() {
+            drop(unbind_raw_event_handler(h));
+        }
+
         self.handle.destroy();
     }
 }
@@ -386,8 +400,11 @@ pub struct TextBoxBuilder<'a> {
     limit: usize,
     readonly: bool,
     focus: bool,
+    smooth_scrolling: bool,
     font: Option<&'a Font>,
-    parent: Option<ControlHandle>
+    parent: Option<ControlHandle>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a> TextBoxBuilder<'a> {
@@ -432,6 +449,11 @@ impl<'a> TextBoxBuilder<'a> {
         self
     }
 
+    pub fn smooth_scrolling(mut self, smooth: bool) -> TextBoxBuilder<'a> {
+        self.smooth_scrolling = smooth;
+        self
+    }
+
     pub fn font(mut self, font: Option<&'a Font>) -> TextBoxBuilder<'a> {
         self.font = font;
         self
@@ -442,8 +464,13 @@ impl<'a> TextBoxBuilder<'a> {
         self
     }
 
+    pub fn name(mut self, name: &str) -> TextBoxBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut TextBox) -> Result<(), NwgError> {
-        let flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
 
         let parent = match self.parent {
             Some(p) => Ok(p),
@@ -453,6 +480,7 @@ impl<'a> TextBoxBuilder<'a> {
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -481,7 +509,19 @@ impl<'a> TextBoxBuilder<'a> {
             out.set_font(Font::global_default().as_ref());
         }
 
+        if self.smooth_scrolling {
+            out.handler0 = Some(bind_smooth_scrolling(&out.handle, 0x020));
+        }
+
         Ok(())
     }
 
 }
+
+impl PartialEq for TextBox {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for TextBox {}
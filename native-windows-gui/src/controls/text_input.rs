@@ -1,5 +1,5 @@
 use winapi::shared::{
-    windef::HBRUSH,
+    windef::{HBRUSH, HWND},
     minwindef::{UINT, WPARAM, LPARAM}
 };
 use winapi::um::{
@@ -10,10 +10,18 @@ use crate::win32::window_helper as wh;
 use crate::win32::base_helper::{check_hwnd, to_utf16};
 use crate::{Font, NwgError, HTextAlign, RawEventHandler};
 use super::{ControlBase, ControlHandle};
-use std::cell::RefCell;
+use super::control_builder::BuilderState;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 use std::ops::Range;
 use std::char;
 
+#[cfg(feature = "tooltip")]
+use super::TooltipIcon;
+
+#[cfg(feature = "winnls")]
+use crate::{Locale, locale_format_number, locale_parse_number};
+
 const NOT_BOUND: &'static str = "TextInput is not yet bound to a winapi object";
 const BAD_HANDLE: &'static str = "INTERNAL ERROR: TextInput handle is not HWND!";
 
@@ -56,14 +64,20 @@ TextInput is not behind any features.
   * `limit`:            The maximum number of character that can be inserted in the control
   * `readonly`:         If the text input should allow user input or not
   * `password`:         The password character. If set to None, the textinput is a regular control.
+  * `password_reveal`:  If true and `password` is set, a button that shows/hides the password is added on the right side of the control.
   * `align`:            The alignment of the text in the text input
   * `background_color`: The color of the textinput top and bottom padding. This is not the white background under the text.
   * `focus`:            The control receive focus after being created
+  * `placeholder_text`: Text displayed in the text input when it is empty
+  * `placeholder_focused`: If the placeholder text is still displayed when the text input has focus. Default `false`
 
 **Control events:**
   * `OnTextInput`: When a TextInput value is changed
   * `MousePress(_)`: Generic mouse press events on the button
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
 
 ```rust
@@ -82,6 +96,9 @@ pub struct TextInput {
     pub handle: ControlHandle,
     background_brush: Option<HBRUSH>,
     handler0: RefCell<Option<RawEventHandler>>,
+    handler1: RefCell<Option<RawEventHandler>>,
+    handler2: RefCell<Option<RawEventHandler>>,
+    hidden_password_char: Rc<Cell<Option<char>>>,
 }
 
 impl TextInput {
@@ -90,18 +107,22 @@ impl TextInput {
         TextInputBuilder {
             text: "",
             placeholder_text: None,
+            placeholder_focused: false,
             size: (100, 25),
             position: (0, 0),
             flags: None,
             ex_flags: 0,
             limit: 0,
             password: None,
+            password_reveal: false,
             align: HTextAlign::Left,
             readonly: false,
             focus: false,
             font: None,
             parent: None,
             background_color: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -141,12 +162,26 @@ impl TextInput {
         use winapi::um::winuser::{InvalidateRect, EM_SETPASSWORDCHAR};
 
         let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.hidden_password_char.set(None);
         wh::send_message(handle, EM_SETPASSWORDCHAR as u32, c.map(|c| c as usize).unwrap_or(0), 0);
 
         // The control needs to be manually refreshed
         unsafe { InvalidateRect(handle, ::std::ptr::null(), 1); }
     }
 
+    /// Return true if the password of the text input is currently displayed in clear text
+    pub fn password_revealed(&self) -> bool {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.hidden_password_char.get().is_some()
+    }
+
+    /// Show or hide the password of the text input. Does nothing if the text input is not a password.
+    /// The text of a password text input cannot be copied to the clipboard, even if the password is revealed.
+    pub fn set_password_revealed(&self, revealed: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        toggle_password(handle, &self.hidden_password_char, revealed);
+    }
+
     /// Return the number of maximum character allowed in this text input
     pub fn limit(&self) -> u32 {
         use winapi::um::winuser::EM_GETLIMITTEXT;
@@ -307,6 +342,23 @@ impl TextInput {
         unsafe { wh::set_window_text(handle, v) }
     }
 
+    /// Parse the text displayed in the TextInput as a number formatted with the rules of `locale`.
+    /// If `locale` is `None`, the user locale is used. Requires the `winnls` feature.
+    #[cfg(feature = "winnls")]
+    pub fn number(&self, locale: Option<&Locale>) -> Result<f64, NwgError> {
+        locale_parse_number(&self.text(), locale)
+    }
+
+    /// Display a number in the TextInput formatted with the rules of `locale`.
+    /// If `locale` is `None`, the user locale is used. If `decimals` is `None`, the locale fractional digits count is used.
+    /// Requires the `winnls` feature.
+    #[cfg(feature = "winnls")]
+    pub fn set_number(&self, value: f64, decimals: Option<u32>, locale: Option<&Locale>) -> Result<(), NwgError> {
+        let text = locale_format_number(value, decimals, locale)?;
+        self.set_text(&text);
+        Ok(())
+    }
+
     /// Return the placeholder text displayed in the TextInput
     /// when it is empty and does not have focus. The string returned will be
     /// as long as the user specified, however it might be longer or shorter than
@@ -329,12 +381,54 @@ impl TextInput {
     /// Set the placeholder text displayed in the TextInput
     /// when it is empty and does not have focus
     pub fn set_placeholder_text<'a>(&self, v: Option<&'a str>) {
+        self.set_placeholder_text_focused(v, false);
+    }
+
+    /// Set the placeholder text displayed in the TextInput when it is empty.
+    /// If `focused` is true, the placeholder text is still displayed when the TextInput has focus.
+    pub fn set_placeholder_text_focused<'a>(&self, v: Option<&'a str>, focused: bool) {
         use winapi::um::commctrl::EM_SETCUEBANNER;
     
         let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
         let placeholder_text = v.unwrap_or("");
         let text = to_utf16(placeholder_text);
-        wh::send_message(handle, EM_SETCUEBANNER, 0, text.as_ptr() as LPARAM);
+        wh::send_message(handle, EM_SETCUEBANNER, focused as WPARAM, text.as_ptr() as LPARAM);
+    }
+
+    /**
+        Show a balloon tip next to the TextInput. Useful to show validation errors.
+        The balloon tip is dismissed when the user types in the control, when the control loses focus, or after a timeout.
+
+        Requires the `tooltip` feature.
+    */
+    #[cfg(feature = "tooltip")]
+    pub fn show_balloon_tip<'a>(&self, title: &'a str, text: &'a str, icon: TooltipIcon) {
+        use winapi::um::commctrl::{EM_SHOWBALLOONTIP, EDITBALLOONTIP};
+        use winapi::shared::minwindef::DWORD;
+        use super::tooltip::default_icon_id;
+        use std::mem;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let title = to_utf16(title);
+        let text = to_utf16(text);
+        let tip = EDITBALLOONTIP {
+            cbStruct: mem::size_of::<EDITBALLOONTIP>() as DWORD,
+            pszTitle: title.as_ptr(),
+            pszText: text.as_ptr(),
+            ttiIcon: default_icon_id(icon) as i32,
+        };
+
+        self.hook_balloon_tip_dismiss();
+        wh::send_message(handle, EM_SHOWBALLOONTIP, 0, &tip as *const EDITBALLOONTIP as LPARAM);
+    }
+
+    /// Hide the balloon tip displayed by `show_balloon_tip`, if any
+    pub fn hide_balloon_tip(&self) {
+        use winapi::um::commctrl::EM_HIDEBALLOONTIP;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        wh::send_message(handle, EM_HIDEBALLOONTIP, 0, 0);
     }
 
     /// Winapi class name used during control creation
@@ -458,6 +552,128 @@ impl TextInput {
         }
     }
 
+    /**
+        Block copying the password to the clipboard and, if `reveal_button` is true,
+        add a button on the right side of the control that shows/hides the password.
+        The hook is installed on every text input, so a password char set with `set_password_char` is also protected.
+    */
+    fn hook_password(&self, reveal_button: bool) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::shared::windef::RECT;
+        use winapi::shared::minwindef::{HIWORD, MAKELONG};
+        use winapi::um::winuser::{WM_COPY, WM_CUT, WM_COMMAND, WM_SIZE, BN_CLICKED, EM_SETMARGINS, EM_GETPASSWORDCHAR, EC_RIGHTMARGIN, WS_CHILD, WS_VISIBLE, WS_CLIPCHILDREN, BS_PUSHBUTTON};
+        use winapi::um::winuser::{CreateWindowExW, GetClientRect, SetWindowPos, SWP_NOZORDER, SWP_NOACTIVATE};
+        use winapi::um::libloaderapi::GetModuleHandleW;
+        use std::{mem, ptr};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let button = match reveal_button {
+            false => ptr::null_mut(),
+            true => unsafe {
+                let mut r: RECT = mem::zeroed();
+                GetClientRect(handle, &mut r);
+                let size = r.bottom - r.top;
+
+                let class_name = to_utf16("BUTTON");
+                let text = to_utf16(REVEAL_TEXT);
+                let button = CreateWindowExW(
+                    0, class_name.as_ptr(), text.as_ptr(),
+                    WS_CHILD | WS_VISIBLE | BS_PUSHBUTTON,
+                    r.right - size, 0, size, size,
+                    handle, ptr::null_mut(), GetModuleHandleW(ptr::null_mut()), ptr::null_mut()
+                );
+
+                if !button.is_null() {
+                    wh::set_window_font(button, Some(wh::get_window_font(handle)), false);
+                    wh::set_style(handle, wh::get_style(handle) | WS_CLIPCHILDREN);
+                    wh::send_message(handle, EM_SETMARGINS as u32, EC_RIGHTMARGIN as WPARAM, MAKELONG(0, size as u16) as LPARAM);
+                }
+
+                button
+            }
+        };
+
+        let hidden = self.hidden_password_char.clone();
+        let handler = bind_raw_event_handler_inner(&self.handle, 2, move |hwnd, msg, w, l| {
+            match msg {
+                // A revealed password keeps its password char in `hidden`
+                WM_COPY | WM_CUT if wh::send_message(hwnd, EM_GETPASSWORDCHAR as u32, 0, 0) != 0 || hidden.get().is_some() => Some(0),
+                WM_COMMAND if !button.is_null() && l as HWND == button && HIWORD(w as u32) == BN_CLICKED => {
+                    let revealed = hidden.get().is_none();
+                    toggle_password(hwnd, &hidden, revealed);
+                    Some(0)
+                },
+                WM_SIZE if !button.is_null() => unsafe {
+                    let mut r: RECT = mem::zeroed();
+                    GetClientRect(hwnd, &mut r);
+                    let size = r.bottom - r.top;
+                    SetWindowPos(button, ptr::null_mut(), r.right - size, 0, size, size, SWP_NOZORDER | SWP_NOACTIVATE);
+                    wh::send_message(hwnd, EM_SETMARGINS as u32, EC_RIGHTMARGIN as WPARAM, MAKELONG(0, size as u16) as LPARAM);
+                    None
+                },
+                _ => None
+            }
+        });
+
+        *self.handler2.borrow_mut() = handler.ok();
+    }
+
+    /// Hide the balloon tip when the user types in the control or when the control loses focus
+    #[cfg(feature = "tooltip")]
+    fn hook_balloon_tip_dismiss(&self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::{WM_CHAR, WM_KILLFOCUS};
+        use winapi::um::commctrl::EM_HIDEBALLOONTIP;
+
+        if self.handler1.borrow().is_some() {
+            return;
+        }
+
+        let handler = bind_raw_event_handler_inner(&self.handle, 1, move |hwnd, msg, _w, _l| {
+            match msg {
+                WM_CHAR | WM_KILLFOCUS => { wh::send_message(hwnd, EM_HIDEBALLOONTIP, 0, 0); },
+                _ => {}
+            }
+
+            None
+        });
+
+        *self.handler1.borrow_mut() = handler.ok();
+    }
+
+}
+
+const REVEAL_TEXT: &'static str = "\u{25CB}";
+const HIDE_TEXT: &'static str = "\u{25CF}";
+
+/// Show (`revealed`) or hide the password of a text input. `hidden` stores the password char while the password is revealed.
+fn toggle_password(handle: HWND, hidden: &Cell<Option<char>>, revealed: bool) {
+    use winapi::um::winuser::{InvalidateRect, EM_GETPASSWORDCHAR, EM_SETPASSWORDCHAR, GetWindow, GW_CHILD};
+
+    match (revealed, hidden.get()) {
+        (true, None) => {
+            let current = wh::send_message(handle, EM_GETPASSWORDCHAR as u32, 0, 0) as u32;
+            if current == 0 { return; }
+
+            hidden.set(char::from_u32(current));
+            wh::send_message(handle, EM_SETPASSWORDCHAR as u32, 0, 0);
+        },
+        (false, Some(c)) => {
+            hidden.set(None);
+            wh::send_message(handle, EM_SETPASSWORDCHAR as u32, c as WPARAM, 0);
+        },
+        _ => { return; }
+    }
+
+    unsafe {
+        let button = GetWindow(handle, GW_CHILD);
+        if !button.is_null() {
+            wh::set_window_text(button, if revealed { HIDE_TEXT } else { REVEAL_TEXT });
+        }
+
+        InvalidateRect(handle, ::std::ptr::null(), 1);
+    }
 }
 
 impl Drop for TextInput {
@@ -468,6 +684,16 @@ impl Drop for TextInput {
         if let Some(h) = handler.as_ref() {
             drop(unbind_raw_event_handler(h));
         }
+
+        let handler = self.handler1.borrow();
+        if let Some(h) = handler.as_ref() {
+            drop(unbind_raw_event_handler(h));
+        }
+
+        let handler = self.handler2.borrow();
+        if let Some(h) = handler.as_ref() {
+            drop(unbind_raw_event_handler(h));
+        }
         
         if let Some(bg) = self.background_brush {
             unsafe { DeleteObject(bg as _); }
@@ -480,18 +706,22 @@ impl Drop for TextInput {
 pub struct TextInputBuilder<'a> {
     text: &'a str,
     placeholder_text: Option<&'a str>,
+    placeholder_focused: bool,
     size: (i32, i32),
     position: (i32, i32),
     flags: Option<TextInputFlags>,
     ex_flags: u32,
     limit: usize,
     password: Option<char>,
+    password_reveal: bool,
     align: HTextAlign,
     readonly: bool,
     font: Option<&'a Font>,
     parent: Option<ControlHandle>,
     background_color: Option<[u8; 3]>,
     focus: bool,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a> TextInputBuilder<'a> {
@@ -516,6 +746,11 @@ impl<'a> TextInputBuilder<'a> {
         self
     }
 
+    pub fn placeholder_focused(mut self, focused: bool) -> TextInputBuilder<'a> {
+        self.placeholder_focused = focused;
+        self
+    }
+
     pub fn size(mut self, size: (i32, i32)) -> TextInputBuilder<'a> {
         self.size = size;
         self
@@ -536,6 +771,11 @@ impl<'a> TextInputBuilder<'a> {
         self
     }
 
+    pub fn password_reveal(mut self, reveal: bool) -> TextInputBuilder<'a> {
+        self.password_reveal = reveal;
+        self
+    }
+
     pub fn align(mut self, align: HTextAlign) -> TextInputBuilder<'a> {
         self.align = align;
         self
@@ -566,8 +806,13 @@ impl<'a> TextInputBuilder<'a> {
         self
     }
 
+    pub fn name(mut self, name: &str) -> TextInputBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut TextInput) -> Result<(), NwgError> {
-        let mut flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let mut flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
 
         match self.align {
             HTextAlign::Left => flags |= ES_LEFT,
@@ -586,6 +831,7 @@ impl<'a> TextInputBuilder<'a> {
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -603,7 +849,7 @@ impl<'a> TextInputBuilder<'a> {
         }
 
         if self.password.is_some() {
-            out.set_password_char(self.password)
+            out.set_password_char(self.password);
         }
 
         if self.readonly {
@@ -620,8 +866,10 @@ impl<'a> TextInputBuilder<'a> {
             out.set_font(Font::global_default().as_ref());
         }
 
+        out.hook_password(self.password.is_some() && self.password_reveal);
+
         if self.placeholder_text.is_some() {
-            out.set_placeholder_text(self.placeholder_text);
+            out.set_placeholder_text_focused(self.placeholder_text, self.placeholder_focused);
         }
 
         Ok(())
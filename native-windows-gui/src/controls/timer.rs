@@ -17,6 +17,7 @@ A timer is an invisible UI component that trigger the `OnTimerTick` event at the
 Timers are mosty used to handle animations OR to create a timeout. To sync multithreaded action see the `Notice` object.
 
 A timer still requires a top level window parent. If the top level window parent is destroyed, the timer becomes invalid.
+The parent can be a `MessageWindow`, so timers also work in applications without a visible window.
 
 Note that timer SHOULD NOT be used when a consistent interval is needed. The timer event might be triggered much faster
 than the `interval` value. For example, when a user resize a window, Timer OnTimerTick gets triggered each time the window size changes.
@@ -26,7 +27,7 @@ This is a Windows "feature", there's probably nothing I can do to fix that.
 Requires the `timer` feature. 
 
 **Builder parameters:**
-  * `parent`:   **Required.** The timer parent container. Should be a top level window
+  * `parent`:   **Required.** The timer parent container. Should be a top level window or a message window
   * `interval`:  The timer tick interval in millisecond
   * `stopped`:   If the timer should start right away. By default timers starts "stopped(true)". Be sure to include `stopped(false)` in your builder if you want the timer to start instantly.
 
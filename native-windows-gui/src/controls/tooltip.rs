@@ -1,10 +1,12 @@
 use winapi::shared::minwindef::{UINT, LPARAM, WPARAM};
+use winapi::shared::windef::HWND;
 use winapi::um::winnt::WCHAR;
+use winapi::um::commctrl::TTTOOLINFOW;
 use crate::win32::window_helper as wh;
 use crate::win32::base_helper::{check_hwnd, to_utf16, from_utf16};
-use crate::{Icon, NwgError};
+use crate::{Icon, NwgError, RawEventHandler, unbind_raw_event_handler};
 use super::{ControlBase, ControlHandle};
-use std::{mem, ptr};
+use std::{mem, ptr, cell::RefCell};
 
 const NOT_BOUND: &'static str = "Tooltip is not yet bound to a winapi object";
 const BAD_HANDLE: &'static str = "INTERNAL ERROR: Tooltip handle is not HWND!";
@@ -32,6 +34,13 @@ This is done/undone using the `register`/`unregister` functions. So do not think
 as Tooltip as a standalone toolip, but more like a manager.
 
 A tooltip can support static text using `register` and dynamic text using `register_callback`.
+`register_with_decoration` registers a control with its own title and icon.
+
+By default, a tooltip text is displayed on a single line. Use `set_max_width` (or the `max_width` builder parameter)
+to wrap long text and to allow line breaks (`\r\n`) in the text.
+
+Tracking tooltips are not shown on hover. Instead, they are shown and moved by the application using
+`track_activate` and `track_position`. This is useful to display hover info over a custom canvas.
 
 Tooltip requires the `tooltip` features
 
@@ -86,9 +95,10 @@ impl GuiStruct {
 ```
 
 */
-#[derive(Default, PartialEq, Eq)]
+#[derive(Default)]
 pub struct Tooltip {
-    pub handle: ControlHandle
+    pub handle: ControlHandle,
+    decorated: RefCell<Vec<(HWND, RawEventHandler)>>,
 }
 
 impl Tooltip {
@@ -99,7 +109,11 @@ impl Tooltip {
             ico: None,
             default_ico: None,
             register: Vec::new(),
-            register_cb: Vec::new()
+            register_cb: Vec::new(),
+            register_deco: Vec::new(),
+            max_width: None,
+            delay_time: None,
+            autopop_time: None,
         }
     }
 
@@ -215,19 +229,10 @@ impl Tooltip {
     /// Set the icon and the title of a tooltip. This method use built-in icon defined by TooltipIcon
     pub fn set_default_decoration<'a>(&self, title: &'a str, icon: TooltipIcon) {
         use winapi::um::commctrl::{TTM_SETTITLEW};
-        use winapi::um::commctrl::{TTI_NONE, TTI_INFO, TTI_WARNING, TTI_ERROR, TTI_INFO_LARGE, TTI_WARNING_LARGE, TTI_ERROR_LARGE};
         
         let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
 
-        let bitmap_handle = match icon {
-            TooltipIcon::None => TTI_NONE,
-            TooltipIcon::Info => TTI_INFO,
-            TooltipIcon::Warning => TTI_WARNING,
-            TooltipIcon::Error => TTI_ERROR,
-            TooltipIcon::InfoLarge => TTI_INFO_LARGE,
-            TooltipIcon::WarningLarge => TTI_WARNING_LARGE,
-            TooltipIcon::ErrorLarge => TTI_ERROR_LARGE
-        };
+        let bitmap_handle = default_icon_id(icon);
 
         let title = to_utf16(title);
 
@@ -272,6 +277,76 @@ impl Tooltip {
         wh::send_message(handle, TTM_GETDELAYTIME, TTDT_INITIAL as WPARAM, 0) as u16
     }
 
+    /// Set the amount of time, in milliseconds, the tooltip remains visible if the pointer is stationary
+    /// Set the value to `None` to reset the value to default
+    pub fn set_autopop_time(&self, time: Option<u16>) {
+        use winapi::um::commctrl::{TTDT_AUTOPOP, TTM_SETDELAYTIME};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let value = match time {
+            Some(d) => d as LPARAM,
+            None => -1,
+        };
+
+        wh::send_message(handle, TTM_SETDELAYTIME, TTDT_AUTOPOP as WPARAM, value);
+    }
+
+    /// Return the amount of time, in milliseconds, the tooltip remains visible if the pointer is stationary
+    pub fn autopop_time(&self) -> u16 {
+        use winapi::um::commctrl::{TTDT_AUTOPOP, TTM_GETDELAYTIME};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        wh::send_message(handle, TTM_GETDELAYTIME, TTDT_AUTOPOP as WPARAM, 0) as u16
+    }
+
+    /// Set the amount of time, in milliseconds, it takes for the tooltip to appear when the pointer moves from one control to another
+    /// Set the value to `None` to reset the value to default
+    pub fn set_reshow_time(&self, time: Option<u16>) {
+        use winapi::um::commctrl::{TTDT_RESHOW, TTM_SETDELAYTIME};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let value = match time {
+            Some(d) => d as LPARAM,
+            None => -1,
+        };
+
+        wh::send_message(handle, TTM_SETDELAYTIME, TTDT_RESHOW as WPARAM, value);
+    }
+
+    /// Return the amount of time, in milliseconds, it takes for the tooltip to appear when the pointer moves from one control to another
+    pub fn reshow_time(&self) -> u16 {
+        use winapi::um::commctrl::{TTDT_RESHOW, TTM_GETDELAYTIME};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        wh::send_message(handle, TTM_GETDELAYTIME, TTDT_RESHOW as WPARAM, 0) as u16
+    }
+
+    /// Set the maximum width of the tooltip in pixels. Text longer than this width is wrapped on multiple lines.
+    /// Setting a maximum width also enables line breaks (`\r\n`) in the text.
+    /// Set the value to `None` to display the text on a single line.
+    pub fn set_max_width(&self, width: Option<u32>) {
+        use winapi::um::commctrl::TTM_SETMAXTIPWIDTH;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let value = match width {
+            Some(w) => w as LPARAM,
+            None => -1,
+        };
+
+        wh::send_message(handle, TTM_SETMAXTIPWIDTH, 0, value);
+    }
+
+    /// Return the maximum width of the tooltip in pixels, or `None` if the text is displayed on a single line
+    pub fn max_width(&self) -> Option<u32> {
+        use winapi::um::commctrl::TTM_GETMAXTIPWIDTH;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        match wh::send_message(handle, TTM_GETMAXTIPWIDTH, 0, 0) as i32 {
+            -1 => None,
+            w => Some(w as u32)
+        }
+    }
+
     /// Enable or disable the control
     /// Windows does not support reading the enabled state of a tooltip btw.
     pub fn set_enabled(&self, v: bool) {
@@ -344,6 +419,130 @@ impl Tooltip {
         wh::send_message(handle, TTM_ADDTOOLW, 0, tool_ptr as LPARAM);
     }
 
+    /**
+        Register the tooltip under a control with a title and an icon that are only used for this control.
+        The decoration set with `set_decoration` is restored when the tooltip is hidden.
+        `owner` must be a window control.
+    */
+    pub fn register_with_decoration<'a, W: Into<ControlHandle>>(&self, owner: W, text: &'a str, title: &'a str, icon: TooltipIcon) {
+        use winapi::um::commctrl::{TTM_ADDTOOLW, TTM_SETTITLEW, TTM_GETTITLE, TTGETTITLE, TTF_IDISHWND, TTF_SUBCLASS, LPSTR_TEXTCALLBACKW};
+        use winapi::um::commctrl::{TTN_GETDISPINFOW, TTN_POP, NMTTDISPINFOW};
+        use winapi::um::winuser::{NMHDR, WM_NOTIFY};
+        use winapi::shared::minwindef::DWORD;
+        use crate::bind_raw_event_handler_inner;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let owner = owner.into();
+        let owner_handle = {
+            if owner.blank() { panic!("{}", NOT_BOUND); }
+            owner.hwnd().expect(BAD_HANDLE)
+        };
+
+        self.unbind_decoration(owner_handle);
+
+        let tool = tool_info(owner_handle, TTF_IDISHWND | TTF_SUBCLASS, LPSTR_TEXTCALLBACKW);
+        let tool_ptr = &tool as *const TTTOOLINFOW;
+        wh::send_message(handle, TTM_ADDTOOLW, 0, tool_ptr as LPARAM);
+
+        let text = to_utf16(text);
+        let title = to_utf16(title);
+        let icon = default_icon_id(icon);
+        let saved: RefCell<Option<(UINT, Vec<WCHAR>)>> = RefCell::new(None);
+
+        let handler = bind_raw_event_handler_inner(&owner, handle as usize, move |_hwnd, msg, _w, l| { unsafe {
+            if msg != WM_NOTIFY {
+                return None;
+            }
+
+            let nmhdr: &NMHDR = mem::transmute(l);
+            if nmhdr.hwndFrom != handle || nmhdr.idFrom != owner_handle as usize {
+                return None;
+            }
+
+            match nmhdr.code {
+                TTN_GETDISPINFOW => {
+                    let mut old_title: Vec<WCHAR> = vec![0; 100];
+                    let mut get_title = TTGETTITLE {
+                        dwSize: mem::size_of::<TTGETTITLE>() as DWORD,
+                        uTitleBitmap: 0,
+                        cch: old_title.len() as UINT,
+                        pszTitle: old_title.as_mut_ptr()
+                    };
+                    wh::send_message(handle, TTM_GETTITLE, 0, &mut get_title as *mut TTGETTITLE as LPARAM);
+                    *saved.borrow_mut() = Some((get_title.uTitleBitmap, old_title));
+
+                    wh::send_message(handle, TTM_SETTITLEW, icon as WPARAM, title.as_ptr() as LPARAM);
+
+                    let info: &mut NMTTDISPINFOW = mem::transmute(l);
+                    info.lpszText = text.as_ptr() as *mut WCHAR;
+                    Some(0)
+                },
+                TTN_POP => {
+                    if let Some((old_icon, old_title)) = saved.borrow_mut().take() {
+                        wh::send_message(handle, TTM_SETTITLEW, old_icon as WPARAM, old_title.as_ptr() as LPARAM);
+                    }
+                    None
+                },
+                _ => None
+            }
+        } });
+
+        if let Ok(handler) = handler {
+            self.decorated.borrow_mut().push((owner_handle, handler));
+        }
+    }
+
+    /**
+        Register a tracking tooltip under a control. A tracking tooltip is not shown when the user hovers the control,
+        instead it is shown using `track_activate` and moved using `track_position`.
+
+        A control cannot be registered as a tracking tooltip and as a regular tooltip at the same time.
+        `owner` must be a window control.
+    */
+    pub fn register_tracking<'a, W: Into<ControlHandle>>(&self, owner: W, text: &'a str) {
+        use winapi::um::commctrl::{TTM_ADDTOOLW, TTF_IDISHWND, TTF_TRACK, TTF_ABSOLUTE};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let owner = owner.into();
+        let owner_handle = {
+            if owner.blank() { panic!("{}", NOT_BOUND); }
+            owner.hwnd().expect(BAD_HANDLE)
+        };
+
+        let mut text = to_utf16(text);
+        let tool = tool_info(owner_handle, TTF_IDISHWND | TTF_TRACK | TTF_ABSOLUTE, text.as_mut_ptr());
+        let tool_ptr = &tool as *const TTTOOLINFOW;
+        wh::send_message(handle, TTM_ADDTOOLW, 0, tool_ptr as LPARAM);
+    }
+
+    /// Show or hide a tracking tooltip registered with `register_tracking`
+    pub fn track_activate<W: Into<ControlHandle>>(&self, owner: W, active: bool) {
+        use winapi::um::commctrl::{TTM_TRACKACTIVATE, TTF_IDISHWND, TTF_TRACK, TTF_ABSOLUTE};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let owner = owner.into();
+        let owner_handle = {
+            if owner.blank() { panic!("{}", NOT_BOUND); }
+            owner.hwnd().expect(BAD_HANDLE)
+        };
+
+        let tool = tool_info(owner_handle, TTF_IDISHWND | TTF_TRACK | TTF_ABSOLUTE, ptr::null_mut());
+        let tool_ptr = &tool as *const TTTOOLINFOW;
+        wh::send_message(handle, TTM_TRACKACTIVATE, active as WPARAM, tool_ptr as LPARAM);
+    }
+
+    /// Move the active tracking tooltip. `x` and `y` are in screen coordinates.
+    pub fn track_position(&self, x: i32, y: i32) {
+        use winapi::um::commctrl::TTM_TRACKPOSITION;
+        use winapi::shared::minwindef::MAKELONG;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        wh::send_message(handle, TTM_TRACKPOSITION, 0, MAKELONG(x as u16, y as u16) as LPARAM);
+    }
+
     /// Remove the tooltip from a control
     pub fn unregister<W: Into<ControlHandle>>(&self, owner: W) {
         use winapi::um::commctrl::{TTM_DELTOOLW, TTTOOLINFOW, TTF_IDISHWND, TTF_SUBCLASS};
@@ -371,6 +570,8 @@ impl Tooltip {
 
         let tool_ptr = &tool as *const TTTOOLINFOW;
         wh::send_message(handle, TTM_DELTOOLW, 0, tool_ptr as LPARAM);   
+
+        self.unbind_decoration(owner_handle);
     }
 
     /// Winapi class name used during control creation
@@ -391,19 +592,75 @@ impl Tooltip {
         WS_POPUP | TTS_ALWAYSTIP | TTS_NOPREFIX
     }
 
+    /// Remove the decoration hook of a control registered with `register_with_decoration`
+    fn unbind_decoration(&self, owner: HWND) {
+        let mut decorated = self.decorated.borrow_mut();
+        if let Some(index) = decorated.iter().position(|(h, _)| *h == owner) {
+            let (_, handler) = decorated.remove(index);
+            drop(unbind_raw_event_handler(&handler));
+        }
+    }
+
 }
 
 impl Drop for Tooltip {
     fn drop(&mut self) {
+        for (_, handler) in self.decorated.borrow().iter() {
+            drop(unbind_raw_event_handler(handler));
+        }
+
         self.handle.destroy();
     }
 }
+
+impl PartialEq for Tooltip {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for Tooltip {}
+
+fn tool_info(owner: HWND, flags: UINT, text: *mut WCHAR) -> TTTOOLINFOW {
+    use winapi::shared::{basetsd::UINT_PTR, windef::RECT};
+
+    TTTOOLINFOW {
+        cbSize: mem::size_of::<TTTOOLINFOW>() as UINT,
+        uFlags: flags,
+        hwnd: owner,
+        uId: owner as UINT_PTR,
+        rect: RECT { left: 0, top: 0, right: 0, bottom: 0 },
+        hinst: ptr::null_mut(),
+        lpszText: text,
+        lParam: 0,
+        lpReserved: ptr::null_mut()
+    }
+}
+
+/// Returns the `TTI_*` identifier of a built-in tooltip icon. Also used by the balloon tips of the edit controls.
+pub(crate) fn default_icon_id(icon: TooltipIcon) -> usize {
+    use winapi::um::commctrl::{TTI_NONE, TTI_INFO, TTI_WARNING, TTI_ERROR, TTI_INFO_LARGE, TTI_WARNING_LARGE, TTI_ERROR_LARGE};
+
+    match icon {
+        TooltipIcon::None => TTI_NONE,
+        TooltipIcon::Info => TTI_INFO,
+        TooltipIcon::Warning => TTI_WARNING,
+        TooltipIcon::Error => TTI_ERROR,
+        TooltipIcon::InfoLarge => TTI_INFO_LARGE,
+        TooltipIcon::WarningLarge => TTI_WARNING_LARGE,
+        TooltipIcon::ErrorLarge => TTI_ERROR_LARGE
+    }
+}
 pub struct TooltipBuilder<'a> {
     title: Option<&'a str>,
     ico: Option<&'a Icon>,
     default_ico: Option<TooltipIcon>,
     register: Vec<(ControlHandle, &'a str)>,
     register_cb: Vec<ControlHandle>,
+    register_deco: Vec<(ControlHandle, &'a str, &'a str, TooltipIcon)>,
+    max_width: Option<u32>,
+    delay_time: Option<u16>,
+    autopop_time: Option<u16>,
 }
 
 impl<'a> TooltipBuilder<'a> {
@@ -418,6 +675,26 @@ impl<'a> TooltipBuilder<'a> {
         self
     }
 
+    pub fn register_with_decoration<W: Into<ControlHandle>>(mut self, widget: W, text: &'a str, title: &'a str, icon: TooltipIcon) -> TooltipBuilder<'a> {
+        self.register_deco.push((widget.into(), text, title, icon));
+        self
+    }
+
+    pub fn max_width(mut self, width: Option<u32>) -> TooltipBuilder<'a> {
+        self.max_width = width;
+        self
+    }
+
+    pub fn delay_time(mut self, time: Option<u16>) -> TooltipBuilder<'a> {
+        self.delay_time = time;
+        self
+    }
+
+    pub fn autopop_time(mut self, time: Option<u16>) -> TooltipBuilder<'a> {
+        self.autopop_time = time;
+        self
+    }
+
     pub fn decoration(mut self, title: Option<&'a str>, ico: Option<&'a Icon>) -> TooltipBuilder<'a> {
         self.title = title;
         self.ico = ico;
@@ -456,6 +733,22 @@ impl<'a> TooltipBuilder<'a> {
             tooltip.register_callback(&handle);
         }
 
+        for (handle, text, title, icon) in self.register_deco {
+            tooltip.register_with_decoration(&handle, text, title, icon);
+        }
+
+        if self.max_width.is_some() {
+            tooltip.set_max_width(self.max_width);
+        }
+
+        if self.delay_time.is_some() {
+            tooltip.set_delay_time(self.delay_time);
+        }
+
+        if self.autopop_time.is_some() {
+            tooltip.set_autopop_time(self.autopop_time);
+        }
+
         Ok(())
     }
 
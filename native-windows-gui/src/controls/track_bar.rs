@@ -11,6 +11,7 @@ use crate::win32::window_helper as wh;
 use crate::win32::base_helper::check_hwnd;
 use crate::{NwgError, RawEventHandler};
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
 use std::cell::RefCell;
 use std::ops::Range;
 
@@ -62,6 +63,9 @@ Requires the `trackbar` feature.
   * `OnHorizontalScroll`: When the value of a trackbar with the HORIZONTAL flags is changed
   * `MousePress(_)`: Generic mouse press events on the button
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
 
 ```rust
@@ -96,7 +100,9 @@ impl TrackBar {
             flags: None,
             ex_flags: 0,
             parent: None,
-            background_color: None
+            background_color: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -334,6 +340,8 @@ pub struct TrackBarBuilder {
     ex_flags: u32,
     parent: Option<ControlHandle>,
     background_color: Option<[u8; 3]>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl TrackBarBuilder {
@@ -388,8 +396,13 @@ impl TrackBarBuilder {
         self
     }
 
+    pub fn name(mut self, name: &str) -> TrackBarBuilder {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut TrackBar) -> Result<(), NwgError> {
-        let flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
 
         let parent = match self.parent {
             Some(p) => Ok(p),
@@ -399,6 +412,7 @@ impl TrackBarBuilder {
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -4,6 +4,7 @@ use super::{ControlBase, ControlHandle};
 use crate::win32::base_helper::to_utf16;
 use crate::win32::window_helper as wh;
 use crate::{Icon, NwgError};
+use winapi::shared::windef::HWND;
 use std::{mem, ptr};
 
 const NOT_BOUND: &'static str = "TrayNotification is not yet bound to a winapi object";
@@ -29,7 +30,7 @@ bitflags! {
     A TrayNotification wraps a single icon in the Windows system tray.
     
     An application can have many TrayNotification, but each window (aka parent) can only have a single traynotification.
-    It is possible to create system tray only application with the `MessageOnlyWindow` control.
+    It is possible to create system tray only application with the `MessageWindow` control as the parent.
 
     A system tray will receive events if `callback` is set to true in the builder (the default behaviour).
     The control will generate mouse events such as `OnMouseMove` when the user interact with the tray icon or the message popup.
@@ -39,7 +40,7 @@ bitflags! {
 
     **Builder parameters:**
 
-        * `parent`:       **Required.** The tray notification parent container.
+        * `parent`:       **Required.** The tray notification parent container. Can be a message window.
         * `icon`:         **Required.** The icon to display in the system tray
         * `tips`:         Display a simple tooltip when hovering the icon in the system tray
         * `flags`:        A combination of the TrayNotificationFlags values.
@@ -243,6 +244,15 @@ impl TrayNotification {
 
 }
 
+/// Show or hide the tray icon owned by `hwnd`. Used by `Window` to minimize itself to the tray.
+pub(crate) fn set_tray_visibility(hwnd: HWND, visible: bool) {
+    let tray = TrayNotification { handle: ControlHandle::SystemTray(hwnd) };
+    tray.set_visibility(visible);
+
+    // The tray icon belongs to its parent window, it must not be deleted when the temporary wrapper is dropped
+    mem::forget(tray);
+}
+
 impl Drop for TrayNotification {
     fn drop(&mut self) {
         use winapi::um::shellapi::NIM_DELETE;
@@ -364,7 +374,7 @@ impl<'a> TrayNotificationBuilder<'a> {
                 Some(handle) => Ok(handle),
                 None => Err(NwgError::control_create("TrayNotification must be window-like control."))
             },
-            None => Err(NwgError::no_parent("Button"))
+            None => Err(NwgError::no_parent("TrayNotification"))
         }?;
 
         let icon = match self.icon {
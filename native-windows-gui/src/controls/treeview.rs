@@ -3,13 +3,20 @@ A tree-view control is a window that displays a hierarchical list of items
 */
 
 use winapi::shared::minwindef::{WPARAM, LPARAM};
+use winapi::shared::windef::HWND;
 use winapi::um::winuser::{WS_VISIBLE, WS_DISABLED, WS_TABSTOP};
 use winapi::um::commctrl::{HTREEITEM, TVIS_EXPANDED, TVIS_SELECTED, TVS_SHOWSELALWAYS, TVITEMW};
 use crate::win32::window_helper as wh;
+use crate::win32::scrolling::bind_smooth_scrolling;
 use crate::win32::base_helper::{check_hwnd, to_utf16, from_utf16};
-use crate::{Font, NwgError};
+use crate::{Font, NwgError, RawEventHandler, unbind_raw_event_handler};
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
+use super::incremental_search::{IncrementalSearch, find_item};
 use std::{mem, ptr};
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::rc::Rc;
 
 #[cfg(feature="image-list")]
 use winapi::um::commctrl::HIMAGELIST;
@@ -64,7 +71,7 @@ pub enum TreeInsert {
     /// Add the item as a root item 
     Root,
 
-    /// Inserts the item into the list in alphabetical order
+    /// Inserts the item into the list in alphabetical order, or in the order of the sort comparator of the tree view (see `TreeView::set_sort_comparator`)
     Sort,
 
     /// Insert the item after the choosen item
@@ -97,7 +104,7 @@ pub enum TreeItemAction {
 }
 
 /// A reference to an item in a TreeView
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct TreeItem {
     pub handle: HTREEITEM
 }
@@ -127,10 +134,16 @@ Requires the `tree-view` feature
   * `font`:       The font used for the treeview text
   * `parent`:     The treeview parent container.
   * `image_list`: Image list containing the icon to use in the tree-view
+  * `sorted`:     If the new items are always inserted in sorted order. See `TreeView::set_sorted`.
+  * `truncated_tooltips`: If the full text of the items cut by the border of the tree view is shown in a tooltip (defaults to true)
+  * `smooth_scrolling`: If the mouse wheel scrolls the tree view smoothly (defaults to false). See `nwg::set_wheel_routing`.
 
 **Control events:**
   * `MousePress(_)`: Generic mouse press events on the tree view
   * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
   * `OnMouseWheel`: Generic mouse wheel event
   * `OnTreeViewClick`: When the user has clicked the left mouse button within the control.
   * `OnTreeViewDoubleClick`: When the user has clicked the left mouse button within the control twice rapidly.
@@ -138,13 +151,21 @@ Requires the `tree-view` feature
   * `OnTreeFocusLost`: When the control has lost the input focus
   * `OnTreeFocus`: When the control has acquired the input focus
   * `OnTreeItemDelete`: Just before an item is deleted. Also sent for all the children.
+  * `OnTreeItemExpanding`: Before an item is expanded or collapsed. The children of the item can be inserted here. Sends a `EventData::OnTreeItemUpdate`.
   * `OnTreeItemExpanded`: After an item was expanded or collapsed. Sends a `EventData::OnTreeItemUpdate`.
   * `OnTreeItemChanged`: After the state of an item was changed. Sends a `EventData::OnTreeItemUpdate`.
   * `OnTreeItemSelectionChanged`: After the current selection was changed. Sends a `EventData::OnTreeItemChanged`.
+  * `OnSearchPrefixChanged`: When the user types in the tree view and the incremental search prefix changes (see `TreeView::search_prefix`)
 */
-#[derive(Default, PartialEq, Eq)]
+#[derive(Default)]
 pub struct TreeView {
-    pub handle: ControlHandle
+    pub handle: ControlHandle,
+    sorted: Cell<bool>,
+    tooltips_disabled: Cell<bool>,
+    sort_comparator: RefCell<Option<Rc<dyn Fn(&TreeItem, &TreeItem) -> Ordering>>>,
+    search: Rc<IncrementalSearch<dyn Fn(&str, &TreeItem) -> bool>>,
+    handler0: Option<RawEventHandler>,
+    handler1: Option<RawEventHandler>,
 } 
 
 
@@ -160,9 +181,14 @@ impl TreeView {
             ex_flags: 0,
             font: None,
             parent: None,
+            sorted: false,
+            truncated_tooltips: true,
+            smooth_scrolling: false,
 
             #[cfg(feature="image-list")]
             image_list: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
@@ -369,6 +395,7 @@ impl TreeView {
 
         let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
 
+        let (position, sort_after) = self.insert_position(position);
         let insert = match position {
             TreeInsert::First => TVI_FIRST,
             TreeInsert::Last => TVI_LAST,
@@ -395,6 +422,9 @@ impl TreeView {
 
         let ptr = &new_item as *const TVINSERTSTRUCTW;
         let handle = wh::send_message(handle, TVM_INSERTITEMW, 0, ptr as LPARAM) as HTREEITEM;
+        if sort_after {
+            self.sort_children(parent, false);
+        }
 
         self.invalidate();
 
@@ -409,6 +439,7 @@ impl TreeView {
 
         let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
 
+        let (position, sort_after) = self.insert_position(position);
         let insert = match position {
             TreeInsert::First => TVI_FIRST,
             TreeInsert::Last => TVI_LAST,
@@ -436,6 +467,9 @@ impl TreeView {
 
         let ptr = &new_item as *const TVINSERTSTRUCTW;
         let handle = wh::send_message(handle, TVM_INSERTITEMW, 0, ptr as LPARAM) as HTREEITEM;
+        if sort_after {
+            self.sort_children(parent, false);
+        }
 
         self.invalidate();
 
@@ -531,6 +565,11 @@ impl TreeView {
         item.pszText = text.as_ptr() as LPWSTR;
 
         wh::send_message(handle, TVM_SETITEMW, 0, &mut item as *mut TVITEMW as LPARAM);
+
+        if self.sorted.get() {
+            let parent = self.parent(tree_item);
+            self.sort_children(parent.as_ref(), false);
+        }
     }
 
     /// Returns the lParam of the selected item. Return None if the item is not in the tree view.
@@ -569,6 +608,224 @@ impl TreeView {
         Some(item.cChildren != 0)
     }
 
+    /**
+        Sets if the expand button is displayed next to an item, even if the item does not have children yet.
+        Use it with `OnTreeItemExpanding` to insert the children of an item only when the user expands it.
+    */
+    pub fn set_item_has_children(&self, tree_item: &TreeItem, has_children: bool) {
+        use winapi::um::commctrl::{TVM_SETITEMW, TVIF_CHILDREN, TVIF_HANDLE};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let mut item: TVITEMW = blank_item();
+        item.hItem = tree_item.handle;
+        item.mask = TVIF_CHILDREN | TVIF_HANDLE;
+        item.cChildren = has_children as i32;
+
+        wh::send_message(handle, TVM_SETITEMW, 0, &mut item as *mut TVITEMW as LPARAM);
+    }
+
+    /// Remove the children of an item. The item keeps its expand button, so its children can be loaded again on the next `OnTreeItemExpanding`.
+    pub fn remove_children(&self, tree_item: &TreeItem) {
+        use winapi::um::commctrl::{TVM_DELETEITEM, TVM_GETNEXTITEM, TVGN_CHILD};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        loop {
+            let child = wh::send_message(handle, TVM_GETNEXTITEM, TVGN_CHILD, tree_item.handle as LPARAM);
+            if child == 0 {
+                break;
+            }
+
+            wh::send_message(handle, TVM_DELETEITEM, 0, child);
+        }
+
+        self.set_item_has_children(tree_item, true);
+    }
+
+    /**
+        Sorts the children of `item`, or the root items if `item` is `None`. The items are sorted with the sort comparator
+        of the tree view (see `set_sort_comparator`), or in alphabetical order if there is none.
+        If `recursive` is true, all the levels of items under `item` are sorted.
+    */
+    pub fn sort_children(&self, item: Option<&TreeItem>, recursive: bool) {
+        use winapi::um::commctrl::{TVM_SORTCHILDREN, TVI_ROOT};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let comparator = self.sort_comparator.borrow().clone();
+        match comparator {
+            Some(compare) => self.sort_children_by(item, recursive, |a, b| compare(a, b)),
+            None => {
+                let parent = item.map(|i| i.handle).unwrap_or(TVI_ROOT);
+                wh::send_message(handle, TVM_SORTCHILDREN, recursive as WPARAM, parent as LPARAM);
+            }
+        }
+    }
+
+    /**
+        Sorts the children of `item`, or the root items if `item` is `None`, with the `compare` function.
+        If `recursive` is true, all the levels of items under `item` are sorted.
+
+        ```rust
+        use native_windows_gui as nwg;
+
+        fn sort_by_length(tree: &nwg::TreeView) {
+            tree.sort_children_by(None, true, |a, b| {
+                let a = tree.item_text(a).unwrap_or_default();
+                let b = tree.item_text(b).unwrap_or_default();
+                a.len().cmp(&b.len())
+            });
+        }
+        ```
+    */
+    pub fn sort_children_by<F>(&self, item: Option<&TreeItem>, recursive: bool, mut compare: F)
+        where F: FnMut(&TreeItem, &TreeItem) -> Ordering
+    {
+        let parent = item.map(|i| i.handle).unwrap_or(ptr::null_mut());
+        self.sort_children_with(parent, recursive, &mut compare);
+    }
+
+    /**
+        Sets the function used to sort the items of the tree view. The comparator is used by `sort_children`, by the items
+        inserted with `TreeInsert::Sort`, and by all the insertions if the tree view is sorted (see `set_sorted`).
+        If `compare` is `None`, the items are sorted in alphabetical order.
+
+        ```rust
+        use native_windows_gui as nwg;
+        use std::rc::Rc;
+
+        fn sort_by_param(tree: &Rc<nwg::TreeView>) {
+            let tree_ref = Rc::downgrade(tree);
+            tree.set_sort_comparator(Some(move |a: &nwg::TreeItem, b: &nwg::TreeItem| {
+                let tree = tree_ref.upgrade().unwrap();
+                tree.item_param(a).cmp(&tree.item_param(b))
+            }));
+
+            tree.set_sorted(true);
+        }
+        ```
+    */
+    pub fn set_sort_comparator<F>(&self, compare: Option<F>)
+        where F: Fn(&TreeItem, &TreeItem) -> Ordering + 'static
+    {
+        *self.sort_comparator.borrow_mut() = compare.map(|f| Rc::new(f) as Rc<dyn Fn(&TreeItem, &TreeItem) -> Ordering>);
+    }
+
+    /**
+        Sets the function that matches the items with the text typed by the user (the incremental search, or type-ahead).
+        The function receives the search prefix and an item, and returns true if the item matches the prefix.
+        The first matching item, starting at the selected item, is selected. Only the visible items (the items whose parents are expanded) are searched.
+        Typing the same character again moves to the next matching item.
+
+        If `matcher` is `None`, the tree view uses the native search, which matches the prefix with the text of the items.
+
+        ```rust
+        use native_windows_gui as nwg;
+        use std::rc::Rc;
+
+        /// Match the prefix anywhere in the text of the items
+        fn search_anywhere(tree: &Rc<nwg::TreeView>) {
+            let tree_ref = Rc::downgrade(tree);
+            tree.set_search_matcher(Some(move |prefix: &str, item: &nwg::TreeItem| {
+                let tree = tree_ref.upgrade().unwrap();
+                let text = tree.item_text(item).unwrap_or_default();
+                text.to_lowercase().contains(&prefix.to_lowercase())
+            }));
+        }
+        ```
+    */
+    pub fn set_search_matcher<F>(&self, matcher: Option<F>)
+        where F: Fn(&str, &TreeItem) -> bool + 'static
+    {
+        self.search.set_matcher(matcher.map(|f| Rc::new(f) as Rc<dyn Fn(&str, &TreeItem) -> bool>));
+    }
+
+    /**
+        Returns the text typed by the user in the current incremental search, or an empty string if there is no search in progress.
+        The prefix is cleared when the user stops typing for about a second. `OnSearchPrefixChanged` is raised when the prefix changes.
+    */
+    pub fn search_prefix(&self) -> String {
+        use winapi::um::commctrl::TVM_GETISEARCHSTRINGW;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        if self.search.matcher().is_some() {
+            return self.search.prefix();
+        }
+
+        let mut buffer = vec![0u16; 256];
+        let length = wh::send_message(handle, TVM_GETISEARCHSTRINGW, 0, buffer.as_mut_ptr() as _) as usize;
+        from_utf16(&buffer[..length.min(buffer.len())])
+    }
+
+    /// Returns `true` if the new items are always inserted in sorted order
+    pub fn sorted(&self) -> bool {
+        self.sorted.get()
+    }
+
+    /**
+        If `sorted` is true, the items inserted in the tree view are placed in sorted order, whatever the insert position,
+        and an item is moved back in order when its text is changed with `set_item_text`. This does not sort the items
+        already in the tree view, use `sort_children` for that.
+    */
+    pub fn set_sorted(&self, sorted: bool) {
+        self.sorted.set(sorted);
+    }
+
+    /**
+        Returns true if the text of an item is cut by the border of the tree view. Returns false if the item is not visible,
+        for example if its parent is collapsed or if it is scrolled out of the view.
+    */
+    pub fn item_text_truncated(&self, item: &TreeItem) -> bool {
+        use winapi::um::commctrl::TVM_GETITEMRECT;
+        use winapi::um::winuser::GetClientRect;
+        use winapi::shared::windef::RECT;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        // The handle of the item is passed in the rect
+        let mut rect: RECT = unsafe { mem::zeroed() };
+        unsafe { *(&mut rect as *mut RECT as *mut HTREEITEM) = item.handle; }
+
+        if wh::send_message(handle, TVM_GETITEMRECT, 1, &mut rect as *mut RECT as LPARAM) == 0 {
+            return false;
+        }
+
+        let mut client: RECT = unsafe { mem::zeroed() };
+        unsafe { GetClientRect(handle, &mut client); }
+
+        rect.right > client.right
+    }
+
+    /// Returns true if the full text of the items cut by the border of the tree view is shown in a tooltip when the mouse hovers them
+    pub fn truncated_tooltips(&self) -> bool {
+        use winapi::um::commctrl::TVM_GETTOOLTIPS;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let tooltip = wh::send_message(handle, TVM_GETTOOLTIPS, 0, 0);
+        tooltip != 0 && !self.tooltips_disabled.get()
+    }
+
+    /**
+        Show or hide the full text of the items cut by the border of the tree view in a tooltip when the mouse hovers them.
+        Enabled by default. Disable it if a `Tooltip` is registered on the tree view.
+
+        The tooltips cannot be enabled if the tree view was built with `truncated_tooltips(false)`.
+    */
+    pub fn set_truncated_tooltips(&self, enable: bool) {
+        use winapi::um::commctrl::{TVM_GETTOOLTIPS, TTM_ACTIVATE};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let tooltip = wh::send_message(handle, TVM_GETTOOLTIPS, 0, 0) as HWND;
+        if tooltip.is_null() {
+            return;
+        }
+
+        wh::send_message(tooltip, TTM_ACTIVATE, enable as WPARAM, 0);
+        self.tooltips_disabled.set(!enable);
+    }
+
     /// Returns the item state in the tree view or `None` if the item is not in the tree view
     pub fn item_state(&self, tree_item: &TreeItem) -> Option<TreeItemState> {
         use winapi::um::commctrl::{TVM_GETITEMW, TVIF_STATE, TVIF_HANDLE};
@@ -745,9 +1002,8 @@ impl TreeView {
     /// Winapi flags required by the control
     pub fn forced_flags(&self) -> u32 {
         use winapi::um::winuser::{WS_CHILD, WS_BORDER};
-        use winapi::um::commctrl::TVS_NOTOOLTIPS;
 
-        WS_CHILD | WS_BORDER | TVS_NOTOOLTIPS
+        WS_CHILD | WS_BORDER
     }
 
     /// Begins to in-place edit the specified item's text.
@@ -778,8 +1034,139 @@ impl TreeView {
     }
 }
 
+impl TreeView {
+
+    /// Returns the insert position of a new item and if the siblings of the item must be sorted after the insertion.
+    /// The items sorted with a comparator are inserted at the end of the list and then moved in place by `sort_children`.
+    fn insert_position(&self, position: TreeInsert) -> (TreeInsert, bool) {
+        let sort = match position {
+            TreeInsert::Sort => true,
+            _ => self.sorted.get()
+        };
+
+        match (sort, self.sort_comparator.borrow().is_some()) {
+            (true, true) => (TreeInsert::Last, true),
+            (true, false) => (TreeInsert::Sort, false),
+            (false, _) => (position, false)
+        }
+    }
+
+    fn sort_children_with(&self, parent: HTREEITEM, recursive: bool, compare: &mut dyn FnMut(&TreeItem, &TreeItem) -> Ordering) {
+        use winapi::um::commctrl::{TVM_SORTCHILDRENCB, TVSORTCB, TVGN_ROOT, TVGN_CHILD, TVGN_NEXT, TVI_ROOT};
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let mut children = Vec::new();
+        let mut next = match parent.is_null() {
+            true => next_treeview_item(&self.handle, TVGN_ROOT, ptr::null_mut()),
+            false => next_treeview_item(&self.handle, TVGN_CHILD, parent)
+        };
+
+        while let Some(child) = next {
+            next = next_treeview_item(&self.handle, TVGN_NEXT, child.handle);
+            children.push(child);
+        }
+
+        if recursive {
+            for child in children.iter() {
+                self.sort_children_with(child.handle, true, compare);
+            }
+        }
+
+        if children.len() < 2 {
+            return;
+        }
+
+        let mut order: Vec<usize> = (0..children.len()).collect();
+        order.sort_by(|&a, &b| compare(&children[a], &children[b]));
+
+        // The sort callback only receives the lParam of the items, so the lParam are replaced by the rank of the items during the sort
+        let params: Vec<isize> = children.iter().map(|c| self.item_param(c).unwrap_or(0)).collect();
+        for (rank, &index) in order.iter().enumerate() {
+            set_item_param(handle, &children[index], rank as isize);
+        }
+
+        let sort = TVSORTCB {
+            hParent: match parent.is_null() { true => TVI_ROOT, false => parent },
+            lpfnCompare: Some(compare_rank),
+            lParam: 0,
+        };
+
+        wh::send_message(handle, TVM_SORTCHILDRENCB, 0, &sort as *const TVSORTCB as LPARAM);
+
+        for (child, param) in children.iter().zip(params) {
+            set_item_param(handle, child, param);
+        }
+    }
+
+    /// Run the incremental search of the application when a character is typed, if there is a search matcher.
+    /// Otherwise the tree view runs its native search. `OnSearchPrefixChanged` is posted so that it is raised after the search.
+    fn bind_search_handler(&mut self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::WM_CHAR;
+        use winapi::um::commctrl::{TVM_GETNEXTITEM, TVM_SELECTITEM, TVGN_ROOT, TVGN_NEXTVISIBLE, TVGN_CARET};
+        use std::char;
+
+        let search = self.search.clone();
+        let handler = bind_raw_event_handler_inner(&self.handle, 0x020, move |hwnd, msg, w, _| {
+            if msg != WM_CHAR {
+                return None;
+            }
+
+            let matcher = match search.matcher() {
+                Some(m) => m,
+                None => {
+                    wh::post_message(hwnd, wh::NWG_SEARCH_PREFIX, 0, 0);
+                    return None;
+                }
+            };
+
+            let step = match char::from_u32(w as u32).and_then(|c| search.push(c)) {
+                Some(step) => step,
+                None => { return None; }
+            };
+
+            let mut items = Vec::new();
+            let mut item = wh::send_message(hwnd, TVM_GETNEXTITEM, TVGN_ROOT, 0) as HTREEITEM;
+            while !item.is_null() {
+                items.push(TreeItem { handle: item });
+                item = wh::send_message(hwnd, TVM_GETNEXTITEM, TVGN_NEXTVISIBLE, item as LPARAM) as HTREEITEM;
+            }
+
+            let selected = wh::send_message(hwnd, TVM_GETNEXTITEM, TVGN_CARET, 0) as HTREEITEM;
+            let current = items.iter().position(|i| i.handle == selected);
+
+            if let Some(index) = find_item(items.len(), current, step.next, |index| matcher(&step.text, &items[index])) {
+                wh::send_message(hwnd, TVM_SELECTITEM, TVGN_CARET, items[index].handle as LPARAM);
+            }
+
+            wh::post_message(hwnd, wh::NWG_SEARCH_PREFIX, 0, 0);
+            Some(0)
+        }).unwrap();
+
+        self.handler0 = Some(handler);
+    }
+
+}
+
+impl PartialEq for TreeView {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for TreeView {}
+
 impl Drop for TreeView {
     fn drop(&mut self) {
+        if let Some(h) = self.handler0.as_ref() {
+            drop(unbind_raw_event_handler(h));
+        }
+
+        if let Some(h) = self.handler1.as_ref() {
+            drop(unbind_raw_event_handler(h));
+        }
+
         self.handle.destroy();
     }
 }
@@ -795,9 +1182,14 @@ pub struct TreeViewBuilder<'a> {
     ex_flags: u32,
     font: Option<&'a Font>,
     parent: Option<ControlHandle>,
+    sorted: bool,
+    truncated_tooltips: bool,
+    smooth_scrolling: bool,
 
     #[cfg(feature="image-list")]
     image_list: Option<&'a ImageList>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 
@@ -843,14 +1235,39 @@ impl<'a> TreeViewBuilder<'a> {
         self
     }
 
+    pub fn sorted(mut self, sorted: bool) -> TreeViewBuilder<'a> {
+        self.sorted = sorted;
+        self
+    }
+
+    pub fn truncated_tooltips(mut self, enable: bool) -> TreeViewBuilder<'a> {
+        self.truncated_tooltips = enable;
+        self
+    }
+
+    pub fn smooth_scrolling(mut self, smooth: bool) -> TreeViewBuilder<'a> {
+        self.smooth_scrolling = smooth;
+        self
+    }
+
     #[cfg(feature="image-list")]
     pub fn image_list(mut self, list: Option<&'a ImageList>) -> TreeViewBuilder<'a> {
         self.image_list = list;
         self
     }
 
+    pub fn name(mut self, name: &str) -> TreeViewBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut TreeView) -> Result<(), NwgError> {
-        let flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        use winapi::um::commctrl::TVS_NOTOOLTIPS;
+
+        let mut flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
+        if !self.truncated_tooltips {
+            flags |= TVS_NOTOOLTIPS;
+        }
 
         let parent = match self.parent {
             Some(p) => Ok(p),
@@ -860,6 +1277,7 @@ impl<'a> TreeViewBuilder<'a> {
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .flags(flags)
@@ -876,6 +1294,12 @@ impl<'a> TreeViewBuilder<'a> {
         }
 
         builder_set_image_list(&self, out);
+        out.set_sorted(self.sorted);
+        out.bind_search_handler();
+
+        if self.smooth_scrolling {
+            out.handler1 = Some(bind_smooth_scrolling(&out.handle, 0x021));
+        }
 
         if self.focus {
             out.set_focus();
@@ -912,6 +1336,21 @@ fn next_treeview_item(handle: &ControlHandle, action: usize, item: HTREEITEM) ->
     }
 }
 
+fn set_item_param(handle: HWND, tree_item: &TreeItem, param: isize) {
+    use winapi::um::commctrl::{TVM_SETITEMW, TVIF_PARAM, TVIF_HANDLE};
+
+    let mut item: TVITEMW = blank_item();
+    item.mask = TVIF_HANDLE | TVIF_PARAM;
+    item.hItem = tree_item.handle;
+    item.lParam = param;
+
+    wh::send_message(handle, TVM_SETITEMW, 0, &mut item as *mut TVITEMW as LPARAM);
+}
+
+unsafe extern "system" fn compare_rank(rank1: LPARAM, rank2: LPARAM, _sort: LPARAM) -> i32 {
+    rank1.cmp(&rank2) as i32
+}
+
 #[cfg(feature="image-list")]
 fn builder_set_image_list(builder: &TreeViewBuilder, out: &TreeView) {
     if builder.image_list.is_some() {
@@ -0,0 +1,423 @@
+use winapi::shared::windef::{HWND, POINT};
+use super::ControlHandle;
+use crate::win32::window_helper as wh;
+use crate::{NwgError, RawEventHandler, unbind_raw_event_handler};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::ptr;
+
+const NOT_BOUND: &'static str = "Viewport2D is not yet bound to a winapi object";
+
+/// The id of the raw event handler of the canvas
+const VIEWPORT_ID: usize = 0x5650;
+
+
+/// The state shared with the raw event handler and with the rulers that track the viewport
+pub(crate) struct ViewportState {
+    pub(crate) canvas: HWND,
+    pub(crate) zoom: Cell<f64>,
+    pub(crate) offset: Cell<(f64, f64)>,
+    zoom_range: Cell<(f64, f64)>,
+    zoom_step: Cell<f64>,
+
+    /// The last cursor position while panning, in client coordinates
+    panning: Cell<Option<(i32, i32)>>,
+
+    /// The windows repainted when the transform changes
+    listeners: RefCell<Vec<HWND>>,
+}
+
+impl ViewportState {
+
+    /// Set the zoom and the offset, and raise `OnViewportChanged` if the transform changed
+    fn set(&self, zoom: f64, offset: (f64, f64)) {
+        let (min, max) = self.zoom_range.get();
+        let zoom = zoom.max(min).min(max);
+        if zoom == self.zoom.get() && offset == self.offset.get() {
+            return;
+        }
+
+        self.zoom.set(zoom);
+        self.offset.set(offset);
+        self.repaint_listeners();
+        wh::send_message(self.canvas, wh::NWG_VIEWPORT_CHANGED, 0, 0);
+    }
+
+    pub(crate) fn add_listener(&self, hwnd: HWND) {
+        self.listeners.borrow_mut().push(hwnd);
+    }
+
+    pub(crate) fn remove_listener(&self, hwnd: HWND) {
+        self.listeners.borrow_mut().retain(|&h| h != hwnd);
+    }
+
+    fn repaint_listeners(&self) {
+        use winapi::um::winuser::InvalidateRect;
+
+        for &hwnd in self.listeners.borrow().iter() {
+            unsafe { InvalidateRect(hwnd, ptr::null(), 0); }
+        }
+    }
+
+    /// Convert a world coordinate to a client coordinate of the canvas along one axis
+    pub(crate) fn world_to_screen_axis(&self, value: f64, vertical: bool) -> f64 {
+        let (ox, oy) = self.offset.get();
+        value * self.zoom.get() + if vertical { oy } else { ox }
+    }
+
+    /// Convert a client coordinate of the canvas to a world coordinate along one axis
+    pub(crate) fn screen_to_world_axis(&self, value: f64, vertical: bool) -> f64 {
+        let (ox, oy) = self.offset.get();
+        (value - if vertical { oy } else { ox }) / self.zoom.get()
+    }
+
+    /// Zoom by `factor` while keeping the world point under `anchor` (in client coordinates) at the same place
+    fn zoom_at(&self, factor: f64, anchor: (f64, f64)) {
+        let (min, max) = self.zoom_range.get();
+        let zoom = self.zoom.get();
+        let new_zoom = (zoom * factor).max(min).min(max);
+
+        let (ox, oy) = self.offset.get();
+        let (ax, ay) = anchor;
+        let ratio = new_zoom / zoom;
+        self.set(new_zoom, (ax - (ax - ox) * ratio, ay - (ay - oy) * ratio));
+    }
+
+}
+
+/**
+A 2D viewport manages the transform between the world coordinates of a drawing and the client coordinates of a canvas.
+It handles the mouse inputs of the canvas so that the drawing code only has to apply the transform.
+
+  * The mouse wheel zooms in and out around the cursor
+  * Dragging with the middle mouse button pans the view
+
+`OnViewportChanged` is raised by the canvas each time the zoom or the offset changes, including changes made with the methods of the viewport.
+Redraw the canvas when this event is received.
+
+The transform is `screen = world * zoom + offset`, where `screen` is in physical pixels of the canvas client area
+(the coordinates of the mouse messages). Use `world_to_screen` and `screen_to_world` to convert points, or `zoom` and `offset`
+to build the transform of the drawing API (GDI world transform, Direct2D matrix, plotters coordinates...).
+
+The other mouse events of the canvas are still raised.
+
+Requires the `extern-canvas` feature.
+
+**Builder parameters:**
+  * `canvas`:     **Required.** The window control (usually an `ExternCanvas`) that displays the drawing.
+  * `zoom`:       The initial zoom. Defaults to 1.0.
+  * `offset`:     The initial offset in pixels. Defaults to (0.0, 0.0).
+  * `zoom_range`: The smallest and the largest zoom allowed. Defaults to (0.01, 100.0).
+  * `zoom_step`:  The zoom factor of one notch of the mouse wheel. Defaults to 1.2.
+
+**Control events (raised by the canvas):**
+  * `OnViewportChanged`: When the zoom or the offset of the viewport changed
+
+```rust
+use native_windows_gui as nwg;
+
+struct Drawing {
+    canvas: nwg::ExternCanvas,
+    viewport: nwg::Viewport2D,
+}
+
+impl Drawing {
+    fn build_viewport(&mut self) {
+        nwg::Viewport2D::builder()
+            .canvas(&self.canvas)
+            .zoom_range((0.1, 20.0))
+            .build(&mut self.viewport)
+            .unwrap();
+    }
+
+    fn clicked(&self, x: i32, y: i32) {
+        let (wx, wy) = self.viewport.screen_to_world((x as f64, y as f64));
+        println!("World position: {}, {}", wx, wy);
+    }
+}
+```
+*/
+#[derive(Default)]
+pub struct Viewport2D {
+    pub handle: ControlHandle,
+    state: Option<Rc<ViewportState>>,
+    handler0: RefCell<Option<RawEventHandler>>,
+}
+
+impl Viewport2D {
+
+    pub fn builder() -> Viewport2DBuilder {
+        Viewport2DBuilder {
+            canvas: None,
+            zoom: 1.0,
+            offset: (0.0, 0.0),
+            zoom_range: (0.01, 100.0),
+            zoom_step: 1.2,
+        }
+    }
+
+    /// Return the zoom of the viewport: the number of pixels of one world unit
+    pub fn zoom(&self) -> f64 {
+        self.state().zoom.get()
+    }
+
+    /// Set the zoom of the viewport, keeping the center of the canvas at the same world position. The zoom is clamped to the zoom range.
+    pub fn set_zoom(&self, zoom: f64) {
+        let state = self.state();
+        let (width, height) = unsafe { wh::get_window_physical_size(state.canvas) };
+        state.zoom_at(zoom / state.zoom.get(), (width as f64 / 2.0, height as f64 / 2.0));
+    }
+
+    /// Multiply the zoom by `factor`, keeping the world point under `anchor` (in client coordinates) at the same place
+    pub fn zoom_at(&self, factor: f64, anchor: (f64, f64)) {
+        self.state().zoom_at(factor, anchor);
+    }
+
+    /// Return the offset of the viewport: the client position of the world origin, in pixels
+    pub fn offset(&self) -> (f64, f64) {
+        self.state().offset.get()
+    }
+
+    /// Set the offset of the viewport
+    pub fn set_offset(&self, offset: (f64, f64)) {
+        let state = self.state();
+        state.set(state.zoom.get(), offset);
+    }
+
+    /// Move the view by `dx`, `dy` pixels
+    pub fn pan(&self, dx: f64, dy: f64) {
+        let state = self.state();
+        let (x, y) = state.offset.get();
+        state.set(state.zoom.get(), (x + dx, y + dy));
+    }
+
+    /// Return the smallest and the largest zoom allowed
+    pub fn zoom_range(&self) -> (f64, f64) {
+        self.state().zoom_range.get()
+    }
+
+    /// Set the smallest and the largest zoom allowed. The current zoom is clamped to the new range.
+    pub fn set_zoom_range(&self, range: (f64, f64)) {
+        let state = self.state();
+        state.zoom_range.set(range);
+        state.set(state.zoom.get(), state.offset.get());
+    }
+
+    /// Return the zoom factor of one notch of the mouse wheel
+    pub fn zoom_step(&self) -> f64 {
+        self.state().zoom_step.get()
+    }
+
+    /// Set the zoom factor of one notch of the mouse wheel
+    pub fn set_zoom_step(&self, step: f64) {
+        self.state().zoom_step.set(step);
+    }
+
+    /// Reset the zoom to 1.0 and the offset to (0.0, 0.0)
+    pub fn reset(&self) {
+        self.state().set(1.0, (0.0, 0.0));
+    }
+
+    /**
+        Zoom and pan so that the world rectangle `[x, y, width, height]` fits in the canvas with a margin in pixels around it.
+        The rectangle is centered in the canvas. The zoom is clamped to the zoom range.
+    */
+    pub fn fit(&self, rect: [f64; 4], margin: u32) {
+        let state = self.state();
+        let (width, height) = unsafe { wh::get_window_physical_size(state.canvas) };
+        let [x, y, w, h] = rect;
+
+        let margin = margin as f64 * 2.0;
+        let available = ((width as f64 - margin).max(1.0), (height as f64 - margin).max(1.0));
+        let (min, max) = state.zoom_range.get();
+        let zoom = match (w > 0.0, h > 0.0) {
+            (true, true) => (available.0 / w).min(available.1 / h),
+            (true, false) => available.0 / w,
+            (false, true) => available.1 / h,
+            (false, false) => state.zoom.get(),
+        }.max(min).min(max);
+
+        let center = (x + w / 2.0, y + h / 2.0);
+        let offset = (width as f64 / 2.0 - center.0 * zoom, height as f64 / 2.0 - center.1 * zoom);
+        state.set(zoom, offset);
+    }
+
+    /// Convert a point from world coordinates to client coordinates of the canvas
+    pub fn world_to_screen(&self, point: (f64, f64)) -> (f64, f64) {
+        let state = self.state();
+        let zoom = state.zoom.get();
+        let (ox, oy) = state.offset.get();
+        (point.0 * zoom + ox, point.1 * zoom + oy)
+    }
+
+    /// Convert a point from client coordinates of the canvas to world coordinates
+    pub fn screen_to_world(&self, point: (f64, f64)) -> (f64, f64) {
+        let state = self.state();
+        let zoom = state.zoom.get();
+        let (ox, oy) = state.offset.get();
+        ((point.0 - ox) / zoom, (point.1 - oy) / zoom)
+    }
+
+    /// Return the world rectangle `[x, y, width, height]` visible in the canvas
+    pub fn visible_world(&self) -> [f64; 4] {
+        let state = self.state();
+        let (width, height) = unsafe { wh::get_window_physical_size(state.canvas) };
+        let (x, y) = self.screen_to_world((0.0, 0.0));
+        let zoom = state.zoom.get();
+        [x, y, width as f64 / zoom, height as f64 / zoom]
+    }
+
+    fn state(&self) -> &ViewportState {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.state.as_ref().unwrap()
+    }
+
+    /// Return the state shared with the rulers that track the viewport
+    pub(crate) fn shared_state(&self) -> Rc<ViewportState> {
+        if self.handle.blank() { panic!("{}", NOT_BOUND); }
+        self.state.clone().unwrap()
+    }
+
+    /// Handle the mouse wheel and the middle button messages of the canvas
+    fn bind(&self) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::{WM_MOUSEWHEEL, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEMOVE, WM_CAPTURECHANGED, GET_WHEEL_DELTA_WPARAM,
+            WHEEL_DELTA, SetCapture, ReleaseCapture, GetCapture};
+
+        let state = self.state.clone().unwrap();
+        let handler = bind_raw_event_handler_inner(&self.handle, VIEWPORT_ID, move |_hwnd, msg, w, _l| {
+            match msg {
+                WM_MOUSEWHEEL => {
+                    let notches = GET_WHEEL_DELTA_WPARAM(w) as f64 / WHEEL_DELTA as f64;
+                    let (x, y) = cursor_position(state.canvas);
+                    state.zoom_at(state.zoom_step.get().powf(notches), (x as f64, y as f64));
+                },
+                WM_MBUTTONDOWN => {
+                    state.panning.set(Some(cursor_position(state.canvas)));
+                    unsafe { SetCapture(state.canvas); }
+                },
+                WM_MOUSEMOVE => if let Some((last_x, last_y)) = state.panning.get() {
+                    let (x, y) = cursor_position(state.canvas);
+                    let (ox, oy) = state.offset.get();
+                    state.panning.set(Some((x, y)));
+                    state.set(state.zoom.get(), (ox + (x - last_x) as f64, oy + (y - last_y) as f64));
+                },
+                WM_MBUTTONUP if state.panning.get().is_some() => {
+                    // Cleared first because `ReleaseCapture` sends `WM_CAPTURECHANGED` to the canvas
+                    state.panning.set(None);
+                    unsafe {
+                        if GetCapture() == state.canvas {
+                            ReleaseCapture();
+                        }
+                    }
+                },
+                WM_CAPTURECHANGED => {
+                    state.panning.set(None);
+                },
+                _ => {}
+            }
+
+            None
+        });
+
+        *self.handler0.borrow_mut() = Some(handler.unwrap());
+    }
+
+}
+
+impl PartialEq for Viewport2D {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for Viewport2D {}
+
+impl Drop for Viewport2D {
+    fn drop(&mut self) {
+        if let Some(h) = self.handler0.borrow_mut().take() {
+            drop(unbind_raw_event_handler(&h));
+        }
+    }
+}
+
+pub struct Viewport2DBuilder {
+    canvas: Option<ControlHandle>,
+    zoom: f64,
+    offset: (f64, f64),
+    zoom_range: (f64, f64),
+    zoom_step: f64,
+}
+
+impl Viewport2DBuilder {
+
+    pub fn canvas<C: Into<ControlHandle>>(mut self, canvas: C) -> Viewport2DBuilder {
+        self.canvas = Some(canvas.into());
+        self
+    }
+
+    pub fn zoom(mut self, zoom: f64) -> Viewport2DBuilder {
+        self.zoom = zoom;
+        self
+    }
+
+    pub fn offset(mut self, offset: (f64, f64)) -> Viewport2DBuilder {
+        self.offset = offset;
+        self
+    }
+
+    pub fn zoom_range(mut self, range: (f64, f64)) -> Viewport2DBuilder {
+        self.zoom_range = range;
+        self
+    }
+
+    pub fn zoom_step(mut self, step: f64) -> Viewport2DBuilder {
+        self.zoom_step = step;
+        self
+    }
+
+    pub fn build(self, out: &mut Viewport2D) -> Result<(), NwgError> {
+        let canvas = match self.canvas {
+            Some(c) => match c.hwnd() {
+                Some(handle) => Ok(handle),
+                None => Err(NwgError::control_create("Viewport2D canvas must be a window control"))
+            },
+            None => Err(NwgError::no_parent("Viewport2D"))
+        }?;
+
+        let (min, max) = self.zoom_range;
+        if !(min > 0.0 && min <= max) {
+            return Err(NwgError::control_create("Viewport2D zoom range must be positive and ordered"));
+        }
+
+        *out = Default::default();
+        out.handle = ControlHandle::Hwnd(canvas);
+        out.state = Some(Rc::new(ViewportState {
+            canvas,
+            zoom: Cell::new(self.zoom.max(min).min(max)),
+            offset: Cell::new(self.offset),
+            zoom_range: Cell::new(self.zoom_range),
+            zoom_step: Cell::new(self.zoom_step),
+            panning: Cell::new(None),
+            listeners: RefCell::new(Vec::new()),
+        }));
+
+        out.bind();
+
+        Ok(())
+    }
+
+}
+
+/// Return the cursor position in the client area of the canvas
+fn cursor_position(canvas: HWND) -> (i32, i32) {
+    use winapi::um::winuser::{GetCursorPos, ScreenToClient};
+
+    let mut p = POINT { x: 0, y: 0 };
+    unsafe {
+        GetCursorPos(&mut p);
+        ScreenToClient(canvas, &mut p);
+    }
+
+    (p.x, p.y)
+}
@@ -2,13 +2,30 @@ use winapi::um::winuser::{WS_OVERLAPPEDWINDOW, WS_CLIPCHILDREN, WS_VISIBLE, WS_D
 WS_MINIMIZEBOX, WS_MAXIMIZEBOX, WS_SYSMENU, WS_THICKFRAME, WS_POPUP, WS_EX_TOPMOST, WS_EX_ACCEPTFILES};
 
 use crate::win32::window_helper as wh;
+use crate::win32::window::nwg_class;
 use crate::win32::base_helper::check_hwnd;
-use crate::{NwgError, Icon};
+use crate::{NwgError, Icon, Bitmap, BackgroundMode, DeviceNotification, RawEventHandler, unbind_raw_event_handler};
+use winapi::shared::guiddef::GUID;
+use winapi::shared::windef::HBITMAP;
 use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+#[cfg(feature = "tray-notification")]
+use super::TrayNotification;
+
+#[cfg(feature = "tray-notification")]
+use winapi::shared::windef::HWND;
 
 const NOT_BOUND: &'static str = "Window is not yet bound to a winapi object";
 const BAD_HANDLE: &'static str = "INTERNAL ERROR: Window handle is not HWND!";
 
+#[cfg(feature = "tray-notification")]
+const MINIMIZE_TO_TRAY_ID: usize = 0x7452;
+
+const BACKGROUND_BITMAP_ID: usize = 0x4247;
+
 
 bitflags! {
 
@@ -44,6 +61,20 @@ bitflags! {
 }
 
 
+/**
+    The action of a window message filter. See `Window::set_message_filter`.
+
+    * Allow: The message can be received from processes with a lower integrity level (ex: a non elevated process)
+    * Disallow: The message is blocked if it comes from a process with a lower integrity level
+    * Reset: Use the default process filter for the message
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MessageFilterAction {
+    Reset,
+    Allow,
+    Disallow
+}
+
 /**
     A basic top level window. At least one top level window is required to make a NWG application.
 
@@ -57,17 +88,25 @@ bitflags! {
       * `size`:        The default size of the window
       * `position`:    The default position of the window in the desktop
       * `icon`:        The window icon
+      * `icon_big`:    The large window icon (alt-tab, taskbar). Defaults to `icon` if not set.
+      * `icon_small`:  The small window icon (title bar). Defaults to `icon` if not set.
       * `accept_file`: If the window should accept files by drag & drop
       * `maximized`:   If the window should be maximized at creation
       * `minimized`:   If the window should be minimized at creation
       * `center`:      Center the window in the current monitor based on its size. If `true`, this overrides `position`
       * `topmost`:     If the window should always be on top of other system window
       * `parent`:      Logical parent of the window, unlike children controls, this is NOT required.
+      * `allow_lower_integrity_drop`: Allow files to be dropped from non elevated processes when the application runs elevated. See `Window::allow_lower_integrity_drop`.
+      * `minimize_to_tray`: Hide the window in the system tray when it is minimized. Uses the `TrayNotification` of the window. See **Minimize to tray** below.
+      * `background_bitmap`: A bitmap drawn in the background of the window and the mode used to draw it. See `Window::set_background_bitmap`.
 
     **Control events:**
       * `OnInit`: The window was created
       * `MousePress(_)`: Generic mouse press events on the button
       * `OnMouseMove`: Generic mouse mouse event
+      * `OnMouseEnter`: When the mouse enters the window
+      * `OnMouseLeave`: When the mouse leaves the window
+      * `OnMouseHover`: When the mouse rests over the window (see `set_hover_time`)
       * `OnMouseWheel`: Generic mouse wheel event
       * `OnPaint`: Generic on paint event
       * `OnKeyPress`: Generic key press
@@ -80,11 +119,52 @@ bitflags! {
       * `OnMove`: When the window is moved by the user
       * `OnFileDrop`: When a file is dropped in the window (only raised if accept_file is set)
       * `OnMinMaxInfo`: When the size or position of the window is about to change and the size of the windows must be restricted
+      * `OnSessionEnding`: When the user session is about to end. Can be used to block the session end.
+      * `OnSessionEnd`: When the user session is ending
+      * `OnPowerSuspend`: When the computer is about to sleep or hibernate
+      * `OnPowerResume`: When the computer resumed from sleep or hibernation
+      * `OnSystemTimeChange`: When the system time was changed
+      * `OnDpiChanged`: When the window moved to a monitor with a different dpi. Requires `set_dpi_awareness_per_monitor`
+      * `OnDeviceArrival`: When a device or a volume was added. See `Window::register_device_notification`
+      * `OnDeviceRemoval`: When a device or a volume was removed. See `Window::register_device_notification`
+      * `OnWindowMinimizedToTray`: When the window was hidden in the system tray (only raised if minimize to tray is enabled)
+      * `OnWindowRestoredFromTray`: When the window was restored from the system tray (only raised if minimize to tray is enabled)
+      * `OnShortcut`: When the user pressed a shortcut of the `ShortcutMap` of the window
+
+    **Minimize to tray**
+
+    With minimize to tray enabled, minimizing the window hides it and shows its tray icon instead.
+    Double clicking the tray icon restores the window and hides the tray icon again. Requires the `tray-notification` feature.
+
+    The `minimize_to_tray` builder parameter uses the `TrayNotification` that has the window as parent. Because the tray is
+    only looked up when the window is minimized, it can be built after the window. Build the tray with `visible(false)` so that
+    it is only shown when the window is minimized. To use a tray notification owned by another window (ex: a `MessageWindow`), use `Window::set_minimize_to_tray`.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn build_window(window: &mut nwg::Window, tray: &mut nwg::TrayNotification, icon: &nwg::Icon) -> Result<(), nwg::NwgError> {
+        nwg::Window::builder()
+            .title("Hello")
+            .minimize_to_tray(true)
+            .build(window)?;
+
+        nwg::TrayNotification::builder()
+            .parent(&*window)
+            .icon(Some(icon))
+            .visible(false)
+            .build(tray)
+    }
+    ```
 
 */
-#[derive(Default, PartialEq, Eq)]
+#[derive(Default)]
 pub struct Window {
-    pub handle: ControlHandle
+    pub handle: ControlHandle,
+    tray_handler0: RefCell<Option<RawEventHandler>>,
+    tray_handler1: RefCell<Option<RawEventHandler>>,
+    background: Rc<Cell<Option<(HBITMAP, BackgroundMode)>>>,
+    background_handler: RefCell<Option<RawEventHandler>>,
 }
 
 impl Window {
@@ -102,10 +182,73 @@ impl Window {
             flags: None,
             ex_flags: 0,
             icon: None,
-            parent: None
+            icon_big: None,
+            icon_small: None,
+            allow_lower_integrity_drop: false,
+            minimize_to_tray: false,
+            background_bitmap: None,
+            parent: None,
+            name: None,
+            state: Default::default(),
         }
     }
 
+    /// Return the mode of the background bitmap, or `None` if the window does not have a background bitmap
+    pub fn background_bitmap_mode(&self) -> Option<BackgroundMode> {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.background.get().map(|(_, mode)| mode)
+    }
+
+    /**
+        Set a bitmap drawn in the background of the window, behind the children controls. `None` removes the background bitmap.
+        The area not covered by the bitmap is filled with the window background color.
+
+        The bitmap is not copied, it must not be freed while the window uses it.
+    */
+    pub fn set_background_bitmap(&self, bitmap: Option<&Bitmap>, mode: BackgroundMode) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::{WM_ERASEBKGND, WM_SIZE, COLOR_WINDOW, InvalidateRect};
+        use winapi::shared::windef::{HDC, HBRUSH};
+        use std::ptr;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        match bitmap {
+            Some(b) => {
+                self.background.set(Some((b.handle as HBITMAP, mode)));
+
+                if self.background_handler.borrow().is_none() {
+                    let background = self.background.clone();
+                    let handler = bind_raw_event_handler_inner(&self.handle, BACKGROUND_BITMAP_ID, move |hwnd, msg, w, _l| {
+                        let (bitmap, mode) = background.get()?;
+                        match msg {
+                            WM_ERASEBKGND => unsafe {
+                                wh::draw_background_bitmap(hwnd, w as HDC, bitmap, mode, COLOR_WINDOW as HBRUSH);
+                                Some(1)
+                            },
+                            WM_SIZE if mode != BackgroundMode::Tile => unsafe {
+                                // The position or the size of the bitmap depends on the client area
+                                InvalidateRect(hwnd, ptr::null(), 1);
+                                None
+                            },
+                            _ => None
+                        }
+                    });
+
+                    *self.background_handler.borrow_mut() = Some(handler.unwrap());
+                }
+            },
+            None => {
+                self.background.set(None);
+                if let Some(h) = self.background_handler.borrow_mut().take() {
+                    drop(unbind_raw_event_handler(&h));
+                }
+            }
+        }
+
+        unsafe { InvalidateRect(handle, ptr::null(), 1); }
+    }
+
     /// Maximize the window
     pub fn maximize(&self) {
         let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
@@ -124,6 +267,82 @@ impl Window {
         wh::restore_window(handle);
     }
 
+    /// Return true if the window is hidden in the system tray when it is minimized
+    pub fn minimize_to_tray(&self) -> bool {
+        self.tray_handler0.borrow().is_some()
+    }
+
+    /**
+        Hide the window in the system tray when it is minimized. The tray icon is shown while the window is hidden and
+        double clicking it restores the window. If `tray` is `None`, minimize to tray is disabled.
+
+        See the **Minimize to tray** section of the window documentation.
+    */
+    #[cfg(feature = "tray-notification")]
+    pub fn set_minimize_to_tray(&self, tray: Option<&TrayNotification>) {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        match tray {
+            Some(tray) => {
+                let tray_handle = tray.handle.tray().expect("TrayNotification is not yet bound to a winapi object");
+                self.bind_minimize_to_tray(tray_handle);
+            },
+            None => self.unbind_minimize_to_tray()
+        }
+    }
+
+    /**
+        Change the message filter of the window. When an application runs elevated, Windows (UIPI) blocks most messages
+        sent by processes with a lower integrity level. Use `MessageFilterAction::Allow` to accept a message from these processes anyway.
+
+        Only allow the messages the window can safely process from an untrusted source.
+    */
+    pub fn set_message_filter(&self, message: u32, action: MessageFilterAction) -> Result<(), NwgError> {
+        const MSGFLT_RESET: u32 = 0;
+        const MSGFLT_ALLOW: u32 = 1;
+        const MSGFLT_DISALLOW: u32 = 2;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let action = match action {
+            MessageFilterAction::Reset => MSGFLT_RESET,
+            MessageFilterAction::Allow => MSGFLT_ALLOW,
+            MessageFilterAction::Disallow => MSGFLT_DISALLOW,
+        };
+
+        match unsafe { wh::change_message_filter(handle, message, action) } {
+            true => Ok(()),
+            false => Err(NwgError::events_binding(format!("Failed to change the message filter of message {:#X}", message)))
+        }
+    }
+
+    /**
+        Allow files to be dropped on the window and `WM_COPYDATA` messages to be received from processes
+        with a lower integrity level (ex: the explorer when the application runs as administrator).
+        The window must also accept dropped files (see the `accept_files` builder parameter).
+    */
+    pub fn allow_lower_integrity_drop(&self) -> Result<(), NwgError> {
+        use winapi::um::winuser::{WM_DROPFILES, WM_COPYDATA};
+
+        // Undocumented message used internally by the drag and drop of files
+        const WM_COPYGLOBALDATA: u32 = 0x0049;
+
+        for &message in [WM_DROPFILES, WM_COPYDATA, WM_COPYGLOBALDATA].iter() {
+            self.set_message_filter(message, MessageFilterAction::Allow)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+        Register the window for the `OnDeviceArrival` and `OnDeviceRemoval` events of the device interfaces of `interface_class`
+        (ex: `GUID_DEVINTERFACE_COMPORT`). If `interface_class` is `None`, all the device interfaces are registered.
+        The events are sent as long as the returned `DeviceNotification` is alive.
+    */
+    pub fn register_device_notification(&self, interface_class: Option<GUID>) -> Result<DeviceNotification, NwgError> {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        DeviceNotification::register(handle, interface_class)
+    }
+
     /// Force the window to refraw iteself and all its children
     pub fn invalidate(&self) {
         use winapi::um::winuser::InvalidateRect;
@@ -168,6 +387,62 @@ impl Window {
         }
     }
 
+    /// Return the large icon of the window (displayed in alt-tab and in the taskbar)
+    pub fn icon_big(&self) -> Option<Icon> {
+        use winapi::um::winuser::{WM_GETICON, ICON_BIG};
+        use winapi::um::winnt::HANDLE;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let handle = wh::send_message(handle, WM_GETICON, ICON_BIG as _, 0);
+        if handle == 0 {
+            None
+        } else {
+            Some(Icon { handle: handle as HANDLE, owned: false })
+        }
+    }
+
+    /// Set the large icon of the window (displayed in alt-tab and in the taskbar).
+    /// The icon is not copied and must be kept alive while it is used by the window.
+    /// - icon: The new icon. If None, the icon is removed
+    pub fn set_icon_big(&self, icon: Option<&Icon>) {
+        use winapi::um::winuser::{WM_SETICON, ICON_BIG};
+        use winapi::shared::minwindef::LPARAM;
+        use std::ptr;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let image_handle = icon.map(|i| i.handle).unwrap_or(ptr::null_mut());
+        wh::send_message(handle, WM_SETICON, ICON_BIG as _, image_handle as LPARAM);
+    }
+
+    /// Return the small icon of the window (displayed in the title bar)
+    pub fn icon_small(&self) -> Option<Icon> {
+        use winapi::um::winuser::{WM_GETICON, ICON_SMALL};
+        use winapi::um::winnt::HANDLE;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let handle = wh::send_message(handle, WM_GETICON, ICON_SMALL as _, 0);
+        if handle == 0 {
+            None
+        } else {
+            Some(Icon { handle: handle as HANDLE, owned: false })
+        }
+    }
+
+    /// Set the small icon of the window (displayed in the title bar).
+    /// The icon is not copied and must be kept alive while it is used by the window.
+    /// - icon: The new icon. If None, the icon is removed
+    pub fn set_icon_small(&self, icon: Option<&Icon>) {
+        use winapi::um::winuser::{WM_SETICON, ICON_SMALL};
+        use winapi::shared::minwindef::LPARAM;
+        use std::ptr;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        let image_handle = icon.map(|i| i.handle).unwrap_or(ptr::null_mut());
+        wh::send_message(handle, WM_SETICON, ICON_SMALL as _, image_handle as LPARAM);
+    }
+
     /// Return true if the control currently has the keyboard focus
     pub fn focus(&self) -> bool {
         let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
@@ -192,6 +467,22 @@ impl Window {
         unsafe { wh::set_window_enabled(handle, v) }
     }
 
+    /**
+        Move the keyboard focus to the next control of the window in the tab order, like the Tab key.
+        If the focus is not in the window, the first control is focused. Returns the focused control, or `None` if no control has a tab stop.
+    */
+    pub fn focus_next(&self) -> Option<ControlHandle> {
+        self.focus_tab_item(false)
+    }
+
+    /**
+        Move the keyboard focus to the previous control of the window in the tab order, like Shift+Tab.
+        If the focus is not in the window, the last control is focused. Returns the focused control, or `None` if no control has a tab stop.
+    */
+    pub fn focus_prev(&self) -> Option<ControlHandle> {
+        self.focus_tab_item(true)
+    }
+
     /// Return true if the control is visible to the user. Will return true even if the 
     /// control is outside of the parent client view (ex: at the position (10000, 10000))
     pub fn visible(&self) -> bool {
@@ -217,6 +508,81 @@ impl Window {
         unsafe { wh::set_window_size(handle, x, y, true) }
     }
 
+    /**
+        Copy an image of the window to the clipboard, like Alt+PrintScreen. Useful for the "report a bug" buttons of an application.
+
+        If `full_frame` is true, the title bar and the borders of the window are included. Otherwise only the client area is copied.
+        The image has the physical size of the window, so the text stays sharp on high dpi screens. The window is captured even if
+        it is covered by other windows.
+
+        The image is copied in the `Dib` and in the `PNG` clipboard formats (see `Clipboard::set_data_image`).
+        Returns an error if the window could not be captured.
+
+        Requires the `clipboard` feature.
+    */
+    #[cfg(feature = "clipboard")]
+    pub fn copy_to_clipboard(&self, full_frame: bool) -> Result<(), NwgError> {
+        use crate::Clipboard;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let (width, height, pixels) = unsafe { wh::capture_window(handle, full_frame) }
+            .ok_or_else(|| NwgError::resource_create("Failed to capture the window"))?;
+
+        Clipboard::set_data_image(&self.handle, width, height, &pixels);
+
+        Ok(())
+    }
+
+    /**
+        Returns the dpi of the window. 96 is a scale of 100%. With the per-monitor dpi awareness (see `set_dpi_awareness_per_monitor`),
+        this is the dpi of the monitor of the window. Otherwise, this is the system dpi.
+    */
+    pub fn dpi(&self) -> u32 {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { crate::win32::high_dpi::window_dpi(handle) as u32 }
+    }
+
+    /**
+        Resize the window to the smallest size where the layouts of the window give every child its preferred size
+        (see `GridLayout::preferred_size`). The size of the content is measured with the current fonts and DPI, so a dialog
+        can fit its content in every language. The menu and the status bar of the window are added to the size.
+
+        Returns `false` and does nothing if the window has no layout, or if it is maximized or minimized.
+    */
+    pub fn size_to_content(&self) -> bool {
+        use winapi::um::winuser::{AdjustWindowRectEx, GetMenu, SetWindowPos, IsZoomed, IsIconic, GWL_STYLE, GWL_EXSTYLE};
+        use winapi::um::winuser::{SWP_NOZORDER, SWP_NOMOVE, SWP_NOACTIVATE, SWP_NOOWNERZORDER};
+        use winapi::shared::windef::RECT;
+        use crate::win32::high_dpi::logical_to_physical_for;
+        use crate::layouts::measure::measure_layouts;
+        use std::ptr;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        if unsafe { IsZoomed(handle) != 0 || IsIconic(handle) != 0 } {
+            return false;
+        }
+
+        let [width, height] = match measure_layouts(handle) {
+            Some(size) => size,
+            None => { return false; }
+        };
+
+        let height = height + wh::get_status_bar_height(handle);
+
+        unsafe {
+            let (w, h) = logical_to_physical_for(handle, width as i32, height as i32);
+            let mut rect = RECT { left: 0, top: 0, right: w, bottom: h };
+            let style = wh::get_window_long(handle, GWL_STYLE) as u32;
+            let ex_style = wh::get_window_long(handle, GWL_EXSTYLE) as u32;
+            let has_menu = !GetMenu(handle).is_null();
+            AdjustWindowRectEx(&mut rect, style, has_menu as i32, ex_style);
+
+            SetWindowPos(handle, ptr::null_mut(), 0, 0, rect.right - rect.left, rect.bottom - rect.top, SWP_NOZORDER|SWP_NOMOVE|SWP_NOACTIVATE|SWP_NOOWNERZORDER);
+        }
+
+        true
+    }
+
     /// Return the position of the button in the parent window
     pub fn position(&self) -> (i32, i32) {
         let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
@@ -243,7 +609,78 @@ impl Window {
 
     /// Winapi class name used during control creation
     pub fn class_name(&self) -> &'static str {
-        "NativeWindowsGuiWindow"
+        nwg_class("NativeWindowsGuiWindow")
+    }
+
+    /// Hooks the window and the tray owner so that the window is hidden on minimize and restored on a tray double click
+    #[cfg(feature = "tray-notification")]
+    fn bind_minimize_to_tray(&self, tray: HWND) {
+        use crate::bind_raw_event_handler_inner;
+        use super::tray_notification::set_tray_visibility;
+        use crate::win32::window_helper::{NWG_TRAY, NWG_WINDOW_TRAY};
+        use winapi::um::winuser::{WM_SIZE, SIZE_MINIMIZED, WM_LBUTTONDBLCLK, SW_HIDE, SW_RESTORE, ShowWindow, SetForegroundWindow};
+        use winapi::shared::minwindef::{UINT, WPARAM, LPARAM, LRESULT, LOWORD};
+        use winapi::shared::basetsd::UINT_PTR;
+
+        self.unbind_minimize_to_tray();
+
+        let window = self.handle.hwnd().unwrap();
+        let callback = move |hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM| -> Option<LRESULT> {
+            if hwnd == window && msg == WM_SIZE && w == SIZE_MINIMIZED {
+                unsafe { ShowWindow(window, SW_HIDE); }
+                set_tray_visibility(tray, true);
+                wh::send_message(window, NWG_WINDOW_TRAY, 1, 0);
+            } else if hwnd == tray && msg == NWG_TRAY && LOWORD(l as u32) as u32 == WM_LBUTTONDBLCLK {
+                unsafe {
+                    ShowWindow(window, SW_RESTORE);
+                    SetForegroundWindow(window);
+                }
+                set_tray_visibility(tray, false);
+                wh::send_message(window, NWG_WINDOW_TRAY, 0, 0);
+            }
+
+            None
+        };
+
+        let handler0 = bind_raw_event_handler_inner(&self.handle, MINIMIZE_TO_TRAY_ID, callback.clone());
+        *self.tray_handler0.borrow_mut() = Some(handler0.unwrap());
+
+        if tray != window {
+            let handler1 = bind_raw_event_handler_inner(&ControlHandle::Hwnd(tray), window as UINT_PTR, callback);
+            *self.tray_handler1.borrow_mut() = Some(handler1.unwrap());
+        }
+    }
+
+    fn unbind_minimize_to_tray(&self) {
+        for handler in [&self.tray_handler0, &self.tray_handler1].iter() {
+            if let Some(h) = handler.borrow_mut().take() {
+                drop(unbind_raw_event_handler(&h));
+            }
+        }
+    }
+
+    /// Focus the control with a tab stop that comes after (or before) the focused control of the window
+    fn focus_tab_item(&self, previous: bool) -> Option<ControlHandle> {
+        use winapi::um::winuser::{GetFocus, GetNextDlgTabItem, IsChild};
+        use std::ptr;
+
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+
+        unsafe {
+            let focus = GetFocus();
+            let current = match !focus.is_null() && IsChild(handle, focus) != 0 {
+                true => focus,
+                false => ptr::null_mut()
+            };
+
+            let next = GetNextDlgTabItem(handle, current, previous as i32);
+            if next.is_null() {
+                return None;
+            }
+
+            wh::set_focus(next);
+            Some(ControlHandle::Hwnd(next))
+        }
     }
 
     // Winapi base flags used during window creation
@@ -257,8 +694,22 @@ impl Window {
     }
 }
 
+impl PartialEq for Window {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for Window {}
+
 impl Drop for Window {
     fn drop(&mut self) {
+        self.unbind_minimize_to_tray();
+
+        if let Some(h) = self.background_handler.borrow_mut().take() {
+            drop(unbind_raw_event_handler(&h));
+        }
+
         self.handle.destroy();
     }
 }
@@ -298,7 +749,14 @@ pub struct WindowBuilder<'a> {
     flags: Option<WindowFlags>,
     ex_flags: u32,
     icon: Option<&'a Icon>,
-    parent: Option<ControlHandle>
+    icon_big: Option<&'a Icon>,
+    icon_small: Option<&'a Icon>,
+    allow_lower_integrity_drop: bool,
+    minimize_to_tray: bool,
+    background_bitmap: Option<(&'a Bitmap, BackgroundMode)>,
+    parent: Option<ControlHandle>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
 }
 
 impl<'a> WindowBuilder<'a> {
@@ -333,6 +791,16 @@ impl<'a> WindowBuilder<'a> {
         self
     }
 
+    pub fn icon_big(mut self, ico: Option<&'a Icon>) -> WindowBuilder<'a> {
+        self.icon_big = ico;
+        self
+    }
+
+    pub fn icon_small(mut self, ico: Option<&'a Icon>) -> WindowBuilder<'a> {
+        self.icon_small = ico;
+        self
+    }
+
     pub fn accept_files(mut self, accept_files: bool) -> WindowBuilder<'a> {
         self.accept_files = accept_files;
         self
@@ -358,15 +826,35 @@ impl<'a> WindowBuilder<'a> {
         self
     }
 
+    pub fn allow_lower_integrity_drop(mut self, allow: bool) -> WindowBuilder<'a> {
+        self.allow_lower_integrity_drop = allow;
+        self
+    }
+
+    pub fn minimize_to_tray(mut self, minimize_to_tray: bool) -> WindowBuilder<'a> {
+        self.minimize_to_tray = minimize_to_tray;
+        self
+    }
+
+    pub fn background_bitmap(mut self, bitmap: Option<&'a Bitmap>, mode: BackgroundMode) -> WindowBuilder<'a> {
+        self.background_bitmap = bitmap.map(|b| (b, mode));
+        self
+    }
+
     pub fn parent<C: Into<ControlHandle>>(mut self, p: Option<C>) -> WindowBuilder<'a> {
         self.parent = p.map(|p2| p2.into());
         self
     }
 
+    pub fn name(mut self, name: &str) -> WindowBuilder<'a> {
+        self.name = Some(name.to_string());
+        self
+    }
+
     pub fn build(self, out: &mut Window) -> Result<(), NwgError> {
-        use crate::win32::high_dpi::physical_to_logical;
+        use crate::win32::high_dpi::physical_to_logical_for;
 
-        let flags = self.flags.map(|f| f.bits()).unwrap_or(out.flags());
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
 
         let mut ex_flags = self.ex_flags;
         if self.topmost { ex_flags |= WS_EX_TOPMOST; }
@@ -375,6 +863,7 @@ impl<'a> WindowBuilder<'a> {
         *out = Default::default();
 
         out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
             .class_name(out.class_name())
             .forced_flags(out.forced_flags())
             .ex_flags(ex_flags)
@@ -385,13 +874,41 @@ impl<'a> WindowBuilder<'a> {
             .parent(self.parent)
             .build()?;
 
+        crate::win32::mnemonics::init_keyboard_cues(out.handle.hwnd().unwrap());
+
         if self.icon.is_some() {
             out.set_icon(self.icon);
         }
 
+        if self.icon_big.is_some() {
+            out.set_icon_big(self.icon_big);
+        }
+
+        if self.icon_small.is_some() {
+            out.set_icon_small(self.icon_small);
+        }
+
+        if self.allow_lower_integrity_drop {
+            out.allow_lower_integrity_drop()?;
+        }
+
+        if let Some((bitmap, mode)) = self.background_bitmap {
+            out.set_background_bitmap(Some(bitmap), mode);
+        }
+
+        if self.minimize_to_tray {
+            #[cfg(feature = "tray-notification")]
+            out.bind_minimize_to_tray(out.handle.hwnd().unwrap());
+
+            #[cfg(not(feature = "tray-notification"))]
+            panic!("minimize_to_tray requires the `tray-notification` feature");
+        }
+
         if self.center {
             let [left, top, right, bottom] = crate::Monitor::monitor_rect_from_window(out as &Window);
-            let (m_width, m_height) = unsafe { physical_to_logical(right-left, bottom-top) };
+            let handle = out.handle.hwnd().unwrap();
+            let (left, top) = unsafe { physical_to_logical_for(handle, left, top) };
+            let (m_width, m_height) = unsafe { physical_to_logical_for(handle, right-left, bottom-top) };
             let (width, height) = self.size;
 
             let x = left + ((m_width-width)/2);
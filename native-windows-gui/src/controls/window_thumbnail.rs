@@ -0,0 +1,277 @@
+use winapi::shared::windef::{HWND, RECT, POINT};
+use winapi::shared::minwindef::DWORD;
+use winapi::shared::winerror::S_OK;
+use winapi::um::dwmapi::{HTHUMBNAIL, DWM_THUMBNAIL_PROPERTIES, DWM_TNP_RECTDESTINATION, DWM_TNP_RECTSOURCE, DWM_TNP_OPACITY,
+  DWM_TNP_VISIBLE, DWM_TNP_SOURCECLIENTAREAONLY};
+use super::ControlHandle;
+use crate::NwgError;
+use std::cell::Cell;
+use std::{ptr, mem};
+
+const NOT_BOUND: &'static str = "WindowThumbnail is not yet bound to a winapi object";
+
+
+/**
+    A live thumbnail of a window rendered by the desktop compositor (DWM) inside a control of the application.
+
+    The source can be any top level window, including the windows of other applications. The thumbnail is drawn over
+    the destination control (usually a `Frame`) and is updated by the system each time the source window changes.
+    The thumbnail is not a window and does not receive any event.
+
+    DWM can only draw thumbnails in top level windows. If the destination control is a child control, the thumbnail
+    is registered in its top level window and positioned over the control. Call `update` if the destination control is moved or resized.
+
+    Requires the `window-thumbnail` feature.
+
+    **Builder parameters:**
+      * `source`:           **Required.** The top level window to display
+      * `destination`:      **Required.** The control where the thumbnail is drawn
+      * `source_rect`:      The region of the source window to display, in source window coordinates. Defaults to the whole window.
+      * `destination_rect`: The region of the destination control covered by the thumbnail, in control coordinates. Defaults to the whole control.
+      * `opacity`:          The opacity of the thumbnail (0-255). Defaults to 255.
+      * `client_only`:      Only display the client area of the source window. Defaults to false.
+      * `visible`:          If the thumbnail is visible. Defaults to true.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn preview(thumbnail: &mut nwg::WindowThumbnail, source: &nwg::Window, frame: &nwg::Frame) -> Result<(), nwg::NwgError> {
+        nwg::WindowThumbnail::builder()
+            .source(source)
+            .destination(frame)
+            .client_only(true)
+            .build(thumbnail)
+    }
+    ```
+*/
+pub struct WindowThumbnail {
+    thumbnail: HTHUMBNAIL,
+    destination: HWND,
+    top_level: HWND,
+    source_rect: Cell<Option<[i32; 4]>>,
+    destination_rect: Cell<Option<[i32; 4]>>,
+    opacity: Cell<u8>,
+    client_only: Cell<bool>,
+    visible: Cell<bool>,
+}
+
+impl WindowThumbnail {
+
+    pub fn builder() -> WindowThumbnailBuilder {
+        WindowThumbnailBuilder {
+            source: None,
+            destination: None,
+            source_rect: None,
+            destination_rect: None,
+            opacity: 255,
+            client_only: false,
+            visible: true,
+        }
+    }
+
+    /// Return the size of the source window, as drawn by the compositor
+    pub fn source_size(&self) -> (u32, u32) {
+        use winapi::um::dwmapi::DwmQueryThumbnailSourceSize;
+        use winapi::shared::windef::SIZE;
+
+        if self.thumbnail.is_null() { panic!("{}", NOT_BOUND); }
+
+        let mut size = SIZE { cx: 0, cy: 0 };
+        unsafe { DwmQueryThumbnailSourceSize(self.thumbnail, &mut size); }
+
+        (size.cx as u32, size.cy as u32)
+    }
+
+    /// Set the region of the source window to display. `None` displays the whole window.
+    pub fn set_source_rect(&self, rect: Option<[i32; 4]>) {
+        self.source_rect.set(rect);
+        self.update();
+    }
+
+    /// Set the region of the destination control covered by the thumbnail. `None` covers the whole control.
+    pub fn set_destination_rect(&self, rect: Option<[i32; 4]>) {
+        self.destination_rect.set(rect);
+        self.update();
+    }
+
+    /// Set the opacity of the thumbnail
+    pub fn set_opacity(&self, opacity: u8) {
+        self.opacity.set(opacity);
+        self.update();
+    }
+
+    /// Only display the client area of the source window
+    pub fn set_client_only(&self, client_only: bool) {
+        self.client_only.set(client_only);
+        self.update();
+    }
+
+    /// Return true if the thumbnail is visible
+    pub fn visible(&self) -> bool {
+        self.visible.get()
+    }
+
+    /// Show or hide the thumbnail
+    pub fn set_visible(&self, visible: bool) {
+        self.visible.set(visible);
+        self.update();
+    }
+
+    /// Apply the properties of the thumbnail and move it over the destination control.
+    /// Must be called when the destination control is moved or resized.
+    pub fn update(&self) {
+        use winapi::um::dwmapi::DwmUpdateThumbnailProperties;
+        use winapi::um::winuser::{GetClientRect, MapWindowPoints};
+
+        if self.thumbnail.is_null() { panic!("{}", NOT_BOUND); }
+
+        let mut flags = DWM_TNP_RECTDESTINATION | DWM_TNP_OPACITY | DWM_TNP_VISIBLE | DWM_TNP_SOURCECLIENTAREAONLY;
+
+        let destination = unsafe {
+            let rect = match self.destination_rect.get() {
+                Some(r) => to_rect(r),
+                None => {
+                    let mut rect: RECT = mem::zeroed();
+                    GetClientRect(self.destination, &mut rect);
+                    rect
+                }
+            };
+
+            // The thumbnail is positioned in the client area of the top level window
+            let mut points = [POINT { x: rect.left, y: rect.top }, POINT { x: rect.right, y: rect.bottom }];
+            MapWindowPoints(self.destination, self.top_level, points.as_mut_ptr(), 2);
+            RECT { left: points[0].x, top: points[0].y, right: points[1].x, bottom: points[1].y }
+        };
+
+        let source = match self.source_rect.get() {
+            Some(r) => {
+                flags |= DWM_TNP_RECTSOURCE;
+                to_rect(r)
+            },
+            None => RECT { left: 0, top: 0, right: 0, bottom: 0 }
+        };
+
+        let properties = DWM_THUMBNAIL_PROPERTIES {
+            dwFlags: flags,
+            rcDestination: destination,
+            rcSource: source,
+            opacity: self.opacity.get(),
+            fVisible: self.visible.get() as _,
+            fSourceClientAreaOnly: self.client_only.get() as _,
+        };
+
+        unsafe { DwmUpdateThumbnailProperties(self.thumbnail, &properties); }
+    }
+
+}
+
+impl Default for WindowThumbnail {
+    fn default() -> WindowThumbnail {
+        WindowThumbnail {
+            thumbnail: ptr::null_mut(),
+            destination: ptr::null_mut(),
+            top_level: ptr::null_mut(),
+            source_rect: Cell::new(None),
+            destination_rect: Cell::new(None),
+            opacity: Cell::new(255),
+            client_only: Cell::new(false),
+            visible: Cell::new(true),
+        }
+    }
+}
+
+impl Drop for WindowThumbnail {
+    fn drop(&mut self) {
+        use winapi::um::dwmapi::DwmUnregisterThumbnail;
+
+        if !self.thumbnail.is_null() {
+            unsafe { DwmUnregisterThumbnail(self.thumbnail); }
+        }
+    }
+}
+
+pub struct WindowThumbnailBuilder {
+    source: Option<ControlHandle>,
+    destination: Option<ControlHandle>,
+    source_rect: Option<[i32; 4]>,
+    destination_rect: Option<[i32; 4]>,
+    opacity: u8,
+    client_only: bool,
+    visible: bool,
+}
+
+impl WindowThumbnailBuilder {
+
+    pub fn source<C: Into<ControlHandle>>(mut self, source: C) -> WindowThumbnailBuilder {
+        self.source = Some(source.into());
+        self
+    }
+
+    pub fn destination<C: Into<ControlHandle>>(mut self, destination: C) -> WindowThumbnailBuilder {
+        self.destination = Some(destination.into());
+        self
+    }
+
+    pub fn source_rect(mut self, rect: Option<[i32; 4]>) -> WindowThumbnailBuilder {
+        self.source_rect = rect;
+        self
+    }
+
+    pub fn destination_rect(mut self, rect: Option<[i32; 4]>) -> WindowThumbnailBuilder {
+        self.destination_rect = rect;
+        self
+    }
+
+    pub fn opacity(mut self, opacity: u8) -> WindowThumbnailBuilder {
+        self.opacity = opacity;
+        self
+    }
+
+    pub fn client_only(mut self, client_only: bool) -> WindowThumbnailBuilder {
+        self.client_only = client_only;
+        self
+    }
+
+    pub fn visible(mut self, visible: bool) -> WindowThumbnailBuilder {
+        self.visible = visible;
+        self
+    }
+
+    pub fn build(self, out: &mut WindowThumbnail) -> Result<(), NwgError> {
+        use winapi::um::dwmapi::DwmRegisterThumbnail;
+        use winapi::um::winuser::{GetAncestor, GA_ROOT};
+
+        let source = self.source.and_then(|s| s.hwnd())
+            .ok_or_else(|| NwgError::control_create("WindowThumbnail source must be a window"))?;
+
+        let destination = self.destination.and_then(|d| d.hwnd())
+            .ok_or_else(|| NwgError::control_create("WindowThumbnail destination must be a window control"))?;
+
+        let top_level = unsafe { GetAncestor(destination, GA_ROOT) };
+
+        *out = Default::default();
+
+        let mut thumbnail = ptr::null_mut();
+        let result = unsafe { DwmRegisterThumbnail(top_level, source, &mut thumbnail) };
+        if result != S_OK {
+            return Err(NwgError::control_create(format!("Failed to register the window thumbnail (error {:#X})", result as DWORD)));
+        }
+
+        out.thumbnail = thumbnail;
+        out.destination = destination;
+        out.top_level = top_level;
+        out.source_rect.set(self.source_rect);
+        out.destination_rect.set(self.destination_rect);
+        out.opacity.set(self.opacity);
+        out.client_only.set(self.client_only);
+        out.visible.set(self.visible);
+        out.update();
+
+        Ok(())
+    }
+
+}
+
+fn to_rect(r: [i32; 4]) -> RECT {
+    RECT { left: r[0], top: r[1], right: r[2], bottom: r[3] }
+}
@@ -0,0 +1,449 @@
+use winapi::um::winuser::{WS_VISIBLE, WS_DISABLED};
+use winapi::shared::windef::{HWND, RECT};
+use winapi::shared::minwindef::{BOOL, DWORD};
+use crate::win32::{base_helper::check_hwnd, window_helper as wh};
+use super::{ControlBase, ControlHandle};
+use super::control_builder::BuilderState;
+use crate::{NwgError, RawEventHandler, unbind_raw_event_handler};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::{Once, atomic::{AtomicBool, Ordering}};
+
+const NOT_BOUND: &'static str = "ZoomView is not yet bound to a winapi object";
+const BAD_HANDLE: &'static str = "INTERNAL ERROR: ZoomView handle is not HWND!";
+
+/// The id of the timer that refreshes the magnified region
+const ZOOM_TIMER_ID: usize = 0x7A4F;
+
+/// Show the cursor in the magnified region
+const MS_SHOWMAGNIFIEDCURSOR: DWORD = 0x0001;
+
+#[repr(C)]
+struct MAGTRANSFORM {
+    v: [[f32; 3]; 3]
+}
+
+#[link(name = "magnification")]
+extern "system" {
+    fn MagInitialize() -> BOOL;
+    fn MagSetWindowSource(hwnd: HWND, rect: RECT) -> BOOL;
+    fn MagSetWindowTransform(hwnd: HWND, transform: *mut MAGTRANSFORM) -> BOOL;
+}
+
+
+bitflags! {
+    /**
+        The zoom view flags
+
+        * VISIBLE:  The zoom view is immediatly visible after creation
+        * DISABLED: The zoom view cannot be interacted with by the user.
+        * SHOW_CURSOR: Draw the cursor in the magnified region
+    */
+    pub struct ZoomViewFlags: u32 {
+        const VISIBLE = WS_VISIBLE;
+        const DISABLED = WS_DISABLED;
+        const SHOW_CURSOR = MS_SHOWMAGNIFIEDCURSOR;
+    }
+}
+
+/// The state shared with the refresh timer
+struct ZoomState {
+    factor: Cell<f32>,
+    follow_cursor: Cell<bool>,
+    center: Cell<(i32, i32)>,
+}
+
+/**
+A zoom view displays a magnified live region of the screen using the Windows Magnification API.
+
+The magnified region is centered on the cursor, or on a fixed point of the screen if `follow_cursor` is disabled.
+The region is refreshed on a timer. The size of the magnified region is the size of the control divided by the zoom factor.
+
+The magnification API does not exclude the application windows. If the cursor moves over the zoom view while following the cursor,
+the zoom view magnifies itself.
+
+Requires the `zoom-view` feature.
+
+**Builder parameters:**
+  * `parent`:           **Required.** The zoom view parent container.
+  * `size`:             The zoom view size.
+  * `position`:         The zoom view position.
+  * `flags`:            A combination of the ZoomViewFlags values.
+  * `ex_flags`:         A combination of win32 window extended flags. Unlike `flags`, ex_flags must be used straight from winapi
+  * `factor`:           The magnification factor. Must be 1.0 or more. Defaults to 2.0.
+  * `follow_cursor`:    If the magnified region follows the cursor. Defaults to true.
+  * `center`:           The center of the magnified region in screen coordinates, when the region does not follow the cursor.
+  * `refresh_interval`: The delay in milliseconds between refreshes of the magnified region. Defaults to 16.
+
+**Control events:**
+  * `MousePress(_)`: Generic mouse press events on the zoom view
+  * `OnMouseMove`: Generic mouse mouse event
+  * `OnMouseEnter`: When the mouse enters the control
+  * `OnMouseLeave`: When the mouse leaves the control
+  * `OnMouseHover`: When the mouse rests over the control (see `set_hover_time`)
+
+```rust
+use native_windows_gui as nwg;
+fn build_zoom(zoom: &mut nwg::ZoomView, window: &nwg::Window) {
+    nwg::ZoomView::builder()
+        .size((200, 200))
+        .factor(4.0)
+        .parent(window)
+        .build(zoom);
+}
+```
+*/
+#[derive(Default)]
+pub struct ZoomView {
+    pub handle: ControlHandle,
+    state: Option<Rc<ZoomState>>,
+    handler0: RefCell<Option<RawEventHandler>>,
+}
+
+impl ZoomView {
+
+    pub fn builder() -> ZoomViewBuilder {
+        ZoomViewBuilder {
+            size: (200, 200),
+            position: (0, 0),
+            flags: None,
+            ex_flags: 0,
+            factor: 2.0,
+            follow_cursor: true,
+            center: (0, 0),
+            refresh_interval: 16,
+            parent: None,
+            name: None,
+            state: Default::default(),
+        }
+    }
+
+    /// Return the magnification factor
+    pub fn factor(&self) -> f32 {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state().factor.get()
+    }
+
+    /// Set the magnification factor. Values smaller than 1.0 are clamped to 1.0.
+    pub fn set_factor(&self, factor: f32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let factor = factor.max(1.0);
+
+        self.state().factor.set(factor);
+
+        let mut transform = MAGTRANSFORM { v: [[factor, 0.0, 0.0], [0.0, factor, 0.0], [0.0, 0.0, 1.0]] };
+        unsafe { MagSetWindowTransform(handle, &mut transform); }
+
+        refresh(handle, self.state());
+    }
+
+    /// Return true if the magnified region follows the cursor
+    pub fn follow_cursor(&self) -> bool {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state().follow_cursor.get()
+    }
+
+    /// Set if the magnified region follows the cursor
+    pub fn set_follow_cursor(&self, follow: bool) {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state().follow_cursor.set(follow);
+    }
+
+    /// Return the center of the magnified region in screen coordinates when the region does not follow the cursor
+    pub fn center(&self) -> (i32, i32) {
+        check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state().center.get()
+    }
+
+    /// Set the center of the magnified region in screen coordinates. Only used if the region does not follow the cursor.
+    pub fn set_center(&self, x: i32, y: i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        self.state().center.set((x, y));
+        refresh(handle, self.state());
+    }
+
+    /// Return the region of the screen currently magnified as `[left, top, right, bottom]`
+    pub fn source_rect(&self) -> [i32; 4] {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        let r = source_rect(handle, self.state());
+        [r.left, r.top, r.right, r.bottom]
+    }
+
+    /// Return true if the control user can interact with the control, return false otherwise
+    pub fn enabled(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_enabled(handle) }
+    }
+
+    /// Enable or disable the control
+    pub fn set_enabled(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_enabled(handle, v) }
+    }
+
+    /// Return true if the control is visible to the user. Will return true even if the
+    /// control is outside of the parent client view (ex: at the position (10000, 10000))
+    pub fn visible(&self) -> bool {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_visibility(handle) }
+    }
+
+    /// Show or hide the control to the user
+    pub fn set_visible(&self, v: bool) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_visibility(handle, v) }
+    }
+
+    /// Return the size of the zoom view in the parent window
+    pub fn size(&self) -> (u32, u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_size(handle) }
+    }
+
+    /// Set the size of the zoom view in the parent window
+    pub fn set_size(&self, x: u32, y: u32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_size(handle, x, y, false) }
+    }
+
+    /// Return the position of the zoom view in the parent window
+    pub fn position(&self) -> (i32, i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_position(handle) }
+    }
+
+    /// Set the position of the zoom view in the parent window
+    pub fn set_position(&self, x: i32, y: i32) {
+        let handle = check_hwnd(&self.handle, NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_position(handle, x, y) }
+    }
+
+    /// Winapi class name used during control creation
+    pub fn class_name(&self) -> &'static str {
+        "Magnifier"
+    }
+
+    /// Winapi base flags used during window creation
+    pub fn flags(&self) -> u32 {
+        WS_VISIBLE
+    }
+
+    /// Winapi flags required by the control
+    pub fn forced_flags(&self) -> u32 {
+        use winapi::um::winuser::WS_CHILD;
+
+        WS_CHILD
+    }
+
+    fn state(&self) -> &ZoomState {
+        self.state.as_ref().expect(NOT_BOUND)
+    }
+
+    /// Refresh the magnified region on a timer
+    fn bind_refresh_timer(&self, interval: u32) {
+        use crate::bind_raw_event_handler_inner;
+        use winapi::um::winuser::{WM_TIMER, SetTimer};
+        use winapi::shared::minwindef::LRESULT;
+
+        let handle = self.handle.hwnd().expect(BAD_HANDLE);
+        let state = self.state.clone().unwrap();
+
+        let handler = bind_raw_event_handler_inner(&self.handle, ZOOM_TIMER_ID, move |hwnd, msg, w, _l| {
+            if msg == WM_TIMER && w == ZOOM_TIMER_ID {
+                refresh(hwnd, &state);
+                return Some(0 as LRESULT);
+            }
+            None
+        });
+
+        *self.handler0.borrow_mut() = Some(handler.unwrap());
+
+        unsafe { SetTimer(handle, ZOOM_TIMER_ID, interval, None); }
+    }
+
+}
+
+impl Drop for ZoomView {
+    fn drop(&mut self) {
+        use winapi::um::winuser::KillTimer;
+
+        if let Some(handle) = self.handle.hwnd() {
+            unsafe { KillTimer(handle, ZOOM_TIMER_ID); }
+        }
+
+        if let Some(h) = self.handler0.borrow_mut().take() {
+            drop(unbind_raw_event_handler(&h));
+        }
+
+        self.handle.destroy();
+    }
+}
+
+impl PartialEq for ZoomView {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for ZoomView {}
+
+pub struct ZoomViewBuilder {
+    size: (i32, i32),
+    position: (i32, i32),
+    flags: Option<ZoomViewFlags>,
+    ex_flags: u32,
+    factor: f32,
+    follow_cursor: bool,
+    center: (i32, i32),
+    refresh_interval: u32,
+    parent: Option<ControlHandle>,
+    name: Option<String>,
+    pub(crate) state: BuilderState,
+}
+
+impl ZoomViewBuilder {
+
+    pub fn flags(mut self, flags: ZoomViewFlags) -> ZoomViewBuilder {
+        self.flags = Some(flags);
+        self
+    }
+
+    pub fn ex_flags(mut self, flags: u32) -> ZoomViewBuilder {
+        self.ex_flags = flags;
+        self
+    }
+
+    pub fn size(mut self, size: (i32, i32)) -> ZoomViewBuilder {
+        self.size = size;
+        self
+    }
+
+    pub fn position(mut self, pos: (i32, i32)) -> ZoomViewBuilder {
+        self.position = pos;
+        self
+    }
+
+    pub fn factor(mut self, factor: f32) -> ZoomViewBuilder {
+        self.factor = factor;
+        self
+    }
+
+    pub fn follow_cursor(mut self, follow: bool) -> ZoomViewBuilder {
+        self.follow_cursor = follow;
+        self
+    }
+
+    pub fn center(mut self, center: (i32, i32)) -> ZoomViewBuilder {
+        self.center = center;
+        self
+    }
+
+    pub fn refresh_interval(mut self, interval: u32) -> ZoomViewBuilder {
+        self.refresh_interval = interval;
+        self
+    }
+
+    pub fn parent<C: Into<ControlHandle>>(mut self, p: C) -> ZoomViewBuilder {
+        self.parent = Some(p.into());
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> ZoomViewBuilder {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn build(self, out: &mut ZoomView) -> Result<(), NwgError> {
+        let flags = self.state.flags(self.flags.map(|f| f.bits()).unwrap_or(out.flags()));
+
+        let parent = match self.parent {
+            Some(p) => Ok(p),
+            None => Err(NwgError::no_parent("ZoomView"))
+        }?;
+
+        init_magnification()?;
+
+        *out = Default::default();
+
+        out.handle = ControlBase::build_hwnd()
+            .name(self.name.as_deref())
+            .class_name(out.class_name())
+            .forced_flags(out.forced_flags())
+            .flags(flags)
+            .ex_flags(self.ex_flags)
+            .size(self.size)
+            .position(self.position)
+            .parent(Some(parent))
+            .build()?;
+
+        out.state = Some(Rc::new(ZoomState {
+            factor: Cell::new(1.0),
+            follow_cursor: Cell::new(self.follow_cursor),
+            center: Cell::new(self.center),
+        }));
+
+        out.set_factor(self.factor);
+        out.bind_refresh_timer(self.refresh_interval.max(1));
+
+        Ok(())
+    }
+
+}
+
+/// Initialize the magnification API. The Magnifier window class is registered by `MagInitialize`.
+fn init_magnification() -> Result<(), NwgError> {
+    static INIT: Once = Once::new();
+    static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+    INIT.call_once(|| {
+        let initialized = unsafe { MagInitialize() != 0 };
+        INITIALIZED.store(initialized, Ordering::SeqCst);
+    });
+
+    match INITIALIZED.load(Ordering::SeqCst) {
+        true => Ok(()),
+        false => Err(NwgError::control_create("Failed to initialize the magnification API"))
+    }
+}
+
+/// Compute the region of the screen to magnify. The region is kept inside the virtual screen.
+fn source_rect(hwnd: HWND, state: &ZoomState) -> RECT {
+    use winapi::um::winuser::{GetSystemMetrics, GetCursorPos, SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN};
+    use winapi::shared::windef::POINT;
+
+    let (width, height) = unsafe { wh::get_window_size(hwnd) };
+    let factor = state.factor.get();
+    let (width, height) = ((width as f32 / factor) as i32, (height as f32 / factor) as i32);
+
+    let (x, y) = match state.follow_cursor.get() {
+        true => unsafe {
+            let mut p = POINT { x: 0, y: 0 };
+            GetCursorPos(&mut p);
+            (p.x, p.y)
+        },
+        false => state.center.get()
+    };
+
+    let (screen_x, screen_y, screen_width, screen_height) = unsafe {(
+        GetSystemMetrics(SM_XVIRTUALSCREEN),
+        GetSystemMetrics(SM_YVIRTUALSCREEN),
+        GetSystemMetrics(SM_CXVIRTUALSCREEN),
+        GetSystemMetrics(SM_CYVIRTUALSCREEN),
+    )};
+
+    let left = (x - width / 2).max(screen_x).min(screen_x + screen_width - width);
+    let top = (y - height / 2).max(screen_y).min(screen_y + screen_height - height);
+
+    RECT { left, top, right: left + width, bottom: top + height }
+}
+
+/// Magnify the current source region
+fn refresh(hwnd: HWND, state: &ZoomState) {
+    use winapi::um::winuser::InvalidateRect;
+    use std::ptr;
+
+    unsafe {
+        MagSetWindowSource(hwnd, source_rect(hwnd, state));
+        InvalidateRect(hwnd, ptr::null(), 1);
+    }
+}
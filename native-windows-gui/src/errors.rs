@@ -44,6 +44,30 @@ pub enum NwgError {
     /// Error raised by one of the locale functions
     #[cfg(feature = "plotting")]
     Plotters(PlottersError),
+
+    /// Error raised when reading or writing a control property at runtime
+    #[cfg(feature = "dyn-control")]
+    PropertyError(String),
+
+    /// Error raised when a UI definition cannot be generated
+    #[cfg(feature = "codegen")]
+    CodegenError(String),
+
+    /// Error raised when the UI state cannot be saved or restored
+    #[cfg(feature = "ui-state")]
+    UiStateError(String),
+
+    /// Error raised when an event recording cannot be saved or loaded
+    #[cfg(feature = "event-recorder")]
+    EventRecorderError(String),
+
+    /// Error raised when a script cannot be compiled or fails while running
+    #[cfg(feature = "scripting")]
+    ScriptingError(String),
+
+    /// Error raised when a shortcut cannot be registered, remapped or loaded
+    #[cfg(feature = "shortcuts")]
+    ShortcutError(String),
 }
 
 impl NwgError {
@@ -87,6 +111,36 @@ impl NwgError {
         NwgError::ImageDecoderError(code, e.into())
     }
 
+    #[cfg(feature = "dyn-control")]
+    pub fn property<S: Into<String>>(e: S) -> NwgError {
+        NwgError::PropertyError(e.into())
+    }
+
+    #[cfg(feature = "codegen")]
+    pub fn codegen<S: Into<String>>(e: S) -> NwgError {
+        NwgError::CodegenError(e.into())
+    }
+
+    #[cfg(feature = "ui-state")]
+    pub fn ui_state<S: Into<String>>(e: S) -> NwgError {
+        NwgError::UiStateError(e.into())
+    }
+
+    #[cfg(feature = "event-recorder")]
+    pub fn event_recorder<S: Into<String>>(e: S) -> NwgError {
+        NwgError::EventRecorderError(e.into())
+    }
+
+    #[cfg(feature = "scripting")]
+    pub fn scripting<S: Into<String>>(e: S) -> NwgError {
+        NwgError::ScriptingError(e.into())
+    }
+
+    #[cfg(feature = "shortcuts")]
+    pub fn shortcut<S: Into<String>>(e: S) -> NwgError {
+        NwgError::ShortcutError(e.into())
+    }
+
     pub fn no_parent(name: &'static str) -> NwgError {
         NwgError::ControlCreationError(format!("No parent defined for {:?} control", name))
     }
@@ -121,6 +175,24 @@ impl fmt::Display for NwgError {
 
             #[cfg(feature = "plotting")]
             Plotters(reason) => write!(f, "Plotting canvas function failed: {}", reason),
+
+            #[cfg(feature = "dyn-control")]
+            PropertyError(reason) => write!(f, "Control property access failed: {:?}", reason),
+
+            #[cfg(feature = "codegen")]
+            CodegenError(reason) => write!(f, "UI code generation failed: {:?}", reason),
+
+            #[cfg(feature = "ui-state")]
+            UiStateError(reason) => write!(f, "UI state persistence failed: {:?}", reason),
+
+            #[cfg(feature = "event-recorder")]
+            EventRecorderError(reason) => write!(f, "Event recording failed: {:?}", reason),
+
+            #[cfg(feature = "scripting")]
+            ScriptingError(reason) => write!(f, "Script failed: {:?}", reason),
+
+            #[cfg(feature = "shortcuts")]
+            ShortcutError(reason) => write!(f, "Shortcut map failed: {:?}", reason),
         }
         
     }
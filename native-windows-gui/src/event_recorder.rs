@@ -0,0 +1,690 @@
+/*!
+    Record the events of an application and play them back, to reproduce a bug without the user.
+
+    The recorder stores the events raised by named controls, with the time elapsed since the start of the recording.
+    The recording is saved as a text file (one event per line) that can be attached to a bug report, then loaded in a
+    debug build with `EventPlayback` and dispatched again to the event handlers of the application.
+*/
+use crate::controls::ControlHandle;
+use crate::{Event, EventData, OwnedEventData, MousePressEvent, SessionEndReason, FileOperationResult, ElevationResult, AnimationTick, DpiChangedData, NwgError};
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+use std::path::Path;
+use std::fs;
+
+/// The first line of a recording. Recordings with another header are refused.
+const RECORDING_HEADER: &'static str = "nwg-events 1";
+
+
+/// An event stored by `EventRecorder` or loaded by `EventPlayback`
+#[derive(Clone, Debug)]
+pub struct RecordedEvent {
+    /// The time elapsed since the start of the recording
+    pub time: Duration,
+
+    /// The name of the control that raised the event
+    pub control: String,
+
+    /// The event
+    pub event: Event,
+
+    /// The data of the event. `None` if the event was loaded from a recording and its data cannot be restored
+    /// (ex: the device context of a paint event). Those events are skipped by the playback.
+    pub data: Option<OwnedEventData>,
+}
+
+/**
+    Records the events raised by a set of named controls. Call `record` at the start of the event handler of the application.
+
+    The controls are identified by name because their handles change every time the application is launched.
+    The events of the controls that were not added to the recorder are ignored.
+
+    Requires the `event-recorder` feature.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn setup_recorder(window: &nwg::Window, button: &nwg::Button) -> nwg::EventRecorder {
+        let recorder = nwg::EventRecorder::new();
+        recorder.add_control(window, "window")
+            .add_control(button, "button");
+
+        recorder
+    }
+
+    fn handle_event(recorder: &nwg::EventRecorder, evt: nwg::Event, evt_data: &nwg::EventData, handle: nwg::ControlHandle) {
+        recorder.record(evt, evt_data, handle);
+
+        // Handle the event ...
+    }
+    ```
+*/
+pub struct EventRecorder {
+    start: Instant,
+    recording: Cell<bool>,
+    controls: RefCell<Vec<(ControlHandle, String)>>,
+    events: RefCell<Vec<RecordedEvent>>,
+}
+
+impl EventRecorder {
+
+    /// Create a recorder. The recording starts immediately.
+    pub fn new() -> EventRecorder {
+        EventRecorder {
+            start: Instant::now(),
+            recording: Cell::new(true),
+            controls: RefCell::new(Vec::new()),
+            events: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Add a control to the recorder. The name must be the same in the application that plays the recording back.
+    pub fn add_control<C: Into<ControlHandle>>(&self, control: C, name: &str) -> &EventRecorder {
+        let handle = control.into();
+        let mut controls = self.controls.borrow_mut();
+        controls.retain(|(h, _)| *h != handle);
+        controls.push((handle, name.to_string()));
+        self
+    }
+
+    /// Remove a control from the recorder. The events already recorded are kept.
+    pub fn remove_control<C: Into<ControlHandle>>(&self, control: C) {
+        let handle = control.into();
+        self.controls.borrow_mut().retain(|(h, _)| *h != handle);
+    }
+
+    /// Pause or resume the recording
+    pub fn set_recording(&self, recording: bool) {
+        self.recording.set(recording);
+    }
+
+    /// Returns true if the recorder stores the events
+    pub fn recording(&self) -> bool {
+        self.recording.get()
+    }
+
+    /// Store an event if it was raised by one of the controls of the recorder
+    pub fn record(&self, evt: Event, data: &EventData, handle: ControlHandle) {
+        if !self.recording.get() {
+            return;
+        }
+
+        let control = match self.controls.borrow().iter().find(|(h, _)| *h == handle) {
+            Some((_, name)) => name.clone(),
+            None => { return; }
+        };
+
+        self.events.borrow_mut().push(RecordedEvent {
+            time: self.start.elapsed(),
+            control,
+            event: evt,
+            data: Some(data.to_owned()),
+        });
+    }
+
+    /// Returns a copy of the recorded events
+    pub fn events(&self) -> Vec<RecordedEvent> {
+        self.events.borrow().clone()
+    }
+
+    /// Returns the number of recorded events
+    pub fn len(&self) -> usize {
+        self.events.borrow().len()
+    }
+
+    /// Returns true if no event was recorded
+    pub fn is_empty(&self) -> bool {
+        self.events.borrow().is_empty()
+    }
+
+    /// Remove the recorded events
+    pub fn clear(&self) {
+        self.events.borrow_mut().clear();
+    }
+
+    /// Return the recording as text. See `EventPlayback::from_string`.
+    pub fn save(&self) -> String {
+        let mut out = String::from(RECORDING_HEADER);
+        out.push('\n');
+
+        for record in self.events.borrow().iter() {
+            let data = match record.data.as_ref() {
+                Some(data) => encode_data(data),
+                None => "?".to_string()
+            };
+
+            out.push_str(&format!("{}\t{}\t{:?}\t{}\n", record.time.as_millis(), escape(&record.control), record.event, data));
+        }
+
+        out
+    }
+
+    /// Save the recording in a file. The parent directory must exist.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), NwgError> {
+        let path = path.as_ref();
+        fs::write(path, self.save())
+            .map_err(|e| NwgError::event_recorder(format!("Failed to write {:?}: {}", path, e)))
+    }
+
+}
+
+impl Default for EventRecorder {
+    fn default() -> EventRecorder {
+        EventRecorder::new()
+    }
+}
+
+
+/**
+    Plays back a recording saved by `EventRecorder`. The controls must be added with the same names as in the recorder.
+
+    Events that cannot be dispatched again are skipped: the events of unknown controls, and the events with data that only
+    exists during the system message (paint, window close, tooltip text, file drop, etc).
+
+    The events are dispatched from a local message loop so the application stays responsive between two events.
+
+    Requires the `event-recorder` feature.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn replay(window: &nwg::Window, button: &nwg::Button) {
+        let mut playback = nwg::EventPlayback::from_file("bug_report.txt").unwrap();
+        playback.add_control(window, "window")
+            .add_control(button, "button");
+
+        // Dispatch the events to the handlers bound to the window, at twice the recorded speed
+        playback.play(window, 2.0);
+    }
+    ```
+*/
+pub struct EventPlayback {
+    controls: Vec<(String, ControlHandle)>,
+    events: Vec<RecordedEvent>,
+}
+
+impl EventPlayback {
+
+    /// Create a playback from the events of a recorder
+    pub fn from_recorder(recorder: &EventRecorder) -> EventPlayback {
+        EventPlayback {
+            controls: Vec::new(),
+            events: recorder.events(),
+        }
+    }
+
+    /// Load a recording returned by `EventRecorder::save`
+    pub fn from_string(recording: &str) -> Result<EventPlayback, NwgError> {
+        let mut lines = recording.lines();
+        if lines.next().map(|h| h.trim()) != Some(RECORDING_HEADER) {
+            return Err(NwgError::event_recorder("Not an event recording"));
+        }
+
+        let mut events = Vec::new();
+        for (i, line) in lines.enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let record = parse_record(line)
+                .ok_or_else(|| NwgError::event_recorder(format!("Invalid event at line {}", i + 2)))?;
+
+            events.push(record);
+        }
+
+        Ok(EventPlayback { controls: Vec::new(), events })
+    }
+
+    /// Load a recording saved by `EventRecorder::save_to_file`
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<EventPlayback, NwgError> {
+        let path = path.as_ref();
+        let recording = fs::read_to_string(path)
+            .map_err(|e| NwgError::event_recorder(format!("Failed to read {:?}: {}", path, e)))?;
+
+        EventPlayback::from_string(&recording)
+    }
+
+    /// Associate a name of the recording with a control of the application
+    pub fn add_control<C: Into<ControlHandle>>(&mut self, control: C, name: &str) -> &mut EventPlayback {
+        self.controls.retain(|(n, _)| n != name);
+        self.controls.push((name.to_string(), control.into()));
+        self
+    }
+
+    /// Returns the events of the recording
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    /**
+        Dispatch the events of the recording to the event handlers bound to `window` and its children with
+        `full_bind_event_handler` or `bind_event_handler`. Raw event handlers are not called.
+
+        `speed` multiplies the speed of the recording. With a speed of 0, the events are dispatched without waiting.
+        Returns the number of events dispatched. The playback stops if the application quits during the playback.
+
+        This function will panic if `window` is not a window handle.
+    */
+    pub fn play<W: Into<ControlHandle>>(&self, window: W, speed: f32) -> usize {
+        let window = window.into().hwnd().expect("Cannot play events on a control that is not a window");
+
+        self.play_events(speed, |evt, data, handle| {
+            for handler in crate::win32::window::bound_handlers(window) {
+                if let Some(data) = to_event_data(data) {
+                    handler(evt, data, handle);
+                }
+            }
+        })
+    }
+
+    /**
+        Dispatch the events of the recording to a callback. Use this if the application does not bind its events handler
+        with `full_bind_event_handler` or `bind_event_handler`. See `play` for the parameters.
+    */
+    pub fn play_with<F>(&self, speed: f32, f: F) -> usize
+        where F: Fn(Event, EventData, ControlHandle) -> ()
+    {
+        self.play_events(speed, |evt, data, handle| {
+            if let Some(data) = to_event_data(data) {
+                f(evt, data, handle);
+            }
+        })
+    }
+
+    fn play_events<F>(&self, speed: f32, dispatch: F) -> usize
+        where F: Fn(Event, &OwnedEventData, ControlHandle) -> ()
+    {
+        let start = Instant::now();
+        let mut count = 0;
+
+        for record in self.events.iter() {
+            let handle = match self.controls.iter().find(|(name, _)| *name == record.control) {
+                Some(&(_, handle)) => handle,
+                None => { continue; }
+            };
+
+            let data = match record.data.as_ref() {
+                Some(data) if to_event_data(data).is_some() => data,
+                _ => { continue; }
+            };
+
+            if speed > 0.0 {
+                let target = record.time.div_f32(speed);
+                if !wait_until(start + target) {
+                    break;
+                }
+            }
+
+            dispatch(record.event, data, handle);
+            count += 1;
+        }
+
+        count
+    }
+
+}
+
+
+/// Dispatch the system messages until `deadline`. Returns false if the application is quitting.
+fn wait_until(deadline: Instant) -> bool {
+    use winapi::um::winuser::{MSG, MsgWaitForMultipleObjects, PeekMessageW, TranslateMessage, DispatchMessageW, IsDialogMessageW,
+        GetAncestor, PostQuitMessage, GA_ROOT, PM_REMOVE, QS_ALLINPUT, WM_QUIT};
+    use std::{mem, ptr};
+
+    unsafe {
+        let mut msg: MSG = mem::zeroed();
+        loop {
+            while PeekMessageW(&mut msg, ptr::null_mut(), 0, 0, PM_REMOVE) != 0 {
+                if msg.message == WM_QUIT {
+                    // Let the main message loop see the quit message
+                    PostQuitMessage(msg.wParam as i32);
+                    return false;
+                }
+
+                if IsDialogMessageW(GetAncestor(msg.hwnd, GA_ROOT), &mut msg) == 0 {
+                    TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                }
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return true;
+            }
+
+            let remaining = (deadline - now).as_millis() as u32;
+            MsgWaitForMultipleObjects(0, ptr::null(), 0, remaining.max(1), QS_ALLINPUT);
+        }
+    }
+}
+
+/// Rebuild the event data of the events that can be dispatched again
+fn to_event_data(data: &OwnedEventData) -> Option<EventData> {
+    let data = match data {
+        OwnedEventData::NoData => EventData::NoData,
+        &OwnedEventData::OnSessionEnd(reason) => EventData::OnSessionEnd(reason),
+        &OwnedEventData::OnElevationResult(result) => EventData::OnElevationResult(result),
+        &OwnedEventData::OnDpiChanged(d) => EventData::OnDpiChanged(d),
+        &OwnedEventData::OnProcessExited(code) => EventData::OnProcessExited(code),
+        &OwnedEventData::OnColorPicked(color) => EventData::OnColorPicked(color),
+        &OwnedEventData::OnFileOperationComplete(result) => EventData::OnFileOperationComplete(result),
+        &OwnedEventData::OnUserIdle(elapsed) => EventData::OnUserIdle(elapsed),
+        &OwnedEventData::OnAnimationTick(tick) => EventData::OnAnimationTick(tick),
+        &OwnedEventData::OnChar(c) => EventData::OnChar(c),
+        &OwnedEventData::OnKey(key) => EventData::OnKey(key),
+        &OwnedEventData::OnMouseWheel(delta) => EventData::OnMouseWheel(delta),
+        #[cfg(feature="tree-view")]
+        OwnedEventData::OnTreeViewEndItemEdit { f_cancel, new_text } => EventData::OnTreeViewEndItemEdit { f_cancel: *f_cancel, new_text: new_text.clone() },
+        #[cfg(feature="list-view")]
+        &OwnedEventData::OnListViewItemIndex { row_index, column_index } => EventData::OnListViewItemIndex { row_index, column_index },
+        #[cfg(feature="list-view")]
+        &OwnedEventData::OnListViewItemChanged { row_index, column_index, selected } => EventData::OnListViewItemChanged { row_index, column_index, selected },
+        #[cfg(feature="shortcuts")]
+        OwnedEventData::OnShortcut(action) => EventData::OnShortcut(action.clone()),
+        _ => { return None; }
+    };
+
+    Some(data)
+}
+
+/// Encode the data of the events that can be dispatched again. The other data is saved in a readable form prefixed by `?`.
+fn encode_data(data: &OwnedEventData) -> String {
+    fn flag(value: bool) -> u8 { value as u8 }
+
+    match data {
+        OwnedEventData::NoData => String::new(),
+        OwnedEventData::OnSessionEnd(r) => format!("session_end:{},{},{}", flag(r.logoff), flag(r.critical), flag(r.close_app)),
+        OwnedEventData::OnElevationResult(ElevationResult::Launched) => "elevation:launched".to_string(),
+        OwnedEventData::OnElevationResult(ElevationResult::Cancelled) => "elevation:cancelled".to_string(),
+        OwnedEventData::OnElevationResult(ElevationResult::Failed(code)) => format!("elevation:failed:{}", code),
+        OwnedEventData::OnDpiChanged(d) => {
+            let [x, y, w, h] = d.suggested_rect;
+            format!("dpi:{},{},{},{},{}", d.dpi, x, y, w, h)
+        },
+        OwnedEventData::OnProcessExited(code) => format!("exit_code:{}", code),
+        OwnedEventData::OnColorPicked([r, g, b]) => format!("color:{},{},{}", r, g, b),
+        OwnedEventData::OnFileOperationComplete(FileOperationResult::Completed) => "file_operation:completed".to_string(),
+        OwnedEventData::OnFileOperationComplete(FileOperationResult::Aborted) => "file_operation:aborted".to_string(),
+        OwnedEventData::OnFileOperationComplete(FileOperationResult::Failed(code)) => format!("file_operation:failed:{}", code),
+        OwnedEventData::OnUserIdle(elapsed) => format!("idle:{}", elapsed.as_millis()),
+        OwnedEventData::OnAnimationTick(t) => match t.progress {
+            Some(progress) => format!("animation:{},{},{},{}", t.elapsed.as_micros(), t.delta.as_micros(), t.tick, progress),
+            None => format!("animation:{},{},{}", t.elapsed.as_micros(), t.delta.as_micros(), t.tick),
+        },
+        OwnedEventData::OnChar(c) => format!("char:{}", *c as u32),
+        OwnedEventData::OnKey(key) => format!("key:{}", key),
+        OwnedEventData::OnMouseWheel(delta) => format!("wheel:{}", delta),
+        #[cfg(feature="tree-view")]
+        OwnedEventData::OnTreeViewEndItemEdit { f_cancel, new_text } => format!("tree_edit:{}:{}", flag(*f_cancel), escape(new_text)),
+        #[cfg(feature="list-view")]
+        OwnedEventData::OnListViewItemIndex { row_index, column_index } => format!("list_view_index:{},{}", row_index, column_index),
+        #[cfg(feature="list-view")]
+        OwnedEventData::OnListViewItemChanged { row_index, column_index, selected } => format!("list_view_changed:{},{},{}", row_index, column_index, flag(*selected)),
+        #[cfg(feature="shortcuts")]
+        OwnedEventData::OnShortcut(action) => format!("shortcut:{}", escape(action)),
+        data => format!("?{}", escape(&format!("{:?}", data))),
+    }
+}
+
+/// Decode the data written by `encode_data`. Returns `Some(None)` for the data that cannot be restored.
+fn decode_data(data: &str) -> Option<Option<OwnedEventData>> {
+    fn values<T: std::str::FromStr>(values: &str) -> Option<Vec<T>> {
+        values.split(',').map(|v| v.parse().ok()).collect()
+    }
+
+    fn flag(value: u8) -> bool { value != 0 }
+
+    if data.is_empty() {
+        return Some(Some(OwnedEventData::NoData));
+    } else if data.starts_with('?') {
+        return Some(None);
+    }
+
+    let (kind, value) = match data.find(':') {
+        Some(i) => (&data[..i], &data[i+1..]),
+        None => (data, "")
+    };
+
+    let data = match kind {
+        "session_end" => match values::<u8>(value)?.as_slice() {
+            &[logoff, critical, close_app] => OwnedEventData::OnSessionEnd(SessionEndReason { logoff: flag(logoff), critical: flag(critical), close_app: flag(close_app) }),
+            _ => { return None; }
+        },
+        "elevation" => match value {
+            "launched" => OwnedEventData::OnElevationResult(ElevationResult::Launched),
+            "cancelled" => OwnedEventData::OnElevationResult(ElevationResult::Cancelled),
+            v if v.starts_with("failed:") => OwnedEventData::OnElevationResult(ElevationResult::Failed(v[7..].parse().ok()?)),
+            _ => { return None; }
+        },
+        "dpi" => match values::<i32>(value)?.as_slice() {
+            &[dpi, x, y, w, h] => OwnedEventData::OnDpiChanged(DpiChangedData { dpi: dpi as u32, suggested_rect: [x, y, w, h] }),
+            _ => { return None; }
+        },
+        "exit_code" => OwnedEventData::OnProcessExited(value.parse().ok()?),
+        "color" => match values::<u8>(value)?.as_slice() {
+            &[r, g, b] => OwnedEventData::OnColorPicked([r, g, b]),
+            _ => { return None; }
+        },
+        "file_operation" => match value {
+            "completed" => OwnedEventData::OnFileOperationComplete(FileOperationResult::Completed),
+            "aborted" => OwnedEventData::OnFileOperationComplete(FileOperationResult::Aborted),
+            v if v.starts_with("failed:") => OwnedEventData::OnFileOperationComplete(FileOperationResult::Failed(v[7..].parse().ok()?)),
+            _ => { return None; }
+        },
+        "idle" => OwnedEventData::OnUserIdle(Duration::from_millis(value.parse().ok()?)),
+        "animation" => {
+            let v: Vec<&str> = value.split(',').collect();
+            if v.len() < 3 || v.len() > 4 {
+                return None;
+            }
+
+            OwnedEventData::OnAnimationTick(AnimationTick {
+                elapsed: Duration::from_micros(v[0].parse().ok()?),
+                delta: Duration::from_micros(v[1].parse().ok()?),
+                tick: v[2].parse().ok()?,
+                progress: match v.get(3) {
+                    Some(progress) => Some(progress.parse().ok()?),
+                    None => None
+                },
+            })
+        },
+        "char" => OwnedEventData::OnChar(std::char::from_u32(value.parse().ok()?)?),
+        "key" => OwnedEventData::OnKey(value.parse().ok()?),
+        "wheel" => OwnedEventData::OnMouseWheel(value.parse().ok()?),
+        #[cfg(feature="tree-view")]
+        "tree_edit" => {
+            let (cancel, text) = value.split_at(value.find(':')?);
+            OwnedEventData::OnTreeViewEndItemEdit { f_cancel: flag(cancel.parse().ok()?), new_text: unescape(&text[1..]) }
+        },
+        #[cfg(feature="list-view")]
+        "list_view_index" => match values::<usize>(value)?.as_slice() {
+            &[row_index, column_index] => OwnedEventData::OnListViewItemIndex { row_index, column_index },
+            _ => { return None; }
+        },
+        #[cfg(feature="list-view")]
+        "list_view_changed" => match values::<usize>(value)?.as_slice() {
+            &[row_index, column_index, selected] => OwnedEventData::OnListViewItemChanged { row_index, column_index, selected: selected != 0 },
+            _ => { return None; }
+        },
+        #[cfg(feature="shortcuts")]
+        "shortcut" => OwnedEventData::OnShortcut(unescape(value)),
+
+        // Data saved by an application compiled with more features
+        _ => { return Some(None); }
+    };
+
+    Some(Some(data))
+}
+
+fn parse_record(line: &str) -> Option<RecordedEvent> {
+    let mut fields = line.splitn(4, '\t');
+    let time = Duration::from_millis(fields.next()?.parse().ok()?);
+    let control = unescape(fields.next()?);
+    let event = parse_event(fields.next()?)?;
+    let data = decode_data(fields.next().unwrap_or(""))?;
+
+    Some(RecordedEvent { time, control, event, data })
+}
+
+/// Parse the debug representation of an event
+fn parse_event(name: &str) -> Option<Event> {
+    let event = match name {
+        "OnMousePress(MousePressLeftUp)" => Event::OnMousePress(MousePressEvent::MousePressLeftUp),
+        "OnMousePress(MousePressLeftDown)" => Event::OnMousePress(MousePressEvent::MousePressLeftDown),
+        "OnMousePress(MousePressRightUp)" => Event::OnMousePress(MousePressEvent::MousePressRightUp),
+        "OnMousePress(MousePressRightDown)" => Event::OnMousePress(MousePressEvent::MousePressRightDown),
+        "Unknown" => Event::Unknown,
+        "OnMouseMove" => Event::OnMouseMove,
+        "OnMouseEnter" => Event::OnMouseEnter,
+        "OnMouseLeave" => Event::OnMouseLeave,
+        "OnMouseHover" => Event::OnMouseHover,
+        "OnMouseWheel" => Event::OnMouseWheel,
+        "OnContextMenu" => Event::OnContextMenu,
+        "OnInit" => Event::OnInit,
+        "OnPaint" => Event::OnPaint,
+        "OnChar" => Event::OnChar,
+        "OnKeyPress" => Event::OnKeyPress,
+        "OnKeyRelease" => Event::OnKeyRelease,
+        "OnSysKeyPress" => Event::OnSysKeyPress,
+        "OnSysKeyRelease" => Event::OnSysKeyRelease,
+        "OnKeyEnter" => Event::OnKeyEnter,
+        "OnKeyEsc" => Event::OnKeyEsc,
+        "OnMinMaxInfo" => Event::OnMinMaxInfo,
+        "OnResize" => Event::OnResize,
+        "OnResizeBegin" => Event::OnResizeBegin,
+        "OnResizeEnd" => Event::OnResizeEnd,
+        "OnWindowMaximize" => Event::OnWindowMaximize,
+        "OnWindowMinimize" => Event::OnWindowMinimize,
+        "OnMove" => Event::OnMove,
+        "OnVerticalScroll" => Event::OnVerticalScroll,
+        "OnHorizontalScroll" => Event::OnHorizontalScroll,
+        "OnFileDrop" => Event::OnFileDrop,
+        "OnTextDrop" => Event::OnTextDrop,
+        "OnDragEnter" => Event::OnDragEnter,
+        "OnDragOver" => Event::OnDragOver,
+        "OnDragLeave" => Event::OnDragLeave,
+        "OnButtonClick" => Event::OnButtonClick,
+        "OnButtonDoubleClick" => Event::OnButtonDoubleClick,
+        "OnSplitDropdown" => Event::OnSplitDropdown,
+        "OnCheckGroupChanged" => Event::OnCheckGroupChanged,
+        "OnLabelClick" => Event::OnLabelClick,
+        "OnLabelDoubleClick" => Event::OnLabelDoubleClick,
+        "OnImageFrameClick" => Event::OnImageFrameClick,
+        "OnImageFrameDoubleClick" => Event::OnImageFrameDoubleClick,
+        "OnTextInput" => Event::OnTextInput,
+        "OnComboBoxClosed" => Event::OnComboBoxClosed,
+        "OnComboBoxDropdown" => Event::OnComboBoxDropdown,
+        "OnComboxBoxSelection" => Event::OnComboxBoxSelection,
+        "OnDatePickerDropdown" => Event::OnDatePickerDropdown,
+        "OnDatePickerClosed" => Event::OnDatePickerClosed,
+        "OnDatePickerChanged" => Event::OnDatePickerChanged,
+        "OnDatePickerTimeChanged" => Event::OnDatePickerTimeChanged,
+        "OnDateRangeChanged" => Event::OnDateRangeChanged,
+        "OnListBoxDoubleClick" => Event::OnListBoxDoubleClick,
+        "OnListBoxSelect" => Event::OnListBoxSelect,
+        "TabsContainerChanged" => Event::TabsContainerChanged,
+        "TabsContainerChanging" => Event::TabsContainerChanging,
+        "TrackBarUpdated" => Event::TrackBarUpdated,
+        "OnWindowMinimizedToTray" => Event::OnWindowMinimizedToTray,
+        "OnWindowRestoredFromTray" => Event::OnWindowRestoredFromTray,
+        "OnMenuOpening" => Event::OnMenuOpening,
+        "OnMenuOpen" => Event::OnMenuOpen,
+        "OnMenuEnter" => Event::OnMenuEnter,
+        "OnMenuExit" => Event::OnMenuExit,
+        "OnMenuHover" => Event::OnMenuHover,
+        "OnMenuItemSelected" => Event::OnMenuItemSelected,
+        "OnMenuItemMeasure" => Event::OnMenuItemMeasure,
+        "OnMenuItemDraw" => Event::OnMenuItemDraw,
+        "OnTooltipText" => Event::OnTooltipText,
+        "OnTreeViewClick" => Event::OnTreeViewClick,
+        "OnTreeViewDoubleClick" => Event::OnTreeViewDoubleClick,
+        "OnTreeViewRightClick" => Event::OnTreeViewRightClick,
+        "OnTreeViewBeginItemEdit" => Event::OnTreeViewBeginItemEdit,
+        "OnTreeViewEndItemEdit" => Event::OnTreeViewEndItemEdit,
+        "OnTreeFocusLost" => Event::OnTreeFocusLost,
+        "OnTreeFocus" => Event::OnTreeFocus,
+        "OnTreeItemDelete" => Event::OnTreeItemDelete,
+        "OnTreeItemExpanded" => Event::OnTreeItemExpanded,
+        "OnTreeItemExpanding" => Event::OnTreeItemExpanding,
+        "OnTreeItemChanged" => Event::OnTreeItemChanged,
+        "OnTreeItemSelectionChanged" => Event::OnTreeItemSelectionChanged,
+        "OnListViewClear" => Event::OnListViewClear,
+        "OnListViewItemRemoved" => Event::OnListViewItemRemoved,
+        "OnListViewItemInsert" => Event::OnListViewItemInsert,
+        "OnListViewItemActivated" => Event::OnListViewItemActivated,
+        "OnListViewClick" => Event::OnListViewClick,
+        "OnListViewRightClick" => Event::OnListViewRightClick,
+        "OnListViewDoubleClick" => Event::OnListViewDoubleClick,
+        "OnListViewColumnClick" => Event::OnListViewColumnClick,
+        "OnListViewItemChanged" => Event::OnListViewItemChanged,
+        "OnListViewFocus" => Event::OnListViewFocus,
+        "OnListViewFocusLost" => Event::OnListViewFocusLost,
+        "OnListViewColumnReordered" => Event::OnListViewColumnReordered,
+        "OnListViewColumnResized" => Event::OnListViewColumnResized,
+        "OnListViewApproachingEnd" => Event::OnListViewApproachingEnd,
+        "OnSearchPrefixChanged" => Event::OnSearchPrefixChanged,
+        "OnTrayNotificationShow" => Event::OnTrayNotificationShow,
+        "OnTrayNotificationHide" => Event::OnTrayNotificationHide,
+        "OnTrayNotificationTimeout" => Event::OnTrayNotificationTimeout,
+        "OnTrayNotificationUserClose" => Event::OnTrayNotificationUserClose,
+        "OnTimerTick" => Event::OnTimerTick,
+        "OnTimerStop" => Event::OnTimerStop,
+        "OnAnimationEnd" => Event::OnAnimationEnd,
+        "OnNotice" => Event::OnNotice,
+        "OnWindowClose" => Event::OnWindowClose,
+        "OnSessionEnding" => Event::OnSessionEnding,
+        "OnSessionEnd" => Event::OnSessionEnd,
+        "OnPowerSuspend" => Event::OnPowerSuspend,
+        "OnPowerResume" => Event::OnPowerResume,
+        "OnSystemTimeChange" => Event::OnSystemTimeChange,
+        "OnDpiChanged" => Event::OnDpiChanged,
+        "OnNetworkConnected" => Event::OnNetworkConnected,
+        "OnNetworkDisconnected" => Event::OnNetworkDisconnected,
+        "OnNetworkAddressChanged" => Event::OnNetworkAddressChanged,
+        "OnElevationResult" => Event::OnElevationResult,
+        "OnDeviceArrival" => Event::OnDeviceArrival,
+        "OnDeviceRemoval" => Event::OnDeviceRemoval,
+        "OnRegistryChange" => Event::OnRegistryChange,
+        "OnProcessExited" => Event::OnProcessExited,
+        "OnPresentReady" => Event::OnPresentReady,
+        "OnColorPicked" => Event::OnColorPicked,
+        "OnColorPickCancelled" => Event::OnColorPickCancelled,
+        "OnFilePicked" => Event::OnFilePicked,
+        "OnFileOperationComplete" => Event::OnFileOperationComplete,
+        "OnUserIdle" => Event::OnUserIdle,
+        "OnUserReturned" => Event::OnUserReturned,
+        "OnRubberBandChanged" => Event::OnRubberBandChanged,
+        "OnRubberBandSelect" => Event::OnRubberBandSelect,
+        "OnRubberBandCancel" => Event::OnRubberBandCancel,
+        "OnViewportChanged" => Event::OnViewportChanged,
+        "OnRulerGuideMoved" => Event::OnRulerGuideMoved,
+        "OnRulerGuidesChanged" => Event::OnRulerGuidesChanged,
+        "OnDesignSelectionChanged" => Event::OnDesignSelectionChanged,
+        "OnDesignGeometryChanged" => Event::OnDesignGeometryChanged,
+        "OnPrintPreviewPageChanged" => Event::OnPrintPreviewPageChanged,
+        "OnShortcut" => Event::OnShortcut,
+        _ => { return None; }
+    };
+
+    Some(event)
+}
+
+/// Escape the characters used as separators in a recording
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('t') => out.push('\t'),
+                Some('n') => out.push('\n'),
+                Some('r') => out.push('\r'),
+                Some(c) => out.push(c),
+                None => out.push('\\'),
+            },
+            c => out.push(c)
+        }
+    }
+
+    out
+}
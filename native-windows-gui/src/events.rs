@@ -24,6 +24,15 @@ pub enum Event {
     /// Generic mouse move event that can be generated by most window controls
     OnMouseMove,
 
+    /// When the mouse enters a window control. Moving over a child control leaves its parent.
+    OnMouseEnter,
+
+    /// When the mouse leaves a window control. Also raised when the mouse moves over a child of the control.
+    OnMouseLeave,
+
+    /// When the mouse rests over a window control for its hover time. See `nwg::set_hover_time`.
+    OnMouseHover,
+
     /// Generic mouse wheel event that can be generated by most window controls
     /// Read the delta value with `EventData::OnMouseWheel` to check which key.
     OnMouseWheel,
@@ -90,15 +99,34 @@ pub enum Event {
     /// When a bar-like control value is changed.
     OnHorizontalScroll,
 
-    /// When a file is dropped into a control
+    /// When a file is dropped into a control (a window with `accept_files` or a control with a `DropTarget`)
     OnFileDrop,
 
+    /// When text is dropped into a control with a `DropTarget`. See `EventData::on_text_drop`.
+    OnTextDrop,
+
+    /// When data accepted by a `DropTarget` is dragged into its control. See `EventData::on_drag`.
+    OnDragEnter,
+
+    /// When data is dragged over a control with a `DropTarget`, or when the keys held by the user change.
+    /// The effect of a drop at the current position can be changed with `EventData::on_drag`.
+    OnDragOver,
+
+    /// When the dragged data leaves a control with a `DropTarget`, or when the drop is cancelled or refused
+    OnDragLeave,
+
     /// When a button is clicked. Similar to a MouseUp event, but only for button control
     OnButtonClick,
 
     /// When a button is clicked twice rapidly
     OnButtonDoubleClick,
 
+    /// When the drop down arrow of a `SplitButton` is clicked
+    OnSplitDropdown,
+
+    /// When the user changes the state of a `CheckBoxGroup`. Sent to the parent check box of the group.
+    OnCheckGroupChanged,
+
     /// When a label is clicked
     OnLabelClick,
 
@@ -132,6 +160,12 @@ pub enum Event {
     /// When the value of the date select is changed
     OnDatePickerChanged,
 
+    /// When the time part of the value of the date select is changed by the user
+    OnDatePickerTimeChanged,
+
+    /// When the range of a date range picker is changed by the user
+    OnDateRangeChanged,
+
     /// When an item on a list box is clicked twice
     OnListBoxDoubleClick,
 
@@ -147,6 +181,16 @@ pub enum Event {
     /// When the trackbar thumb is released by the user
     TrackBarUpdated,
 
+    /// When a window with minimize to tray enabled was minimized and hidden in the system tray
+    OnWindowMinimizedToTray,
+
+    /// When a window with minimize to tray enabled was restored by double clicking its tray icon
+    OnWindowRestoredFromTray,
+
+    /// When a menu or a popup menu is about to be displayed. The content of the menu can still be modified at this point.
+    /// The handle is a `ControlHandle::PopMenu` for popup menus and a `ControlHandle::Menu` for the other menus.
+    OnMenuOpening,
+
     /// When a menu control is opened
     OnMenuOpen,
 
@@ -162,6 +206,14 @@ pub enum Event {
     /// When the user selects on a menu item
     OnMenuItemSelected,
 
+    /// When the system needs the size of an owner drawn menu item.
+    /// The callback will also receive a `EventData::OnMenuItemMeasure`
+    OnMenuItemMeasure,
+
+    /// When an owner drawn menu item must be painted.
+    /// The callback will also receive a `EventData::OnMenuItemDraw`
+    OnMenuItemDraw,
+
     /// When the user hovers over a callback tooltip
     /// The callback will also receive a `EventData::OnTooltipText`
     OnTooltipText,
@@ -193,6 +245,10 @@ pub enum Event {
     /// When an item is expanded. Generates a `EventData::OnTreeItemDelete`
     OnTreeItemExpanded,
 
+    /// Before an item is expanded or collapsed. Generates a `EventData::OnTreeItemUpdate`
+    /// The children of an item with the "has children" hint (see `TreeView::set_item_has_children`) can be inserted here.
+    OnTreeItemExpanding,
+
     /// When the state of a tree item is changed.
     OnTreeItemChanged,
 
@@ -243,6 +299,22 @@ pub enum Event {
     /// When the control has lost the input focus
     OnListViewFocusLost,
 
+    /// When the user has dropped a column header to a new position.
+    /// Use `ListView::column_order` to read the new order.
+    OnListViewColumnReordered,
+
+    /// When the user has resized a column by dragging or double clicking a header divider
+    /// Generates an `EventData::ListViewItemIndex`. `row_index` is always 0.
+    OnListViewColumnResized,
+
+    /// When the user scrolls the list view near its last item. See `ListView::set_approaching_end_threshold`.
+    /// Raised once until items are added to the list view or removed from it.
+    OnListViewApproachingEnd,
+
+    /// When the user types in a list view or a tree view and the incremental search prefix changes.
+    /// See `ListView::search_prefix` and `TreeView::search_prefix`.
+    OnSearchPrefixChanged,
+
     /// When a TrayNotification info popup (not the tooltip) is shown 
     OnTrayNotificationShow,
 
@@ -255,17 +327,117 @@ pub enum Event {
     /// When a TrayNotification is closed due to a user click
     OnTrayNotificationUserClose,
 
-    /// When a timer delay is elapsed
+    /// When a timer delay is elapsed. An `AnimationTimer` sends the timing of the tick in a `EventData::OnAnimationTick`.
     OnTimerTick,
 
     /// When a timer end condition is reached
     OnTimerStop,
 
+    /// When the lifetime of an `AnimationTimer` is reached, just after `OnTimerStop`. Sends a `EventData::OnAnimationTick` with a progress of 1.0.
+    OnAnimationEnd,
+
     /// When a notice is... noticed
     OnNotice,
 
     /// When a user clicks on the X button of a window
     OnWindowClose,
+
+    /// When the user session is about to end (logoff, shutdown, restart). The event can be used to block the session end.
+    /// Only sent to top level windows.
+    OnSessionEnding,
+
+    /// When the user session is ending. The application will be terminated after the event, so any state must be saved here.
+    /// Only sent to top level windows.
+    OnSessionEnd,
+
+    /// When the computer is about to enter sleep or hibernation. Only sent to top level windows.
+    OnPowerSuspend,
+
+    /// When the computer resumed from sleep or hibernation. Only sent to top level windows.
+    OnPowerResume,
+
+    /// When the system time was changed. Only sent to top level windows.
+    OnSystemTimeChange,
+
+    /// When the dpi of a top level window changed, ex: the window was moved to a monitor with a different scale.
+    /// Only raised with the per-monitor dpi awareness (see `set_dpi_awareness_per_monitor`). The new dpi is passed in `EventData::OnDpiChanged`.
+    /// After the event, the window is moved to the rectangle suggested by the system, which updates the layouts of the window.
+    OnDpiChanged,
+
+    /// When the system gained a route to the internet. Sent by a `NetworkListener`.
+    OnNetworkConnected,
+
+    /// When the system lost its route to the internet. Sent by a `NetworkListener`.
+    OnNetworkDisconnected,
+
+    /// When an ip address of the system was added, removed or changed. Sent by a `NetworkListener`.
+    OnNetworkAddressChanged,
+
+    /// When the UAC prompt opened by `relaunch_elevated` was closed
+    OnElevationResult,
+
+    /// When a device or a volume was added to the system. See `DeviceNotification`.
+    OnDeviceArrival,
+
+    /// When a device or a volume was removed from the system. See `DeviceNotification`.
+    OnDeviceRemoval,
+
+    /// When a value or a sub key of a watched `RegistryKey` changed
+    OnRegistryChange,
+
+    /// When the process watched by a `ProcessWatcher` exited
+    OnProcessExited,
+
+    /// When the desktop compositor is ready for a new frame. Sent to an `ExternCanvas` with present events enabled.
+    OnPresentReady,
+
+    /// When the user picked a color with an `Eyedropper`
+    OnColorPicked,
+
+    /// When the user cancelled a pick with an `Eyedropper`
+    OnColorPickCancelled,
+
+    /// When the user picked a file in a `FilePicker`. Sent to the `frame` of the picker.
+    OnFilePicked,
+
+    /// When an operation of a `FileOperation` is done. The result is passed in `EventData::OnFileOperationComplete`
+    OnFileOperationComplete,
+
+    /// When the user did not send any input for the threshold of an `IdleWatcher`. The idle time is passed in `EventData::OnUserIdle`
+    OnUserIdle,
+
+    /// When the user sent an input after an `IdleWatcher` raised `OnUserIdle`
+    OnUserReturned,
+
+    /// When the selection rectangle of a `RubberBandSelector` changed. Raised by the canvas of the selector.
+    OnRubberBandChanged,
+
+    /// When the user released the mouse button at the end of a `RubberBandSelector` selection. Raised by the canvas of the selector.
+    OnRubberBandSelect,
+
+    /// When the user pressed escape during a `RubberBandSelector` selection. Raised by the canvas of the selector.
+    OnRubberBandCancel,
+
+    /// When the zoom or the offset of a `Viewport2D` changed. Raised by the canvas of the viewport.
+    OnViewportChanged,
+
+    /// When the user drags a guide of a `Ruler`
+    OnRulerGuideMoved,
+
+    /// When the user added, moved or removed a guide of a `Ruler`
+    OnRulerGuidesChanged,
+
+    /// When the user selected controls on a `DesignSurface`. Raised by the parent of the surface.
+    OnDesignSelectionChanged,
+
+    /// When the user moved or resized controls on a `DesignSurface`. Raised by the parent of the surface.
+    OnDesignGeometryChanged,
+
+    /// When the user displayed another page in a `PrintPreview`
+    OnPrintPreviewPageChanged,
+
+    /// When the user pressed a shortcut of a `ShortcutMap`. Raised by the window of the map. The action is passed in `EventData::OnShortcut`
+    OnShortcut,
 }
 
 
@@ -278,6 +450,36 @@ pub enum EventData {
     /// Sets if the window should be closed after the event
     OnWindowClose(WindowCloseData),
 
+    /// The reason of the session end. Can be used to block the session end.
+    OnSessionEnding(SessionEndingData),
+
+    /// The reason of the session end
+    OnSessionEnd(SessionEndReason),
+
+    /// The device that was added or removed
+    OnDeviceChange(DeviceChangeData),
+
+    /// The result of `relaunch_elevated`
+    OnElevationResult(crate::ElevationResult),
+
+    /// The new dpi of a window and the rectangle suggested by the system
+    OnDpiChanged(DpiChangedData),
+
+    /// The exit code of the process watched by a `ProcessWatcher`
+    OnProcessExited(u32),
+
+    /// The [r, g, b] color picked with an `Eyedropper`
+    OnColorPicked([u8; 3]),
+
+    /// The result of an operation of a `FileOperation`
+    OnFileOperationComplete(FileOperationResult),
+
+    /// The time since the last input of the user when an `IdleWatcher` raised `OnUserIdle`
+    OnUserIdle(std::time::Duration),
+
+    /// The timing of an `AnimationTimer` tick
+    OnAnimationTick(AnimationTick),
+
     /// Contains the default maximized position and dimensions, and the default minimum and maximum tracking sizes. 
     /// An application can override the defaults by setting the members of this event.
     OnMinMaxInfo(MinMaxInfo),
@@ -302,6 +504,20 @@ pub enum EventData {
     /// The path to one or more files that were dropped in the application
     OnFileDrop(DropFiles),
 
+    /// The text dropped into a control with a `DropTarget`
+    #[cfg(feature="drag-drop")]
+    OnTextDrop(crate::DropText),
+
+    /// The state of a drag and drop operation over a control with a `DropTarget`
+    #[cfg(feature="drag-drop")]
+    OnDrag(crate::DragData),
+
+    /// Sets the size of an owner drawn menu item
+    OnMenuItemMeasure(MenuItemMeasureData),
+
+    /// Resources used to paint an owner drawn menu item
+    OnMenuItemDraw(MenuItemDrawData),
+
     /// The handle to the item being deleted. The item is still valid.
     #[cfg(feature="tree-view")]
     OnTreeItemDelete(crate::TreeItem),
@@ -326,6 +542,14 @@ pub enum EventData {
     /// Row index, column index, and selected state of the list view item that raised the event
     #[cfg(feature="list-view")]
     OnListViewItemChanged { row_index: usize, column_index: usize, selected: bool },
+
+    /// The controls moved or resized by the user on a `DesignSurface`, with their new `[x, y, width, height]`
+    #[cfg(feature="design-surface")]
+    OnDesignGeometryChanged(Vec<(crate::ControlHandle, [i32; 4])>),
+
+    /// The action of the shortcut pressed by the user
+    #[cfg(feature="shortcuts")]
+    OnShortcut(String),
 }
 
 impl EventData {
@@ -362,7 +586,7 @@ impl EventData {
         }
     }
 
-    /// Unwraps event data into a `&DragData`. Panics if it's not the right type.
+    /// Unwraps event data into a `&DropFiles`. Panics if it's not the right type.
     pub fn on_file_drop(&self) -> &DropFiles {
         match self {
             EventData::OnFileDrop(d) => d,
@@ -370,6 +594,120 @@ impl EventData {
         }
     }
 
+    /// Unwraps event data into a `&DropText`. Panics if it's not the right type.
+    #[cfg(feature="drag-drop")]
+    pub fn on_text_drop(&self) -> &crate::DropText {
+        match self {
+            EventData::OnTextDrop(d) => d,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
+    /// Unwraps event data into a `&DragData`. Panics if it's not the right type.
+    #[cfg(feature="drag-drop")]
+    pub fn on_drag(&self) -> &crate::DragData {
+        match self {
+            EventData::OnDrag(d) => d,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
+    /// Unwraps event data into a `&SessionEndingData`. Panics if it's not the right type.
+    pub fn on_session_ending(&self) -> &SessionEndingData {
+        match self {
+            EventData::OnSessionEnding(d) => d,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
+    /// Unwraps event data into a `SessionEndReason`. Panics if it's not the right type.
+    pub fn on_session_end(&self) -> SessionEndReason {
+        match self {
+            EventData::OnSessionEnd(r) => *r,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
+    /// Unwraps event data into a `&DeviceChangeData`. Panics if it's not the right type.
+    pub fn on_device_change(&self) -> &DeviceChangeData {
+        match self {
+            EventData::OnDeviceChange(d) => d,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
+    /// Unwraps event data into a `ElevationResult`. Panics if it's not the right type.
+    pub fn on_elevation_result(&self) -> crate::ElevationResult {
+        match self {
+            EventData::OnElevationResult(r) => *r,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
+    /// Unwraps event data into a `DpiChangedData`. Panics if it's not the right type.
+    pub fn on_dpi_changed(&self) -> DpiChangedData {
+        match self {
+            EventData::OnDpiChanged(d) => *d,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
+    /// Unwraps event data into the exit code of a process. Panics if it's not the right type.
+    pub fn on_process_exited(&self) -> u32 {
+        match self {
+            EventData::OnProcessExited(code) => *code,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
+    /// Unwraps event data into the [r, g, b] color picked with an `Eyedropper`. Panics if it's not the right type.
+    pub fn on_color_picked(&self) -> [u8; 3] {
+        match self {
+            EventData::OnColorPicked(color) => *color,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
+    /// Unwraps event data into the result of a `FileOperation`. Panics if it's not the right type.
+    pub fn on_file_operation_complete(&self) -> FileOperationResult {
+        match self {
+            EventData::OnFileOperationComplete(result) => *result,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
+    /// Unwraps event data into the idle time of the user sent by an `IdleWatcher`. Panics if it's not the right type.
+    pub fn on_user_idle(&self) -> std::time::Duration {
+        match self {
+            EventData::OnUserIdle(elapsed) => *elapsed,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
+    /// Unwraps event data into the timing of an `AnimationTimer` tick. Panics if it's not the right type.
+    pub fn on_animation_tick(&self) -> AnimationTick {
+        match self {
+            EventData::OnAnimationTick(tick) => *tick,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
+    /// Unwraps event data into a `&MenuItemMeasureData`. Panics if it's not the right type.
+    pub fn on_menu_item_measure(&self) -> &MenuItemMeasureData {
+        match self {
+            EventData::OnMenuItemMeasure(d) => d,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
+    /// Unwraps event data into a `&MenuItemDrawData`. Panics if it's not the right type.
+    pub fn on_menu_item_draw(&self) -> &MenuItemDrawData {
+        match self {
+            EventData::OnMenuItemDraw(d) => d,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
     /// Unwraps event data into the virtual key code for `OnKeyPress` and `OnKeyRelease`
     pub fn on_key(&self) -> u32 {
         match self {
@@ -434,6 +772,211 @@ impl EventData {
         }
     }
 
+    /// Unwraps event data into the controls moved or resized on a `DesignSurface` and their new `[x, y, width, height]`
+    #[cfg(feature="design-surface")]
+    pub fn on_design_geometry_changed(&self) -> &[(crate::ControlHandle, [i32; 4])] {
+        match self {
+            EventData::OnDesignGeometryChanged(geometry) => geometry,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
+    /// Unwraps event data into the action of the shortcut pressed by the user. Panics if it's not the right type.
+    #[cfg(feature="shortcuts")]
+    pub fn on_shortcut(&self) -> &str {
+        match self {
+            EventData::OnShortcut(action) => action,
+            d => panic!("Wrong data type: {:?}", d)
+        }
+    }
+
+    /**
+        Copies the event data into an `OwnedEventData` that can be stored after the event handler returns.
+
+        Some variants of `EventData` point to the parameters of the system message and are only valid during the event.
+        The owned version holds a snapshot of the values that can be read from them. Setting a value back (ex: closing a window
+        or the size of a menu item) is only possible with the original data.
+    */
+    pub fn to_owned(&self) -> OwnedEventData {
+        match self {
+            EventData::NoData => OwnedEventData::NoData,
+            EventData::OnWindowClose(d) => OwnedEventData::OnWindowClose { closing: d.closing() },
+            EventData::OnSessionEnding(d) => OwnedEventData::OnSessionEnding { reason: d.reason(), blocked: d.blocked() },
+            &EventData::OnSessionEnd(reason) => OwnedEventData::OnSessionEnd(reason),
+            EventData::OnDeviceChange(d) => OwnedEventData::OnDeviceChange(DeviceChangeSnapshot {
+                interface_class: d.interface_class(),
+                path: d.path(),
+                volumes: d.volumes(),
+            }),
+            &EventData::OnElevationResult(result) => OwnedEventData::OnElevationResult(result),
+            &EventData::OnDpiChanged(d) => OwnedEventData::OnDpiChanged(d),
+            &EventData::OnProcessExited(code) => OwnedEventData::OnProcessExited(code),
+            &EventData::OnColorPicked(color) => OwnedEventData::OnColorPicked(color),
+            &EventData::OnFileOperationComplete(result) => OwnedEventData::OnFileOperationComplete(result),
+            &EventData::OnUserIdle(elapsed) => OwnedEventData::OnUserIdle(elapsed),
+            &EventData::OnAnimationTick(tick) => OwnedEventData::OnAnimationTick(tick),
+            EventData::OnMinMaxInfo(i) => OwnedEventData::OnMinMaxInfo {
+                maximized_size: i.maximized_size(),
+                maximized_pos: i.maximized_pos(),
+                max_size: i.max_size(),
+                min_size: i.min_size(),
+            },
+            EventData::OnTooltipText(_) => OwnedEventData::OnTooltipText,
+            &EventData::OnChar(c) => OwnedEventData::OnChar(c),
+            &EventData::OnKey(key) => OwnedEventData::OnKey(key),
+            EventData::OnPaint(_) => OwnedEventData::OnPaint,
+            &EventData::OnMouseWheel(delta) => OwnedEventData::OnMouseWheel(delta),
+            EventData::OnFileDrop(d) => OwnedEventData::OnFileDrop { point: d.point(), files: d.files() },
+            #[cfg(feature="drag-drop")]
+            EventData::OnTextDrop(d) => OwnedEventData::OnTextDrop { point: d.point(), keys: d.keys(), text: d.text().to_string() },
+            #[cfg(feature="drag-drop")]
+            EventData::OnDrag(d) => OwnedEventData::OnDrag {
+                point: d.point(),
+                keys: d.keys(),
+                files: d.has_files(),
+                text: d.has_text(),
+                effect: d.effect(),
+            },
+            EventData::OnMenuItemMeasure(d) => OwnedEventData::OnMenuItemMeasure { size: d.size() },
+            EventData::OnMenuItemDraw(d) => OwnedEventData::OnMenuItemDraw {
+                rect: d.rect(),
+                selected: d.selected(),
+                disabled: d.disabled(),
+                checked: d.checked(),
+            },
+            #[cfg(feature="tree-view")]
+            EventData::OnTreeItemDelete(item) => OwnedEventData::OnTreeItemDelete(item.clone()),
+            #[cfg(feature="tree-view")]
+            EventData::OnTreeItemUpdate { item, action } => OwnedEventData::OnTreeItemUpdate { item: item.clone(), action: *action },
+            #[cfg(feature="tree-view")]
+            EventData::OnTreeViewEndItemEdit { f_cancel, new_text } => OwnedEventData::OnTreeViewEndItemEdit { f_cancel: *f_cancel, new_text: new_text.clone() },
+            #[cfg(feature="tree-view")]
+            EventData::OnTreeItemSelectionChanged { old, new } => OwnedEventData::OnTreeItemSelectionChanged { old: old.clone(), new: new.clone() },
+            #[cfg(feature="list-view")]
+            &EventData::OnListViewItemIndex { row_index, column_index } => OwnedEventData::OnListViewItemIndex { row_index, column_index },
+            #[cfg(feature="list-view")]
+            &EventData::OnListViewItemChanged { row_index, column_index, selected } => OwnedEventData::OnListViewItemChanged { row_index, column_index, selected },
+            #[cfg(feature="design-surface")]
+            EventData::OnDesignGeometryChanged(geometry) => OwnedEventData::OnDesignGeometryChanged(geometry.clone()),
+            #[cfg(feature="shortcuts")]
+            EventData::OnShortcut(action) => OwnedEventData::OnShortcut(action.clone()),
+        }
+    }
+
+}
+
+
+/**
+    A copy of an `EventData` that does not depend on the system message that raised the event. Returned by `EventData::to_owned`.
+    Can be stored to record the events of an application or to handle them later.
+
+    The variants have the same names as the variants of `EventData`. The data that was only valid during the event
+    (the device context of a paint event, the text buffer of a tooltip) is not kept.
+*/
+#[derive(Clone, Debug)]
+pub enum OwnedEventData {
+    /// The event has no data
+    NoData,
+
+    /// If the window was going to be closed after the event
+    OnWindowClose { closing: bool },
+
+    /// The reason of the session end and if the session end was blocked by a handler
+    OnSessionEnding { reason: SessionEndReason, blocked: bool },
+
+    /// The reason of the session end
+    OnSessionEnd(SessionEndReason),
+
+    /// The device that was added or removed
+    OnDeviceChange(DeviceChangeSnapshot),
+
+    /// The result of `relaunch_elevated`
+    OnElevationResult(crate::ElevationResult),
+
+    /// The new dpi of a window and the rectangle suggested by the system
+    OnDpiChanged(DpiChangedData),
+
+    /// The exit code of the process watched by a `ProcessWatcher`
+    OnProcessExited(u32),
+
+    /// The [r, g, b] color picked with an `Eyedropper`
+    OnColorPicked([u8; 3]),
+
+    /// The result of an operation of a `FileOperation`
+    OnFileOperationComplete(FileOperationResult),
+
+    /// The time since the last input of the user when an `IdleWatcher` raised `OnUserIdle`
+    OnUserIdle(std::time::Duration),
+
+    /// The timing of an `AnimationTimer` tick
+    OnAnimationTick(AnimationTick),
+
+    /// The values of the `MinMaxInfo` at the end of the event
+    OnMinMaxInfo { maximized_size: [i32; 2], maximized_pos: [i32; 2], max_size: [i32; 2], min_size: [i32; 2] },
+
+    /// A tooltip text was requested
+    OnTooltipText,
+
+    /// The character entered by a user by an `OnChar` event
+    OnChar(char),
+
+    /// The windows key code entered by a user. See the `nwg::keys` module
+    OnKey(u32),
+
+    /// The control had to be painted
+    OnPaint,
+
+    /// The delta value of a mouse wheel event
+    OnMouseWheel(i32),
+
+    /// The position of the mouse pointer in the control and the path of the files that were dropped in the application
+    OnFileDrop { point: [i32; 2], files: Vec<String> },
+
+    /// The position of the mouse pointer in the control, the keys held and the text dropped
+    #[cfg(feature="drag-drop")]
+    OnTextDrop { point: [i32; 2], keys: crate::DragKeys, text: String },
+
+    /// The state of a drag and drop operation at the end of the event
+    #[cfg(feature="drag-drop")]
+    OnDrag { point: [i32; 2], keys: crate::DragKeys, files: bool, text: bool, effect: crate::DropEffect },
+
+    /// The size of an owner drawn menu item at the end of the event
+    OnMenuItemMeasure { size: (u32, u32) },
+
+    /// The area and the state of an owner drawn menu item
+    OnMenuItemDraw { rect: [i32; 4], selected: bool, disabled: bool, checked: bool },
+
+    /// The handle to the item being deleted. The item is only valid during the event.
+    #[cfg(feature="tree-view")]
+    OnTreeItemDelete(crate::TreeItem),
+
+    /// The handle to the item being changed.
+    #[cfg(feature="tree-view")]
+    OnTreeItemUpdate{ item: crate::TreeItem, action: crate::TreeItemAction },
+
+    /// When ends the editing of a treeview item's label.
+    #[cfg(feature="tree-view")]
+    OnTreeViewEndItemEdit{ f_cancel: bool, new_text: String },
+
+    /// The handles the the old item and the new item.
+    #[cfg(feature="tree-view")]
+    OnTreeItemSelectionChanged{ old: crate::TreeItem, new: crate::TreeItem },
+
+    /// Row index and column index of the list view item that raised the event
+    #[cfg(feature="list-view")]
+    OnListViewItemIndex { row_index: usize, column_index: usize },
+
+    /// Row index, column index, and selected state of the list view item that raised the event
+    #[cfg(feature="list-view")]
+    OnListViewItemChanged { row_index: usize, column_index: usize, selected: bool },
+
+    /// The controls moved or resized by the user on a `DesignSurface`, with their new `[x, y, width, height]`
+    #[cfg(feature="design-surface")]
+    OnDesignGeometryChanged(Vec<(crate::ControlHandle, [i32; 4])>),
+
+    /// The action of the shortcut pressed by the user
+    #[cfg(feature="shortcuts")]
+    OnShortcut(String),
 }
 
 //
@@ -441,9 +984,11 @@ impl EventData {
 //
 
 use winapi::um::commctrl::NMTTDISPINFOW;
-use winapi::um::winuser::{PAINTSTRUCT, MINMAXINFO, BeginPaint, EndPaint};
+use winapi::um::winuser::{PAINTSTRUCT, MINMAXINFO, MEASUREITEMSTRUCT, DRAWITEMSTRUCT, BeginPaint, EndPaint};
 use winapi::um::shellapi::{HDROP, DragFinish};
-use winapi::shared::windef::{HWND, POINT};
+use winapi::um::dbt::{DEV_BROADCAST_HDR, DEV_BROADCAST_DEVICEINTERFACE_W};
+use winapi::shared::guiddef::GUID;
+use winapi::shared::windef::{HWND, HDC, POINT};
 use std::fmt;
 
 /// A wrapper structure that sets the tooltip text on an `OnTooltipText` callback
@@ -529,6 +1074,287 @@ impl fmt::Debug for WindowCloseData {
 }
 
 
+/// The reason of a session end. If no value is set, the system is shutting down or restarting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SessionEndReason {
+    /// The user is logging off
+    pub logoff: bool,
+
+    /// The application is forced to shut down
+    pub critical: bool,
+
+    /// The application must close because it is using a file that must be replaced (ex: by an installer) or the system is being serviced
+    pub close_app: bool,
+}
+
+impl SessionEndReason {
+
+    pub(crate) fn from_flags(flags: u32) -> SessionEndReason {
+        use winapi::um::winuser::{ENDSESSION_LOGOFF, ENDSESSION_CRITICAL, ENDSESSION_CLOSEAPP};
+
+        SessionEndReason {
+            logoff: flags & ENDSESSION_LOGOFF == ENDSESSION_LOGOFF,
+            critical: flags & ENDSESSION_CRITICAL == ENDSESSION_CRITICAL,
+            close_app: flags & ENDSESSION_CLOSEAPP == ENDSESSION_CLOSEAPP,
+        }
+    }
+
+}
+
+/// Opaque type that manages if the session end should be blocked after an `OnSessionEnding` event
+pub struct SessionEndingData {
+    pub(crate) reason: SessionEndReason,
+    pub(crate) block: *mut Option<String>
+}
+
+impl SessionEndingData {
+
+    /// The reason of the session end
+    pub fn reason(&self) -> SessionEndReason {
+        self.reason
+    }
+
+    /**
+        Ask the system to not end the session. `message` is displayed to the user in the shutdown screen and should explain
+        why the application is blocking (ex: "Unsaved changes"). The system can still end the session if `reason().critical` is set.
+    */
+    pub fn block(&self, message: &str) {
+        unsafe { *self.block = Some(message.to_string()); }
+    }
+
+    /// Returns true if the session end will be blocked after the event
+    pub fn blocked(&self) -> bool {
+        unsafe { (&*self.block).is_some() }
+    }
+}
+
+impl fmt::Debug for SessionEndingData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SessionEndingData({:?}, {})", self.reason, self.blocked())
+    }
+}
+
+
+/// Opaque type over the device that raised an `OnDeviceArrival` or `OnDeviceRemoval` event
+pub struct DeviceChangeData {
+    pub(crate) data: *const DEV_BROADCAST_HDR
+}
+
+impl DeviceChangeData {
+
+    /// Return the interface class of the device. `None` if the device is not a device interface (ex: a volume).
+    pub fn interface_class(&self) -> Option<GUID> {
+        self.device_interface().map(|d| d.dbcc_classguid)
+    }
+
+    /// Return the path of the device interface (ex: `\\?\USB#VID_0403&PID_6001#...`). `None` if the device is not a device interface.
+    pub fn path(&self) -> Option<String> {
+        use crate::win32::base_helper::from_utf16;
+        use std::{mem, slice};
+
+        let device = self.device_interface()?;
+        let header_size = mem::size_of::<DEV_BROADCAST_DEVICEINTERFACE_W>() - mem::size_of::<u16>();
+        let max_length = (device.dbcc_size as usize).saturating_sub(header_size) / mem::size_of::<u16>();
+
+        unsafe {
+            let name = slice::from_raw_parts(device.dbcc_name.as_ptr(), max_length);
+            let length = name.iter().position(|&c| c == 0).unwrap_or(max_length);
+            Some(from_utf16(&name[..length]))
+        }
+    }
+
+    /// Return the drive letters of the volumes that were added or removed. Empty if the device is not a volume.
+    pub fn volumes(&self) -> Vec<char> {
+        use winapi::um::dbt::{DEV_BROADCAST_VOLUME, DBT_DEVTYP_VOLUME};
+
+        unsafe {
+            if (&*self.data).dbch_devicetype != DBT_DEVTYP_VOLUME {
+                return Vec::new();
+            }
+
+            let volume = &*(self.data as *const DEV_BROADCAST_VOLUME);
+            (0..26u8)
+                .filter(|&i| volume.dbcv_unitmask & (1 << i) != 0)
+                .map(|i| (b'A' + i) as char)
+                .collect()
+        }
+    }
+
+    fn device_interface(&self) -> Option<&DEV_BROADCAST_DEVICEINTERFACE_W> {
+        use winapi::um::dbt::DBT_DEVTYP_DEVICEINTERFACE;
+
+        unsafe {
+            match (&*self.data).dbch_devicetype == DBT_DEVTYP_DEVICEINTERFACE {
+                true => Some(&*(self.data as *const DEV_BROADCAST_DEVICEINTERFACE_W)),
+                false => None
+            }
+        }
+    }
+
+}
+
+impl fmt::Debug for DeviceChangeData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DeviceChangeData({:?}, {:?})", self.path(), self.volumes())
+    }
+}
+
+/// The values read from a `DeviceChangeData`. Used by `OwnedEventData::OnDeviceChange`.
+#[derive(Clone)]
+pub struct DeviceChangeSnapshot {
+    /// The interface class of the device. `None` if the device is not a device interface.
+    pub interface_class: Option<GUID>,
+
+    /// The path of the device interface. `None` if the device is not a device interface.
+    pub path: Option<String>,
+
+    /// The drive letters of the volumes that were added or removed. Empty if the device is not a volume.
+    pub volumes: Vec<char>,
+}
+
+impl fmt::Debug for DeviceChangeSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DeviceChangeSnapshot({:?}, {:?})", self.path, self.volumes)
+    }
+}
+
+
+/// The result of an operation of a `FileOperation`, sent with `OnFileOperationComplete`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FileOperationResult {
+    /// Every file was processed
+    Completed,
+
+    /// The user cancelled the operation or declined a confirmation. Some files may have been processed.
+    Aborted,
+
+    /// The operation failed. Contains the error code returned by the shell.
+    Failed(u32),
+}
+
+impl FileOperationResult {
+
+    pub(crate) fn from_lparam(l: isize) -> FileOperationResult {
+        match l {
+            0 => FileOperationResult::Completed,
+            -1 => FileOperationResult::Aborted,
+            code => FileOperationResult::Failed(code as u32)
+        }
+    }
+
+}
+
+
+/// The new dpi of a window, sent with `OnDpiChanged`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DpiChangedData {
+    /// The new dpi of the window. 96 is a scale of 100%.
+    pub dpi: u32,
+
+    /// The new `[x, y, width, height]` of the window suggested by the system, in physical pixels
+    pub suggested_rect: [i32; 4],
+}
+
+impl DpiChangedData {
+
+    /// The new scale of the window, ex: 1.5 for a dpi of 144
+    pub fn scale_factor(&self) -> f64 {
+        self.dpi as f64 / 96.0
+    }
+
+}
+
+/// The timing of an `AnimationTimer` tick, sent with `OnTimerTick` and `OnAnimationEnd`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AnimationTick {
+    /// The time since the timer was started
+    pub elapsed: std::time::Duration,
+
+    /// The time since the last tick handled by the application, or since the timer was started for the first tick
+    pub delta: std::time::Duration,
+
+    /// The number of ticks sent since the timer was started, including this one
+    pub tick: u64,
+
+    /// `elapsed` divided by the lifetime of the timer, between 0.0 and 1.0. `None` if the timer has no lifetime.
+    /// Use it with the functions of the `easing` module.
+    pub progress: Option<f32>,
+}
+
+
+/// A wrapper structure that sets the size of an owner drawn menu item on an `OnMenuItemMeasure` callback
+pub struct MenuItemMeasureData {
+    pub(crate) data: *mut MEASUREITEMSTRUCT
+}
+
+impl MenuItemMeasureData {
+
+    /// Return the size of the menu item as (width, height)
+    pub fn size(&self) -> (u32, u32) {
+        let data = unsafe { &*self.data };
+        (data.itemWidth, data.itemHeight)
+    }
+
+    /// Sets the size of the menu item. The width do not include the space reserved for the check mark.
+    pub fn set_size(&self, width: u32, height: u32) {
+        let data = unsafe { &mut *self.data };
+        data.itemWidth = width;
+        data.itemHeight = height;
+    }
+
+}
+
+impl fmt::Debug for MenuItemMeasureData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MenuItemMeasureData({:?})", self.size())
+    }
+}
+
+/// Opaque type over the resources used to paint an owner drawn menu item on an `OnMenuItemDraw` callback
+pub struct MenuItemDrawData {
+    pub(crate) data: *const DRAWITEMSTRUCT
+}
+
+impl MenuItemDrawData {
+
+    /// The device context to draw into
+    pub fn hdc(&self) -> HDC {
+        unsafe { (&*self.data).hDC }
+    }
+
+    /// The area of the menu item in the device context as `[left, top, right, bottom]`
+    pub fn rect(&self) -> [i32; 4] {
+        let r = unsafe { (&*self.data).rcItem };
+        [r.left, r.top, r.right, r.bottom]
+    }
+
+    /// If the menu item is highlighted (hovered by the mouse or selected with the keyboard)
+    pub fn selected(&self) -> bool {
+        use winapi::um::winuser::ODS_SELECTED;
+        unsafe { (&*self.data).itemState & ODS_SELECTED == ODS_SELECTED }
+    }
+
+    /// If the menu item is disabled
+    pub fn disabled(&self) -> bool {
+        use winapi::um::winuser::{ODS_DISABLED, ODS_GRAYED};
+        unsafe { (&*self.data).itemState & (ODS_DISABLED | ODS_GRAYED) != 0 }
+    }
+
+    /// If the menu item is checked
+    pub fn checked(&self) -> bool {
+        use winapi::um::winuser::ODS_CHECKED;
+        unsafe { (&*self.data).itemState & ODS_CHECKED == ODS_CHECKED }
+    }
+
+}
+
+impl fmt::Debug for MenuItemDrawData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MenuItemDrawData({:?})", self.rect())
+    }
+}
+
+
 /// Opaque type over a paint event's data
 #[derive(Debug)]
 pub struct PaintData {
@@ -559,6 +1385,7 @@ impl PaintData {
 /// Opaque type over one or more dragged files.
 pub struct DropFiles {
     pub(crate) drop: HDROP,
+    pub(crate) keys: u32,
 }
 
 impl DropFiles {
@@ -575,6 +1402,12 @@ impl DropFiles {
         }
     }
 
+    /// Returns the keyboard modifiers and the mouse buttons held when the files were dropped
+    #[cfg(feature="drag-drop")]
+    pub fn keys(&self) -> crate::DragKeys {
+        crate::DragKeys::from_bits_truncate(self.keys)
+    }
+
     /// Return the number of files dropped 
     pub fn len(&self) -> usize {
         use winapi::um::shellapi::DragQueryFileW;
@@ -631,6 +1464,9 @@ impl Drop for DropFiles {
 
 pub struct MinMaxInfo {
     pub(crate) inner: *mut MINMAXINFO,
+
+    /// The window that receives the message. Its dpi is used to convert the values.
+    pub(crate) window: HWND,
 }
 
 impl MinMaxInfo {
@@ -638,28 +1474,28 @@ impl MinMaxInfo {
     /// The maximized width and the maximized height of the window. For top-level windows, this value is based on the width of the primary monitor.
     pub fn set_maximized_size(&self, width: i32, height: i32) {
         let info = unsafe { &mut *self.inner };
-        let (x, y) = unsafe { crate::win32::high_dpi::logical_to_physical(width as i32, height as i32) };
+        let (x, y) = unsafe { crate::win32::high_dpi::logical_to_physical_for(self.window, width as i32, height as i32) };
         info.ptMaxSize = POINT { x, y };
     }
 
     /// Returns the maximized width and the maximized height of the window. For top-level windows, this value is based on the width of the primary monitor.
     pub fn maximized_size(&self) -> [i32; 2] {
         let info = unsafe { &mut *self.inner };
-        let (w, h) = unsafe { crate::win32::high_dpi::physical_to_logical(info.ptMaxSize.x, info.ptMaxSize.y) };
+        let (w, h) = unsafe { crate::win32::high_dpi::physical_to_logical_for(self.window, info.ptMaxSize.x, info.ptMaxSize.y) };
         [w, h]
     }
 
     /// Sets the position of the left side of the maximized window and the position of the top of the maximized window. For top-level windows, this value is based on the position of the primary monitor.
     pub fn set_maximized_pos(&self, x: i32, y: i32) {
         let info = unsafe { &mut *self.inner };
-        let (x, y) = unsafe { crate::win32::high_dpi::logical_to_physical(x, y) };
+        let (x, y) = unsafe { crate::win32::high_dpi::logical_to_physical_for(self.window, x, y) };
         info.ptMaxPosition = POINT { x, y };
     }
 
     /// Returns the position of the left side of the maximized window and the position of the top of the maximized window. For top-level windows, this value is based on the position of the primary monitor.
     pub fn maximized_pos(&self) -> [i32; 2] {
         let info = unsafe { &mut *self.inner };
-        let (x, y) = unsafe { crate::win32::high_dpi::physical_to_logical(info.ptMaxPosition.x, info.ptMaxPosition.y) };
+        let (x, y) = unsafe { crate::win32::high_dpi::physical_to_logical_for(self.window, info.ptMaxPosition.x, info.ptMaxPosition.y) };
         
         [x, y]
     }
@@ -667,28 +1503,28 @@ impl MinMaxInfo {
     /// Sets the maximum size of the window
     pub fn set_max_size(&self, width: i32, height: i32) {
         let info = unsafe { &mut *self.inner };
-        let (x, y) = unsafe { crate::win32::high_dpi::logical_to_physical(width, height) };
+        let (x, y) = unsafe { crate::win32::high_dpi::logical_to_physical_for(self.window, width, height) };
         info.ptMaxTrackSize = POINT { x, y };
     }
 
     /// Returns the maximum size of the window
     pub fn max_size(&self) -> [i32; 2] {
         let info = unsafe { &mut *self.inner };
-        let (w, h) = unsafe { crate::win32::high_dpi::physical_to_logical(info.ptMaxTrackSize.x, info.ptMaxTrackSize.y) };
+        let (w, h) = unsafe { crate::win32::high_dpi::physical_to_logical_for(self.window, info.ptMaxTrackSize.x, info.ptMaxTrackSize.y) };
         [w, h]
     }
 
     /// Sets the maximum size of the window
     pub fn set_min_size(&self, width: i32, height: i32) {
         let info = unsafe { &mut *self.inner };
-        let (x, y) = unsafe { crate::win32::high_dpi::logical_to_physical(width as i32, height as i32) };
+        let (x, y) = unsafe { crate::win32::high_dpi::logical_to_physical_for(self.window, width as i32, height as i32) };
         info.ptMinTrackSize = POINT { x, y };
     }
 
     /// Returns the minimum size of the window
     pub fn min_size(&self) -> [i32; 2] {
         let info = unsafe { &mut *self.inner };
-        let (w, h) = unsafe { crate::win32::high_dpi::physical_to_logical(info.ptMinTrackSize.x, info.ptMinTrackSize.y) };
+        let (w, h) = unsafe { crate::win32::high_dpi::physical_to_logical_for(self.window, info.ptMinTrackSize.x, info.ptMinTrackSize.y) };
         [w, h]
     }
 }
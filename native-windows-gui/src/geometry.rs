@@ -0,0 +1,385 @@
+/*!
+    Points, sizes and rectangles tagged with their unit: logical pixels or physical pixels.
+
+    The position and the size of the controls are given in logical pixels: a logical pixel is a physical pixel at 96 dpi.
+    The system works in physical pixels (ex: the mouse position, the `RECT` of the winapi functions). The types of this module
+    make the unit part of the type, so a physical position cannot be given where a logical position is expected.
+    Use `to_physical`/`to_logical` with a dpi, or `to_physical_for`/`to_logical_for` with the dpi of the monitor of a control.
+
+    The `Geometry` trait reads and changes the position and the size of the window-like controls with these types.
+
+    Without the `high-dpi` feature, the application is not dpi aware and the logical and physical pixels are the same.
+*/
+use crate::controls::ControlHandle;
+use crate::win32::{high_dpi, window_helper as wh, base_helper::check_hwnd};
+use crate::easing::lerp;
+use winapi::shared::windef::HWND;
+use std::marker::PhantomData;
+
+/// The dpi where a logical pixel is a physical pixel
+pub const DEFAULT_DPI: u32 = 96;
+
+
+/// The unit of the coordinates of a `Point`, a `Size` or a `Rect` in logical pixels
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Logical;
+
+/// The unit of the coordinates of a `Point`, a `Size` or a `Rect` in physical pixels
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Physical;
+
+/// A point in logical pixels
+pub type LogicalPoint = Point<Logical>;
+
+/// A point in physical pixels
+pub type PhysicalPoint = Point<Physical>;
+
+/// A size in logical pixels
+pub type LogicalSize = Size<Logical>;
+
+/// A size in physical pixels
+pub type PhysicalSize = Size<Physical>;
+
+/// A rectangle in logical pixels
+pub type LogicalRect = Rect<Logical>;
+
+/// A rectangle in physical pixels
+pub type PhysicalRect = Rect<Physical>;
+
+
+/// A position. See `LogicalPoint` and `PhysicalPoint`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Point<U> {
+    pub x: i32,
+    pub y: i32,
+    unit: PhantomData<U>,
+}
+
+impl<U> Point<U> {
+
+    pub fn new(x: i32, y: i32) -> Point<U> {
+        Point { x, y, unit: PhantomData }
+    }
+
+    /// Returns the point moved by `dx` and `dy`
+    pub fn offset(&self, dx: i32, dy: i32) -> Point<U> {
+        Point::new(self.x + dx, self.y + dy)
+    }
+
+    /// Interpolates between `self` and `to` with the eased progress `t`. See the `easing` module.
+    pub fn lerp(&self, to: Point<U>, t: f32) -> Point<U> {
+        Point::new(lerp(self.x, to.x, t), lerp(self.y, to.y, t))
+    }
+
+}
+
+impl Point<Logical> {
+
+    /// Converts the point to physical pixels with `dpi`
+    pub fn to_physical(&self, dpi: u32) -> Point<Physical> {
+        Point::new(scale(self.x, dpi, DEFAULT_DPI), scale(self.y, dpi, DEFAULT_DPI))
+    }
+
+    /// Converts the point to physical pixels with the dpi of `control`. See `control_dpi`.
+    pub fn to_physical_for<C: Into<ControlHandle>>(&self, control: C) -> Point<Physical> {
+        self.to_physical(control_dpi(control))
+    }
+
+}
+
+impl Point<Physical> {
+
+    /// Converts the point to logical pixels with `dpi`
+    pub fn to_logical(&self, dpi: u32) -> Point<Logical> {
+        Point::new(scale(self.x, DEFAULT_DPI, dpi), scale(self.y, DEFAULT_DPI, dpi))
+    }
+
+    /// Converts the point to logical pixels with the dpi of `control`. See `control_dpi`.
+    pub fn to_logical_for<C: Into<ControlHandle>>(&self, control: C) -> Point<Logical> {
+        self.to_logical(control_dpi(control))
+    }
+
+}
+
+impl<U> From<(i32, i32)> for Point<U> {
+    fn from(p: (i32, i32)) -> Point<U> { Point::new(p.0, p.1) }
+}
+
+impl<U> From<Point<U>> for (i32, i32) {
+    fn from(p: Point<U>) -> (i32, i32) { (p.x, p.y) }
+}
+
+
+/// A width and a height. See `LogicalSize` and `PhysicalSize`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Size<U> {
+    pub width: u32,
+    pub height: u32,
+    unit: PhantomData<U>,
+}
+
+impl<U> Size<U> {
+
+    pub fn new(width: u32, height: u32) -> Size<U> {
+        Size { width, height, unit: PhantomData }
+    }
+
+    /// Interpolates between `self` and `to` with the eased progress `t`. See the `easing` module.
+    pub fn lerp(&self, to: Size<U>, t: f32) -> Size<U> {
+        let width = lerp(self.width as i32, to.width as i32, t).max(0);
+        let height = lerp(self.height as i32, to.height as i32, t).max(0);
+        Size::new(width as u32, height as u32)
+    }
+
+}
+
+impl Size<Logical> {
+
+    /// Converts the size to physical pixels with `dpi`
+    pub fn to_physical(&self, dpi: u32) -> Size<Physical> {
+        Size::new(scale_size(self.width, dpi, DEFAULT_DPI), scale_size(self.height, dpi, DEFAULT_DPI))
+    }
+
+    /// Converts the size to physical pixels with the dpi of `control`. See `control_dpi`.
+    pub fn to_physical_for<C: Into<ControlHandle>>(&self, control: C) -> Size<Physical> {
+        self.to_physical(control_dpi(control))
+    }
+
+}
+
+impl Size<Physical> {
+
+    /// Converts the size to logical pixels with `dpi`
+    pub fn to_logical(&self, dpi: u32) -> Size<Logical> {
+        Size::new(scale_size(self.width, DEFAULT_DPI, dpi), scale_size(self.height, DEFAULT_DPI, dpi))
+    }
+
+    /// Converts the size to logical pixels with the dpi of `control`. See `control_dpi`.
+    pub fn to_logical_for<C: Into<ControlHandle>>(&self, control: C) -> Size<Logical> {
+        self.to_logical(control_dpi(control))
+    }
+
+}
+
+impl<U> From<(u32, u32)> for Size<U> {
+    fn from(s: (u32, u32)) -> Size<U> { Size::new(s.0, s.1) }
+}
+
+impl<U> From<Size<U>> for (u32, u32) {
+    fn from(s: Size<U>) -> (u32, u32) { (s.width, s.height) }
+}
+
+
+/// A position and a size. See `LogicalRect` and `PhysicalRect`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rect<U> {
+    pub origin: Point<U>,
+    pub size: Size<U>,
+}
+
+impl<U> Rect<U> {
+
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Rect<U> {
+        Rect { origin: Point::new(x, y), size: Size::new(width, height) }
+    }
+
+    /// The horizontal coordinate after the right edge of the rectangle
+    pub fn right(&self) -> i32 {
+        self.origin.x + self.size.width as i32
+    }
+
+    /// The vertical coordinate after the bottom edge of the rectangle
+    pub fn bottom(&self) -> i32 {
+        self.origin.y + self.size.height as i32
+    }
+
+    /// Returns true if `point` is inside the rectangle
+    pub fn contains(&self, point: Point<U>) -> bool {
+        point.x >= self.origin.x && point.x < self.right() && point.y >= self.origin.y && point.y < self.bottom()
+    }
+
+    /// Interpolates between `self` and `to` with the eased progress `t`. See the `easing` module.
+    pub fn lerp(&self, to: Rect<U>, t: f32) -> Rect<U> {
+        Rect { origin: self.origin.lerp(to.origin, t), size: self.size.lerp(to.size, t) }
+    }
+
+}
+
+impl Rect<Logical> {
+
+    /// Converts the rectangle to physical pixels with `dpi`
+    pub fn to_physical(&self, dpi: u32) -> Rect<Physical> {
+        Rect { origin: self.origin.to_physical(dpi), size: self.size.to_physical(dpi) }
+    }
+
+    /// Converts the rectangle to physical pixels with the dpi of `control`. See `control_dpi`.
+    pub fn to_physical_for<C: Into<ControlHandle>>(&self, control: C) -> Rect<Physical> {
+        self.to_physical(control_dpi(control))
+    }
+
+}
+
+impl Rect<Physical> {
+
+    /// Converts the rectangle to logical pixels with `dpi`
+    pub fn to_logical(&self, dpi: u32) -> Rect<Logical> {
+        Rect { origin: self.origin.to_logical(dpi), size: self.size.to_logical(dpi) }
+    }
+
+    /// Converts the rectangle to logical pixels with the dpi of `control`. See `control_dpi`.
+    pub fn to_logical_for<C: Into<ControlHandle>>(&self, control: C) -> Rect<Logical> {
+        self.to_logical(control_dpi(control))
+    }
+
+}
+
+
+const NOT_BOUND: &'static str = "Control is not yet bound to a winapi object";
+const BAD_HANDLE: &'static str = "Control handle is not HWND!";
+
+/**
+    The position and the size of a window-like control, in logical pixels or in physical pixels.
+    Implemented for every control that converts to a `ControlHandle`.
+
+    The position is in the client area of the parent of the control (or on the desktop for a top level window) and the size is the size
+    of the client area of the control. The methods panic if the control is not bound or if it is not a window-like control.
+
+    `ExternCanvas` and `Plotters` have their own `physical_size` method that returns a tuple. Use `Geometry::physical_size(&canvas)`
+    to get a `PhysicalSize`.
+
+    ```rust
+    use native_windows_gui as nwg;
+    use nwg::Geometry;
+
+    fn grow(button: &nwg::Button) {
+        let mut size = button.logical_size();
+        size.width += 20;
+        button.set_logical_size(size);
+    }
+    ```
+*/
+pub trait Geometry {
+
+    /// Returns the position of the control in logical pixels
+    fn logical_position(&self) -> LogicalPoint;
+
+    /// Returns the position of the control in physical pixels
+    fn physical_position(&self) -> PhysicalPoint;
+
+    /// Moves the control, in logical pixels
+    fn set_logical_position(&self, pos: LogicalPoint);
+
+    /// Moves the control, in physical pixels
+    fn set_physical_position(&self, pos: PhysicalPoint);
+
+    /// Returns the size of the control in logical pixels
+    fn logical_size(&self) -> LogicalSize;
+
+    /// Returns the size of the control in physical pixels
+    fn physical_size(&self) -> PhysicalSize;
+
+    /// Resizes the control, in logical pixels
+    fn set_logical_size(&self, size: LogicalSize);
+
+    /// Resizes the control, in physical pixels
+    fn set_physical_size(&self, size: PhysicalSize);
+
+    /// Returns the position and the size of the control in logical pixels
+    fn logical_rect(&self) -> LogicalRect;
+
+    /// Returns the position and the size of the control in physical pixels
+    fn physical_rect(&self) -> PhysicalRect;
+
+    /// Moves and resizes the control with a single call, in logical pixels
+    fn set_logical_rect(&self, rect: LogicalRect);
+
+    /// Moves and resizes the control with a single call, in physical pixels
+    fn set_physical_rect(&self, rect: PhysicalRect);
+
+}
+
+impl<T> Geometry for T where for<'a> &'a T: Into<ControlHandle> {
+
+    fn logical_position(&self) -> LogicalPoint {
+        self.physical_position().to_logical_for(self)
+    }
+
+    fn physical_position(&self) -> PhysicalPoint {
+        let handle = check_hwnd(&self.into(), NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_physical_position(handle).into() }
+    }
+
+    fn set_logical_position(&self, pos: LogicalPoint) {
+        self.set_physical_position(pos.to_physical_for(self));
+    }
+
+    fn set_physical_position(&self, pos: PhysicalPoint) {
+        let handle = check_hwnd(&self.into(), NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_physical_position(handle, pos.x, pos.y); }
+    }
+
+    fn logical_size(&self) -> LogicalSize {
+        self.physical_size().to_logical_for(self)
+    }
+
+    fn physical_size(&self) -> PhysicalSize {
+        let handle = check_hwnd(&self.into(), NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::get_window_physical_size(handle).into() }
+    }
+
+    fn set_logical_size(&self, size: LogicalSize) {
+        self.set_physical_size(size.to_physical_for(self));
+    }
+
+    fn set_physical_size(&self, size: PhysicalSize) {
+        let handle = check_hwnd(&self.into(), NOT_BOUND, BAD_HANDLE);
+        unsafe { wh::set_window_physical_size(handle, size.width, size.height, top_level(handle)); }
+    }
+
+    fn logical_rect(&self) -> LogicalRect {
+        self.physical_rect().to_logical_for(self)
+    }
+
+    fn physical_rect(&self) -> PhysicalRect {
+        Rect { origin: self.physical_position(), size: self.physical_size() }
+    }
+
+    fn set_logical_rect(&self, rect: LogicalRect) {
+        self.set_physical_rect(rect.to_physical_for(self));
+    }
+
+    fn set_physical_rect(&self, rect: PhysicalRect) {
+        let handle = check_hwnd(&self.into(), NOT_BOUND, BAD_HANDLE);
+        let Rect { origin, size } = rect;
+        unsafe { wh::set_window_physical_rect(handle, origin.x, origin.y, size.width, size.height, top_level(handle)); }
+    }
+
+}
+
+/// The size of a top level window is the size of its client area. See `wh::set_window_size`.
+fn top_level(handle: HWND) -> bool {
+    use winapi::um::winuser::WS_CHILD;
+    wh::get_style(handle) & WS_CHILD == 0
+}
+
+
+/**
+    Returns the dpi used to convert the coordinates of `control` between logical and physical pixels.
+    With the per-monitor dpi awareness, this is the dpi of the monitor of the control. Otherwise this is the system dpi.
+
+    Returns `DEFAULT_DPI` if the `high-dpi` feature is disabled, or if `control` is not a window-like control.
+*/
+pub fn control_dpi<C: Into<ControlHandle>>(control: C) -> u32 {
+    match (cfg!(feature = "high-dpi"), control.into().hwnd()) {
+        (true, Some(handle)) => unsafe { high_dpi::window_dpi(handle) as u32 },
+        _ => DEFAULT_DPI
+    }
+}
+
+fn scale(v: i32, num: u32, den: u32) -> i32 {
+    (v as f64 * num as f64 / den as f64).round() as i32
+}
+
+fn scale_size(v: u32, num: u32, den: u32) -> u32 {
+    (v as f64 * num as f64 / den as f64).round() as u32
+}
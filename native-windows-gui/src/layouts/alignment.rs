@@ -0,0 +1,221 @@
+/*!
+    Alignment tools over a set of controls: align the edges or the centers, distribute evenly, match the sizes and snap to a grid.
+    These are the commands of the "Format" menu of a form designer.
+
+    Every operation has two versions: one that moves the controls directly, and one that works on rectangles (`[x, y, width, height]`)
+    so a designer can preview the result or apply it to its own model. The first rectangle (or control) of the slice is the reference
+    of the alignment and of the size matching, like the primary selection of a designer.
+
+    The controls must have the same parent. Positions and sizes are in logical pixels, in the client area of the parent.
+*/
+use winapi::shared::windef::HWND;
+use crate::controls::ControlHandle;
+use crate::win32::window_helper as wh;
+use crate::NwgError;
+
+
+/// The edge or the center used by `align_rects` and `align_controls`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    /// Align the left edges
+    Left,
+
+    /// Align the right edges
+    Right,
+
+    /// Align the top edges
+    Top,
+
+    /// Align the bottom edges
+    Bottom,
+
+    /// Align the horizontal centers, so the items are stacked on the same vertical line
+    HorizontalCenter,
+
+    /// Align the vertical centers, so the items are placed on the same horizontal line
+    VerticalCenter,
+}
+
+/// The direction used by `distribute_rects` and `distribute_controls`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Distribution {
+    /// Use the same horizontal space between the items
+    Horizontal,
+
+    /// Use the same vertical space between the items
+    Vertical,
+}
+
+/// The dimensions copied by `match_rect_sizes` and `match_control_sizes`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SizeMatch {
+    Width,
+    Height,
+    Both,
+}
+
+/// Align the rectangles on the edge or the center of the first rectangle
+pub fn align_rects(rects: &mut [[i32; 4]], alignment: Alignment) {
+    let [rx, ry, rw, rh] = match rects.first() {
+        Some(&r) => r,
+        None => { return; }
+    };
+
+    for rect in rects.iter_mut().skip(1) {
+        let [x, y, w, h] = *rect;
+        *rect = match alignment {
+            Alignment::Left => [rx, y, w, h],
+            Alignment::Right => [rx + rw - w, y, w, h],
+            Alignment::Top => [x, ry, w, h],
+            Alignment::Bottom => [x, ry + rh - h, w, h],
+            Alignment::HorizontalCenter => [rx + (rw - w) / 2, y, w, h],
+            Alignment::VerticalCenter => [x, ry + (rh - h) / 2, w, h],
+        };
+    }
+}
+
+/**
+    Move the rectangles so the space between two neighbours is the same. The first and the last rectangles
+    along the direction do not move. The order of the rectangles in the slice is not changed.
+    Does nothing with less than 3 rectangles.
+*/
+pub fn distribute_rects(rects: &mut [[i32; 4]], distribution: Distribution) {
+    if rects.len() < 3 {
+        return;
+    }
+
+    let (pos, len) = match distribution {
+        Distribution::Horizontal => (0, 2),
+        Distribution::Vertical => (1, 3),
+    };
+
+    let mut order: Vec<usize> = (0..rects.len()).collect();
+    order.sort_by_key(|&i| rects[i][pos]);
+
+    let first = rects[order[0]];
+    let last = rects[order[order.len() - 1]];
+    let total: i32 = order.iter().map(|&i| rects[i][len]).sum();
+    let space = (last[pos] + last[len]) - first[pos] - total;
+    let gaps = (order.len() - 1) as i32;
+
+    let mut current = first[pos];
+    for (n, &i) in order.iter().enumerate() {
+        // The remainder of the division is spread over the first gaps so the last rectangle stays in place
+        rects[i][pos] = current;
+        let n = n as i32;
+        let gap = space.div_euclid(gaps) + if n < space.rem_euclid(gaps) { 1 } else { 0 };
+        current += rects[i][len] + gap;
+    }
+}
+
+/// Resize the rectangles to the width, the height, or both, of the first rectangle. The positions are not changed.
+pub fn match_rect_sizes(rects: &mut [[i32; 4]], size: SizeMatch) {
+    let [_, _, rw, rh] = match rects.first() {
+        Some(&r) => r,
+        None => { return; }
+    };
+
+    for rect in rects.iter_mut().skip(1) {
+        match size {
+            SizeMatch::Width => { rect[2] = rw; },
+            SizeMatch::Height => { rect[3] = rh; },
+            SizeMatch::Both => { rect[2] = rw; rect[3] = rh; },
+        }
+    }
+}
+
+/// Move the top left corner of the rectangles to the closest point of a grid. `pitch` is the size of a grid cell.
+pub fn snap_rects_to_grid(rects: &mut [[i32; 4]], pitch: (u32, u32)) {
+    for rect in rects.iter_mut() {
+        let (x, y) = snap_to_grid((rect[0], rect[1]), pitch);
+        rect[0] = x;
+        rect[1] = y;
+    }
+}
+
+/**
+    Return the closest point of a grid. `pitch` is the size of a grid cell. A pitch of 0 disables the snapping on this axis.
+    Useful to snap the position of a control while it is dragged.
+*/
+pub fn snap_to_grid(point: (i32, i32), pitch: (u32, u32)) -> (i32, i32) {
+    fn snap(value: i32, pitch: u32) -> i32 {
+        match pitch {
+            0 => value,
+            p => {
+                let p = p as i32;
+                let down = value.div_euclid(p) * p;
+                match value - down >= (p + 1) / 2 {
+                    true => down + p,
+                    false => down
+                }
+            }
+        }
+    }
+
+    (snap(point.0, pitch.0), snap(point.1, pitch.1))
+}
+
+/**
+    Align the controls on the edge or the center of the first control.
+    Returns an error if one of the controls is not a window.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn align_buttons(buttons: &[nwg::Button]) {
+        let handles: Vec<nwg::ControlHandle> = buttons.iter().map(|b| b.handle).collect();
+        nwg::align_controls(&handles, nwg::Alignment::Left).unwrap();
+        nwg::distribute_controls(&handles, nwg::Distribution::Vertical).unwrap();
+    }
+    ```
+*/
+pub fn align_controls(controls: &[ControlHandle], alignment: Alignment) -> Result<(), NwgError> {
+    update_controls(controls, |rects| align_rects(rects, alignment))
+}
+
+/// Move the controls so the space between two neighbours is the same. See `distribute_rects`.
+pub fn distribute_controls(controls: &[ControlHandle], distribution: Distribution) -> Result<(), NwgError> {
+    update_controls(controls, |rects| distribute_rects(rects, distribution))
+}
+
+/// Resize the controls to the width, the height, or both, of the first control
+pub fn match_control_sizes(controls: &[ControlHandle], size: SizeMatch) -> Result<(), NwgError> {
+    update_controls(controls, |rects| match_rect_sizes(rects, size))
+}
+
+/// Move the top left corner of the controls to the closest point of a grid. `pitch` is the size of a grid cell.
+pub fn snap_controls_to_grid(controls: &[ControlHandle], pitch: (u32, u32)) -> Result<(), NwgError> {
+    update_controls(controls, |rects| snap_rects_to_grid(rects, pitch))
+}
+
+/// Read the rectangles of the controls, update them with `f`, then move and resize the controls that changed
+fn update_controls<F: FnOnce(&mut [[i32; 4]])>(controls: &[ControlHandle], f: F) -> Result<(), NwgError> {
+    let handles = controls.iter()
+        .map(|c| c.hwnd().ok_or_else(|| NwgError::layout_create("Only windows can be aligned")))
+        .collect::<Result<Vec<HWND>, NwgError>>()?;
+
+    let old: Vec<[i32; 4]> = handles.iter()
+        .map(|&handle| unsafe {
+            let (x, y) = wh::get_window_position(handle);
+            let (w, h) = wh::get_window_size(handle);
+            [x, y, w as i32, h as i32]
+        })
+        .collect();
+
+    let mut new = old.clone();
+    f(&mut new);
+
+    for ((&handle, old), new) in handles.iter().zip(old.iter()).zip(new.iter()) {
+        unsafe {
+            if old[0..2] != new[0..2] {
+                wh::set_window_position(handle, new[0], new[1]);
+            }
+
+            if old[2..4] != new[2..4] {
+                wh::set_window_size(handle, new[2].max(0) as u32, new[3].max(0) as u32, false);
+            }
+        }
+    }
+
+    Ok(())
+}
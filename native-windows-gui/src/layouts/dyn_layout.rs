@@ -2,6 +2,7 @@ use crate::controls::ControlHandle;
 use crate::win32::window::bind_raw_event_handler_inner;
 use crate::win32::window_helper as wh;
 use crate::NwgError;
+use super::measure;
 use winapi::shared::windef::{HWND};
 use std::rc::Rc;
 use std::cell::RefCell;
@@ -78,7 +79,9 @@ impl DynLayout {
         let pos = unsafe { wh::get_window_position(hwnd) };
         let size = unsafe { wh::get_window_size(hwnd) };
 
-        let (whost, hhost) = unsafe { wh::get_window_size(self.inner.borrow_mut().base) };
+        let base = self.inner.borrow().base;
+        let (whost, hhost) = unsafe { wh::get_window_size(base) };
+        let hhost = hhost.saturating_sub(wh::get_status_bar_height(base));
 
         let xdelta = 0.01 * whost as f32;
         let ydelta = 0.01 * hhost as f32;
@@ -207,6 +210,35 @@ impl DynLayout {
         self.update_layout(w, h);
     }
 
+    /**
+        Returns the smallest size of the parent (in logical pixels) where no child crosses the right or the bottom border of the parent.
+        The children that move or grow with the parent by 100% or more are ignored. The status bar of the parent is not included.
+        See `Window::size_to_content`.
+
+        Panic:
+          - The layout must have been successfully built otherwise this function will panic.
+    */
+    pub fn preferred_size(&self) -> [u32; 2] {
+        let inner = self.inner.borrow();
+        if inner.base.is_null() {
+            panic!("Layout is not bound to a parent control.")
+        }
+
+        // The right edge of a child is at `pos + size + host * (mv + sz) / 100`
+        let fit = |pos: i32, size: i32, grow: i32| -> u32 {
+            match grow < 100 {
+                true => (((pos + size).max(0) as f32) / (1.0 - 0.01 * grow as f32)).ceil() as u32,
+                false => 0
+            }
+        };
+
+        inner.children.iter().fold([0, 0], |[w, h], item| {
+            let item_w = fit(item.pos_init.0, item.size_init.0, item.mv.0.max(0) + item.sz.0.max(0));
+            let item_h = fit(item.pos_init.1, item.size_init.1, item.mv.1.max(0) + item.sz.1.max(0));
+            [w.max(item_w), h.max(item_h)]
+        })
+    }
+
     fn update_layout(&self, width: u32, height: u32) -> () {
         use winapi::um::winuser::{BeginDeferWindowPos, DeferWindowPos, EndDeferWindowPos};
         use winapi::um::winuser::{HWND_TOP, SWP_NOZORDER, SWP_NOREPOSITION, SWP_NOACTIVATE, SWP_NOCOPYBITS};
@@ -217,6 +249,9 @@ impl DynLayout {
             return;
         }
 
+        // The children are placed above the status bar of the parent
+        let height = height.saturating_sub(wh::get_status_bar_height(inner.base));
+
         let xdelta = 0.01 * width as f32;
         let ydelta = 0.01 * height as f32;
 
@@ -327,13 +362,15 @@ impl DynLayoutBuilder {
 
         // Bind the event handler
         let event_layout = layout.clone();
-        let cb = move |_h, msg, _w, l| {
+        let cb = move |hwnd, msg, _w, l| {
             if msg == WM_SIZE {
                 let size = l as u32;
                 let width = LOWORD(size) as i32;
                 let height = HIWORD(size) as i32;
-                let (w, h) = unsafe { crate::win32::high_dpi::physical_to_logical(width, height) };
+                let (w, h) = unsafe { crate::win32::high_dpi::physical_to_logical_for(hwnd, width, height) };
                 DynLayout::update_layout(&event_layout, w as u32, h as u32);
+            } else if msg == wh::NWG_LAYOUT_MEASURE {
+                measure::merge_measure(l, event_layout.preferred_size());
             }
             None
         };
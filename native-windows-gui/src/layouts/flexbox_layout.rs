@@ -2,8 +2,10 @@ use crate::controls::ControlHandle;
 use crate::win32::window_helper as wh;
 use crate::win32::window::{RawEventHandler, unbind_raw_event_handler, bind_raw_event_handler_inner};
 use crate::NwgError;
+use super::visibility::{is_hidden, VisibilityHandlers};
+use super::measure;
 use winapi::shared::windef::HWND;
-use std::{ptr, rc::Rc, cell::{RefCell, RefMut, Ref} };
+use std::{ptr, rc::{Rc, Weak}, cell::{RefCell, RefMut, Ref} };
 
 use stretch::{
     number::Number,
@@ -18,6 +20,9 @@ pub struct FlexboxLayoutItem {
     /// The handle to the control in the item
     control: HWND,
     style: Style,
+
+    /// If the item was hidden with `FlexboxLayout::set_item_hidden`
+    hidden: bool,
 }
 
 pub enum FlexboxLayoutChild {
@@ -32,6 +37,14 @@ impl FlexboxLayoutChild {
             FlexboxLayoutChild::Flexbox(layout) => fnc(&mut layout.inner.borrow_mut().style),
         }
     }
+
+    /// Returns false if the child is an item excluded from the layout
+    fn in_layout(&self, skip_hidden: bool) -> bool {
+        match self {
+            FlexboxLayoutChild::Item(item) => !item.hidden && !(skip_hidden && is_hidden(item.control)),
+            FlexboxLayoutChild::Flexbox(_) => true,
+        }
+    }
 }
 
 /// This is the inner data shared between the callback and the application
@@ -41,6 +54,16 @@ struct FlexboxLayoutInner {
     style: Style,
     children: Vec<FlexboxLayoutChild>,
     parent_layout: Option<FlexboxLayout>,
+    auto_size: bool,
+    skip_hidden: bool,
+    visibility_handlers: VisibilityHandlers,
+}
+
+impl FlexboxLayoutInner {
+    /// Returns the children that are not excluded from the layout
+    fn layout_children(&self) -> Vec<&FlexboxLayoutChild> {
+        self.children.iter().filter(|child| child.in_layout(self.skip_hidden)).collect()
+    }
 }
 
 
@@ -48,6 +71,10 @@ struct FlexboxLayoutInner {
     A flexbox layout that organizes the children control in a parent control.
     Flexbox uses the stretch library internally ( https://github.com/vislyhq/stretch ).
 
+    With `skip_hidden`, the hidden children are excluded from the layout and the layout is updated when a child is shown or hidden.
+    A child can also be excluded with `set_item_hidden`. If the size of the children is computed automatically (see `auto_size`),
+    it is split between the children that are not excluded.
+
     FlexboxLayout requires the `flexbox` feature.
 */
 #[derive(Clone)]
@@ -64,6 +91,9 @@ impl FlexboxLayout {
             style: Default::default(),
             children: Vec::new(),
             parent_layout: None,
+            auto_size: false,
+            skip_hidden: false,
+            visibility_handlers: VisibilityHandlers::new(),
         };
 
         FlexboxLayoutBuilder { layout, current_index: None, auto_size: true, auto_spacing: Some(5) }
@@ -115,12 +145,14 @@ impl FlexboxLayout {
     
             let item = FlexboxLayoutItem {
                 control: c.into().hwnd().expect("Control must be window like (HWND handle)"),
-                style
+                style,
+                hidden: false,
             };
     
             inner.children.push(FlexboxLayoutChild::Item(item));
         }
 
+        self.bind_visibility_handlers();
         self.fit()
     }
 
@@ -133,19 +165,23 @@ impl FlexboxLayout {
         * If the layout was not initialized
     */
     pub fn remove_child<W: Into<ControlHandle>>(&self, c: W) {
-        let mut inner = self.inner.borrow_mut();
-        if inner.base.is_null() {
-            panic!("Flexbox layout is not yet initialized!");
-        }
+        {
+            let mut inner = self.inner.borrow_mut();
+            if inner.base.is_null() {
+                panic!("Flexbox layout is not yet initialized!");
+            }
 
-        let handle = c.into().hwnd().expect("Control must be window like (HWND handle)");
-        let index = inner.children.iter()
-            .position(|child| child.is_item() && child.as_item().control == handle);
+            let handle = c.into().hwnd().expect("Control must be window like (HWND handle)");
+            let index = inner.children.iter()
+                .position(|child| child.is_item() && child.as_item().control == handle);
 
-        match index {
-            Some(i) => { inner.children.remove(i); },
-            None => { panic!("Control was not found in layout"); }
+            match index {
+                Some(i) => { inner.children.remove(i); },
+                None => { panic!("Control was not found in layout"); }
+            }
         }
+
+        self.bind_visibility_handlers();
     }
 
     /**
@@ -166,6 +202,104 @@ impl FlexboxLayout {
         inner.children.iter().any(|child| child.is_item() && child.as_item().control == handle)
     }
 
+    /// Returns true if the hidden children are excluded from the layout
+    pub fn skip_hidden(&self) -> bool {
+        self.inner.borrow().skip_hidden
+    }
+
+    /**
+        Exclude the hidden children from the layout. The layout is updated when a child is shown or hidden.
+        Does not change the sublayouts.
+
+        Panic:
+        - The layout must have been successfully built otherwise this function will panic.
+    */
+    pub fn set_skip_hidden(&self, skip: bool) -> Result<(), stretch::Error> {
+        {
+            let mut inner = self.inner.borrow_mut();
+            if inner.base.is_null() {
+                panic!("Flexbox layout is not yet initialized!");
+            }
+
+            inner.skip_hidden = skip;
+        }
+
+        self.bind_visibility_handlers();
+        self.fit()
+    }
+
+    /**
+        Hide or show a child of the layout. A hidden child is excluded from the layout, even if `skip_hidden` is not set.
+        The control is hidden or shown with the item. Does not look in the sublayouts.
+
+        Panic:
+        * If the control is not a window-like control
+        * If the control is not in the layout (see `has_child`)
+        * If the layout was not initialized
+    */
+    pub fn set_item_hidden<W: Into<ControlHandle>>(&self, c: W, hidden: bool) -> Result<(), stretch::Error> {
+        let handle = c.into().hwnd().expect("Control must be window like (HWND handle)");
+
+        {
+            let mut inner = self.inner.borrow_mut();
+            if inner.base.is_null() {
+                panic!("Flexbox layout is not yet initialized!");
+            }
+
+            let item = inner.children.iter_mut()
+                .find(|child| child.is_item() && child.as_item().control == handle);
+
+            match item {
+                Some(child) => { child.as_item_mut().hidden = hidden; },
+                None => { panic!("Control was not found in layout"); }
+            }
+        }
+
+        unsafe { wh::set_window_visibility(handle, !hidden); }
+        self.fit()
+    }
+
+    /**
+        Returns true if the child was hidden with `set_item_hidden`. Returns false if the control is not in the layout.
+
+        Panic:
+        * If the control is not a window-like control
+    */
+    pub fn item_hidden<W: Into<ControlHandle>>(&self, c: W) -> bool {
+        let handle = c.into().hwnd().expect("Control must be window like (HWND handle)");
+        self.inner.borrow().children.iter()
+            .any(|child| child.is_item() && child.as_item().control == handle && child.as_item().hidden)
+    }
+
+    /// Bind a visibility handler on every item if `skip_hidden` is set. Remove the old handlers.
+    fn bind_visibility_handlers(&self) {
+        let mut inner = self.inner.borrow_mut();
+        inner.visibility_handlers.unbind_all();
+
+        if !inner.skip_hidden {
+            return;
+        }
+
+        let controls: Vec<HWND> = inner.children.iter()
+            .filter(|child| child.is_item())
+            .map(|child| child.as_item().control)
+            .collect();
+
+        for control in controls {
+            let layout = Rc::downgrade(&self.inner);
+            inner.visibility_handlers.bind(control, move || FlexboxLayout::visibility_changed(&layout));
+        }
+    }
+
+    fn visibility_changed(layout: &Weak<RefCell<FlexboxLayoutInner>>) {
+        if let Some(inner) = layout.upgrade() {
+            let layout = FlexboxLayout { inner };
+            if layout.inner.try_borrow_mut().is_ok() {
+                layout.fit().ok();
+            }
+        }
+    }
+
     /**
         Borrow the inner value of the flexbox layout. While the returned value lives, calling other method
         of the the flexbox layout that modify the inner state will cause a panic. Simple looktup (ex: `has_child`) will still work.
@@ -225,21 +359,96 @@ impl FlexboxLayout {
         }
     }
 
+    /**
+        Returns the smallest size of the parent (in logical pixels) where every visible child gets at least its preferred size:
+        the size of the content of the buttons, the labels and the text inputs, or the current size of the other controls.
+        The children with a size in points keep that size. The status bar of the parent is not included. See `Window::size_to_content`.
+
+        Panic:
+        - The layout must have been successfully built otherwise this function will panic.
+    */
+    pub fn preferred_size(&self) -> Result<[u32; 2], stretch::Error> {
+        let inner = self.inner.borrow();
+        if inner.base.is_null() {
+            panic!("FlexboxLayout is not bound to a parent control.")
+        }
+
+        let mut stretch = Stretch::new();
+        let nodes = FlexboxLayout::build_measure_nodes(&inner, &mut stretch)?;
+
+        let mut style = inner.style.clone();
+        style.size = Size { width: Dimension::Auto, height: Dimension::Auto };
+        let node = stretch.new_node(style, nodes)?;
+
+        stretch.compute_layout(node, Size::undefined())?;
+
+        let Size { width, height } = stretch.layout(node)?.size;
+        Ok([width.ceil() as u32, height.ceil() as u32])
+    }
+
+    // Utility function to compile tree of children nodes with the preferred size of the children.
+    // The sizes in points are kept, the other sizes are replaced by the preferred size of the control or computed from the children layouts.
+    fn build_measure_nodes(layout: &FlexboxLayoutInner, stretch: &mut Stretch) -> Result<Vec<Node>, stretch::Error> {
+        let mut nodes = Vec::new();
+
+        for child in layout.layout_children() {
+            match child {
+                FlexboxLayoutChild::Item(child) => {
+                    let [w, h] = measure::preferred_size(child.control);
+                    let mut style = child.style;
+                    style.size = Size {
+                        width: points_or(style.size.width, Dimension::Points(w as f32)),
+                        height: points_or(style.size.height, Dimension::Points(h as f32)),
+                    };
+
+                    nodes.push(stretch.new_node(style, Vec::new())?);
+                },
+                FlexboxLayoutChild::Flexbox(child) => {
+                    let child_inner = child.inner.borrow();
+                    let mut style = child_inner.style;
+                    style.size = Size {
+                        width: points_or(style.size.width, Dimension::Auto),
+                        height: points_or(style.size.height, Dimension::Auto),
+                    };
+
+                    let child_nodes = FlexboxLayout::build_measure_nodes(&child_inner, stretch)?;
+                    nodes.push(stretch.new_node(style, child_nodes)?);
+                },
+            };
+        }
+
+        Ok(nodes)
+    }
+
     // Utility function to compile tree of children nodes for layout purposes
     // Also returns the total number of children items to allow cleaner deferred positioning
-    fn build_child_nodes(children: &Vec<FlexboxLayoutChild>, stretch: &mut Stretch) -> Result<(usize, Vec<Node>), stretch::Error> {
+    // Excluded children are skipped. If the children size is automatic, it is split between the remaining children.
+    fn build_child_nodes(layout: &FlexboxLayoutInner, stretch: &mut Stretch) -> Result<(usize, Vec<Node>), stretch::Error> {
         let mut nodes = Vec::new();
         let mut item_count = 0;
 
-        for child in children.iter() {
+        let children = layout.layout_children();
+        let auto_size = match layout.auto_size && children.len() < layout.children.len() {
+            true => Some(auto_child_size(&layout.style, children.len())),
+            false => None
+        };
+
+        for child in children {
             match child {
                 FlexboxLayoutChild::Item(child) =>{
-                    nodes.push(stretch.new_node(child.style, Vec::new())?);
+                    let mut style = child.style;
+                    if let Some(size) = auto_size { style.size = size; }
+
+                    nodes.push(stretch.new_node(style, Vec::new())?);
                     item_count += 1;
                 },
                 FlexboxLayoutChild::Flexbox(child) => {
-                    let (child_count, child_nodes) = FlexboxLayout::build_child_nodes(child.children().children(), stretch)?;
-                    nodes.push(stretch.new_node(child.style(), child_nodes)?);
+                    let child_inner = child.inner.borrow();
+                    let mut style = child_inner.style;
+                    if let Some(size) = auto_size { style.size = size; }
+
+                    let (child_count, child_nodes) = FlexboxLayout::build_child_nodes(&child_inner, stretch)?;
+                    nodes.push(stretch.new_node(style, child_nodes)?);
                     item_count += child_count;
                 },
             };
@@ -250,10 +459,10 @@ impl FlexboxLayout {
 
     // Applies the calculated item positions for this layout
     // Uses deferred window positioning to prevent rendering artefacts
-    fn apply_layout_deferred(positioner: &mut wh::DeferredWindowPositioner, stretch: &mut Stretch, nodes: Vec<Node>, children: &Vec<FlexboxLayoutChild>, last_handle: &mut Option<HWND>, offset: (i32, i32)) -> Result<(), stretch::Error> {
+    fn apply_layout_deferred(positioner: &mut wh::DeferredWindowPositioner, stretch: &mut Stretch, nodes: Vec<Node>, layout: &FlexboxLayoutInner, last_handle: &mut Option<HWND>, offset: (i32, i32)) -> Result<(), stretch::Error> {
         use FlexboxLayoutChild as Child;
 
-        for (node, child) in nodes.into_iter().zip(children.iter()) {
+        for (node, child) in nodes.into_iter().zip(layout.layout_children()) {
             let layout = stretch.layout(node)?;
             let Point { x, y } = layout.location;
             let Size { width, height } = layout.size;
@@ -265,7 +474,7 @@ impl FlexboxLayout {
                 },
                 Child::Flexbox(child) => {
                     let children_nodes = stretch.children(node)?;
-                    FlexboxLayout::apply_layout_deferred(positioner, stretch, children_nodes, child.children().children(), last_handle, (x as i32, y as i32))?;
+                    FlexboxLayout::apply_layout_deferred(positioner, stretch, children_nodes, &child.inner.borrow(), last_handle, (x as i32, y as i32))?;
                 }
             }
             
@@ -276,10 +485,10 @@ impl FlexboxLayout {
 
     // Applies the calculated item positions for this layout
     // Uses immediate window positioning, which might cause visual artefacts in some cases
-    fn apply_layout_immediate(stretch: &mut Stretch, nodes: Vec<Node>, children: &Vec<FlexboxLayoutChild>, last_handle: &mut Option<HWND>, offset: (i32, i32)) -> Result<(), stretch::Error> {
+    fn apply_layout_immediate(stretch: &mut Stretch, nodes: Vec<Node>, layout: &FlexboxLayoutInner, last_handle: &mut Option<HWND>, offset: (i32, i32)) -> Result<(), stretch::Error> {
         use FlexboxLayoutChild as Child;
 
-        for (node, child) in nodes.into_iter().zip(children.iter()) {
+        for (node, child) in nodes.into_iter().zip(layout.layout_children()) {
             let layout = stretch.layout(node)?;
             let Point { x, y } = layout.location;
             let Size { width, height } = layout.size;
@@ -293,7 +502,7 @@ impl FlexboxLayout {
                 },
                 Child::Flexbox(child) => {
                     let children_nodes = stretch.children(node)?;
-                    FlexboxLayout::apply_layout_immediate(stretch, children_nodes, child.children().children(), last_handle, (x as i32, y as i32))?;
+                    FlexboxLayout::apply_layout_immediate(stretch, children_nodes, &child.inner.borrow(), last_handle, (x as i32, y as i32))?;
                 }
             }
             
@@ -308,8 +517,11 @@ impl FlexboxLayout {
             return Ok(());
         }
 
+        // The children are placed above the status bar of the parent
+        let height = height.saturating_sub(wh::get_status_bar_height(inner.base));
+
         let mut stretch = Stretch::new();
-        let (item_count, nodes) = FlexboxLayout::build_child_nodes(&inner.children, &mut stretch)?;
+        let (item_count, nodes) = FlexboxLayout::build_child_nodes(&inner, &mut stretch)?;
 
         let mut style = inner.style.clone();
         style.size = Size { width: Dimension::Points(width as f32), height: Dimension::Points(height as f32) };
@@ -319,13 +531,13 @@ impl FlexboxLayout {
 
         // Keep a fallback case to prevent panics if the layout is too large to be deferred
         if let Ok(mut positioner) = wh::DeferredWindowPositioner::new(item_count as i32) {
-            let layout_result = FlexboxLayout::apply_layout_deferred(&mut positioner, &mut stretch, nodes, self.children().children(), &mut None, offset);
+            let layout_result = FlexboxLayout::apply_layout_deferred(&mut positioner, &mut stretch, nodes, &inner, &mut None, offset);
             positioner.end();
     
             layout_result
         }
         else {
-            FlexboxLayout::apply_layout_immediate(&mut stretch, nodes, self.children().children(), &mut None, offset)
+            FlexboxLayout::apply_layout_immediate(&mut stretch, nodes, &inner, &mut None, offset)
         }
     }
 }
@@ -352,7 +564,8 @@ impl FlexboxLayoutBuilder {
         
         let item = FlexboxLayoutItem {
             control: child.into().hwnd().unwrap(),
-            style: Style::default()
+            style: Style::default(),
+            hidden: false,
         };
 
         self.layout.children.push(FlexboxLayoutChild::Item(item));
@@ -376,6 +589,12 @@ impl FlexboxLayoutBuilder {
         self
     }
 
+    /// Exclude the hidden children from the layout
+    pub fn skip_hidden(mut self, skip: bool) -> FlexboxLayoutBuilder {
+        self.layout.skip_hidden = skip;
+        self
+    }
+
     /// Automatically generate padding and margin for the parent layout and the children from the selected value.
     /// This flags is erased when `padding` is called on the layout or when `child_margin` is called on the children
     pub fn auto_spacing(mut self, auto: Option<u32>) -> FlexboxLayoutBuilder {
@@ -550,19 +769,10 @@ impl FlexboxLayoutBuilder {
         let base_handle = ControlHandle::Hwnd(self.layout.base);
 
         // Auto compute size if enabled
+        self.layout.auto_size = self.auto_size;
         if self.auto_size {
-            let children_count = self.layout.children.len();
-            let size = 1.0f32 / (children_count as f32);
+            let child_size = auto_child_size(&self.layout.style, self.layout.children.len());
             for child in self.layout.children.iter_mut() {
-                let child_size = match &self.layout.style.flex_direction {
-                    FlexDirection::Row | FlexDirection::RowReverse => {
-                        Size { width: Dimension::Percent(size), height: Dimension::Auto }
-                    },
-                    FlexDirection::Column | FlexDirection::ColumnReverse => {
-                        Size { width: Dimension::Auto, height: Dimension::Percent(size) }
-                    }
-                };
-
                 child.modify_style(|s| s.size = child_size);
             }
         }
@@ -583,10 +793,14 @@ impl FlexboxLayoutBuilder {
             if layout_inner.handler.is_some() {
                 drop(unbind_raw_event_handler(layout_inner.handler.as_ref().unwrap()));
             }
+
+            layout_inner.visibility_handlers.unbind_all();
             
             *layout_inner = self.layout;        
         }
 
+        layout.bind_visibility_handlers();
+
         // Sets the parent_layout of any child layout to this layout
         for child in layout.inner.borrow_mut().children.iter_mut() {
             match child {
@@ -607,13 +821,17 @@ impl FlexboxLayoutBuilder {
  
         // Bind the event handler
         let event_layout = layout.clone();
-        let cb = move |_h, msg, _w, l| {
+        let cb = move |hwnd, msg, _w, l| {
             if msg == WM_SIZE {
                 let size = l as u32;
                 let width = LOWORD(size) as i32;
                 let height = HIWORD(size) as i32;
-                let (w, h) = unsafe { crate::win32::high_dpi::physical_to_logical(width, height) };
+                let (w, h) = unsafe { crate::win32::high_dpi::physical_to_logical_for(hwnd, width, height) };
                 FlexboxLayout::update_layout(&event_layout, w as u32, h as u32, (0, 0)).expect("Failed to compute layout!");
+            } else if msg == wh::NWG_LAYOUT_MEASURE {
+                if let Ok(size) = event_layout.preferred_size() {
+                    measure::merge_measure(l, size);
+                }
             }
             None
         };
@@ -633,19 +851,10 @@ impl FlexboxLayoutBuilder {
         }
 
         // Auto compute size if enabled
+        self.layout.auto_size = self.auto_size;
         if self.auto_size {
-            let children_count = self.layout.children.len();
-            let size = 1.0f32 / (children_count as f32);
-            for child in self.layout.children.iter_mut() {               
-                let child_size = match &self.layout.style.flex_direction {
-                    FlexDirection::Row | FlexDirection::RowReverse => {
-                        Size { width: Dimension::Percent(size), height: Dimension::Auto }
-                    },
-                    FlexDirection::Column | FlexDirection::ColumnReverse => {
-                        Size { width: Dimension::Auto, height: Dimension::Percent(size) }
-                    }
-                };
-
+            let child_size = auto_child_size(&self.layout.style, self.layout.children.len());
+            for child in self.layout.children.iter_mut() {
                 child.modify_style(|s| s.size = child_size);
             }
         }
@@ -666,14 +875,39 @@ impl FlexboxLayoutBuilder {
             if layout_inner.handler.is_some() {
                 drop(unbind_raw_event_handler(layout_inner.handler.as_ref().unwrap()));
             }
+
+            layout_inner.visibility_handlers.unbind_all();
             
             *layout_inner = self.layout;        
         }
 
+        layout.bind_visibility_handlers();
+
         Ok(())
     }
 }
 
+/// Returns `size` if it is a size in points, or `default` otherwise
+fn points_or(size: Dimension, default: Dimension) -> Dimension {
+    match size {
+        Dimension::Points(_) => size,
+        _ => default
+    }
+}
+
+/// The size of the children of a layout with `auto_size`. The children share the layout equally along its direction.
+fn auto_child_size(style: &Style, children_count: usize) -> Size<Dimension> {
+    let size = 1.0f32 / (children_count as f32);
+    match &style.flex_direction {
+        FlexDirection::Row | FlexDirection::RowReverse => {
+            Size { width: Dimension::Percent(size), height: Dimension::Auto }
+        },
+        FlexDirection::Column | FlexDirection::ColumnReverse => {
+            Size { width: Dimension::Auto, height: Dimension::Percent(size) }
+        }
+    }
+}
+
 impl Default for FlexboxLayout {
 
     fn default() -> FlexboxLayout {
@@ -683,6 +917,9 @@ impl Default for FlexboxLayout {
             children: Vec::new(),
             style: Default::default(),
             parent_layout: None,
+            auto_size: false,
+            skip_hidden: false,
+            visibility_handlers: VisibilityHandlers::new(),
         };
 
         FlexboxLayout {
@@ -748,4 +985,38 @@ impl<'a> FlexboxLayoutChildren<'a> {
     pub fn children<'b>(&'b self) -> &'b Vec<FlexboxLayoutChild> {
         &self.inner.children
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(hidden: bool) -> FlexboxLayoutChild {
+        FlexboxLayoutChild::Item(FlexboxLayoutItem { control: ptr::null_mut(), style: Default::default(), hidden })
+    }
+
+    fn in_layout(children: Vec<FlexboxLayoutChild>) -> Vec<bool> {
+        let layout = FlexboxLayout::default();
+        layout.inner.borrow_mut().children = children;
+
+        let inner = layout.inner.borrow();
+        let kept = inner.layout_children();
+        inner.children.iter().map(|c| kept.iter().any(|&k| ptr::eq(k, c))).collect()
+    }
+
+    #[test]
+    fn all_hidden_items_are_excluded() {
+        assert_eq!(in_layout(vec![item(true), item(true)]), vec![false, false]);
+    }
+
+    #[test]
+    fn hidden_first_and_last_items_are_excluded() {
+        assert_eq!(in_layout(vec![item(true), item(false), item(false), item(true)]), vec![false, true, true, false]);
+    }
+
+    #[test]
+    fn nested_layouts_are_kept() {
+        let nested = FlexboxLayoutChild::Flexbox(FlexboxLayout::default());
+        assert_eq!(in_layout(vec![item(true), nested]), vec![false, true]);
+    }
+}
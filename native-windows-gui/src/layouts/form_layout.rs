@@ -0,0 +1,246 @@
+use winapi::shared::windef::HWND;
+use crate::controls::ControlHandle;
+use crate::win32::window_helper as wh;
+use crate::NwgError;
+use super::grid_layout::{GridLayout, GridLayoutItem};
+
+
+/// A row of a `FormLayout`
+enum FormRow {
+    /// A label and its control. The label is placed in the label columns, the control in the field columns.
+    Field(HWND, HWND),
+
+    /// A control that spans the whole width of the form
+    Full(HWND),
+}
+
+/**
+A layout for the forms of the settings dialogs: a column of labels on the left, a column of controls on the right,
+and an optional row of buttons at the bottom. `FormLayout` builds a `GridLayout` from the rows of the form.
+
+  * The labels are aligned on the right, next to their control. Only the `Label` controls are aligned.
+  * The rows have the same height (`row_height`, in logical pixels) and are placed from the top of the parent.
+    The DPI scaling is handled like in the other layouts.
+  * The buttons share the width of the bottom row.
+  * The tab order of the controls follows the order of the rows: each label and its control, then the buttons.
+
+**Builder parameters:**
+  * `parent`:        **Required.** The control that holds the form
+  * `row`:           Add a row with a label and a control
+  * `full_row`:      Add a row with a control that spans the whole width of the form (ex: a check box)
+  * `button`:        Add a button to the button row at the bottom of the form
+  * `label_columns`: The width of the labels, relative to `field_columns` (default: 1)
+  * `field_columns`: The width of the controls, relative to `label_columns` (default: 2)
+  * `row_height`:    The height of the rows. `None` stretches the rows to fill the parent (default: Some(25))
+  * `margin`:        The top, right, bottom, left margins of the form (default: [5, 5, 5, 5])
+  * `spacing`:       The spacing between the controls (default: 3)
+  * `align_labels`:  Align the labels on the right (default: true)
+
+```rust
+use native_windows_gui as nwg;
+
+fn layout(form: &nwg::FormLayout, window: &nwg::Window, name_label: &nwg::Label, name: &nwg::TextInput, ok: &nwg::Button, cancel: &nwg::Button) {
+    nwg::FormLayout::builder()
+        .parent(window)
+        .row(name_label, name)
+        .button(ok)
+        .button(cancel)
+        .build(form)
+        .expect("Failed to build the form");
+}
+```
+*/
+#[derive(Clone, Default)]
+pub struct FormLayout {
+    grid: GridLayout,
+}
+
+impl FormLayout {
+
+    pub fn builder() -> FormLayoutBuilder {
+        FormLayoutBuilder {
+            parent: None,
+            rows: Vec::new(),
+            buttons: Vec::new(),
+            label_columns: 1,
+            field_columns: 2,
+            row_height: Some(25),
+            margins: [5, 5, 5, 5],
+            spacing: 3,
+            align_labels: true,
+        }
+    }
+
+    /// Returns the grid layout that places the controls of the form. Use it to change the form after it was built.
+    pub fn grid(&self) -> &GridLayout {
+        &self.grid
+    }
+
+    /// Resize the form to fit the parent. Call this after the form or its controls were changed.
+    pub fn fit(&self) {
+        self.grid.fit();
+    }
+
+}
+
+
+/// Builder for a `FormLayout` struct
+pub struct FormLayoutBuilder {
+    parent: Option<HWND>,
+    rows: Vec<FormRow>,
+    buttons: Vec<HWND>,
+    label_columns: u32,
+    field_columns: u32,
+    row_height: Option<u32>,
+    margins: [u32; 4],
+    spacing: u32,
+    align_labels: bool,
+}
+
+impl FormLayoutBuilder {
+
+    /// Set the control that holds the form
+    pub fn parent<W: Into<ControlHandle>>(mut self, p: W) -> FormLayoutBuilder {
+        self.parent = Some(p.into().hwnd().expect("Parent must be HWND"));
+        self
+    }
+
+    /// Add a row with a label and a control
+    pub fn row<L: Into<ControlHandle>, W: Into<ControlHandle>>(mut self, label: L, control: W) -> FormLayoutBuilder {
+        let label = label.into().hwnd().expect("Child must be a window-like control (HWND handle)");
+        let control = control.into().hwnd().expect("Child must be a window-like control (HWND handle)");
+        self.rows.push(FormRow::Field(label, control));
+        self
+    }
+
+    /// Add a row with a control that spans the whole width of the form
+    pub fn full_row<W: Into<ControlHandle>>(mut self, control: W) -> FormLayoutBuilder {
+        let control = control.into().hwnd().expect("Child must be a window-like control (HWND handle)");
+        self.rows.push(FormRow::Full(control));
+        self
+    }
+
+    /// Add a button to the button row at the bottom of the form
+    pub fn button<W: Into<ControlHandle>>(mut self, button: W) -> FormLayoutBuilder {
+        let button = button.into().hwnd().expect("Child must be a window-like control (HWND handle)");
+        self.buttons.push(button);
+        self
+    }
+
+    /// Set the width of the labels, relative to the width of the controls
+    pub fn label_columns(mut self, columns: u32) -> FormLayoutBuilder {
+        self.label_columns = columns.max(1);
+        self
+    }
+
+    /// Set the width of the controls, relative to the width of the labels
+    pub fn field_columns(mut self, columns: u32) -> FormLayoutBuilder {
+        self.field_columns = columns.max(1);
+        self
+    }
+
+    /// Set the height of the rows. `None` stretches the rows to fill the parent.
+    pub fn row_height(mut self, height: Option<u32>) -> FormLayoutBuilder {
+        self.row_height = height;
+        self
+    }
+
+    /// Set the margins of the form. The four values are in this order: top, right, bottom, left.
+    pub fn margin(mut self, m: [u32; 4]) -> FormLayoutBuilder {
+        self.margins = m;
+        self
+    }
+
+    /// Set the spacing between the controls
+    pub fn spacing(mut self, sp: u32) -> FormLayoutBuilder {
+        self.spacing = sp;
+        self
+    }
+
+    /// Align the labels on the right, next to their control
+    pub fn align_labels(mut self, align: bool) -> FormLayoutBuilder {
+        self.align_labels = align;
+        self
+    }
+
+    /// Build the form and the grid layout that places its controls
+    pub fn build(self, form: &FormLayout) -> Result<(), NwgError> {
+        let parent = match self.parent {
+            Some(p) => p,
+            None => { return Err(NwgError::layout_create("FormLayout does not have a parent.")); }
+        };
+
+        if self.rows.is_empty() && self.buttons.is_empty() {
+            return Err(NwgError::layout_create("FormLayout does not have any row."));
+        }
+
+        // Each button takes `columns` grid columns, so the labels and the controls are scaled by the number of buttons
+        let scale = self.buttons.len().max(1) as u32;
+        let columns = self.label_columns + self.field_columns;
+        let label_span = self.label_columns * scale;
+        let field_span = self.field_columns * scale;
+
+        let row_count = self.rows.len() as u32 + (!self.buttons.is_empty()) as u32;
+
+        let mut grid = GridLayout::builder()
+            .parent(ControlHandle::Hwnd(parent))
+            .margin(self.margins)
+            .spacing(self.spacing)
+            .max_column(Some(columns * scale))
+            .max_row(Some(row_count));
+
+        if let Some(height) = self.row_height {
+            let [m_top, _, m_bottom, _] = self.margins;
+            let max_height = m_top + m_bottom + row_count * (height + self.spacing * 2);
+            grid = grid.max_size([u32::max_value(), max_height]);
+        }
+
+        for (row, form_row) in self.rows.iter().enumerate() {
+            let row = row as u32;
+            match *form_row {
+                FormRow::Field(label, control) => {
+                    if self.align_labels {
+                        align_label_right(label);
+                    }
+
+                    grid = grid
+                        .child_item(GridLayoutItem::new(ControlHandle::Hwnd(label), 0, row, label_span, 1))
+                        .child_item(GridLayoutItem::new(ControlHandle::Hwnd(control), label_span, row, field_span, 1));
+                },
+                FormRow::Full(control) => {
+                    grid = grid.child_item(GridLayoutItem::new(ControlHandle::Hwnd(control), 0, row, columns * scale, 1));
+                }
+            }
+        }
+
+        let button_row = self.rows.len() as u32;
+        for (index, &button) in self.buttons.iter().enumerate() {
+            let col = index as u32 * columns;
+            grid = grid.child_item(GridLayoutItem::new(ControlHandle::Hwnd(button), col, button_row, columns, 1));
+        }
+
+        grid.build(&form.grid)
+    }
+
+}
+
+/// Align the text of a label on the right. Does nothing if the control is not a label.
+fn align_label_right(label: HWND) {
+    use winapi::um::winuser::{GetClassNameW, InvalidateRect, GWL_STYLE, SS_TYPEMASK, SS_LEFT, SS_CENTER, SS_RIGHT};
+    use std::ptr;
+
+    let mut class_name = [0u16; 10];
+    let count = unsafe { GetClassNameW(label, class_name.as_mut_ptr(), 10) } as usize;
+    if String::from_utf16_lossy(&class_name[..count]) != "Static" {
+        return;
+    }
+
+    let style = wh::get_window_long(label, GWL_STYLE) as u32;
+    match style & SS_TYPEMASK {
+        SS_LEFT | SS_CENTER => {
+            wh::set_window_long(label, GWL_STYLE, ((style & !SS_TYPEMASK) | SS_RIGHT) as usize);
+            unsafe { InvalidateRect(label, ptr::null(), 1); }
+        },
+        _ => {}
+    }
+}
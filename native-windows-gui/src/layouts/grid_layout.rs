@@ -2,8 +2,10 @@ use crate::controls::ControlHandle;
 use crate::win32::window::bind_raw_event_handler_inner;
 use crate::win32::window_helper as wh;
 use crate::NwgError;
+use super::visibility::{is_hidden, VisibilityHandlers};
+use super::measure;
 use winapi::shared::windef::{HWND};
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 use std::cell::RefCell;
 use std::ptr;
 
@@ -24,7 +26,10 @@ pub struct GridLayoutItem {
     pub col_span: u32,
 
     /// The number row this item should span. Should be 1 for single row item.
-    pub row_span: u32
+    pub row_span: u32,
+
+    /// If the item was hidden with `GridLayout::set_item_hidden`
+    hidden: bool,
 }
 
 impl GridLayoutItem {
@@ -38,7 +43,8 @@ impl GridLayoutItem {
             col,
             row,
             col_span,
-            row_span
+            row_span,
+            hidden: false,
         }
     }
 
@@ -70,7 +76,13 @@ pub struct GridLayoutInner {
     row_count: Option<u32>, 
 
     /// The spacing between controls
-    spacing: u32
+    spacing: u32,
+
+    /// If the hidden children are excluded from the layout
+    skip_hidden: bool,
+
+    /// The handlers that update the layout when a child is shown or hidden. Only bound if `skip_hidden` is set.
+    visibility_handlers: VisibilityHandlers,
 }
 
 /** 
@@ -84,6 +96,10 @@ A GridLayouts has the following properties:
 * max_size - The maximum size of the layout - (default: [u32::max_value(), u32::max_value()])
 * max_column - Number of columns - (default: None),
 * max_row - Number of rows - (default: None),
+* skip_hidden - Exclude the hidden children from the layout - (default: false)
+
+When `skip_hidden` is set, the hidden children do not take space: a row or a column that only holds hidden children
+is collapsed, and the layout is updated when a child is shown or hidden. A child can also be excluded with `set_item_hidden`.
 
 ```rust
     use native_windows_gui as nwg;
@@ -115,7 +131,9 @@ impl GridLayout {
             min_size: [0, 0],
             max_size: [u32::max_value(), u32::max_value()],
             column_count: None,
-            row_count: None
+            row_count: None,
+            skip_hidden: false,
+            visibility_handlers: VisibilityHandlers::new(),
         };
 
         GridLayoutBuilder { layout }
@@ -137,6 +155,7 @@ impl GridLayout {
             row,
             col_span: 1,
             row_span: 1,
+            hidden: false,
         };
 
         self.add_child_item(item);
@@ -162,6 +181,7 @@ impl GridLayout {
             inner.base
         };
         
+        self.bind_visibility_handlers();
 
         let (w, h) = unsafe { wh::get_window_size(base) };
         self.update_layout(w as u32, h as u32);
@@ -193,6 +213,7 @@ impl GridLayout {
             inner.base
         };
         
+        self.bind_visibility_handlers();
 
         let (w, h) = unsafe { wh::get_window_size(base) };
         self.update_layout(w as u32, h as u32);
@@ -223,6 +244,7 @@ impl GridLayout {
             inner.base
         };
         
+        self.bind_visibility_handlers();
 
         let (w, h) = unsafe { wh::get_window_size(base) };
         self.update_layout(w as u32, h as u32);
@@ -349,6 +371,51 @@ impl GridLayout {
         self.update_layout(w, h);
     }
 
+    /**
+        Returns the smallest size of the parent (in logical pixels) where every visible child gets at least its preferred size:
+        the size of the content of the buttons, the labels and the text inputs, or the current size of the other controls.
+        The cells of the grid have the same size, so the size of the biggest child is used for all the cells. The size is kept between
+        `min_size` and `max_size`. The status bar of the parent is not included. See `Window::size_to_content`.
+
+        Panic:
+          - The layout must have been successfully built otherwise this function will panic.
+    */
+    pub fn preferred_size(&self) -> [u32; 2] {
+        let inner = self.inner.borrow();
+        if inner.base.is_null() {
+            panic!("Grid layout is not bound to a parent control.")
+        }
+
+        let [m_top, m_right, m_bottom, m_left] = inner.margins;
+        let sp2 = inner.spacing * 2;
+
+        let (visible, collapsed_columns, collapsed_rows) = layout_cells(&inner);
+        let active_columns = collapsed_columns.iter().filter(|&&c| !c).count() as u32;
+        let active_rows = collapsed_rows.iter().filter(|&&r| !r).count() as u32;
+
+        // The size of a cell that fits the part of each child in the cells it spans
+        let mut cell = [0u32, 0u32];
+        for item in visible.iter() {
+            let [w, h] = measure::preferred_size(item.control);
+            let col_range = (item.col as usize)..((item.col + item.col_span) as usize);
+            let row_range = (item.row as usize)..((item.row + item.row_span) as usize);
+            let columns = (collapsed_columns[col_range].iter().filter(|&&c| !c).count() as u32).max(1);
+            let rows = (collapsed_rows[row_range].iter().filter(|&&r| !r).count() as u32).max(1);
+
+            let w = w.saturating_sub(sp2 * (columns - 1));
+            let h = h.saturating_sub(sp2 * (rows - 1));
+            cell[0] = cell[0].max((w + columns - 1) / columns);
+            cell[1] = cell[1].max((h + rows - 1) / rows);
+        }
+
+        let width = m_left + m_right + active_columns * (cell[0] + sp2);
+        let height = m_top + m_bottom + active_rows * (cell[1] + sp2);
+
+        let [min_w, min_h] = inner.min_size;
+        let [max_w, max_h] = inner.max_size;
+        [width.max(min_w).min(max_w), height.max(min_h).min(max_h)]
+    }
+
     /// Set the margins of the layout. The four values are in this order: top, right, bottom, left.
     pub fn margin(&self, m: [u32; 4]) {
         let mut inner = self.inner.borrow_mut();
@@ -385,6 +452,96 @@ impl GridLayout {
         inner.row_count = count;
     }
 
+    /// Returns true if the hidden children are excluded from the layout
+    pub fn skip_hidden(&self) -> bool {
+        self.inner.borrow().skip_hidden
+    }
+
+    /**
+        Exclude the hidden children from the layout. The layout is updated when a child is shown or hidden.
+
+        Panic:
+        - If the layout is not initialized
+    */
+    pub fn set_skip_hidden(&self, skip: bool) {
+        {
+            let mut inner = self.inner.borrow_mut();
+            if inner.base.is_null() {
+                panic!("GridLayout is not initialized");
+            }
+
+            inner.skip_hidden = skip;
+        }
+
+        self.bind_visibility_handlers();
+        self.fit();
+    }
+
+    /**
+        Hide or show a child of the layout. A hidden child is excluded from the layout, even if `skip_hidden` is not set.
+        The control is hidden or shown with the item.
+
+        This method won't do anything if the control is not in the layout.
+
+        Panic:
+        - If the layout is not initialized
+        - If the control is not window-like (HWND handle)
+    */
+    pub fn set_item_hidden<W: Into<ControlHandle>>(&self, c: W, hidden: bool) {
+        let handle = c.into().hwnd().expect("Control must be window-like (HWND handle)");
+
+        {
+            let mut inner = self.inner.borrow_mut();
+            if inner.base.is_null() {
+                panic!("GridLayout is not initialized");
+            }
+
+            match inner.children.iter_mut().find(|item| item.control == handle) {
+                Some(item) => { item.hidden = hidden; },
+                None => { return; }
+            }
+        }
+
+        unsafe { wh::set_window_visibility(handle, !hidden); }
+        self.fit();
+    }
+
+    /**
+        Returns true if the child was hidden with `set_item_hidden`. Returns false if the control is not in the layout.
+
+        Panic:
+        - If the control is not window-like (HWND handle)
+    */
+    pub fn item_hidden<W: Into<ControlHandle>>(&self, c: W) -> bool {
+        let handle = c.into().hwnd().expect("Control must be window-like (HWND handle)");
+        self.inner.borrow().children.iter().any(|item| item.control == handle && item.hidden)
+    }
+
+    /// Bind a visibility handler on every child if `skip_hidden` is set. Remove the old handlers.
+    fn bind_visibility_handlers(&self) {
+        let mut inner = self.inner.borrow_mut();
+        inner.visibility_handlers.unbind_all();
+
+        if !inner.skip_hidden {
+            return;
+        }
+
+        let controls: Vec<HWND> = inner.children.iter().map(|item| item.control).collect();
+        for control in controls {
+            let layout = Rc::downgrade(&self.inner);
+            inner.visibility_handlers.bind(control, move || GridLayout::visibility_changed(&layout));
+        }
+    }
+
+    fn visibility_changed(layout: &Weak<RefCell<GridLayoutInner>>) {
+        if let Some(inner) = layout.upgrade() {
+            let layout = GridLayout { inner };
+            if layout.inner.try_borrow_mut().is_ok() {
+                layout.fit();
+            }
+        }
+    }
+
     fn update_layout(&self, mut width: u32, mut height: u32) -> () {
         let inner = self.inner.borrow();
         if inner.base.is_null() || inner.children.len() == 0 {
@@ -394,7 +551,8 @@ impl GridLayout {
         let [m_top, m_right, m_bottom, m_left] = inner.margins;
         let sp = inner.spacing;
 
-        let children = &inner.children;
+        // The children are placed above the status bar of the parent
+        height = height.saturating_sub(wh::get_status_bar_height(inner.base));
 
         let [min_w, min_h] = inner.min_size;
         if width < min_w { width = min_w; }
@@ -404,21 +562,19 @@ impl GridLayout {
         if width > max_w { width = max_w; }
         if height > max_h { height = max_h; }
 
-        let column_count = match inner.column_count {
-            Some(c) => c,
-            None => children.iter().map(|item| item.col + item.col_span).max().unwrap_or(1)
-        };
+        let (visible, collapsed_columns, collapsed_rows) = layout_cells(&inner);
+        let active_columns = collapsed_columns.iter().filter(|&&c| !c).count() as u32;
+        let active_rows = collapsed_rows.iter().filter(|&&r| !r).count() as u32;
 
-        let row_count = match inner.row_count {
-            Some(c) => c,
-            None => children.iter().map(|item| item.row + item.row_span).max().unwrap_or(1)
-        };
+        if visible.is_empty() || active_columns == 0 || active_rows == 0 {
+            return;
+        }
 
-        if width < (m_right + m_left) + ((sp * 2) * column_count) {
+        if width < (m_right + m_left) + ((sp * 2) * active_columns) {
             return;
         }
 
-        if height < (m_top + m_bottom) + ((sp * 2) * row_count) {
+        if height < (m_top + m_bottom) + ((sp * 2) * active_rows) {
             return;
         }
 
@@ -427,35 +583,27 @@ impl GridLayout {
         height = height - m_top - m_bottom;
 
         // Apply spacing
-        width = width - ((sp * 2) * column_count);
-        height = height - ((sp * 2) * row_count);
+        width = width - ((sp * 2) * active_columns);
+        height = height - ((sp * 2) * active_rows);
 
-        let item_width = width / column_count;
-        let item_height = height / row_count;
         let sp2 = sp * 2;
-
-        let mut columns = vec![item_width; column_count as usize];
-        let mut rows = vec![item_height; row_count as usize];
-        let extra_width = width - item_width * column_count;
-        if extra_width > 0 {
-            for x in &mut columns[0..(extra_width as usize)] {
-                *x += 1;
-            }
-        }
-        let extra_height = height - item_height * row_count;
-        if extra_height > 0 {
-            for x in &mut rows[0..(extra_height as usize)] {
-                *x += 1;
-            }
-        }
+        let columns = cell_sizes(width, &collapsed_columns);
+        let rows = cell_sizes(height, &collapsed_rows);
+        let columns_x = cell_offsets(m_left, sp, &columns, &collapsed_columns);
+        let rows_y = cell_offsets(m_top, sp, &rows, &collapsed_rows);
 
         let mut last_handle = None;
-        for item in inner.children.iter() {
-            let x: u32 = m_left + (sp + (sp2 * item.col)) + columns[0..(item.col as usize)].iter().sum::<u32>();
-            let y: u32 = m_top + (sp + (sp2 * item.row)) + rows[0..(item.row as usize)].iter().sum::<u32>();
+        for item in visible.iter() {
+            let x: u32 = columns_x[item.col as usize];
+            let y: u32 = rows_y[item.row as usize];
+
+            let col_range = (item.col as usize)..((item.col + item.col_span) as usize);
+            let row_range = (item.row as usize)..((item.row + item.row_span) as usize);
+            let col_spacing = sp2 * (collapsed_columns[col_range.clone()].iter().filter(|&&c| !c).count() as u32).saturating_sub(1);
+            let row_spacing = sp2 * (collapsed_rows[row_range.clone()].iter().filter(|&&r| !r).count() as u32).saturating_sub(1);
 
-            let local_width: u32 = &columns[(item.col as usize)..((item.col + item.col_span) as usize)].iter().sum::<u32>() + (sp2 * (item.col_span - 1));
-            let local_height: u32 = &rows[(item.row as usize)..((item.row + item.row_span) as usize)].iter().sum::<u32>() + (sp2 * (item.row_span - 1));
+            let local_width: u32 = columns[col_range].iter().sum::<u32>() + col_spacing;
+            let local_height: u32 = rows[row_range].iter().sum::<u32>() + row_spacing;
 
             unsafe {
                 wh::set_window_position(item.control, x as i32, y as i32);
@@ -468,6 +616,73 @@ impl GridLayout {
     }
 }
 
+/// Returns the visible children of the layout, and the columns and the rows that are collapsed because they only hold excluded children
+fn layout_cells(inner: &GridLayoutInner) -> (Vec<&GridLayoutItem>, Vec<bool>, Vec<bool>) {
+    let children = &inner.children;
+
+    let column_count = match inner.column_count {
+        Some(c) => c,
+        None => children.iter().map(|item| item.col + item.col_span).max().unwrap_or(1)
+    };
+
+    let row_count = match inner.row_count {
+        Some(c) => c,
+        None => children.iter().map(|item| item.row + item.row_span).max().unwrap_or(1)
+    };
+
+    let skip_hidden = inner.skip_hidden;
+    let (visible, excluded): (Vec<&GridLayoutItem>, Vec<&GridLayoutItem>) = children.iter()
+        .partition(|item| !item.hidden && !(skip_hidden && is_hidden(item.control)));
+
+    let collapsed_columns = collapsed_cells(&visible, &excluded, column_count, |item| (item.col, item.col_span));
+    let collapsed_rows = collapsed_cells(&visible, &excluded, row_count, |item| (item.row, item.row_span));
+
+    (visible, collapsed_columns, collapsed_rows)
+}
+
+/// Returns the rows or the columns that are only covered by excluded children
+fn collapsed_cells<F>(visible: &[&GridLayoutItem], excluded: &[&GridLayoutItem], count: u32, span: F) -> Vec<bool>
+    where F: Fn(&GridLayoutItem) -> (u32, u32)
+{
+    let covers = |item: &&GridLayoutItem, cell: u32| {
+        let (start, len) = span(item);
+        cell >= start && cell < start + len
+    };
+
+    (0..count)
+        .map(|cell| excluded.iter().any(|item| covers(item, cell)) && !visible.iter().any(|item| covers(item, cell)))
+        .collect()
+}
+
+/// Split `size` between the cells that are not collapsed. The remainder is given to the first cells.
+fn cell_sizes(size: u32, collapsed: &[bool]) -> Vec<u32> {
+    let active = collapsed.iter().filter(|&&c| !c).count() as u32;
+    let cell_size = size / active;
+    let mut extra = size - cell_size * active;
+
+    collapsed.iter()
+        .map(|&c| match c {
+            true => 0,
+            false if extra > 0 => { extra -= 1; cell_size + 1 },
+            false => cell_size
+        })
+        .collect()
+}
+
+/// Returns the position of each cell. Collapsed cells do not take spacing.
+fn cell_offsets(margin: u32, sp: u32, sizes: &[u32], collapsed: &[bool]) -> Vec<u32> {
+    let mut offset = margin;
+    sizes.iter().zip(collapsed.iter())
+        .map(|(&size, &c)| {
+            let position = offset + sp;
+            if !c {
+                offset += size + (sp * 2);
+            }
+            position
+        })
+        .collect()
+}
+
 impl Default for GridLayout {
 
     fn default() -> GridLayout {
@@ -480,6 +695,8 @@ impl Default for GridLayout {
             column_count: None,
             row_count: None,
             spacing: 5,
+            skip_hidden: false,
+            visibility_handlers: VisibilityHandlers::new(),
         };
 
         GridLayout {
@@ -514,6 +731,7 @@ impl GridLayoutBuilder {
             row,
             col_span: 1,
             row_span: 1,
+            hidden: false,
         });
 
         self
@@ -562,6 +780,12 @@ impl GridLayoutBuilder {
         self
     }
 
+    /// Exclude the hidden children from the layout
+    pub fn skip_hidden(mut self, skip: bool) -> GridLayoutBuilder {
+        self.layout.skip_hidden = skip;
+        self
+    }
+
     /// Build the layout object and bind the callback.
     /// Children must only contains window object otherwise this method will panic.
     pub fn build(self, layout: &GridLayout) -> Result<(), NwgError> {
@@ -591,21 +815,27 @@ impl GridLayoutBuilder {
         // Saves the new layout. TODO: should free the old one too (if any)
         {
             let mut layout_inner = layout.inner.borrow_mut();
+            layout_inner.visibility_handlers.unbind_all();
+
             *layout_inner = self.layout;        
         }
 
+        layout.bind_visibility_handlers();
+
         // Initial layout update
         layout.update_layout(w, h);
        
         // Bind the event handler
         let event_layout = layout.clone();
-        let cb = move |_h, msg, _w, l| {
+        let cb = move |hwnd, msg, _w, l| {
             if msg == WM_SIZE {
                 let size = l as u32;
                 let width = LOWORD(size) as i32;
                 let height = HIWORD(size) as i32;
-                let (w, h) = unsafe { crate::win32::high_dpi::physical_to_logical(width, height) };
+                let (w, h) = unsafe { crate::win32::high_dpi::physical_to_logical_for(hwnd, width, height) };
                 GridLayout::update_layout(&event_layout, w as u32, h as u32);
+            } else if msg == wh::NWG_LAYOUT_MEASURE {
+                measure::merge_measure(l, event_layout.preferred_size());
             }
             None
         };
@@ -619,3 +849,67 @@ impl GridLayoutBuilder {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(col: u32, row: u32, col_span: u32, row_span: u32) -> GridLayoutItem {
+        GridLayoutItem { control: ptr::null_mut(), col, row, col_span, row_span, hidden: false }
+    }
+
+    fn collapsed_rows(visible: &[GridLayoutItem], excluded: &[GridLayoutItem], count: u32) -> Vec<bool> {
+        let visible: Vec<&GridLayoutItem> = visible.iter().collect();
+        let excluded: Vec<&GridLayoutItem> = excluded.iter().collect();
+        collapsed_cells(&visible, &excluded, count, |item| (item.row, item.row_span))
+    }
+
+    #[test]
+    fn all_hidden_collapses_every_cell() {
+        let excluded = [item(0, 0, 1, 1), item(0, 1, 1, 1), item(0, 2, 1, 1)];
+        assert_eq!(collapsed_rows(&[], &excluded, 3), vec![true, true, true]);
+    }
+
+    #[test]
+    fn empty_cells_are_not_collapsed() {
+        let visible = [item(0, 0, 1, 1)];
+        let excluded = [item(0, 2, 1, 1)];
+        assert_eq!(collapsed_rows(&visible, &excluded, 4), vec![false, false, true, false]);
+    }
+
+    #[test]
+    fn hidden_first_and_last_row() {
+        let visible = [item(0, 1, 1, 1)];
+        let excluded = [item(0, 0, 1, 1), item(0, 2, 1, 1)];
+        assert_eq!(collapsed_rows(&visible, &excluded, 3), vec![true, false, true]);
+    }
+
+    #[test]
+    fn visible_span_keeps_hidden_cells() {
+        let visible = [item(0, 0, 1, 3)];
+        let excluded = [item(1, 1, 1, 1)];
+        assert_eq!(collapsed_rows(&visible, &excluded, 3), vec![false, false, false]);
+    }
+
+    #[test]
+    fn hidden_span_collapses_every_covered_cell() {
+        let visible = [item(0, 0, 1, 1)];
+        let excluded = [item(0, 1, 1, 2)];
+        assert_eq!(collapsed_rows(&visible, &excluded, 3), vec![false, true, true]);
+    }
+
+    #[test]
+    fn cell_sizes_skip_collapsed_cells() {
+        assert_eq!(cell_sizes(100, &[false, false, false]), vec![34, 33, 33]);
+        assert_eq!(cell_sizes(100, &[true, false, false]), vec![0, 50, 50]);
+        assert_eq!(cell_sizes(101, &[false, true, false]), vec![51, 0, 50]);
+        assert_eq!(cell_sizes(100, &[false, false, true]), vec![50, 50, 0]);
+    }
+
+    #[test]
+    fn cell_offsets_skip_collapsed_cells() {
+        let collapsed = [true, false, true, false];
+        let sizes = cell_sizes(100, &collapsed);
+        assert_eq!(cell_offsets(5, 2, &sizes, &collapsed), vec![7, 7, 61, 61]);
+    }
+}
@@ -0,0 +1,111 @@
+/*!
+    Intrinsic measurement of the controls and of the layouts. Used by `Window::size_to_content`.
+
+    The layouts bound to a parent answer the `NWG_LAYOUT_MEASURE` message sent to the parent with their preferred size.
+    All the sizes are in logical pixels.
+*/
+use winapi::shared::windef::HWND;
+use winapi::shared::minwindef::LPARAM;
+use crate::win32::window_helper as wh;
+use crate::win32::high_dpi;
+use crate::win32::base_helper::to_utf16;
+
+/// The text measured for the width of an empty text input
+const EMPTY_INPUT_TEXT: &'static str = "0000000000";
+
+/// The padding of the text of a text input (borders and margins)
+const INPUT_PADDING: i32 = 8;
+
+
+/**
+    Returns the smallest size of the parent client area where the layouts of `parent` give every child its preferred size.
+    Returns `None` if no layout is bound to `parent`.
+*/
+pub(crate) fn measure_layouts(parent: HWND) -> Option<[u32; 2]> {
+    let mut size: Option<[u32; 2]> = None;
+    wh::send_message(parent, wh::NWG_LAYOUT_MEASURE, 0, &mut size as *mut Option<[u32; 2]> as LPARAM);
+    size
+}
+
+/// Merge the preferred size of a layout into the answer of a `NWG_LAYOUT_MEASURE` message. The parent must fit all its layouts.
+pub(crate) fn merge_measure(l: LPARAM, size: [u32; 2]) {
+    let answer = unsafe { &mut *(l as *mut Option<[u32; 2]>) };
+    *answer = match *answer {
+        Some([w, h]) => Some([w.max(size[0]), h.max(size[1])]),
+        None => Some(size)
+    };
+}
+
+/**
+    Returns the preferred size of a control: the size that fits its content.
+    Only the buttons, the labels and the single line text inputs are measured. The other controls keep their current size.
+*/
+pub(crate) fn preferred_size(control: HWND) -> [u32; 2] {
+    use winapi::um::winuser::ES_MULTILINE;
+
+    let current = unsafe { wh::get_window_size(control) };
+    let current = [current.0, current.1];
+
+    match class_name(control).as_str() {
+        "Button" => button_ideal_size(control).unwrap_or(current),
+        "Static" => {
+            let text = unsafe { wh::get_window_text(control) };
+            let text = if text.is_empty() { " " } else { text.as_str() };
+            let (w, h) = text_extent(control, text);
+            logical(control, [w, h])
+        },
+        "Edit" if wh::get_style(control) & ES_MULTILINE == 0 => {
+            let text = unsafe { wh::get_window_text(control) };
+            let (text_w, text_h) = text_extent(control, &text);
+            let (empty_w, empty_h) = text_extent(control, EMPTY_INPUT_TEXT);
+            logical(control, [text_w.max(empty_w) + INPUT_PADDING, text_h.max(empty_h) + INPUT_PADDING])
+        },
+        _ => current
+    }
+}
+
+fn class_name(control: HWND) -> String {
+    use winapi::um::winuser::GetClassNameW;
+
+    let mut buffer = [0u16; 64];
+    let count = unsafe { GetClassNameW(control, buffer.as_mut_ptr(), 64) } as usize;
+    String::from_utf16_lossy(&buffer[..count])
+}
+
+/// The size of the content of a button, check box or radio button
+fn button_ideal_size(control: HWND) -> Option<[u32; 2]> {
+    use winapi::um::commctrl::BCM_GETIDEALSIZE;
+    use winapi::shared::windef::SIZE;
+
+    let mut size = SIZE { cx: 0, cy: 0 };
+    match wh::send_message(control, BCM_GETIDEALSIZE, 0, &mut size as *mut SIZE as LPARAM) {
+        0 => None,
+        _ => Some(logical(control, [size.cx, size.cy]))
+    }
+}
+
+/// The physical size of `text` drawn with the font of `control`. Line breaks start new lines.
+fn text_extent(control: HWND, text: &str) -> (i32, i32) {
+    use winapi::um::winuser::{GetDC, ReleaseDC, DrawTextW, DT_CALCRECT, WM_GETFONT};
+    use winapi::um::wingdi::SelectObject;
+    use winapi::shared::windef::{RECT, HFONT};
+
+    let text = to_utf16(text);
+    let mut rect = RECT { left: 0, top: 0, right: 0, bottom: 0 };
+
+    unsafe {
+        let font = wh::send_message(control, WM_GETFONT, 0, 0) as HFONT;
+        let dc = GetDC(control);
+        let old = SelectObject(dc, font as _);
+        DrawTextW(dc, text.as_ptr(), -1, &mut rect, DT_CALCRECT);
+        SelectObject(dc, old);
+        ReleaseDC(control, dc);
+    }
+
+    (rect.right - rect.left, rect.bottom - rect.top)
+}
+
+fn logical(control: HWND, size: [i32; 2]) -> [u32; 2] {
+    let (w, h) = unsafe { high_dpi::physical_to_logical_for(control, size[0], size[1]) };
+    [w.max(0) as u32, h.max(0) as u32]
+}
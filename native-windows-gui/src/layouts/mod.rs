@@ -1,5 +1,15 @@
 mod grid_layout;
 
+mod form_layout;
+
+mod tile_layout;
+
+mod alignment;
+
+mod visibility;
+
+pub(crate) mod measure;
+
 #[cfg(feature = "flexbox")]
 mod flexbox_layout;
 
@@ -8,6 +18,13 @@ mod dyn_layout;
 
 pub use self::grid_layout::{GridLayout, GridLayoutInner, GridLayoutBuilder, GridLayoutItem};
 
+pub use self::form_layout::{FormLayout, FormLayoutBuilder};
+
+pub use self::tile_layout::{TileLayout, TileArrangement};
+
+pub use self::alignment::{Alignment, Distribution, SizeMatch, align_rects, distribute_rects, match_rect_sizes, snap_rects_to_grid, snap_to_grid,
+    align_controls, distribute_controls, match_control_sizes, snap_controls_to_grid};
+
 #[cfg(feature = "flexbox")]
 pub use self::flexbox_layout::{FlexboxLayout, FlexboxLayoutBuilder, FlexboxLayoutItem, FlexboxLayoutChildrenMut, FlexboxLayoutChildren};
 
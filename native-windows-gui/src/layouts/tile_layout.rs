@@ -0,0 +1,210 @@
+use winapi::shared::windef::HWND;
+use crate::controls::ControlHandle;
+use crate::win32::window_helper as wh;
+use crate::NwgError;
+
+
+/// How `TileLayout` arranges the windows
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TileArrangement {
+    /// The windows are placed side by side, from left to right, and share the width of the parent
+    Horizontal,
+
+    /// The windows are stacked from top to bottom and share the height of the parent
+    Vertical,
+
+    /// The windows are placed in a grid with the same number of rows and columns, or with the number of columns set with `columns`
+    Grid,
+
+    /// The windows overlap from the top left corner of the parent, each one offset from the previous one. The last window is on top.
+    Cascade,
+}
+
+/**
+    A one shot arrangement of child windows (usually `Frame`) inside a parent. Useful for dashboards
+    or to implement "Tile" and "Cascade" commands over a set of child windows.
+
+    Unlike the other layouts, `TileLayout` is not bound to the parent: the windows are only moved when `apply` is called.
+    Call it again after the parent is resized or after a window is added to the set.
+
+    Sizes and positions are in logical pixels.
+
+    **Parameters:**
+      * `gap`:            The space between two windows (default: 5)
+      * `margin`:         The top, right, bottom, left margins around the windows (default: [5, 5, 5, 5])
+      * `columns`:        The number of columns of a `Grid` arrangement. Defaults to the smallest square grid that holds all the windows.
+      * `cascade_offset`: The offset between two windows of a `Cascade` arrangement (default: (25, 25))
+      * `cascade_size`:   The size of the windows of a `Cascade` arrangement. Defaults to 2/3 of the parent.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn tile(window: &nwg::Window, panels: &[nwg::Frame]) {
+        let handles: Vec<nwg::ControlHandle> = panels.iter().map(|p| p.handle).collect();
+        nwg::TileLayout::new(nwg::TileArrangement::Grid)
+            .gap(10)
+            .apply(window, &handles)
+            .unwrap();
+    }
+    ```
+*/
+#[derive(Copy, Clone, Debug)]
+pub struct TileLayout {
+    arrangement: TileArrangement,
+    gap: u32,
+    margins: [u32; 4],
+    columns: Option<u32>,
+    cascade_offset: (u32, u32),
+    cascade_size: Option<(u32, u32)>,
+}
+
+impl TileLayout {
+
+    pub fn new(arrangement: TileArrangement) -> TileLayout {
+        TileLayout {
+            arrangement,
+            gap: 5,
+            margins: [5, 5, 5, 5],
+            columns: None,
+            cascade_offset: (25, 25),
+            cascade_size: None,
+        }
+    }
+
+    /// Set the space between two windows
+    pub fn gap(mut self, gap: u32) -> TileLayout {
+        self.gap = gap;
+        self
+    }
+
+    /// Set the margins around the windows. The four values are in this order: top, right, bottom, left.
+    pub fn margin(mut self, m: [u32; 4]) -> TileLayout {
+        self.margins = m;
+        self
+    }
+
+    /// Set the number of columns of a `Grid` arrangement. `None` uses the smallest square grid that holds all the windows.
+    pub fn columns(mut self, columns: Option<u32>) -> TileLayout {
+        self.columns = columns.map(|c| c.max(1));
+        self
+    }
+
+    /// Set the offset between two windows of a `Cascade` arrangement
+    pub fn cascade_offset(mut self, offset: (u32, u32)) -> TileLayout {
+        self.cascade_offset = offset;
+        self
+    }
+
+    /// Set the size of the windows of a `Cascade` arrangement. `None` uses 2/3 of the parent.
+    pub fn cascade_size(mut self, size: Option<(u32, u32)>) -> TileLayout {
+        self.cascade_size = size;
+        self
+    }
+
+    /**
+        Move and resize `children` inside the client area of `parent`. The windows are arranged in the order of the slice.
+        Returns an error if the parent or one of the children is not a window.
+    */
+    pub fn apply<C: Into<ControlHandle>>(&self, parent: C, children: &[ControlHandle]) -> Result<(), NwgError> {
+        let parent = parent.into().hwnd()
+            .ok_or_else(|| NwgError::layout_create("TileLayout parent must be a window"))?;
+
+        let handles = children.iter()
+            .map(|c| c.hwnd().ok_or_else(|| NwgError::layout_create("TileLayout children must be windows")))
+            .collect::<Result<Vec<HWND>, NwgError>>()?;
+
+        let (width, height) = unsafe { wh::get_window_size(parent) };
+        let height = height.saturating_sub(wh::get_status_bar_height(parent));
+        let rects = self.rects((width, height), handles.len());
+
+        let mut last_handle = None;
+        for (&handle, &[x, y, w, h]) in handles.iter().zip(rects.iter()) {
+            unsafe {
+                wh::set_window_position(handle, x as i32, y as i32);
+                wh::set_window_size(handle, w, h, false);
+
+                // Cascaded windows must overlap in order. Tiled windows keep the tab order of the slice.
+                match self.arrangement {
+                    TileArrangement::Cascade => wh::set_window_after(handle, None),
+                    _ => wh::set_window_after(handle, last_handle),
+                }
+            }
+
+            last_handle = Some(handle);
+        }
+
+        Ok(())
+    }
+
+    /**
+        Compute the position and the size (`[x, y, width, height]`) of `count` windows in an area of size `area`, without moving anything.
+        Windows that do not fit in the area receive a size of 0.
+    */
+    pub fn rects(&self, area: (u32, u32), count: usize) -> Vec<[u32; 4]> {
+        let [m_top, m_right, m_bottom, m_left] = self.margins;
+        let gap = self.gap;
+        let count32 = count as u32;
+
+        let width = area.0.saturating_sub(m_left + m_right);
+        let height = area.1.saturating_sub(m_top + m_bottom);
+
+        if count == 0 {
+            return Vec::new();
+        }
+
+        match self.arrangement {
+            TileArrangement::Horizontal => {
+                let cells = split(width, count32, gap);
+                cells.into_iter().map(|(x, w)| [m_left + x, m_top, w, height]).collect()
+            },
+            TileArrangement::Vertical => {
+                let cells = split(height, count32, gap);
+                cells.into_iter().map(|(y, h)| [m_left, m_top + y, width, h]).collect()
+            },
+            TileArrangement::Grid => {
+                let columns = self.columns.unwrap_or_else(|| (count as f64).sqrt().ceil() as u32).min(count32);
+                let rows = (count32 + columns - 1) / columns;
+                let xs = split(width, columns, gap);
+                let ys = split(height, rows, gap);
+
+                (0..count32).map(|i| {
+                    let (x, w) = xs[(i % columns) as usize];
+                    let (y, h) = ys[(i / columns) as usize];
+                    [m_left + x, m_top + y, w, h]
+                }).collect()
+            },
+            TileArrangement::Cascade => {
+                let (dx, dy) = self.cascade_offset;
+                let (w, h) = self.cascade_size.unwrap_or(((width * 2) / 3, (height * 2) / 3));
+
+                // Restart from the top left corner when a window would leave the parent
+                let steps_x = match dx { 0 => count32, dx => (width.saturating_sub(w) / dx) + 1 };
+                let steps_y = match dy { 0 => count32, dy => (height.saturating_sub(h) / dy) + 1 };
+                let steps = steps_x.min(steps_y).max(1);
+
+                (0..count32).map(|i| {
+                    let step = i % steps;
+                    [m_left + dx * step, m_top + dy * step, w, h]
+                }).collect()
+            }
+        }
+    }
+
+}
+
+/// Split `length` in `count` cells separated by `gap`. Returns the offset and the length of each cell.
+/// The pixels left by the integer division are given to the first cells.
+fn split(length: u32, count: u32, gap: u32) -> Vec<(u32, u32)> {
+    let total_gap = gap * count.saturating_sub(1);
+    let available = length.saturating_sub(total_gap);
+    let cell = available / count;
+    let extra = available - cell * count;
+
+    let mut offset = 0;
+    (0..count).map(|i| {
+        let size = cell + if i < extra { 1 } else { 0 };
+        let item = (offset, size);
+        offset += size + gap;
+        item
+    }).collect()
+}
@@ -0,0 +1,84 @@
+/*!
+    Visibility tracking for the layouts that skip their hidden children
+*/
+use crate::controls::ControlHandle;
+use crate::win32::window::{RawEventHandler, bind_raw_event_handler_inner, unbind_raw_event_handler};
+use crate::win32::window_helper as wh;
+use winapi::shared::windef::HWND;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::cell::RefCell;
+
+
+/// Returns true if the `WS_VISIBLE` style of the control is not set. The visibility of the parents is ignored,
+/// so the children of a window that is not yet shown are not skipped.
+pub(crate) fn is_hidden(control: HWND) -> bool {
+    use winapi::um::winuser::{GWL_STYLE, WS_VISIBLE};
+
+    (wh::get_window_long(control, GWL_STYLE) as u32) & WS_VISIBLE == 0
+}
+
+/// The first id of the visibility handlers. The ids up to the ones of `CheckBoxGroup` (0xBFFF) are reserved for the layouts.
+const FIRST_HANDLER_ID: usize = 0xAFFF;
+
+static NEXT_HANDLER_ID: AtomicUsize = AtomicUsize::new(FIRST_HANDLER_ID);
+
+thread_local! {
+    /// The ids of the dropped layouts, reused by the next layouts
+    static FREE_HANDLER_IDS: RefCell<Vec<usize>> = RefCell::new(Vec::new());
+}
+
+/**
+    The handlers that update a layout when its children are shown or hidden. The handlers of a layout share one id
+    for the lifetime of the layout. The handlers are unbound and the id is reused by another layout once the layout is dropped.
+*/
+pub(crate) struct VisibilityHandlers {
+    id: usize,
+    handlers: Vec<RawEventHandler>,
+}
+
+impl VisibilityHandlers {
+
+    pub(crate) fn new() -> VisibilityHandlers {
+        let id = FREE_HANDLER_IDS.with(|ids| ids.borrow_mut().pop())
+            .unwrap_or_else(|| NEXT_HANDLER_ID.fetch_add(1, Ordering::SeqCst));
+
+        VisibilityHandlers { id, handlers: Vec::new() }
+    }
+
+    /// Calls `on_change` after `control` is shown or hidden. Does nothing if the handler could not be bound.
+    pub(crate) fn bind<F: Fn() + 'static>(&mut self, control: HWND, on_change: F) {
+        use winapi::um::winuser::{WM_WINDOWPOSCHANGED, WINDOWPOS, SWP_SHOWWINDOW, SWP_HIDEWINDOW};
+
+        let cb = move |_h, msg, _w, l| {
+            if msg == WM_WINDOWPOSCHANGED {
+                let flags = unsafe { (*(l as *const WINDOWPOS)).flags };
+                if flags & (SWP_SHOWWINDOW | SWP_HIDEWINDOW) != 0 {
+                    on_change();
+                }
+            }
+
+            None
+        };
+
+        if let Ok(handler) = bind_raw_event_handler_inner(&ControlHandle::Hwnd(control), self.id, cb) {
+            self.handlers.push(handler);
+        }
+    }
+
+    /// Removes the handlers. The id is kept for the next handlers of the layout.
+    pub(crate) fn unbind_all(&mut self) {
+        for handler in self.handlers.drain(..) {
+            drop(unbind_raw_event_handler(&handler));
+        }
+    }
+
+}
+
+impl Drop for VisibilityHandlers {
+    fn drop(&mut self) {
+        self.unbind_all();
+
+        let id = self.id;
+        FREE_HANDLER_IDS.try_with(|ids| ids.borrow_mut().push(id)).ok();
+    }
+}
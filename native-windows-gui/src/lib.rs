@@ -9,6 +9,9 @@ extern crate winapi;
 #[cfg(feature="flexbox")]
 pub extern crate stretch;
 
+#[cfg(feature="ui-state")]
+pub extern crate serde_json;
+
 #[cfg(feature="all")]
 #[cfg(test)]
 mod tests;
@@ -22,9 +25,14 @@ pub use events::*;
 mod common_types;
 pub use common_types::*;
 
+// `Point`, `Size` and `Rect` are only exported through their aliases, to not collide with the `stretch` types of the same name
+mod geometry;
+pub use geometry::{Geometry, Logical, Physical, LogicalPoint, PhysicalPoint, LogicalSize, PhysicalSize, LogicalRect, PhysicalRect, control_dpi, DEFAULT_DPI};
+
 pub(crate) mod win32;
 pub use win32::{
  dispatch_thread_events, dispatch_thread_events_with_callback, stop_thread_dispatch, enable_visual_styles, init_common_controls, 
+ enable_visual_styles_from_resource, common_controls_v6, common_controls_version, visual_styles_active,
  window::{
      EventHandler, RawEventHandler,
      full_bind_event_handler, bind_event_handler, unbind_event_handler,
@@ -36,16 +44,49 @@ pub use win32::{
 pub(crate) use win32::window::bind_raw_event_handler_inner;
 
 #[allow(deprecated)]
-pub use win32::high_dpi::{set_dpi_awareness, scale_factor, dpi};
+pub use win32::high_dpi::{set_dpi_awareness, set_dpi_awareness_per_monitor, scale_factor, dpi};
 
 pub use win32::monitor::Monitor;
 
+pub use win32::device::DeviceNotification;
+
+pub use win32::elevation::{ElevationResult, is_elevated, relaunch_elevated};
+
+pub use win32::idle::last_input_elapsed;
+
+pub use win32::power::{DisplayKeepAwake, KeepAwakeMode};
+
+pub use win32::modal::{ModalScope, modal_scope};
+
+pub use win32::console::{attach_console, alloc_console, free_console, has_console};
+
+pub use win32::bootstrap::{AppBootstrap, AppMode, Activation};
+
+pub use win32::control_names::{set_control_name, control_name, find_control, named_controls};
+
+pub use win32::dialog_buttons::{default_button, cancel_button};
+
+pub use win32::mnemonics::{text_mnemonic, mnemonics, mnemonic_conflicts, assign_mnemonics};
+pub use win32::mouse_tracking::{set_hover_time, hover_time};
+pub use win32::scrolling::{set_wheel_routing, wheel_routing};
+pub use win32::init_config::{InitConfig, DpiAwareness, CommonControlClasses, init_with};
+
+#[cfg(feature = "shortcuts")]
+pub use win32::shortcuts::{ShortcutMap, Shortcut, ShortcutModifiers};
+
+pub use win32::custom_control::{CustomControl, register_custom_control, is_custom_control_registered, register_control_class};
+
+pub use win32::paths::{KnownFolder, app_config_dir, app_data_dir, compact_path, compact_path_to_width, format_file_size, format_file_size_kb, format_file_time};
+
 #[cfg(feature="cursor")]
 pub use win32::cursor::GlobalCursor;
 
 #[cfg(feature="clipboard")]
 pub use win32::clipboard::{Clipboard, ClipboardFormat, ClipboardData};
 
+#[cfg(feature="crash-dialog")]
+pub use win32::crash::{CrashHandler, CrashHandlerBuilder};
+
 mod resources;
 pub use resources::*;
 
@@ -61,6 +102,36 @@ mod winnls;
 #[cfg(feature = "winnls")]
 pub use winnls::*;
 
+#[cfg(feature = "codegen")]
+mod codegen;
+
+#[cfg(feature = "codegen")]
+pub use codegen::{UiDefinition, ControlDefinition, EventDefinition, GridLayoutDefinition, GridItemDefinition};
+
+#[cfg(feature = "ui-state")]
+mod ui_state;
+
+#[cfg(feature = "ui-state")]
+pub use ui_state::{UiState, PersistState};
+
+#[cfg(feature = "event-recorder")]
+mod event_recorder;
+
+#[cfg(feature = "event-recorder")]
+pub use event_recorder::{EventRecorder, EventPlayback, RecordedEvent};
+
+#[cfg(feature = "scripting")]
+pub extern crate rhai;
+
+#[cfg(feature = "scripting")]
+mod scripting;
+
+#[cfg(feature = "scripting")]
+pub use scripting::ScriptEngine;
+
+#[cfg(feature = "asyncio")]
+pub mod asyncio;
+
 /**
     A structure that implements this trait is considered a GUI structure. The structure will hold GUI components and possibly user data.
 
@@ -122,10 +193,11 @@ pub trait NativeUi<UI> {
 
 /// Initializes some application wide GUI settings.
 /// This includes default styling and common controls resources.
+///
+/// `init` does not fail if the visual styles cannot be enabled: the controls use the classic look instead.
+/// See `enable_visual_styles` and `common_controls_v6`.
+///
+/// This is `init_with` with the default `InitConfig`.
 pub fn init() -> std::result::Result<(), errors::NwgError> {
-    if cfg!(not(feature="no-styling")) {
-        enable_visual_styles();
-    }
-    
-    init_common_controls()
+    init_with(InitConfig::default())
 }
@@ -10,8 +10,8 @@ use super::EmbedResource;
 /** 
 A wrapper over a bitmap file (*.bmp)
 
-Note that Bitmap object are only used as display resources (ie: it's impossible to read pixels or resized it).
-If those features are needed, see the `image-decoder` feature.
+Note that Bitmap object are mostly used as display resources. Raw pixels can be copied in and out of a bitmap using
+`Bitmap::from_rgba` and `Bitmap::to_rgba`. For resizing or decoding other image formats, see the `image-decoder` feature.
 
 To display a bitmap in an application, see the `ImageFrame` control.
 
@@ -151,6 +151,55 @@ impl Bitmap {
         Ok(bitmap)
     }
 
+    /**
+        Creates a new bitmap from RGBA pixels (ex: the output of the `image` crate or a GPU readback).
+        `data` must contain `width * height * 4` bytes, row by row from the top left corner.
+
+        The bitmap keeps the transparency of the source.
+    */
+    pub fn from_rgba(width: u32, height: u32, data: &[u8]) -> Result<Bitmap, NwgError> {
+        let handle = unsafe { rh::bitmap_from_rgba(width, height, data, true)? };
+        Ok(Bitmap { handle: handle as HANDLE, owned: true })
+    }
+
+    /**
+        Returns the bitmap pixels as RGBA in a `(width, height, pixels)` tuple.
+
+        Panics if the bitmap is not initialized
+    */
+    pub fn to_rgba(&self) -> Result<(u32, u32, Vec<u8>), NwgError> {
+        if self.handle.is_null() {
+            panic!("Bitmap was not initialized");
+        }
+
+        unsafe { rh::bitmap_to_rgba(self.handle as _, true) }
+    }
+
+    /**
+        Renders a svg document into a new bitmap.
+
+        `size` is the logical size of the bitmap. If `None`, the size defined in the document is used.
+        The bitmap is scaled by `dpi / 96`. If `dpi` is `None`, the system dpi is used.
+
+        Requires the `svg` feature.
+    */
+    #[cfg(feature = "svg")]
+    pub fn from_svg(data: &[u8], size: Option<(u32, u32)>, dpi: Option<u32>) -> Result<Bitmap, NwgError> {
+        let (w, h, pixels) = super::svg::render_svg(data, size, dpi)?;
+        Bitmap::from_rgba(w, h, &pixels)
+    }
+
+    /**
+        Renders a svg file into a new bitmap. See `Bitmap::from_svg`.
+
+        Requires the `svg` feature.
+    */
+    #[cfg(feature = "svg")]
+    pub fn from_svg_file(path: &str, size: Option<(u32, u32)>, dpi: Option<u32>) -> Result<Bitmap, NwgError> {
+        let (w, h, pixels) = super::svg::render_svg_file(path, size, dpi)?;
+        Bitmap::from_rgba(w, h, &pixels)
+    }
+
     /**
         Creates a new icon from the bitmap data.
         
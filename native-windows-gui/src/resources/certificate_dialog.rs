@@ -0,0 +1,185 @@
+use winapi::shared::minwindef::DWORD;
+use winapi::shared::windef::HWND;
+use winapi::um::wincrypt::{HCERTSTORE, PCCERT_CONTEXT, CERT_SHA1_HASH_PROP_ID, CERT_NAME_SIMPLE_DISPLAY_TYPE, CERT_NAME_ISSUER_FLAG,
+  CertOpenSystemStoreW, CertCloseStore, CertFreeCertificateContext, CertGetNameStringW, CertGetCertificateContextProperty};
+use winapi::um::winnt::LPCWSTR;
+use winapi::ctypes::c_void;
+use crate::win32::base_helper::{to_utf16, from_utf16};
+use crate::controls::ControlHandle;
+use crate::NwgError;
+use std::{ptr, slice};
+
+
+#[link(name = "cryptui")]
+extern "system" {
+    fn CryptUIDlgSelectCertificateFromStore(hCertStore: HCERTSTORE, hwnd: HWND, pwszTitle: LPCWSTR, pwszDisplayString: LPCWSTR,
+        dwDontUseColumn: DWORD, dwFlags: DWORD, pvReserved: *mut c_void) -> PCCERT_CONTEXT;
+}
+
+
+/// A certificate selected by the user in a `CertificateDialog`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Certificate {
+    /// The display name of the certificate subject
+    pub subject: String,
+
+    /// The display name of the certificate issuer
+    pub issuer: String,
+
+    /// The SHA-1 thumbprint of the certificate as an uppercase hexadecimal string. Can be used to find the certificate in the store later.
+    pub thumbprint: String,
+
+    /// The DER encoded certificate
+    pub der: Vec<u8>,
+}
+
+impl Certificate {
+
+    unsafe fn from_context(context: PCCERT_CONTEXT) -> Certificate {
+        let name = |flags: DWORD| {
+            let length = CertGetNameStringW(context, CERT_NAME_SIMPLE_DISPLAY_TYPE, flags, ptr::null_mut(), ptr::null_mut(), 0);
+            let mut buffer = vec![0u16; length as usize];
+            CertGetNameStringW(context, CERT_NAME_SIMPLE_DISPLAY_TYPE, flags, ptr::null_mut(), buffer.as_mut_ptr(), length);
+            from_utf16(&buffer)
+        };
+
+        let mut hash = [0u8; 20];
+        let mut hash_size = hash.len() as DWORD;
+        CertGetCertificateContextProperty(context, CERT_SHA1_HASH_PROP_ID, hash.as_mut_ptr() as _, &mut hash_size);
+
+        let context = &*context;
+        Certificate {
+            subject: name(0),
+            issuer: name(CERT_NAME_ISSUER_FLAG),
+            thumbprint: hash[..hash_size as usize].iter().map(|b| format!("{:02X}", b)).collect(),
+            der: slice::from_raw_parts(context.pbCertEncoded, context.cbCertEncoded as usize).to_vec(),
+        }
+    }
+
+}
+
+/**
+    Displays the standard Windows dialog box that lets the user select a certificate from a system certificate store.
+
+    Requires the `certificate-dialog` feature.
+
+    **Builder parameters:**
+      * `title`:   The title of the dialog
+      * `message`: The message displayed in the dialog
+      * `store`:   The name of the system store. Defaults to "MY", the personal certificates of the current user.
+                   Other common values are "ROOT" and "CA".
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn select_certificate(window: &nwg::Window) -> Option<nwg::Certificate> {
+        let mut dialog = Default::default();
+        nwg::CertificateDialog::builder()
+            .title("Client certificate")
+            .message("Select the certificate used to sign the documents")
+            .build(&mut dialog)
+            .unwrap();
+
+        dialog.run(Some(window)).unwrap_or(None)
+    }
+    ```
+*/
+#[derive(Default)]
+pub struct CertificateDialog {
+    title: String,
+    message: String,
+    store: String,
+}
+
+impl CertificateDialog {
+
+    pub fn builder<'a>() -> CertificateDialogBuilder<'a> {
+        CertificateDialogBuilder {
+            title: "",
+            message: "",
+            store: "MY",
+        }
+    }
+
+    /**
+        Execute the certificate dialog.
+        Return the selected certificate, `None` if the dialog was cancelled, or an error if the certificate store could not be opened.
+    */
+    pub fn run<C: Into<ControlHandle>>(&self, owner: Option<C>) -> Result<Option<Certificate>, NwgError> {
+        let owner = match owner {
+            Some(o) => o.into().hwnd().expect("Certificate dialog owner must be a window control"),
+            None => ptr::null_mut()
+        };
+
+        let store_name = match self.store.is_empty() {
+            true => to_utf16("MY"),
+            false => to_utf16(&self.store)
+        };
+        let title = to_utf16(&self.title);
+        let message = to_utf16(&self.message);
+
+        unsafe {
+            let store = CertOpenSystemStoreW(0, store_name.as_ptr());
+            if store.is_null() {
+                return Err(NwgError::resource_create(format!("Failed to open the certificate store {:?}", self.store)));
+            }
+
+            let context = CryptUIDlgSelectCertificateFromStore(
+                store,
+                owner,
+                if self.title.is_empty() { ptr::null() } else { title.as_ptr() },
+                if self.message.is_empty() { ptr::null() } else { message.as_ptr() },
+                0,
+                0,
+                ptr::null_mut()
+            );
+
+            let certificate = match context.is_null() {
+                true => None,
+                false => {
+                    let certificate = Certificate::from_context(context);
+                    CertFreeCertificateContext(context);
+                    Some(certificate)
+                }
+            };
+
+            CertCloseStore(store, 0);
+
+            Ok(certificate)
+        }
+    }
+
+}
+
+/// The builder for a `CertificateDialog` object. Use `CertificateDialog::builder` to create one.
+pub struct CertificateDialogBuilder<'a> {
+    title: &'a str,
+    message: &'a str,
+    store: &'a str,
+}
+
+impl<'a> CertificateDialogBuilder<'a> {
+
+    pub fn title(mut self, title: &'a str) -> CertificateDialogBuilder<'a> {
+        self.title = title;
+        self
+    }
+
+    pub fn message(mut self, message: &'a str) -> CertificateDialogBuilder<'a> {
+        self.message = message;
+        self
+    }
+
+    pub fn store(mut self, store: &'a str) -> CertificateDialogBuilder<'a> {
+        self.store = store;
+        self
+    }
+
+    pub fn build(self, out: &mut CertificateDialog) -> Result<(), NwgError> {
+        out.title = self.title.to_string();
+        out.message = self.message.to_string();
+        out.store = self.store.to_string();
+        Ok(())
+    }
+
+}
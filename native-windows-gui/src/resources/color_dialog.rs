@@ -15,6 +15,28 @@ struct InnerColorDialog {
 
 /**
  Displays a modal dialog box that allows the user to choose a specific color value.
+
+ The dialog has 16 saved colors (the "Custom colors" of the dialog). They can be seeded with the builder,
+ read back after the dialog is closed with `saved_colors`, and persisted between runs with `store_saved_colors` and
+ `load_saved_colors` if the `registry` feature is enabled.
+
+ ```rust
+ use native_windows_gui as nwg;
+
+ fn pick_color(dialog: &nwg::ColorDialog, window: &nwg::Window) -> Option<[u8; 3]> {
+     match dialog.run(Some(window)) {
+         true => Some(dialog.color()),
+         false => None
+     }
+ }
+
+ fn build_dialog(dialog: &mut nwg::ColorDialog) {
+     nwg::ColorDialog::builder()
+         .saved_colors(&[[255, 0, 0], [0, 255, 0], [0, 0, 255]])
+         .build(dialog)
+         .unwrap();
+ }
+ ```
 */
 pub struct ColorDialog {
     data: RefCell<InnerColorDialog>,
@@ -45,6 +67,18 @@ impl ColorDialog {
         }
     }
 
+    /**
+        Returns a future that executes the color dialog when it is awaited and resolves to the result of `run`.
+        Use it in a task started with `nwg::asyncio::spawn_local`: the event handler that starts the task is not blocked by the dialog.
+
+        Requires the `asyncio` feature.
+    */
+    #[cfg(feature = "asyncio")]
+    pub fn run_async<'a, C: Into<ControlHandle>>(&'a self, owner: Option<C>) -> impl std::future::Future<Output = bool> + 'a {
+        let owner = owner.map(|o| o.into());
+        crate::asyncio::deferred(move || self.run(owner))
+    }
+
     /**
     Return the color choosen by the user. The returned color is a [r, g, b] array.
     If the dialog was never executed, this returns `[0, 0, 0]` (black);
@@ -77,6 +111,62 @@ impl ColorDialog {
         [GetRValue(v), GetGValue(v), GetBValue(v)]
     }
 
+    /**
+        Returns the 16 saved colors of the dialog. After the dialog is closed, this includes the colors added by the user.
+    */
+    pub fn saved_colors(&self) -> [[u8; 3]; 16] {
+        let data = self.data.borrow();
+        let mut colors = [[0u8; 3]; 16];
+        for (color, &v) in colors.iter_mut().zip(data.custom_colors.iter()) {
+            *color = [GetRValue(v), GetGValue(v), GetBValue(v)];
+        }
+
+        colors
+    }
+
+    /**
+        Sets the saved colors of the dialog, starting at index 0. Colors past the 16th are ignored.
+        The saved colors that are not in `colors` are not modified.
+    */
+    pub fn set_saved_colors(&self, colors: &[[u8; 3]]) {
+        let mut data = self.data.borrow_mut();
+        for (v, color) in data.custom_colors.iter_mut().zip(colors.iter()) {
+            *v = RGB(color[0], color[1], color[2]);
+        }
+    }
+
+    /**
+        Writes the 16 saved colors in a binary value of a registry key so that they can be restored in the next runs
+        of the application with `load_saved_colors`. The key must be opened with write access.
+
+        Requires the `registry` feature.
+    */
+    #[cfg(feature = "registry")]
+    pub fn store_saved_colors(&self, key: &crate::RegistryKey, name: &str) -> Result<(), NwgError> {
+        let colors = self.saved_colors();
+        let bytes: Vec<u8> = colors.iter().flat_map(|c| c.iter().cloned()).collect();
+        key.set_binary(name, &bytes)
+    }
+
+    /**
+        Reads the saved colors written by `store_saved_colors` from a registry key.
+        Returns `false` and keeps the current colors if the value does not exist or is not valid.
+
+        Requires the `registry` feature.
+    */
+    #[cfg(feature = "registry")]
+    pub fn load_saved_colors(&self, key: &crate::RegistryKey, name: &str) -> bool {
+        let bytes = match key.get_binary(name) {
+            Some(b) if b.len() == 16 * 3 => b,
+            _ => { return false; }
+        };
+
+        let colors: Vec<[u8; 3]> = bytes.chunks_exact(3).map(|c| [c[0], c[1], c[2]]).collect();
+        self.set_saved_colors(&colors);
+
+        true
+    }
+
 }
 
 /// The builder for a `ColorDialog` object. Use `ColorDialog::builder` to create one.
@@ -91,6 +181,14 @@ impl ColorDialogBuilder {
         self
     }
 
+    /// Sets the saved colors of the dialog, starting at index 0. Colors past the 16th are ignored.
+    pub fn saved_colors(mut self, colors: &[[u8; 3]]) -> ColorDialogBuilder {
+        for (v, color) in self.default_colors.iter_mut().zip(colors.iter()) {
+            *v = RGB(color[0], color[1], color[2]);
+        }
+        self
+    }
+
     pub fn build(self, out: &mut ColorDialog) -> Result<(), NwgError> {
         *out.data.borrow_mut().custom_colors.as_mut() = self.default_colors;
         Ok(())
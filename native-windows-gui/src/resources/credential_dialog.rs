@@ -0,0 +1,244 @@
+use winapi::shared::minwindef::{DWORD, ULONG, BOOL};
+use winapi::shared::winerror::{ERROR_SUCCESS, ERROR_CANCELLED};
+use winapi::um::wincred::{CREDUI_INFOW, CREDUIWIN_GENERIC, CREDUIWIN_CHECKBOX, CREDUI_MAX_USERNAME_LENGTH,
+  CREDUI_MAX_DOMAIN_TARGET_LENGTH, CREDUI_MAX_PASSWORD_LENGTH, CredUIPromptForWindowsCredentialsW,
+  CredUnPackAuthenticationBufferW, CredPackAuthenticationBufferW};
+use winapi::um::combaseapi::CoTaskMemFree;
+use crate::win32::base_helper::{to_utf16, from_utf16};
+use crate::controls::ControlHandle;
+use crate::NwgError;
+use std::{ptr, mem};
+
+
+/// The credentials entered by the user in a `CredentialDialog`
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    /// The user name. If the user typed `DOMAIN\user`, the domain is included.
+    pub username: String,
+
+    /// The domain name, if the system returned it separately from the user name
+    pub domain: String,
+
+    /// The password in plain text
+    pub password: String,
+
+    /// The state of the "Remember my credentials" checkbox. Always `false` if the checkbox is not displayed.
+    pub save: bool,
+}
+
+impl ::std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        // Never print the password
+        write!(f, "Credentials {{ username: {:?}, domain: {:?}, save: {} }}", self.username, self.domain, self.save)
+    }
+}
+
+/**
+    Displays the standard Windows credential dialog box that lets the user enter a user name and a password.
+    The dialog does not validate the credentials, it only collects them.
+
+    Requires the `credential-dialog` feature.
+
+    **Builder parameters:**
+      * `caption`:       The title of the dialog
+      * `message`:       The message displayed in the dialog
+      * `username`:      The user name displayed by default in the dialog
+      * `save_checkbox`: Display a "Remember my credentials" checkbox. The state of the checkbox is returned in `Credentials::save`.
+      * `auth_error`:    A system error code displayed in the dialog (ex: `ERROR_LOGON_FAILURE` after a failed login). `0` for no error.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn login(window: &nwg::Window) -> Option<nwg::Credentials> {
+        let mut dialog = Default::default();
+        nwg::CredentialDialog::builder()
+            .caption("Connect to server")
+            .message("Enter your domain credentials")
+            .build(&mut dialog)
+            .unwrap();
+
+        dialog.run(Some(window)).unwrap_or(None)
+    }
+    ```
+*/
+#[derive(Default)]
+pub struct CredentialDialog {
+    caption: String,
+    message: String,
+    username: Option<String>,
+    save_checkbox: bool,
+    auth_error: u32,
+}
+
+impl CredentialDialog {
+
+    pub fn builder<'a>() -> CredentialDialogBuilder<'a> {
+        CredentialDialogBuilder {
+            caption: "",
+            message: "",
+            username: None,
+            save_checkbox: false,
+            auth_error: 0,
+        }
+    }
+
+    /**
+        Execute the credential dialog.
+        Return the credentials if the user accepted the dialog, `None` if the dialog was cancelled, or an error if the dialog could not be displayed.
+    */
+    pub fn run<C: Into<ControlHandle>>(&self, owner: Option<C>) -> Result<Option<Credentials>, NwgError> {
+        let owner = match owner {
+            Some(o) => o.into().hwnd().expect("Credential dialog owner must be a window control"),
+            None => ptr::null_mut()
+        };
+
+        let caption = to_utf16(&self.caption);
+        let message = to_utf16(&self.message);
+        let mut info = CREDUI_INFOW {
+            cbSize: mem::size_of::<CREDUI_INFOW>() as DWORD,
+            hwndParent: owner,
+            pszMessageText: if self.message.is_empty() { ptr::null() } else { message.as_ptr() },
+            pszCaptionText: if self.caption.is_empty() { ptr::null() } else { caption.as_ptr() },
+            hbmBanner: ptr::null_mut(),
+        };
+
+        let mut flags = CREDUIWIN_GENERIC;
+        if self.save_checkbox {
+            flags |= CREDUIWIN_CHECKBOX;
+        }
+
+        let in_buffer = self.username.as_ref().map(|u| pack_username(u)).unwrap_or(Vec::new());
+
+        let mut auth_package: ULONG = 0;
+        let mut out_buffer = ptr::null_mut();
+        let mut out_size: ULONG = 0;
+        let mut save: BOOL = 0;
+
+        let result = unsafe {
+            CredUIPromptForWindowsCredentialsW(
+                &mut info,
+                self.auth_error,
+                &mut auth_package,
+                if in_buffer.is_empty() { ptr::null() } else { in_buffer.as_ptr() as _ },
+                in_buffer.len() as ULONG,
+                &mut out_buffer,
+                &mut out_size,
+                &mut save,
+                flags
+            )
+        };
+
+        match result {
+            ERROR_SUCCESS => {},
+            ERROR_CANCELLED => { return Ok(None); },
+            code => { return Err(NwgError::resource_create(format!("Failed to display the credential dialog (error {})", code))); }
+        }
+
+        let mut username = vec![0u16; CREDUI_MAX_USERNAME_LENGTH as usize + 1];
+        let mut domain = vec![0u16; CREDUI_MAX_DOMAIN_TARGET_LENGTH as usize + 1];
+        let mut password = vec![0u16; CREDUI_MAX_PASSWORD_LENGTH as usize + 1];
+        let mut username_len = username.len() as DWORD;
+        let mut domain_len = domain.len() as DWORD;
+        let mut password_len = password.len() as DWORD;
+
+        let unpacked = unsafe {
+            let unpacked = CredUnPackAuthenticationBufferW(
+                0,
+                out_buffer,
+                out_size,
+                username.as_mut_ptr(), &mut username_len,
+                domain.as_mut_ptr(), &mut domain_len,
+                password.as_mut_ptr(), &mut password_len
+            );
+
+            // The buffer holds the password. Clear it before freeing it.
+            ptr::write_bytes(out_buffer as *mut u8, 0, out_size as usize);
+            CoTaskMemFree(out_buffer);
+
+            unpacked
+        };
+
+        if unpacked == 0 {
+            return Err(NwgError::resource_create("Failed to read the credentials entered by the user"));
+        }
+
+        let credentials = Credentials {
+            username: from_utf16(&username),
+            domain: from_utf16(&domain),
+            password: from_utf16(&password),
+            save: save != 0,
+        };
+
+        password.iter_mut().for_each(|c| *c = 0);
+
+        Ok(Some(credentials))
+    }
+
+}
+
+/// Pack a user name in a buffer accepted by `CredUIPromptForWindowsCredentialsW`
+fn pack_username(username: &str) -> Vec<u8> {
+    let mut username = to_utf16(username);
+    let mut password = to_utf16("");
+    let mut size: DWORD = 0;
+
+    unsafe {
+        CredPackAuthenticationBufferW(0, username.as_mut_ptr(), password.as_mut_ptr(), ptr::null_mut(), &mut size);
+        if size == 0 {
+            return Vec::new();
+        }
+
+        let mut buffer = vec![0u8; size as usize];
+        match CredPackAuthenticationBufferW(0, username.as_mut_ptr(), password.as_mut_ptr(), buffer.as_mut_ptr(), &mut size) {
+            0 => Vec::new(),
+            _ => buffer
+        }
+    }
+}
+
+/// The builder for a `CredentialDialog` object. Use `CredentialDialog::builder` to create one.
+pub struct CredentialDialogBuilder<'a> {
+    caption: &'a str,
+    message: &'a str,
+    username: Option<&'a str>,
+    save_checkbox: bool,
+    auth_error: u32,
+}
+
+impl<'a> CredentialDialogBuilder<'a> {
+
+    pub fn caption(mut self, caption: &'a str) -> CredentialDialogBuilder<'a> {
+        self.caption = caption;
+        self
+    }
+
+    pub fn message(mut self, message: &'a str) -> CredentialDialogBuilder<'a> {
+        self.message = message;
+        self
+    }
+
+    pub fn username(mut self, username: Option<&'a str>) -> CredentialDialogBuilder<'a> {
+        self.username = username;
+        self
+    }
+
+    pub fn save_checkbox(mut self, save_checkbox: bool) -> CredentialDialogBuilder<'a> {
+        self.save_checkbox = save_checkbox;
+        self
+    }
+
+    pub fn auth_error(mut self, auth_error: u32) -> CredentialDialogBuilder<'a> {
+        self.auth_error = auth_error;
+        self
+    }
+
+    pub fn build(self, out: &mut CredentialDialog) -> Result<(), NwgError> {
+        out.caption = self.caption.to_string();
+        out.message = self.message.to_string();
+        out.username = self.username.map(|u| u.to_string());
+        out.save_checkbox = self.save_checkbox;
+        out.auth_error = self.auth_error;
+        Ok(())
+    }
+
+}
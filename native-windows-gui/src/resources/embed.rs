@@ -173,6 +173,31 @@ impl EmbedResource {
         self.icon(name.as_ptr() as usize, size)
     }
 
+    /**
+        Load an icon from the rc file with a size matching a dpi value. Returns `None` if `id` does not map to a icon.
+        The best matching image of the icon group is selected by the system.
+
+        - id: The ordinal of the icon in the rc file
+        - small: If true, load a small icon (16x16 at 96 dpi, ex: window title bar). Otherwise, load a large icon (32x32 at 96 dpi, ex: alt-tab)
+        - dpi: The target dpi. If `None`, use the dpi of the screen.
+    */
+    pub fn icon_for_dpi(&self, id: usize, small: bool, dpi: Option<u32>) -> Option<Icon> {
+        let dpi = dpi.unwrap_or_else(|| unsafe { crate::win32::high_dpi::dpi() as u32 });
+        let base = match small {
+            true => 16,
+            false => 32
+        };
+
+        let size = (base * dpi + 48) / 96;
+        self.icon(id, Some((size, size)))
+    }
+
+    /// Load an icon identified by a string in a resource file with a size matching a dpi value. See `icon_for_dpi`.
+    pub fn icon_str_for_dpi(&self, id: &str, small: bool, dpi: Option<u32>) -> Option<Icon> {
+        let name = to_utf16(id);
+        self.icon_for_dpi(name.as_ptr() as usize, small, dpi)
+    }
+
     /// Load a bitmap file from the rc file. Returns `None` if `id` does not map to a bitmap.
     pub fn bitmap(&self, id: usize, size: Option<(u32, u32)>) -> Option<Bitmap> {
         use winapi::um::winuser::IMAGE_BITMAP;
@@ -0,0 +1,230 @@
+/*!
+    Register the current executable as the handler of a file extension or of a URI protocol, and read how the application was activated.
+    The registrations are written in `HKEY_CURRENT_USER\Software\Classes`, so they do not require administrator rights.
+*/
+use winapi::shared::minwindef::{LONG, UINT, LPCVOID};
+use super::{RegistryKey, RegistryRoot};
+use crate::NwgError;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::{env, ptr};
+
+#[link(name = "shell32")]
+extern "system" {
+    fn SHChangeNotify(wEventId: LONG, uFlags: UINT, dwItem1: LPCVOID, dwItem2: LPCVOID);
+}
+
+const SHCNE_ASSOCCHANGED: LONG = 0x08000000;
+const SHCNF_IDLIST: UINT = 0x0000;
+
+const CLASSES_PATH: &'static str = "Software\\Classes";
+
+
+/**
+    The parameters of `register_file_extension`
+
+    * `extension`:   The file extension, with or without the leading dot (ex: ".mydoc")
+    * `prog_id`:     A unique identifier of the document type, usually `Company.Application.Type` (ex: "Acme.Editor.Document")
+    * `description`: The document type displayed by the explorer (ex: "Acme Document")
+    * `icon`:        The path and the index of the icon of the documents. Defaults to the first icon of the executable.
+    * `arguments`:   The command line arguments used to open a document. `%1` is replaced by the path of the document. Defaults to `"%1"`.
+*/
+#[derive(Clone, Debug)]
+pub struct FileAssociation<'a> {
+    pub extension: &'a str,
+    pub prog_id: &'a str,
+    pub description: &'a str,
+    pub icon: Option<(&'a str, i32)>,
+    pub arguments: Option<&'a str>,
+}
+
+/// How the application was started. Returned by `launch_activation`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LaunchActivation {
+    /// The application was started to open a file (ex: double click on a document in the explorer)
+    File(PathBuf),
+
+    /// The application was started to open a URI of a registered protocol (ex: "myapp://open?id=10")
+    Url(String),
+}
+
+/**
+    Register the current executable as the default handler of a file extension for the current user.
+    The explorer is notified of the change so the new icon and description are displayed right away.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn register() -> Result<(), nwg::NwgError> {
+        nwg::register_file_extension(&nwg::FileAssociation {
+            extension: ".mydoc",
+            prog_id: "Acme.Editor.Document",
+            description: "Acme Document",
+            icon: None,
+            arguments: None,
+        })
+    }
+    ```
+*/
+pub fn register_file_extension(association: &FileAssociation) -> Result<(), NwgError> {
+    let extension = normalize_extension(association.extension)?;
+    if association.prog_id.is_empty() {
+        return Err(NwgError::resource_create("A prog id is required to register a file extension"));
+    }
+
+    let exe = current_exe()?;
+    let icon = match association.icon {
+        Some((path, index)) => format!("{},{}", path, index),
+        None => format!("{},0", exe),
+    };
+    let command = format!("\"{}\" {}", exe, association.arguments.unwrap_or("\"%1\""));
+
+    let classes = classes_key()?;
+
+    let prog_id = classes.open_subkey(association.prog_id, true)?;
+    prog_id.set_string("", association.description)?;
+    prog_id.open_subkey("DefaultIcon", true)?.set_string("", &icon)?;
+    prog_id.open_subkey("shell\\open\\command", true)?.set_string("", &command)?;
+
+    let ext = classes.open_subkey(&extension, true)?;
+    ext.set_string("", association.prog_id)?;
+    ext.open_subkey("OpenWithProgids", true)?.set_string(association.prog_id, "")?;
+
+    notify_association_changed();
+
+    Ok(())
+}
+
+/**
+    Remove a file extension registered with `register_file_extension`.
+    The extension itself is only reset if it is still associated with `prog_id`, so the registrations of other applications are kept.
+*/
+pub fn unregister_file_extension(extension: &str, prog_id: &str) -> Result<(), NwgError> {
+    let extension = normalize_extension(extension)?;
+    if prog_id.is_empty() {
+        return Err(NwgError::resource_create("A prog id is required to unregister a file extension"));
+    }
+
+    let classes = classes_key()?;
+
+    if let Ok(ext) = classes.open_subkey(&extension, false) {
+        if ext.get_string("").as_ref().map(|p| p.as_str()) == Some(prog_id) {
+            ext.delete_value("")?;
+        }
+
+        if let Ok(progids) = ext.open_subkey("OpenWithProgids", false) {
+            drop(progids.delete_value(prog_id));
+        }
+    }
+
+    if classes.open_subkey(prog_id, false).is_ok() {
+        classes.delete_subkey(prog_id)?;
+    }
+
+    notify_association_changed();
+
+    Ok(())
+}
+
+/**
+    Register the current executable as the handler of a custom URI protocol for the current user.
+    Once registered, opening `scheme:...` (ex: from a web browser or with `start myapp://open`) starts the application
+    with the URI as its first argument. Use `launch_activation` to read it.
+
+    `scheme` is the name of the protocol without `:` or `//` (ex: "myapp"). It must start with a letter and only contain
+    letters, digits, `+`, `-` and `.`.
+*/
+pub fn register_url_protocol(scheme: &str, description: &str) -> Result<(), NwgError> {
+    check_scheme(scheme)?;
+
+    let exe = current_exe()?;
+    let classes = classes_key()?;
+
+    let protocol = classes.open_subkey(scheme, true)?;
+    protocol.set_string("", &format!("URL:{}", description))?;
+    protocol.set_string("URL Protocol", "")?;
+    protocol.open_subkey("DefaultIcon", true)?.set_string("", &format!("{},0", exe))?;
+    protocol.open_subkey("shell\\open\\command", true)?.set_string("", &format!("\"{}\" \"%1\"", exe))?;
+
+    Ok(())
+}
+
+/// Remove a URI protocol registered with `register_url_protocol`
+pub fn unregister_url_protocol(scheme: &str) -> Result<(), NwgError> {
+    check_scheme(scheme)?;
+
+    let classes = classes_key()?;
+    if classes.open_subkey(scheme, false).is_ok() {
+        classes.delete_subkey(scheme)?;
+    }
+
+    Ok(())
+}
+
+/**
+    Read the command line of the process and return the file or the URI the application was started with,
+    or `None` if the application was started without argument.
+
+    The first argument is a URI if it starts with a scheme followed by `:` (ex: "myapp://open" or "myapp:open").
+    Drive letters (ex: "C:\\file.txt") are not confused with a scheme. Otherwise the argument is a file path.
+*/
+pub fn launch_activation() -> Option<LaunchActivation> {
+    let arg = env::args_os().nth(1)?;
+    Some(parse_activation(arg))
+}
+
+fn parse_activation(arg: OsString) -> LaunchActivation {
+    if let Some(text) = arg.to_str() {
+        if let Some(index) = text.find(':') {
+            if index > 1 && check_scheme(&text[..index]).is_ok() {
+                return LaunchActivation::Url(text.to_string());
+            }
+        }
+    }
+
+    LaunchActivation::File(PathBuf::from(arg))
+}
+
+fn classes_key() -> Result<RegistryKey, NwgError> {
+    let mut key = RegistryKey::default();
+    RegistryKey::builder()
+        .root(RegistryRoot::CurrentUser)
+        .path(CLASSES_PATH)
+        .create(true)
+        .writable(true)
+        .build(&mut key)?;
+
+    Ok(key)
+}
+
+fn current_exe() -> Result<String, NwgError> {
+    env::current_exe()
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(|e| NwgError::resource_create(format!("Failed to read the path of the executable: {}", e)))
+}
+
+fn normalize_extension(extension: &str) -> Result<String, NwgError> {
+    let name = extension.trim_start_matches('.');
+    if name.is_empty() || name.contains(|c| c == '\\' || c == '/' || c == ' ') {
+        return Err(NwgError::resource_create(format!("Invalid file extension {:?}", extension)));
+    }
+
+    Ok(format!(".{}", name))
+}
+
+fn check_scheme(scheme: &str) -> Result<(), NwgError> {
+    let mut chars = scheme.chars();
+    let valid = match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() => chars.all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.'),
+        _ => false
+    };
+
+    match valid {
+        true => Ok(()),
+        false => Err(NwgError::resource_create(format!("Invalid URI scheme {:?}", scheme)))
+    }
+}
+
+fn notify_association_changed() {
+    unsafe { SHChangeNotify(SHCNE_ASSOCCHANGED, SHCNF_IDLIST, ptr::null(), ptr::null()); }
+}
@@ -1,9 +1,23 @@
-use winapi::um::shobjidl::IFileDialog;
+use winapi::um::shobjidl::{IFileDialog, IFileDialogEvents, IFileDialogEventsVtbl, IFileDialogCustomize, IFileOpenDialog, FDE_SHAREVIOLATION_RESPONSE, FDE_OVERWRITE_RESPONSE};
+use winapi::um::shobjidl_core::IShellItem;
+use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use winapi::shared::guiddef::{REFIID, IsEqualIID};
+use winapi::shared::minwindef::{DWORD, ULONG, TRUE, FALSE};
+use winapi::shared::winerror::{HRESULT, S_OK, S_FALSE, E_NOINTERFACE, E_NOTIMPL};
+use winapi::ctypes::c_void;
+use winapi::Interface;
 use crate::win32::resources_helper as rh;
 
 use crate::win32::base_helper::to_utf16;
 use crate::{ControlHandle, NwgError};
 use std::{fmt, ptr, mem, ffi::OsString};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// The id of the read-only checkbox added to the dialog
+const READ_ONLY_CHECKBOX_ID: DWORD = 1;
+
+type ValidationCallback = Box<dyn Fn(&[OsString]) -> Result<(), String>>;
 
 
 /**
@@ -30,6 +44,10 @@ pub enum FileDialogAction {
     * default_folder: Default folder to show in the dialog.
     * filters: If defined, filter the files that the user can select (In a Open dialog) or which extension to add to the saved file (in a Save dialog)
     The `filters` value must be a '|' separated string having this format: "Test(*.txt;*.rs)|Any(*.*)"  
+    * filter_index: The index of the filter selected when the dialog opens (starts at 0)
+    * read_only_checkbox: If defined, add a checkbox with this label and initial state to the dialog. Read its state with `read_only`.
+    * validation: A callback called with the selected item(s) before the dialog closes. If it returns an error, the message
+      is displayed to the user and the dialog stays open.
 
     ```rust
         use native_windows_gui as nwg;
@@ -40,11 +58,25 @@ pub enum FileDialogAction {
                 .multiselect(true)
                 .build(dialog);
         }
+
+        fn validated(dialog: &mut nwg::FileDialog) {
+            nwg::FileDialog::builder()
+                .action(nwg::FileDialogAction::Open)
+                .filters("Text(*.txt)|Any(*.*)")
+                .read_only_checkbox("Open as read-only", false)
+                .validation(|items| match items.len() > 10 {
+                    true => Err("Select 10 files or less".to_string()),
+                    false => Ok(())
+                })
+                .build(dialog);
+        }
     ```
 */
 pub struct FileDialog {
     handle: *mut IFileDialog,
-    action: FileDialogAction
+    action: FileDialogAction,
+    read_only: bool,
+    events: Cell<Option<(*mut FileDialogEventsHandler, DWORD)>>,
 }
 
 impl FileDialog {
@@ -55,7 +87,10 @@ impl FileDialog {
             action: FileDialogAction::Save,
             multiselect: false,
             default_folder: None,
-            filters: None
+            filters: None,
+            filter_index: None,
+            read_only: None,
+            validation: None,
         }
     }
 
@@ -83,6 +118,18 @@ impl FileDialog {
         unsafe { (&mut *self.handle).Show(parent_handle) == S_OK }
     }
 
+    /**
+        Returns a future that displays the dialog when it is awaited and resolves to the result of `run`.
+        Use it in a task started with `nwg::asyncio::spawn_local`: the event handler that starts the task is not blocked by the dialog.
+
+        Requires the `asyncio` feature.
+    */
+    #[cfg(feature = "asyncio")]
+    pub fn run_async<'a, C: Into<ControlHandle>>(&'a self, parent: Option<C>) -> impl std::future::Future<Output = bool> + 'a {
+        let parent = parent.map(|p| p.into());
+        crate::asyncio::deferred(move || self.run(parent))
+    }
+
     /**
         Return the item selected in the dialog by the user. 
         
@@ -177,6 +224,94 @@ impl FileDialog {
         }
     }
 
+    /**
+        Return the index of the filter selected by the user (starts at 0).
+        Returns 0 if the dialog has no filters.
+    */
+    pub fn filter_index(&self) -> u32 {
+        let mut index = 0;
+        unsafe {
+            let handle = &mut *self.handle;
+            handle.GetFileTypeIndex(&mut index);
+        }
+
+        // The dialog index starts at 1
+        index.saturating_sub(1)
+    }
+
+    /// Select the filter at `index` (starts at 0). Must be called after the filters are set.
+    pub fn set_filter_index(&self, index: u32) {
+        unsafe {
+            let handle = &mut *self.handle;
+            handle.SetFileTypeIndex(index + 1);
+        }
+    }
+
+    /**
+        Return the state of the read-only checkbox, or `None` if the dialog was built without one.
+        After the dialog ran, this is the value selected by the user.
+    */
+    pub fn read_only(&self) -> Option<bool> {
+        if !self.read_only {
+            return None;
+        }
+
+        unsafe {
+            let customize = query_customize(self.handle)?;
+            let mut checked = FALSE;
+            (&mut *customize).GetCheckButtonState(READ_ONLY_CHECKBOX_ID, &mut checked);
+            (&mut *customize).Release();
+            Some(checked == TRUE)
+        }
+    }
+
+    /// Check or uncheck the read-only checkbox. Does nothing if the dialog was built without one.
+    pub fn set_read_only(&self, checked: bool) {
+        if !self.read_only {
+            return;
+        }
+
+        unsafe {
+            if let Some(customize) = query_customize(self.handle) {
+                (&mut *customize).SetCheckButtonState(READ_ONLY_CHECKBOX_ID, checked as i32);
+                (&mut *customize).Release();
+            }
+        }
+    }
+
+    /**
+        Set a callback that validates the selected item(s) before the dialog closes. If the callback returns an error,
+        the message is displayed to the user and the dialog stays open. `None` removes the callback.
+
+        The callback must not use the dialog itself.
+    */
+    pub fn set_validation<F>(&self, callback: Option<F>) 
+        where F: Fn(&[OsString]) -> Result<(), String> + 'static
+    {
+        unsafe {
+            let handle = &mut *self.handle;
+
+            if let Some((events, cookie)) = self.events.take() {
+                handle.Unadvise(cookie);
+                events_release(events as *mut IUnknown);
+            }
+
+            if let Some(cb) = callback {
+                let events = Box::into_raw(Box::new(FileDialogEventsHandler {
+                    vtbl: &EVENTS_VTBL,
+                    refs: AtomicU32::new(1),
+                    callback: Box::new(cb),
+                }));
+
+                let mut cookie = 0;
+                match handle.Advise(events as *mut IFileDialogEvents, &mut cookie) {
+                    S_OK => self.events.set(Some((events, cookie))),
+                    _ => { events_release(events as *mut IUnknown); }
+                }
+            }
+        }
+    }
+
     /// Change the dialog title
     pub fn set_title<'a>(&self, title: &'a str) {
         unsafe {
@@ -209,7 +344,20 @@ impl Default for FileDialog {
     fn default() -> FileDialog {
         FileDialog {
             handle: ptr::null_mut(),
-            action: FileDialogAction::Open
+            action: FileDialogAction::Open,
+            read_only: false,
+            events: Cell::new(None),
+        }
+    }
+}
+
+impl Drop for FileDialog {
+    fn drop(&mut self) {
+        if let Some((events, cookie)) = self.events.take() {
+            unsafe {
+                (&mut *self.handle).Unadvise(cookie);
+                events_release(events as *mut IUnknown);
+            }
         }
     }
 }
@@ -230,7 +378,10 @@ pub struct FileDialogBuilder {
     pub action: FileDialogAction,
     pub multiselect: bool,
     pub default_folder: Option<String>,
-    pub filters: Option<String>
+    pub filters: Option<String>,
+    pub filter_index: Option<u32>,
+    pub read_only: Option<(String, bool)>,
+    pub validation: Option<ValidationCallback>,
 }
 
 impl FileDialogBuilder {
@@ -260,6 +411,23 @@ impl FileDialogBuilder {
         self
     }
 
+    pub fn filter_index(mut self, index: u32) -> FileDialogBuilder {
+        self.filter_index = Some(index);
+        self
+    }
+
+    pub fn read_only_checkbox<S: Into<String>>(mut self, label: S, checked: bool) -> FileDialogBuilder {
+        self.read_only = Some((label.into(), checked));
+        self
+    }
+
+    pub fn validation<F>(mut self, callback: F) -> FileDialogBuilder 
+        where F: Fn(&[OsString]) -> Result<(), String> + 'static
+    {
+        self.validation = Some(Box::new(callback));
+        self
+    }
+
     pub fn build(self, out: &mut FileDialog) -> Result<(), NwgError> {
         unsafe {
             out.handle = rh::create_file_dialog(
@@ -276,8 +444,172 @@ impl FileDialogBuilder {
             out.set_title(&title);
         }
 
+        if let Some(index) = self.filter_index {
+            out.set_filter_index(index);
+        }
+
+        if let Some((label, checked)) = self.read_only {
+            unsafe {
+                let customize = query_customize(out.handle)
+                    .ok_or_else(|| NwgError::file_dialog("Failed to customize the file dialog"))?;
+
+                let label = to_utf16(&label);
+                let result = (&mut *customize).AddCheckButton(READ_ONLY_CHECKBOX_ID, label.as_ptr(), checked as i32);
+                (&mut *customize).Release();
+
+                if result != S_OK {
+                    return Err(NwgError::file_dialog("Failed to add the read-only checkbox"));
+                }
+            }
+
+            out.read_only = true;
+        }
+
+        if let Some(validation) = self.validation {
+            out.set_validation(Some(validation));
+        }
+
         Ok(())
     }
 
 }
 
+unsafe fn query_customize(dialog: *mut IFileDialog) -> Option<*mut IFileDialogCustomize> {
+    let mut customize: *mut IFileDialogCustomize = ptr::null_mut();
+    let result = (&mut *dialog).QueryInterface(
+        &IFileDialogCustomize::uuidof(),
+        &mut customize as *mut *mut IFileDialogCustomize as *mut *mut c_void
+    );
+
+    match result {
+        S_OK => Some(customize),
+        _ => None
+    }
+}
+
+/// Read the item(s) currently selected in a dialog
+unsafe fn dialog_selection(dialog: &mut IFileDialog) -> Vec<OsString> {
+    use winapi::um::shobjidl::FOS_ALLOWMULTISELECT;
+
+    let flags = rh::file_dialog_options(dialog).unwrap_or(0);
+    if flags & FOS_ALLOWMULTISELECT == 0 {
+        return rh::filedialog_get_item(dialog).map(|i| vec![i]).unwrap_or_default();
+    }
+
+    let mut open: *mut IFileOpenDialog = ptr::null_mut();
+    let result = dialog.QueryInterface(
+        &IFileOpenDialog::uuidof(),
+        &mut open as *mut *mut IFileOpenDialog as *mut *mut c_void
+    );
+
+    if result != S_OK {
+        return Vec::new();
+    }
+
+    let items = rh::filedialog_get_items(&mut *open).unwrap_or_default();
+    (&mut *open).Release();
+
+    items
+}
+
+
+/*
+    A minimal `IFileDialogEvents` implementation that calls the validation callback when the user accepts the dialog.
+*/
+#[repr(C)]
+struct FileDialogEventsHandler {
+    vtbl: *const IFileDialogEventsVtbl,
+    refs: AtomicU32,
+    callback: ValidationCallback,
+}
+
+static EVENTS_VTBL: IFileDialogEventsVtbl = IFileDialogEventsVtbl {
+    parent: IUnknownVtbl {
+        QueryInterface: events_query_interface,
+        AddRef: events_add_ref,
+        Release: events_release,
+    },
+    OnFileOk: events_file_ok,
+    OnFolderChanging: events_folder_changing,
+    OnFolderChange: events_ignore,
+    OnSelectionChange: events_ignore,
+    OnShareViolation: events_share_violation,
+    OnTypeChange: events_ignore,
+    OnOverwrite: events_overwrite,
+};
+
+unsafe extern "system" fn events_query_interface(this: *mut IUnknown, riid: REFIID, out: *mut *mut c_void) -> HRESULT {
+    let riid = &*riid;
+    if IsEqualIID(riid, &IUnknown::uuidof()) || IsEqualIID(riid, &IFileDialogEvents::uuidof()) {
+        events_add_ref(this);
+        *out = this as *mut c_void;
+        S_OK
+    } else {
+        *out = ptr::null_mut();
+        E_NOINTERFACE
+    }
+}
+
+unsafe extern "system" fn events_add_ref(this: *mut IUnknown) -> ULONG {
+    let handler = &*(this as *mut FileDialogEventsHandler);
+    handler.refs.fetch_add(1, Ordering::SeqCst) + 1
+}
+
+unsafe extern "system" fn events_release(this: *mut IUnknown) -> ULONG {
+    let refs = {
+        let handler = &*(this as *mut FileDialogEventsHandler);
+        handler.refs.fetch_sub(1, Ordering::SeqCst) - 1
+    };
+
+    if refs == 0 {
+        drop(Box::from_raw(this as *mut FileDialogEventsHandler));
+    }
+
+    refs
+}
+
+unsafe extern "system" fn events_file_ok(this: *mut IFileDialogEvents, dialog: *mut IFileDialog) -> HRESULT {
+    use winapi::um::winuser::GetActiveWindow;
+    use crate::win32::window_helper as wh;
+    use crate::{MessageParams, MessageButtons, MessageIcons};
+
+    let handler = &*(this as *mut FileDialogEventsHandler);
+    let items = dialog_selection(&mut *dialog);
+
+    match (handler.callback)(&items) {
+        Ok(()) => S_OK,
+        Err(msg) => {
+            // The file dialog is the active window while this event runs
+            let hwnd = GetActiveWindow();
+            let title = match hwnd.is_null() {
+                true => String::new(),
+                false => wh::get_window_text(hwnd)
+            };
+
+            let params = MessageParams { title: &title, content: &msg, buttons: MessageButtons::Ok, icons: MessageIcons::Warning };
+            match hwnd.is_null() {
+                true => { crate::message(&params); },
+                false => { crate::modal_message(ControlHandle::Hwnd(hwnd), &params); }
+            }
+
+            S_FALSE
+        }
+    }
+}
+
+unsafe extern "system" fn events_folder_changing(_this: *mut IFileDialogEvents, _dialog: *mut IFileDialog, _folder: *mut IShellItem) -> HRESULT {
+    S_OK
+}
+
+unsafe extern "system" fn events_ignore(_this: *mut IFileDialogEvents, _dialog: *mut IFileDialog) -> HRESULT {
+    S_OK
+}
+
+unsafe extern "system" fn events_share_violation(_this: *mut IFileDialogEvents, _dialog: *mut IFileDialog, _item: *mut IShellItem, _response: *mut FDE_SHAREVIOLATION_RESPONSE) -> HRESULT {
+    E_NOTIMPL
+}
+
+unsafe extern "system" fn events_overwrite(_this: *mut IFileDialogEvents, _dialog: *mut IFileDialog, _item: *mut IShellItem, _response: *mut FDE_OVERWRITE_RESPONSE) -> HRESULT {
+    E_NOTIMPL
+}
+
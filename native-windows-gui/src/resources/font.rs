@@ -1,8 +1,9 @@
-use winapi::shared::windef::HFONT;
+use winapi::shared::windef::{HFONT, HWND};
+use winapi::um::wingdi::LOGFONTW;
 use winapi::um::winnt::HANDLE;
 use crate::win32::resources_helper as rh;
 use crate::win32::base_helper::{to_utf16, from_utf16};
-use crate::NwgError;
+use crate::{NwgError, ControlHandle};
 use std::ptr;
 
 use std::sync::Mutex;
@@ -56,6 +57,58 @@ pub struct FontInfo {
     pub name: String
 }
 
+impl FontInfo {
+
+    pub(crate) fn from_logfont(font: &LOGFONTW, point_size: u32) -> FontInfo {
+        let end = font.lfFaceName.iter().position(|&c| c == 0).unwrap_or(0);
+        let name = String::from_utf16(&font.lfFaceName[0..end]).unwrap_or("ERROR".to_string());
+
+        FontInfo {
+            point_size,
+            height: font.lfHeight as i32,
+            width: font.lfWidth as i32,
+            escapement: font.lfEscapement as i32,
+            orientation: font.lfOrientation as i32,
+            weight: font.lfWeight as i32,
+            italic: font.lfItalic == 1,
+            underline: font.lfUnderline == 1,
+            strike_out: font.lfStrikeOut == 1,
+            char_set: font.lfCharSet as u8,
+            out_precision: font.lfOutPrecision as u8,
+            clip_precision: font.lfClipPrecision as u8,
+            quality: font.lfQuality as u8,
+            pitch_and_family: font.lfPitchAndFamily as u8,
+            name
+        }
+    }
+
+    pub(crate) fn to_logfont(&self) -> LOGFONTW {
+        let mut font: LOGFONTW = unsafe { std::mem::zeroed() };
+        font.lfHeight = self.height;
+        font.lfWidth = self.width;
+        font.lfEscapement = self.escapement;
+        font.lfOrientation = self.orientation;
+        font.lfWeight = self.weight;
+        font.lfItalic = self.italic as u8;
+        font.lfUnderline = self.underline as u8;
+        font.lfStrikeOut = self.strike_out as u8;
+        font.lfCharSet = self.char_set;
+        font.lfOutPrecision = self.out_precision;
+        font.lfClipPrecision = self.clip_precision;
+        font.lfQuality = self.quality;
+        font.lfPitchAndFamily = self.pitch_and_family;
+
+        // The face name is truncated to 31 characters to keep the null terminator
+        let max = font.lfFaceName.len() - 1;
+        for (dst, src) in font.lfFaceName.iter_mut().zip(self.name.encode_utf16().take(max)) {
+            *dst = src;
+        }
+
+        font
+    }
+
+}
+
 
 /**
 
@@ -115,6 +168,22 @@ impl Font {
         Ok(Font::set_global_default(Some(font)))
     }
 
+    /**
+        Create a font from a `FontInfo`. Use this to rebuild the font selected in a `FontDialog`,
+        or a font description that was saved by the application.
+    */
+    pub fn from_info(info: &FontInfo) -> Result<Font, NwgError> {
+        use winapi::um::wingdi::CreateFontIndirectW;
+
+        let logfont = info.to_logfont();
+        let handle = unsafe { CreateFontIndirectW(&logfont) };
+        if handle.is_null() {
+            Err( NwgError::resource_create("Failed to create font") )
+        } else {
+            Ok( Font { handle } )
+        }
+    }
+
     /// Return the default font that was previously set using `Font::set_default`
     pub fn global_default() -> Option<Font> {
         DEFAULT_FONT.lock()
@@ -242,7 +311,8 @@ Parameters:
 pub struct FontBuilder<'a> {
     size: Option<i32>,
     weight: u32,
-    family: Option<&'a str>
+    family: Option<&'a str>,
+    window: HWND,
 }
 
 impl<'a> FontBuilder<'a> {
@@ -252,6 +322,7 @@ impl<'a> FontBuilder<'a> {
             size: None,
             weight: 0,
             family: None,
+            window: ptr::null_mut(),
         }
     }
 
@@ -275,6 +346,13 @@ impl<'a> FontBuilder<'a> {
         self
     }
 
+    /// The window where the font is used. The size is scaled with the dpi of its monitor instead of the system dpi.
+    /// Panics if the control is not a window-like control.
+    pub fn window<C: Into<ControlHandle>>(mut self, window: C) -> FontBuilder<'a> {
+        self.window = window.into().hwnd().expect("The window of a font must be a window-like control");
+        self
+    }
+
     pub fn build(self, font: &mut Font) -> Result<(), NwgError> {
         
 
@@ -282,7 +360,8 @@ impl<'a> FontBuilder<'a> {
             self.size.unwrap_or(0),
             self.weight,
             [false, false, false],
-            self.family
+            self.family,
+            self.window
         ) }?;
 
         Ok(())
@@ -1,6 +1,8 @@
-use winapi::shared::minwindef::DWORD;
-use winapi::um::commdlg::{CHOOSEFONTW, ChooseFontW};
-use winapi::um::wingdi::{LOGFONTW};
+use winapi::shared::minwindef::{DWORD, UINT, WPARAM, LPARAM};
+use winapi::shared::windef::{HWND, COLORREF};
+use winapi::shared::basetsd::UINT_PTR;
+use winapi::um::commdlg::{CHOOSEFONTW, ChooseFontW, CF_SCREENFONTS, CF_EFFECTS, CF_APPLY, CF_ENABLEHOOK, CF_INITTOLOGFONTSTRUCT};
+use winapi::um::wingdi::{LOGFONTW, GetBValue, GetRValue, GetGValue, RGB};
 use super::FontInfo;
 use crate::controls::ControlHandle;
 use crate::NwgError;
@@ -8,16 +10,53 @@ use std::cell::{RefCell};
 use std::{ptr, mem};
 use std::pin::Pin;
 
+/// The id of the "Apply" button of the font dialog (`psh3` in dlgs.h)
+const APPLY_BUTTON_ID: u16 = 0x0402;
+
+/// The id of the color combobox of the font dialog (`cmb4` in dlgs.h)
+const COLOR_COMBO_ID: i32 = 0x0473;
+
+type ApplyCallback = Box<dyn Fn(&FontInfo, [u8; 3])>;
+
 
 struct InnerFontDialog {
     font: Pin<Box<LOGFONTW>>,
+    apply: Option<Pin<Box<ApplyCallback>>>,
     dialog: CHOOSEFONTW,
 }
 
 
-/// The Font dialog box lets the user choose attributes for a logical font, such as
-/// font family and associated font style, point size, effects (underline, strikeout),
-/// and a script (or character set).
+/**
+The Font dialog box lets the user choose attributes for a logical font, such as
+font family and associated font style, point size, effects (underline, strikeout),
+and a script (or character set).
+
+The effects section (color, underline, strikeout) is only displayed if `effects` is set in the builder.
+If an apply callback is set, the dialog displays an "Apply" button. Each time it is clicked, the callback is called
+with the font and the color currently selected in the dialog, so the application can preview the font without closing the dialog.
+The callback must not use the dialog itself.
+
+`set_font` initializes the dialog with a font. It accepts the `FontInfo` returned by `font`, so a font selection
+can be saved by the application and restored later. Use `Font::from_info` to create the selected font.
+
+Requires the `font-dialog` feature.
+
+```rust
+use native_windows_gui as nwg;
+
+fn build_dialog(dialog: &mut nwg::FontDialog, label: std::rc::Rc<nwg::Label>) {
+    nwg::FontDialog::builder()
+        .effects(true)
+        .apply_callback(move |info, _color| {
+            if let Ok(font) = nwg::Font::from_info(info) {
+                label.set_font(Some(&font));
+            }
+        })
+        .build(dialog)
+        .unwrap();
+}
+```
+*/
 pub struct FontDialog {
     data: RefCell<InnerFontDialog>,
 }
@@ -25,7 +64,12 @@ pub struct FontDialog {
 impl FontDialog {
 
     pub fn builder() -> FontDialogBuilder {
-        FontDialogBuilder {}
+        FontDialogBuilder {
+            effects: false,
+            font: None,
+            color: [0, 0, 0],
+            apply: None,
+        }
     }
 
     /// Execute the font dialog.
@@ -36,37 +80,77 @@ impl FontDialog {
             self.data.borrow_mut().dialog.hwndOwner = ownder_handle.hwnd().expect("Color dialog must be a window control");
         }
 
-        unsafe {
+        // The data is not borrowed while the dialog runs because the apply callback may be called
+        let dialog_ptr = {
             let mut data = self.data.borrow_mut();
-            let data_ref = &mut data.dialog;
-            ChooseFontW(data_ref as *mut CHOOSEFONTW) > 0
+            &mut data.dialog as *mut CHOOSEFONTW
+        };
+
+        unsafe {
+            ChooseFontW(dialog_ptr) > 0
         }
     }
 
     /// Return a `FontInfo` structure that describe the font selected by the user.
     pub fn font(&self) -> FontInfo {
         let data: &InnerFontDialog = &self.data.borrow();
-        let font: &LOGFONTW = &data.font;
-
-        let end = font.lfFaceName.iter().position(|&c| c == 0).unwrap_or(0);
-        let name = String::from_utf16(&font.lfFaceName[0..end]).unwrap_or("ERROR".to_string());
-
-        FontInfo {
-            point_size: data.dialog.iPointSize as u32,
-            height: font.lfHeight as i32,
-            width: font.lfWidth as i32,
-            escapement: font.lfEscapement as i32,
-            orientation: font.lfOrientation as i32,
-            weight: font.lfWeight as i32,
-            italic: font.lfItalic == 1,
-            underline: font.lfUnderline == 1,
-            strike_out: font.lfStrikeOut == 1,
-            char_set: font.lfCharSet as u8,
-            out_precision: font.lfOutPrecision as u8,
-            clip_precision: font.lfClipPrecision as u8,
-            quality: font.lfQuality as u8,
-            pitch_and_family: font.lfPitchAndFamily as u8,
-            name
+        FontInfo::from_logfont(&data.font, data.dialog.iPointSize as u32)
+    }
+
+    /// Initialize the dialog with a font. The next time the dialog runs, this font will be selected.
+    pub fn set_font(&self, font: &FontInfo) {
+        let mut data = self.data.borrow_mut();
+        *data.font = font.to_logfont();
+        data.dialog.Flags |= CF_INITTOLOGFONTSTRUCT;
+    }
+
+    /// Return the text color selected by the user. Only meaningful if the effects are displayed.
+    pub fn color(&self) -> [u8; 3] {
+        let v = self.data.borrow().dialog.rgbColors;
+        [GetRValue(v), GetGValue(v), GetBValue(v)]
+    }
+
+    /// Set the text color selected when the dialog runs. Only meaningful if the effects are displayed.
+    pub fn set_color(&self, color: &[u8; 3]) {
+        self.data.borrow_mut().dialog.rgbColors = RGB(color[0], color[1], color[2]);
+    }
+
+    /// Return true if the effects section (color, underline, strikeout) is displayed
+    pub fn effects(&self) -> bool {
+        self.data.borrow().dialog.Flags & CF_EFFECTS == CF_EFFECTS
+    }
+
+    /// Show or hide the effects section (color, underline, strikeout) of the dialog
+    pub fn set_effects(&self, effects: bool) {
+        let mut data = self.data.borrow_mut();
+        match effects {
+            true => { data.dialog.Flags |= CF_EFFECTS; },
+            false => { data.dialog.Flags &= !CF_EFFECTS; }
+        }
+    }
+
+    /**
+        Set the callback called when the user clicks on the "Apply" button of the dialog. The callback receives the font
+        and the color selected in the dialog. If the callback is `None`, the "Apply" button is not displayed.
+    */
+    pub fn set_apply_callback<F: Fn(&FontInfo, [u8; 3]) + 'static>(&self, callback: Option<F>) {
+        let mut data = self.data.borrow_mut();
+        let data = &mut *data;
+
+        match callback {
+            Some(cb) => {
+                let callback: Pin<Box<ApplyCallback>> = Box::pin(Box::new(cb));
+                data.dialog.lCustData = &*callback as *const ApplyCallback as LPARAM;
+                data.dialog.lpfnHook = Some(font_dialog_hook);
+                data.dialog.Flags |= CF_APPLY | CF_ENABLEHOOK;
+                data.apply = Some(callback);
+            },
+            None => {
+                data.dialog.lCustData = 0;
+                data.dialog.lpfnHook = None;
+                data.dialog.Flags &= !(CF_APPLY | CF_ENABLEHOOK);
+                data.apply = None;
+            }
         }
     }
 
@@ -75,11 +159,52 @@ impl FontDialog {
 
 /// The builder for a `FontDialog` object. Use `FontDialog::builder` to create one.
 pub struct FontDialogBuilder {
+    effects: bool,
+    font: Option<FontInfo>,
+    color: [u8; 3],
+    apply: Option<ApplyCallback>,
 }
 
 impl FontDialogBuilder {
 
-    pub fn build(self, _out: &mut FontDialog) -> Result<(), NwgError> {
+    /// Display the effects section (color, underline, strikeout) of the dialog
+    pub fn effects(mut self, effects: bool) -> FontDialogBuilder {
+        self.effects = effects;
+        self
+    }
+
+    /// The font selected when the dialog runs
+    pub fn font(mut self, font: Option<FontInfo>) -> FontDialogBuilder {
+        self.font = font;
+        self
+    }
+
+    /// The text color selected when the dialog runs
+    pub fn color(mut self, color: [u8; 3]) -> FontDialogBuilder {
+        self.color = color;
+        self
+    }
+
+    /// Display an "Apply" button that calls `callback` with the font and the color selected in the dialog
+    pub fn apply_callback<F: Fn(&FontInfo, [u8; 3]) + 'static>(mut self, callback: F) -> FontDialogBuilder {
+        self.apply = Some(Box::new(callback));
+        self
+    }
+
+    pub fn build(self, out: &mut FontDialog) -> Result<(), NwgError> {
+        *out = Default::default();
+
+        out.set_effects(self.effects);
+        out.set_color(&self.color);
+
+        if let Some(font) = self.font.as_ref() {
+            out.set_font(font);
+        }
+
+        if let Some(apply) = self.apply {
+            out.set_apply_callback(Some(apply));
+        }
+
         Ok(())
     }
 
@@ -94,7 +219,7 @@ impl Default for FontDialog {
             hDC: ptr::null_mut(),
             lpLogFont: ptr::null_mut(),
             iPointSize: 0,
-            Flags: 0,
+            Flags: CF_SCREENFONTS,
             rgbColors: 0,
             lCustData: 0,
             lpfnHook: None,
@@ -111,6 +236,7 @@ impl Default for FontDialog {
 
         let mut inner = InnerFontDialog {
             font: Box::pin(font),
+            apply: None,
             dialog,
         };
 
@@ -124,3 +250,46 @@ impl Default for FontDialog {
     }
 
 }
+
+/// Hook procedure of the font dialog. Calls the apply callback when the "Apply" button is clicked.
+unsafe extern "system" fn font_dialog_hook(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> UINT_PTR {
+    use winapi::um::winuser::{WM_INITDIALOG, WM_COMMAND, GWLP_USERDATA, CB_GETCURSEL, CB_GETITEMDATA, CB_ERR, SendMessageW, SendDlgItemMessageW, GetDC, ReleaseDC};
+    use winapi::um::commdlg::WM_CHOOSEFONT_GETLOGFONT;
+    use winapi::um::wingdi::{GetDeviceCaps, LOGPIXELSY};
+    use winapi::um::winbase::MulDiv;
+    use winapi::shared::minwindef::LOWORD;
+    use crate::win32::window_helper as wh;
+
+    match msg {
+        WM_INITDIALOG => {
+            let params = &*(l as *const CHOOSEFONTW);
+            wh::set_window_long(hwnd, GWLP_USERDATA, params.lCustData as usize);
+        },
+        WM_COMMAND if LOWORD(w as DWORD) == APPLY_BUTTON_ID => {
+            let callback = wh::get_window_long(hwnd, GWLP_USERDATA) as *const ApplyCallback;
+            if callback.is_null() {
+                return 0;
+            }
+
+            let mut font: LOGFONTW = mem::zeroed();
+            SendMessageW(hwnd, WM_CHOOSEFONT_GETLOGFONT, 0, &mut font as *mut LOGFONTW as LPARAM);
+
+            // The point size is only computed by the dialog when it closes
+            let dc = GetDC(ptr::null_mut());
+            let point_size = MulDiv(font.lfHeight.abs(), 720, GetDeviceCaps(dc, LOGPIXELSY));
+            ReleaseDC(ptr::null_mut(), dc);
+
+            let index = SendDlgItemMessageW(hwnd, COLOR_COMBO_ID, CB_GETCURSEL, 0, 0);
+            let color = match index {
+                CB_ERR => 0,
+                i => SendDlgItemMessageW(hwnd, COLOR_COMBO_ID, CB_GETITEMDATA, i as WPARAM, 0) as COLORREF
+            };
+
+            let info = FontInfo::from_logfont(&font, point_size.max(0) as u32);
+            (*callback)(&info, [GetRValue(color), GetGValue(color), GetBValue(color)]);
+        },
+        _ => {}
+    }
+
+    0
+}
@@ -126,6 +126,75 @@ impl Icon {
         Ok(icon)
     }
 
+    /**
+        Creates a new icon from RGBA pixels. `data` must contain `width * height * 4` bytes, row by row from the top left corner.
+
+        If `hotspot` is defined, the icon is created as a cursor with its hotspot at the specified position.
+    */
+    pub fn from_rgba(width: u32, height: u32, data: &[u8], hotspot: Option<(u32, u32)>) -> Result<Icon, NwgError> {
+        let handle = unsafe { rh::icon_from_rgba(width, height, data, hotspot)? };
+        Ok(Icon { handle, owned: true })
+    }
+
+    /**
+        Returns the icon pixels as RGBA in a `(width, height, pixels)` tuple.
+
+        Panics if the icon is not initialized
+    */
+    pub fn to_rgba(&self) -> Result<(u32, u32, Vec<u8>), NwgError> {
+        if self.handle.is_null() {
+            panic!("Icon was not initialized");
+        }
+
+        unsafe { rh::icon_to_rgba(self.handle) }
+    }
+
+    /**
+        Creates a copy of the icon with a badge drawn in the bottom right corner. The badge is a rounded label
+        displaying `text` (ex: an unread count) in `foreground` over `background`. Colors are RGB.
+
+        The same icon can be used for a window and a tray notification. The icon must be kept alive as long as it is used.
+
+        ```rust
+        use native_windows_gui as nwg;
+        fn notify_unread(window: &nwg::Window, tray: &nwg::TrayNotification, base: &nwg::Icon, count: u32) -> nwg::Icon {
+            let badged = base.with_badge(&count.to_string(), [220, 30, 30], [255, 255, 255]).unwrap();
+            window.set_icon_small(Some(&badged));
+            tray.set_icon(&badged);
+            badged
+        }
+        ```
+
+        Panics if the icon is not initialized
+    */
+    pub fn with_badge(&self, text: &str, background: [u8; 3], foreground: [u8; 3]) -> Result<Icon, NwgError> {
+        let (w, h, mut pixels) = self.to_rgba()?;
+        unsafe { rh::draw_badge(w, h, &mut pixels, text, background, foreground)?; }
+        Icon::from_rgba(w, h, &pixels, None)
+    }
+
+    /**
+        Renders a svg document into a new icon. See `Bitmap::from_svg` for the meaning of `size` and `dpi`.
+
+        Requires the `svg` feature.
+    */
+    #[cfg(feature = "svg")]
+    pub fn from_svg(data: &[u8], size: Option<(u32, u32)>, dpi: Option<u32>) -> Result<Icon, NwgError> {
+        let (w, h, pixels) = super::svg::render_svg(data, size, dpi)?;
+        Icon::from_rgba(w, h, &pixels, None)
+    }
+
+    /**
+        Renders a svg file into a new icon. See `Bitmap::from_svg` for the meaning of `size` and `dpi`.
+
+        Requires the `svg` feature.
+    */
+    #[cfg(feature = "svg")]
+    pub fn from_svg_file(path: &str, size: Option<(u32, u32)>, dpi: Option<u32>) -> Result<Icon, NwgError> {
+        let (w, h, pixels) = super::svg::render_svg_file(path, size, dpi)?;
+        Icon::from_rgba(w, h, &pixels, None)
+    }
+
     /**
         Single line helper function over the icon builder api.
 
@@ -1,6 +1,6 @@
 use winapi::um::commctrl::{HIMAGELIST, ImageList_AddMasked};
-use winapi::shared::windef::{HICON, HBITMAP};
-use crate::{Bitmap, Icon, NwgError};
+use winapi::shared::windef::{HICON, HBITMAP, HWND};
+use crate::{Bitmap, Icon, NwgError, ControlHandle};
 use std::ptr;
 
 
@@ -14,10 +14,14 @@ There are two kinds of image list in Winapi: masked. This is a wrapper over the
 
 Image list and the method that use them in controls are behind the "image-list" feature. 
 
+An image list can also be created from a sprite sheet using `ImageList::from_sprite_sheet`.
+
 **Builder parameters:**
   * `size`:    The size size of the images in the image list. Default `(32, 32)`
   * `initial`: The initial size (in images) of the image list. Default `5`
   * `grow`:    The number of images by which the image list can grow when the system needs to make room for new images. Default `5`
+  * `dpi_scaled`: If true, `size` is a logical size and it is scaled by the dpi of `window`. Requires the "high-dpi" feature. Default `false`
+  * `window`:  The window where the images are displayed. Its dpi scales `size`. Default: the system dpi
 
 ```rust
 use native_windows_gui as nwg;
@@ -42,10 +46,30 @@ impl ImageList {
         ImageListBuilder {
             size: (32, 32),
             initial: 5,
-            grow: 5
+            grow: 5,
+            dpi_scaled: false,
+            window: ptr::null_mut(),
         }
     }
 
+    /**
+        Creates an image list from a sprite sheet. The sheet is split into tiles of `tile_size`, from left to right and from top to bottom.
+        Incomplete tiles on the right and bottom edges of the sheet are ignored.
+
+        Returns an error if the sheet could not be read or if it is smaller than a single tile. Panics if the bitmap was not initialized.
+    */
+    pub fn from_sprite_sheet(sheet: &Bitmap, tile_size: (i32, i32)) -> Result<ImageList, NwgError> {
+        let mut list = ImageList::default();
+        ImageList::builder()
+            .size(tile_size)
+            .initial(0)
+            .build(&mut list)?;
+
+        list.add_sprite_sheet(sheet)?;
+
+        Ok(list)
+    }
+
     /// Returns the size of the images in the image list
     pub fn size(&self) -> (i32, i32) {
         use winapi::um::commctrl::ImageList_GetIconSize;
@@ -85,6 +109,68 @@ impl ImageList {
         unsafe { ImageList_AddMasked(self.handle, bitmap.handle as HBITMAP, 0) }
     }
 
+    /**
+        Adds a new bitmap to the image list using `mask` as the transparency mask of the image.
+        Returns the index to the image. Panics if the bitmap or the mask was not initialized
+    */
+    pub fn add_bitmap_with_mask(&self, bitmap: &Bitmap, mask: &Bitmap) -> i32 {
+        use winapi::um::commctrl::ImageList_Add;
+
+        if self.handle.is_null() { panic!("{}", NOT_BOUND); }
+        if bitmap.handle.is_null() { panic!("Bitmap was not initialized"); }
+        if mask.handle.is_null() { panic!("Mask was not initialized"); }
+
+        unsafe { ImageList_Add(self.handle, bitmap.handle as HBITMAP, mask.handle as HBITMAP) }
+    }
+
+    /**
+        Adds a new image from RGBA pixels. The pixels must have the same size as the image list.
+        Returns the index to the image or an error if the image could not be created.
+    */
+    pub fn add_rgba(&self, data: &[u8]) -> Result<i32, NwgError> {
+        if self.handle.is_null() { panic!("{}", NOT_BOUND); }
+
+        let bitmap = self.bitmap_from_rgba(data)?;
+        Ok(self.add_bitmap(&bitmap))
+    }
+
+    /**
+        Splits `sheet` into tiles of the image list size and adds them to the image list, from left to right
+        and from top to bottom. Incomplete tiles on the right and bottom edges of the sheet are ignored.
+
+        Returns the indices of the new images or an error if the sheet could not be read. Panics if the bitmap was not initialized.
+    */
+    pub fn add_sprite_sheet(&self, sheet: &Bitmap) -> Result<Vec<i32>, NwgError> {
+        if self.handle.is_null() { panic!("{}", NOT_BOUND); }
+
+        let (tile_w, tile_h) = self.size();
+        let (tile_w, tile_h) = (tile_w as usize, tile_h as usize);
+        let (sheet_w, sheet_h, pixels) = sheet.to_rgba()?;
+        let (sheet_w, sheet_h) = (sheet_w as usize, sheet_h as usize);
+
+        if tile_w == 0 || tile_h == 0 || sheet_w < tile_w || sheet_h < tile_h {
+            return Err(NwgError::resource_create("Sprite sheet is smaller than the image list size"));
+        }
+
+        let mut indices = Vec::with_capacity((sheet_w / tile_w) * (sheet_h / tile_h));
+        let mut tile = vec![0u8; tile_w * tile_h * 4];
+        let row_size = tile_w * 4;
+
+        for tile_y in 0..(sheet_h / tile_h) {
+            for tile_x in 0..(sheet_w / tile_w) {
+                for y in 0..tile_h {
+                    let src = (((tile_y * tile_h) + y) * sheet_w + (tile_x * tile_w)) * 4;
+                    let dst = y * row_size;
+                    tile[dst..(dst+row_size)].copy_from_slice(&pixels[src..(src+row_size)]);
+                }
+
+                indices.push(self.add_rgba(&tile)?);
+            }
+        }
+
+        Ok(indices)
+    }
+
     /**
         Adds a bitmap directly from a filename. The image is resized to the image list size.
         Returns the index to the image or an error if the image could not be loaded
@@ -169,6 +255,27 @@ impl ImageList {
         unsafe { ImageList_Replace(self.handle, index, bitmap.handle as HBITMAP, ptr::null_mut()); }
     }
 
+    /// Replaces an image in the image list using `mask` as the transparency mask of the new image. Panics if the bitmap or the mask was not initialized
+    pub fn replace_bitmap_with_mask(&self, index: i32, bitmap: &Bitmap, mask: &Bitmap) {
+        use winapi::um::commctrl::ImageList_Replace;
+
+        if self.handle.is_null() { panic!("{}", NOT_BOUND); }
+        if bitmap.handle.is_null() { panic!("Bitmap was not initialized"); }
+        if mask.handle.is_null() { panic!("Mask was not initialized"); }
+
+        unsafe { ImageList_Replace(self.handle, index, bitmap.handle as HBITMAP, mask.handle as HBITMAP); }
+    }
+
+    /// Replaces an image in the image list by RGBA pixels. The pixels must have the same size as the image list.
+    pub fn replace_rgba(&self, index: i32, data: &[u8]) -> Result<(), NwgError> {
+        if self.handle.is_null() { panic!("{}", NOT_BOUND); }
+
+        let bitmap = self.bitmap_from_rgba(data)?;
+        self.replace_bitmap(index, &bitmap);
+
+        Ok(())
+    }
+
     /// Replaces an image in the image list by an icon. Panics if the icon was not initialized
     pub fn replace_icon(&self, index: i32, icon: &Icon) {
         use winapi::um::commctrl::ImageList_ReplaceIcon;
@@ -179,6 +286,24 @@ impl ImageList {
         unsafe { ImageList_ReplaceIcon(self.handle, index, icon.handle as HICON); }
     }
 
+    /// Removes every image from the image list
+    pub fn clear(&self) {
+        use winapi::um::commctrl::ImageList_RemoveAll;
+
+        if self.handle.is_null() { panic!("{}", NOT_BOUND); }
+
+        unsafe { ImageList_RemoveAll(self.handle); }
+    }
+
+    fn bitmap_from_rgba(&self, data: &[u8]) -> Result<Bitmap, NwgError> {
+        let (w, h) = self.size();
+        if data.len() != (w as usize) * (h as usize) * 4 {
+            return Err(NwgError::resource_create("Image data does not match the image list size"));
+        }
+
+        Bitmap::from_rgba(w as u32, h as u32, data)
+    }
+
 }
 
 impl Drop for ImageList {
@@ -213,6 +338,8 @@ pub struct ImageListBuilder {
     size: (i32, i32),
     initial: i32,
     grow: i32,
+    dpi_scaled: bool,
+    window: HWND,
 }
 
 impl ImageListBuilder {
@@ -232,11 +359,26 @@ impl ImageListBuilder {
         self
     }
 
+    pub fn dpi_scaled(mut self, scaled: bool) -> ImageListBuilder {
+        self.dpi_scaled = scaled;
+        self
+    }
+
+    pub fn window<C: Into<ControlHandle>>(mut self, window: C) -> ImageListBuilder {
+        self.window = window.into().hwnd().expect("The window of an image list must be a window-like control");
+        self
+    }
+
     pub fn build(self, list: &mut ImageList) -> Result<(), NwgError> {
         use winapi::um::commctrl::{ImageList_Create, ILC_COLOR32, ILC_MASK};
+        use crate::win32::high_dpi::logical_to_physical_for;
 
         unsafe {
-            let (w, h) = self.size;
+            let (w, h) = match self.dpi_scaled {
+                true => logical_to_physical_for(self.window, self.size.0, self.size.1),
+                false => self.size
+            };
+
             let handle = ImageList_Create(w, h, ILC_COLOR32 | ILC_MASK, self.initial, self.grow);
             if handle.is_null() {
                 return Err(NwgError::resource_create("Failed to create image list"));
@@ -16,12 +16,33 @@ mod color_dialog;
 #[cfg(feature = "font-dialog")]
 mod font_dialog;
 
+#[cfg(feature = "credential-dialog")]
+mod credential_dialog;
+
+#[cfg(feature = "certificate-dialog")]
+mod certificate_dialog;
+
+#[cfg(feature = "progress-dialog")]
+mod progress_dialog;
+
+#[cfg(feature = "printing")]
+mod print_document;
+
+#[cfg(feature = "registry")]
+mod registry;
+
+#[cfg(feature = "registry")]
+mod file_association;
+
 #[cfg(feature = "image-list")]
 mod image_list;
 
 #[cfg(feature = "embed-resource")]
 mod embed;
 
+#[cfg(feature = "svg")]
+pub(crate) mod svg;
+
 pub use font::{Font, MemFont, FontInfo, FontBuilder};
 pub use system_images::*;
 pub use icon::{Icon, IconBuilder};
@@ -40,6 +61,28 @@ pub use color_dialog::{ColorDialog, ColorDialogBuilder};
 #[cfg(feature = "font-dialog")]
 pub use font_dialog::{FontDialog, FontDialogBuilder};
 
+#[cfg(feature = "credential-dialog")]
+pub use credential_dialog::{CredentialDialog, CredentialDialogBuilder, Credentials};
+
+#[cfg(feature = "certificate-dialog")]
+pub use certificate_dialog::{CertificateDialog, CertificateDialogBuilder, Certificate};
+
+#[cfg(feature = "progress-dialog")]
+pub use progress_dialog::{ProgressDialog, ProgressDialogBuilder, ProgressDialogFlags, ProgressDialogSender};
+
+#[cfg(feature = "printing")]
+pub use print_document::{PrintDocument, PrintDocumentBuilder, PrintPage};
+
+#[cfg(feature = "printing")]
+pub(crate) use print_document::DocumentState;
+
+#[cfg(feature = "registry")]
+pub use registry::{RegistryKey, RegistryKeyBuilder, RegistryRoot};
+
+#[cfg(feature = "registry")]
+pub use file_association::{FileAssociation, LaunchActivation, register_file_extension, unregister_file_extension,
+  register_url_protocol, unregister_url_protocol, launch_activation};
+
 #[cfg(feature = "image-list")]
 pub use image_list::{ImageList, ImageListBuilder};
 
@@ -0,0 +1,474 @@
+use winapi::shared::minwindef::WORD;
+use winapi::shared::windef::{HDC, RECT};
+use winapi::um::commdlg::{PRINTDLGW, PrintDlgW, CommDlgExtendedError, PD_RETURNDC, PD_RETURNDEFAULT, PD_PAGENUMS, PD_NOSELECTION,
+  PD_USEDEVMODECOPIESANDCOLLATE};
+use winapi::um::wingdi::{DOCINFOW, StartDocW, StartPage, EndPage, EndDoc, AbortDoc, CreateDCW, DeleteDC, GetDeviceCaps, SaveDC, RestoreDC,
+  SetMapMode, SetWindowExtEx, SetViewportExtEx, SetViewportOrgEx, IntersectClipRect, MM_ANISOTROPIC,
+  PHYSICALWIDTH, PHYSICALHEIGHT, PHYSICALOFFSETX, PHYSICALOFFSETY};
+use winapi::um::winbase::GlobalFree;
+use crate::win32::base_helper::to_utf16;
+use crate::controls::ControlHandle;
+use crate::NwgError;
+use std::cell::{Cell, RefCell};
+use std::ops::Range;
+use std::rc::Rc;
+use std::{ptr, mem};
+
+/// The size of an A4 page, in hundredths of a millimeter
+const A4: (i32, i32) = (21000, 29700);
+
+/// The default margins, in hundredths of a millimeter
+const DEFAULT_MARGIN: i32 = 2000;
+
+type DrawCallback = Rc<dyn Fn(&PrintPage)>;
+
+
+/**
+    A page drawn by the draw callback of a `PrintDocument`.
+
+    The device context is mapped to the page: the coordinates are in hundredths of a millimeter, with the origin at the
+    top left corner of the paper. The same callback draws the pages sent to the printer and the pages of a `PrintPreview`,
+    so the callback must only use the coordinates of the page, never the pixels of the device.
+*/
+pub struct PrintPage {
+    /// The device context of the page. It is only valid during the callback.
+    pub hdc: HDC,
+
+    /// The index of the page, starting at 0
+    pub index: usize,
+
+    /// The size of the page, in hundredths of a millimeter
+    pub page_size: (i32, i32),
+
+    /// The area of the page inside the margins, as `[left, top, right, bottom]`
+    pub content: [i32; 4],
+
+    /// True if the page is drawn in a `PrintPreview`, false if it is sent to a printer
+    pub preview: bool,
+}
+
+
+/// The state of a document, shared with the `PrintPreview` controls displaying it
+pub(crate) struct DocumentState {
+    name: RefCell<String>,
+    page_count: Cell<usize>,
+    page_size: Cell<(i32, i32)>,
+    margins: Cell<[i32; 4]>,
+    draw: RefCell<Option<DrawCallback>>,
+
+    /// The previews displaying the document. They are repainted when the document changes.
+    previews: RefCell<Vec<ControlHandle>>,
+}
+
+impl DocumentState {
+
+    pub(crate) fn page_count(&self) -> usize {
+        self.page_count.get()
+    }
+
+    pub(crate) fn page_size(&self) -> (i32, i32) {
+        self.page_size.get()
+    }
+
+    pub(crate) fn margins(&self) -> [i32; 4] {
+        self.margins.get()
+    }
+
+    pub(crate) fn add_preview(&self, preview: ControlHandle) {
+        self.previews.borrow_mut().push(preview);
+    }
+
+    pub(crate) fn remove_preview(&self, preview: ControlHandle) {
+        self.previews.borrow_mut().retain(|&p| p != preview);
+    }
+
+    fn repaint_previews(&self) {
+        use winapi::um::winuser::InvalidateRect;
+
+        for preview in self.previews.borrow().iter() {
+            if let Some(hwnd) = preview.hwnd() {
+                unsafe { InvalidateRect(hwnd, ptr::null(), 0); }
+            }
+        }
+    }
+
+    /**
+        Map the page to `target` (in device units of `hdc`) and call the draw callback.
+        The drawing is clipped to the page.
+    */
+    pub(crate) unsafe fn render_page(&self, hdc: HDC, index: usize, target: &RECT, preview: bool) {
+        // The callback is cloned so it can replace itself while it runs
+        let draw = match self.draw.borrow().as_ref() {
+            Some(draw) => draw.clone(),
+            None => { return; }
+        };
+
+        let (width, height) = self.page_size.get();
+        let margins = self.margins.get();
+
+        let saved = SaveDC(hdc);
+        SetMapMode(hdc, MM_ANISOTROPIC);
+        SetWindowExtEx(hdc, width, height, ptr::null_mut());
+        SetViewportExtEx(hdc, target.right - target.left, target.bottom - target.top, ptr::null_mut());
+        SetViewportOrgEx(hdc, target.left, target.top, ptr::null_mut());
+        IntersectClipRect(hdc, 0, 0, width, height);
+
+        let page = PrintPage {
+            hdc,
+            index,
+            page_size: (width, height),
+            content: [margins[0], margins[1], width - margins[2], height - margins[3]],
+            preview,
+        };
+
+        draw(&page);
+
+        RestoreDC(hdc, saved);
+    }
+
+    /// Show the print dialog and print the pages selected by the user. Returns false if the user cancelled the dialog.
+    pub(crate) fn print_with_dialog(&self, owner: Option<ControlHandle>) -> Result<bool, NwgError> {
+        let page_count = self.page_count.get();
+        if page_count == 0 {
+            return Err(NwgError::resource_create("The document has no pages"));
+        }
+
+        let max_page = page_count.min(WORD::max_value() as usize) as WORD;
+
+        let mut dialog: PRINTDLGW = unsafe { mem::zeroed() };
+        dialog.lStructSize = mem::size_of::<PRINTDLGW>() as u32;
+        dialog.hwndOwner = owner.and_then(|o| o.hwnd()).unwrap_or(ptr::null_mut());
+        dialog.Flags = PD_RETURNDC | PD_USEDEVMODECOPIESANDCOLLATE | PD_NOSELECTION;
+        dialog.nMinPage = 1;
+        dialog.nMaxPage = max_page;
+        dialog.nFromPage = 1;
+        dialog.nToPage = max_page;
+        dialog.nCopies = 1;
+
+        let dc = unsafe { run_print_dialog(&mut dialog)? };
+        let dc = match dc {
+            Some(dc) => dc,
+            None => { return Ok(false); }
+        };
+
+        let pages = match dialog.Flags & PD_PAGENUMS == PD_PAGENUMS {
+            true => (dialog.nFromPage.max(1) as usize - 1)..(dialog.nToPage as usize).min(page_count),
+            false => 0..page_count
+        };
+
+        unsafe {
+            let result = self.print_dc(dc, pages);
+            DeleteDC(dc);
+            result.map(|_| true)
+        }
+    }
+
+    /// Print all the pages on `printer`, or on the default printer if `printer` is `None`
+    pub(crate) fn print_to(&self, printer: Option<&str>) -> Result<(), NwgError> {
+        if self.page_count.get() == 0 {
+            return Err(NwgError::resource_create("The document has no pages"));
+        }
+
+        let dc = match printer {
+            Some(name) => {
+                let driver = to_utf16("WINSPOOL");
+                let device = to_utf16(name);
+                let dc = unsafe { CreateDCW(driver.as_ptr(), device.as_ptr(), ptr::null(), ptr::null()) };
+                match dc.is_null() {
+                    true => { return Err(NwgError::resource_create(format!("Failed to open the printer {:?}", name))); },
+                    false => dc
+                }
+            },
+            None => {
+                let mut dialog: PRINTDLGW = unsafe { mem::zeroed() };
+                dialog.lStructSize = mem::size_of::<PRINTDLGW>() as u32;
+                dialog.Flags = PD_RETURNDEFAULT | PD_RETURNDC;
+
+                match unsafe { run_print_dialog(&mut dialog)? } {
+                    Some(dc) => dc,
+                    None => { return Err(NwgError::resource_create("There is no default printer")); }
+                }
+            }
+        };
+
+        unsafe {
+            let result = self.print_dc(dc, 0..self.page_count.get());
+            DeleteDC(dc);
+            result
+        }
+    }
+
+    /// Send the pages to a printer device context. The pages are scaled to fit the paper of the printer.
+    unsafe fn print_dc(&self, dc: HDC, pages: Range<usize>) -> Result<(), NwgError> {
+        let name = to_utf16(&self.name.borrow());
+        let info = DOCINFOW {
+            cbSize: mem::size_of::<DOCINFOW>() as i32,
+            lpszDocName: name.as_ptr(),
+            lpszOutput: ptr::null(),
+            lpszDatatype: ptr::null(),
+            fwType: 0,
+        };
+
+        if StartDocW(dc, &info) <= 0 {
+            return Err(NwgError::resource_create("Failed to start the print job"));
+        }
+
+        // The origin of a printer device context is the top left corner of the printable area, not of the paper
+        let (paper_width, paper_height) = (GetDeviceCaps(dc, PHYSICALWIDTH), GetDeviceCaps(dc, PHYSICALHEIGHT));
+        let (offset_x, offset_y) = (GetDeviceCaps(dc, PHYSICALOFFSETX), GetDeviceCaps(dc, PHYSICALOFFSETY));
+        let (width, height) = self.page_size.get();
+        let scale = (paper_width as f64 / width as f64).min(paper_height as f64 / height as f64);
+        let target = RECT {
+            left: -offset_x,
+            top: -offset_y,
+            right: -offset_x + (width as f64 * scale).round() as i32,
+            bottom: -offset_y + (height as f64 * scale).round() as i32,
+        };
+
+        for index in pages {
+            if StartPage(dc) <= 0 {
+                AbortDoc(dc);
+                return Err(NwgError::resource_create("Failed to start a page of the print job"));
+            }
+
+            self.render_page(dc, index, &target, false);
+
+            if EndPage(dc) <= 0 {
+                AbortDoc(dc);
+                return Err(NwgError::resource_create("Failed to send a page of the print job"));
+            }
+        }
+
+        EndDoc(dc);
+
+        Ok(())
+    }
+
+}
+
+/// Run the print dialog and free the printer settings it allocated. Returns `None` if the dialog was cancelled.
+unsafe fn run_print_dialog(dialog: &mut PRINTDLGW) -> Result<Option<HDC>, NwgError> {
+    let ok = PrintDlgW(dialog) != 0;
+
+    if !dialog.hDevMode.is_null() {
+        GlobalFree(dialog.hDevMode);
+    }
+
+    if !dialog.hDevNames.is_null() {
+        GlobalFree(dialog.hDevNames);
+    }
+
+    match (ok, CommDlgExtendedError()) {
+        (true, _) if !dialog.hDC.is_null() => Ok(Some(dialog.hDC)),
+        (true, _) => Err(NwgError::resource_create("The print dialog did not return a printer")),
+        (false, 0) => Ok(None),
+        (false, code) => Err(NwgError::resource_create(format!("The print dialog failed with the error 0x{:X}", code))),
+    }
+}
+
+
+/**
+A document that can be printed. The pages are drawn by a callback that receives a `PrintPage`, so the same code draws
+the printed pages and the pages displayed by a `PrintPreview`.
+
+The coordinates of the pages are in hundredths of a millimeter. The pages are scaled to fit the paper of the printer.
+Changing the document repaints the previews displaying it.
+
+Requires the `printing` feature.
+
+**Builder parameters:**
+  * `name`:       The name of the print job, displayed in the printer queue.
+  * `page_count`: The number of pages of the document. Defaults to 1.
+  * `page_size`:  The size of the pages in hundredths of a millimeter. Defaults to A4 `(21000, 29700)`.
+  * `margins`:    The margins of the pages as `[left, top, right, bottom]`, in hundredths of a millimeter. Defaults to 20 mm.
+  * `draw`:       The callback that draws a page.
+
+```rust
+use native_windows_gui as nwg;
+use winapi::um::wingdi::TextOutW;
+
+fn build_document(document: &mut nwg::PrintDocument, lines: Vec<String>) {
+    nwg::PrintDocument::builder()
+        .name("Report")
+        .page_count(1)
+        .draw(move |page| {
+            let [left, top, _, _] = page.content;
+            for (i, line) in lines.iter().enumerate() {
+                let text: Vec<u16> = line.encode_utf16().collect();
+                unsafe { TextOutW(page.hdc, left, top + (i as i32 * 600), text.as_ptr(), text.len() as i32); }
+            }
+        })
+        .build(document)
+        .unwrap();
+}
+
+fn print(document: &nwg::PrintDocument, window: &nwg::Window) {
+    match document.print(Some(window)) {
+        Ok(_) => {},
+        Err(e) => { nwg::error_message("Print", &format!("{}", e)); }
+    }
+}
+```
+*/
+pub struct PrintDocument {
+    state: Rc<DocumentState>,
+}
+
+impl PrintDocument {
+
+    pub fn builder() -> PrintDocumentBuilder {
+        PrintDocumentBuilder {
+            name: "Document".to_string(),
+            page_count: 1,
+            page_size: A4,
+            margins: [DEFAULT_MARGIN; 4],
+            draw: None,
+        }
+    }
+
+    /// Return the name of the print job
+    pub fn name(&self) -> String {
+        self.state.name.borrow().clone()
+    }
+
+    /// Set the name of the print job
+    pub fn set_name(&self, name: &str) {
+        *self.state.name.borrow_mut() = name.to_string();
+    }
+
+    /// Return the number of pages of the document
+    pub fn page_count(&self) -> usize {
+        self.state.page_count.get()
+    }
+
+    /// Set the number of pages of the document
+    pub fn set_page_count(&self, count: usize) {
+        self.state.page_count.set(count);
+        self.state.repaint_previews();
+    }
+
+    /// Return the size of the pages, in hundredths of a millimeter
+    pub fn page_size(&self) -> (i32, i32) {
+        self.state.page_size.get()
+    }
+
+    /// Set the size of the pages, in hundredths of a millimeter. Does nothing if the size is empty.
+    pub fn set_page_size(&self, size: (i32, i32)) {
+        if size.0 > 0 && size.1 > 0 {
+            self.state.page_size.set(size);
+            self.state.repaint_previews();
+        }
+    }
+
+    /// Return the margins of the pages as `[left, top, right, bottom]`, in hundredths of a millimeter
+    pub fn margins(&self) -> [i32; 4] {
+        self.state.margins.get()
+    }
+
+    /// Set the margins of the pages as `[left, top, right, bottom]`, in hundredths of a millimeter
+    pub fn set_margins(&self, margins: [i32; 4]) {
+        self.state.margins.set(margins);
+        self.state.repaint_previews();
+    }
+
+    /// Set the callback that draws the pages
+    pub fn set_draw_callback<F: Fn(&PrintPage) + 'static>(&self, callback: F) {
+        *self.state.draw.borrow_mut() = Some(Rc::new(callback));
+        self.state.repaint_previews();
+    }
+
+    /// Repaint the previews displaying the document. Call it after the data drawn by the callback changed.
+    pub fn refresh(&self) {
+        self.state.repaint_previews();
+    }
+
+    /**
+        Show the print dialog, then print the pages selected by the user.
+        Returns `Ok(false)` if the user cancelled the dialog.
+    */
+    pub fn print<C: Into<ControlHandle>>(&self, owner: Option<C>) -> Result<bool, NwgError> {
+        self.state.print_with_dialog(owner.map(|o| o.into()))
+    }
+
+    /// Print all the pages without a dialog, on the printer named `printer` or on the default printer if `printer` is `None`
+    pub fn print_to(&self, printer: Option<&str>) -> Result<(), NwgError> {
+        self.state.print_to(printer)
+    }
+
+    pub(crate) fn shared_state(&self) -> Rc<DocumentState> {
+        self.state.clone()
+    }
+
+}
+
+impl Default for PrintDocument {
+
+    fn default() -> PrintDocument {
+        PrintDocument {
+            state: Rc::new(DocumentState {
+                name: RefCell::new("Document".to_string()),
+                page_count: Cell::new(1),
+                page_size: Cell::new(A4),
+                margins: Cell::new([DEFAULT_MARGIN; 4]),
+                draw: RefCell::new(None),
+                previews: RefCell::new(Vec::new()),
+            })
+        }
+    }
+
+}
+
+
+/// The builder for a `PrintDocument` object. Use `PrintDocument::builder` to create one.
+pub struct PrintDocumentBuilder {
+    name: String,
+    page_count: usize,
+    page_size: (i32, i32),
+    margins: [i32; 4],
+    draw: Option<DrawCallback>,
+}
+
+impl PrintDocumentBuilder {
+
+    pub fn name(mut self, name: &str) -> PrintDocumentBuilder {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn page_count(mut self, count: usize) -> PrintDocumentBuilder {
+        self.page_count = count;
+        self
+    }
+
+    pub fn page_size(mut self, size: (i32, i32)) -> PrintDocumentBuilder {
+        self.page_size = size;
+        self
+    }
+
+    pub fn margins(mut self, margins: [i32; 4]) -> PrintDocumentBuilder {
+        self.margins = margins;
+        self
+    }
+
+    pub fn draw<F: Fn(&PrintPage) + 'static>(mut self, callback: F) -> PrintDocumentBuilder {
+        self.draw = Some(Rc::new(callback));
+        self
+    }
+
+    pub fn build(self, out: &mut PrintDocument) -> Result<(), NwgError> {
+        if self.page_size.0 <= 0 || self.page_size.1 <= 0 {
+            return Err(NwgError::resource_create("The page size of a PrintDocument must not be empty"));
+        }
+
+        *out = Default::default();
+
+        out.set_name(&self.name);
+        out.state.page_count.set(self.page_count);
+        out.state.page_size.set(self.page_size);
+        out.state.margins.set(self.margins);
+        *out.state.draw.borrow_mut() = self.draw;
+
+        Ok(())
+    }
+
+}
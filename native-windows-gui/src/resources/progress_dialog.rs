@@ -0,0 +1,503 @@
+use winapi::shared::minwindef::{DWORD, UINT, BOOL, LPCVOID, HINSTANCE};
+use winapi::shared::windef::HWND;
+use winapi::shared::basetsd::UINT_PTR;
+use winapi::shared::guiddef::GUID;
+use winapi::shared::ntdef::{PCWSTR, ULONGLONG};
+use winapi::shared::winerror::{HRESULT, S_OK};
+use winapi::um::unknwnbase::{IUnknown, IUnknownVtbl};
+use crate::win32::base_helper::to_utf16;
+use crate::{ControlHandle, NwgError};
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::{fmt, ptr, mem};
+
+/// Interval of the timer that forwards the values of the `ProgressDialogSender` to the dialog
+const UPDATE_INTERVAL: UINT = 100;
+
+const PDTIMER_RESET: DWORD = 0x01;
+
+const CLSID_PROGRESS_DIALOG: GUID = GUID {
+    Data1: 0xF8383852, Data2: 0xFCD3, Data3: 0x11D1,
+    Data4: [0xA6, 0xB9, 0x00, 0x60, 0x97, 0xDF, 0x5B, 0xD4]
+};
+
+const IID_IPROGRESS_DIALOG: GUID = GUID {
+    Data1: 0xEBBC7C04, Data2: 0x315E, Data3: 0x11D2,
+    Data4: [0xB6, 0x2F, 0x00, 0x60, 0x97, 0xDF, 0x5B, 0xD4]
+};
+
+/*
+    IProgressDialog is not defined in winapi
+*/
+#[allow(non_snake_case)]
+#[repr(C)]
+struct IProgressDialogVtbl {
+    parent: IUnknownVtbl,
+    StartProgressDialog: unsafe extern "system" fn(This: *mut IProgressDialog, hwndParent: HWND, punkEnableModless: *mut IUnknown, dwFlags: DWORD, pvResevered: LPCVOID) -> HRESULT,
+    StopProgressDialog: unsafe extern "system" fn(This: *mut IProgressDialog) -> HRESULT,
+    SetTitle: unsafe extern "system" fn(This: *mut IProgressDialog, pwzTitle: PCWSTR) -> HRESULT,
+    SetAnimation: unsafe extern "system" fn(This: *mut IProgressDialog, hInstAnimation: HINSTANCE, idAnimation: UINT) -> HRESULT,
+    HasUserCancelled: unsafe extern "system" fn(This: *mut IProgressDialog) -> BOOL,
+    SetProgress: unsafe extern "system" fn(This: *mut IProgressDialog, dwCompleted: DWORD, dwTotal: DWORD) -> HRESULT,
+    SetProgress64: unsafe extern "system" fn(This: *mut IProgressDialog, ullCompleted: ULONGLONG, ullTotal: ULONGLONG) -> HRESULT,
+    SetLine: unsafe extern "system" fn(This: *mut IProgressDialog, dwLineNum: DWORD, pwzString: PCWSTR, fCompactPath: BOOL, pvResevered: LPCVOID) -> HRESULT,
+    SetCancelMsg: unsafe extern "system" fn(This: *mut IProgressDialog, pwzCancelMsg: PCWSTR, pvResevered: LPCVOID) -> HRESULT,
+    Timer: unsafe extern "system" fn(This: *mut IProgressDialog, dwTimerAction: DWORD, pvResevered: LPCVOID) -> HRESULT,
+}
+
+#[repr(C)]
+struct IProgressDialog {
+    lpVtbl: *const IProgressDialogVtbl,
+}
+
+bitflags! {
+    /**
+        The progress dialog flags
+
+        * NONE:            A normal progress dialog
+        * MODAL:           The dialog is modal to its parent window
+        * AUTO_TIME:       Estimate the remaining time and display it on the third line of the dialog
+        * NO_TIME:         Do not display the remaining time
+        * NO_MINIMIZE:     Hide the minimize button
+        * NO_PROGRESS_BAR: Hide the progress bar
+        * MARQUEE:         Display a marquee progress bar, for operations with an unknown length
+        * NO_CANCEL:       Hide the cancel button. The operation cannot be cancelled by the user.
+    */
+    pub struct ProgressDialogFlags: u32 {
+        const NONE = 0;
+        const MODAL = 0x01;
+        const AUTO_TIME = 0x02;
+        const NO_TIME = 0x04;
+        const NO_MINIMIZE = 0x08;
+        const NO_PROGRESS_BAR = 0x10;
+        const MARQUEE = 0x20;
+        const NO_CANCEL = 0x40;
+    }
+}
+
+/// The values sent by a `ProgressDialogSender` that were not yet applied to the dialog
+#[derive(Default)]
+struct PendingProgress {
+    progress: Option<(u64, u64)>,
+    lines: [Option<String>; 3],
+    finished: bool,
+}
+
+struct ProgressShared {
+    pending: Mutex<PendingProgress>,
+    cancelled: AtomicBool,
+}
+
+thread_local! {
+    /// The dialogs currently displayed, by update timer id
+    static RUNNING_DIALOGS: RefCell<HashMap<UINT_PTR, (*mut IProgressDialog, Arc<ProgressShared>)>> = RefCell::new(HashMap::new());
+}
+
+
+/**
+A progress dialog displays the progress of a long operation in a separate window. It is a wrapper over the shell progress dialog.
+
+The dialog has a title, three lines of text, a progress bar and a cancel button. With the `AUTO_TIME` flag, the third line
+is replaced by an estimation of the remaining time.
+
+Unlike the other dialogs, `start` does not block: the dialog runs in its own thread and the operation keeps running in the current thread
+or in a worker thread. The user cancelling the dialog does not stop anything; the operation must poll `cancelled` and stop by itself.
+
+To update the dialog from a worker thread, use a `ProgressDialogSender` (see `sender`). The values sent are applied to
+the dialog by the GUI thread every 100 ms, so the GUI thread must be dispatching its events.
+
+Requires the `progress-dialog` feature.
+
+**Builder parameters:**
+  * `title`:          The title of the dialog
+  * `line1`:          The text of the first line
+  * `line2`:          The text of the second line
+  * `cancel_message`: The text displayed while the operation is being cancelled
+  * `flags`:          A combination of the ProgressDialogFlags values
+
+```rust
+use native_windows_gui as nwg;
+use std::thread;
+
+fn copy_files(dialog: &nwg::ProgressDialog, window: &nwg::Window, files: Vec<String>) {
+    dialog.start(Some(window)).unwrap();
+
+    let sender = dialog.sender();
+    thread::spawn(move || {
+        let total = files.len() as u64;
+        for (i, file) in files.iter().enumerate() {
+            if sender.cancelled() {
+                break;
+            }
+
+            sender.set_line(2, file);
+            sender.set_progress(i as u64, total);
+            // copy the file
+        }
+
+        sender.finish();
+    });
+}
+
+fn build_dialog(dialog: &mut nwg::ProgressDialog) {
+    nwg::ProgressDialog::builder()
+        .title("Copying files")
+        .line1("Copying the selected files...")
+        .flags(nwg::ProgressDialogFlags::AUTO_TIME | nwg::ProgressDialogFlags::MODAL)
+        .build(dialog)
+        .unwrap();
+}
+```
+*/
+pub struct ProgressDialog {
+    handle: *mut IProgressDialog,
+    flags: Cell<u32>,
+    timer: Cell<UINT_PTR>,
+    shared: Arc<ProgressShared>,
+}
+
+impl ProgressDialog {
+
+    pub fn builder<'a>() -> ProgressDialogBuilder<'a> {
+        ProgressDialogBuilder {
+            title: None,
+            line1: None,
+            line2: None,
+            cancel_message: None,
+            flags: ProgressDialogFlags::NONE,
+        }
+    }
+
+    /**
+        Display the dialog and return immediately. The dialog runs in a separate thread until `stop` is called,
+        the dialog is dropped, or a `ProgressDialogSender` calls `finish`.
+
+        This also resets the cancelled flag and the remaining time estimation.
+        The parent argument must be a window control otherwise the method will panic.
+    */
+    pub fn start<C: Into<ControlHandle>>(&self, parent: Option<C>) -> Result<(), NwgError> {
+        use winapi::um::winuser::SetTimer;
+
+        if self.running() {
+            return Ok(());
+        }
+
+        let parent_handle = match parent {
+            Some(p) => p.into().hwnd().expect("Progress dialog parent must be a window control"),
+            None => ptr::null_mut()
+        };
+
+        self.shared.cancelled.store(false, Ordering::SeqCst);
+        if let Ok(mut pending) = self.shared.pending.lock() {
+            pending.finished = false;
+        }
+
+        unsafe {
+            let dialog = self.dialog();
+            let result = ((*dialog.lpVtbl).StartProgressDialog)(self.handle, parent_handle, ptr::null_mut(), self.flags.get(), ptr::null());
+            if result != S_OK {
+                return Err(NwgError::resource_create("Failed to start the progress dialog"));
+            }
+
+            ((*dialog.lpVtbl).Timer)(self.handle, PDTIMER_RESET, ptr::null());
+
+            let timer = SetTimer(ptr::null_mut(), 0, UPDATE_INTERVAL, Some(progress_update));
+            self.timer.set(timer);
+
+            let running = (self.handle, self.shared.clone());
+            RUNNING_DIALOGS.with(|dialogs| dialogs.borrow_mut().insert(timer, running));
+        }
+
+        Ok(())
+    }
+
+    /// Close the dialog. Does nothing if the dialog is not displayed.
+    pub fn stop(&self) {
+        stop_dialog(self.timer.get());
+    }
+
+    /// Return `true` if the dialog is currently displayed
+    pub fn running(&self) -> bool {
+        let timer = self.timer.get();
+        RUNNING_DIALOGS.with(|dialogs| dialogs.borrow().contains_key(&timer))
+    }
+
+    /// Return `true` if the user clicked on the cancel button since the dialog was started
+    pub fn cancelled(&self) -> bool {
+        if self.running() && unsafe { ((*self.dialog().lpVtbl).HasUserCancelled)(self.handle) } != 0 {
+            self.shared.cancelled.store(true, Ordering::SeqCst);
+        }
+
+        self.shared.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Change the dialog title
+    pub fn set_title<'a>(&self, title: &'a str) {
+        let title = to_utf16(title);
+        unsafe { ((*self.dialog().lpVtbl).SetTitle)(self.handle, title.as_ptr()); }
+    }
+
+    /**
+        Set the text of a line of the dialog. `line` must be 1, 2 or 3. The third line is not displayed with the `AUTO_TIME` flag.
+        If `compact_path` is true and the text is a path that is too long, the middle of the path is replaced by an ellipsis.
+    */
+    pub fn set_line<'a>(&self, line: u32, text: &'a str, compact_path: bool) {
+        unsafe { set_dialog_line(self.handle, line, text, compact_path); }
+    }
+
+    /// Set the text displayed while the operation is being cancelled
+    pub fn set_cancel_message<'a>(&self, message: &'a str) {
+        let message = to_utf16(message);
+        unsafe { ((*self.dialog().lpVtbl).SetCancelMsg)(self.handle, message.as_ptr(), ptr::null()); }
+    }
+
+    /// Set the progress of the operation. The remaining time is estimated from the changes of `completed`.
+    pub fn set_progress(&self, completed: u64, total: u64) {
+        unsafe { ((*self.dialog().lpVtbl).SetProgress64)(self.handle, completed, total); }
+    }
+
+    /// Restart the estimation of the remaining time. Use this if the operation was paused.
+    pub fn reset_timer(&self) {
+        unsafe { ((*self.dialog().lpVtbl).Timer)(self.handle, PDTIMER_RESET, ptr::null()); }
+    }
+
+    /// Return the dialog flags
+    pub fn flags(&self) -> ProgressDialogFlags {
+        ProgressDialogFlags::from_bits_truncate(self.flags.get())
+    }
+
+    /// Set the dialog flags. The flags are used the next time the dialog is started.
+    pub fn set_flags(&self, flags: ProgressDialogFlags) {
+        self.flags.set(flags.bits());
+    }
+
+    /// Create a new `ProgressDialogSender` that can update this dialog from another thread
+    pub fn sender(&self) -> ProgressDialogSender {
+        ProgressDialogSender {
+            shared: self.shared.clone()
+        }
+    }
+
+    fn dialog(&self) -> &IProgressDialog {
+        if self.handle.is_null() {
+            panic!("ProgressDialog is not yet bound to a winapi object");
+        }
+
+        unsafe { &*self.handle }
+    }
+
+}
+
+impl fmt::Debug for ProgressDialog {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ProgressDialog {{ flags: {:?} }}", self.flags())
+    }
+
+}
+
+impl Default for ProgressDialog {
+    fn default() -> ProgressDialog {
+        ProgressDialog {
+            handle: ptr::null_mut(),
+            flags: Cell::new(0),
+            timer: Cell::new(0),
+            shared: Arc::new(ProgressShared {
+                pending: Mutex::new(Default::default()),
+                cancelled: AtomicBool::new(false),
+            }),
+        }
+    }
+}
+
+impl PartialEq for ProgressDialog {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl Eq for ProgressDialog {}
+
+impl Drop for ProgressDialog {
+    fn drop(&mut self) {
+        self.stop();
+
+        if !self.handle.is_null() {
+            unsafe { ((*(*self.handle).lpVtbl).parent.Release)(self.handle as *mut IUnknown); }
+        }
+    }
+}
+
+
+/**
+    A ProgressDialogSender updates its parent `ProgressDialog` from another thread.
+    The values are applied to the dialog by the GUI thread, at most 100 ms after they were sent.
+*/
+#[derive(Clone)]
+pub struct ProgressDialogSender {
+    shared: Arc<ProgressShared>,
+}
+
+impl ProgressDialogSender {
+
+    /// Set the progress of the operation
+    pub fn set_progress(&self, completed: u64, total: u64) {
+        if let Ok(mut pending) = self.shared.pending.lock() {
+            pending.progress = Some((completed, total));
+        }
+    }
+
+    /// Set the text of a line of the dialog. `line` must be 1, 2 or 3. Paths are compacted if they are too long.
+    pub fn set_line<S: Into<String>>(&self, line: u32, text: S) {
+        if line < 1 || line > 3 {
+            return;
+        }
+
+        if let Ok(mut pending) = self.shared.pending.lock() {
+            pending.lines[(line - 1) as usize] = Some(text.into());
+        }
+    }
+
+    /// Return `true` if the user clicked on the cancel button of the dialog
+    pub fn cancelled(&self) -> bool {
+        self.shared.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Close the dialog. The last values sent are applied before the dialog is closed.
+    pub fn finish(&self) {
+        if let Ok(mut pending) = self.shared.pending.lock() {
+            pending.finished = true;
+        }
+    }
+
+}
+
+
+/// The builder for a `ProgressDialog` object. Use `ProgressDialog::builder` to create one.
+pub struct ProgressDialogBuilder<'a> {
+    title: Option<&'a str>,
+    line1: Option<&'a str>,
+    line2: Option<&'a str>,
+    cancel_message: Option<&'a str>,
+    flags: ProgressDialogFlags,
+}
+
+impl<'a> ProgressDialogBuilder<'a> {
+
+    pub fn title(mut self, title: &'a str) -> ProgressDialogBuilder<'a> {
+        self.title = Some(title);
+        self
+    }
+
+    pub fn line1(mut self, text: &'a str) -> ProgressDialogBuilder<'a> {
+        self.line1 = Some(text);
+        self
+    }
+
+    pub fn line2(mut self, text: &'a str) -> ProgressDialogBuilder<'a> {
+        self.line2 = Some(text);
+        self
+    }
+
+    pub fn cancel_message(mut self, message: &'a str) -> ProgressDialogBuilder<'a> {
+        self.cancel_message = Some(message);
+        self
+    }
+
+    pub fn flags(mut self, flags: ProgressDialogFlags) -> ProgressDialogBuilder<'a> {
+        self.flags = flags;
+        self
+    }
+
+    pub fn build(self, out: &mut ProgressDialog) -> Result<(), NwgError> {
+        use winapi::um::combaseapi::CoCreateInstance;
+        use winapi::shared::wtypesbase::CLSCTX_INPROC_SERVER;
+
+        *out = Default::default();
+
+        let mut handle: *mut IProgressDialog = ptr::null_mut();
+        let result = unsafe {
+            CoCreateInstance(&CLSID_PROGRESS_DIALOG, ptr::null_mut(), CLSCTX_INPROC_SERVER, &IID_IPROGRESS_DIALOG, mem::transmute(&mut handle))
+        };
+
+        if result != S_OK {
+            return Err(NwgError::resource_create("Failed to create the progress dialog"));
+        }
+
+        out.handle = handle;
+        out.set_flags(self.flags);
+
+        if let Some(title) = self.title {
+            out.set_title(title);
+        }
+
+        if let Some(text) = self.line1 {
+            out.set_line(1, text, false);
+        }
+
+        if let Some(text) = self.line2 {
+            out.set_line(2, text, false);
+        }
+
+        if let Some(message) = self.cancel_message {
+            out.set_cancel_message(message);
+        }
+
+        Ok(())
+    }
+
+}
+
+
+unsafe fn set_dialog_line(handle: *mut IProgressDialog, line: u32, text: &str, compact_path: bool) {
+    let text = to_utf16(text);
+    ((*(*handle).lpVtbl).SetLine)(handle, line, text.as_ptr(), compact_path as BOOL, ptr::null());
+}
+
+/// Kill the update timer and close the dialog associated with `timer`
+fn stop_dialog(timer: UINT_PTR) {
+    use winapi::um::winuser::KillTimer;
+
+    let running = RUNNING_DIALOGS.with(|dialogs| dialogs.borrow_mut().remove(&timer));
+    if let Some((handle, _)) = running {
+        unsafe {
+            KillTimer(ptr::null_mut(), timer);
+            ((*(*handle).lpVtbl).StopProgressDialog)(handle);
+        }
+    }
+}
+
+/// Apply the values sent by the `ProgressDialogSender` to the dialog and update the cancelled flag
+unsafe extern "system" fn progress_update(_hwnd: HWND, _msg: UINT, timer: UINT_PTR, _time: DWORD) {
+    let running = RUNNING_DIALOGS.with(|dialogs| dialogs.borrow().get(&timer).map(|(h, s)| (*h, s.clone())));
+    let (handle, shared) = match running {
+        Some(r) => r,
+        None => { return; }
+    };
+
+    if ((*(*handle).lpVtbl).HasUserCancelled)(handle) != 0 {
+        shared.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    let finished = match shared.pending.lock() {
+        Ok(mut pending) => {
+            if let Some((completed, total)) = pending.progress.take() {
+                ((*(*handle).lpVtbl).SetProgress64)(handle, completed, total);
+            }
+
+            for (index, line) in pending.lines.iter_mut().enumerate() {
+                if let Some(text) = line.take() {
+                    set_dialog_line(handle, (index + 1) as u32, &text, true);
+                }
+            }
+
+            pending.finished
+        },
+        Err(_) => false
+    };
+
+    if finished {
+        stop_dialog(timer);
+    }
+}
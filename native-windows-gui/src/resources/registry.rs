@@ -0,0 +1,494 @@
+use winapi::shared::minwindef::{HKEY, DWORD, WPARAM};
+use winapi::shared::windef::HWND;
+use winapi::shared::winerror::ERROR_SUCCESS;
+use winapi::um::winnt::{HANDLE, KEY_READ, KEY_WRITE, REG_OPTION_NON_VOLATILE, REG_SZ, REG_DWORD, REG_QWORD, REG_BINARY, REG_MULTI_SZ};
+use winapi::um::winreg::{HKEY_CLASSES_ROOT, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, HKEY_USERS, HKEY_CURRENT_CONFIG,
+  RRF_RT_REG_SZ, RRF_RT_REG_EXPAND_SZ, RRF_RT_REG_DWORD, RRF_RT_REG_QWORD, RRF_RT_REG_BINARY, RRF_RT_REG_MULTI_SZ,
+  RegOpenKeyExW, RegCreateKeyExW, RegCloseKey, RegGetValueW, RegSetValueExW, RegDeleteValueW, RegDeleteTreeW, RegEnumKeyExW, RegEnumValueW};
+use crate::win32::{base_helper::{to_utf16, from_utf16}, window_helper as wh, window::build_notice};
+use crate::controls::ControlHandle;
+use crate::NwgError;
+use std::{ptr, thread};
+
+const NOT_BOUND: &'static str = "RegistryKey is not yet bound to a winapi object";
+
+/// The maximum length of a key name, in characters
+const MAX_KEY_LENGTH: usize = 255;
+
+/// The maximum length of a value name, in characters
+const MAX_VALUE_NAME_LENGTH: usize = 16383;
+
+
+/// The predefined keys at the root of the registry
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RegistryRoot {
+    ClassesRoot,
+    CurrentUser,
+    LocalMachine,
+    Users,
+    CurrentConfig,
+}
+
+impl RegistryRoot {
+
+    fn hkey(self) -> HKEY {
+        match self {
+            RegistryRoot::ClassesRoot => HKEY_CLASSES_ROOT,
+            RegistryRoot::CurrentUser => HKEY_CURRENT_USER,
+            RegistryRoot::LocalMachine => HKEY_LOCAL_MACHINE,
+            RegistryRoot::Users => HKEY_USERS,
+            RegistryRoot::CurrentConfig => HKEY_CURRENT_CONFIG,
+        }
+    }
+
+}
+
+/**
+An open key of the Windows registry.
+
+A registry key can be used to read and write typed values (string, dword, qword, binary and multi-string),
+to enumerate the sub keys and the values, and to create or delete sub keys.
+The key is closed when the object is dropped.
+
+If the `watch` parameter is set, the key is watched in a background thread and an `OnRegistryChange` event
+is sent to the parent window each time a value of the key is changed, added or deleted. The events are sent with `RegistryKey::handle`.
+
+Requires the `registry` feature.
+
+**Builder parameters:**
+  * `root`:          The predefined root key. Defaults to `RegistryRoot::CurrentUser`.
+  * `path`:          **Required.** The path of the key under the root key (ex: `Software\\MyApp`).
+  * `create`:        Create the key if it does not exist. Defaults to `false`.
+  * `writable`:      Open the key with write access. Defaults to `false`.
+  * `watch`:         A top level window that will receive the `OnRegistryChange` events. Defaults to `None`.
+  * `watch_subtree`: Also report the changes in the sub keys. Defaults to `false`.
+
+**Control events:**
+  * `OnRegistryChange`: When a value or a sub key of the watched key changed
+
+```rust
+use native_windows_gui as nwg;
+
+fn load_settings(window: &nwg::Window, key: &mut nwg::RegistryKey) -> Result<(), nwg::NwgError> {
+    nwg::RegistryKey::builder()
+        .path("Software\\MyApp")
+        .create(true)
+        .writable(true)
+        .watch(Some(window))
+        .build(key)?;
+
+    let theme = key.get_string("Theme").unwrap_or("Light".to_string());
+    let launches = key.get_dword("Launches").unwrap_or(0);
+    key.set_dword("Launches", launches + 1)?;
+
+    println!("{} theme, launched {} times", theme, launches);
+
+    Ok(())
+}
+```
+*/
+pub struct RegistryKey {
+    pub handle: ControlHandle,
+    key: HKEY,
+    stop_event: HANDLE,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl RegistryKey {
+
+    pub fn builder<'a>() -> RegistryKeyBuilder<'a> {
+        RegistryKeyBuilder {
+            root: RegistryRoot::CurrentUser,
+            path: "",
+            create: false,
+            writable: false,
+            watch: None,
+            watch_subtree: false,
+        }
+    }
+
+    /// Return true if the object holds an open registry key
+    pub fn is_open(&self) -> bool {
+        !self.key.is_null()
+    }
+
+    /**
+        Open a sub key of this key. If `create` is true, the key is created if it does not exist.
+        The sub key is opened with write access if possible. It is not watched.
+    */
+    pub fn open_subkey(&self, path: &str, create: bool) -> Result<RegistryKey, NwgError> {
+        if self.key.is_null() { panic!("{}", NOT_BOUND); }
+
+        let key = open_key(self.key, path, create, true)
+            .or_else(|_| open_key(self.key, path, create, false))?;
+
+        Ok(RegistryKey {
+            handle: ControlHandle::NoHandle,
+            key,
+            stop_event: ptr::null_mut(),
+            thread: None,
+        })
+    }
+
+    /// Delete a sub key of this key, including all its values and sub keys
+    pub fn delete_subkey(&self, path: &str) -> Result<(), NwgError> {
+        if self.key.is_null() { panic!("{}", NOT_BOUND); }
+
+        let path = to_utf16(path);
+        let result = unsafe { RegDeleteTreeW(self.key, path.as_ptr()) };
+        check(result as DWORD, "Failed to delete the registry key")
+    }
+
+    /// Delete a value of this key
+    pub fn delete_value(&self, name: &str) -> Result<(), NwgError> {
+        if self.key.is_null() { panic!("{}", NOT_BOUND); }
+
+        let name = to_utf16(name);
+        let result = unsafe { RegDeleteValueW(self.key, name.as_ptr()) };
+        check(result as DWORD, "Failed to delete the registry value")
+    }
+
+    /// Return the names of the sub keys of this key
+    pub fn subkeys(&self) -> Vec<String> {
+        if self.key.is_null() { panic!("{}", NOT_BOUND); }
+
+        let mut names = Vec::new();
+        let mut buffer = vec![0u16; MAX_KEY_LENGTH + 1];
+        let mut index = 0;
+
+        loop {
+            let mut length = buffer.len() as DWORD;
+            let result = unsafe {
+                RegEnumKeyExW(self.key, index, buffer.as_mut_ptr(), &mut length, ptr::null_mut(), ptr::null_mut(), ptr::null_mut(), ptr::null_mut())
+            };
+
+            match result as DWORD {
+                ERROR_SUCCESS => names.push(from_utf16(&buffer[..length as usize])),
+                _ => break
+            }
+
+            index += 1;
+        }
+
+        names
+    }
+
+    /// Return the names of the values of this key. The default value of the key is returned as an empty string.
+    pub fn values(&self) -> Vec<String> {
+        if self.key.is_null() { panic!("{}", NOT_BOUND); }
+
+        let mut names = Vec::new();
+        let mut buffer = vec![0u16; MAX_VALUE_NAME_LENGTH + 1];
+        let mut index = 0;
+
+        loop {
+            let mut length = buffer.len() as DWORD;
+            let result = unsafe {
+                RegEnumValueW(self.key, index, buffer.as_mut_ptr(), &mut length, ptr::null_mut(), ptr::null_mut(), ptr::null_mut(), ptr::null_mut())
+            };
+
+            match result as DWORD {
+                ERROR_SUCCESS => names.push(from_utf16(&buffer[..length as usize])),
+                _ => break
+            }
+
+            index += 1;
+        }
+
+        names
+    }
+
+    /// Read a string value. Environment variables in `REG_EXPAND_SZ` values are expanded.
+    /// Return `None` if the value does not exist or if it is not a string.
+    pub fn get_string(&self, name: &str) -> Option<String> {
+        let data = self.get_value(name, RRF_RT_REG_SZ | RRF_RT_REG_EXPAND_SZ)?;
+        Some(from_utf16(&bytes_to_wide(&data)))
+    }
+
+    /// Read a dword value. Return `None` if the value does not exist or if it is not a dword.
+    pub fn get_dword(&self, name: &str) -> Option<u32> {
+        let data = self.get_value(name, RRF_RT_REG_DWORD)?;
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(data.get(..4)?);
+        Some(u32::from_le_bytes(bytes))
+    }
+
+    /// Read a qword value. Return `None` if the value does not exist or if it is not a qword.
+    pub fn get_qword(&self, name: &str) -> Option<u64> {
+        let data = self.get_value(name, RRF_RT_REG_QWORD)?;
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(data.get(..8)?);
+        Some(u64::from_le_bytes(bytes))
+    }
+
+    /// Read a binary value. Return `None` if the value does not exist or if it is not a binary value.
+    pub fn get_binary(&self, name: &str) -> Option<Vec<u8>> {
+        self.get_value(name, RRF_RT_REG_BINARY)
+    }
+
+    /// Read a multi-string value. Return `None` if the value does not exist or if it is not a multi-string.
+    pub fn get_multi_string(&self, name: &str) -> Option<Vec<String>> {
+        let data = self.get_value(name, RRF_RT_REG_MULTI_SZ)?;
+        let wide = bytes_to_wide(&data);
+
+        let strings = wide.split(|&c| c == 0)
+            .filter(|s| !s.is_empty())
+            .map(|s| from_utf16(s))
+            .collect();
+
+        Some(strings)
+    }
+
+    /// Write a string value. The key must be opened with write access.
+    pub fn set_string(&self, name: &str, value: &str) -> Result<(), NwgError> {
+        let value = to_utf16(value);
+        self.set_value(name, REG_SZ, &wide_to_bytes(&value))
+    }
+
+    /// Write a dword value. The key must be opened with write access.
+    pub fn set_dword(&self, name: &str, value: u32) -> Result<(), NwgError> {
+        self.set_value(name, REG_DWORD, &value.to_le_bytes())
+    }
+
+    /// Write a qword value. The key must be opened with write access.
+    pub fn set_qword(&self, name: &str, value: u64) -> Result<(), NwgError> {
+        self.set_value(name, REG_QWORD, &value.to_le_bytes())
+    }
+
+    /// Write a binary value. The key must be opened with write access.
+    pub fn set_binary(&self, name: &str, value: &[u8]) -> Result<(), NwgError> {
+        self.set_value(name, REG_BINARY, value)
+    }
+
+    /// Write a multi-string value. Empty strings are skipped because they cannot be stored in a multi-string.
+    /// The key must be opened with write access.
+    pub fn set_multi_string<S: AsRef<str>>(&self, name: &str, value: &[S]) -> Result<(), NwgError> {
+        let mut wide: Vec<u16> = Vec::new();
+        for s in value.iter().map(|s| s.as_ref()).filter(|s| !s.is_empty()) {
+            wide.extend(s.encode_utf16());
+            wide.push(0);
+        }
+        wide.push(0);
+
+        self.set_value(name, REG_MULTI_SZ, &wide_to_bytes(&wide))
+    }
+
+    fn get_value(&self, name: &str, flags: DWORD) -> Option<Vec<u8>> {
+        if self.key.is_null() { panic!("{}", NOT_BOUND); }
+
+        let name = to_utf16(name);
+        let mut size: DWORD = 0;
+
+        unsafe {
+            let result = RegGetValueW(self.key, ptr::null(), name.as_ptr(), flags, ptr::null_mut(), ptr::null_mut(), &mut size);
+            if result as DWORD != ERROR_SUCCESS {
+                return None;
+            }
+
+            let mut data = vec![0u8; size as usize];
+            let result = RegGetValueW(self.key, ptr::null(), name.as_ptr(), flags, ptr::null_mut(), data.as_mut_ptr() as _, &mut size);
+            if result as DWORD != ERROR_SUCCESS {
+                return None;
+            }
+
+            data.truncate(size as usize);
+            Some(data)
+        }
+    }
+
+    fn set_value(&self, name: &str, value_type: DWORD, data: &[u8]) -> Result<(), NwgError> {
+        if self.key.is_null() { panic!("{}", NOT_BOUND); }
+
+        let name = to_utf16(name);
+        let result = unsafe { RegSetValueExW(self.key, name.as_ptr(), 0, value_type, data.as_ptr(), data.len() as DWORD) };
+        check(result as DWORD, "Failed to write the registry value")
+    }
+
+}
+
+impl Default for RegistryKey {
+    fn default() -> RegistryKey {
+        RegistryKey {
+            handle: ControlHandle::NoHandle,
+            key: ptr::null_mut(),
+            stop_event: ptr::null_mut(),
+            thread: None,
+        }
+    }
+}
+
+impl PartialEq for RegistryKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for RegistryKey {}
+
+impl Drop for RegistryKey {
+    fn drop(&mut self) {
+        use winapi::um::synchapi::SetEvent;
+        use winapi::um::handleapi::CloseHandle;
+
+        if !self.stop_event.is_null() {
+            unsafe { SetEvent(self.stop_event); }
+
+            if let Some(thread) = self.thread.take() {
+                drop(thread.join());
+            }
+
+            unsafe { CloseHandle(self.stop_event); }
+        }
+
+        if !self.key.is_null() {
+            unsafe { RegCloseKey(self.key); }
+        }
+    }
+}
+
+pub struct RegistryKeyBuilder<'a> {
+    root: RegistryRoot,
+    path: &'a str,
+    create: bool,
+    writable: bool,
+    watch: Option<ControlHandle>,
+    watch_subtree: bool,
+}
+
+impl<'a> RegistryKeyBuilder<'a> {
+
+    pub fn root(mut self, root: RegistryRoot) -> RegistryKeyBuilder<'a> {
+        self.root = root;
+        self
+    }
+
+    pub fn path(mut self, path: &'a str) -> RegistryKeyBuilder<'a> {
+        self.path = path;
+        self
+    }
+
+    pub fn create(mut self, create: bool) -> RegistryKeyBuilder<'a> {
+        self.create = create;
+        self
+    }
+
+    pub fn writable(mut self, writable: bool) -> RegistryKeyBuilder<'a> {
+        self.writable = writable;
+        self
+    }
+
+    pub fn watch<C: Into<ControlHandle>>(mut self, watch: Option<C>) -> RegistryKeyBuilder<'a> {
+        self.watch = watch.map(|w| w.into());
+        self
+    }
+
+    pub fn watch_subtree(mut self, watch_subtree: bool) -> RegistryKeyBuilder<'a> {
+        self.watch_subtree = watch_subtree;
+        self
+    }
+
+    pub fn build(self, out: &mut RegistryKey) -> Result<(), NwgError> {
+        use winapi::um::synchapi::CreateEventW;
+
+        if self.path.is_empty() {
+            return Err(NwgError::resource_create("A registry key path is required"));
+        }
+
+        let watch = match self.watch {
+            Some(p) => match p.hwnd() {
+                Some(handle) => Some(handle),
+                None => { return Err(NwgError::resource_create("Wrong registry watch parent type")); }
+            },
+            None => None
+        };
+
+        *out = Default::default();
+        out.key = open_key(self.root.hkey(), self.path, self.create, self.writable)?;
+
+        if let Some(parent) = watch {
+            let stop_event = unsafe { CreateEventW(ptr::null_mut(), 1, 0, ptr::null()) };
+            if stop_event.is_null() {
+                return Err(NwgError::resource_create("Failed to create the RegistryKey stop event"));
+            }
+
+            out.handle = build_notice(parent);
+            out.stop_event = stop_event;
+
+            let (_, id) = out.handle.notice().unwrap();
+            let (hwnd, key, stop_event, subtree) = (parent as usize, out.key as usize, stop_event as usize, self.watch_subtree);
+            out.thread = Some(thread::spawn(move || unsafe { watch_key(hwnd, id, key, stop_event, subtree) }));
+        }
+
+        Ok(())
+    }
+
+}
+
+/// Open or create a registry key
+fn open_key(parent: HKEY, path: &str, create: bool, writable: bool) -> Result<HKEY, NwgError> {
+    let path = to_utf16(path);
+    let access = match writable {
+        true => KEY_READ | KEY_WRITE,
+        false => KEY_READ
+    };
+
+    let mut key: HKEY = ptr::null_mut();
+    let result = unsafe {
+        match create {
+            true => RegCreateKeyExW(parent, path.as_ptr(), 0, ptr::null_mut(), REG_OPTION_NON_VOLATILE, access, ptr::null_mut(), &mut key, ptr::null_mut()),
+            false => RegOpenKeyExW(parent, path.as_ptr(), 0, access, &mut key)
+        }
+    };
+
+    match result as DWORD {
+        ERROR_SUCCESS => Ok(key),
+        code => Err(NwgError::resource_create(format!("Failed to open the registry key (error {})", code)))
+    }
+}
+
+fn check(result: DWORD, message: &str) -> Result<(), NwgError> {
+    match result {
+        ERROR_SUCCESS => Ok(()),
+        code => Err(NwgError::resource_create(format!("{} (error {})", message, code)))
+    }
+}
+
+fn bytes_to_wide(data: &[u8]) -> Vec<u16> {
+    data.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect()
+}
+
+fn wide_to_bytes(data: &[u16]) -> Vec<u8> {
+    data.iter().flat_map(|c| c.to_le_bytes().to_vec()).collect()
+}
+
+/// The watcher thread. Waits for changes in the key until the stop event is set.
+/// The notification must be registered from the thread that waits for it, because it is removed when the thread exits.
+unsafe fn watch_key(hwnd: usize, id: u32, key: usize, stop_event: usize, subtree: bool) {
+    use winapi::um::winreg::RegNotifyChangeKeyValue;
+    use winapi::um::winnt::{REG_NOTIFY_CHANGE_NAME, REG_NOTIFY_CHANGE_LAST_SET};
+    use winapi::um::synchapi::{CreateEventW, WaitForMultipleObjects};
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::winbase::{WAIT_OBJECT_0, INFINITE};
+
+    let change_event = CreateEventW(ptr::null_mut(), 0, 0, ptr::null());
+    if change_event.is_null() {
+        return;
+    }
+
+    let handles = [change_event, stop_event as HANDLE];
+    let filter = REG_NOTIFY_CHANGE_NAME | REG_NOTIFY_CHANGE_LAST_SET;
+
+    loop {
+        if RegNotifyChangeKeyValue(key as HKEY, subtree as _, filter, change_event, 1) as DWORD != ERROR_SUCCESS {
+            break;
+        }
+
+        if WaitForMultipleObjects(2, handles.as_ptr(), 0, INFINITE) != WAIT_OBJECT_0 {
+            break;
+        }
+
+        wh::post_message(hwnd as HWND, wh::NWG_REGISTRY_CHANGE, id as WPARAM, 0);
+    }
+
+    CloseHandle(change_event);
+}
@@ -0,0 +1,55 @@
+/*!
+    Svg rasterization using `resvg`. Used by `Bitmap::from_svg` and `Icon::from_svg`.
+    Text elements are not rendered because no fonts are loaded by the rasterizer.
+*/
+use resvg::{usvg, tiny_skia};
+use crate::NwgError;
+
+
+/**
+    Render svg data into RGBA pixels. Returns `(width, height, pixels)`.
+
+    `size` is the logical size of the output image. If `None`, the size defined in the svg document is used.
+    The output is scaled by `dpi / 96`. If `dpi` is `None`, the system dpi is used.
+*/
+pub(crate) fn render_svg(data: &[u8], size: Option<(u32, u32)>, dpi: Option<u32>) -> Result<(u32, u32, Vec<u8>), NwgError> {
+    let dpi = dpi.unwrap_or_else(|| unsafe { crate::win32::high_dpi::dpi() as u32 });
+
+    // The document is parsed in logical pixels (1in = 96px) and scaled to `dpi` once, when rendered
+    let mut options = usvg::Options::default();
+    options.dpi = 96.0;
+
+    let tree = usvg::Tree::from_data(data, &options)
+        .map_err(|e| NwgError::resource_create(format!("Failed to parse svg: {}", e)))?;
+
+    let svg_size = tree.size();
+    let scale = dpi as f32 / 96.0;
+    let (width, height) = match size {
+        Some((w, h)) => (w as f32 * scale, h as f32 * scale),
+        None => (svg_size.width() * scale, svg_size.height() * scale)
+    };
+
+    let (width, height) = (width.ceil() as u32, height.ceil() as u32);
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| NwgError::resource_create(format!("Invalid svg render size: {}x{}", width, height)))?;
+
+    let transform = tiny_skia::Transform::from_scale(width as f32 / svg_size.width(), height as f32 / svg_size.height());
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+    // tiny-skia pixels are premultiplied
+    let mut pixels = Vec::with_capacity((width as usize) * (height as usize) * 4);
+    for p in pixmap.pixels() {
+        let c = p.demultiply();
+        pixels.extend_from_slice(&[c.red(), c.green(), c.blue(), c.alpha()]);
+    }
+
+    Ok((width, height, pixels))
+}
+
+/// Read a svg file and render it. See `render_svg`.
+pub(crate) fn render_svg_file(path: &str, size: Option<(u32, u32)>, dpi: Option<u32>) -> Result<(u32, u32, Vec<u8>), NwgError> {
+    let data = ::std::fs::read(path)
+        .map_err(|e| NwgError::resource_create(format!("Failed to read svg file {:?}: {}", path, e)))?;
+
+    render_svg(&data, size, dpi)
+}
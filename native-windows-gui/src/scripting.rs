@@ -0,0 +1,273 @@
+/*!
+    Run Rhai scripts in an application, so the users can automate the interface or extend it with macros without recompiling.
+
+    The scripts address the controls by name (see `set_control_name` and the `name` parameter of the builders),
+    and subscribe script functions to the events of the named controls.
+*/
+use crate::controls::ControlHandle;
+use crate::{Event, NwgError};
+use crate::win32::window_helper as wh;
+use rhai::{Engine, Scope, AST, Dynamic, Array, EvalAltResult};
+use winapi::shared::windef::HWND;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::path::Path;
+use std::fs;
+
+
+/// A script function subscribed to an event of a named control with `on(control, event, function)`
+#[derive(Clone, Debug)]
+struct Subscription {
+    control: String,
+    event: String,
+    function: String,
+}
+
+/**
+    An embedded Rhai engine bound to a window. The scripts can read and change the named controls of the window and
+    subscribe to their events. Call `process_event` in the event handler of the application to run the subscribed functions.
+
+    Requires the `scripting` feature.
+
+    Functions available to the scripts:
+      * `text(control)` / `set_text(control, text)`: The text of a control
+      * `enabled(control)` / `set_enabled(control, enabled)`: If the control can be used
+      * `visible(control)` / `set_visible(control, visible)`: If the control is visible
+      * `checked(control)` / `set_checked(control, checked)`: The check state of a check box or a radio button
+      * `click(control)`: Click a button
+      * `focus(control)`: Set the keyboard focus on a control
+      * `controls()`: The names of the named controls of the window
+      * `message(title, text)`: Show a message box
+      * `on(control, event, function)`: Call the script function `function` when `control` raises `event` (ex: `"OnButtonClick"`)
+
+    The application can register its own functions with `engine_mut`.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    const SCRIPT: &'static str = r#"
+        fn clear_all() {
+            set_text("name_input", "");
+            set_checked("subscribe_check", false);
+        }
+
+        on("clear_btn", "OnButtonClick", "clear_all");
+    "#;
+
+    fn load_script(window: &nwg::Window) -> Result<nwg::ScriptEngine, nwg::NwgError> {
+        let engine = nwg::ScriptEngine::new(window);
+        engine.load(SCRIPT)?;
+        Ok(engine)
+    }
+
+    fn handle_event(engine: &nwg::ScriptEngine, evt: nwg::Event, handle: nwg::ControlHandle) {
+        if let Err(e) = engine.process_event(evt, handle) {
+            println!("{}", e);
+        }
+    }
+    ```
+*/
+pub struct ScriptEngine {
+    engine: Engine,
+    ast: RefCell<Option<AST>>,
+    scope: RefCell<Scope<'static>>,
+    subscriptions: Rc<RefCell<Vec<Subscription>>>,
+}
+
+impl ScriptEngine {
+
+    /// Create an engine bound to `window`. This function will panic if `window` is not a window-like control.
+    pub fn new<C: Into<ControlHandle>>(window: C) -> ScriptEngine {
+        let window = window.into();
+        window.hwnd().expect("A script engine must be bound to a window-like control");
+
+        let subscriptions = Rc::new(RefCell::new(Vec::new()));
+
+        let mut engine = Engine::new();
+        register_functions(&mut engine, window, &subscriptions);
+
+        ScriptEngine {
+            engine,
+            ast: RefCell::new(None),
+            scope: RefCell::new(Scope::new()),
+            subscriptions,
+        }
+    }
+
+    /// Returns the Rhai engine, to register the functions and the types of the application
+    pub fn engine_mut(&mut self) -> &mut Engine {
+        &mut self.engine
+    }
+
+    /**
+        Compile and run a script. The functions of the script can then be called with `call` and by the events.
+        Loading a script replaces the previous script, its global variables and its event subscriptions.
+    */
+    pub fn load(&self, script: &str) -> Result<(), NwgError> {
+        let ast = self.engine.compile(script)
+            .map_err(|e| NwgError::scripting(format!("{}", e)))?;
+
+        self.subscriptions.borrow_mut().clear();
+
+        let mut scope = Scope::new();
+        self.engine.run_ast_with_scope(&mut scope, &ast)
+            .map_err(|e| NwgError::scripting(format!("{}", e)))?;
+
+        *self.scope.borrow_mut() = scope;
+        *self.ast.borrow_mut() = Some(ast);
+
+        Ok(())
+    }
+
+    /// Load a script from a file. See `load`.
+    pub fn load_file<P: AsRef<Path>>(&self, path: P) -> Result<(), NwgError> {
+        let script = fs::read_to_string(path)
+            .map_err(|e| NwgError::scripting(format!("Failed to read the script: {}", e)))?;
+
+        self.load(&script)
+    }
+
+    /// Evaluate an expression or a statement (ex: from a macro console) and return its value as a string.
+    /// The global variables and the functions of the loaded script are available.
+    pub fn eval(&self, code: &str) -> Result<String, NwgError> {
+        let code = self.engine.compile(code)
+            .map_err(|e| NwgError::scripting(format!("{}", e)))?;
+
+        let ast = match self.ast.borrow().as_ref() {
+            Some(ast) => ast.clone_functions_only().merge(&code),
+            None => code
+        };
+
+        let mut scope = self.scope.try_borrow_mut()
+            .map_err(|_| NwgError::scripting("The script engine is already running"))?;
+
+        self.engine.eval_ast_with_scope::<Dynamic>(&mut scope, &ast)
+            .map(|value| value.to_string())
+            .map_err(|e| NwgError::scripting(format!("{}", e)))
+    }
+
+    /// Call a function of the loaded script without argument
+    pub fn call(&self, function: &str) -> Result<(), NwgError> {
+        let ast = self.ast.borrow();
+        let ast = match ast.as_ref() {
+            Some(ast) => ast,
+            None => { return Err(NwgError::scripting("No script is loaded")); }
+        };
+
+        let mut scope = self.scope.try_borrow_mut()
+            .map_err(|_| NwgError::scripting("The script engine is already running"))?;
+
+        self.engine.call_fn::<Dynamic>(&mut scope, ast, function, ())
+            .map(|_| ())
+            .map_err(|e| NwgError::scripting(format!("{}", e)))
+    }
+
+    /**
+        Call the script functions subscribed to `evt` on the control `handle`. Only the named controls can raise script events.
+
+        A function that changes a control can raise other events while it runs. The script functions subscribed to those
+        events are not called, because the engine is already running.
+    */
+    pub fn process_event(&self, evt: Event, handle: ControlHandle) -> Result<(), NwgError> {
+        let name = match crate::control_name(handle) {
+            Some(name) => name,
+            None => { return Ok(()); }
+        };
+
+        let event = format!("{:?}", evt);
+        let functions: Vec<String> = self.subscriptions.borrow().iter()
+            .filter(|s| s.control == name && event_matches(&s.event, &event))
+            .map(|s| s.function.clone())
+            .collect();
+
+        if functions.is_empty() || self.scope.try_borrow_mut().is_err() {
+            return Ok(());
+        }
+
+        for function in functions {
+            self.call(&function)?;
+        }
+
+        Ok(())
+    }
+
+}
+
+/// `OnMousePress` matches all the mouse press events, such as `OnMousePress(MousePressLeftUp)`
+fn event_matches(subscribed: &str, event: &str) -> bool {
+    subscribed == event || (event.starts_with(subscribed) && event[subscribed.len()..].starts_with('('))
+}
+
+fn register_functions(engine: &mut Engine, window: ControlHandle, subscriptions: &Rc<RefCell<Vec<Subscription>>>) {
+    use winapi::um::winuser::{BM_GETCHECK, BM_SETCHECK, BM_CLICK, BST_CHECKED, BST_UNCHECKED};
+
+    engine.register_fn("text", move |name: &str| {
+        find(window, name).map(|h| unsafe { wh::get_window_text(h) })
+    });
+
+    engine.register_fn("set_text", move |name: &str, text: &str| {
+        find(window, name).map(|h| unsafe { wh::set_window_text(h, text) })
+    });
+
+    engine.register_fn("enabled", move |name: &str| {
+        find(window, name).map(|h| unsafe { wh::get_window_enabled(h) })
+    });
+
+    engine.register_fn("set_enabled", move |name: &str, enabled: bool| {
+        find(window, name).map(|h| unsafe { wh::set_window_enabled(h, enabled) })
+    });
+
+    engine.register_fn("visible", move |name: &str| {
+        find(window, name).map(|h| unsafe { wh::get_window_visibility(h) })
+    });
+
+    engine.register_fn("set_visible", move |name: &str, visible: bool| {
+        find(window, name).map(|h| unsafe { wh::set_window_visibility(h, visible) })
+    });
+
+    engine.register_fn("checked", move |name: &str| {
+        find(window, name).map(|h| wh::send_message(h, BM_GETCHECK, 0, 0) as usize == BST_CHECKED)
+    });
+
+    engine.register_fn("set_checked", move |name: &str, checked: bool| {
+        let state = match checked {
+            true => BST_CHECKED,
+            false => BST_UNCHECKED
+        };
+
+        find(window, name).map(|h| { wh::send_message(h, BM_SETCHECK, state, 0); })
+    });
+
+    engine.register_fn("click", move |name: &str| {
+        find(window, name).map(|h| { wh::send_message(h, BM_CLICK, 0, 0); })
+    });
+
+    engine.register_fn("focus", move |name: &str| {
+        find(window, name).map(|h| unsafe { wh::set_focus(h) })
+    });
+
+    engine.register_fn("controls", move || {
+        crate::named_controls(window).into_iter()
+            .map(|(name, _)| Dynamic::from(name))
+            .collect::<Array>()
+    });
+
+    engine.register_fn("message", move |title: &str, text: &str| {
+        crate::modal_info_message(window, title, text);
+    });
+
+    let subscriptions = subscriptions.clone();
+    engine.register_fn("on", move |control: &str, event: &str, function: &str| {
+        subscriptions.borrow_mut().push(Subscription {
+            control: control.to_string(),
+            event: event.to_string(),
+            function: function.to_string(),
+        });
+    });
+}
+
+fn find(window: ControlHandle, name: &str) -> Result<HWND, Box<EvalAltResult>> {
+    crate::find_control(window, name)
+        .and_then(|handle| handle.hwnd())
+        .ok_or_else(|| format!("No control named {:?}", name).into())
+}
@@ -0,0 +1,111 @@
+use crate::controls::{days_from_civil, civil_from_days};
+use crate::{DatePickerValue, DateRangePreset};
+
+
+#[test]
+fn epoch() {
+    assert_eq!(days_from_civil(1970, 1, 1), 0);
+    assert_eq!(civil_from_days(0), (1970, 1, 1));
+    assert_eq!(days_from_civil(1970, 12, 31), 364);
+    assert_eq!(days_from_civil(1971, 1, 1), 365);
+}
+
+#[test]
+fn before_epoch() {
+    assert_eq!(days_from_civil(1969, 12, 31), -1);
+    assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    assert_eq!(days_from_civil(1969, 1, 1), -365);
+    assert_eq!(days_from_civil(1900, 1, 1), -25567);
+    assert_eq!(civil_from_days(-25567), (1900, 1, 1));
+    assert_eq!(days_from_civil(1601, 1, 1), -134774);
+}
+
+#[test]
+fn leap_years() {
+    // 2000 is a leap year, 1900 and 2100 are not
+    assert_eq!(days_from_civil(2000, 3, 1) - days_from_civil(2000, 2, 28), 2);
+    assert_eq!(civil_from_days(days_from_civil(2000, 2, 28) + 1), (2000, 2, 29));
+    assert_eq!(days_from_civil(1900, 3, 1) - days_from_civil(1900, 2, 28), 1);
+    assert_eq!(days_from_civil(2100, 3, 1) - days_from_civil(2100, 2, 28), 1);
+    assert_eq!(days_from_civil(2024, 3, 1) - days_from_civil(2024, 2, 28), 2);
+    assert_eq!(days_from_civil(2023, 3, 1) - days_from_civil(2023, 2, 28), 1);
+
+    assert_eq!(days_from_civil(2001, 1, 1) - days_from_civil(2000, 1, 1), 366);
+    assert_eq!(days_from_civil(1901, 1, 1) - days_from_civil(1900, 1, 1), 365);
+}
+
+#[test]
+fn civil_round_trip() {
+    let mut expected = (1599, 1, 1);
+    for days in days_from_civil(1599, 1, 1)..days_from_civil(2401, 1, 1) {
+        assert_eq!(civil_from_days(days), expected);
+        assert_eq!(days_from_civil(expected.0, expected.1, expected.2), days);
+
+        let (year, month, day) = expected;
+        let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+        let month_days = match month {
+            2 if leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        };
+
+        expected = match (month, day) {
+            (12, 31) => (year + 1, 1, 1),
+            (_, d) if d == month_days => (year, month + 1, 1),
+            _ => (year, month, day + 1),
+        };
+    }
+}
+
+fn date(year: u16, month: u16, day: u16) -> DatePickerValue {
+    DatePickerValue { year, month, day }
+}
+
+fn range(preset: DateRangePreset, today: DatePickerValue) -> [(u16, u16, u16); 2] {
+    let [start, end] = preset.range(today).unwrap();
+    [(start.year, start.month, start.day), (end.year, end.month, end.day)]
+}
+
+#[test]
+fn range_presets() {
+    use DateRangePreset::*;
+
+    let today = date(2021, 6, 15);
+    assert_eq!(Custom.range(today), None);
+    assert_eq!(range(Today, today), [(2021, 6, 15), (2021, 6, 15)]);
+    assert_eq!(range(Yesterday, today), [(2021, 6, 14), (2021, 6, 14)]);
+    assert_eq!(range(Last7Days, today), [(2021, 6, 9), (2021, 6, 15)]);
+    assert_eq!(range(Last30Days, today), [(2021, 5, 17), (2021, 6, 15)]);
+    assert_eq!(range(ThisMonth, today), [(2021, 6, 1), (2021, 6, 15)]);
+    assert_eq!(range(LastMonth, today), [(2021, 5, 1), (2021, 5, 31)]);
+    assert_eq!(range(ThisYear, today), [(2021, 1, 1), (2021, 6, 15)]);
+}
+
+#[test]
+fn range_presets_month_boundary() {
+    use DateRangePreset::*;
+
+    let today = date(2021, 3, 1);
+    assert_eq!(range(Yesterday, today), [(2021, 2, 28), (2021, 2, 28)]);
+    assert_eq!(range(Last7Days, today), [(2021, 2, 23), (2021, 3, 1)]);
+    assert_eq!(range(ThisMonth, today), [(2021, 3, 1), (2021, 3, 1)]);
+    assert_eq!(range(LastMonth, today), [(2021, 2, 1), (2021, 2, 28)]);
+
+    let today = date(2020, 3, 31);
+    assert_eq!(range(Last30Days, today), [(2020, 3, 2), (2020, 3, 31)]);
+    assert_eq!(range(LastMonth, today), [(2020, 2, 1), (2020, 2, 29)]);
+}
+
+#[test]
+fn range_presets_year_boundary() {
+    use DateRangePreset::*;
+
+    let today = date(2021, 1, 1);
+    assert_eq!(range(Yesterday, today), [(2020, 12, 31), (2020, 12, 31)]);
+    assert_eq!(range(Last7Days, today), [(2020, 12, 26), (2021, 1, 1)]);
+    assert_eq!(range(Last30Days, today), [(2020, 12, 3), (2021, 1, 1)]);
+    assert_eq!(range(ThisMonth, today), [(2021, 1, 1), (2021, 1, 1)]);
+    assert_eq!(range(LastMonth, today), [(2020, 12, 1), (2020, 12, 31)]);
+    assert_eq!(range(ThisYear, today), [(2021, 1, 1), (2021, 1, 1)]);
+}
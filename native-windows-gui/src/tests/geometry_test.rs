@@ -0,0 +1,88 @@
+use crate::*;
+
+
+#[test]
+fn point_to_physical_and_back() {
+    let p = LogicalPoint::new(10, -20);
+    assert_eq!(p.to_physical(DEFAULT_DPI), PhysicalPoint::new(10, -20));
+    assert_eq!(p.to_physical(144), PhysicalPoint::new(15, -30));
+    assert_eq!(p.to_physical(144).to_logical(144), p);
+    assert_eq!(PhysicalPoint::new(15, -30).to_logical(192), LogicalPoint::new(8, -15));
+}
+
+#[test]
+fn size_to_physical_and_back() {
+    let s = LogicalSize::new(100, 25);
+    assert_eq!(s.to_physical(DEFAULT_DPI), PhysicalSize::new(100, 25));
+    assert_eq!(s.to_physical(120), PhysicalSize::new(125, 31));
+    assert_eq!(s.to_physical(192).to_logical(192), s);
+    assert_eq!(PhysicalSize::new(125, 31).to_logical(120), LogicalSize::new(100, 25));
+}
+
+#[test]
+fn rect_to_physical_and_back() {
+    let r = LogicalRect::new(5, 10, 100, 50);
+    assert_eq!(r.to_physical(144), PhysicalRect::new(8, 15, 150, 75));
+    assert_eq!(r.to_physical(192).to_logical(192), r);
+}
+
+#[test]
+fn conversions_round_to_the_nearest_pixel() {
+    assert_eq!(LogicalPoint::new(1, 3).to_physical(120), PhysicalPoint::new(1, 4));
+    assert_eq!(PhysicalPoint::new(-3, 5).to_logical(144), LogicalPoint::new(-2, 3));
+    assert_eq!(LogicalSize::new(1, 3).to_physical(120), PhysicalSize::new(1, 4));
+}
+
+#[test]
+fn control_dpi_without_window() {
+    assert_eq!(control_dpi(ControlHandle::NoHandle), DEFAULT_DPI);
+    assert_eq!(LogicalPoint::new(7, 9).to_physical_for(ControlHandle::NoHandle), PhysicalPoint::new(7, 9));
+}
+
+#[test]
+fn point_offset() {
+    assert_eq!(LogicalPoint::new(10, 10).offset(5, -15), LogicalPoint::new(15, -5));
+}
+
+#[test]
+fn rect_edges() {
+    let r = LogicalRect::new(-10, 20, 30, 40);
+    assert_eq!(r.right(), 20);
+    assert_eq!(r.bottom(), 60);
+}
+
+#[test]
+fn rect_contains() {
+    let r = LogicalRect::new(0, 0, 10, 10);
+    assert!(r.contains(LogicalPoint::new(0, 0)));
+    assert!(r.contains(LogicalPoint::new(9, 9)));
+    assert!(!r.contains(LogicalPoint::new(10, 5)));
+    assert!(!r.contains(LogicalPoint::new(5, 10)));
+    assert!(!r.contains(LogicalPoint::new(-1, 5)));
+    assert!(!LogicalRect::new(0, 0, 0, 0).contains(LogicalPoint::new(0, 0)));
+}
+
+#[test]
+fn lerp() {
+    let from = LogicalRect::new(0, 100, 10, 50);
+    let to = LogicalRect::new(100, 0, 30, 10);
+    assert_eq!(from.lerp(to, 0.0), from);
+    assert_eq!(from.lerp(to, 1.0), to);
+    assert_eq!(from.lerp(to, 0.5), LogicalRect::new(50, 50, 20, 30));
+}
+
+#[test]
+fn lerp_size_is_never_negative() {
+    let from = LogicalSize::new(10, 10);
+    let to = LogicalSize::new(0, 0);
+    assert_eq!(from.lerp(to, 1.5), LogicalSize::new(0, 0));
+}
+
+#[test]
+fn tuples() {
+    let p: LogicalPoint = (3, -4).into();
+    let s: PhysicalSize = (30, 40).into();
+    assert_eq!(p, LogicalPoint::new(3, -4));
+    assert_eq!(<(i32, i32)>::from(p), (3, -4));
+    assert_eq!(<(u32, u32)>::from(s), (30, 40));
+}
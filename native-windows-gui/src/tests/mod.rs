@@ -12,6 +12,18 @@ use freeing_test::*;
 
 mod other;
 
+mod modal_test;
+
+mod properties_test;
+
+mod winnls_test;
+
+mod date_test;
+
+mod geometry_test;
+
+mod shortcuts_test;
+
 
 #[derive(Default)]
 pub struct TestControlPanel {
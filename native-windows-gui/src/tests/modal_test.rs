@@ -0,0 +1,29 @@
+use crate::Event;
+use crate::win32::modal::can_defer;
+
+
+#[test]
+fn events_dispatched_immediately() {
+    let immediate = [
+        Event::OnWindowClose, Event::OnSessionEnding, Event::OnDeviceArrival, Event::OnDeviceRemoval, Event::OnMinMaxInfo,
+        Event::OnTooltipText, Event::OnPaint, Event::OnFileDrop, Event::OnMenuItemMeasure, Event::OnMenuItemDraw,
+        Event::OnDragEnter, Event::OnDragOver, Event::OnTreeItemDelete, Event::OnTreeItemExpanding, Event::OnMenuOpening,
+    ];
+
+    for evt in immediate.iter() {
+        assert!(!can_defer(*evt), "{:?} must not be deferred", evt);
+    }
+}
+
+#[test]
+fn events_deferred() {
+    let deferred = [
+        Event::OnButtonClick, Event::OnResize, Event::OnMove, Event::OnTimerTick, Event::OnMenuItemSelected,
+        Event::OnTreeItemExpanded, Event::OnTreeItemSelectionChanged, Event::OnDragLeave, Event::OnTextDrop,
+        Event::OnSessionEnd, Event::OnChar,
+    ];
+
+    for evt in deferred.iter() {
+        assert!(can_defer(*evt), "{:?} must be deferred", evt);
+    }
+}
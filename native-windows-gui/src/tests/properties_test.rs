@@ -0,0 +1,136 @@
+use crate::*;
+use crate::controls::{escape, unescape};
+use std::cell::RefCell;
+
+
+/// A control that stores its properties, to test the serialization without a window
+struct FakeControl {
+    values: RefCell<Vec<(&'static str, PropertyValue)>>,
+}
+
+const FAKE_PROPERTIES: &'static [PropertyInfo] = &[
+    PropertyInfo { name: "text", kind: PropertyKind::Text },
+    PropertyInfo { name: "size", kind: PropertyKind::Size },
+    PropertyInfo { name: "enabled", kind: PropertyKind::Bool },
+];
+
+impl FakeControl {
+    fn new(values: Vec<(&'static str, PropertyValue)>) -> FakeControl {
+        FakeControl { values: RefCell::new(values) }
+    }
+}
+
+impl ControlProperties for FakeControl {
+    fn properties(&self) -> Vec<PropertyInfo> {
+        FAKE_PROPERTIES.to_vec()
+    }
+
+    fn property(&self, name: &str) -> Option<PropertyValue> {
+        self.values.borrow().iter().find(|(n, _)| *n == name).map(|(_, v)| v.clone())
+    }
+
+    fn set_property(&self, name: &str, value: PropertyValue) -> Result<(), NwgError> {
+        let mut values = self.values.borrow_mut();
+        match values.iter_mut().find(|(n, _)| *n == name) {
+            Some(v) => { v.1 = value; },
+            None => { return Err(NwgError::property(format!("Unknown property {:?}", name))); }
+        }
+
+        Ok(())
+    }
+}
+
+
+#[test]
+fn parse_values() {
+    assert_eq!(PropertyValue::parse(PropertyKind::Bool, " true "), Some(PropertyValue::Bool(true)));
+    assert_eq!(PropertyValue::parse(PropertyKind::Int, "-12"), Some(PropertyValue::Int(-12)));
+    assert_eq!(PropertyValue::parse(PropertyKind::Text, " a b "), Some(PropertyValue::Text(" a b ".to_string())));
+    assert_eq!(PropertyValue::parse(PropertyKind::Size, "100 x 25"), Some(PropertyValue::Size(100, 25)));
+    assert_eq!(PropertyValue::parse(PropertyKind::Position, "10,-5"), Some(PropertyValue::Position(10, -5)));
+    assert_eq!(PropertyValue::parse(PropertyKind::Choice(&["left", "right"]), "right"), Some(PropertyValue::Text("right".to_string())));
+}
+
+#[test]
+fn parse_malformed_values() {
+    assert_eq!(PropertyValue::parse(PropertyKind::Bool, "yes"), None);
+    assert_eq!(PropertyValue::parse(PropertyKind::Bool, ""), None);
+    assert_eq!(PropertyValue::parse(PropertyKind::Int, "1.5"), None);
+    assert_eq!(PropertyValue::parse(PropertyKind::Size, "100x"), None);
+    assert_eq!(PropertyValue::parse(PropertyKind::Size, "x25"), None);
+    assert_eq!(PropertyValue::parse(PropertyKind::Size, "-1x25"), None);
+    assert_eq!(PropertyValue::parse(PropertyKind::Size, "100,25"), None);
+    assert_eq!(PropertyValue::parse(PropertyKind::Position, "10"), None);
+    assert_eq!(PropertyValue::parse(PropertyKind::Position, "a,b"), None);
+    assert_eq!(PropertyValue::parse(PropertyKind::Choice(&["left", "right"]), "Left"), None);
+}
+
+#[test]
+fn parse_formatted_values() {
+    let values = [
+        (PropertyKind::Bool, PropertyValue::Bool(false)),
+        (PropertyKind::Int, PropertyValue::Int(i64::min_value())),
+        (PropertyKind::Text, PropertyValue::Text("Hello, world".to_string())),
+        (PropertyKind::Size, PropertyValue::Size(0, u32::max_value())),
+        (PropertyKind::Position, PropertyValue::Position(-10, 20)),
+    ];
+
+    for (kind, value) in values.iter() {
+        assert_eq!(PropertyValue::parse(*kind, &value.to_string()).as_ref(), Some(value));
+    }
+}
+
+#[test]
+fn unescape_sequences() {
+    assert_eq!(unescape("a\\nb"), "a\nb");
+    assert_eq!(unescape("a\\r\\nb"), "a\r\nb");
+    assert_eq!(unescape("c:\\\\dir"), "c:\\dir");
+    assert_eq!(unescape("\\t"), "t");
+    assert_eq!(unescape("end\\"), "end\\");
+    assert_eq!(unescape(""), "");
+}
+
+#[test]
+fn escape_round_trip() {
+    let values = ["", "plain", "two\nlines", "crlf\r\n", "c:\\dir\\new", "\\n is not a line break", "\\"];
+    for value in values.iter() {
+        let escaped = escape(value);
+        assert!(!escaped.contains('\n') && !escaped.contains('\r'));
+        assert_eq!(unescape(&escaped), *value);
+    }
+}
+
+#[test]
+fn serialize_round_trip() {
+    let source = FakeControl::new(vec![
+        ("text", PropertyValue::Text("first\nc:\\second".to_string())),
+        ("size", PropertyValue::Size(100, 25)),
+        ("enabled", PropertyValue::Bool(false)),
+    ]);
+
+    let data = serialize_properties(&source);
+    assert_eq!(data.lines().count(), 3);
+
+    let target = FakeControl::new(vec![
+        ("text", PropertyValue::Text(String::new())),
+        ("size", PropertyValue::Size(0, 0)),
+        ("enabled", PropertyValue::Bool(true)),
+    ]);
+
+    deserialize_properties(&target, &data).unwrap();
+    assert_eq!(target.property_values(), source.property_values());
+}
+
+#[test]
+fn deserialize_ignores_unknown_properties() {
+    let target = FakeControl::new(vec![("size", PropertyValue::Size(0, 0))]);
+    deserialize_properties(&target, "color=red\nsize=10x20\n").unwrap();
+    assert_eq!(target.property("size"), Some(PropertyValue::Size(10, 20)));
+}
+
+#[test]
+fn deserialize_bad_value() {
+    let target = FakeControl::new(vec![("size", PropertyValue::Size(0, 0))]);
+    assert!(deserialize_properties(&target, "size=big").is_err());
+    assert_eq!(target.property("size"), Some(PropertyValue::Size(0, 0)));
+}
@@ -0,0 +1,93 @@
+use crate::*;
+
+fn map() -> ShortcutMap {
+    ShortcutMap::detached()
+}
+
+fn shortcut(text: &str) -> Option<Shortcut> {
+    Some(Shortcut::parse(text).unwrap())
+}
+
+#[test]
+fn parse_shortcuts() {
+    assert_eq!(Shortcut::parse("Ctrl+S"), Some(Shortcut::new(ShortcutModifiers::CTRL, 0x53)));
+    assert_eq!(Shortcut::parse(" ctrl + shift + s "), Some(Shortcut::new(ShortcutModifiers::CTRL | ShortcutModifiers::SHIFT, 0x53)));
+    assert_eq!(Shortcut::parse("F5"), Some(Shortcut::new(ShortcutModifiers::NONE, 0x74)));
+    assert_eq!(Shortcut::parse("Alt+Return"), Some(Shortcut::new(ShortcutModifiers::ALT, 0x0D)));
+    assert_eq!(Shortcut::parse("Ctrl+Num+"), Some(Shortcut::new(ShortcutModifiers::CTRL, 0x6B)));
+    assert_eq!(Shortcut::parse("Win+0x2F"), Some(Shortcut::new(ShortcutModifiers::WIN, 0x2F)));
+}
+
+#[test]
+fn parse_invalid_shortcuts() {
+    assert_eq!(Shortcut::parse(""), None);
+    assert_eq!(Shortcut::parse("Ctrl+"), None);
+    assert_eq!(Shortcut::parse("Hyper+S"), None);
+    assert_eq!(Shortcut::parse("F25"), None);
+    assert_eq!(Shortcut::parse("Num10"), None);
+}
+
+#[test]
+fn format_shortcuts() {
+    let all = ShortcutModifiers::CTRL | ShortcutModifiers::ALT | ShortcutModifiers::SHIFT | ShortcutModifiers::WIN;
+    assert_eq!(Shortcut::new(all, 0x41).to_string(), "Ctrl+Alt+Shift+Win+A");
+    assert_eq!(Shortcut::new(ShortcutModifiers::NONE, 0x87).to_string(), "F24");
+    assert_eq!(Shortcut::new(ShortcutModifiers::CTRL, 0x6B).to_string(), "Ctrl+Num+");
+    assert_eq!(Shortcut::new(ShortcutModifiers::NONE, 0xFF).to_string(), "0xFF");
+
+    for text in ["Ctrl+S", "Shift+Delete", "Alt+Num7", "Ctrl+Num+", "Ctrl+]", "0xFF"].iter() {
+        assert_eq!(Shortcut::parse(text).unwrap().to_string(), *text);
+    }
+}
+
+#[test]
+fn save_and_load() {
+    let map = map();
+    map.register("save", "Save", shortcut("Ctrl+S")).unwrap();
+    map.register("open", "Open", shortcut("Ctrl+O")).unwrap();
+    map.register("find", "Find", None).unwrap();
+
+    assert_eq!(map.save(), "nwg-shortcuts 1\n");
+
+    // Swap two shortcuts and give one to an action without a default
+    map.set_shortcut("save", None).unwrap();
+    map.set_shortcut("open", shortcut("Ctrl+S")).unwrap();
+    map.set_shortcut("save", shortcut("Ctrl+O")).unwrap();
+    map.set_shortcut("find", shortcut("Ctrl+F")).unwrap();
+    let saved = map.save();
+
+    map.reset_all();
+    assert_eq!(map.shortcut("save"), shortcut("Ctrl+S"));
+    assert_eq!(map.shortcut("find"), None);
+
+    map.load(&saved).unwrap();
+    assert_eq!(map.shortcut("save"), shortcut("Ctrl+O"));
+    assert_eq!(map.shortcut("open"), shortcut("Ctrl+S"));
+    assert_eq!(map.shortcut("find"), shortcut("Ctrl+F"));
+    assert_eq!(map.save(), saved);
+}
+
+#[test]
+fn load_ignores_unknown_actions() {
+    let map = map();
+    map.register("save", "Save", shortcut("Ctrl+S")).unwrap();
+
+    map.load("nwg-shortcuts 1\nremoved\tCtrl+R\nsave\t\n").unwrap();
+    assert_eq!(map.shortcut("save"), None);
+}
+
+#[test]
+fn failed_load_keeps_the_shortcuts() {
+    let map = map();
+    map.register("save", "Save", shortcut("Ctrl+S")).unwrap();
+    map.register("open", "Open", shortcut("Ctrl+O")).unwrap();
+    map.set_shortcut("save", shortcut("Ctrl+Shift+S")).unwrap();
+
+    assert!(map.load("other header\n").is_err());
+    assert!(map.load("nwg-shortcuts 1\nsave Ctrl+S\n").is_err());
+    assert!(map.load("nwg-shortcuts 1\nsave\tCtrl+Hyper\n").is_err());
+    assert!(map.load("nwg-shortcuts 1\nsave\tCtrl+O\n").is_err());
+
+    assert_eq!(map.shortcut("save"), shortcut("Ctrl+Shift+S"));
+    assert_eq!(map.shortcut("open"), shortcut("Ctrl+O"));
+}
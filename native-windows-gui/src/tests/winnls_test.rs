@@ -0,0 +1,49 @@
+use crate::winnls::{parse_grouping, parse_number};
+
+
+#[test]
+fn grouping() {
+    assert_eq!(parse_grouping("3;0"), 3);
+    assert_eq!(parse_grouping("3;2;0"), 32);
+    assert_eq!(parse_grouping("3"), 30);
+    assert_eq!(parse_grouping("3;2"), 320);
+    assert_eq!(parse_grouping(" 3 ; 0 "), 3);
+}
+
+#[test]
+fn invalid_grouping() {
+    assert_eq!(parse_grouping(""), 0);
+    assert_eq!(parse_grouping("abc"), 0);
+    assert_eq!(parse_grouping("3;x;0"), 30);
+}
+
+#[test]
+fn parse_numbers() {
+    assert_eq!(parse_number("1,234.5", ".", ",").unwrap(), 1234.5);
+    assert_eq!(parse_number(" 42 ", ".", ",").unwrap(), 42.0);
+    assert_eq!(parse_number("1.234,5", ",", ".").unwrap(), 1234.5);
+
+    // Non-breaking space separator typed with regular spaces
+    assert_eq!(parse_number("1\u{a0}234,5", ",", "\u{a0}").unwrap(), 1234.5);
+    assert_eq!(parse_number("1 234 567,5", ",", "\u{a0}").unwrap(), 1234567.5);
+}
+
+#[test]
+fn parse_negative_numbers() {
+    assert_eq!(parse_number("-1,234.5", ".", ",").unwrap(), -1234.5);
+    assert_eq!(parse_number("1,234.5-", ".", ",").unwrap(), -1234.5);
+    assert_eq!(parse_number("- 12", ".", ",").unwrap(), -12.0);
+    assert_eq!(parse_number("(1,234.5)", ".", ",").unwrap(), -1234.5);
+    assert_eq!(parse_number("-1,5", ",", "\u{a0}").unwrap(), -1.5);
+}
+
+#[test]
+fn parse_invalid_numbers() {
+    assert!(parse_number("", ".", ",").is_err());
+    assert!(parse_number("-", ".", ",").is_err());
+    assert!(parse_number("()", ".", ",").is_err());
+    assert!(parse_number("abc", ".", ",").is_err());
+    assert!(parse_number("1.2.3", ".", ",").is_err());
+    assert!(parse_number("1,5", ".", "").is_err());
+    assert!(parse_number("1-2", ".", ",").is_err());
+}
@@ -0,0 +1,446 @@
+/*!
+    Save the state of the controls visible to the user (text, check states, selections, column widths, window placement)
+    and restore it on the next launch. The state is stored as JSON.
+*/
+use serde_json::{Value, Map, json};
+use crate::controls::*;
+use crate::NwgError;
+use std::path::Path;
+use std::fs;
+
+/// The version of the saved format. States saved with another version are ignored.
+const STATE_VERSION: u64 = 1;
+
+
+/**
+    A control with a state that can be saved by `UiState`.
+
+    Implement this trait to save the state of custom controls (ex: the position of a splitter).
+    `restore_state` must ignore values it does not understand, because the saved state may come from an older version of the application.
+*/
+pub trait PersistState {
+    /// Return the state of the control
+    fn save_state(&self) -> Value;
+
+    /// Restore a state returned by `save_state`
+    fn restore_state(&self, state: &Value);
+}
+
+struct StateEntry<'a> {
+    name: String,
+    control: &'a dyn PersistState,
+    persisted: bool,
+}
+
+/**
+    Save and restore the state of a set of controls. Each control is registered with a name that must stay the same
+    between two versions of the application. Controls can be excluded from the state with `set_persisted` (ex: a password field).
+
+    Restoring a state only changes the controls that are both registered and present in the state. Unknown names are ignored.
+
+    Requires the `ui-state` feature.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn save(window: &nwg::Window, name: &nwg::TextInput, remember: &nwg::CheckBox, password: &nwg::TextInput) {
+        let mut state = nwg::UiState::new();
+        state.register("window", window)
+            .register("name", name)
+            .register("remember", remember)
+            .register("password", password);
+
+        state.set_persisted("password", false);
+
+        let path = nwg::app_config_dir("MyApp").unwrap().join("ui.json");
+        state.save_to_file(&path).unwrap();
+    }
+    ```
+*/
+#[derive(Default)]
+pub struct UiState<'a> {
+    controls: Vec<StateEntry<'a>>,
+}
+
+impl<'a> UiState<'a> {
+
+    pub fn new() -> UiState<'a> {
+        UiState { controls: Vec::new() }
+    }
+
+    /// Register a control under `name`. Registering another control with the same name replaces the old one.
+    pub fn register<C: PersistState>(&mut self, name: &str, control: &'a C) -> &mut UiState<'a> {
+        self.controls.retain(|e| e.name != name);
+        self.controls.push(StateEntry { name: name.to_string(), control, persisted: true });
+        self
+    }
+
+    /// Remove a control from the registered controls
+    pub fn unregister(&mut self, name: &str) {
+        self.controls.retain(|e| e.name != name);
+    }
+
+    /// Include or exclude a registered control from the saved and restored state. Does nothing if `name` is not registered.
+    pub fn set_persisted(&mut self, name: &str, persisted: bool) {
+        if let Some(entry) = self.controls.iter_mut().find(|e| e.name == name) {
+            entry.persisted = persisted;
+        }
+    }
+
+    /// Return true if a control is registered under `name` and is included in the state
+    pub fn persisted(&self, name: &str) -> bool {
+        self.controls.iter().any(|e| e.name == name && e.persisted)
+    }
+
+    /// Return the state of the registered controls
+    pub fn save_value(&self) -> Value {
+        let controls: Map<String, Value> = self.controls.iter()
+            .filter(|e| e.persisted)
+            .map(|e| (e.name.clone(), e.control.save_state()))
+            .collect();
+
+        json!({ "version": STATE_VERSION, "controls": controls })
+    }
+
+    /// Restore the registered controls from a state returned by `save_value`. States of another version are ignored.
+    pub fn restore_value(&self, state: &Value) {
+        if state.get("version").and_then(Value::as_u64) != Some(STATE_VERSION) {
+            return;
+        }
+
+        let controls = match state.get("controls").and_then(Value::as_object) {
+            Some(c) => c,
+            None => { return; }
+        };
+
+        for entry in self.controls.iter().filter(|e| e.persisted) {
+            if let Some(value) = controls.get(&entry.name) {
+                entry.control.restore_state(value);
+            }
+        }
+    }
+
+    /// Return the state of the registered controls as a JSON string
+    pub fn save(&self) -> String {
+        serde_json::to_string_pretty(&self.save_value()).unwrap_or_default()
+    }
+
+    /// Restore the registered controls from a JSON string returned by `save`
+    pub fn restore(&self, state: &str) -> Result<(), NwgError> {
+        let value: Value = serde_json::from_str(state)
+            .map_err(|e| NwgError::ui_state(format!("Invalid UI state: {}", e)))?;
+
+        self.restore_value(&value);
+        Ok(())
+    }
+
+    /// Save the state of the registered controls in a file. The parent directory must exist.
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), NwgError> {
+        let path = path.as_ref();
+        fs::write(path, self.save())
+            .map_err(|e| NwgError::ui_state(format!("Failed to write {:?}: {}", path, e)))
+    }
+
+    /**
+        Restore the registered controls from a file written by `save_to_file`.
+        Returns `Ok(false)` if the file does not exist (ex: on the first launch of the application).
+    */
+    pub fn restore_from_file<P: AsRef<Path>>(&self, path: P) -> Result<bool, NwgError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        let state = fs::read_to_string(path)
+            .map_err(|e| NwgError::ui_state(format!("Failed to read {:?}: {}", path, e)))?;
+
+        self.restore(&state)?;
+        Ok(true)
+    }
+
+}
+
+
+impl PersistState for Window {
+
+    /// Save the normal position and size of the window, and if the window is maximized
+    fn save_state(&self) -> Value {
+        use winapi::um::winuser::{GetWindowPlacement, WINDOWPLACEMENT, SW_SHOWMAXIMIZED, WPF_RESTORETOMAXIMIZED};
+        use std::mem;
+
+        let handle = match self.handle.hwnd() {
+            Some(h) => h,
+            None => { return Value::Null; }
+        };
+
+        let mut placement: WINDOWPLACEMENT = unsafe { mem::zeroed() };
+        placement.length = mem::size_of::<WINDOWPLACEMENT>() as u32;
+        if unsafe { GetWindowPlacement(handle, &mut placement) } == 0 {
+            return Value::Null;
+        }
+
+        // A minimized window is restored as it was before being minimized
+        let maximized = placement.showCmd as i32 == SW_SHOWMAXIMIZED || placement.flags & WPF_RESTORETOMAXIMIZED != 0;
+        let r = placement.rcNormalPosition;
+
+        json!({
+            "x": r.left,
+            "y": r.top,
+            "width": r.right - r.left,
+            "height": r.bottom - r.top,
+            "maximized": maximized,
+        })
+    }
+
+    /// Restore the window placement. A hidden window keeps its visibility, and is maximized only when it is shown.
+    fn restore_state(&self, state: &Value) {
+        use winapi::um::winuser::{GetWindowPlacement, SetWindowPlacement, IsWindowVisible, WINDOWPLACEMENT, SW_SHOWMAXIMIZED, SW_SHOWNORMAL,
+            SW_HIDE, WPF_RESTORETOMAXIMIZED};
+        use winapi::shared::windef::RECT;
+        use std::mem;
+
+        let handle = match self.handle.hwnd() {
+            Some(h) => h,
+            None => { return; }
+        };
+
+        let get = |key: &str| state.get(key).and_then(Value::as_i64).map(|v| v as i32);
+        let (x, y, width, height) = match (get("x"), get("y"), get("width"), get("height")) {
+            (Some(x), Some(y), Some(w), Some(h)) if w > 0 && h > 0 => (x, y, w, h),
+            _ => { return; }
+        };
+        let maximized = state.get("maximized").and_then(Value::as_bool).unwrap_or(false);
+
+        let mut placement: WINDOWPLACEMENT = unsafe { mem::zeroed() };
+        placement.length = mem::size_of::<WINDOWPLACEMENT>() as u32;
+        unsafe { GetWindowPlacement(handle, &mut placement); }
+
+        // The system moves the window back on a monitor if the saved position is not visible anymore
+        placement.rcNormalPosition = RECT { left: x, top: y, right: x + width, bottom: y + height };
+        placement.flags = 0;
+        placement.showCmd = match (unsafe { IsWindowVisible(handle) } != 0, maximized) {
+            (true, true) => SW_SHOWMAXIMIZED,
+            (true, false) => SW_SHOWNORMAL,
+            (false, true) => { placement.flags = WPF_RESTORETOMAXIMIZED; SW_HIDE },
+            (false, false) => SW_HIDE,
+        } as u32;
+
+        unsafe { SetWindowPlacement(handle, &placement); }
+    }
+
+}
+
+impl PersistState for TextInput {
+    fn save_state(&self) -> Value {
+        Value::String(self.text())
+    }
+
+    fn restore_state(&self, state: &Value) {
+        if let Some(text) = state.as_str() {
+            self.set_text(text);
+        }
+    }
+}
+
+#[cfg(feature = "textbox")]
+impl PersistState for TextBox {
+    fn save_state(&self) -> Value {
+        Value::String(self.text())
+    }
+
+    fn restore_state(&self, state: &Value) {
+        if let Some(text) = state.as_str() {
+            self.set_text(text);
+        }
+    }
+}
+
+#[cfg(feature = "rich-textbox")]
+impl PersistState for RichTextBox {
+    fn save_state(&self) -> Value {
+        Value::String(self.text())
+    }
+
+    fn restore_state(&self, state: &Value) {
+        if let Some(text) = state.as_str() {
+            self.set_text(text);
+        }
+    }
+}
+
+impl PersistState for CheckBox {
+    fn save_state(&self) -> Value {
+        match self.check_state() {
+            CheckBoxState::Checked => json!("checked"),
+            CheckBoxState::Unchecked => json!("unchecked"),
+            CheckBoxState::Indeterminate => json!("indeterminate"),
+        }
+    }
+
+    fn restore_state(&self, state: &Value) {
+        let check = match state.as_str() {
+            Some("checked") => CheckBoxState::Checked,
+            Some("unchecked") => CheckBoxState::Unchecked,
+            Some("indeterminate") => CheckBoxState::Indeterminate,
+            _ => { return; }
+        };
+
+        self.set_check_state(check);
+    }
+}
+
+impl PersistState for RadioButton {
+    fn save_state(&self) -> Value {
+        Value::Bool(self.check_state() == RadioButtonState::Checked)
+    }
+
+    fn restore_state(&self, state: &Value) {
+        match state.as_bool() {
+            Some(true) => self.set_check_state(RadioButtonState::Checked),
+            Some(false) => self.set_check_state(RadioButtonState::Unchecked),
+            None => {}
+        }
+    }
+}
+
+/// The selected index of a combobox, or `null`
+#[cfg(feature = "combobox")]
+impl<D: std::fmt::Display + Default> PersistState for ComboBox<D> {
+    fn save_state(&self) -> Value {
+        json!(self.selection())
+    }
+
+    fn restore_state(&self, state: &Value) {
+        match state {
+            Value::Null => self.set_selection(None),
+            v => if let Some(index) = v.as_u64().map(|i| i as usize).filter(|&i| i < self.len()) {
+                self.set_selection(Some(index));
+            }
+        }
+    }
+}
+
+/// The selected index of a single selection list box, or the selected indices of a multi selection list box
+#[cfg(feature = "listbox")]
+impl<D: std::fmt::Display + Default> PersistState for ListBox<D> {
+    fn save_state(&self) -> Value {
+        match is_multi_select(&self.handle) {
+            true => json!(self.multi_selection()),
+            false => json!(self.selection()),
+        }
+    }
+
+    fn restore_state(&self, state: &Value) {
+        let len = self.len();
+        match (is_multi_select(&self.handle), state) {
+            (true, Value::Array(indices)) => {
+                self.unselect_all();
+                for index in indices.iter().filter_map(Value::as_u64).map(|i| i as usize).filter(|&i| i < len) {
+                    self.multi_add_selection(index);
+                }
+            },
+            (false, Value::Null) => self.set_selection(None),
+            (false, v) => if let Some(index) = v.as_u64().map(|i| i as usize).filter(|&i| i < len) {
+                self.set_selection(Some(index));
+            },
+            _ => {}
+        }
+    }
+}
+
+#[cfg(feature = "listbox")]
+fn is_multi_select(handle: &ControlHandle) -> bool {
+    use winapi::um::winuser::{GetWindowLongW, GWL_STYLE, LBS_MULTIPLESEL, LBS_EXTENDEDSEL};
+
+    match handle.hwnd() {
+        Some(h) => unsafe { GetWindowLongW(h, GWL_STYLE) as u32 & (LBS_MULTIPLESEL | LBS_EXTENDEDSEL) != 0 },
+        None => false
+    }
+}
+
+#[cfg(feature = "trackbar")]
+impl PersistState for TrackBar {
+    fn save_state(&self) -> Value {
+        json!(self.pos())
+    }
+
+    fn restore_state(&self, state: &Value) {
+        if let Some(pos) = state.as_u64() {
+            self.set_pos(pos as usize);
+        }
+    }
+}
+
+/// The date of a date picker as `[year, month, day]`, or `null` if the date picker is unchecked
+#[cfg(feature = "datetime-picker")]
+impl PersistState for DatePicker {
+    fn save_state(&self) -> Value {
+        match self.value() {
+            Some(v) => json!([v.year, v.month, v.day]),
+            None => Value::Null
+        }
+    }
+
+    fn restore_state(&self, state: &Value) {
+        match state {
+            Value::Null => self.set_value(None),
+            Value::Array(v) if v.len() == 3 => {
+                let values: Vec<u16> = v.iter().filter_map(Value::as_u64).map(|i| i as u16).collect();
+                if let [year, month, day] = values[..] {
+                    self.set_value(Some(DatePickerValue { year, month, day }));
+                }
+            },
+            _ => {}
+        }
+    }
+}
+
+/// The width and the display order of the columns of a list view
+#[cfg(feature = "list-view")]
+impl PersistState for ListView {
+    fn save_state(&self) -> Value {
+        let widths: Vec<i32> = (0..self.column_len())
+            .filter_map(|i| self.column(i, 1))
+            .map(|c| c.width)
+            .collect();
+
+        json!({ "column_widths": widths, "column_order": self.column_order() })
+    }
+
+    fn restore_state(&self, state: &Value) {
+        let widths = match state.get("column_widths").and_then(Value::as_array) {
+            Some(w) => w,
+            None => { return; }
+        };
+
+        // Columns added after the state was saved keep their width
+        let count = self.column_len();
+        for (i, width) in widths.iter().enumerate().take(count) {
+            if let Some(width) = width.as_i64().filter(|&w| w >= 0) {
+                self.set_column_width(i, width as isize);
+            }
+        }
+
+        // The order is only restored if it still matches the columns
+        if let Some(order) = state.get("column_order").and_then(Value::as_array) {
+            let order: Option<Vec<usize>> = order.iter().map(|i| i.as_u64().map(|i| i as usize)).collect();
+            if let Some(order) = order {
+                self.set_column_order(&order);
+            }
+        }
+    }
+}
+
+/// The index of the selected tab
+#[cfg(feature = "tabs")]
+impl PersistState for TabsContainer {
+    fn save_state(&self) -> Value {
+        json!(self.selected_tab())
+    }
+
+    fn restore_state(&self, state: &Value) {
+        if let Some(index) = state.as_u64().map(|i| i as usize).filter(|&i| i < self.tab_count()) {
+            self.set_selected_tab(index);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+/*!
+    Start an application either as a command line tool or as a GUI, depending on its arguments.
+*/
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::env;
+
+
+/// The reason a GUI application was started. See `AppBootstrap`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Activation {
+    /// The application was started without argument
+    Normal,
+
+    /// The application was started to open a file (ex: double click on a document in the explorer)
+    File(PathBuf),
+
+    /// The application was started to open a URI of one of the protocols given to `AppBootstrap::url_scheme`
+    Url(String),
+
+    /// The application was started from a task of its jump list (ex: `app.exe --task new-window`)
+    Task(String),
+}
+
+/// The mode selected by `AppBootstrap` from the command line arguments
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AppMode {
+    /// Show the user interface
+    Gui(Activation),
+
+    /// Run as a command line tool. Holds the arguments of the command line, including the name of the executable.
+    Cli(Vec<OsString>),
+}
+
+/**
+    Select between the command line mode and the GUI mode of an application from its arguments, and run the matching code path.
+
+    An application compiled with `windows_subsystem = "windows"` has no console, so a command line parser (ex: clap) prints
+    its help and its errors to nowhere. `AppBootstrap` attaches the application to the console of the terminal before
+    running the command line mode (see `attach_console`), and initializes native-windows-gui before running the GUI mode.
+
+    The GUI mode is selected when:
+      * There is no argument
+      * The only argument is an existing file (ex: a document opened from the explorer)
+      * The first argument is a URI of one of the registered schemes (see `url_scheme`)
+      * The first argument is the jump list task argument (`--task` by default) followed by the name of the task
+
+    Any other command line selects the command line mode.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn main() {
+        let code = nwg::AppBootstrap::new()
+            .url_scheme("myapp")
+            .run(
+                |args| {
+                    println!("Running headless with {:?}", args);
+                    0
+                },
+                |activation| {
+                    println!("Started with {:?}", activation);
+                    // let app = App::build_ui(Default::default()).expect("Failed to build UI");
+                    nwg::dispatch_thread_events();
+                    0
+                }
+            );
+
+        std::process::exit(code);
+    }
+    ```
+*/
+#[derive(Clone, Debug)]
+pub struct AppBootstrap {
+    url_schemes: Vec<String>,
+    task_argument: String,
+    attach_console: bool,
+}
+
+impl AppBootstrap {
+
+    pub fn new() -> AppBootstrap {
+        AppBootstrap {
+            url_schemes: Vec::new(),
+            task_argument: "--task".to_string(),
+            attach_console: true,
+        }
+    }
+
+    /// Start the GUI mode when the first argument is a URI with this scheme (ex: "myapp" for "myapp://open?id=10")
+    pub fn url_scheme(mut self, scheme: &str) -> AppBootstrap {
+        self.url_schemes.push(scheme.to_lowercase());
+        self
+    }
+
+    /// The argument that precedes the name of a jump list task. Accepts `--task name` and `--task=name`. Defaults to `--task`.
+    pub fn task_argument(mut self, argument: &str) -> AppBootstrap {
+        self.task_argument = argument.to_string();
+        self
+    }
+
+    /// Attach the application to the console of the terminal in the command line mode. Defaults to true.
+    pub fn attach_console(mut self, attach: bool) -> AppBootstrap {
+        self.attach_console = attach;
+        self
+    }
+
+    /// Select the mode from the arguments of the current process
+    pub fn mode(&self) -> AppMode {
+        self.mode_from(env::args_os())
+    }
+
+    /// Select the mode from a list of arguments. The first argument must be the name of the executable.
+    pub fn mode_from<I: IntoIterator<Item=OsString>>(&self, args: I) -> AppMode {
+        let args: Vec<OsString> = args.into_iter().collect();
+        match self.activation(&args) {
+            Some(activation) => AppMode::Gui(activation),
+            None => AppMode::Cli(args)
+        }
+    }
+
+    /**
+        Run `cli` or `gui` depending on the mode selected by `mode`, and return the exit code returned by the closure.
+
+        In the GUI mode, native-windows-gui is initialized before `gui` is called. If the initialization fails,
+        an error message is shown and the exit code is 1.
+    */
+    pub fn run<C, G>(self, cli: C, gui: G) -> i32
+        where C: FnOnce(Vec<OsString>) -> i32,
+              G: FnOnce(Activation) -> i32
+    {
+        match self.mode() {
+            AppMode::Cli(args) => {
+                if self.attach_console {
+                    super::console::attach_console();
+                }
+
+                cli(args)
+            },
+            AppMode::Gui(activation) => match crate::init() {
+                Ok(()) => gui(activation),
+                Err(e) => {
+                    super::message_box::error_message("Error", &format!("Failed to initialize the application: {}", e));
+                    1
+                }
+            }
+        }
+    }
+
+    fn activation(&self, args: &[OsString]) -> Option<Activation> {
+        let first = match args.get(1) {
+            Some(arg) => arg,
+            None => { return Some(Activation::Normal); }
+        };
+
+        let text = first.to_str().unwrap_or("");
+
+        if text == self.task_argument {
+            return match args.len() {
+                3 => args[2].to_str().map(|task| Activation::Task(task.to_string())),
+                _ => None
+            };
+        }
+
+        let task_prefix = format!("{}=", self.task_argument);
+        if text.starts_with(&task_prefix) && args.len() == 2 {
+            return Some(Activation::Task(text[task_prefix.len()..].to_string()));
+        }
+
+        if let Some(index) = text.find(':') {
+            if self.url_schemes.iter().any(|s| *s == text[..index].to_lowercase()) {
+                return Some(Activation::Url(text.to_string()));
+            }
+        }
+
+        if args.len() == 2 && !text.starts_with('-') && Path::new(first).is_file() {
+            return Some(Activation::File(PathBuf::from(first)));
+        }
+
+        None
+    }
+
+}
+
+impl Default for AppBootstrap {
+    fn default() -> AppBootstrap {
+        AppBootstrap::new()
+    }
+}
@@ -1,6 +1,6 @@
 use crate::controls::ControlHandle;
 use super::base_helper::{to_utf16};
-use winapi::um::winuser::{CF_BITMAP, CF_TEXT, CF_UNICODETEXT};
+use winapi::um::winuser::{CF_BITMAP, CF_DIB, CF_TEXT, CF_UNICODETEXT};
 use winapi::um::winnt::HANDLE;
 
 
@@ -15,6 +15,9 @@ pub enum ClipboardFormat {
     /// A bitmap file
     Bitmap,
 
+    /// A device independent bitmap: a `BITMAPINFO` header followed by the pixels
+    Dib,
+
     /// Global clipboard format to share data between applications
     /// The format name comparison is case-insensitive.
     Global(&'static str)
@@ -31,6 +34,7 @@ impl ClipboardFormat {
             Text => CF_TEXT,
             UnicodeText => CF_UNICODETEXT,
             Bitmap => CF_BITMAP,
+            Dib => CF_DIB,
             Global(v) => unsafe {
                 let v = to_utf16(v);
                 RegisterClipboardFormatW(v.as_ptr())
@@ -191,6 +195,34 @@ impl Clipboard {
         Clipboard::close();
     }
 
+    /**
+        Fill the clipboard with an image. `pixels` are the RGBA pixels of the image, row by row from the top.
+        The image is copied in the `ClipboardFormat::Dib` format and in the `PNG` format, so it can be pasted in the applications
+        that do not support the transparency of the bitmaps (`Dib`) as well as in the image editors and the web browsers (`PNG`).
+
+        This is a high level function that handles `open` and `close`
+
+        This function will panic if `pixels` is smaller than `width * height * 4`.
+    */
+    pub fn set_data_image<C: Into<ControlHandle>>(handle: C, width: u32, height: u32, pixels: &[u8]) {
+        let length = (width as usize) * (height as usize) * 4;
+        assert!(pixels.len() >= length, "The image has less than width * height pixels");
+
+        let pixels = &pixels[..length];
+        let dib = dib_data(width, height, pixels);
+        let png = png_data(width, height, pixels);
+
+        Clipboard::open(handle);
+        Clipboard::empty();
+
+        unsafe {
+            Clipboard::set_data(ClipboardFormat::Dib, dib.as_ptr(), dib.len());
+            Clipboard::set_data(ClipboardFormat::Global("PNG"), png.as_ptr(), png.len());
+        }
+
+        Clipboard::close();
+    }
+
     /**
         Return the current text value in the clipboard (if there is one).
         This function will return the text if the clipboard has either the `UnicodeText` format or the `Text` format.
@@ -373,6 +405,125 @@ impl Clipboard {
 }
 
 
+/// A `CF_DIB` image: a `BITMAPINFOHEADER` followed by the pixels in BGRA, row by row from the bottom
+fn dib_data(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    use winapi::um::wingdi::{BITMAPINFOHEADER, BI_RGB};
+    use std::{mem, slice};
+
+    let header = BITMAPINFOHEADER {
+        biSize: mem::size_of::<BITMAPINFOHEADER>() as u32,
+        biWidth: width as i32, biHeight: height as i32,
+        biPlanes: 1, biBitCount: 32, biCompression: BI_RGB,
+        biSizeImage: width * height * 4,
+        biXPelsPerMeter: 0, biYPelsPerMeter: 0,
+        biClrUsed: 0, biClrImportant: 0
+    };
+
+    let header_size = mem::size_of::<BITMAPINFOHEADER>();
+    let mut data = Vec::with_capacity(header_size + pixels.len());
+    data.extend_from_slice(unsafe { slice::from_raw_parts(&header as *const BITMAPINFOHEADER as *const u8, header_size) });
+
+    let stride = (width * 4) as usize;
+    if stride > 0 {
+        for row in pixels.chunks_exact(stride).rev() {
+            for p in row.chunks_exact(4) {
+                data.extend_from_slice(&[p[2], p[1], p[0], p[3]]);
+            }
+        }
+    }
+
+    data
+}
+
+/**
+    A PNG image with the RGBA pixels. The image data is stored without compression: the clipboard data
+    only lives until the next copy, so the encoding speed matters more than the size.
+*/
+fn png_data(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    /// Largest block of a deflate stream without compression
+    const STORED_BLOCK: usize = 0xFFFF;
+
+    // Every row starts with the filter type (0: None)
+    let stride = (width * 4) as usize;
+    let mut raw = Vec::with_capacity((stride + 1) * height as usize);
+    if stride > 0 {
+        for row in pixels.chunks_exact(stride) {
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+    }
+
+    // zlib stream made of stored deflate blocks
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = raw.chunks(STORED_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none() as u8;
+        let len = block.len() as u16;
+        zlib.push(last);
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[8, 6, 0, 0, 0]); // 8 bits per channel, RGBA, deflate, adaptive filtering, no interlace
+
+    let mut png = b"\x89PNG\r\n\x1A\n".to_vec();
+    png_chunk(&mut png, b"IHDR", &header);
+    png_chunk(&mut png, b"IDAT", &zlib);
+    png_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+
+    let crc = crc32(crc32(0xFFFF_FFFF, kind), data);
+    png.extend_from_slice(&(!crc).to_be_bytes());
+}
+
+/// Update a CRC-32 (ISO-HDLC) with `data`. The caller inverts the initial and the final value.
+fn crc32(mut crc: u32, data: &[u8]) -> u32 {
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xEDB8_8320,
+                _ => crc >> 1
+            };
+        }
+    }
+
+    crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD_ADLER: u32 = 65521;
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+
+        a %= MOD_ADLER;
+        b %= MOD_ADLER;
+    }
+
+    (b << 16) | a
+}
+
 unsafe fn from_wide_ptr(ptr: *const u16) -> Option<String> {
     use std::slice::from_raw_parts;
     use std::ffi::OsString;
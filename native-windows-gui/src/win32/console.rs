@@ -0,0 +1,113 @@
+/*!
+    Console helpers for the applications compiled with `windows_subsystem = "windows"`. Those applications do not have
+    a console, so `println!` and `eprintln!` print nothing, even when the application is launched from a terminal.
+*/
+use winapi::shared::minwindef::DWORD;
+use winapi::um::winbase::{STD_INPUT_HANDLE, STD_OUTPUT_HANDLE, STD_ERROR_HANDLE};
+use crate::win32::base_helper::to_utf16;
+use std::ptr;
+
+
+/**
+    Attach the application to the console of the terminal that launched it, and redirect the standard output, the standard error
+    and the standard input to this console. Returns false if the application was not launched from a terminal or if it already has a console.
+
+    The standard streams that were redirected by the user (ex: `app.exe > log.txt`) are not changed.
+    The terminal does not wait for a GUI application, so the output is printed after the prompt of the terminal.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn init_logging() {
+        if std::env::args().any(|a| a == "--verbose") && nwg::attach_console() {
+            println!();
+            println!("Verbose mode enabled");
+        }
+    }
+    ```
+*/
+pub fn attach_console() -> bool {
+    use winapi::um::wincon::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    unsafe {
+        if AttachConsole(ATTACH_PARENT_PROCESS) == 0 {
+            return false;
+        }
+
+        redirect_std_handles();
+        true
+    }
+}
+
+/**
+    Attach the application to the console of the terminal that launched it, or open a new console window if there is none.
+    The standard streams are redirected like in `attach_console`. Returns false if the application already has a console.
+
+    The console window is closed with `free_console` or when the application exits.
+*/
+pub fn alloc_console() -> bool {
+    use winapi::um::consoleapi::AllocConsole;
+
+    if attach_console() {
+        return true;
+    }
+
+    unsafe {
+        if AllocConsole() == 0 {
+            return false;
+        }
+
+        redirect_std_handles();
+        true
+    }
+}
+
+/// Detach the application from its console. Returns false if the application does not have a console.
+pub fn free_console() -> bool {
+    use winapi::um::wincon::FreeConsole;
+
+    unsafe { FreeConsole() != 0 }
+}
+
+/// Returns true if the application has a console
+pub fn has_console() -> bool {
+    use winapi::um::wincon::GetConsoleWindow;
+
+    unsafe { !GetConsoleWindow().is_null() }
+}
+
+/// The rust standard streams read the system handles on each call, so setting the handles is enough
+unsafe fn redirect_std_handles() {
+    redirect_std_handle(STD_OUTPUT_HANDLE, "CONOUT$");
+    redirect_std_handle(STD_ERROR_HANDLE, "CONOUT$");
+    redirect_std_handle(STD_INPUT_HANDLE, "CONIN$");
+}
+
+unsafe fn redirect_std_handle(std_handle: DWORD, device: &str) {
+    use winapi::um::processenv::{GetStdHandle, SetStdHandle};
+    use winapi::um::fileapi::{CreateFileW, GetFileType, OPEN_EXISTING};
+    use winapi::um::winbase::FILE_TYPE_UNKNOWN;
+    use winapi::um::winnt::{GENERIC_READ, GENERIC_WRITE, FILE_SHARE_READ, FILE_SHARE_WRITE};
+    use winapi::um::handleapi::INVALID_HANDLE_VALUE;
+
+    // Keep the streams redirected to a file or a pipe
+    let current = GetStdHandle(std_handle);
+    if !current.is_null() && current != INVALID_HANDLE_VALUE && GetFileType(current) != FILE_TYPE_UNKNOWN {
+        return;
+    }
+
+    let device = to_utf16(device);
+    let handle = CreateFileW(
+        device.as_ptr(),
+        GENERIC_READ | GENERIC_WRITE,
+        FILE_SHARE_READ | FILE_SHARE_WRITE,
+        ptr::null_mut(),
+        OPEN_EXISTING,
+        0,
+        ptr::null_mut()
+    );
+
+    if handle != INVALID_HANDLE_VALUE {
+        SetStdHandle(std_handle, handle);
+    }
+}
@@ -0,0 +1,136 @@
+/*!
+    Names of the controls. A name is stored in a property of the control window, so a control can be found by its name
+    from its top level window without access to the struct that owns it.
+*/
+use winapi::shared::windef::HWND;
+use winapi::shared::minwindef::{BOOL, UINT, WPARAM, LPARAM, LRESULT};
+use winapi::shared::basetsd::{UINT_PTR, DWORD_PTR};
+use crate::controls::ControlHandle;
+use super::base_helper::to_utf16;
+
+const NAME_PROP: &'static str = "NWG_CONTROL_NAME";
+
+/// The id of the subclass that removes the name property when a named control is destroyed
+const NAME_SUBCLASS_ID: UINT_PTR = 0x4E414D45;
+
+
+/**
+    Sets the name of a control. The name is used to find the control with `find_control`. Names should be unique in a top level window.
+    Controls can also be named with the `name` parameter of their builder.
+
+    This function will panic if `control` is not a window-like control.
+*/
+pub fn set_control_name<C: Into<ControlHandle>>(control: C, name: &str) {
+    use winapi::um::winuser::SetPropW;
+    use winapi::um::commctrl::SetWindowSubclass;
+
+    let handle = control.into().hwnd().expect("Only the window-like controls can have a name");
+
+    // The property holds a boxed name. It is freed when the name is replaced or when the control is destroyed.
+    let name = Box::into_raw(Box::new(name.to_string()));
+    let prop = to_utf16(NAME_PROP);
+    unsafe {
+        free_name(handle);
+        SetPropW(handle, prop.as_ptr(), name as _);
+        SetWindowSubclass(handle, Some(remove_name_on_destroy), NAME_SUBCLASS_ID, 0);
+    }
+}
+
+/// Returns the name of a control, or `None` if the control has no name or is not a window-like control.
+pub fn control_name<C: Into<ControlHandle>>(control: C) -> Option<String> {
+    let handle = control.into().hwnd()?;
+    unsafe { name_of(handle).cloned() }
+}
+
+/**
+    Returns the control named `name` in `window`, or `None` if there is none. `window` and all its descendants are searched,
+    so the controls of the tabs and the frames of the window are found. If more than one control has this name, the first one is returned.
+
+    The returned handle is a `ControlHandle::Hwnd`. Compare it with the handle of a control to know its type.
+
+    This function will panic if `window` is not a window-like control.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn save_button(window: &nwg::Window) -> Option<nwg::ControlHandle> {
+        nwg::find_control(window, "save_btn")
+    }
+    ```
+*/
+pub fn find_control<C: Into<ControlHandle>>(window: C, name: &str) -> Option<ControlHandle> {
+    named_windows(window)
+        .into_iter()
+        .find(|(_, n)| n == name)
+        .map(|(hwnd, _)| ControlHandle::Hwnd(hwnd))
+}
+
+/**
+    Returns the names and the handles of all the named controls in `window`, including `window`.
+
+    This function will panic if `window` is not a window-like control.
+*/
+pub fn named_controls<C: Into<ControlHandle>>(window: C) -> Vec<(String, ControlHandle)> {
+    named_windows(window)
+        .into_iter()
+        .map(|(hwnd, name)| (name, ControlHandle::Hwnd(hwnd)))
+        .collect()
+}
+
+/// Returns the named windows in `window` and their name
+fn named_windows<C: Into<ControlHandle>>(window: C) -> Vec<(HWND, String)> {
+    use winapi::um::winuser::EnumChildWindows;
+
+    let handle = window.into().hwnd().expect("Controls can only be searched in a window-like control");
+
+    let mut windows = Vec::new();
+    unsafe {
+        if let Some(name) = name_of(handle) {
+            windows.push((handle, name.clone()));
+        }
+
+        EnumChildWindows(handle, Some(collect_named_window), &mut windows as *mut Vec<(HWND, String)> as LPARAM);
+    }
+
+    windows
+}
+
+unsafe extern "system" fn collect_named_window(handle: HWND, windows: LPARAM) -> BOOL {
+    let windows = &mut *(windows as *mut Vec<(HWND, String)>);
+    if let Some(name) = name_of(handle) {
+        windows.push((handle, name.clone()));
+    }
+
+    1
+}
+
+unsafe fn name_of<'a>(handle: HWND) -> Option<&'a String> {
+    use winapi::um::winuser::GetPropW;
+
+    let prop = to_utf16(NAME_PROP);
+    (GetPropW(handle, prop.as_ptr()) as *const String).as_ref()
+}
+
+/// Removes the name property of a control and frees its name
+unsafe fn free_name(handle: HWND) {
+    use winapi::um::winuser::RemovePropW;
+
+    let prop = to_utf16(NAME_PROP);
+    let name = RemovePropW(handle, prop.as_ptr()) as *mut String;
+    if !name.is_null() {
+        drop(Box::from_raw(name));
+    }
+}
+
+/// Removes the name property of a control and frees its name when it is destroyed. Setting the name again only replaces the subclass.
+unsafe extern "system" fn remove_name_on_destroy(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, id: UINT_PTR, _data: DWORD_PTR) -> LRESULT {
+    use winapi::um::winuser::WM_NCDESTROY;
+    use winapi::um::commctrl::{DefSubclassProc, RemoveWindowSubclass};
+
+    if msg == WM_NCDESTROY {
+        free_name(hwnd);
+        RemoveWindowSubclass(hwnd, Some(remove_name_on_destroy), id);
+    }
+
+    DefSubclassProc(hwnd, msg, w, l)
+}
@@ -0,0 +1,388 @@
+/*!
+    A crash dialog for the applications without a console. A panic or an unhandled system exception shows a task dialog
+    with the error, writes a report (and a minidump) on the disk, then closes the application.
+*/
+use winapi::shared::minwindef::{BOOL, DWORD, UINT, WPARAM, LPARAM};
+use winapi::shared::basetsd::LONG_PTR;
+use winapi::shared::windef::HWND;
+use winapi::shared::ntdef::{HANDLE, HRESULT, LONG};
+use winapi::um::winnt::EXCEPTION_POINTERS;
+use crate::win32::base_helper::to_utf16;
+use crate::NwgError;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{env, fs, mem, panic, process, ptr, thread};
+
+#[repr(C, packed(4))]
+#[allow(non_snake_case)]
+struct MINIDUMP_EXCEPTION_INFORMATION {
+    ThreadId: DWORD,
+    ExceptionPointers: *mut EXCEPTION_POINTERS,
+    ClientPointers: BOOL,
+}
+
+#[link(name = "dbghelp")]
+extern "system" {
+    fn MiniDumpWriteDump(process: HANDLE, process_id: DWORD, file: HANDLE, dump_type: UINT,
+        exception_param: *const MINIDUMP_EXCEPTION_INFORMATION, user_stream_param: *const u8, callback_param: *const u8) -> BOOL;
+}
+
+const MINIDUMP_WITH_INDIRECTLY_REFERENCED_MEMORY: UINT = 0x40;
+const MINIDUMP_WITH_THREAD_INFO: UINT = 0x1000;
+const EXCEPTION_EXECUTE_HANDLER: LONG = 1;
+
+const COPY_BUTTON: i32 = 1000;
+const RESTART_BUTTON: i32 = 1001;
+const CLOSE_BUTTON: i32 = 1002;
+
+/// The exit code of the application after a crash. Same as the exit code of a Rust panic.
+const CRASH_EXIT_CODE: i32 = 101;
+
+type CrashLog = dyn Fn() -> String + Send + Sync;
+
+struct CrashConfig {
+    app_name: String,
+    report_dir: PathBuf,
+    minidump: bool,
+    restart: bool,
+    log: Option<Box<CrashLog>>,
+}
+
+lazy_static! {
+    static ref CRASH_CONFIG: Mutex<Option<Arc<CrashConfig>>> = Mutex::new(None);
+}
+
+/// Set by the first crash so a crash in the dialog does not open a second one
+static CRASHING: AtomicBool = AtomicBool::new(false);
+
+
+/**
+    Shows a crash dialog when the application panics or raises an unhandled system exception (ex: an access violation in an external library).
+    Without it, a `windows_subsystem = "windows"` application closes without a message.
+
+    The dialog displays the panic message and saves a report in the report directory: a text file with the details of the error
+    and the output of the `log` callback, and a minidump of the process that can be opened with a debugger. The user can copy the
+    details to the clipboard or restart the application. The application always exits with the code 101 after the dialog is closed.
+
+    The panic hook installed before the crash handler (ex: the default hook that prints the panic message) is still called.
+
+    Requires the `crash-dialog` feature.
+
+    **Builder parameters:**
+      * `app_name`:    The name of the application displayed in the dialog. Defaults to the name of the executable.
+      * `report_dir`:  The directory of the crash reports. Created if it does not exist. Defaults to the temporary directory.
+      * `minidump`:    If a minidump is written with the report. Defaults to true.
+      * `restart`:     If the dialog offers to restart the application. Defaults to true.
+      * `log`:         A callback that returns the recent log of the application, appended to the report. It must not panic.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn init_crash_handler() {
+        let report_dir = nwg::app_data_dir("MyApp").unwrap().join("crashes");
+
+        nwg::CrashHandler::builder()
+            .app_name("My App")
+            .report_dir(report_dir)
+            .install()
+            .expect("Failed to install the crash handler");
+    }
+    ```
+*/
+pub struct CrashHandler;
+
+impl CrashHandler {
+
+    pub fn builder() -> CrashHandlerBuilder {
+        CrashHandlerBuilder {
+            app_name: None,
+            report_dir: None,
+            minidump: true,
+            restart: true,
+            log: None,
+        }
+    }
+
+    /// Returns true if a crash handler was installed
+    pub fn installed() -> bool {
+        CRASH_CONFIG.lock().map(|c| c.is_some()).unwrap_or(false)
+    }
+
+}
+
+pub struct CrashHandlerBuilder {
+    app_name: Option<String>,
+    report_dir: Option<PathBuf>,
+    minidump: bool,
+    restart: bool,
+    log: Option<Box<CrashLog>>,
+}
+
+impl CrashHandlerBuilder {
+
+    pub fn app_name<S: Into<String>>(mut self, name: S) -> CrashHandlerBuilder {
+        self.app_name = Some(name.into());
+        self
+    }
+
+    pub fn report_dir<P: Into<PathBuf>>(mut self, dir: P) -> CrashHandlerBuilder {
+        self.report_dir = Some(dir.into());
+        self
+    }
+
+    pub fn minidump(mut self, minidump: bool) -> CrashHandlerBuilder {
+        self.minidump = minidump;
+        self
+    }
+
+    pub fn restart(mut self, restart: bool) -> CrashHandlerBuilder {
+        self.restart = restart;
+        self
+    }
+
+    pub fn log<F: Fn() -> String + Send + Sync + 'static>(mut self, log: F) -> CrashHandlerBuilder {
+        self.log = Some(Box::new(log));
+        self
+    }
+
+    /// Install the crash handler. Installing it a second time replaces the parameters of the first one.
+    pub fn install(self) -> Result<(), NwgError> {
+        use winapi::um::errhandlingapi::SetUnhandledExceptionFilter;
+
+        let app_name = match self.app_name {
+            Some(name) => name,
+            None => env::current_exe().ok()
+                .and_then(|exe| exe.file_stem().map(|s| s.to_string_lossy().to_string()))
+                .unwrap_or_else(|| "The application".to_string())
+        };
+
+        let config = CrashConfig {
+            app_name,
+            report_dir: self.report_dir.unwrap_or_else(env::temp_dir),
+            minidump: self.minidump,
+            restart: self.restart,
+            log: self.log,
+        };
+
+        let mut current = CRASH_CONFIG.lock()
+            .map_err(|_| NwgError::initialization("The crash handler is not available"))?;
+
+        if current.is_none() {
+            let previous_hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                previous_hook(info);
+
+                let message = match info.payload().downcast_ref::<&str>() {
+                    Some(s) => s.to_string(),
+                    None => match info.payload().downcast_ref::<String>() {
+                        Some(s) => s.clone(),
+                        None => "Unknown panic".to_string()
+                    }
+                };
+
+                let location = info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+                let details = format!("Panic: {}\nLocation: {}", message, location.as_deref().unwrap_or("unknown"));
+                crash(&message, &details, ptr::null_mut());
+            }));
+
+            unsafe { SetUnhandledExceptionFilter(Some(exception_filter)); }
+        }
+
+        *current = Some(Arc::new(config));
+
+        Ok(())
+    }
+
+}
+
+
+unsafe extern "system" fn exception_filter(info: *mut EXCEPTION_POINTERS) -> LONG {
+    let record = &*(&*info).ExceptionRecord;
+    let code = record.ExceptionCode;
+    let message = format!("Unhandled exception 0x{:08X}", code);
+    let details = format!("Exception: 0x{:08X}\nAddress: {:p}", code, record.ExceptionAddress);
+    crash(&message, &details, info);
+
+    EXCEPTION_EXECUTE_HANDLER
+}
+
+/// Write the report, show the dialog and exit the application
+fn crash(message: &str, details: &str, exception: *mut EXCEPTION_POINTERS) {
+    if CRASHING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    // Another thread may have panicked while holding the lock
+    let config = match CRASH_CONFIG.lock() {
+        Ok(config) => config.clone(),
+        Err(poisoned) => poisoned.into_inner().clone()
+    };
+
+    let config = match config {
+        Some(config) => config,
+        None => { return; }
+    };
+
+    let report = write_report(&config, details, exception);
+    let choice = show_dialog(&config, message, &report);
+
+    if choice == RESTART_BUTTON {
+        if let Ok(exe) = env::current_exe() {
+            drop(process::Command::new(exe).args(env::args_os().skip(1)).spawn());
+        }
+    }
+
+    process::exit(CRASH_EXIT_CODE);
+}
+
+/// Write the crash report and the minidump. Returns the details with the path of the files.
+fn write_report(config: &CrashConfig, details: &str, exception: *mut EXCEPTION_POINTERS) -> String {
+    let time = SystemTime::now().duration_since(UNIX_EPOCH).map(|t| t.as_secs()).unwrap_or(0);
+    let thread = thread::current();
+
+    let mut report = format!(
+        "{} crashed\n{}\nThread: {}\nTime: {}\nExecutable: {}\n",
+        config.app_name,
+        details,
+        thread.name().unwrap_or("unnamed"),
+        time,
+        env::current_exe().map(|e| e.display().to_string()).unwrap_or_default()
+    );
+
+    if let Some(log) = config.log.as_ref() {
+        report.push_str("\nLog:\n");
+        report.push_str(&log());
+        report.push('\n');
+    }
+
+    if fs::create_dir_all(&config.report_dir).is_err() {
+        return report;
+    }
+
+    let name = format!("crash-{}", time);
+    let report_path = config.report_dir.join(format!("{}.txt", name));
+    let mut files = Vec::new();
+
+    if fs::write(&report_path, &report).is_ok() {
+        files.push(report_path);
+    }
+
+    if config.minidump {
+        let dump_path = config.report_dir.join(format!("{}.dmp", name));
+        if write_minidump(&dump_path, exception) {
+            files.push(dump_path);
+        }
+    }
+
+    for file in files {
+        report.push_str(&format!("\nSaved to {}", file.display()));
+    }
+
+    report
+}
+
+fn write_minidump(path: &Path, exception: *mut EXCEPTION_POINTERS) -> bool {
+    use winapi::um::processthreadsapi::{GetCurrentProcess, GetCurrentProcessId, GetCurrentThreadId};
+    use std::os::windows::io::AsRawHandle;
+
+    let file = match fs::File::create(path) {
+        Ok(file) => file,
+        Err(_) => { return false; }
+    };
+
+    unsafe {
+        let exception_info = MINIDUMP_EXCEPTION_INFORMATION {
+            ThreadId: GetCurrentThreadId(),
+            ExceptionPointers: exception,
+            ClientPointers: 0,
+        };
+
+        let exception_param = match exception.is_null() {
+            true => ptr::null(),
+            false => &exception_info as *const MINIDUMP_EXCEPTION_INFORMATION
+        };
+
+        let written = MiniDumpWriteDump(
+            GetCurrentProcess(),
+            GetCurrentProcessId(),
+            file.as_raw_handle() as HANDLE,
+            MINIDUMP_WITH_INDIRECTLY_REFERENCED_MEMORY | MINIDUMP_WITH_THREAD_INFO,
+            exception_param,
+            ptr::null(),
+            ptr::null()
+        );
+
+        written != 0
+    }
+}
+
+/// Show the task dialog. Falls back to a message box if the task dialog is not available (comctl32 version 5).
+fn show_dialog(config: &CrashConfig, message: &str, report: &str) -> i32 {
+    use winapi::um::commctrl::{TaskDialogIndirect, TASKDIALOGCONFIG, TASKDIALOG_BUTTON, TD_ERROR_ICON,
+        TDF_ALLOW_DIALOG_CANCELLATION, TDF_SIZE_TO_CONTENT};
+    use winapi::um::winuser::{MessageBoxW, MB_OK, MB_ICONERROR};
+
+    let title = to_utf16(&config.app_name);
+    let instruction = to_utf16(&format!("{} has stopped working", config.app_name));
+    let content = to_utf16(message);
+    let expanded = to_utf16(report);
+    let copy_text = to_utf16("Copy details");
+    let restart_text = to_utf16("Restart");
+    let close_text = to_utf16("Close");
+
+    let mut buttons = vec![
+        TASKDIALOG_BUTTON { nButtonID: COPY_BUTTON, pszButtonText: copy_text.as_ptr() },
+    ];
+
+    if config.restart {
+        buttons.push(TASKDIALOG_BUTTON { nButtonID: RESTART_BUTTON, pszButtonText: restart_text.as_ptr() });
+    }
+
+    buttons.push(TASKDIALOG_BUTTON { nButtonID: CLOSE_BUTTON, pszButtonText: close_text.as_ptr() });
+
+    unsafe {
+        let mut dialog: TASKDIALOGCONFIG = mem::zeroed();
+        dialog.cbSize = mem::size_of::<TASKDIALOGCONFIG>() as UINT;
+        dialog.dwFlags = TDF_ALLOW_DIALOG_CANCELLATION | TDF_SIZE_TO_CONTENT;
+        dialog.pszWindowTitle = title.as_ptr();
+        *dialog.u1.pszMainIcon_mut() = TD_ERROR_ICON;
+        dialog.pszMainInstruction = instruction.as_ptr();
+        dialog.pszContent = content.as_ptr();
+        dialog.cButtons = buttons.len() as UINT;
+        dialog.pButtons = buttons.as_ptr();
+        dialog.nDefaultButton = CLOSE_BUTTON;
+        dialog.pszExpandedInformation = expanded.as_ptr();
+        dialog.pfCallback = Some(dialog_callback);
+
+        // The length of the report is needed to rebuild the `&str` in the callback
+        let report_ref: &&str = &report;
+        dialog.lpCallbackData = report_ref as *const &str as LONG_PTR;
+
+        let mut choice = 0;
+        if TaskDialogIndirect(&dialog, &mut choice, ptr::null_mut(), ptr::null_mut()) == 0 {
+            return choice;
+        }
+
+        let text = to_utf16(&format!("{} has stopped working.\n\n{}\n\n{}", config.app_name, message, report));
+        MessageBoxW(ptr::null_mut(), text.as_ptr(), title.as_ptr(), MB_OK | MB_ICONERROR);
+        CLOSE_BUTTON
+    }
+}
+
+unsafe extern "system" fn dialog_callback(hwnd: HWND, msg: UINT, w: WPARAM, _l: LPARAM, data: LONG_PTR) -> HRESULT {
+    use winapi::um::commctrl::TDN_BUTTON_CLICKED;
+    use winapi::shared::winerror::{S_OK, S_FALSE};
+    use crate::{Clipboard, ControlHandle};
+
+    if msg == TDN_BUTTON_CLICKED && w as i32 == COPY_BUTTON {
+        let report = *(data as *const &str);
+        Clipboard::set_data_text(ControlHandle::Hwnd(hwnd), report);
+
+        // Keep the dialog open
+        return S_FALSE;
+    }
+
+    S_OK
+}
@@ -75,7 +75,7 @@ impl GlobalCursor {
         unsafe {
             ScreenToClient(handle, &mut p);
 
-            high_dpi::physical_to_logical(p.x as i32, p.y as i32)
+            high_dpi::physical_to_logical_for(handle, p.x as i32, p.y as i32)
         }
     }
 
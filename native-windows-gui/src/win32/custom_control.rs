@@ -0,0 +1,169 @@
+/*!
+    Extension API for the controls implemented outside of native-windows-gui. A crate can register the window class of its
+    control and translate the notifications of the control into native-windows-gui events, without changes to native-windows-gui.
+*/
+use winapi::shared::windef::HBRUSH;
+use winapi::shared::minwindef::UINT;
+use winapi::um::winuser::{WNDPROC, NMHDR};
+use crate::{Event, EventData, NwgError};
+use super::window::build_sysclass;
+use std::sync::Mutex;
+use std::ptr;
+
+lazy_static! {
+    /// The custom control classes registered with `register_custom_control`
+    static ref CUSTOM_CLASSES: Mutex<Vec<CustomClass>> = Mutex::new(Vec::new());
+}
+
+/// The event translation hooks of a custom control class
+#[derive(Clone)]
+struct CustomClass {
+    name: String,
+    command: fn(u16) -> Event,
+    notify: unsafe fn(&NMHDR) -> (Event, EventData),
+}
+
+
+/**
+    A control implemented outside of native-windows-gui. Register the control with `register_custom_control` (or build it
+    with `HwndBuilder::custom_control`) and the events of the control are dispatched to the event handlers like the events of the built-in controls.
+
+    The notifications of the control are sent to its parent with `WM_COMMAND` (for the notification codes that fit a `u16`) or with `WM_NOTIFY`.
+    The control can use the existing events (ex: `Event::OnButtonClick`) or `Event::Unknown` with its own data.
+
+    To be used with the derive macro, the control must also implement `Default`, a builder with a `build(self, &mut Control)` function,
+    and the conversions to `ControlHandle` (see the `custom_control_handle` macro).
+
+    ```rust
+    use native_windows_gui as nwg;
+    use winapi::shared::minwindef::{UINT, WPARAM, LPARAM, LRESULT};
+    use winapi::shared::windef::HWND;
+    use winapi::um::winuser::DefWindowProcW;
+
+    const CHART_CLICKED: u16 = 1;
+
+    #[derive(Default)]
+    pub struct Chart {
+        pub handle: nwg::ControlHandle
+    }
+
+    nwg::custom_control_handle!(Chart);
+
+    impl nwg::CustomControl for Chart {
+        fn class_name() -> &'static str { "MyCrateChart" }
+
+        fn register_class() -> Result<(), nwg::NwgError> {
+            nwg::register_control_class(Self::class_name(), Some(chart_proc), None, None)
+        }
+
+        fn command_event(code: u16) -> nwg::Event {
+            match code {
+                CHART_CLICKED => nwg::Event::OnButtonClick,
+                _ => nwg::Event::Unknown
+            }
+        }
+    }
+
+    unsafe extern "system" fn chart_proc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
+        DefWindowProcW(hwnd, msg, w, l)
+    }
+
+    fn build_chart(chart: &mut Chart, window: &nwg::Window) -> Result<(), nwg::NwgError> {
+        use winapi::um::winuser::{WS_VISIBLE, WS_CHILD};
+
+        chart.handle = nwg::ControlBase::build_hwnd()
+            .custom_control::<Chart>()
+            .flags(WS_VISIBLE | WS_CHILD)
+            .size((300, 200))
+            .parent(Some(window.handle))
+            .build()?;
+
+        Ok(())
+    }
+    ```
+*/
+pub trait CustomControl {
+
+    /// The name of the window class of the control. Must be unique in the application.
+    fn class_name() -> &'static str;
+
+    /// Register the window class of the control. Called once by `register_custom_control`.
+    /// The default implementation does nothing, for the classes registered by a system library.
+    fn register_class() -> Result<(), NwgError> {
+        Ok(())
+    }
+
+    /// Translate the code of a `WM_COMMAND` notification sent by the control. Returns `Event::Unknown` by default.
+    fn command_event(_code: u16) -> Event {
+        Event::Unknown
+    }
+
+    /// Translate a `WM_NOTIFY` notification sent by the control. Returns `Event::Unknown` by default.
+    /// `notif` can be cast to the notification struct of the control.
+    unsafe fn notify_event(_notif: &NMHDR) -> (Event, EventData) {
+        (Event::Unknown, EventData::NoData)
+    }
+
+}
+
+/**
+    Register a custom control: its window class and its event translation hooks. Registering a control more than once does nothing.
+    Must be called after `nwg::init` and before the control is created.
+*/
+pub fn register_custom_control<C: CustomControl>() -> Result<(), NwgError> {
+    let name = C::class_name();
+    if CUSTOM_CLASSES.lock().unwrap().iter().any(|c| c.name == name) {
+        return Ok(());
+    }
+
+    C::register_class()?;
+
+    let mut classes = CUSTOM_CLASSES.lock().unwrap();
+    if !classes.iter().any(|c| c.name == name) {
+        classes.push(CustomClass {
+            name: name.to_string(),
+            command: C::command_event,
+            notify: C::notify_event,
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns true if a custom control with the window class `class_name` was registered
+pub fn is_custom_control_registered(class_name: &str) -> bool {
+    CUSTOM_CLASSES.lock().unwrap().iter().any(|c| c.name == class_name)
+}
+
+/**
+    Register a window class in the current module. `background` defaults to the window color and `style` to `CS_HREDRAW | CS_VREDRAW`.
+    Registering a class that already exists does nothing.
+*/
+pub fn register_control_class(class_name: &str, proc: WNDPROC, background: Option<HBRUSH>, style: Option<UINT>) -> Result<(), NwgError> {
+    use winapi::um::libloaderapi::GetModuleHandleW;
+
+    unsafe {
+        let hmod = GetModuleHandleW(ptr::null_mut());
+        if hmod.is_null() { return Err(NwgError::initialization("GetModuleHandleW failed")); }
+
+        build_sysclass(hmod, class_name, proc, background, style)
+    }
+}
+
+/// Translate a `WM_COMMAND` notification of a custom control. Returns `None` if the class is not a registered custom control.
+pub(crate) fn command_event(class_name: &str, code: u16) -> Option<Event> {
+    let command = CUSTOM_CLASSES.lock().unwrap().iter()
+        .find(|c| c.name == class_name)
+        .map(|c| c.command)?;
+
+    Some(command(code))
+}
+
+/// Translate a `WM_NOTIFY` notification of a custom control. Returns `None` if the class is not a registered custom control.
+pub(crate) unsafe fn notify_event(class_name: &str, notif: &NMHDR) -> Option<(Event, EventData)> {
+    let notify = CUSTOM_CLASSES.lock().unwrap().iter()
+        .find(|c| c.name == class_name)
+        .map(|c| c.notify)?;
+
+    Some(notify(notif))
+}
@@ -0,0 +1,75 @@
+/*!
+    Device change notifications (ex: usb devices plugged or unplugged)
+*/
+use winapi::shared::guiddef::GUID;
+use winapi::shared::windef::HWND;
+use winapi::um::winuser::{HDEVNOTIFY, RegisterDeviceNotificationW, UnregisterDeviceNotification, DEVICE_NOTIFY_WINDOW_HANDLE, DEVICE_NOTIFY_ALL_INTERFACE_CLASSES};
+use winapi::um::dbt::{DEV_BROADCAST_DEVICEINTERFACE_W, DBT_DEVTYP_DEVICEINTERFACE};
+use crate::NwgError;
+use std::{mem, ptr};
+
+
+/**
+    A device interface notification registration. Created by `Window::register_device_notification` or `MessageWindow::register_device_notification`.
+
+    While the registration is alive, the window receives `OnDeviceArrival` and `OnDeviceRemoval` events when a device
+    interface of the registered class is added or removed. The registration is removed when the object is dropped.
+
+    Volume arrival and removal (ex: usb drives) are always sent to top level windows, even without a registration.
+    Message only windows must register a device notification to receive any event.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn watch_devices(window: &nwg::Window) -> nwg::DeviceNotification {
+        // Receive notifications for every device interface class
+        window.register_device_notification(None).expect("Failed to register the device notification")
+    }
+    ```
+*/
+pub struct DeviceNotification {
+    handle: HDEVNOTIFY
+}
+
+impl DeviceNotification {
+
+    /// Register `hwnd` for the device interface notifications of `interface_class`. If `interface_class` is `None`, all classes are registered.
+    pub(crate) fn register(hwnd: HWND, interface_class: Option<GUID>) -> Result<DeviceNotification, NwgError> {
+        let mut filter: DEV_BROADCAST_DEVICEINTERFACE_W = unsafe { mem::zeroed() };
+        filter.dbcc_size = mem::size_of::<DEV_BROADCAST_DEVICEINTERFACE_W>() as u32;
+        filter.dbcc_devicetype = DBT_DEVTYP_DEVICEINTERFACE;
+
+        let mut flags = DEVICE_NOTIFY_WINDOW_HANDLE;
+        match interface_class {
+            Some(class) => { filter.dbcc_classguid = class; },
+            None => { flags |= DEVICE_NOTIFY_ALL_INTERFACE_CLASSES; }
+        }
+
+        let handle = unsafe { RegisterDeviceNotificationW(hwnd as _, &mut filter as *mut DEV_BROADCAST_DEVICEINTERFACE_W as _, flags) };
+        if handle.is_null() {
+            return Err(NwgError::events_binding("Failed to register the device notification"));
+        }
+
+        Ok(DeviceNotification { handle })
+    }
+
+    /// Return true if the object holds a device notification registration
+    pub fn registered(&self) -> bool {
+        !self.handle.is_null()
+    }
+
+}
+
+impl Default for DeviceNotification {
+    fn default() -> DeviceNotification {
+        DeviceNotification { handle: ptr::null_mut() }
+    }
+}
+
+impl Drop for DeviceNotification {
+    fn drop(&mut self) {
+        if !self.handle.is_null() {
+            unsafe { UnregisterDeviceNotification(self.handle); }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+/*!
+    Default and cancel buttons of the top level windows. The dispatch loop sends `IDOK` to a top level window when the user
+    presses Enter, and `IDCANCEL` when the user presses Escape. When the window has a default (or a cancel) button, `OnButtonClick`
+    is raised on the button instead of raising `OnKeyEnter` (or `OnKeyEsc`) on the window, like in a dialog.
+*/
+use winapi::shared::windef::HWND;
+use winapi::shared::minwindef::WPARAM;
+use crate::controls::ControlHandle;
+use super::base_helper::to_utf16;
+use super::window_helper as wh;
+
+const DEFAULT_BUTTON_PROP: &'static str = "NWG_DEFAULT_BUTTON";
+const CANCEL_BUTTON_PROP: &'static str = "NWG_CANCEL_BUTTON";
+
+
+/// The role of a button in its top level window
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum DialogButton {
+    /// Clicked when the user presses Enter
+    Default,
+
+    /// Clicked when the user presses Escape
+    Cancel,
+}
+
+impl DialogButton {
+
+    fn prop(self) -> Vec<u16> {
+        match self {
+            DialogButton::Default => to_utf16(DEFAULT_BUTTON_PROP),
+            DialogButton::Cancel => to_utf16(CANCEL_BUTTON_PROP),
+        }
+    }
+
+}
+
+/// Sets or clears the role of `button` in its top level window. Setting a role replaces the button that had it.
+pub(crate) fn set_dialog_button(button: HWND, role: DialogButton, value: bool) {
+    use winapi::um::winuser::{SetPropW, RemovePropW};
+
+    let root = root_window(button);
+    let prop = role.prop();
+    let current = dialog_button(root, role);
+
+    unsafe {
+        match value {
+            true => {
+                if let Some(current) = current {
+                    if role == DialogButton::Default && current != button {
+                        set_default_style(current, false);
+                    }
+                }
+
+                SetPropW(root, prop.as_ptr(), button as _);
+            },
+            false => {
+                if current != Some(button) {
+                    return;
+                }
+
+                RemovePropW(root, prop.as_ptr());
+            }
+        }
+    }
+
+    if role == DialogButton::Default {
+        set_default_style(button, value);
+    }
+}
+
+/// Returns true if `button` has the role `role` in its top level window
+pub(crate) fn is_dialog_button(button: HWND, role: DialogButton) -> bool {
+    dialog_button(root_window(button), role) == Some(button)
+}
+
+/**
+    Returns the button with the role `role` in the top level window `root`. Returns `None` if there is none,
+    or if the button was destroyed or moved to another window.
+*/
+pub(crate) fn dialog_button(root: HWND, role: DialogButton) -> Option<HWND> {
+    use winapi::um::winuser::{GetPropW, IsWindow};
+
+    let prop = role.prop();
+    let button = unsafe { GetPropW(root, prop.as_ptr()) } as HWND;
+    if button.is_null() || unsafe { IsWindow(button) } == 0 || root_window(button) != root {
+        return None;
+    }
+
+    Some(button)
+}
+
+/// Returns true if the user could click `button`: a disabled or hidden dialog button ignores Enter and Escape.
+pub(crate) fn can_click(button: HWND) -> bool {
+    unsafe { wh::get_window_enabled(button) && wh::get_window_visibility(button) }
+}
+
+/// Returns the default button of the top level window of `window`. See `Button::set_default`.
+pub fn default_button<C: Into<ControlHandle>>(window: C) -> Option<ControlHandle> {
+    let handle = window.into().hwnd().expect("Only the window-like controls have a default button");
+    dialog_button(root_window(handle), DialogButton::Default).map(ControlHandle::Hwnd)
+}
+
+/// Returns the cancel button of the top level window of `window`. See `Button::set_cancel`.
+pub fn cancel_button<C: Into<ControlHandle>>(window: C) -> Option<ControlHandle> {
+    let handle = window.into().hwnd().expect("Only the window-like controls have a cancel button");
+    dialog_button(root_window(handle), DialogButton::Cancel).map(ControlHandle::Hwnd)
+}
+
+fn root_window(handle: HWND) -> HWND {
+    use winapi::um::winuser::{GetAncestor, GA_ROOT};
+    unsafe { GetAncestor(handle, GA_ROOT) }
+}
+
+/// Draw the button with the thick border of the default button. Keeps the type of the button (push button, split button or command link).
+fn set_default_style(button: HWND, default: bool) {
+    use winapi::um::winuser::{GWL_STYLE, BM_SETSTYLE, BS_TYPEMASK, BS_PUSHBUTTON, BS_DEFPUSHBUTTON};
+    use winapi::um::commctrl::{BS_SPLITBUTTON, BS_DEFSPLITBUTTON, BS_COMMANDLINK, BS_DEFCOMMANDLINK};
+
+    let style = wh::get_window_long(button, GWL_STYLE) as u32;
+    let button_type = match (style & BS_TYPEMASK, default) {
+        (BS_PUSHBUTTON, true) | (BS_DEFPUSHBUTTON, true) => BS_DEFPUSHBUTTON,
+        (BS_PUSHBUTTON, false) | (BS_DEFPUSHBUTTON, false) => BS_PUSHBUTTON,
+        (BS_SPLITBUTTON, true) | (BS_DEFSPLITBUTTON, true) => BS_DEFSPLITBUTTON,
+        (BS_SPLITBUTTON, false) | (BS_DEFSPLITBUTTON, false) => BS_SPLITBUTTON,
+        (BS_COMMANDLINK, true) | (BS_DEFCOMMANDLINK, true) => BS_DEFCOMMANDLINK,
+        (BS_COMMANDLINK, false) | (BS_DEFCOMMANDLINK, false) => BS_COMMANDLINK,
+        _ => { return; }
+    };
+
+    wh::send_message(button, BM_SETSTYLE, ((style & !BS_TYPEMASK) | button_type) as WPARAM, 1);
+}
@@ -0,0 +1,163 @@
+/*!
+    Helpers to implement the UAC elevation pattern: check if the process is elevated and relaunch it as administrator.
+*/
+use winapi::shared::minwindef::{DWORD, WPARAM};
+use super::base_helper::to_utf16;
+use super::window_helper::{self as wh, NWG_ELEVATION_RESULT};
+use crate::controls::ControlHandle;
+use crate::NwgError;
+use std::{env, mem, ptr};
+
+
+/// The result of `relaunch_elevated`. Sent with the `OnElevationResult` event.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ElevationResult {
+    /// The elevated process was started. The current process should usually exit.
+    Launched,
+
+    /// The user refused the elevation in the UAC prompt
+    Cancelled,
+
+    /// The elevated process could not be started. Holds the system error code.
+    Failed(u32)
+}
+
+impl ElevationResult {
+
+    pub(crate) fn to_wparam(self) -> WPARAM {
+        match self {
+            ElevationResult::Launched => 0,
+            ElevationResult::Cancelled => 1,
+            ElevationResult::Failed(code) => 2 | ((code as WPARAM) << 2),
+        }
+    }
+
+    pub(crate) fn from_wparam(w: WPARAM) -> ElevationResult {
+        match w & 0b11 {
+            0 => ElevationResult::Launched,
+            1 => ElevationResult::Cancelled,
+            _ => ElevationResult::Failed((w >> 2) as u32),
+        }
+    }
+
+}
+
+/// Return true if the current process runs elevated (as administrator)
+pub fn is_elevated() -> bool {
+    use winapi::um::processthreadsapi::{GetCurrentProcess, OpenProcessToken};
+    use winapi::um::securitybaseapi::GetTokenInformation;
+    use winapi::um::handleapi::CloseHandle;
+    use winapi::um::winnt::{TOKEN_QUERY, TOKEN_ELEVATION, TokenElevation};
+
+    unsafe {
+        let mut token = ptr::null_mut();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+            return false;
+        }
+
+        let mut elevation: TOKEN_ELEVATION = mem::zeroed();
+        let mut size = 0;
+        let ok = GetTokenInformation(
+            token,
+            TokenElevation,
+            &mut elevation as *mut TOKEN_ELEVATION as _,
+            mem::size_of::<TOKEN_ELEVATION>() as DWORD,
+            &mut size
+        );
+
+        CloseHandle(token);
+
+        ok != 0 && elevation.TokenIsElevated != 0
+    }
+}
+
+/**
+    Relaunch the current executable as administrator with the selected command line arguments.
+    The UAC prompt is owned by `parent`. This function blocks until the user answers the prompt.
+
+    Once the prompt is closed, an `OnElevationResult` event is sent to `parent` with the same result that is returned.
+    The current process is not closed. If the result is `ElevationResult::Launched`, the application should usually exit.
+
+    Use `Button::set_elevation_required` to display the UAC shield on the button that triggers the elevation.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn elevate(window: &nwg::Window) {
+        if !nwg::is_elevated() {
+            if let Ok(nwg::ElevationResult::Launched) = nwg::relaunch_elevated(window, &["--settings"]) {
+                nwg::stop_thread_dispatch();
+            }
+        }
+    }
+    ```
+*/
+pub fn relaunch_elevated<C: Into<ControlHandle>>(parent: C, args: &[&str]) -> Result<ElevationResult, NwgError> {
+    use winapi::um::shellapi::{ShellExecuteExW, SHELLEXECUTEINFOW, SEE_MASK_NOASYNC};
+    use winapi::um::errhandlingapi::GetLastError;
+    use winapi::um::winuser::SW_SHOWNORMAL;
+    use winapi::shared::winerror::ERROR_CANCELLED;
+
+    let parent = parent.into().hwnd().ok_or_else(|| NwgError::control_create("Wrong parent type"))?;
+
+    let exe = env::current_exe().map_err(|e| NwgError::initialization(format!("Failed to get the executable path: {}", e)))?;
+    let exe = to_utf16(&exe.to_string_lossy());
+    let verb = to_utf16("runas");
+    let parameters = to_utf16(&args.iter().map(|a| quote_argument(a)).collect::<Vec<String>>().join(" "));
+    let directory = env::current_dir().ok().map(|d| to_utf16(&d.to_string_lossy()));
+
+    let mut info: SHELLEXECUTEINFOW = unsafe { mem::zeroed() };
+    info.cbSize = mem::size_of::<SHELLEXECUTEINFOW>() as DWORD;
+    info.fMask = SEE_MASK_NOASYNC;
+    info.hwnd = parent;
+    info.lpVerb = verb.as_ptr();
+    info.lpFile = exe.as_ptr();
+    info.lpParameters = parameters.as_ptr();
+    info.lpDirectory = directory.as_ref().map(|d| d.as_ptr()).unwrap_or(ptr::null());
+    info.nShow = SW_SHOWNORMAL;
+
+    let result = unsafe {
+        match ShellExecuteExW(&mut info) {
+            0 => match GetLastError() {
+                ERROR_CANCELLED => ElevationResult::Cancelled,
+                code => ElevationResult::Failed(code)
+            },
+            _ => ElevationResult::Launched
+        }
+    };
+
+    wh::post_message(parent, NWG_ELEVATION_RESULT, result.to_wparam(), 0);
+
+    Ok(result)
+}
+
+/// Quote a command line argument using the rules of `CommandLineToArgvW`
+fn quote_argument(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains(|c: char| c == ' ' || c == '\t' || c == '"') {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => { backslashes += 1; },
+            '"' => {
+                quoted.extend(std::iter::repeat('\\').take(backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            },
+            c => {
+                quoted.extend(std::iter::repeat('\\').take(backslashes));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+
+    quoted.extend(std::iter::repeat('\\').take(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
@@ -3,9 +3,11 @@
 */
 use winapi::shared::minwindef::{UINT, WPARAM, LPARAM, LRESULT};
 use winapi::shared::windef::{HWND};
-use super::window::build_sysclass;
+use super::window::{build_sysclass, nwg_class};
+use super::window_helper::{self as wh, NWG_PRESENT_READY};
 use crate::NwgError;
-use std::ptr;
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+use std::{ptr, mem, thread, time::Duration};
 
 pub const EXT_CANVAS_CLASS_ID: &'static str = "NWG_EXTERN_CANVAS";
 
@@ -20,7 +22,7 @@ pub fn create_extern_canvas_classes() -> Result<(), NwgError>  {
     if hmod.is_null() { return Err(NwgError::initialization("GetModuleHandleW failed")); }
 
     unsafe { 
-        build_sysclass(hmod, EXT_CANVAS_CLASS_ID, Some(extern_canvas_proc), Some(0 as HBRUSH), Some(CS_OWNDC|CS_VREDRAW|CS_HREDRAW))?;
+        build_sysclass(hmod, nwg_class(EXT_CANVAS_CLASS_ID), Some(extern_canvas_proc), Some(0 as HBRUSH), Some(CS_OWNDC|CS_VREDRAW|CS_HREDRAW))?;
     }
 
     Ok(())
@@ -43,3 +45,76 @@ unsafe extern "system" fn extern_canvas_proc(hwnd: HWND, msg: UINT, w: WPARAM, l
         DefWindowProcW(hwnd, msg, w, l)
     }
 }
+
+
+/// Return the refresh rate of the desktop compositor in hertz. Return 60 if the compositor timing is not available.
+pub fn compositor_refresh_rate() -> f64 {
+    use winapi::um::dwmapi::{DwmGetCompositionTimingInfo, DWM_TIMING_INFO};
+    use winapi::shared::winerror::S_OK;
+
+    let mut info: DWM_TIMING_INFO = unsafe { mem::zeroed() };
+    info.cbSize = mem::size_of::<DWM_TIMING_INFO>() as u32;
+
+    let result = unsafe { DwmGetCompositionTimingInfo(ptr::null_mut(), &mut info) };
+    let rate = info.rateRefresh;
+    let (numerator, denominator) = (rate.uiNumerator, rate.uiDenominator);
+
+    match result == S_OK && numerator != 0 && denominator != 0 {
+        true => numerator as f64 / denominator as f64,
+        false => 60.0
+    }
+}
+
+/**
+    A thread that waits for the desktop compositor and posts `NWG_PRESENT_READY` to a window once per frame.
+
+    A new message is only posted once the previous one was handled (see `PresentThread::pending`), so a slow
+    event handler skips frames instead of filling the message queue.
+*/
+pub struct PresentThread {
+    stop: Arc<AtomicBool>,
+    pending: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl PresentThread {
+
+    pub fn start(hwnd: HWND) -> PresentThread {
+        use winapi::um::dwmapi::DwmFlush;
+        use winapi::shared::winerror::S_OK;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let pending = Arc::new(AtomicBool::new(false));
+
+        let (thread_stop, thread_pending, hwnd) = (stop.clone(), pending.clone(), hwnd as usize);
+        let thread = thread::spawn(move || {
+            while !thread_stop.load(Ordering::SeqCst) {
+                // DwmFlush blocks until the next composition. If the composition is disabled, use the refresh rate instead.
+                if unsafe { DwmFlush() } != S_OK {
+                    thread::sleep(Duration::from_secs_f64(1.0 / compositor_refresh_rate()));
+                }
+
+                if !thread_pending.swap(true, Ordering::SeqCst) {
+                    wh::post_message(hwnd as HWND, NWG_PRESENT_READY, 0, 0);
+                }
+            }
+        });
+
+        PresentThread { stop, pending, thread: Some(thread) }
+    }
+
+    /// The flag set when a message is posted. It must be cleared when the message is handled.
+    pub fn pending(&self) -> Arc<AtomicBool> {
+        self.pending.clone()
+    }
+
+}
+
+impl Drop for PresentThread {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            drop(thread.join());
+        }
+    }
+}
@@ -1,3 +1,12 @@
+use winapi::shared::windef::HWND;
+use winapi::shared::minwindef::UINT;
+use std::mem;
+
+lazy_static! {
+    /// `GetDpiForWindow` is loaded at runtime because it requires Windows 10 1607
+    static ref GET_DPI_FOR_WINDOW: usize = unsafe { user32_proc("GetDpiForWindow\0") };
+}
+
 #[cfg(not(feature = "high-dpi"))]
 #[deprecated(note = "Specifying the default process DPI awareness via API is not recommended. Use the '<dpiAware>true</dpiAware>' setting in the application manifest. https://docs.microsoft.com/ru-ru/windows/win32/hidpi/setting-the-default-dpi-awareness-for-a-process")]
 pub unsafe fn set_dpi_awareness() {
@@ -52,6 +61,103 @@ pub unsafe fn physical_to_logical(x: i32, y: i32) -> (i32, i32) {
     (x, y)
 }
 
+/**
+    Enable the per-monitor (v2) dpi awareness of the process. The windows receive `OnDpiChanged` when they move to a monitor
+    with a different dpi, and the controls and the layouts of the window are scaled with the dpi of the window (see `Window::dpi`).
+    Returns `false` if the system does not support it (before Windows 10 1703). In that case, the process is made system dpi aware.
+
+    Like `set_dpi_awareness`, this must be called before any window is created. The recommended way is the
+    `<dpiAwareness>PerMonitorV2</dpiAwareness>` setting in the application manifest.
+*/
+#[cfg(feature = "high-dpi")]
+pub unsafe fn set_dpi_awareness_per_monitor() -> bool {
+    use winapi::um::winuser::SetProcessDPIAware;
+    use winapi::shared::windef::{DPI_AWARENESS_CONTEXT, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2};
+    use winapi::shared::minwindef::BOOL;
+
+    type SetProcessDpiAwarenessContextFn = unsafe extern "system" fn(DPI_AWARENESS_CONTEXT) -> BOOL;
+
+    let set_context = user32_proc("SetProcessDpiAwarenessContext\0");
+    if set_context != 0 {
+        let set_context: SetProcessDpiAwarenessContextFn = mem::transmute(set_context);
+        if set_context(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2) != 0 {
+            return true;
+        }
+    }
+
+    SetProcessDPIAware();
+    false
+}
+
+#[cfg(not(feature = "high-dpi"))]
+pub unsafe fn set_dpi_awareness_per_monitor() -> bool {
+    false
+}
+
+/**
+    Returns the dpi of a window. With the per-monitor dpi awareness, this is the dpi of the monitor of the window.
+    Otherwise, or if the system does not support it, this is the system dpi (see `dpi`).
+*/
+pub unsafe fn window_dpi(handle: HWND) -> i32 {
+    type GetDpiForWindowFn = unsafe extern "system" fn(HWND) -> UINT;
+
+    if *GET_DPI_FOR_WINDOW != 0 && !handle.is_null() {
+        let get_dpi: GetDpiForWindowFn = mem::transmute(*GET_DPI_FOR_WINDOW);
+        match get_dpi(handle) {
+            0 => {},
+            dpi => { return dpi as i32; }
+        }
+    }
+
+    dpi()
+}
+
+/// Like `logical_to_physical`, with the dpi of the window `handle`
+#[cfg(not(feature = "high-dpi"))]
+pub unsafe fn logical_to_physical_for(_handle: HWND, x: i32, y: i32) -> (i32, i32) {
+    (x, y)
+}
+
+#[cfg(feature = "high-dpi")]
+pub unsafe fn logical_to_physical_for(handle: HWND, x: i32, y: i32) -> (i32, i32) {
+    use muldiv::MulDiv;
+    use winapi::um::winuser::USER_DEFAULT_SCREEN_DPI;
+    let dpi = window_dpi(handle);
+    let x = x.mul_div_round(dpi, USER_DEFAULT_SCREEN_DPI).unwrap_or(x);
+    let y = y.mul_div_round(dpi, USER_DEFAULT_SCREEN_DPI).unwrap_or(y);
+    (x, y)
+}
+
+/// Like `physical_to_logical`, with the dpi of the window `handle`
+#[cfg(not(feature = "high-dpi"))]
+pub unsafe fn physical_to_logical_for(_handle: HWND, x: i32, y: i32) -> (i32, i32) {
+    (x, y)
+}
+
+#[cfg(feature = "high-dpi")]
+pub unsafe fn physical_to_logical_for(handle: HWND, x: i32, y: i32) -> (i32, i32) {
+    use muldiv::MulDiv;
+    use winapi::um::winuser::USER_DEFAULT_SCREEN_DPI;
+    let dpi = window_dpi(handle);
+    let x = x.mul_div_round(USER_DEFAULT_SCREEN_DPI, dpi).unwrap_or(x);
+    let y = y.mul_div_round(USER_DEFAULT_SCREEN_DPI, dpi).unwrap_or(y);
+    (x, y)
+}
+
+/// Returns the address of a function of user32, or 0 if the function does not exist on this version of Windows.
+/// `name` must be null terminated.
+unsafe fn user32_proc(name: &str) -> usize {
+    use winapi::um::libloaderapi::{GetModuleHandleW, GetProcAddress};
+    use super::base_helper::to_utf16;
+
+    let module = GetModuleHandleW(to_utf16("user32.dll").as_ptr());
+    if module.is_null() {
+        return 0;
+    }
+
+    GetProcAddress(module, name.as_ptr() as *const i8) as usize
+}
+
 pub unsafe fn dpi() -> i32 {
     use winapi::um::winuser::GetDC;
     use winapi::um::wingdi::GetDeviceCaps;
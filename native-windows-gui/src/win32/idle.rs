@@ -0,0 +1,36 @@
+/*!
+    Detection of the user activity on the session.
+*/
+use std::time::Duration;
+use std::mem;
+
+
+/**
+    Return the time elapsed since the last keyboard or mouse input of the user, in any application of the session.
+    Returns a duration of 0 if the information is not available.
+
+    ```rust
+    use native_windows_gui as nwg;
+    use std::time::Duration;
+
+    fn user_away() -> bool {
+        nwg::last_input_elapsed() > Duration::from_secs(5 * 60)
+    }
+    ```
+*/
+pub fn last_input_elapsed() -> Duration {
+    use winapi::um::winuser::{GetLastInputInfo, LASTINPUTINFO};
+    use winapi::um::sysinfoapi::GetTickCount;
+    use winapi::shared::minwindef::UINT;
+
+    unsafe {
+        let mut info = LASTINPUTINFO { cbSize: mem::size_of::<LASTINPUTINFO>() as UINT, dwTime: 0 };
+        if GetLastInputInfo(&mut info) == 0 {
+            return Duration::from_millis(0);
+        }
+
+        // Both values are tick counts that wrap around after 49.7 days
+        let elapsed = GetTickCount().wrapping_sub(info.dwTime);
+        Duration::from_millis(elapsed as u64)
+    }
+}
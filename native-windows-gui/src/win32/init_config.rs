@@ -0,0 +1,212 @@
+/*!
+    Application wide settings applied by `init_with`. `nwg::init()` is `init_with` with the default configuration.
+*/
+use crate::{NwgError, Font};
+use super::base_helper::to_utf16;
+
+
+bitflags! {
+    /**
+        The classes of the common controls registered by `init_with`. The classes of the controls that are not registered
+        can still be used if another part of the application registers them.
+
+        * STANDARD:   Buttons, edits, list boxes, combo boxes, scroll bars and labels
+        * BAR:        Tool bars, status bars, track bars and tooltips
+        * DATE:       Date and time picker
+        * PROGRESS:   Progress bar
+        * TAB:        Tabs
+        * TREE_VIEW:  Tree view
+        * LIST_VIEW:  List view and its header
+        * RICH_EDIT:  Rich edit (loads `Msftedit.dll`)
+    */
+    pub struct CommonControlClasses: u32 {
+        const STANDARD = 0b00000001;
+        const BAR = 0b00000010;
+        const DATE = 0b00000100;
+        const PROGRESS = 0b00001000;
+        const TAB = 0b00010000;
+        const TREE_VIEW = 0b00100000;
+        const LIST_VIEW = 0b01000000;
+        const RICH_EDIT = 0b10000000;
+    }
+}
+
+impl Default for CommonControlClasses {
+    /// The classes of the controls enabled by the features of the crate
+    fn default() -> CommonControlClasses {
+        let mut classes = CommonControlClasses::STANDARD | CommonControlClasses::BAR;
+        classes.set(CommonControlClasses::DATE, cfg!(feature = "datetime-picker"));
+        classes.set(CommonControlClasses::PROGRESS, cfg!(feature = "progress-bar"));
+        classes.set(CommonControlClasses::TAB, cfg!(feature = "tabs"));
+        classes.set(CommonControlClasses::TREE_VIEW, cfg!(feature = "tree-view"));
+        classes.set(CommonControlClasses::LIST_VIEW, cfg!(feature = "list-view"));
+        classes.set(CommonControlClasses::RICH_EDIT, cfg!(feature = "rich-textbox"));
+        classes
+    }
+}
+
+/// The dpi awareness of the process set by `init_with`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DpiAwareness {
+    /// Keep the dpi awareness of the application manifest (unaware without a manifest)
+    Manifest,
+
+    /// The windows are scaled with the dpi of the primary monitor. See `set_dpi_awareness`.
+    System,
+
+    /// The windows are scaled with the dpi of their monitor. See `set_dpi_awareness_per_monitor`.
+    PerMonitor,
+}
+
+/**
+    The application wide settings of native-windows-gui. Pass the configuration to `init_with` before creating any window.
+
+    The configuration groups the settings that must be applied before the first window is created:
+
+      * `classes`:        The classes of the common controls to register. Defaults to the controls enabled by the features of the crate.
+      * `dpi_awareness`:  The dpi awareness of the process. Defaults to the setting of the application manifest.
+      * `dark_mode`:      If the title bar and the frame of the top level windows are dark. The controls keep the colors of the system theme. Requires Windows 10 20H1. Defaults to false.
+      * `visual_styles`:  If the visual styles are enabled with `enable_visual_styles`. Defaults to true, unless the `no-styling` feature is enabled.
+      * `default_font`:   The default font of the controls. See `Font::set_global_default`.
+      * `language`:       The language of the user interface of the thread (ex: "fr-FR"). Changes the language of the system dialogs and the strings loaded from the resources.
+      * `class_prefix`:   A prefix added to the name of the window classes registered by native-windows-gui. Use it when two modules of the process (ex: a plugin) embed native-windows-gui.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn start() -> Result<(), nwg::NwgError> {
+        let config = nwg::InitConfig::new()
+            .dpi_awareness(nwg::DpiAwareness::PerMonitor)
+            .dark_mode(true)
+            .language("en-US")
+            .class_prefix("MyPlugin_");
+
+        nwg::init_with(config)
+    }
+    ```
+*/
+#[derive(Debug)]
+pub struct InitConfig {
+    classes: CommonControlClasses,
+    dpi_awareness: DpiAwareness,
+    dark_mode: bool,
+    visual_styles: bool,
+    default_font: Option<Font>,
+    language: Option<String>,
+    class_prefix: Option<String>,
+}
+
+impl InitConfig {
+
+    pub fn new() -> InitConfig {
+        InitConfig {
+            classes: Default::default(),
+            dpi_awareness: DpiAwareness::Manifest,
+            dark_mode: false,
+            visual_styles: cfg!(not(feature = "no-styling")),
+            default_font: None,
+            language: None,
+            class_prefix: None,
+        }
+    }
+
+    pub fn classes(mut self, classes: CommonControlClasses) -> InitConfig {
+        self.classes = classes;
+        self
+    }
+
+    pub fn dpi_awareness(mut self, awareness: DpiAwareness) -> InitConfig {
+        self.dpi_awareness = awareness;
+        self
+    }
+
+    pub fn dark_mode(mut self, dark: bool) -> InitConfig {
+        self.dark_mode = dark;
+        self
+    }
+
+    pub fn visual_styles(mut self, enabled: bool) -> InitConfig {
+        self.visual_styles = enabled;
+        self
+    }
+
+    pub fn default_font(mut self, font: Option<Font>) -> InitConfig {
+        self.default_font = font;
+        self
+    }
+
+    pub fn language(mut self, language: &str) -> InitConfig {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    pub fn class_prefix(mut self, prefix: &str) -> InitConfig {
+        self.class_prefix = Some(prefix.to_string());
+        self
+    }
+
+}
+
+impl Default for InitConfig {
+    fn default() -> InitConfig {
+        InitConfig::new()
+    }
+}
+
+/**
+    Initializes native-windows-gui with the settings of `config`. See `InitConfig`.
+
+    Returns an error if the language is not a known locale name, or if the classes of native-windows-gui cannot be registered.
+    Like `init`, it does not fail if the visual styles cannot be enabled.
+*/
+pub fn init_with(config: InitConfig) -> Result<(), NwgError> {
+    if let Some(prefix) = config.class_prefix.as_ref() {
+        super::window::set_class_prefix(prefix);
+    }
+
+    unsafe {
+        match config.dpi_awareness {
+            DpiAwareness::Manifest => {},
+            #[allow(deprecated)]
+            DpiAwareness::System => super::high_dpi::set_dpi_awareness(),
+            DpiAwareness::PerMonitor => { super::high_dpi::set_dpi_awareness_per_monitor(); }
+        }
+    }
+
+    if let Some(language) = config.language.as_ref() {
+        set_language(language)?;
+    }
+
+    super::window::set_dark_mode(config.dark_mode);
+
+    if config.visual_styles {
+        super::enable_visual_styles();
+    }
+
+    super::register_common_controls(config.classes)?;
+
+    if config.default_font.is_some() {
+        Font::set_global_default(config.default_font);
+    }
+
+    Ok(())
+}
+
+/// Set the language of the user interface and the locale of the current thread
+fn set_language(name: &str) -> Result<(), NwgError> {
+    use winapi::um::winnls::{LocaleNameToLCID, SetThreadUILanguage, SetThreadLocale};
+    use winapi::um::winnt::LANGIDFROMLCID;
+
+    let locale_name = to_utf16(name);
+    let lcid = unsafe { LocaleNameToLCID(locale_name.as_ptr(), 0) };
+    if lcid == 0 {
+        return Err(NwgError::initialization(format!("Unknown language: {}", name)));
+    }
+
+    unsafe {
+        SetThreadUILanguage(LANGIDFROMLCID(lcid));
+        SetThreadLocale(lcid);
+    }
+
+    Ok(())
+}
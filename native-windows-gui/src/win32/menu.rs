@@ -1,7 +1,7 @@
 /*!
 Native Windows GUI menu base.
 */
-use winapi::shared::windef::{HMENU, HWND};
+use winapi::shared::windef::{HMENU, HWND, HBITMAP};
 use winapi::shared::minwindef::UINT;
 use super::base_helper::{CUSTOM_ID_BEGIN, to_utf16};
 use crate::controls::ControlHandle;
@@ -13,16 +13,19 @@ use std::sync::atomic::{AtomicU32, Ordering};
 static MENU_ITEMS_ID: AtomicU32 = AtomicU32::new(CUSTOM_ID_BEGIN); 
 
 
-/// Build a system menu
-pub unsafe fn build_hmenu_control(text: Option<String>, item: bool, separator: bool, popup: bool, hmenu: Option<HMENU>, hwnd: Option<HWND>) -> Result<ControlHandle, NwgError> {
-    use winapi::um::winuser::{CreateMenu, CreatePopupMenu, GetMenu, SetMenu, DrawMenuBar, AppendMenuW};
+/// Value stored in the `dwMenuData` of popup menus. Used to identify popup menus in `WM_INITMENUPOPUP`.
+const POPUP_MENU_DATA: usize = 0x4E5747;
+
+/// Build a system menu. If `position` is set, the menu is inserted at this position in the parent instead of being appended.
+pub unsafe fn build_hmenu_control(text: Option<String>, item: bool, separator: bool, popup: bool, position: Option<u32>, hmenu: Option<HMENU>, hwnd: Option<HWND>) -> Result<ControlHandle, NwgError> {
+    use winapi::um::winuser::{CreateMenu, CreatePopupMenu, GetMenu, SetMenu, DrawMenuBar};
     use winapi::um::winuser::{MF_STRING, MF_POPUP};
 
     if separator {
         if hmenu.is_none() {
             return Err(NwgError::menu_create("Separator without parent"));
         }
-        return Ok(build_hmenu_separator(hmenu.unwrap()));
+        return Ok(build_hmenu_separator(hmenu.unwrap(), position));
     }
 
     if popup {
@@ -36,6 +39,7 @@ pub unsafe fn build_hmenu_control(text: Option<String>, item: bool, separator: b
         }
 
         use_menu_command(menu);
+        set_menu_data(menu, POPUP_MENU_DATA);
 
         return Ok(ControlHandle::PopMenu(hwnd.unwrap(), menu));
     }
@@ -62,7 +66,7 @@ pub unsafe fn build_hmenu_control(text: Option<String>, item: bool, separator: b
         if item {
             menu = menubar;
             item_id = MENU_ITEMS_ID.fetch_add(1, Ordering::SeqCst);
-            AppendMenuW(menubar, flags, item_id as usize, text.as_ptr());
+            insert_menu(menubar, position, flags, item_id as usize, text.as_ptr());
         } else {
             parent_menu = menubar;
             menu = CreateMenu();
@@ -70,7 +74,7 @@ pub unsafe fn build_hmenu_control(text: Option<String>, item: bool, separator: b
                 return Err(NwgError::menu_create("Menu without parent"));
             }
             use_menu_command(menu);
-            AppendMenuW(menubar, flags, mem::transmute(menu), text.as_ptr());
+            insert_menu(menubar, position, flags, mem::transmute(menu), text.as_ptr());
         }
 
         // Draw the menu bar to make sure the changes are visible
@@ -81,7 +85,7 @@ pub unsafe fn build_hmenu_control(text: Option<String>, item: bool, separator: b
         if item {
             menu = parent;
             item_id = MENU_ITEMS_ID.fetch_add(1, Ordering::SeqCst);
-            AppendMenuW(parent, flags, item_id as usize, text.as_ptr());
+            insert_menu(parent, position, flags, item_id as usize, text.as_ptr());
         } else {
             parent_menu = parent;
             menu = CreateMenu();
@@ -89,7 +93,7 @@ pub unsafe fn build_hmenu_control(text: Option<String>, item: bool, separator: b
                 return Err(NwgError::menu_create("Menu without parent"));
             }
             use_menu_command(menu);
-            AppendMenuW(parent, flags, mem::transmute(menu), text.as_ptr());
+            insert_menu(parent, position, flags, mem::transmute(menu), text.as_ptr());
         }
     }
 
@@ -190,19 +194,99 @@ pub unsafe fn menu_item_checked(parent_menu: HMENU, id: u32) -> bool {
     GetMenuState(parent_menu, id, MF_BYCOMMAND) & MF_CHECKED == MF_CHECKED
 }
 
+/// Sets the bitmap displayed next to the text of a menu item. Use a null handle to remove the bitmap.
+pub unsafe fn set_menu_item_bitmap(parent_menu: HMENU, id: u32, bitmap: HBITMAP) {
+    use winapi::um::winuser::{MENUITEMINFOW, MIIM_BITMAP, SetMenuItemInfoW};
+    use winapi::shared::minwindef::BOOL;
+
+    let mut info = MENUITEMINFOW { 
+        cbSize: mem::size_of::<MENUITEMINFOW>() as UINT,
+        fMask: MIIM_BITMAP, fType: 0, fState: 0,
+        wID: 0, hSubMenu: ptr::null_mut(), hbmpChecked: ptr::null_mut(),
+        hbmpUnchecked: ptr::null_mut(), dwItemData: 0, dwTypeData: ptr::null_mut(),
+        cch: 0, hbmpItem: bitmap
+    };
+
+    SetMenuItemInfoW(parent_menu, id, false as BOOL, &mut info);
+}
+
+/// Sets the bitmaps displayed instead of the default check mark. Use null handles to restore the default check marks.
+pub unsafe fn set_menu_item_check_bitmaps(parent_menu: HMENU, id: u32, checked: HBITMAP, unchecked: HBITMAP) {
+    use winapi::um::winuser::{SetMenuItemBitmaps, MF_BYCOMMAND};
+    SetMenuItemBitmaps(parent_menu, id, MF_BYCOMMAND, unchecked, checked);
+}
+
+/// Sets or clears the default item of a menu. The default item is displayed in bold.
+pub unsafe fn set_menu_default_item(parent_menu: HMENU, id: u32, default: bool) {
+    use winapi::um::winuser::SetMenuDefaultItem;
+
+    if default {
+        SetMenuDefaultItem(parent_menu, id, 0);
+    } else if menu_default_item(parent_menu) == Some(id) {
+        SetMenuDefaultItem(parent_menu, UINT::max_value(), 0);
+    }
+}
+
+/// Return the id of the default item of a menu
+pub unsafe fn menu_default_item(parent_menu: HMENU) -> Option<u32> {
+    use winapi::um::winuser::{GetMenuDefaultItem, GMDI_USEDISABLED};
+
+    match GetMenuDefaultItem(parent_menu, 0, GMDI_USEDISABLED) {
+        u32::MAX => None,
+        id => Some(id)
+    }
+}
+
+/**
+    Enable or disable owner drawing on a menu item. The parent menu handle is stored in the item data
+    because `WM_MEASUREITEM` does not include it.
+*/
+pub unsafe fn set_menu_item_owner_draw(parent_menu: HMENU, id: u32, owner_draw: bool) {
+    use winapi::um::winuser::{MENUITEMINFOW, MIIM_FTYPE, MIIM_DATA, MFT_OWNERDRAW, SetMenuItemInfoW, GetMenuItemInfoW};
+    use winapi::shared::minwindef::BOOL;
+
+    let mut info = MENUITEMINFOW { 
+        cbSize: mem::size_of::<MENUITEMINFOW>() as UINT,
+        fMask: MIIM_FTYPE, fType: 0, fState: 0,
+        wID: 0, hSubMenu: ptr::null_mut(), hbmpChecked: ptr::null_mut(),
+        hbmpUnchecked: ptr::null_mut(), dwItemData: 0, dwTypeData: ptr::null_mut(),
+        cch: 0, hbmpItem: ptr::null_mut()
+    };
+
+    GetMenuItemInfoW(parent_menu, id, false as BOOL, &mut info);
+
+    info.fMask = MIIM_FTYPE | MIIM_DATA;
+    info.dwItemData = parent_menu as _;
+    match owner_draw {
+        true => { info.fType |= MFT_OWNERDRAW; },
+        false => { info.fType &= !MFT_OWNERDRAW; }
+    }
+
+    SetMenuItemInfoW(parent_menu, id, false as BOOL, &mut info);
+}
+
+/// Return true if the menu item is owner drawn
+pub unsafe fn menu_item_owner_draw(parent_menu: HMENU, id: u32) -> bool {
+    use winapi::um::winuser::{GetMenuState, MF_BYCOMMAND, MF_OWNERDRAW};
+    GetMenuState(parent_menu, id, MF_BYCOMMAND) & MF_OWNERDRAW == MF_OWNERDRAW
+}
 
-unsafe fn build_hmenu_separator(menu: HMENU) -> ControlHandle {
-    use winapi::um::winuser::{GetMenuItemCount, SetMenuItemInfoW, AppendMenuW};
+unsafe fn build_hmenu_separator(menu: HMENU, position: Option<u32>) -> ControlHandle {
+    use winapi::um::winuser::{GetMenuItemCount, SetMenuItemInfoW};
     use winapi::um::winuser::{MENUITEMINFOW, MF_SEPARATOR, MIIM_ID};
     use winapi::shared::minwindef::{BOOL};
 
     let item_id = MENU_ITEMS_ID.fetch_add(1, Ordering::SeqCst);
 
     // MF_SEPARATOR ignore the lpNewItem and uIDNewItem parameters, so they must be set using SetMenuItemInfo
-    AppendMenuW(menu, MF_SEPARATOR, 0, ptr::null());
+    insert_menu(menu, position, MF_SEPARATOR, 0, ptr::null());
 
     // Set the unique id of the separator
-    let pos = GetMenuItemCount(menu) - 1;
+    let count = GetMenuItemCount(menu) as u32;
+    let pos = match position {
+        Some(p) if p < count => p,
+        _ => count - 1
+    };
     let mut info = MENUITEMINFOW { 
         cbSize: mem::size_of::<MENUITEMINFOW>() as UINT,
         fMask: MIIM_ID, fType: 0, fState: 0,
@@ -217,6 +301,124 @@ unsafe fn build_hmenu_separator(menu: HMENU) -> ControlHandle {
     ControlHandle::MenuItem(menu, item_id)
 }
 
+/// Append a menu item or insert it at `position` if it is set. A position out of bounds appends the item.
+unsafe fn insert_menu(parent: HMENU, position: Option<u32>, flags: UINT, id: usize, text: *const u16) {
+    use winapi::um::winuser::{AppendMenuW, InsertMenuW, MF_BYPOSITION};
+
+    match position {
+        Some(p) => { InsertMenuW(parent, p, flags | MF_BYPOSITION, id, text); },
+        None => { AppendMenuW(parent, flags, id, text); }
+    }
+}
+
+/// Return the number of items in a menu
+pub unsafe fn menu_item_count(menu: HMENU) -> u32 {
+    use winapi::um::winuser::GetMenuItemCount;
+    GetMenuItemCount(menu).max(0) as u32
+}
+
+/// Delete the item at `index` in a menu. If the item opens a submenu, the submenu is destroyed.
+pub unsafe fn delete_menu_item_at(menu: HMENU, index: u32) -> bool {
+    use winapi::um::winuser::{DeleteMenu, MF_BYPOSITION};
+    DeleteMenu(menu, index, MF_BYPOSITION) != 0
+}
+
+/**
+    Return the handle of the item at `index` in a menu: `ControlHandle::Menu` if the item opens a submenu,
+    `ControlHandle::MenuItem` otherwise.
+*/
+pub unsafe fn menu_item_at(menu: HMENU, index: u32) -> Option<ControlHandle> {
+    use winapi::um::winuser::{GetSubMenu, GetMenuItemID};
+
+    if index >= menu_item_count(menu) {
+        return None;
+    }
+
+    let sub_menu = GetSubMenu(menu, index as i32);
+    if !sub_menu.is_null() {
+        return Some(ControlHandle::Menu(menu, sub_menu));
+    }
+
+    Some(ControlHandle::MenuItem(menu, GetMenuItemID(menu, index as i32)))
+}
+
+/// Return the index of the menu item with the selected id
+pub unsafe fn menu_item_index(menu: HMENU, id: u32) -> Option<u32> {
+    use winapi::um::winuser::GetMenuItemID;
+
+    (0..menu_item_count(menu)).find(|&i| GetMenuItemID(menu, i as i32) == id)
+}
+
+/// Return the text of the menu item at `index`. Separators and bitmap items return an empty string.
+pub unsafe fn menu_item_text(menu: HMENU, index: u32) -> String {
+    use winapi::um::winuser::{GetMenuStringW, MF_BYPOSITION};
+    use super::base_helper::from_utf16;
+
+    let length = GetMenuStringW(menu, index, ptr::null_mut(), 0, MF_BYPOSITION);
+    if length <= 0 {
+        return String::new();
+    }
+
+    let mut buffer: Vec<u16> = vec![0; (length + 1) as usize];
+    GetMenuStringW(menu, index, buffer.as_mut_ptr(), length + 1, MF_BYPOSITION);
+
+    from_utf16(&buffer)
+}
+
+/// Set the text of the menu item at `index`
+pub unsafe fn set_menu_item_text(menu: HMENU, index: u32, text: &str) {
+    use winapi::um::winuser::{MENUITEMINFOW, MIIM_STRING, SetMenuItemInfoW};
+    use winapi::shared::minwindef::BOOL;
+
+    let mut text = to_utf16(text);
+    let mut info = MENUITEMINFOW { 
+        cbSize: mem::size_of::<MENUITEMINFOW>() as UINT,
+        fMask: MIIM_STRING, fType: 0, fState: 0,
+        wID: 0, hSubMenu: ptr::null_mut(), hbmpChecked: ptr::null_mut(),
+        hbmpUnchecked: ptr::null_mut(), dwItemData: 0, dwTypeData: text.as_mut_ptr(),
+        cch: 0, hbmpItem: ptr::null_mut()
+    };
+
+    SetMenuItemInfoW(menu, index, true as BOOL, &mut info);
+}
+
+/// Return true if the menu was created as a popup menu
+pub unsafe fn is_popup_menu(menu: HMENU) -> bool {
+    use winapi::um::winuser::{MENUINFO, MIM_MENUDATA, GetMenuInfo};
+    use winapi::shared::minwindef::DWORD;
+
+    let mut info = MENUINFO {
+        cbSize: mem::size_of::<MENUINFO>() as DWORD,
+        fMask: MIM_MENUDATA,
+        dwStyle: 0,
+        cyMax: 0,
+        hbrBack: ptr::null_mut(),
+        dwContextHelpID: 0,
+        dwMenuData: 0
+    };
+
+    GetMenuInfo(menu, &mut info);
+
+    info.dwMenuData == POPUP_MENU_DATA
+}
+
+unsafe fn set_menu_data(h: HMENU, data: usize) {
+    use winapi::um::winuser::{MENUINFO, MIM_MENUDATA, SetMenuInfo};
+    use winapi::shared::minwindef::DWORD;
+
+    let mut info = MENUINFO {
+        cbSize: mem::size_of::<MENUINFO>() as DWORD,
+        fMask: MIM_MENUDATA,
+        dwStyle: 0,
+        cyMax: 0,
+        hbrBack: ptr::null_mut(),
+        dwContextHelpID: 0,
+        dwMenuData: data
+    };
+
+    SetMenuInfo(h, &mut info);
+}
+
 /**
     Configure the menu to use a WM_MENUCOMMAND instead of a WM_COMMAND when its action are triggered.
     Required in order to allow nwg to dispatch the events correctly
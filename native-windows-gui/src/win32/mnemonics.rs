@@ -0,0 +1,222 @@
+/*!
+    Mnemonics (access keys) of the controls. A mnemonic is the character that follows a `&` in the text of a control (ex: `"&Save"`).
+    The character is underlined, and pressing Alt and the character (or only the character when the focus is not in a text input)
+    activates the control:
+
+      * Buttons are clicked, check boxes are toggled and radio buttons are selected
+      * Labels and group boxes move the focus to the next control in the tab order. Labels only display mnemonics when
+        they are built with `mnemonic(true)` (see `Label::set_mnemonic`)
+
+    When more than one control of a window has the same mnemonic, pressing the mnemonic moves the focus from one control
+    to the next without activating them. Use `mnemonic_conflicts` to find these controls and `assign_mnemonics` to give
+    a unique mnemonic to the controls that do not have one.
+
+    The underlines are hidden until the user presses Alt or navigates with the keyboard, like in the system dialogs,
+    unless the user asked Windows to always show them.
+*/
+use winapi::shared::windef::HWND;
+use winapi::shared::minwindef::{BOOL, LPARAM};
+use crate::controls::ControlHandle;
+use super::window_helper as wh;
+use std::collections::HashSet;
+
+
+/**
+    Returns the mnemonic of `text`, in uppercase. Returns `None` if the text has no mnemonic. `&&` is a literal `&`.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    assert_eq!(nwg::text_mnemonic("&Save"), Some('S'));
+    assert_eq!(nwg::text_mnemonic("Save && Close"), None);
+    ```
+*/
+pub fn text_mnemonic(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '&' {
+            continue;
+        }
+
+        match chars.next() {
+            Some('&') => continue,
+            Some(m) => { return m.to_uppercase().next(); },
+            None => { return None; }
+        }
+    }
+
+    None
+}
+
+/**
+    Returns the controls of `window` that have a mnemonic, with their mnemonic in uppercase. `window` and all its descendants are searched,
+    in the tab order of the controls.
+
+    This function will panic if `window` is not a window-like control.
+*/
+pub fn mnemonics<C: Into<ControlHandle>>(window: C) -> Vec<(ControlHandle, char)> {
+    mnemonic_controls(window)
+        .into_iter()
+        .filter_map(|(hwnd, text)| text_mnemonic(&text).map(|m| (ControlHandle::Hwnd(hwnd), m)))
+        .collect()
+}
+
+/**
+    Returns the mnemonics shared by more than one control of `window`, with the controls that share them.
+
+    This function will panic if `window` is not a window-like control.
+*/
+pub fn mnemonic_conflicts<C: Into<ControlHandle>>(window: C) -> Vec<(char, Vec<ControlHandle>)> {
+    let mut conflicts: Vec<(char, Vec<ControlHandle>)> = Vec::new();
+    for (handle, m) in mnemonics(window) {
+        match conflicts.iter_mut().find(|(m2, _)| *m2 == m) {
+            Some((_, controls)) => controls.push(handle),
+            None => conflicts.push((m, vec![handle]))
+        }
+    }
+
+    conflicts.retain(|(_, controls)| controls.len() > 1);
+    conflicts
+}
+
+/**
+    Gives a mnemonic to the controls of `window` that do not have one. The mnemonics are not used by the other controls
+    or by the menu bar of the window. The first letter of a word is preferred, then the other letters and digits of the text.
+    A control keeps its text if all its characters are already used.
+
+    Returns the controls that received a mnemonic, with their new mnemonic. The existing mnemonics are not changed.
+    Call this function again after the text of the controls changed.
+
+    This function will panic if `window` is not a window-like control.
+*/
+pub fn assign_mnemonics<C: Into<ControlHandle>>(window: C) -> Vec<(ControlHandle, char)> {
+    let handle = window.into().hwnd().expect("Mnemonics can only be searched in a window-like control");
+    let controls = mnemonic_controls(handle);
+
+    let mut used: HashSet<char> = menu_mnemonics(handle).into_iter().collect();
+    used.extend(controls.iter().filter_map(|(_, text)| text_mnemonic(text)));
+
+    let mut assigned = Vec::new();
+    for (hwnd, text) in controls {
+        if text_mnemonic(&text).is_some() {
+            continue;
+        }
+
+        let free = mnemonic_candidates(&text)
+            .into_iter()
+            .find(|&(_, c)| c.to_uppercase().next().map(|u| !used.contains(&u)).unwrap_or(false));
+
+        if let Some((index, c)) = free {
+            let m = c.to_uppercase().next().unwrap();
+            used.insert(m);
+
+            let mut new_text = text.clone();
+            new_text.insert(index, '&');
+            unsafe { wh::set_window_text(hwnd, &new_text); }
+
+            assigned.push((ControlHandle::Hwnd(hwnd), m));
+        }
+    }
+
+    assigned
+}
+
+/// Returns the characters of `text` that can be a mnemonic, with their byte index. The first letters of the words come first.
+fn mnemonic_candidates(text: &str) -> Vec<(usize, char)> {
+    let mut first = Vec::new();
+    let mut others = Vec::new();
+    let mut word_start = true;
+
+    for (index, c) in text.char_indices() {
+        if c.is_alphanumeric() {
+            match word_start {
+                true => first.push((index, c)),
+                false => others.push((index, c))
+            }
+        }
+
+        word_start = c.is_whitespace();
+    }
+
+    first.extend(others);
+    first
+}
+
+/// Returns the controls of `window` that display a mnemonic in their text (buttons and labels), with their text
+fn mnemonic_controls<C: Into<ControlHandle>>(window: C) -> Vec<(HWND, String)> {
+    use winapi::um::winuser::EnumChildWindows;
+
+    let handle = window.into().hwnd().expect("Mnemonics can only be searched in a window-like control");
+
+    let mut controls = Vec::new();
+    unsafe {
+        EnumChildWindows(handle, Some(collect_mnemonic_control), &mut controls as *mut Vec<(HWND, String)> as LPARAM);
+    }
+
+    controls
+}
+
+unsafe extern "system" fn collect_mnemonic_control(handle: HWND, controls: LPARAM) -> BOOL {
+    let controls = &mut *(controls as *mut Vec<(HWND, String)>);
+    if shows_mnemonic(handle) {
+        controls.push((handle, wh::get_window_text(handle)));
+    }
+
+    1
+}
+
+/// Returns true if the text of `handle` can have a mnemonic: the buttons (including the check boxes, the radio buttons and the group boxes),
+/// and the text labels that do not have the `SS_NOPREFIX` style.
+fn shows_mnemonic(handle: HWND) -> bool {
+    use winapi::um::winuser::{GetClassNameW, SS_NOPREFIX, SS_TYPEMASK, SS_LEFT, SS_CENTER, SS_RIGHT, SS_SIMPLE, SS_LEFTNOWORDWRAP};
+
+    let mut class_name = [0u16; 10];
+    let count = unsafe { GetClassNameW(handle, class_name.as_mut_ptr(), 10) } as usize;
+    let style = wh::get_style(handle);
+
+    match String::from_utf16_lossy(&class_name[..count]).as_str() {
+        "Button" => true,
+        "Static" => style & SS_NOPREFIX == 0 && match style & SS_TYPEMASK {
+            SS_LEFT | SS_CENTER | SS_RIGHT | SS_SIMPLE | SS_LEFTNOWORDWRAP => true,
+            _ => false
+        },
+        _ => false
+    }
+}
+
+/// Returns the mnemonics of the menu bar of `window`. Child windows do not have a menu bar.
+fn menu_mnemonics(window: HWND) -> Vec<char> {
+    use winapi::um::winuser::{GetMenu, GetMenuItemCount, GetMenuStringW, WS_CHILD, MF_BYPOSITION};
+    use super::base_helper::from_utf16;
+
+    if wh::get_style(window) & WS_CHILD == WS_CHILD {
+        return Vec::new();
+    }
+
+    let menu = unsafe { GetMenu(window) };
+    if menu.is_null() {
+        return Vec::new();
+    }
+
+    let count = unsafe { GetMenuItemCount(menu) }.max(0) as u32;
+    let mut buffer = [0u16; 256];
+
+    (0..count)
+        .filter_map(|i| {
+            let len = unsafe { GetMenuStringW(menu, i, buffer.as_mut_ptr(), 256, MF_BYPOSITION) };
+            match len > 0 {
+                true => text_mnemonic(&from_utf16(&buffer[..len as usize])),
+                false => None
+            }
+        })
+        .collect()
+}
+
+/// Let the window hide the mnemonic underlines and the focus rectangles until the user uses the keyboard, like a dialog
+pub(crate) fn init_keyboard_cues(window: HWND) {
+    use winapi::um::winuser::{WM_CHANGEUISTATE, UIS_INITIALIZE, UISF_HIDEACCEL, UISF_HIDEFOCUS};
+    use winapi::shared::minwindef::{MAKELONG, WPARAM};
+
+    let state = MAKELONG(UIS_INITIALIZE, UISF_HIDEACCEL | UISF_HIDEFOCUS);
+    wh::send_message(window, WM_CHANGEUISTATE, state as WPARAM, 0);
+}
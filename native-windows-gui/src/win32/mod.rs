@@ -5,6 +5,24 @@ pub(crate) mod window;
 pub(crate) mod message_box;
 pub(crate) mod high_dpi;
 pub(crate) mod monitor;
+pub(crate) mod device;
+pub(crate) mod elevation;
+pub(crate) mod paths;
+pub(crate) mod idle;
+pub(crate) mod power;
+pub(crate) mod modal;
+pub(crate) mod console;
+pub(crate) mod bootstrap;
+pub(crate) mod control_names;
+pub(crate) mod dialog_buttons;
+pub(crate) mod mnemonics;
+pub(crate) mod mouse_tracking;
+pub(crate) mod scrolling;
+pub(crate) mod init_config;
+
+#[cfg(feature = "shortcuts")]
+pub(crate) mod shortcuts;
+pub(crate) mod custom_control;
 
 #[cfg(feature = "menu")]
 pub(crate) mod menu;
@@ -15,6 +33,9 @@ pub(crate) mod cursor;
 #[cfg(feature = "clipboard")]
 pub(crate) mod clipboard;
 
+#[cfg(feature = "crash-dialog")]
+pub(crate) mod crash;
+
 #[cfg(feature = "tabs")]
 pub(crate) mod tabs;
 
@@ -30,6 +51,9 @@ pub(crate) mod richedit;
 #[cfg(feature = "plotting")]
 pub(crate) mod plotters_d2d;
 
+#[cfg(feature = "plotting-pdf")]
+pub(crate) mod plotters_pdf;
+
 use std::{fs, mem, ptr};
 use crate::errors::NwgError;
 
@@ -46,7 +70,8 @@ pub fn dispatch_thread_events() {
     unsafe {
         let mut msg: MSG = mem::zeroed();
         while GetMessageW(&mut msg, ptr::null_mut(), 0, 0) != 0 {
-            if IsDialogMessageW(GetAncestor(msg.hwnd, GA_ROOT), &mut msg) == 0 {
+            scrolling::route_wheel(&mut msg);
+            if !translate_shortcut(&msg) && IsDialogMessageW(GetAncestor(msg.hwnd, GA_ROOT), &mut msg) == 0 {
                 TranslateMessage(&msg); 
                 DispatchMessageW(&msg); 
             }
@@ -70,7 +95,8 @@ pub fn dispatch_thread_events_with_callback<F>(mut cb: F)
         while msg.message != WM_QUIT {
             let has_message = PeekMessageW(&mut msg, ptr::null_mut(), 0, 0, PM_REMOVE) != 0;
             if has_message {
-                if IsDialogMessageW(GetAncestor(msg.hwnd, GA_ROOT), &mut msg) == 0 {
+                scrolling::route_wheel(&mut msg);
+                if !translate_shortcut(&msg) && IsDialogMessageW(GetAncestor(msg.hwnd, GA_ROOT), &mut msg) == 0 {
                     TranslateMessage(&msg); 
                     DispatchMessageW(&msg); 
                 }
@@ -93,13 +119,20 @@ pub fn stop_thread_dispatch() {
 
 
 /**
-  Enable the Windows visual style in the application without having to use a manifest
+  Enable the Windows visual style in the application without having to use a manifest.
+  Returns true if the version 6 of the common controls is active after the call.
+
+  Nothing is done if the application manifest already enables the visual styles. Otherwise the manifest of the common controls
+  is written to a temporary file and activated for the whole process. If the temporary file cannot be written (locked temp directory,
+  restricted account), the manifest embedded in `shell32.dll` is used instead.
+
+  If every method fails, the application keeps running with the version 5 of the common controls: the controls use the classic look
+  and the features of the version 6 (ex: the `LinkLabel` control, the split buttons) are not available. Use `common_controls_v6` to
+  detect this case and `enable_visual_styles_from_resource` to supply the manifest from the resources of the application.
 */
-pub fn enable_visual_styles() {
-    use winapi::shared::minwindef::{ULONG, DWORD, MAX_PATH};
-    use winapi::shared::basetsd::ULONG_PTR;
+pub fn enable_visual_styles() -> bool {
+    use winapi::shared::minwindef::MAX_PATH;
     use winapi::um::fileapi::{GetTempFileNameW, GetTempPathW};
-    use winapi::um::winbase::{ACTCTXW, CreateActCtxW, ActivateActCtx};
 
     const MANIFEST_CONTENT: &str = r#"
 <?xml version="1.0" encoding="UTF-8" standalone="yes"?> 
@@ -112,41 +145,196 @@ pub fn enable_visual_styles() {
     </dependency>
 </assembly>
 "#;
-    const ACTCTX_FLAG_SET_PROCESS_DEFAULT: DWORD = 0x010;
 
-    let mut tmp_dir = [0u16; MAX_PATH + 1];
-    if unsafe { GetTempPathW(tmp_dir.len() as u32, tmp_dir.as_mut_ptr()) } == 0 {
-        return;
+    // The id of the common controls manifest in the resources of shell32.dll
+    const SHELL32_MANIFEST_ID: usize = 124;
+
+    if common_controls_manifest_active() {
+        return common_controls_v6();
     }
+
+    let mut written = false;
+    let mut tmp_dir = [0u16; MAX_PATH + 1];
     let mut tmp_path = [0u16; MAX_PATH]; // Smaller than above, but these are the respective maximums for each function.
-    let prefix = ['n' as u16, 'w' as u16, 'g' as u16];
-    if unsafe { GetTempFileNameW(tmp_dir.as_ptr(), prefix.as_ptr(), 0, tmp_path.as_mut_ptr())} == 0 {
-        return;
+    let prefix = ['n' as u16, 'w' as u16, 'g' as u16, 0];
+
+    if unsafe { GetTempPathW(tmp_dir.len() as u32, tmp_dir.as_mut_ptr()) } != 0 {
+        if unsafe { GetTempFileNameW(tmp_dir.as_ptr(), prefix.as_ptr(), 0, tmp_path.as_mut_ptr())} != 0 {
+            let manifest_path = base_helper::from_utf16(&tmp_path);
+            written = fs::write(&manifest_path, MANIFEST_CONTENT).is_ok();
+            if written {
+                activate_manifest(tmp_path.as_ptr(), None);
+            }
+
+            let _ = fs::remove_file(&manifest_path);
+        }
+    }
+
+    if !written || !common_controls_v6() {
+        let shell32 = base_helper::to_utf16("shell32.dll");
+        activate_manifest(shell32.as_ptr(), Some(SHELL32_MANIFEST_ID));
+    }
+
+    common_controls_v6()
+}
+
+/**
+  Enable the Windows visual style with a manifest embedded in the resources of a module, instead of a temporary file.
+  `module` is the name of the module that contains the manifest (`None` for the current executable) and `id` is the id of the `RT_MANIFEST` resource.
+  Returns true if the version 6 of the common controls is active after the call.
+
+  The manifest with the id 1 is already used by Windows when the executable starts. Store the manifest under another id (ex: 2),
+  and enable the feature `no-styling` so that `nwg::init` does not write the temporary manifest.
+*/
+pub fn enable_visual_styles_from_resource(module: Option<&str>, id: usize) -> bool {
+    use winapi::shared::minwindef::MAX_PATH;
+    use winapi::um::libloaderapi::{GetModuleHandleW, GetModuleFileNameW};
+
+    if common_controls_manifest_active() {
+        return common_controls_v6();
+    }
+
+    let mut path = [0u16; MAX_PATH];
+    unsafe {
+        let hmod = match module {
+            Some(name) => GetModuleHandleW(base_helper::to_utf16(name).as_ptr()),
+            None => GetModuleHandleW(ptr::null_mut())
+        };
+
+        if hmod.is_null() || GetModuleFileNameW(hmod, path.as_mut_ptr(), path.len() as u32) == 0 {
+            return false;
+        }
+    }
+
+    activate_manifest(path.as_ptr(), Some(id));
+    common_controls_v6()
+}
+
+/**
+  Returns true if the version 6 (or later) of the common controls is loaded in the current activation context.
+  With the version 5, the controls use the classic look of Windows whatever the theme of the system.
+*/
+pub fn common_controls_v6() -> bool {
+    common_controls_version().0 >= 6
+}
+
+/**
+  Returns the version of the common controls library (comctl32.dll) used by the application, as `(major, minor)`.
+  Returns `(0, 0)` if the version cannot be read.
+*/
+pub fn common_controls_version() -> (u32, u32) {
+    use winapi::shared::minwindef::DWORD;
+    use winapi::shared::winerror::HRESULT;
+    use winapi::um::libloaderapi::{LoadLibraryW, GetProcAddress, FreeLibrary};
+
+    #[repr(C)]
+    #[allow(non_snake_case)]
+    struct DLLVERSIONINFO {
+        cbSize: DWORD,
+        dwMajorVersion: DWORD,
+        dwMinorVersion: DWORD,
+        dwBuildNumber: DWORD,
+        dwPlatformID: DWORD,
     }
 
-    let manifest_path_raw = tmp_path;
-    let manifest_path = base_helper::from_utf16(&tmp_path);
-    let _ = fs::write(&manifest_path, MANIFEST_CONTENT);
+    type DllGetVersion = unsafe extern "system" fn(*mut DLLVERSIONINFO) -> HRESULT;
+
+    unsafe {
+        // LoadLibraryW follows the activation context, so it returns the version used by the controls
+        let lib = base_helper::to_utf16("comctl32.dll");
+        let module = LoadLibraryW(lib.as_ptr());
+        if module.is_null() {
+            return (0, 0);
+        }
+
+        let proc = GetProcAddress(module, "DllGetVersion\0".as_ptr() as _);
+        let version = match proc.is_null() {
+            true => (0, 0),
+            false => {
+                let get_version: DllGetVersion = mem::transmute(proc);
+                let mut info = DLLVERSIONINFO {
+                    cbSize: mem::size_of::<DLLVERSIONINFO>() as DWORD,
+                    dwMajorVersion: 0,
+                    dwMinorVersion: 0,
+                    dwBuildNumber: 0,
+                    dwPlatformID: 0
+                };
+
+                match get_version(&mut info) {
+                    0 => (info.dwMajorVersion, info.dwMinorVersion),
+                    _ => (0, 0)
+                }
+            }
+        };
+
+        FreeLibrary(module);
+
+        version
+    }
+}
+
+/**
+  Returns true if the active activation context redirects comctl32.dll to a side-by-side version, ie: a manifest that enables
+  the version 6 of the common controls is active. Unlike `common_controls_version`, the library is not loaded.
+*/
+fn common_controls_manifest_active() -> bool {
+    use winapi::um::winbase::{ACTCTX_SECTION_KEYED_DATA, FindActCtxSectionStringW};
+    use winapi::um::winnt::ACTIVATION_CONTEXT_SECTION_DLL_REDIRECTION;
+    use winapi::shared::minwindef::ULONG;
+
+    let lib = base_helper::to_utf16("comctl32.dll");
+    unsafe {
+        let mut data: ACTCTX_SECTION_KEYED_DATA = mem::zeroed();
+        data.cbSize = mem::size_of::<ACTCTX_SECTION_KEYED_DATA>() as ULONG;
+        FindActCtxSectionStringW(0, ptr::null(), ACTIVATION_CONTEXT_SECTION_DLL_REDIRECTION, lib.as_ptr(), &mut data) != 0
+    }
+}
+
+/**
+  Returns true if the controls are drawn with the visual styles: the version 6 of the common controls is active and the theme service is running.
+  The theme service is stopped in safe mode and in some remote sessions. The controls then use the classic look.
+*/
+pub fn visual_styles_active() -> bool {
+    use winapi::um::uxtheme::{IsAppThemed, IsThemeActive};
+    common_controls_v6() && unsafe { IsAppThemed() != 0 && IsThemeActive() != 0 }
+}
+
+/// Create an activation context from a manifest file or from the manifest resource `resource` of a module and make it the default of the process.
+fn activate_manifest(source: *const u16, resource: Option<usize>) -> bool {
+    use winapi::shared::minwindef::{ULONG, DWORD};
+    use winapi::shared::basetsd::ULONG_PTR;
+    use winapi::um::winbase::{ACTCTXW, CreateActCtxW, ActivateActCtx};
+    use winapi::um::handleapi::INVALID_HANDLE_VALUE;
+
+    const ACTCTX_FLAG_RESOURCE_NAME_VALID: DWORD = 0x008;
+    const ACTCTX_FLAG_SET_PROCESS_DEFAULT: DWORD = 0x010;
+
+    let (flags, resource_name) = match resource {
+        Some(id) => (ACTCTX_FLAG_SET_PROCESS_DEFAULT | ACTCTX_FLAG_RESOURCE_NAME_VALID, id as *const u16),
+        None => (ACTCTX_FLAG_SET_PROCESS_DEFAULT, ptr::null())
+    };
 
     let mut activation_cookie: ULONG_PTR = 0;
     let mut act_ctx = ACTCTXW {
-        cbSize: mem::size_of::<ACTCTXW> as ULONG,
-        dwFlags: ACTCTX_FLAG_SET_PROCESS_DEFAULT,
-        lpSource: manifest_path_raw.as_ptr(),
+        cbSize: mem::size_of::<ACTCTXW>() as ULONG,
+        dwFlags: flags,
+        lpSource: source,
         wProcessorArchitecture: 0,
         wLangId: 0,
         lpAssemblyDirectory: ptr::null_mut(),
-        lpResourceName: ptr::null_mut(),
+        lpResourceName: resource_name,
         lpApplicationName: ptr::null_mut(),
         hModule: ptr::null_mut()
     };
 
     unsafe {
         let handle = CreateActCtxW(&mut act_ctx);
-        ActivateActCtx(handle, &mut activation_cookie);
-    }
+        if handle == INVALID_HANDLE_VALUE {
+            return false;
+        }
 
-    let _ = fs::remove_file(&manifest_path);
+        ActivateActCtx(handle, &mut activation_cookie) != 0
+    }
 }
 
 /**
@@ -154,37 +342,35 @@ pub fn enable_visual_styles() {
     Also register the custom classes used by NWG
 */
 pub fn init_common_controls() -> Result<(), NwgError> {
+    register_common_controls(Default::default())
+}
+
+/// Register the classes of the common controls in `controls` and the custom classes used by NWG
+pub(crate) fn register_common_controls(controls: init_config::CommonControlClasses) -> Result<(), NwgError> {
     use winapi::um::objbase::CoInitialize;
     use winapi::um::libloaderapi::LoadLibraryW;
     use winapi::um::commctrl::{InitCommonControlsEx, INITCOMMONCONTROLSEX};
     use winapi::um::commctrl::{ICC_BAR_CLASSES, ICC_STANDARD_CLASSES, ICC_DATE_CLASSES, ICC_PROGRESS_CLASS,
      ICC_TAB_CLASSES, ICC_TREEVIEW_CLASSES, ICC_LISTVIEW_CLASSES};
     use winapi::shared::winerror::{S_OK, S_FALSE};
+    use self::init_config::CommonControlClasses as Classes;
 
     unsafe {
-        let mut classes = ICC_BAR_CLASSES | ICC_STANDARD_CLASSES;
-
-        if cfg!(feature = "datetime-picker") {
-            classes |= ICC_DATE_CLASSES;
-        }
-
-        if cfg!(feature = "progress-bar") {
-            classes |= ICC_PROGRESS_CLASS;
-        }
-
-        if cfg!(feature = "tabs") {
-            classes |= ICC_TAB_CLASSES;
-        }
-
-        if cfg!(feature = "tree-view") {
-            classes |= ICC_TREEVIEW_CLASSES;
-        }
-
-        if cfg!(feature = "list-view") {
-            classes |= ICC_LISTVIEW_CLASSES;
-        }
-
-        if cfg!(feature = "rich-textbox") {
+        let flags = [
+            (Classes::STANDARD, ICC_STANDARD_CLASSES),
+            (Classes::BAR, ICC_BAR_CLASSES),
+            (Classes::DATE, ICC_DATE_CLASSES),
+            (Classes::PROGRESS, ICC_PROGRESS_CLASS),
+            (Classes::TAB, ICC_TAB_CLASSES),
+            (Classes::TREE_VIEW, ICC_TREEVIEW_CLASSES),
+            (Classes::LIST_VIEW, ICC_LISTVIEW_CLASSES),
+        ];
+
+        let classes = flags.iter()
+            .filter(|(class, _)| controls.contains(*class))
+            .fold(0, |acc, (_, icc)| acc | icc);
+
+        if controls.contains(Classes::RICH_EDIT) {
             let lib = base_helper::to_utf16("Msftedit.dll");
             LoadLibraryW(lib.as_ptr());
         }
@@ -220,6 +406,12 @@ fn extern_canvas_init() -> Result<(), NwgError> { extern_canvas::create_extern_c
 #[cfg(not(feature = "extern-canvas"))]
 fn extern_canvas_init() -> Result<(), NwgError> { Ok(()) }
 
+#[cfg(feature = "shortcuts")]
+unsafe fn translate_shortcut(msg: &winapi::um::winuser::MSG) -> bool { shortcuts::translate_shortcut(msg) }
+
+#[cfg(not(feature = "shortcuts"))]
+unsafe fn translate_shortcut(_msg: &winapi::um::winuser::MSG) -> bool { false }
+
 #[cfg(feature = "frame")]
 fn frame_init() -> Result<(), NwgError> { window::create_frame_classes() }
 
@@ -0,0 +1,141 @@
+/*!
+    Deferral of the events of a window while a modal dialog is running.
+
+    A modal dialog runs its own message loop. The messages received by the window that opened it are still dispatched
+    during that loop, so its event handlers can be called again while the handler that opened the dialog is still running.
+    If both handlers borrow the same `RefCell`, the application panics.
+*/
+use winapi::shared::windef::HWND;
+use winapi::um::winuser::{GetAncestor, GA_ROOT};
+use crate::controls::ControlHandle;
+use crate::{Event, EventData};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+
+struct DeferredWindow {
+    window: HWND,
+    scopes: usize,
+    events: Vec<Box<dyn FnOnce()>>,
+}
+
+thread_local! {
+    static DEFERRED_WINDOWS: RefCell<Vec<DeferredWindow>> = RefCell::new(Vec::new());
+}
+
+
+/**
+    A guard that defers the events of a window until it is dropped. See `modal_scope`.
+*/
+pub struct ModalScope {
+    window: HWND,
+}
+
+impl Drop for ModalScope {
+    fn drop(&mut self) {
+        let events = DEFERRED_WINDOWS.with(|windows| {
+            let mut windows = windows.borrow_mut();
+            let index = match windows.iter().position(|w| w.window == self.window) {
+                Some(index) => index,
+                None => { return Vec::new(); }
+            };
+
+            windows[index].scopes -= 1;
+            match windows[index].scopes {
+                0 => windows.remove(index).events,
+                _ => Vec::new()
+            }
+        });
+
+        // The queue is released before the replay so a handler can open a new scope
+        for event in events {
+            event();
+        }
+    }
+}
+
+/**
+    Defers the events of the top level window of `window` until the returned guard is dropped. The deferred events are
+    then dispatched in the order they were received. Use it around a modal dialog (message box, file dialog, color dialog, etc)
+    opened from an event handler, so the other handlers of the window are not called while the first one is still running.
+
+    Only the events of the handlers bound with `full_bind_event_handler` and `bind_event_handler` are deferred. Raw event handlers
+    are not affected. The events that need an answer before the message returns (`OnWindowClose`, `OnPaint`, `OnMinMaxInfo`,
+    `OnTooltipText`, `OnFileDrop`, `OnSessionEnding`, `OnDeviceArrival`, `OnDeviceRemoval`, `OnMenuItemMeasure`, `OnMenuItemDraw`,
+    `OnDragEnter`, `OnDragOver`, `OnTreeItemDelete`, `OnTreeItemExpanding` and `OnMenuOpening`) are always dispatched immediately.
+
+    Scopes can be nested. The events are dispatched when the last scope of the window is dropped.
+
+    This function will panic if `window` is not a window handle.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn confirm_delete(window: &nwg::Window) -> bool {
+        let _scope = nwg::modal_scope(window);
+        let params = nwg::MessageParams {
+            title: "Delete",
+            content: "Delete the selected items?",
+            buttons: nwg::MessageButtons::YesNo,
+            icons: nwg::MessageIcons::Question
+        };
+
+        nwg::modal_message(window, &params) == nwg::MessageChoice::Yes
+    }
+    ```
+*/
+pub fn modal_scope<C: Into<ControlHandle>>(window: C) -> ModalScope {
+    let handle = window.into().hwnd().expect("Cannot open a modal scope on a control that is not a window");
+    let window = unsafe { GetAncestor(handle, GA_ROOT) };
+
+    DEFERRED_WINDOWS.with(|windows| {
+        let mut windows = windows.borrow_mut();
+        match windows.iter_mut().find(|w| w.window == window) {
+            Some(w) => { w.scopes += 1; },
+            None => windows.push(DeferredWindow { window, scopes: 1, events: Vec::new() })
+        }
+    });
+
+    ModalScope { window }
+}
+
+/**
+    Call the event handler `f` bound to `bound`, or queue the call if a modal scope is open on the top level window of `bound`.
+*/
+pub(crate) fn dispatch_event<F>(bound: HWND, f: &Rc<F>, evt: Event, data: EventData, handle: ControlHandle)
+    where F: Fn(Event, EventData, ControlHandle) -> () + 'static
+{
+    let window = match can_defer(evt) && DEFERRED_WINDOWS.with(|windows| !windows.borrow().is_empty()) {
+        true => unsafe { GetAncestor(bound, GA_ROOT) },
+        false => { return f(evt, data, handle); }
+    };
+
+    let f = f.clone();
+    let event: Box<dyn FnOnce()> = Box::new(move || f(evt, data, handle));
+
+    let event = DEFERRED_WINDOWS.with(move |windows| {
+        match windows.borrow_mut().iter_mut().find(|w| w.window == window) {
+            Some(w) => { w.events.push(event); None },
+            None => Some(event)
+        }
+    });
+
+    if let Some(event) = event {
+        event();
+    }
+}
+
+/**
+    The events that must be handled before the message returns. Their data point to the message parameters, to a value read back
+    by the sender once the handler returns (ex: the drop effect of `OnDragOver`), or to an item that is freed after the notification
+    (`OnTreeItemDelete`). The children of an item are inserted in `OnTreeItemExpanding` and the items of a popup menu are updated
+    in `OnMenuOpening` before the control displays them.
+*/
+pub(crate) fn can_defer(evt: Event) -> bool {
+    match evt {
+        Event::OnWindowClose | Event::OnSessionEnding | Event::OnDeviceArrival | Event::OnDeviceRemoval | Event::OnMinMaxInfo |
+        Event::OnTooltipText | Event::OnPaint | Event::OnFileDrop | Event::OnMenuItemMeasure | Event::OnMenuItemDraw |
+        Event::OnDragEnter | Event::OnDragOver | Event::OnTreeItemDelete | Event::OnTreeItemExpanding | Event::OnMenuOpening => false,
+        _ => true
+    }
+}
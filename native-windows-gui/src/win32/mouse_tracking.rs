@@ -0,0 +1,161 @@
+/*!
+    Mouse tracking of the controls. Raises `OnMouseEnter`, `OnMouseHover` and `OnMouseLeave` for every control bound to an event handler.
+
+    The mouse leaves a window when it moves over one of its children: a window receives `OnMouseLeave` and the child receives `OnMouseEnter`.
+    `OnMouseHover` is raised when the mouse rests over a control for the hover time of the control (see `set_hover_time`).
+    It is raised again when the mouse moves and rests again.
+*/
+use winapi::shared::windef::HWND;
+use winapi::shared::minwindef::{UINT, WPARAM, LPARAM, LRESULT};
+use winapi::shared::basetsd::{UINT_PTR, DWORD_PTR};
+use crate::controls::ControlHandle;
+use super::base_helper::to_utf16;
+use super::window_helper as wh;
+
+/// The window property that holds the tracking state of a control
+const TRACKING_PROP: &'static str = "NWG_MOUSE_TRACKING";
+
+/// The window property that holds the hover time of a control, in milliseconds
+const HOVER_TIME_PROP: &'static str = "NWG_HOVER_TIME";
+
+/// The id of the subclass that removes the properties when a control is destroyed
+const TRACKING_SUBCLASS_ID: UINT_PTR = 0x4E4D5452;
+
+/// The mouse is over the control and `OnMouseHover` was not raised yet
+const TRACKING_HOVER: usize = 1;
+
+/// The mouse is over the control and `OnMouseHover` was raised
+const TRACKING_LEAVE: usize = 2;
+
+
+/**
+    Sets the time the mouse must rest over `control` before `OnMouseHover` is raised, in milliseconds.
+    Set `time` to `None` to use the hover time of the system (400 ms by default).
+    The new time is used the next time the mouse enters or moves over the control.
+
+    This function will panic if `control` is not a window-like control.
+*/
+pub fn set_hover_time<C: Into<ControlHandle>>(control: C, time: Option<u32>) {
+    use winapi::um::winuser::{SetPropW, RemovePropW};
+
+    let handle = control.into().hwnd().expect("Only the window-like controls can have a hover time");
+    let prop = to_utf16(HOVER_TIME_PROP);
+
+    unsafe {
+        match time {
+            Some(time) => {
+                SetPropW(handle, prop.as_ptr(), time.max(1) as usize as _);
+                remove_props_on_destroy(handle);
+            },
+            None => { RemovePropW(handle, prop.as_ptr()); }
+        }
+    }
+}
+
+/**
+    Returns the time the mouse must rest over `control` before `OnMouseHover` is raised, in milliseconds.
+    Returns the hover time of the system if the control has no hover time.
+
+    This function will panic if `control` is not a window-like control.
+*/
+pub fn hover_time<C: Into<ControlHandle>>(control: C) -> u32 {
+    use winapi::um::winuser::{SystemParametersInfoW, SPI_GETMOUSEHOVERTIME};
+
+    let handle = control.into().hwnd().expect("Only the window-like controls can have a hover time");
+    match unsafe { get_prop(handle, HOVER_TIME_PROP) } {
+        0 => {
+            let mut time: u32 = 0;
+            unsafe { SystemParametersInfoW(SPI_GETMOUSEHOVERTIME, 0, &mut time as *mut u32 as _, 0); }
+            time
+        },
+        time => time as u32
+    }
+}
+
+/**
+    Track the mouse after it moved over `hwnd`. Sends `NWG_MOUSE_ENTER` to `hwnd` when the mouse enters it,
+    so that all the event handlers bound to the control raise `OnMouseEnter`.
+*/
+pub(crate) unsafe fn mouse_moved(hwnd: HWND) {
+    use winapi::um::winuser::{TrackMouseEvent, TRACKMOUSEEVENT, TME_LEAVE, TME_HOVER, HOVER_DEFAULT};
+    use std::mem;
+
+    let state = get_prop(hwnd, TRACKING_PROP);
+    if state == TRACKING_HOVER {
+        return;
+    }
+
+    let hover_time = match get_prop(hwnd, HOVER_TIME_PROP) {
+        0 => HOVER_DEFAULT,
+        time => time as u32
+    };
+
+    let mut track = TRACKMOUSEEVENT {
+        cbSize: mem::size_of::<TRACKMOUSEEVENT>() as u32,
+        dwFlags: TME_LEAVE | TME_HOVER,
+        hwndTrack: hwnd,
+        dwHoverTime: hover_time,
+    };
+
+    if TrackMouseEvent(&mut track) == 0 {
+        return;
+    }
+
+    set_state(hwnd, TRACKING_HOVER);
+
+    if state == 0 {
+        wh::send_message(hwnd, wh::NWG_MOUSE_ENTER, 0, 0);
+    }
+}
+
+/// The mouse rested over `hwnd`. Hover is tracked again when the mouse moves.
+pub(crate) unsafe fn mouse_hovered(hwnd: HWND) {
+    if get_prop(hwnd, TRACKING_PROP) != 0 {
+        set_state(hwnd, TRACKING_LEAVE);
+    }
+}
+
+/// The mouse left `hwnd`
+pub(crate) unsafe fn mouse_left(hwnd: HWND) {
+    use winapi::um::winuser::RemovePropW;
+
+    let prop = to_utf16(TRACKING_PROP);
+    RemovePropW(hwnd, prop.as_ptr());
+}
+
+unsafe fn set_state(hwnd: HWND, state: usize) {
+    use winapi::um::winuser::SetPropW;
+
+    let prop = to_utf16(TRACKING_PROP);
+    SetPropW(hwnd, prop.as_ptr(), state as _);
+    remove_props_on_destroy(hwnd);
+}
+
+unsafe fn get_prop(hwnd: HWND, name: &str) -> usize {
+    use winapi::um::winuser::GetPropW;
+
+    let prop = to_utf16(name);
+    GetPropW(hwnd, prop.as_ptr()) as usize
+}
+
+/// Removes the properties of `hwnd` when it is destroyed. Installing the subclass again only replaces it.
+unsafe fn remove_props_on_destroy(hwnd: HWND) {
+    use winapi::um::commctrl::SetWindowSubclass;
+    SetWindowSubclass(hwnd, Some(remove_props), TRACKING_SUBCLASS_ID, 0);
+}
+
+unsafe extern "system" fn remove_props(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, id: UINT_PTR, _data: DWORD_PTR) -> LRESULT {
+    use winapi::um::winuser::{RemovePropW, WM_NCDESTROY};
+    use winapi::um::commctrl::{DefSubclassProc, RemoveWindowSubclass};
+
+    if msg == WM_NCDESTROY {
+        for name in &[TRACKING_PROP, HOVER_TIME_PROP] {
+            let prop = to_utf16(name);
+            RemovePropW(hwnd, prop.as_ptr());
+        }
+
+        RemoveWindowSubclass(hwnd, Some(remove_props), id);
+    }
+
+    DefSubclassProc(hwnd, msg, w, l)
+}
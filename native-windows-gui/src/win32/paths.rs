@@ -0,0 +1,251 @@
+/*!
+    Known folders of the user profile and helpers to display paths, file sizes and file dates like the Windows explorer.
+*/
+use winapi::shared::minwindef::{BOOL, DWORD, UINT};
+use winapi::shared::ntdef::{LPCWSTR, LPWSTR, PWSTR, LONGLONG};
+use winapi::shared::windef::HDC;
+use super::base_helper::{to_utf16, from_utf16};
+use crate::controls::ControlHandle;
+use crate::NwgError;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use std::{fs, ptr};
+
+#[link(name = "shlwapi")]
+extern "system" {
+    fn StrFormatByteSizeW(qdw: LONGLONG, pszBuf: LPWSTR, cchBuf: UINT) -> PWSTR;
+    fn StrFormatKBSizeW(qdw: LONGLONG, pszBuf: LPWSTR, cchBuf: UINT) -> PWSTR;
+    fn PathCompactPathExW(pszOut: LPWSTR, pszSrc: LPCWSTR, cchMax: UINT, dwFlags: DWORD) -> BOOL;
+    fn PathCompactPathW(hDC: HDC, pszPath: LPWSTR, dx: UINT) -> BOOL;
+}
+
+/// The size of the buffers used by the path functions
+const MAX_PATH_BUFFER: usize = 260;
+
+const DATE_SHORTDATE: DWORD = 0x01;
+const TIME_NOSECONDS: DWORD = 0x02;
+
+
+/// The folders of the system and of the user profile that can be located with `KnownFolder::path`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum KnownFolder {
+    Desktop,
+    Documents,
+    Downloads,
+    Music,
+    Pictures,
+    Videos,
+    SavedGames,
+    Templates,
+    StartMenu,
+    Startup,
+
+    /// The root of the user profile (ex: `C:\Users\name`)
+    Profile,
+
+    /// The per-user application data that follows the user between computers (ex: `C:\Users\name\AppData\Roaming`)
+    RoamingAppData,
+
+    /// The per-user application data that stays on the computer (ex: `C:\Users\name\AppData\Local`)
+    LocalAppData,
+
+    /// The per-user application data for low integrity processes (ex: `C:\Users\name\AppData\LocalLow`)
+    LocalAppDataLow,
+
+    /// The application data shared by every user (ex: `C:\ProgramData`)
+    ProgramData,
+    ProgramFiles,
+    ProgramFilesX86,
+
+    /// The public user profile (ex: `C:\Users\Public`)
+    Public,
+    Fonts,
+    System,
+    Windows,
+}
+
+impl KnownFolder {
+
+    /**
+        Return the path of the folder. The path follows the redirections made by the user (ex: Documents moved to another drive).
+        Returns an error if the folder does not exist on this system.
+    */
+    pub fn path(&self) -> Result<PathBuf, NwgError> {
+        use winapi::um::shlobj::SHGetKnownFolderPath;
+        use winapi::um::combaseapi::CoTaskMemFree;
+        use winapi::shared::winerror::S_OK;
+
+        let id = self.id();
+        let mut raw: PWSTR = ptr::null_mut();
+
+        unsafe {
+            let result = SHGetKnownFolderPath(&id, 0, ptr::null_mut(), &mut raw);
+            if result != S_OK {
+                if !raw.is_null() { CoTaskMemFree(raw as _); }
+                return Err(NwgError::initialization(format!("Failed to locate the known folder {:?}", self)));
+            }
+
+            let mut length = 0;
+            while *raw.add(length) != 0 {
+                length += 1;
+            }
+
+            let path = from_utf16(std::slice::from_raw_parts(raw, length));
+            CoTaskMemFree(raw as _);
+
+            Ok(PathBuf::from(path))
+        }
+    }
+
+    fn id(&self) -> winapi::shared::guiddef::GUID {
+        use winapi::um::knownfolders::*;
+
+        match self {
+            KnownFolder::Desktop => FOLDERID_Desktop,
+            KnownFolder::Documents => FOLDERID_Documents,
+            KnownFolder::Downloads => FOLDERID_Downloads,
+            KnownFolder::Music => FOLDERID_Music,
+            KnownFolder::Pictures => FOLDERID_Pictures,
+            KnownFolder::Videos => FOLDERID_Videos,
+            KnownFolder::SavedGames => FOLDERID_SavedGames,
+            KnownFolder::Templates => FOLDERID_Templates,
+            KnownFolder::StartMenu => FOLDERID_StartMenu,
+            KnownFolder::Startup => FOLDERID_Startup,
+            KnownFolder::Profile => FOLDERID_Profile,
+            KnownFolder::RoamingAppData => FOLDERID_RoamingAppData,
+            KnownFolder::LocalAppData => FOLDERID_LocalAppData,
+            KnownFolder::LocalAppDataLow => FOLDERID_LocalAppDataLow,
+            KnownFolder::ProgramData => FOLDERID_ProgramData,
+            KnownFolder::ProgramFiles => FOLDERID_ProgramFiles,
+            KnownFolder::ProgramFilesX86 => FOLDERID_ProgramFilesX86,
+            KnownFolder::Public => FOLDERID_Public,
+            KnownFolder::Fonts => FOLDERID_Fonts,
+            KnownFolder::System => FOLDERID_System,
+            KnownFolder::Windows => FOLDERID_Windows,
+        }
+    }
+
+}
+
+/**
+    Return the folder where an application should store its configuration: `RoamingAppData\app`.
+    `app` can contain sub folders (ex: "Company\\App"). The folder is created if it does not exist.
+*/
+pub fn app_config_dir(app: &str) -> Result<PathBuf, NwgError> {
+    app_dir(KnownFolder::RoamingAppData, app)
+}
+
+/**
+    Return the folder where an application should store its data and caches: `LocalAppData\app`.
+    `app` can contain sub folders (ex: "Company\\App"). The folder is created if it does not exist.
+*/
+pub fn app_data_dir(app: &str) -> Result<PathBuf, NwgError> {
+    app_dir(KnownFolder::LocalAppData, app)
+}
+
+fn app_dir(folder: KnownFolder, app: &str) -> Result<PathBuf, NwgError> {
+    let path = folder.path()?.join(app);
+    fs::create_dir_all(&path)
+        .map_err(|e| NwgError::initialization(format!("Failed to create {:?}: {}", path, e)))?;
+
+    Ok(path)
+}
+
+/**
+    Shorten `path` to at most `max_chars` characters by replacing the middle of the path with an ellipsis
+    (ex: `C:\Users\...\report.docx`). The file name is kept as long as possible.
+*/
+pub fn compact_path(path: &str, max_chars: usize) -> String {
+    let source = to_utf16(path);
+    let max_chars = max_chars.min(MAX_PATH_BUFFER - 1);
+    let mut buffer = vec![0u16; MAX_PATH_BUFFER];
+
+    unsafe {
+        if PathCompactPathExW(buffer.as_mut_ptr(), source.as_ptr(), (max_chars + 1) as UINT, 0) == 0 {
+            return path.to_string();
+        }
+    }
+
+    from_utf16(&buffer)
+}
+
+/**
+    Shorten `path` so that it fits in `width` logical pixels when drawn with the font of `control`. Useful to display a path in a label.
+    Panics if the control is not a window.
+*/
+pub fn compact_path_to_width<C: Into<ControlHandle>>(control: C, path: &str, width: u32) -> String {
+    use winapi::um::winuser::{GetDC, ReleaseDC, WM_GETFONT};
+    use winapi::um::wingdi::SelectObject;
+    use super::window_helper as wh;
+    use super::high_dpi::logical_to_physical_for;
+
+    let hwnd = control.into().hwnd().expect("compact_path_to_width requires a window control");
+
+    let mut buffer = to_utf16(path);
+    if buffer.len() < MAX_PATH_BUFFER {
+        buffer.resize(MAX_PATH_BUFFER, 0);
+    }
+
+    unsafe {
+        let (width, _) = logical_to_physical_for(hwnd, width as i32, 0);
+        let font = wh::send_message(hwnd, WM_GETFONT, 0, 0);
+        let dc = GetDC(hwnd);
+        let old = SelectObject(dc, font as _);
+        PathCompactPathW(dc, buffer.as_mut_ptr(), width.max(0) as UINT);
+        SelectObject(dc, old);
+        ReleaseDC(hwnd, dc);
+    }
+
+    from_utf16(&buffer)
+}
+
+/// Format a file size with the unit that fits best, like the explorer status bar and properties dialog (ex: "12.3 KB", "4.50 MB")
+pub fn format_file_size(bytes: u64) -> String {
+    let mut buffer = [0u16; 64];
+    unsafe { StrFormatByteSizeW(bytes as LONGLONG, buffer.as_mut_ptr(), buffer.len() as UINT); }
+    from_utf16(&buffer)
+}
+
+/// Format a file size in kilobytes, like the size column of the explorer (ex: "1,024 KB")
+pub fn format_file_size_kb(bytes: u64) -> String {
+    let mut buffer = [0u16; 64];
+    unsafe { StrFormatKBSizeW(bytes as LONGLONG, buffer.as_mut_ptr(), buffer.len() as UINT); }
+    from_utf16(&buffer)
+}
+
+/// Format a file time in the local time zone with the short date and time formats of the user, like the date columns of the explorer
+pub fn format_file_time(time: SystemTime) -> String {
+    use winapi::shared::minwindef::FILETIME;
+    use winapi::um::minwinbase::SYSTEMTIME;
+    use winapi::um::timezoneapi::{FileTimeToSystemTime, SystemTimeToTzSpecificLocalTime};
+    use winapi::um::datetimeapi::{GetDateFormatW, GetTimeFormatW};
+    use winapi::um::winnt::LOCALE_USER_DEFAULT;
+    use std::time::UNIX_EPOCH;
+    use std::mem;
+
+    // Number of 100ns intervals between 1601-01-01 and 1970-01-01
+    const EPOCH_DIFFERENCE: u64 = 116_444_736_000_000_000;
+
+    let since_epoch = match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => d,
+        Err(_) => { return String::new(); }
+    };
+
+    let ticks = EPOCH_DIFFERENCE + (since_epoch.as_nanos() / 100) as u64;
+    let file_time = FILETIME { dwLowDateTime: ticks as u32, dwHighDateTime: (ticks >> 32) as u32 };
+
+    unsafe {
+        let mut utc: SYSTEMTIME = mem::zeroed();
+        let mut local: SYSTEMTIME = mem::zeroed();
+        if FileTimeToSystemTime(&file_time, &mut utc) == 0 || SystemTimeToTzSpecificLocalTime(ptr::null(), &utc, &mut local) == 0 {
+            return String::new();
+        }
+
+        let mut date = [0u16; 64];
+        let mut time = [0u16; 64];
+        GetDateFormatW(LOCALE_USER_DEFAULT, DATE_SHORTDATE, &local, ptr::null(), date.as_mut_ptr(), date.len() as i32);
+        GetTimeFormatW(LOCALE_USER_DEFAULT, TIME_NOSECONDS, &local, ptr::null(), time.as_mut_ptr(), time.len() as i32);
+
+        format!("{} {}", from_utf16(&date), from_utf16(&time))
+    }
+}
@@ -109,6 +109,7 @@ struct PixelBitmap {
 }
 
 struct Target {
+    hwnd: HWND,
     render_target: *mut ID2D1HwndRenderTarget,
     brushes: HashMap<Color, *mut ID2D1SolidColorBrush>,
 
@@ -386,8 +387,9 @@ impl<'a> DrawingBackend for &'a PlottersBackend {
     type ErrorType = PlottersError;
 
     fn get_size(&self) -> (u32, u32) {
-        let (width, height) = self.target().size;
-        let (width, height) = unsafe { high_dpi::physical_to_logical(width as i32, height as i32) };
+        let target = self.target();
+        let (width, height) = target.size;
+        let (width, height) = unsafe { high_dpi::physical_to_logical_for(target.hwnd, width as i32, height as i32) };
         (width as u32, height as u32)
     }
 
@@ -754,6 +756,7 @@ unsafe fn build_render_target(hwnd: HWND, factory: &mut ID2D1Factory) -> Result<
         Err(PlottersError::RendererInit("Failed to create the direct2D render target".into()))
     } else {
         Ok(Target {
+            hwnd,
             render_target,
             brushes: Default::default(),
             pixel_bitmap: None,
@@ -0,0 +1,436 @@
+/*!
+    A minimal PDF writer used as a plotters backend, to export the charts drawn with plotters without a printer.
+
+    The document has a single page. Each pixel of the drawing area is a point (1/72 inch) of the page.
+    The shapes are written as vectors. The text uses the standard PDF fonts (Helvetica, Times and Courier), so no font is embedded
+    and only the latin-1 characters can be displayed.
+*/
+use plotters::prelude::DrawingBackend;
+use plotters_backend::{DrawingErrorKind, BackendColor, BackendStyle, BackendTextStyle, BackendCoord};
+use super::plotters_d2d::PlottersError;
+use std::{cell::RefCell, fs, io, path::Path};
+
+/// The distance of the bezier control points used to draw a quarter of a circle, relative to the radius
+const CIRCLE_KAPPA: f64 = 0.5523;
+
+/// The height of the text above the baseline, relative to the font size
+const FONT_ASCENT: f64 = 0.8;
+
+
+struct PdfContent {
+    /// The drawing operators of the page
+    operators: String,
+
+    /// The base fonts used by the page. The index is the font resource name (ex: `/F0`)
+    fonts: Vec<&'static str>,
+
+    /// The size and the RGB pixels of the images used by the page. The index is the image resource name (ex: `/Im0`)
+    images: Vec<(u32, u32, Vec<u8>)>,
+}
+
+/**
+    A plotters backend that draws into a PDF document. Draw on the backend with plotters, then write the document with `save` or `to_bytes`.
+    See also `Plotters::export_pdf` to export a chart at the size of a plotters control.
+
+    Requires the `plotting-pdf` feature.
+
+    ```rust
+    use native_windows_gui as nwg;
+    use plotters::prelude::*;
+
+    fn export_chart() -> std::io::Result<()> {
+        let backend = nwg::PdfBackend::new(600, 400);
+        {
+            let root: DrawingArea<_, _> = (&backend).into();
+            root.fill(&WHITE).unwrap();
+            root.draw(&Circle::new((300, 200), 100, BLUE.filled())).unwrap();
+        }
+
+        backend.save("chart.pdf")
+    }
+    ```
+*/
+pub struct PdfBackend {
+    size: (u32, u32),
+    content: RefCell<PdfContent>,
+}
+
+impl PdfBackend {
+
+    /// Create an empty PDF document with a page of `width` by `height` points
+    pub fn new(width: u32, height: u32) -> PdfBackend {
+        // Flip the y axis of the page so that the coordinates of plotters can be used as is
+        let operators = format!("1 0 0 -1 0 {} cm\n1 J 1 j\n", height);
+
+        PdfBackend {
+            size: (width, height),
+            content: RefCell::new(PdfContent { operators, fonts: Vec::new(), images: Vec::new() }),
+        }
+    }
+
+    /// Write the PDF document to the file at `path`. The file is replaced if it exists.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    /// Returns the PDF document
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let content = self.content.borrow();
+        let (width, height) = self.size;
+
+        let fonts_start = 5;
+        let images_start = fonts_start + content.fonts.len();
+
+        let font_resources: String = (0..content.fonts.len())
+            .map(|i| format!("/F{} {} 0 R ", i, fonts_start + i))
+            .collect();
+
+        let image_resources: String = (0..content.images.len())
+            .map(|i| format!("/Im{} {} 0 R ", i, images_start + i))
+            .collect();
+
+        let mut objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents 4 0 R /Resources << /Font << {}>> /XObject << {}>> >> >>",
+                width, height, font_resources, image_resources
+            ).into_bytes(),
+            pdf_stream("", content.operators.as_bytes()),
+        ];
+
+        for font in content.fonts.iter() {
+            objects.push(format!("<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>", font).into_bytes());
+        }
+
+        for (width, height, pixels) in content.images.iter() {
+            let dict = format!("/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB /BitsPerComponent 8 ", width, height);
+            objects.push(pdf_stream(&dict, pixels));
+        }
+
+        let mut pdf = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            pdf.extend_from_slice(object);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref = pdf.len();
+        pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in offsets {
+            pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+
+        pdf.extend_from_slice(format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref).as_bytes());
+
+        pdf
+    }
+
+    fn push(&self, operators: &str) {
+        self.content.borrow_mut().operators.push_str(operators);
+    }
+
+    /// Returns the resource index of a base font, adding the font to the page if it was not used before
+    fn font_index(&self, font: &'static str) -> usize {
+        let mut content = self.content.borrow_mut();
+        match content.fonts.iter().position(|&f| f == font) {
+            Some(index) => index,
+            None => {
+                content.fonts.push(font);
+                content.fonts.len() - 1
+            }
+        }
+    }
+
+}
+
+/// The color operands of a plotters color. Returns `None` if the color is fully transparent.
+fn pdf_color(color: &BackendColor) -> Option<String> {
+    if color.alpha <= 0.0 {
+        return None;
+    }
+
+    let (r, g, b) = color.rgb;
+    Some(format!("{:.3} {:.3} {:.3}", r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0))
+}
+
+/// A stream object with the dictionary entries `dict`
+fn pdf_stream(dict: &str, data: &[u8]) -> Vec<u8> {
+    let mut stream = format!("<< {}/Length {} >>\nstream\n", dict, data.len()).into_bytes();
+    stream.extend_from_slice(data);
+    stream.extend_from_slice(b"\nendstream");
+    stream
+}
+
+/// A PDF string in the WinAnsi encoding. The characters that cannot be encoded are replaced by `?`.
+fn pdf_string(text: &str) -> String {
+    let mut string = String::with_capacity(text.len() + 2);
+    string.push('(');
+
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => { string.push('\\'); string.push(c); },
+            ' '..='~' => string.push(c),
+            '\u{A0}'..='\u{FF}' => string.push_str(&format!("\\{:03o}", c as u32)),
+            _ => string.push('?'),
+        }
+    }
+
+    string.push(')');
+    string
+}
+
+/// The standard PDF font that matches the text style, and its average character width relative to the font size
+fn pdf_font<T: BackendTextStyle>(style: &T) -> (&'static str, f64) {
+    use plotters_backend::{FontFamily::*, FontStyle::*};
+
+    let (fonts, char_width) = match style.family() {
+        Serif => (["Times-Roman", "Times-Bold", "Times-Italic"], 0.48),
+        Monospace => (["Courier", "Courier-Bold", "Courier-Oblique"], 0.6),
+        SansSerif | Name(_) => (["Helvetica", "Helvetica-Bold", "Helvetica-Oblique"], 0.52),
+    };
+
+    let font = match style.style() {
+        Normal => fonts[0],
+        Bold => fonts[1],
+        Italic | Oblique => fonts[2],
+    };
+
+    (font, char_width)
+}
+
+/// Rotate the offset `(x, y)` like the text transform of plotters
+fn rotate<T: BackendTextStyle>(style: &T, (x, y): (f64, f64)) -> (f64, f64) {
+    use plotters_backend::FontTransform::*;
+
+    match style.transform() {
+        None => (x, y),
+        Rotate90 => (-y, x),
+        Rotate180 => (-x, -y),
+        Rotate270 => (y, -x),
+    }
+}
+
+impl<'a> DrawingBackend for &'a PdfBackend {
+    type ErrorType = PlottersError;
+
+    fn get_size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        Ok(())
+    }
+
+    fn draw_pixel(
+        &mut self,
+        point: (i32, i32),
+        color: BackendColor
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        if let Some(color) = pdf_color(&color) {
+            self.push(&format!("{} rg {} {} 1 1 re f\n", color, point.0, point.1));
+        }
+
+        Ok(())
+    }
+
+    fn draw_line<S: BackendStyle>(
+        &mut self,
+        from: (i32, i32),
+        to: (i32, i32),
+        style: &S
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        if let Some(color) = pdf_color(&style.color()) {
+            self.push(&format!("{} RG {} w {} {} m {} {} l S\n", color, style.stroke_width(), from.0, from.1, to.0, to.1));
+        }
+
+        Ok(())
+    }
+
+    fn draw_rect<S: BackendStyle>(
+        &mut self,
+        upper_left: (i32, i32),
+        bottom_right: (i32, i32),
+        style: &S,
+        fill: bool
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let color = match pdf_color(&style.color()) {
+            Some(color) => color,
+            None => { return Ok(()); }
+        };
+
+        let (x, y) = upper_left;
+        let width = (bottom_right.0 - x).max(0);
+        let height = (bottom_right.1 - y).max(0);
+
+        match fill {
+            true => self.push(&format!("{} rg {} {} {} {} re f\n", color, x, y, width, height)),
+            false => self.push(&format!("{} RG {} w {} {} {} {} re S\n", color, style.stroke_width(), x, y, width, height)),
+        }
+
+        Ok(())
+    }
+
+    fn draw_path<S: BackendStyle, I: IntoIterator<Item = (i32, i32)>>(
+        &mut self,
+        path: I,
+        style: &S
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let color = match pdf_color(&style.color()) {
+            Some(color) => color,
+            None => { return Ok(()); }
+        };
+
+        let mut operators = format!("{} RG {} w", color, style.stroke_width());
+        for (i, (x, y)) in path.into_iter().enumerate() {
+            let op = if i == 0 { "m" } else { "l" };
+            operators.push_str(&format!(" {} {} {}", x, y, op));
+        }
+
+        operators.push_str(" S\n");
+        self.push(&operators);
+
+        Ok(())
+    }
+
+    fn draw_circle<S: BackendStyle>(
+        &mut self,
+        center: (i32, i32),
+        radius: u32,
+        style: &S,
+        fill: bool
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let color = match pdf_color(&style.color()) {
+            Some(color) => color,
+            None => { return Ok(()); }
+        };
+
+        let (x, y) = (center.0 as f64, center.1 as f64);
+        let r = radius as f64;
+        let k = r * CIRCLE_KAPPA;
+
+        let mut operators = match fill {
+            true => format!("{} rg", color),
+            false => format!("{} RG {} w", color, style.stroke_width()),
+        };
+
+        operators.push_str(&format!(" {:.2} {:.2} m", x + r, y));
+        operators.push_str(&format!(" {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c", x + r, y + k, x + k, y + r, x, y + r));
+        operators.push_str(&format!(" {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c", x - k, y + r, x - r, y + k, x - r, y));
+        operators.push_str(&format!(" {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c", x - r, y - k, x - k, y - r, x, y - r));
+        operators.push_str(&format!(" {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c", x + k, y - r, x + r, y - k, x + r, y));
+        operators.push_str(if fill { " f\n" } else { " s\n" });
+
+        self.push(&operators);
+
+        Ok(())
+    }
+
+    fn fill_polygon<S: BackendStyle, I: IntoIterator<Item = BackendCoord>>(
+        &mut self,
+        vert: I,
+        style: &S
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let color = match pdf_color(&style.color()) {
+            Some(color) => color,
+            None => { return Ok(()); }
+        };
+
+        let mut operators = format!("{} rg", color);
+        for (i, (x, y)) in vert.into_iter().enumerate() {
+            let op = if i == 0 { "m" } else { "l" };
+            operators.push_str(&format!(" {} {} {}", x, y, op));
+        }
+
+        operators.push_str(" h f\n");
+        self.push(&operators);
+
+        Ok(())
+    }
+
+    fn draw_text<TStyle: BackendTextStyle>(
+        &mut self,
+        text: &str,
+        style: &TStyle,
+        pos: (i32, i32)
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        use plotters::style::text_anchor::{HPos, VPos};
+
+        let color = match pdf_color(&style.color()) {
+            Some(color) => color,
+            None => { return Ok(()); }
+        };
+
+        let (font, char_width) = pdf_font(style);
+        let font = self.font_index(font);
+
+        let size = style.size();
+        let text_width = text.chars().count() as f64 * size * char_width;
+
+        let dx = match style.anchor().h_pos {
+            HPos::Left => 0.0,
+            HPos::Right => -text_width,
+            HPos::Center => -text_width / 2.0,
+        };
+        let dy = match style.anchor().v_pos {
+            VPos::Top => 0.0,
+            VPos::Center => -size / 2.0,
+            VPos::Bottom => -size,
+        };
+
+        // The text space has its y axis pointing up. The page y axis is flipped, so the text y axis is the rotated (0, -1).
+        let (a, b) = rotate(style, (1.0, 0.0));
+        let (c, d) = rotate(style, (0.0, -1.0));
+        let (ox, oy) = rotate(style, (dx, dy + size * FONT_ASCENT));
+        let (e, f) = (pos.0 as f64 + ox, pos.1 as f64 + oy);
+
+        self.push(&format!(
+            "BT {} rg /F{} {:.2} Tf {} {} {} {} {:.2} {:.2} Tm {} Tj ET\n",
+            color, font, size, a, b, c, d, e, f, pdf_string(text)
+        ));
+
+        Ok(())
+    }
+
+    fn estimate_text_size<TStyle: BackendTextStyle>(
+        &self,
+        text: &str,
+        style: &TStyle
+    ) -> Result<(u32, u32), DrawingErrorKind<Self::ErrorType>> {
+        let (_, char_width) = pdf_font(style);
+        let size = style.size();
+        let width = text.chars().count() as f64 * size * char_width;
+
+        Ok((width as u32, size as u32))
+    }
+
+    fn blit_bitmap<'b>(
+        &mut self,
+        pos: (i32, i32),
+        (iw, ih): (u32, u32),
+        src: &'b [u8]
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let length = (iw * ih * 3) as usize;
+        if iw == 0 || ih == 0 || src.len() < length {
+            return Ok(());
+        }
+
+        let index = {
+            let mut content = self.content.borrow_mut();
+            content.images.push((iw, ih, src[..length].to_vec()));
+            content.images.len() - 1
+        };
+
+        // The image is drawn in the unit square. The first row of pixels is at the top of the square.
+        self.push(&format!("q {} 0 0 -{} {} {} cm /Im{} Do Q\n", iw, ih, pos.0, pos.1 + ih as i32, index));
+
+        Ok(())
+    }
+
+}
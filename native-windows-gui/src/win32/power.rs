@@ -0,0 +1,79 @@
+/*!
+    Prevent the system from going to sleep or turning off the display while the application is busy.
+*/
+use winapi::um::winnt::{EXECUTION_STATE, ES_CONTINUOUS, ES_SYSTEM_REQUIRED, ES_DISPLAY_REQUIRED, ES_AWAYMODE_REQUIRED};
+use winapi::um::winbase::SetThreadExecutionState;
+use crate::NwgError;
+use std::marker::PhantomData;
+
+
+/// What a `DisplayKeepAwake` keeps awake
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum KeepAwakeMode {
+    /// The system does not go to sleep, but the display can still turn off. Useful for downloads or long computations.
+    System,
+
+    /// The system does not go to sleep and the display stays on. The screen saver does not start. Useful for media players and presentations.
+    Display,
+}
+
+/**
+    A guard that prevents the system from sleeping (and optionally the display from turning off) as long as it lives.
+    The previous state is restored when the guard is dropped.
+
+    The state is set for the thread that created the guard, so a guard cannot be sent to another thread.
+    Guards can be nested, as long as they are dropped in the reverse order of their creation.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn play_video() -> Result<(), nwg::NwgError> {
+        let _awake = nwg::DisplayKeepAwake::new(nwg::KeepAwakeMode::Display)?;
+
+        // ... play the video. The display stays on until `_awake` is dropped.
+
+        Ok(())
+    }
+    ```
+*/
+pub struct DisplayKeepAwake {
+    mode: KeepAwakeMode,
+    previous: EXECUTION_STATE,
+    _thread: PhantomData<*const ()>,
+}
+
+impl DisplayKeepAwake {
+
+    /// Keep the system awake until the guard is dropped. Returns an error if the state could not be changed.
+    pub fn new(mode: KeepAwakeMode) -> Result<DisplayKeepAwake, NwgError> {
+        let flags = match mode {
+            KeepAwakeMode::System => ES_SYSTEM_REQUIRED,
+            KeepAwakeMode::Display => ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED,
+        };
+
+        let previous = unsafe { SetThreadExecutionState(ES_CONTINUOUS | flags) };
+        if previous == 0 {
+            return Err(NwgError::initialization("Failed to set the thread execution state"));
+        }
+
+        Ok(DisplayKeepAwake { mode, previous, _thread: PhantomData })
+    }
+
+    /// Return what the guard keeps awake
+    pub fn mode(&self) -> KeepAwakeMode {
+        self.mode
+    }
+
+}
+
+impl Drop for DisplayKeepAwake {
+    fn drop(&mut self) {
+        // Only continuous requirements are still active. The other flags of the previous state only reset the idle timers once.
+        let previous = match self.previous & ES_CONTINUOUS {
+            0 => 0,
+            _ => self.previous & (ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED | ES_AWAYMODE_REQUIRED),
+        };
+
+        unsafe { SetThreadExecutionState(ES_CONTINUOUS | previous); }
+    }
+}
@@ -1,4 +1,4 @@
-use winapi::shared::windef::{HFONT, HBITMAP};
+use winapi::shared::windef::{HFONT, HBITMAP, HWND};
 use winapi::ctypes::c_int;
 use winapi::um::winnt::HANDLE;
 
@@ -40,6 +40,7 @@ pub unsafe fn build_font(
     weight: u32,
     style: [bool; 3],
     family_name: Option<&str>,
+    window: HWND,
 ) -> Result<HFONT, NwgError> 
 {  
     use winapi::um::wingdi::{DEFAULT_CHARSET, OUT_DEFAULT_PRECIS, CLIP_DEFAULT_PRECIS, CLEARTYPE_QUALITY, VARIABLE_PITCH};
@@ -56,7 +57,7 @@ pub unsafe fn build_font(
         family_name_ptr = ptr::null();
     }
 
-    let (size, _) = super::high_dpi::logical_to_physical(size as i32, 0);
+    let (size, _) = super::high_dpi::logical_to_physical_for(window, size as i32, 0);
 
     let handle = CreateFontW(
         size as c_int,            // nHeight
@@ -333,6 +334,304 @@ pub unsafe fn icon_from_memory(_src: &[u8], _strict: bool, _size: Option<(u32, u
     unimplemented!("Loading icons from memory require the \"image-decoder\" feature");
 }
 
+/**
+    Create a 32 bits top-down DIB section from RGBA pixels. `data` must hold `width * height * 4` bytes.
+    If `premultiply` is true, the color channels are multiplied by the alpha value (required by `AlphaBlend` and the static controls).
+    Icons expect straight alpha.
+*/
+pub unsafe fn bitmap_from_rgba(width: u32, height: u32, data: &[u8], premultiply: bool) -> Result<HBITMAP, NwgError> {
+    use winapi::um::wingdi::{CreateDIBSection, DeleteObject, BITMAPINFO, BITMAPINFOHEADER, DIB_RGB_COLORS, BI_RGB, RGBQUAD};
+    use winapi::shared::{ntdef::LONG, minwindef::DWORD};
+    use winapi::um::winuser::{GetDC, ReleaseDC};
+
+    let expected_len = (width as usize) * (height as usize) * 4;
+    if width == 0 || height == 0 || data.len() != expected_len {
+        let msg = format!("Invalid RGBA source. Expected {} bytes for a {}x{} image, got {} bytes.", expected_len, width, height, data.len());
+        return Err(NwgError::resource_create(msg));
+    }
+
+    let header = BITMAPINFOHEADER {
+        biSize: mem::size_of::<BITMAPINFOHEADER>() as DWORD,
+        biWidth: width as LONG, biHeight: -(height as LONG),
+        biPlanes: 1, biBitCount: 32, biCompression: BI_RGB,
+        biSizeImage: expected_len as u32,
+        biXPelsPerMeter: 0, biYPelsPerMeter: 0,
+        biClrUsed: 0, biClrImportant: 0
+    };
+
+    let quad = RGBQUAD { rgbBlue: 0, rgbGreen: 0, rgbRed: 0, rgbReserved: 0 };
+    let bitmap_info = BITMAPINFO {
+        bmiHeader: header,
+        bmiColors: [quad],
+    };
+
+    let mut bits = ptr::null_mut();
+    let screen_dc = GetDC(ptr::null_mut());
+    let bitmap = CreateDIBSection(screen_dc, &bitmap_info, DIB_RGB_COLORS, &mut bits, ptr::null_mut(), 0);
+    ReleaseDC(ptr::null_mut(), screen_dc);
+
+    if bitmap.is_null() || bits.is_null() {
+        if !bitmap.is_null() {
+            DeleteObject(bitmap as _);
+        }
+
+        return Err(NwgError::resource_create("CreateDIBSection failed"));
+    }
+
+    // RGBA -> BGRA
+    let dst = ::std::slice::from_raw_parts_mut(bits as *mut u8, expected_len);
+    for (d, s) in dst.chunks_exact_mut(4).zip(data.chunks_exact(4)) {
+        let a = s[3] as u32;
+        let (r, g, b) = match premultiply {
+            true => ((s[0] as u32 * a / 255) as u8, (s[1] as u32 * a / 255) as u8, (s[2] as u32 * a / 255) as u8),
+            false => (s[0], s[1], s[2])
+        };
+
+        d[0] = b; d[1] = g; d[2] = r; d[3] = s[3];
+    }
+
+    Ok(bitmap)
+}
+
+/**
+    Read the pixels of a bitmap as RGBA. Returns `(width, height, pixels)`.
+    If `premultiplied` is true, the pixels are converted back to straight alpha. Icons already use straight alpha.
+    Bitmaps without an alpha channel are returned as fully opaque.
+*/
+pub unsafe fn bitmap_to_rgba(handle: HBITMAP, premultiplied: bool) -> Result<(u32, u32, Vec<u8>), NwgError> {
+    use winapi::um::wingdi::{GetObjectW, GetDIBits, BITMAP, BITMAPINFO, BITMAPINFOHEADER, DIB_RGB_COLORS, BI_RGB, RGBQUAD};
+    use winapi::shared::{ntdef::LONG, minwindef::DWORD};
+    use winapi::um::winuser::{GetDC, ReleaseDC};
+
+    let mut bitmap: BITMAP = mem::zeroed();
+    let size = mem::size_of::<BITMAP>() as c_int;
+    if GetObjectW(handle as _, size, &mut bitmap as *mut BITMAP as _) == 0 {
+        return Err(NwgError::resource_create("Handle is not a valid bitmap"));
+    }
+
+    let (width, height) = (bitmap.bmWidth as u32, bitmap.bmHeight.abs() as u32);
+    let len = (width as usize) * (height as usize) * 4;
+
+    let header = BITMAPINFOHEADER {
+        biSize: mem::size_of::<BITMAPINFOHEADER>() as DWORD,
+        biWidth: width as LONG, biHeight: -(height as LONG),
+        biPlanes: 1, biBitCount: 32, biCompression: BI_RGB,
+        biSizeImage: 0,
+        biXPelsPerMeter: 0, biYPelsPerMeter: 0,
+        biClrUsed: 0, biClrImportant: 0
+    };
+
+    let quad = RGBQUAD { rgbBlue: 0, rgbGreen: 0, rgbRed: 0, rgbReserved: 0 };
+    let mut bitmap_info = BITMAPINFO {
+        bmiHeader: header,
+        bmiColors: [quad],
+    };
+
+    let mut pixels: Vec<u8> = vec![0; len];
+    let screen_dc = GetDC(ptr::null_mut());
+    let lines = GetDIBits(screen_dc, handle, 0, height, pixels.as_mut_ptr() as _, &mut bitmap_info, DIB_RGB_COLORS);
+    ReleaseDC(ptr::null_mut(), screen_dc);
+
+    if lines == 0 {
+        return Err(NwgError::resource_create("GetDIBits failed"));
+    }
+
+    // BGRA -> RGBA
+    let has_alpha = bitmap.bmBitsPixel == 32 && pixels.chunks_exact(4).any(|p| p[3] != 0);
+    for p in pixels.chunks_exact_mut(4) {
+        let (b, g, r, a) = (p[0] as u32, p[1] as u32, p[2] as u32, p[3] as u32);
+        match (has_alpha, premultiplied, a) {
+            (false, _, _) => { p[0] = r as u8; p[2] = b as u8; p[3] = 255; },
+            (true, false, _) => { p[0] = r as u8; p[2] = b as u8; },
+            (true, true, 0) => { p[0] = 0; p[1] = 0; p[2] = 0; },
+            (true, true, a) => {
+                p[0] = (r * 255 / a).min(255) as u8;
+                p[1] = (g * 255 / a).min(255) as u8;
+                p[2] = (b * 255 / a).min(255) as u8;
+            }
+        }
+    }
+
+    Ok((width, height, pixels))
+}
+
+/**
+    Create an icon from RGBA pixels. If `hotspot` is defined, a cursor is created instead with its hotspot at the specified position.
+*/
+pub unsafe fn icon_from_rgba(width: u32, height: u32, data: &[u8], hotspot: Option<(u32, u32)>) -> Result<HANDLE, NwgError> {
+    use winapi::um::wingdi::{CreateBitmap, DeleteObject};
+    use winapi::um::winuser::{CreateIconIndirect, ICONINFO};
+
+    let color = bitmap_from_rgba(width, height, data, false)?;
+
+    // The mask is ignored when the color bitmap has an alpha channel, but it must still be defined.
+    // A zeroed mask keeps every pixel opaque on the displays that use it. Its rows are aligned on 16 bits.
+    let mask_bits = vec![0u8; (((width as usize) + 15) / 16) * 2 * (height as usize)];
+    let mask = CreateBitmap(width as c_int, height as c_int, 1, 1, mask_bits.as_ptr() as _);
+    if mask.is_null() {
+        DeleteObject(color as _);
+        return Err(NwgError::resource_create("Failed to create the icon mask"));
+    }
+
+    let (x, y) = hotspot.unwrap_or((0, 0));
+    let mut icon_info = ICONINFO {
+        fIcon: hotspot.is_none() as _,
+        xHotspot: x,
+        yHotspot: y,
+        hbmMask: mask,
+        hbmColor: color
+    };
+
+    let icon = CreateIconIndirect(&mut icon_info);
+
+    // CreateIconIndirect copies the bitmaps
+    DeleteObject(color as _);
+    DeleteObject(mask as _);
+
+    match icon.is_null() {
+        true => Err(NwgError::resource_create("Failed to create icon from RGBA source")),
+        false => Ok(icon as _)
+    }
+}
+
+/**
+    Read the pixels of an icon as RGBA. Returns `(width, height, pixels)`.
+    Icons without an alpha channel use their mask to compute the transparency.
+*/
+pub unsafe fn icon_to_rgba(handle: HANDLE) -> Result<(u32, u32, Vec<u8>), NwgError> {
+    use winapi::um::wingdi::DeleteObject;
+    use winapi::um::winuser::{GetIconInfo, ICONINFO};
+
+    let mut info: ICONINFO = mem::zeroed();
+    if GetIconInfo(handle as _, &mut info) == 0 {
+        return Err(NwgError::resource_create("Handle is not a valid icon"));
+    }
+
+    let result = match info.hbmColor.is_null() {
+        true => Err(NwgError::resource_create("Monochrome icons are not supported")),
+        false => bitmap_to_rgba(info.hbmColor, false).and_then(|(width, height, mut pixels)| {
+            let has_alpha = pixels.chunks_exact(4).any(|p| p[3] != 255);
+            if !has_alpha {
+                // In the mask, white pixels are transparent
+                let (_, _, mask) = bitmap_to_rgba(info.hbmMask, false)?;
+                for (p, m) in pixels.chunks_exact_mut(4).zip(mask.chunks_exact(4)) {
+                    p[3] = 255 - m[0];
+                }
+            }
+
+            Ok((width, height, pixels))
+        })
+    };
+
+    DeleteObject(info.hbmColor as _);
+    DeleteObject(info.hbmMask as _);
+
+    result
+}
+
+/**
+    Draw a badge (a rounded label, ex: an unread count) in the bottom right corner of RGBA pixels.
+    `background` and `foreground` are RGB colors. The badge covers about 60% of the image height.
+*/
+pub unsafe fn draw_badge(width: u32, height: u32, pixels: &mut [u8], text: &str, background: [u8; 3], foreground: [u8; 3]) -> Result<(), NwgError> {
+    use winapi::um::wingdi::{CreateCompatibleDC, CreateDIBSection, CreateFontW, SelectObject, DeleteObject, DeleteDC, SetTextColor, SetBkMode, GetTextExtentPoint32W,
+        BITMAPINFO, BITMAPINFOHEADER, DIB_RGB_COLORS, BI_RGB, RGBQUAD, RGB, TRANSPARENT, FW_BOLD, DEFAULT_CHARSET, OUT_DEFAULT_PRECIS, CLIP_DEFAULT_PRECIS,
+        ANTIALIASED_QUALITY, DEFAULT_PITCH};
+    use winapi::um::winuser::{DrawTextW, DT_CENTER, DT_VCENTER, DT_SINGLELINE, DT_NOPREFIX};
+    use winapi::shared::{ntdef::LONG, minwindef::DWORD, windef::{SIZE, RECT}};
+
+    if pixels.len() != (width as usize) * (height as usize) * 4 {
+        return Err(NwgError::resource_create("Invalid RGBA source"));
+    }
+
+    let badge_h = i32::max((height as f32 * 0.6).round() as i32, 6);
+    let text_utf16 = to_utf16(text);
+    let text_len = (text_utf16.len() - 1) as c_int;
+
+    // Render the text as white on black. The green channel is then used as the text coverage.
+    let dc = CreateCompatibleDC(ptr::null_mut());
+    if dc.is_null() {
+        return Err(NwgError::resource_create("Failed to create the badge device context"));
+    }
+
+    let face = to_utf16("Segoe UI");
+    let font = CreateFontW(-(badge_h * 3 / 4), 0, 0, 0, FW_BOLD, 0, 0, 0, DEFAULT_CHARSET, OUT_DEFAULT_PRECIS, CLIP_DEFAULT_PRECIS, ANTIALIASED_QUALITY, DEFAULT_PITCH, face.as_ptr());
+    let old_font = SelectObject(dc, font as _);
+
+    let mut text_size: SIZE = mem::zeroed();
+    GetTextExtentPoint32W(dc, text_utf16.as_ptr(), text_len, &mut text_size);
+
+    let badge_w = i32::min(i32::max(badge_h, text_size.cx + badge_h / 2), width as i32);
+    let badge_h = i32::min(badge_h, height as i32);
+
+    let header = BITMAPINFOHEADER {
+        biSize: mem::size_of::<BITMAPINFOHEADER>() as DWORD,
+        biWidth: badge_w as LONG, biHeight: -(badge_h as LONG),
+        biPlanes: 1, biBitCount: 32, biCompression: BI_RGB,
+        biSizeImage: 0,
+        biXPelsPerMeter: 0, biYPelsPerMeter: 0,
+        biClrUsed: 0, biClrImportant: 0
+    };
+
+    let quad = RGBQUAD { rgbBlue: 0, rgbGreen: 0, rgbRed: 0, rgbReserved: 0 };
+    let bitmap_info = BITMAPINFO { bmiHeader: header, bmiColors: [quad] };
+
+    let mut bits = ptr::null_mut();
+    let bitmap = CreateDIBSection(dc, &bitmap_info, DIB_RGB_COLORS, &mut bits, ptr::null_mut(), 0);
+    if bitmap.is_null() || bits.is_null() {
+        SelectObject(dc, old_font);
+        DeleteObject(font as _);
+        DeleteDC(dc);
+        return Err(NwgError::resource_create("CreateDIBSection failed"));
+    }
+
+    let old_bitmap = SelectObject(dc, bitmap as _);
+    SetTextColor(dc, RGB(255, 255, 255));
+    SetBkMode(dc, TRANSPARENT as _);
+
+    let mut rect = RECT { left: 0, top: 0, right: badge_w, bottom: badge_h };
+    DrawTextW(dc, text_utf16.as_ptr(), text_len, &mut rect, DT_CENTER | DT_VCENTER | DT_SINGLELINE | DT_NOPREFIX);
+
+    let text_pixels = ::std::slice::from_raw_parts(bits as *const u8, (badge_w * badge_h * 4) as usize);
+
+    // Composite the badge shape (a capsule) and the text over the icon
+    let radius = badge_h as f32 / 2.0;
+    let (origin_x, origin_y) = (width as i32 - badge_w, height as i32 - badge_h);
+    for y in 0..badge_h {
+        for x in 0..badge_w {
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+            let cx = px.max(radius).min(badge_w as f32 - radius);
+            let distance = ((px - cx).powi(2) + (py - radius).powi(2)).sqrt();
+            let shape = (radius - distance + 0.5).max(0.0).min(1.0);
+            if shape == 0.0 {
+                continue;
+            }
+
+            let coverage = text_pixels[((y * badge_w + x) * 4 + 1) as usize] as f32 / 255.0;
+            let index = (((origin_y + y) * width as i32 + (origin_x + x)) * 4) as usize;
+            let dst = &mut pixels[index..index+4];
+
+            let dst_alpha = dst[3] as f32 / 255.0;
+            let out_alpha = shape + dst_alpha * (1.0 - shape);
+            for c in 0..3 {
+                let badge_color = background[c] as f32 * (1.0 - coverage) + foreground[c] as f32 * coverage;
+                let color = (badge_color * shape + dst[c] as f32 * dst_alpha * (1.0 - shape)) / out_alpha;
+                dst[c] = color.round().max(0.0).min(255.0) as u8;
+            }
+
+            dst[3] = (out_alpha * 255.0).round() as u8;
+        }
+    }
+
+    SelectObject(dc, old_bitmap);
+    SelectObject(dc, old_font);
+    DeleteObject(bitmap as _);
+    DeleteObject(font as _);
+    DeleteDC(dc);
+
+    Ok(())
+}
+
 //
 // File dialog low level methods
 //
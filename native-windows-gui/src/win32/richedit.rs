@@ -3,7 +3,8 @@
 use winapi::um::winuser::WM_USER;
 use winapi::um::wingdi::{LF_FACESIZE, RGB};
 use winapi::shared::{
-    minwindef::{UINT, DWORD, WORD, BYTE},
+    basetsd::DWORD_PTR,
+    minwindef::{UINT, DWORD, WORD, BYTE, LPBYTE},
     ntdef::{LONG, SHORT, LCID},
     windef::{HWND, COLORREF}
 };
@@ -11,7 +12,7 @@ use crate::win32::window_helper as wh;
 use crate::win32::base_helper::{to_utf16, from_utf16};
 use crate::controls::{CharFormat, ParaFormat, CharEffects, UnderlineType, ParaNumbering,
 ParaNumberingStyle, ParaAlignment, ParaLineSpacing};
-use std::{mem, ptr};
+use std::{mem, ptr, io};
 use std::convert::TryFrom;
 
 pub const EM_SETBKGNDCOLOR: u32 = WM_USER + 67;
@@ -20,10 +21,14 @@ const EM_GETCHARFORMAT: u32 = WM_USER + 58;
 const EM_GETPARAFORMAT: u32 = WM_USER + 61;
 const EM_SETCHARFORMAT: u32 = WM_USER + 68;
 const EM_SETPARAFORMAT: u32 = WM_USER + 71;
+const EM_STREAMIN: u32 = WM_USER + 73;
+const EM_STREAMOUT: u32 = WM_USER + 74;
 const SCF_SELECTION: u32 = 1;
 
 const MAX_TAB_STOPS: usize = 32;
 
+pub const SF_RTF: u32 = 0x0002;
+
 const CFM_EFFECTS: u32 = 0x001 | 0x002 | 0x004 | 0x008 | 0x010 | 0x020 | 0x40000000;
 const CFM_SIZE: u32 = 0x80000000;
 const CFM_OFFSET: u32 = 0x10000000;
@@ -123,6 +128,27 @@ struct PARAFORMAT {
     wBorders: WORD
 }
 
+/// Like all the structures of richedit.h, `EDITSTREAM` is packed on 4 bytes
+#[repr(C, packed(4))]
+#[allow(non_snake_case)]
+struct EDITSTREAM {
+    dwCookie: DWORD_PTR,
+    dwError: DWORD,
+    pfnCallback: unsafe extern "system" fn(DWORD_PTR, LPBYTE, LONG, *mut LONG) -> DWORD,
+}
+
+/// The cookie of `EM_STREAMIN`
+struct StreamReader<'a> {
+    reader: &'a mut dyn io::Read,
+    error: Option<io::Error>,
+}
+
+/// The cookie of `EM_STREAMOUT`
+struct StreamWriter<'a> {
+    writer: &'a mut dyn io::Write,
+    error: Option<io::Error>,
+}
+
 
 pub(crate) fn set_char_format(handle: HWND, fmt: &CharFormat) {
 
@@ -453,3 +479,86 @@ pub(crate) fn para_format(handle: HWND) -> ParaFormat {
     }
 }
 
+
+/**
+    Replace the content of the control with the data of `reader`, in `format` (ex: `SF_RTF`).
+    The errors of `reader` are returned. If the control rejects the data, an `InvalidData` error is returned.
+*/
+pub(crate) fn stream_in(handle: HWND, format: u32, reader: &mut dyn io::Read) -> io::Result<()> {
+    let mut cookie = StreamReader { reader, error: None };
+    let mut stream = EDITSTREAM {
+        dwCookie: &mut cookie as *mut StreamReader as DWORD_PTR,
+        dwError: 0,
+        pfnCallback: stream_in_callback,
+    };
+
+    wh::send_message(handle, EM_STREAMIN, format as _, &mut stream as *mut EDITSTREAM as _);
+
+    let error = stream.dwError;
+    match (cookie.error, error) {
+        (Some(e), _) => Err(e),
+        (None, 0) => Ok(()),
+        (None, code) => Err(io::Error::new(io::ErrorKind::InvalidData, format!("The rich edit control could not read the data (error {})", code as i32)))
+    }
+}
+
+/**
+    Write the content of the control to `writer`, in `format` (ex: `SF_RTF`).
+    The errors of `writer` are returned.
+*/
+pub(crate) fn stream_out(handle: HWND, format: u32, writer: &mut dyn io::Write) -> io::Result<()> {
+    let mut cookie = StreamWriter { writer, error: None };
+    let mut stream = EDITSTREAM {
+        dwCookie: &mut cookie as *mut StreamWriter as DWORD_PTR,
+        dwError: 0,
+        pfnCallback: stream_out_callback,
+    };
+
+    wh::send_message(handle, EM_STREAMOUT, format as _, &mut stream as *mut EDITSTREAM as _);
+
+    let error = stream.dwError;
+    match (cookie.error, error) {
+        (Some(e), _) => Err(e),
+        (None, 0) => cookie.writer.flush(),
+        (None, code) => Err(io::Error::new(io::ErrorKind::Other, format!("The rich edit control could not write the data (error {})", code as i32)))
+    }
+}
+
+/// Fill the buffer of the control with the data of the reader. Reading 0 bytes ends the stream. A non-zero value stops the stream on error.
+unsafe extern "system" fn stream_in_callback(cookie: DWORD_PTR, buffer: LPBYTE, size: LONG, read: *mut LONG) -> DWORD {
+    let cookie = &mut *(cookie as *mut StreamReader);
+    let buffer = std::slice::from_raw_parts_mut(buffer, size.max(0) as usize);
+
+    loop {
+        match cookie.reader.read(buffer) {
+            Ok(count) => {
+                *read = count as LONG;
+                return 0;
+            },
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => {
+                *read = 0;
+                cookie.error = Some(e);
+                return 1;
+            }
+        }
+    }
+}
+
+/// Write the buffer of the control to the writer. A non-zero value stops the stream on error.
+unsafe extern "system" fn stream_out_callback(cookie: DWORD_PTR, buffer: LPBYTE, size: LONG, written: *mut LONG) -> DWORD {
+    let cookie = &mut *(cookie as *mut StreamWriter);
+    let buffer = std::slice::from_raw_parts(buffer, size.max(0) as usize);
+
+    match cookie.writer.write_all(buffer) {
+        Ok(()) => {
+            *written = size;
+            0
+        },
+        Err(e) => {
+            *written = 0;
+            cookie.error = Some(e);
+            1
+        }
+    }
+}
@@ -0,0 +1,171 @@
+/*!
+    Mouse wheel routing and smooth scrolling.
+
+    By default Windows sends the mouse wheel messages to the control that has the keyboard focus. With `set_wheel_routing`,
+    the event loop of native-windows-gui sends them to the control under the mouse instead, like most multi-pane applications.
+
+    The smooth scrolling of the list views, the tree views and the text boxes (see the `smooth_scrolling` builder parameter of these controls)
+    spreads the lines scrolled by a wheel notch over a few frames. The scrolling slows down when it approaches the target, and
+    the wheel notches received while the control is scrolling add to the remaining distance, so fast wheel moves scroll further.
+*/
+use winapi::shared::windef::HWND;
+use winapi::shared::minwindef::{UINT, WPARAM};
+use winapi::shared::basetsd::UINT_PTR;
+use winapi::um::winuser::MSG;
+use crate::controls::ControlHandle;
+use crate::{RawEventHandler, bind_raw_event_handler_inner};
+use super::window_helper as wh;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The id of the timer that scrolls the control. Unlikely to be used by the system controls.
+const SCROLL_TIMER_ID: UINT_PTR = 0x4E57_5343;
+
+/// Time between two scroll steps, in milliseconds
+const SCROLL_STEP_TIME: UINT = 16;
+
+/// Fraction of the remaining distance scrolled at each step
+const SCROLL_FRICTION: f32 = 0.3;
+
+/// Lines scrolled by a wheel notch when the system scrolls one page per notch
+const PAGE_LINES: f32 = 10.0;
+
+static WHEEL_ROUTING: AtomicBool = AtomicBool::new(false);
+
+
+/**
+    If `enable` is true, the mouse wheel messages are sent to the control under the mouse instead of the control that has the keyboard focus.
+    The controls that do not scroll pass the message to their parent, so the wheel scrolls the closest scrollable control under the mouse.
+
+    Only the windows of the current thread are targeted, and only the messages dispatched by the event loop of native-windows-gui
+    (`dispatch_thread_events` and `dispatch_thread_events_with_callback`) are routed. Disabled by default.
+*/
+pub fn set_wheel_routing(enable: bool) {
+    WHEEL_ROUTING.store(enable, Ordering::SeqCst);
+}
+
+/// Returns true if the mouse wheel messages are sent to the control under the mouse. See `set_wheel_routing`.
+pub fn wheel_routing() -> bool {
+    WHEEL_ROUTING.load(Ordering::SeqCst)
+}
+
+/// Change the target of a mouse wheel message to the enabled control under the mouse, if the wheel routing is enabled
+pub(crate) unsafe fn route_wheel(msg: &mut MSG) {
+    use winapi::um::winuser::{WindowFromPoint, GetWindowThreadProcessId, IsWindowEnabled, WM_MOUSEWHEEL, WM_MOUSEHWHEEL};
+    use winapi::um::processthreadsapi::GetCurrentThreadId;
+    use winapi::shared::windef::POINT;
+    use winapi::shared::minwindef::{LOWORD, HIWORD};
+
+    if (msg.message != WM_MOUSEWHEEL && msg.message != WM_MOUSEHWHEEL) || !wheel_routing() {
+        return;
+    }
+
+    // The position of the wheel messages is in screen coordinates
+    let point = POINT { x: LOWORD(msg.lParam as u32) as i16 as i32, y: HIWORD(msg.lParam as u32) as i16 as i32 };
+    let target = WindowFromPoint(point);
+    if target.is_null() || target == msg.hwnd || IsWindowEnabled(target) == 0 {
+        return;
+    }
+
+    if GetWindowThreadProcessId(target, std::ptr::null_mut()) == GetCurrentThreadId() {
+        msg.hwnd = target;
+    }
+}
+
+/**
+    Scroll `handle` smoothly when the mouse wheel is used. The control is scrolled line by line with `WM_VSCROLL`.
+    The wheel messages with the Control or the Shift key are left to the control (ex: zoom).
+*/
+pub(crate) fn bind_smooth_scrolling(handle: &ControlHandle, handler_id: UINT_PTR) -> RawEventHandler {
+    use winapi::um::winuser::{SetTimer, KillTimer, WM_MOUSEWHEEL, WM_TIMER, WM_DESTROY, MK_CONTROL, MK_SHIFT, GET_WHEEL_DELTA_WPARAM, GET_KEYSTATE_WPARAM, WHEEL_DELTA};
+
+    // The lines left to scroll. Negative values scroll up.
+    let remaining: Cell<f32> = Cell::new(0.0);
+
+    // The fraction of a line scrolled but not applied yet
+    let fraction: Cell<f32> = Cell::new(0.0);
+
+    bind_raw_event_handler_inner(handle, handler_id, move |hwnd, msg, w, _| {
+        match msg {
+            WM_MOUSEWHEEL => {
+                if GET_KEYSTATE_WPARAM(w) as usize & (MK_CONTROL | MK_SHIFT) != 0 {
+                    return None;
+                }
+
+                let notches = -(GET_WHEEL_DELTA_WPARAM(w) as f32) / (WHEEL_DELTA as f32);
+                let lines = notches * wheel_lines();
+
+                // Changing the direction stops the current scrolling
+                let current = remaining.get();
+                let start = match current == 0.0 || current.signum() == lines.signum() {
+                    true => current,
+                    false => { fraction.set(0.0); 0.0 }
+                };
+
+                remaining.set(start + lines);
+                unsafe { SetTimer(hwnd, SCROLL_TIMER_ID, SCROLL_STEP_TIME, None); }
+
+                Some(0)
+            },
+            WM_TIMER if w == SCROLL_TIMER_ID => {
+                let left = remaining.get();
+                let step = match left.abs() <= 1.0 {
+                    true => left,
+                    false => left * SCROLL_FRICTION
+                };
+
+                remaining.set(left - step);
+
+                let lines = fraction.get() + step;
+                let whole = lines.trunc();
+                fraction.set(lines - whole);
+                scroll_lines(hwnd, whole as i32);
+
+                if remaining.get() == 0.0 {
+                    fraction.set(0.0);
+                    unsafe { KillTimer(hwnd, SCROLL_TIMER_ID); }
+                }
+
+                Some(0)
+            },
+            WM_DESTROY => {
+                unsafe { KillTimer(hwnd, SCROLL_TIMER_ID); }
+                None
+            },
+            _ => None
+        }
+    }).unwrap()
+}
+
+/// The number of lines scrolled by a wheel notch, from the system settings
+fn wheel_lines() -> f32 {
+    use winapi::um::winuser::{SystemParametersInfoW, SPI_GETWHEELSCROLLLINES, WHEEL_PAGESCROLL};
+
+    let mut lines: UINT = 3;
+    unsafe { SystemParametersInfoW(SPI_GETWHEELSCROLLLINES, 0, &mut lines as *mut UINT as _, 0); }
+
+    match lines {
+        WHEEL_PAGESCROLL => PAGE_LINES,
+        lines => lines as f32
+    }
+}
+
+/// Scroll `hwnd` by `lines` lines. Negative values scroll up.
+fn scroll_lines(hwnd: HWND, lines: i32) {
+    use winapi::um::winuser::{WM_VSCROLL, SB_LINEUP, SB_LINEDOWN, SB_ENDSCROLL};
+
+    if lines == 0 {
+        return;
+    }
+
+    let code = match lines < 0 {
+        true => SB_LINEUP,
+        false => SB_LINEDOWN
+    };
+
+    for _ in 0..lines.abs() {
+        wh::send_message(hwnd, WM_VSCROLL, code as WPARAM, 0);
+    }
+
+    wh::send_message(hwnd, WM_VSCROLL, SB_ENDSCROLL as WPARAM, 0);
+}
@@ -0,0 +1,647 @@
+/*!
+    Keyboard shortcuts of a window. A `ShortcutMap` collects the shortcuts of the actions of a window, refuses the shortcuts
+    that are already used, lets the user remap the shortcuts and generates the "keyboard shortcuts" help of the application.
+
+    The shortcuts are matched by the event loop of native-windows-gui (`dispatch_thread_events` and `dispatch_thread_events_with_callback`)
+    before the key is sent to the focused control, so they work in every control of the window.
+*/
+use winapi::shared::windef::HWND;
+use winapi::shared::minwindef::LPARAM;
+use winapi::um::winuser::MSG;
+use crate::controls::ControlHandle;
+use crate::NwgError;
+use super::base_helper::to_utf16;
+use super::window_helper as wh;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+
+/// The window property that points to the shortcuts of a window
+const SHORTCUTS_PROP: &'static str = "NWG_SHORTCUTS";
+
+/// The first line of the saved shortcuts. Other headers are refused.
+const SHORTCUTS_HEADER: &'static str = "nwg-shortcuts 1";
+
+/// The names of the keys that are not a letter or a digit
+const KEY_NAMES: &'static [(u32, &'static str)] = &[
+    (0x08, "Backspace"), (0x09, "Tab"), (0x0D, "Enter"), (0x13, "Pause"), (0x1B, "Esc"), (0x20, "Space"),
+    (0x21, "PageUp"), (0x22, "PageDown"), (0x23, "End"), (0x24, "Home"),
+    (0x25, "Left"), (0x26, "Up"), (0x27, "Right"), (0x28, "Down"),
+    (0x2C, "PrintScreen"), (0x2D, "Insert"), (0x2E, "Delete"), (0x5D, "Menu"),
+    (0x6A, "Num*"), (0x6B, "Num+"), (0x6D, "Num-"), (0x6E, "Num."), (0x6F, "Num/"),
+    (0xBA, ";"), (0xBB, "="), (0xBC, ","), (0xBD, "-"), (0xBE, "."), (0xBF, "/"), (0xC0, "`"),
+    (0xDB, "["), (0xDC, "\\"), (0xDD, "]"), (0xDE, "'"),
+];
+
+
+bitflags! {
+    /**
+        The modifier keys of a shortcut
+
+        * NONE:  No modifier
+        * CTRL:  The Control key
+        * ALT:   The Alt key
+        * SHIFT: The Shift key
+        * WIN:   The Windows key
+    */
+    pub struct ShortcutModifiers: u32 {
+        const NONE = 0;
+        const CTRL = 0b0001;
+        const ALT = 0b0010;
+        const SHIFT = 0b0100;
+        const WIN = 0b1000;
+    }
+}
+
+/**
+    A key and its modifiers. The key is a virtual key code (see `nwg::keys`).
+
+    A shortcut can be written and parsed as text, in the form used by the menus of Windows: `"Ctrl+Shift+S"`, `"F5"`, `"Alt+Enter"`.
+    The key names are in english.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    let save = nwg::Shortcut::new(nwg::ShortcutModifiers::CTRL, nwg::keys::_S);
+    assert_eq!(nwg::Shortcut::parse("ctrl+s"), Some(save));
+    assert_eq!(save.to_string(), "Ctrl+S");
+    ```
+*/
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    pub modifiers: ShortcutModifiers,
+    pub key: u32,
+}
+
+impl Shortcut {
+
+    pub fn new(modifiers: ShortcutModifiers, key: u32) -> Shortcut {
+        Shortcut { modifiers, key }
+    }
+
+    /// Parse a shortcut written like `"Ctrl+Shift+S"`. The names are not case sensitive. Returns `None` if the text is not a valid shortcut.
+    pub fn parse(text: &str) -> Option<Shortcut> {
+        let mut modifiers = ShortcutModifiers::NONE;
+
+        // The key can end with a "+" (ex: "Ctrl+Num+")
+        let text = text.trim();
+        let search = text.strip_suffix('+').unwrap_or(text);
+        let (mods, key) = match search.rfind('+') {
+            Some(i) => (&text[..i], &text[i + 1..]),
+            None => ("", text)
+        };
+
+        for name in mods.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match name.trim().to_lowercase().as_str() {
+                "ctrl" | "control" => ShortcutModifiers::CTRL,
+                "alt" => ShortcutModifiers::ALT,
+                "shift" => ShortcutModifiers::SHIFT,
+                "win" | "windows" => ShortcutModifiers::WIN,
+                _ => { return None; }
+            };
+        }
+
+        parse_key(key.trim()).map(|key| Shortcut { modifiers, key })
+    }
+
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = [
+            (ShortcutModifiers::CTRL, "Ctrl+"),
+            (ShortcutModifiers::ALT, "Alt+"),
+            (ShortcutModifiers::SHIFT, "Shift+"),
+            (ShortcutModifiers::WIN, "Win+"),
+        ];
+
+        for &(modifier, name) in names.iter() {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+
+        f.write_str(&key_name(self.key))
+    }
+}
+
+/// An action of a `ShortcutMap`
+struct ShortcutEntry {
+    action: String,
+    description: String,
+    default: Option<Shortcut>,
+    shortcut: Option<Shortcut>,
+
+    /// The id of the system hotkey, for the actions registered with `register_hotkey`
+    hotkey: Option<i32>,
+}
+
+/// State shared between the map and the event loop
+struct ShortcutMapInner {
+    window: HWND,
+    entries: RefCell<Vec<ShortcutEntry>>,
+    next_hotkey: Cell<i32>,
+}
+
+/**
+    The keyboard shortcuts of a window. Each shortcut runs an action identified by a name. When the user presses a shortcut,
+    the window raises `OnShortcut` and the name of the action can be read with `EventData::on_shortcut`.
+
+    A shortcut can only be used by one action of a map: registering a shortcut that is already used returns an error that
+    names the other action. Actions can be registered without a shortcut, so the user can give them one later.
+
+    The shortcuts can be remapped by the user with `set_shortcut`. `save` returns the shortcuts that differ from the defaults,
+    and `load` restores them. `help_table` and `help_text` list the shortcuts with the description of their action.
+
+    The actions registered with `register_hotkey` are system-wide: they are raised even if the window is not active.
+
+    Only one map can be used by a window. The map must be kept alive as long as its shortcuts are used.
+
+    Requires the `shortcuts` feature.
+
+    ```rust
+    use native_windows_gui as nwg;
+
+    fn build_shortcuts(window: &nwg::Window) -> Result<nwg::ShortcutMap, nwg::NwgError> {
+        let map = nwg::ShortcutMap::new(window);
+        map.register("save", "Save the document", nwg::Shortcut::parse("Ctrl+S"))?;
+        map.register("find", "Find text", nwg::Shortcut::parse("Ctrl+F"))?;
+        map.register_hotkey("show", "Show the application", nwg::Shortcut::parse("Ctrl+Alt+N").unwrap())?;
+        Ok(map)
+    }
+
+    fn handle_event(evt: nwg::Event, evt_data: &nwg::EventData) {
+        if evt == nwg::Event::OnShortcut {
+            match evt_data.on_shortcut() {
+                "save" => { /* ... */ },
+                "find" => { /* ... */ },
+                _ => {}
+            }
+        }
+    }
+    ```
+*/
+pub struct ShortcutMap {
+    inner: Box<ShortcutMapInner>,
+}
+
+impl ShortcutMap {
+
+    /**
+        Create an empty shortcut map for `window`. The shortcuts are matched when the focus is in the window or in one of its controls.
+
+        This function will panic if `window` is not a window-like control.
+    */
+    pub fn new<C: Into<ControlHandle>>(window: C) -> ShortcutMap {
+        use winapi::um::winuser::{GetAncestor, SetPropW, GA_ROOT};
+
+        let handle = window.into().hwnd().expect("Shortcuts can only be added to a window-like control");
+        let window = unsafe { GetAncestor(handle, GA_ROOT) };
+
+        let inner = Box::new(ShortcutMapInner {
+            window,
+            entries: RefCell::new(Vec::new()),
+            next_hotkey: Cell::new(1),
+        });
+
+        let prop = to_utf16(SHORTCUTS_PROP);
+        unsafe { SetPropW(window, prop.as_ptr(), &*inner as *const ShortcutMapInner as _); }
+
+        ShortcutMap { inner }
+    }
+
+    /// Create a shortcut map that is not bound to a window. Used by the tests.
+    #[cfg(test)]
+    pub(crate) fn detached() -> ShortcutMap {
+        let inner = Box::new(ShortcutMapInner {
+            window: std::ptr::null_mut(),
+            entries: RefCell::new(Vec::new()),
+            next_hotkey: Cell::new(1),
+        });
+
+        ShortcutMap { inner }
+    }
+
+    /**
+        Register an action with its default shortcut. `description` is displayed by the help of the shortcuts.
+
+        Returns an error if the action is already registered, if the name of the action contains a tab or a line break,
+        or if the shortcut is already used by another action.
+    */
+    pub fn register(&self, action: &str, description: &str, shortcut: Option<Shortcut>) -> Result<(), NwgError> {
+        self.check_new_action(action, shortcut)?;
+
+        self.inner.entries.borrow_mut().push(ShortcutEntry {
+            action: action.to_string(),
+            description: description.to_string(),
+            default: shortcut,
+            shortcut,
+            hotkey: None,
+        });
+
+        Ok(())
+    }
+
+    /**
+        Register an action with a system-wide shortcut. The action is raised even if the window is not active.
+        The window of the map can be a `MessageWindow`, so the hotkeys of a background application do not need a visible window.
+
+        Returns the same errors as `register`, and an error if the shortcut is already used by another application.
+    */
+    pub fn register_hotkey(&self, action: &str, description: &str, shortcut: Shortcut) -> Result<(), NwgError> {
+        self.check_new_action(action, Some(shortcut))?;
+
+        let id = self.inner.next_hotkey.get();
+        unsafe { register_system_hotkey(self.inner.window, id, shortcut)?; }
+        self.inner.next_hotkey.set(id + 1);
+
+        self.inner.entries.borrow_mut().push(ShortcutEntry {
+            action: action.to_string(),
+            description: description.to_string(),
+            default: Some(shortcut),
+            shortcut: Some(shortcut),
+            hotkey: Some(id),
+        });
+
+        Ok(())
+    }
+
+    /// Remove an action from the map. Does nothing if the action is not registered.
+    pub fn unregister(&self, action: &str) {
+        use winapi::um::winuser::UnregisterHotKey;
+
+        let mut entries = self.inner.entries.borrow_mut();
+        if let Some(index) = entries.iter().position(|e| e.action == action) {
+            let entry = entries.remove(index);
+            if let (Some(id), Some(_)) = (entry.hotkey, entry.shortcut) {
+                unsafe { UnregisterHotKey(self.inner.window, id); }
+            }
+        }
+    }
+
+    /// Returns the names of the registered actions, in the registration order
+    pub fn actions(&self) -> Vec<String> {
+        self.inner.entries.borrow().iter().map(|e| e.action.clone()).collect()
+    }
+
+    /// Returns the shortcut of an action. Returns `None` if the action has no shortcut or is not registered.
+    pub fn shortcut(&self, action: &str) -> Option<Shortcut> {
+        self.inner.entries.borrow().iter()
+            .find(|e| e.action == action)
+            .and_then(|e| e.shortcut)
+    }
+
+    /// Returns the action that uses `shortcut`, if any
+    pub fn action(&self, shortcut: Shortcut) -> Option<String> {
+        self.inner.entries.borrow().iter()
+            .find(|e| e.shortcut == Some(shortcut))
+            .map(|e| e.action.clone())
+    }
+
+    /**
+        Change the shortcut of an action. `None` removes the shortcut. Use this function to let the user remap the shortcuts,
+        and `action` to warn the user before a shortcut is taken from another action.
+
+        Returns an error if the action is not registered, if the shortcut is used by another action, or if the system
+        refuses the new shortcut of a hotkey. The shortcut of the action is not changed on error.
+    */
+    pub fn set_shortcut(&self, action: &str, shortcut: Option<Shortcut>) -> Result<(), NwgError> {
+        use winapi::um::winuser::UnregisterHotKey;
+
+        if let Some(other) = shortcut.and_then(|s| self.action(s)) {
+            if other != action {
+                return Err(conflict_error(shortcut.unwrap(), &other));
+            }
+        }
+
+        let mut entries = self.inner.entries.borrow_mut();
+        let entry = match entries.iter_mut().find(|e| e.action == action) {
+            Some(entry) => entry,
+            None => { return Err(NwgError::shortcut(format!("The action {:?} is not registered", action))); }
+        };
+
+        if entry.shortcut == shortcut {
+            return Ok(());
+        }
+
+        if let Some(id) = entry.hotkey {
+            unsafe {
+                if entry.shortcut.is_some() {
+                    UnregisterHotKey(self.inner.window, id);
+                }
+
+                if let Some(new) = shortcut {
+                    if let Err(e) = register_system_hotkey(self.inner.window, id, new) {
+                        // Restore the old hotkey
+                        if let Some(old) = entry.shortcut {
+                            register_system_hotkey(self.inner.window, id, old).ok();
+                        }
+
+                        return Err(e);
+                    }
+                }
+            }
+        }
+
+        entry.shortcut = shortcut;
+
+        Ok(())
+    }
+
+    /// Restore the default shortcut of an action. Returns an error if the default shortcut was given to another action.
+    pub fn reset(&self, action: &str) -> Result<(), NwgError> {
+        let default = self.inner.entries.borrow().iter().find(|e| e.action == action).map(|e| e.default);
+        match default {
+            Some(default) => self.set_shortcut(action, default),
+            None => Err(NwgError::shortcut(format!("The action {:?} is not registered", action)))
+        }
+    }
+
+    /// Restore the default shortcuts of all the actions
+    pub fn reset_all(&self) {
+        let actions = self.actions();
+
+        // Remove the shortcuts first so that two actions can swap back their defaults
+        for action in actions.iter() {
+            self.set_shortcut(action, None).ok();
+        }
+
+        for action in actions.iter() {
+            self.reset(action).ok();
+        }
+    }
+
+    /**
+        Returns the shortcuts that differ from the defaults, as text. Save the text in the settings of the application
+        and restore it with `load` after the actions are registered.
+    */
+    pub fn save(&self) -> String {
+        let mut out = String::from(SHORTCUTS_HEADER);
+        out.push('\n');
+
+        for entry in self.inner.entries.borrow().iter().filter(|e| e.shortcut != e.default) {
+            let shortcut = entry.shortcut.map(|s| s.to_string()).unwrap_or_default();
+            out.push_str(&format!("{}\t{}\n", entry.action, shortcut));
+        }
+
+        out
+    }
+
+    /**
+        Restore the shortcuts saved by `save`. The actions that are not registered are ignored, so the saved shortcuts
+        of an older version of the application can be loaded.
+
+        Returns an error if the text is not valid or if two actions would use the same shortcut. The shortcuts are not changed on error.
+    */
+    pub fn load(&self, text: &str) -> Result<(), NwgError> {
+        let mut lines = text.lines();
+        if lines.next().map(|h| h.trim()) != Some(SHORTCUTS_HEADER) {
+            return Err(NwgError::shortcut("The text is not a saved shortcut map"));
+        }
+
+        let mut changes: Vec<(String, Option<Shortcut>)> = Vec::new();
+        for line in lines.filter(|l| !l.trim().is_empty()) {
+            let (action, shortcut) = match line.find('\t') {
+                Some(i) => (&line[..i], line[i + 1..].trim()),
+                None => { return Err(NwgError::shortcut(format!("Invalid line {:?}", line))); }
+            };
+
+            let shortcut = match shortcut.is_empty() {
+                true => None,
+                false => Some(Shortcut::parse(shortcut).ok_or_else(|| NwgError::shortcut(format!("Invalid shortcut {:?}", shortcut)))?)
+            };
+
+            changes.push((action.to_string(), shortcut));
+        }
+
+        // Validate the final shortcuts before changing the map
+        let entries = self.inner.entries.borrow();
+        let result: Vec<(String, Option<Shortcut>)> = entries.iter()
+            .map(|e| {
+                let change = changes.iter().find(|(action, _)| *action == e.action);
+                (e.action.clone(), change.map(|(_, s)| *s).unwrap_or(e.default))
+            })
+            .collect();
+        drop(entries);
+
+        for (i, (action, shortcut)) in result.iter().enumerate() {
+            if let Some(shortcut) = shortcut {
+                if let Some((other, _)) = result[..i].iter().find(|(_, s)| *s == Some(*shortcut)) {
+                    return Err(NwgError::shortcut(format!("{} is used by the actions {:?} and {:?}", shortcut, other, action)));
+                }
+            }
+        }
+
+        // The system can still refuse a hotkey. In this case, the old shortcuts are restored.
+        let saved: Vec<(String, Option<Shortcut>)> = self.inner.entries.borrow().iter()
+            .map(|e| (e.action.clone(), e.shortcut))
+            .collect();
+
+        if let Err(e) = self.apply_shortcuts(&result) {
+            self.apply_shortcuts(&saved).ok();
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the description and the shortcut text of the actions that have a shortcut, in the registration order
+    pub fn help_table(&self) -> Vec<(String, String)> {
+        self.inner.entries.borrow().iter()
+            .filter_map(|e| e.shortcut.map(|s| (e.description.clone(), s.to_string())))
+            .collect()
+    }
+
+    /**
+        Returns the help of the shortcuts as text: one line per action with a shortcut, with the descriptions aligned in a column.
+        Display it in a monospace font, or use `help_table` to fill a list view.
+    */
+    pub fn help_text(&self) -> String {
+        let table = self.help_table();
+        let width = table.iter().map(|(_, s)| s.chars().count()).max().unwrap_or(0);
+
+        let mut out = String::new();
+        for (description, shortcut) in table {
+            out.push_str(&format!("{:width$}  {}\n", shortcut, description, width = width));
+        }
+
+        out
+    }
+
+    /// Replace the shortcuts of the actions. The shortcuts are removed first so that the actions can swap their shortcuts.
+    fn apply_shortcuts(&self, shortcuts: &[(String, Option<Shortcut>)]) -> Result<(), NwgError> {
+        for (action, _) in shortcuts.iter() {
+            self.set_shortcut(action, None).ok();
+        }
+
+        for (action, shortcut) in shortcuts.iter() {
+            self.set_shortcut(action, *shortcut)?;
+        }
+
+        Ok(())
+    }
+
+    fn check_new_action(&self, action: &str, shortcut: Option<Shortcut>) -> Result<(), NwgError> {
+        if action.contains(|c| c == '\t' || c == '\n' || c == '\r') {
+            return Err(NwgError::shortcut(format!("The action {:?} contains a tab or a line break", action)));
+        }
+
+        if self.inner.entries.borrow().iter().any(|e| e.action == action) {
+            return Err(NwgError::shortcut(format!("The action {:?} is already registered", action)));
+        }
+
+        if let Some(other) = shortcut.and_then(|s| self.action(s)) {
+            return Err(conflict_error(shortcut.unwrap(), &other));
+        }
+
+        Ok(())
+    }
+
+}
+
+impl Drop for ShortcutMap {
+    fn drop(&mut self) {
+        use winapi::um::winuser::{GetPropW, RemovePropW, UnregisterHotKey};
+
+        for entry in self.inner.entries.borrow().iter() {
+            if let (Some(id), Some(_)) = (entry.hotkey, entry.shortcut) {
+                unsafe { UnregisterHotKey(self.inner.window, id); }
+            }
+        }
+
+        // Another map might have replaced this one
+        let prop = to_utf16(SHORTCUTS_PROP);
+        unsafe {
+            if GetPropW(self.inner.window, prop.as_ptr()) as *const ShortcutMapInner == &*self.inner as *const ShortcutMapInner {
+                RemovePropW(self.inner.window, prop.as_ptr());
+            }
+        }
+    }
+}
+
+/**
+    Raise `OnShortcut` if `msg` is a key press that matches a shortcut of the window of the focused control, or a hotkey
+    of a shortcut map. Returns true if the message was a shortcut and must not be dispatched.
+*/
+pub(crate) unsafe fn translate_shortcut(msg: &MSG) -> bool {
+    use winapi::um::winuser::{GetAncestor, GetPropW, GetKeyState, IsWindowEnabled, GA_ROOT, WM_KEYDOWN, WM_SYSKEYDOWN, WM_HOTKEY,
+      VK_CONTROL, VK_MENU, VK_SHIFT, VK_LWIN, VK_RWIN};
+
+    let window = match msg.message {
+        WM_KEYDOWN | WM_SYSKEYDOWN => GetAncestor(msg.hwnd, GA_ROOT),
+        WM_HOTKEY => msg.hwnd,
+        _ => { return false; }
+    };
+
+    if window.is_null() {
+        return false;
+    }
+
+    let prop = to_utf16(SHORTCUTS_PROP);
+    let map = GetPropW(window, prop.as_ptr()) as *const ShortcutMapInner;
+    if map.is_null() {
+        return false;
+    }
+
+    let action = match msg.message {
+        WM_HOTKEY => (*map).entries.borrow().iter()
+            .find(|e| e.hotkey == Some(msg.wParam as i32))
+            .map(|e| e.action.clone()),
+        _ => {
+            // A disabled window has a modal window over it
+            let key = msg.wParam as i32;
+            if IsWindowEnabled(window) == 0 || [VK_CONTROL, VK_MENU, VK_SHIFT, VK_LWIN, VK_RWIN].contains(&key) {
+                return false;
+            }
+
+            let pressed = |key: i32| GetKeyState(key) < 0;
+            let mut modifiers = ShortcutModifiers::NONE;
+            modifiers.set(ShortcutModifiers::CTRL, pressed(VK_CONTROL));
+            modifiers.set(ShortcutModifiers::ALT, pressed(VK_MENU));
+            modifiers.set(ShortcutModifiers::SHIFT, pressed(VK_SHIFT));
+            modifiers.set(ShortcutModifiers::WIN, pressed(VK_LWIN) || pressed(VK_RWIN));
+
+            let shortcut = Shortcut { modifiers, key: key as u32 };
+            (*map).entries.borrow().iter()
+                .find(|e| e.hotkey.is_none() && e.shortcut == Some(shortcut))
+                .map(|e| e.action.clone())
+        }
+    };
+
+    match action {
+        Some(action) => {
+            wh::send_message(window, wh::NWG_SHORTCUT, 0, &action as *const String as LPARAM);
+            true
+        },
+        None => msg.message == WM_HOTKEY
+    }
+}
+
+unsafe fn register_system_hotkey(window: HWND, id: i32, shortcut: Shortcut) -> Result<(), NwgError> {
+    use winapi::um::winuser::{RegisterHotKey, MOD_CONTROL, MOD_ALT, MOD_SHIFT, MOD_WIN, MOD_NOREPEAT};
+
+    let mut modifiers = MOD_NOREPEAT;
+    if shortcut.modifiers.contains(ShortcutModifiers::CTRL) { modifiers |= MOD_CONTROL; }
+    if shortcut.modifiers.contains(ShortcutModifiers::ALT) { modifiers |= MOD_ALT; }
+    if shortcut.modifiers.contains(ShortcutModifiers::SHIFT) { modifiers |= MOD_SHIFT; }
+    if shortcut.modifiers.contains(ShortcutModifiers::WIN) { modifiers |= MOD_WIN; }
+
+    match RegisterHotKey(window, id, modifiers as u32, shortcut.key) {
+        0 => Err(NwgError::shortcut(format!("{} is already used by another application", shortcut))),
+        _ => Ok(())
+    }
+}
+
+fn conflict_error(shortcut: Shortcut, other: &str) -> NwgError {
+    NwgError::shortcut(format!("{} is already used by the action {:?}", shortcut, other))
+}
+
+fn key_name(key: u32) -> String {
+    match key {
+        0x30..=0x39 | 0x41..=0x5A => (key as u8 as char).to_string(),
+        0x60..=0x69 => format!("Num{}", key - 0x60),
+        0x70..=0x87 => format!("F{}", key - 0x70 + 1),
+        key => match KEY_NAMES.iter().find(|(k, _)| *k == key) {
+            Some((_, name)) => name.to_string(),
+            None => format!("0x{:02X}", key)
+        }
+    }
+}
+
+fn parse_key(name: &str) -> Option<u32> {
+    let upper = name.to_uppercase();
+    let mut chars = upper.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c @ '0'..='9'), None) | (Some(c @ 'A'..='Z'), None) => { return Some(c as u32); },
+        _ => {}
+    }
+
+    if let Some(n) = upper.strip_prefix("NUM").and_then(|n| n.parse::<u32>().ok()) {
+        return if n <= 9 { Some(0x60 + n) } else { None };
+    }
+
+    if let Some(n) = upper.strip_prefix('F').and_then(|n| n.parse::<u32>().ok()) {
+        return if n >= 1 && n <= 24 { Some(0x70 + n - 1) } else { None };
+    }
+
+    if let Some(code) = upper.strip_prefix("0X").and_then(|n| u32::from_str_radix(n, 16).ok()) {
+        return Some(code);
+    }
+
+    let alias = match upper.as_str() {
+        "RETURN" => "ENTER",
+        "ESCAPE" => "ESC",
+        "DEL" => "DELETE",
+        "INS" => "INSERT",
+        "PGUP" => "PAGEUP",
+        "PGDN" => "PAGEDOWN",
+        "PLUS" => "=",
+        "+" => "NUM+",
+        "MINUS" => "-",
+        name => name
+    };
+
+    KEY_NAMES.iter()
+        .find(|(_, n)| n.to_uppercase() == alias)
+        .map(|(k, _)| *k)
+}
@@ -3,7 +3,7 @@
 */
 use winapi::shared::minwindef::{UINT, WPARAM, LPARAM, LRESULT};
 use winapi::shared::windef::{HWND};
-use super::window::build_sysclass;
+use super::window::{build_sysclass, nwg_class};
 use crate::{NwgError};
 use std::{ptr};
 
@@ -20,7 +20,7 @@ pub fn create_tab_classes() -> Result<(), NwgError>  {
     if hmod.is_null() { return Err(NwgError::initialization("GetModuleHandleW failed")); }
 
     unsafe { 
-        build_sysclass(hmod, TAB_CLASS_ID, Some(tab_proc), Some(COLOR_BTNFACE as HBRUSH), None)?;
+        build_sysclass(hmod, nwg_class(TAB_CLASS_ID), Some(tab_proc), Some(COLOR_BTNFACE as HBRUSH), None)?;
     }
 
     Ok(())
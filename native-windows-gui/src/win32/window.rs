@@ -9,7 +9,13 @@ use winapi::shared::basetsd::{DWORD_PTR, UINT_PTR};
 use winapi::um::winuser::{WNDPROC, NMHDR, IDCANCEL, IDOK};
 use winapi::um::commctrl::{NMTTDISPINFOW, SUBCLASSPROC};
 use super::base_helper::{CUSTOM_ID_BEGIN, to_utf16};
-use super::window_helper::{NOTICE_MESSAGE, NWG_INIT, NWG_TRAY, NWG_TIMER_TICK, NWG_TIMER_STOP};
+use super::window_helper::{NOTICE_MESSAGE, NWG_INIT, NWG_TRAY, NWG_TIMER_TICK, NWG_TIMER_STOP, NWG_DATE_RANGE_CHANGED, NWG_WINDOW_TRAY,
+  NWG_NETWORK_CHANGE, NWG_ELEVATION_RESULT, NWG_REGISTRY_CHANGE,
+  NWG_PROCESS_EXITED, NWG_PRESENT_READY, NWG_COLOR_PICKED, NWG_FILE_PICKED,
+  NWG_FILE_OPERATION_DONE, NWG_USER_IDLE, NWG_USER_RETURNED,
+  NWG_RUBBER_BAND, NWG_VIEWPORT_CHANGED, NWG_RULER_GUIDE, NWG_DESIGN_SURFACE,
+  NWG_LIST_VIEW_HEADER, NWG_ANIMATION_END, NWG_CHECK_GROUP_CHANGED, NWG_LIST_VIEW_END,
+  NWG_SEARCH_PREFIX, NWG_DRAG_DROP, NWG_PRINT_PREVIEW_PAGE, NWG_MOUSE_ENTER, NWG_SHORTCUT};
 use super::high_dpi;
 use crate::controls::ControlHandle;
 use crate::{Event, EventData, NwgError};
@@ -17,12 +23,28 @@ use std::{ptr, mem};
 use std::rc::Rc;
 use std::ffi::OsString;
 use std::os::windows::prelude::OsStringExt;
-use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::collections::HashMap;
+
+extern "system" {
+    fn ShutdownBlockReasonCreate(hwnd: HWND, reason: *const u16) -> BOOL;
+    fn ShutdownBlockReasonDestroy(hwnd: HWND) -> BOOL;
+}
 
 
 static TIMER_ID: AtomicU32 = AtomicU32::new(1); 
 static NOTICE_ID: AtomicU32 = AtomicU32::new(1); 
 static EVENT_HANDLER_ID: AtomicUsize = AtomicUsize::new(1);
+static DARK_MODE: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    /// The prefix of the window classes registered by NWG. See `InitConfig::class_prefix`.
+    static ref CLASS_PREFIX: Mutex<String> = Mutex::new(String::new());
+
+    /// The prefixed names of the window classes. The names are leaked because the controls return them as static strings.
+    static ref PREFIXED_CLASSES: Mutex<HashMap<&'static str, &'static str>> = Mutex::new(HashMap::new());
+}
 
 const NO_DATA: EventData = EventData::NoData;
 
@@ -70,6 +92,38 @@ pub unsafe fn build_timer(parent: HWND, interval: u32, stopped: bool) -> Control
     ControlHandle::Timer(parent, id)
 }
 
+#[cfg(feature = "event-recorder")]
+thread_local! {
+    /// The handlers bound with `full_bind_event_handler` and `bind_event_handler`. Used to play back recorded events.
+    static BOUND_HANDLERS: std::cell::RefCell<Vec<(HWND, std::rc::Weak<Callback>)>> = std::cell::RefCell::new(Vec::new());
+}
+
+#[cfg(feature = "event-recorder")]
+fn register_bound_handler(hwnd: HWND, callback: &Rc<Callback>) {
+    BOUND_HANDLERS.with(|handlers| {
+        let mut handlers = handlers.borrow_mut();
+        handlers.retain(|(_, h)| h.strong_count() > 0);
+        handlers.push((hwnd, Rc::downgrade(callback)));
+    });
+}
+
+#[cfg(not(feature = "event-recorder"))]
+fn register_bound_handler(_hwnd: HWND, _callback: &Rc<Callback>) {
+}
+
+/// Returns the handlers that are still bound to `window` or one of its children
+#[cfg(feature = "event-recorder")]
+pub(crate) fn bound_handlers(window: HWND) -> Vec<Rc<Callback>> {
+    use winapi::um::winuser::IsChild;
+
+    BOUND_HANDLERS.with(|handlers| {
+        handlers.borrow().iter()
+            .filter(|&&(hwnd, _)| hwnd == window || unsafe { IsChild(window, hwnd) != 0 })
+            .filter_map(|(_, h)| h.upgrade())
+            .collect()
+    })
+}
+
 /**
     Hook the window subclass with the default event dispatcher.
     The hook is applied to the window and all it's children (recursively).
@@ -122,7 +176,9 @@ pub fn full_bind_event_handler<F>(handle: &ControlHandle, f: F) -> EventHandler
 
     // The callback function must be passed to each children of the control
     // To do so, we must RC the callback
-    let callback: Rc<Callback> = Rc::new(f);
+    let f = Rc::new(f);
+    let callback: Rc<Callback> = Rc::new(move |evt, data, handle| super::modal::dispatch_event(hwnd, &f, evt, data, handle));
+    register_bound_handler(hwnd, &callback);
     let callback_box: Box<*const Callback> = Box::new(Rc::into_raw(callback));
     let callback_ptr: *mut *const Callback = Box::into_raw(callback_box);
     
@@ -167,7 +223,9 @@ pub fn bind_event_handler<F>(handle: &ControlHandle, parent_handle: &ControlHand
     let hwnd = handle.hwnd().expect("Cannot bind control with an handle of type");
     let parent_hwnd = parent_handle.hwnd().expect("Cannot bind control with an handle of type");
     
-    let callback: Rc<Callback> = Rc::new(f);
+    let f = Rc::new(f);
+    let callback: Rc<Callback> = Rc::new(move |evt, data, handle| super::modal::dispatch_event(hwnd, &f, evt, data, handle));
+    register_bound_handler(hwnd, &callback);
     let parent_callback = callback.clone();
 
     let callback_box: Box<*const Callback> = Box::new(Rc::into_raw(callback));
@@ -382,9 +440,11 @@ pub(crate) unsafe fn build_hwnd_control<'a>(
 
     let pos = pos.unwrap_or((0, 0));
     let size = size.unwrap_or((500, 500));
-    let (px, py) = high_dpi::logical_to_physical(pos.0, pos.1);
-    let (mut sx, mut sy) = high_dpi::logical_to_physical(size.0, size.1);
     let parent_handle = parent.unwrap_or(ptr::null_mut());
+
+    // The children are created with the dpi of their parent. The top level windows use the system dpi.
+    let (px, py) = high_dpi::logical_to_physical_for(parent_handle, pos.0, pos.1);
+    let (mut sx, mut sy) = high_dpi::logical_to_physical_for(parent_handle, size.0, size.1);
     let menu = ptr::null_mut();
     let lp_params = ptr::null_mut();
 
@@ -412,10 +472,47 @@ pub(crate) unsafe fn build_hwnd_control<'a>(
     if handle.is_null() {
         Err(NwgError::initialization("Window creation failed"))
     } else {
+        if parent.is_none() && DARK_MODE.load(Ordering::SeqCst) {
+            set_dark_frame(handle);
+        }
+
         Ok(ControlHandle::Hwnd(handle))
     }
 }
 
+/// Set the prefix of the window classes registered by NWG. Must be called before the classes are registered.
+pub(crate) fn set_class_prefix(prefix: &str) {
+    *CLASS_PREFIX.lock().unwrap() = prefix.to_string();
+    PREFIXED_CLASSES.lock().unwrap().clear();
+}
+
+/// Returns the name of a window class registered by NWG, with the prefix set by `InitConfig::class_prefix`
+pub(crate) fn nwg_class(name: &'static str) -> &'static str {
+    let prefix = CLASS_PREFIX.lock().unwrap();
+    if prefix.is_empty() {
+        return name;
+    }
+
+    let mut classes = PREFIXED_CLASSES.lock().unwrap();
+    *classes.entry(name).or_insert_with(|| Box::leak(format!("{}{}", prefix, name).into_boxed_str()))
+}
+
+/// If `dark` is true, the top level windows created after the call have a dark title bar and frame
+pub(crate) fn set_dark_mode(dark: bool) {
+    DARK_MODE.store(dark, Ordering::SeqCst);
+}
+
+/// Use the dark title bar and frame of Windows 10 20H1 and later. Does nothing on older systems.
+unsafe fn set_dark_frame(handle: HWND) {
+    use winapi::um::dwmapi::DwmSetWindowAttribute;
+    use winapi::shared::minwindef::TRUE;
+
+    const DWMWA_USE_IMMERSIVE_DARK_MODE: DWORD = 20;
+
+    let dark: BOOL = TRUE;
+    DwmSetWindowAttribute(handle, DWMWA_USE_IMMERSIVE_DARK_MODE, &dark as *const BOOL as _, mem::size_of::<BOOL>() as DWORD);
+}
+
 pub(crate) unsafe fn build_sysclass<'a>(
     hmod: HMODULE,
     class_name: &'a str,
@@ -465,7 +562,7 @@ pub(crate) fn init_window_class() -> Result<(), NwgError> {
         let hmod = GetModuleHandleW(ptr::null_mut());
         if hmod.is_null() { return Err(NwgError::initialization("GetModuleHandleW failed")); }
 
-        build_sysclass(hmod, "NativeWindowsGuiWindow", Some(blank_window_proc), None, None)?;
+        build_sysclass(hmod, nwg_class("NativeWindowsGuiWindow"), Some(blank_window_proc), None, None)?;
     }
     
     Ok(())
@@ -481,21 +578,21 @@ pub(crate) fn create_frame_classes() -> Result<(), NwgError> {
         let hmod = GetModuleHandleW(ptr::null_mut());
         if hmod.is_null() { return Err(NwgError::initialization("GetModuleHandleW failed")); }
 
-        build_sysclass(hmod, "NWG_FRAME", Some(blank_window_proc), None, None)?;
+        build_sysclass(hmod, nwg_class("NWG_FRAME"), Some(blank_window_proc), None, None)?;
     }
     
     Ok(())
 }
 
-#[cfg(feature = "message-window")]
-/// Create a message only window. Used with the `MessageWindow` control
+#[cfg(any(feature = "message-window", feature = "asyncio"))]
+/// Create a message only window. Used with the `MessageWindow` control and by the executor of `asyncio`
 pub(crate) fn create_message_window() -> Result<ControlHandle, NwgError> {
     use winapi::um::winuser::HWND_MESSAGE;
     use winapi::um::winuser::CreateWindowExW;
     use winapi::um::libloaderapi::GetModuleHandleW;
 
 
-    let class_name = to_utf16("NativeWindowsGuiWindow");
+    let class_name = to_utf16(nwg_class("NativeWindowsGuiWindow"));
     let window_title = vec![0];
 
     unsafe {
@@ -528,8 +625,9 @@ pub(crate) fn create_message_window() -> Result<ControlHandle, NwgError> {
     A blank system procedure used when creating new window class. Actual system event handling is done in the subclass procedure `process_events`.
 */
 unsafe extern "system" fn blank_window_proc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
-    use winapi::um::winuser::{WM_CREATE, WM_CLOSE, SW_HIDE};
-    use winapi::um::winuser::{DefWindowProcW, PostMessageW, ShowWindow};
+    use winapi::um::winuser::{WM_CREATE, WM_CLOSE, WM_DPICHANGED, SW_HIDE, SWP_NOZORDER, SWP_NOACTIVATE};
+    use winapi::um::winuser::{DefWindowProcW, PostMessageW, ShowWindow, SetWindowPos};
+    use winapi::shared::windef::RECT;
 
     let handled = match msg {
         WM_CREATE => {
@@ -540,6 +638,12 @@ unsafe extern "system" fn blank_window_proc(hwnd: HWND, msg: UINT, w: WPARAM, l:
             ShowWindow(hwnd, SW_HIDE);
             true
         },
+        WM_DPICHANGED => {
+            // Resizing the window with the suggested rect updates the layouts with the new dpi
+            let r = &*(l as *const RECT);
+            SetWindowPos(hwnd, ptr::null_mut(), r.left, r.top, r.right - r.left, r.bottom - r.top, SWP_NOZORDER | SWP_NOACTIVATE);
+            true
+        },
         _ => false
     };
 
@@ -563,7 +667,11 @@ unsafe extern "system" fn process_events(hwnd: HWND, msg: UINT, w: WPARAM, l: LP
     use winapi::um::winuser::{WM_CLOSE, WM_COMMAND, WM_MENUCOMMAND, WM_TIMER, WM_NOTIFY, WM_HSCROLL, WM_VSCROLL, WM_LBUTTONDOWN, WM_LBUTTONUP,
       WM_RBUTTONDOWN, WM_RBUTTONUP, WM_SIZE, WM_MOVE, WM_PAINT, WM_MOUSEMOVE, WM_CONTEXTMENU, WM_INITMENUPOPUP, WM_MENUSELECT, WM_EXITSIZEMOVE,
       WM_ENTERSIZEMOVE, SIZE_MAXIMIZED, SIZE_MINIMIZED, WM_KEYDOWN, WM_KEYUP, WM_CHAR, WM_MOUSEWHEEL, WM_DROPFILES, GET_WHEEL_DELTA_WPARAM,
-      WM_GETMINMAXINFO, WM_ENTERMENULOOP, WM_EXITMENULOOP, WM_SYSKEYDOWN, WM_SYSKEYUP};
+      WM_GETMINMAXINFO, WM_ENTERMENULOOP, WM_EXITMENULOOP, WM_SYSKEYDOWN, WM_SYSKEYUP, WM_MEASUREITEM, WM_DRAWITEM, MEASUREITEMSTRUCT,
+      DRAWITEMSTRUCT, ODT_MENU, WM_QUERYENDSESSION, WM_ENDSESSION, WM_POWERBROADCAST, PBT_APMSUSPEND, PBT_APMRESUMEAUTOMATIC, WM_TIMECHANGE,
+      WM_DEVICECHANGE, WM_DPICHANGED, WM_MOUSEHOVER, WM_MOUSELEAVE};
+    use winapi::shared::windef::RECT;
+    use winapi::um::dbt::{DBT_DEVICEARRIVAL, DBT_DEVICEREMOVECOMPLETE};
     use winapi::um::shellapi::{NIN_BALLOONSHOW, NIN_BALLOONHIDE, NIN_BALLOONTIMEOUT, NIN_BALLOONUSERCLICK};
     use winapi::um::winnt::WCHAR;
     use winapi::shared::minwindef::{HIWORD, LOWORD};
@@ -613,6 +721,10 @@ unsafe extern "system" fn process_events(hwnd: HWND, msg: UINT, w: WPARAM, l: LP
             callback(Event::OnMenuItemSelected, NO_DATA, handle);
         },
         WM_INITMENUPOPUP => {
+            // The high word of lparam is set for the window menu
+            if HIWORD(l as u32) == 0 {
+                callback(Event::OnMenuOpening, NO_DATA, menu_opening_handle(hwnd, w as HMENU));
+            }
             callback(Event::OnMenuOpen, NO_DATA, ControlHandle::Menu(ptr::null_mut(), w as HMENU));
         },
         WM_ENTERMENULOOP => {
@@ -652,9 +764,14 @@ unsafe extern "system" fn process_events(hwnd: HWND, msg: UINT, w: WPARAM, l: LP
                 "ComboBox" => callback(combo_commands(message), NO_DATA, handle),
                 "Static" => callback(static_commands(child_handle, message), NO_DATA, handle),
                 "ListBox" => callback(listbox_commands(message), NO_DATA, handle),
-                _ => match w as i32 {
-                    IDOK | IDCANCEL => callback(no_class_name_commands(w), NO_DATA, base_handle),
-                    _ => {}
+                _ => match super::custom_control::command_event(&class_name, message) {
+                    Some(evt) => callback(evt, NO_DATA, handle),
+                    None => match w as i32 {
+                        IDOK | IDCANCEL => if !dialog_button_command(hwnd, w, callback) {
+                            callback(no_class_name_commands(w), NO_DATA, base_handle)
+                        },
+                        _ => {}
+                    }
                 },
             }
         },
@@ -683,6 +800,10 @@ unsafe extern "system" fn process_events(hwnd: HWND, msg: UINT, w: WPARAM, l: LP
                 _ => {}
             }
         },
+        NWG_WINDOW_TRAY => match w {
+            1 => callback(Event::OnWindowMinimizedToTray, NO_DATA, base_handle),
+            _ => callback(Event::OnWindowRestoredFromTray, NO_DATA, base_handle),
+        },
         WM_SIZE => {
             match w {
                 SIZE_MAXIMIZED => callback(Event::OnWindowMaximize, NO_DATA, base_handle),
@@ -695,11 +816,11 @@ unsafe extern "system" fn process_events(hwnd: HWND, msg: UINT, w: WPARAM, l: LP
             callback(Event::OnPaint, data, base_handle)
         },
         WM_DROPFILES => {
-            let data = EventData::OnFileDrop(DropFiles { drop: w as _ });
+            let data = EventData::OnFileDrop(DropFiles { drop: w as _, keys: drop_files_keys() });
             callback(Event::OnFileDrop, data, base_handle)
         },
         WM_GETMINMAXINFO => {
-            let data = EventData::OnMinMaxInfo(MinMaxInfo { inner: l as _ });
+            let data = EventData::OnMinMaxInfo(MinMaxInfo { inner: l as _, window: hwnd });
             callback(Event::OnMinMaxInfo, data, base_handle)
         },
         WM_CHAR => callback(Event::OnChar, EventData::OnChar(char::from_u32(w as u32).unwrap_or('?')), base_handle),
@@ -709,15 +830,124 @@ unsafe extern "system" fn process_events(hwnd: HWND, msg: UINT, w: WPARAM, l: LP
         WM_MOVE => callback(Event::OnMove, NO_DATA, base_handle),
         WM_HSCROLL => callback(Event::OnHorizontalScroll, NO_DATA, ControlHandle::Hwnd(l as HWND)),
         WM_VSCROLL => callback(Event::OnVerticalScroll, NO_DATA, ControlHandle::Hwnd(l as HWND)),
-        WM_MOUSEMOVE => callback(Event::OnMouseMove, NO_DATA, base_handle), 
+        WM_MOUSEMOVE => {
+            super::mouse_tracking::mouse_moved(hwnd);
+            callback(Event::OnMouseMove, NO_DATA, base_handle)
+        },
+        NWG_MOUSE_ENTER => callback(Event::OnMouseEnter, NO_DATA, base_handle),
+        WM_MOUSEHOVER => {
+            super::mouse_tracking::mouse_hovered(hwnd);
+            callback(Event::OnMouseHover, NO_DATA, base_handle)
+        },
+        WM_MOUSELEAVE => {
+            super::mouse_tracking::mouse_left(hwnd);
+            callback(Event::OnMouseLeave, NO_DATA, base_handle)
+        },
         WM_LBUTTONUP => callback(Event::OnMousePress(MousePressEvent::MousePressLeftUp), NO_DATA,  base_handle), 
         WM_LBUTTONDOWN => callback(Event::OnMousePress(MousePressEvent::MousePressLeftDown), NO_DATA, base_handle), 
         WM_RBUTTONUP => callback(Event::OnMousePress(MousePressEvent::MousePressRightUp), NO_DATA, base_handle), 
         WM_RBUTTONDOWN => callback(Event::OnMousePress(MousePressEvent::MousePressRightDown), NO_DATA, base_handle),
         NOTICE_MESSAGE => callback(Event::OnNotice, NO_DATA, ControlHandle::Notice(hwnd, w as u32)),
         NWG_TIMER_STOP => callback(Event::OnTimerStop, NO_DATA, ControlHandle::Timer(hwnd, w as u32)),
-        NWG_TIMER_TICK => callback(Event::OnTimerTick, NO_DATA, ControlHandle::Timer(hwnd, w as u32)),
+        NWG_TIMER_TICK => animation_timer_tick(hwnd, w as u32, callback),
+        NWG_ANIMATION_END => animation_timer_end(hwnd, w as u32, callback),
+        NWG_CHECK_GROUP_CHANGED => callback(Event::OnCheckGroupChanged, NO_DATA, ControlHandle::Hwnd(l as HWND)),
         NWG_INIT => callback(Event::OnInit, NO_DATA, base_handle),
+        NWG_DATE_RANGE_CHANGED => callback(Event::OnDateRangeChanged, NO_DATA, base_handle),
+        NWG_NETWORK_CHANGE => network_change(hwnd, w as u32, l, callback),
+        NWG_ELEVATION_RESULT => {
+            let result = super::elevation::ElevationResult::from_wparam(w);
+            callback(Event::OnElevationResult, EventData::OnElevationResult(result), base_handle);
+        },
+        NWG_REGISTRY_CHANGE => callback(Event::OnRegistryChange, EventData::NoData, ControlHandle::Notice(hwnd, w as u32)),
+        NWG_PRESENT_READY => callback(Event::OnPresentReady, EventData::NoData, base_handle),
+        NWG_COLOR_PICKED => color_picked(hwnd, w as u32, l, callback),
+        NWG_FILE_PICKED => callback(Event::OnFilePicked, NO_DATA, base_handle),
+        NWG_RUBBER_BAND => rubber_band(w, callback, base_handle),
+        NWG_VIEWPORT_CHANGED => callback(Event::OnViewportChanged, NO_DATA, base_handle),
+        NWG_RULER_GUIDE => ruler_guide(w, callback, base_handle),
+        NWG_DESIGN_SURFACE => design_surface(w, l, callback, base_handle),
+        NWG_LIST_VIEW_HEADER => list_view_header(w, l, callback, base_handle),
+        NWG_LIST_VIEW_END => callback(Event::OnListViewApproachingEnd, NO_DATA, base_handle),
+        NWG_SEARCH_PREFIX => callback(Event::OnSearchPrefixChanged, NO_DATA, base_handle),
+        #[cfg(feature = "shortcuts")]
+        NWG_SHORTCUT => {
+            let action = &*(l as *const String);
+            callback(Event::OnShortcut, EventData::OnShortcut(action.clone()), base_handle);
+        },
+        NWG_DRAG_DROP => drag_drop(w, l, callback, base_handle),
+        NWG_PRINT_PREVIEW_PAGE => callback(Event::OnPrintPreviewPageChanged, NO_DATA, base_handle),
+        NWG_FILE_OPERATION_DONE => {
+            let result = crate::FileOperationResult::from_lparam(l);
+            callback(Event::OnFileOperationComplete, EventData::OnFileOperationComplete(result), ControlHandle::Notice(hwnd, w as u32));
+        },
+        NWG_PROCESS_EXITED => callback(Event::OnProcessExited, EventData::OnProcessExited(l as u32), ControlHandle::Notice(hwnd, w as u32)),
+        NWG_USER_IDLE => {
+            let elapsed = std::time::Duration::from_millis(l as u64);
+            callback(Event::OnUserIdle, EventData::OnUserIdle(elapsed), ControlHandle::Notice(hwnd, w as u32));
+        },
+        NWG_USER_RETURNED => callback(Event::OnUserReturned, NO_DATA, ControlHandle::Notice(hwnd, w as u32)),
+        WM_MEASUREITEM => {
+            let measure = l as *mut MEASUREITEMSTRUCT;
+            if (&*measure).CtlType == ODT_MENU {
+                // The parent menu of owner drawn items is stored in the item data. See `set_menu_item_owner_draw`
+                let handle = ControlHandle::MenuItem((&*measure).itemData as HMENU, (&*measure).itemID);
+                callback(Event::OnMenuItemMeasure, EventData::OnMenuItemMeasure(MenuItemMeasureData { data: measure }), handle);
+                return 1;
+            }
+        },
+        WM_DRAWITEM => {
+            let draw = l as *const DRAWITEMSTRUCT;
+            if (&*draw).CtlType == ODT_MENU {
+                let handle = ControlHandle::MenuItem((&*draw).hwndItem as HMENU, (&*draw).itemID);
+                callback(Event::OnMenuItemDraw, EventData::OnMenuItemDraw(MenuItemDrawData { data: draw }), handle);
+                return 1;
+            }
+        },
+        WM_QUERYENDSESSION => {
+            let mut block = None;
+            let reason = SessionEndReason::from_flags(l as u32);
+            let data = EventData::OnSessionEnding(SessionEndingData { reason, block: &mut block as *mut Option<String> });
+            callback(Event::OnSessionEnding, data, base_handle);
+
+            if let Some(message) = block {
+                let message = to_utf16(&message);
+                ShutdownBlockReasonCreate(hwnd, message.as_ptr());
+                return 0;
+            }
+        },
+        WM_ENDSESSION => {
+            if w != 0 {
+                let reason = SessionEndReason::from_flags(l as u32);
+                callback(Event::OnSessionEnd, EventData::OnSessionEnd(reason), base_handle);
+            }
+
+            ShutdownBlockReasonDestroy(hwnd);
+        },
+        WM_POWERBROADCAST => match w {
+            PBT_APMSUSPEND => callback(Event::OnPowerSuspend, NO_DATA, base_handle),
+            PBT_APMRESUMEAUTOMATIC => callback(Event::OnPowerResume, NO_DATA, base_handle),
+            _ => {}
+        },
+        WM_TIMECHANGE => callback(Event::OnSystemTimeChange, NO_DATA, base_handle),
+        WM_DPICHANGED => {
+            // The window is moved to the suggested rect by `blank_window_proc`
+            let rect = &*(l as *const RECT);
+            let data = DpiChangedData {
+                dpi: LOWORD(w as u32) as u32,
+                suggested_rect: [rect.left, rect.top, rect.right - rect.left, rect.bottom - rect.top],
+            };
+
+            callback(Event::OnDpiChanged, EventData::OnDpiChanged(data), base_handle);
+        },
+        WM_DEVICECHANGE if l != 0 => {
+            let data = EventData::OnDeviceChange(DeviceChangeData { data: l as _ });
+            match w {
+                DBT_DEVICEARRIVAL => callback(Event::OnDeviceArrival, data, base_handle),
+                DBT_DEVICEREMOVECOMPLETE => callback(Event::OnDeviceRemoval, data, base_handle),
+                _ => {}
+            }
+        },
         WM_CLOSE => {
             let mut should_exit = true;
             let data = EventData::OnWindowClose(WindowCloseData { data: &mut should_exit as *mut bool });
@@ -759,6 +989,14 @@ fn button_commands(m: u16) -> Event {
     }
 }
 
+fn button_notify_commands(m: u32) -> Event {
+    use winapi::um::commctrl::BCN_DROPDOWN;
+    match m {
+        BCN_DROPDOWN => Event::OnSplitDropdown,
+        _ => Event::Unknown
+    }
+}
+
 fn edit_commands(m: u16) -> Event {
     use winapi::um::winuser::{EN_CHANGE};
 
@@ -788,6 +1026,214 @@ fn datetimepick_commands(m: u32) -> Event {
     }
 }
 
+/// Sends the `NetworkListener` events
+#[cfg(feature = "network-listener")]
+fn network_change(hwnd: HWND, id: u32, change: LPARAM, callback: &Callback) {
+    use crate::controls::{NETWORK_ADDRESS_CHANGED, NETWORK_CONNECTED, NETWORK_DISCONNECTED};
+
+    let handle = ControlHandle::Notice(hwnd, id);
+    match change {
+        NETWORK_ADDRESS_CHANGED => callback(Event::OnNetworkAddressChanged, EventData::NoData, handle),
+        NETWORK_CONNECTED => callback(Event::OnNetworkConnected, EventData::NoData, handle),
+        NETWORK_DISCONNECTED => callback(Event::OnNetworkDisconnected, EventData::NoData, handle),
+        _ => {}
+    }
+}
+
+#[cfg(not(feature = "network-listener"))]
+fn network_change(_hwnd: HWND, _id: u32, _change: LPARAM, _callback: &Callback) {
+}
+
+#[cfg(feature = "eyedropper")]
+fn color_picked(hwnd: HWND, id: u32, color: LPARAM, callback: &Callback) {
+    use crate::controls::COLOR_PICK_CANCELLED;
+
+    let handle = ControlHandle::Notice(hwnd, id);
+    match color {
+        COLOR_PICK_CANCELLED => callback(Event::OnColorPickCancelled, EventData::NoData, handle),
+        c => {
+            let rgb = [(c & 0xFF) as u8, ((c >> 8) & 0xFF) as u8, ((c >> 16) & 0xFF) as u8];
+            callback(Event::OnColorPicked, EventData::OnColorPicked(rgb), handle);
+        }
+    }
+}
+
+#[cfg(not(feature = "eyedropper"))]
+fn color_picked(_hwnd: HWND, _id: u32, _color: LPARAM, _callback: &Callback) {
+}
+
+#[cfg(feature = "rubber-band")]
+fn rubber_band(step: WPARAM, callback: &Callback, handle: ControlHandle) {
+    use crate::controls::{RUBBER_BAND_SELECT, RUBBER_BAND_CANCEL};
+
+    match step {
+        RUBBER_BAND_SELECT => callback(Event::OnRubberBandSelect, EventData::NoData, handle),
+        RUBBER_BAND_CANCEL => callback(Event::OnRubberBandCancel, EventData::NoData, handle),
+        _ => callback(Event::OnRubberBandChanged, EventData::NoData, handle),
+    }
+}
+
+#[cfg(not(feature = "rubber-band"))]
+fn rubber_band(_step: WPARAM, _callback: &Callback, _handle: ControlHandle) {
+}
+
+#[cfg(feature = "animation-timer")]
+fn animation_timer_tick(hwnd: HWND, id: u32, callback: &Callback) {
+    let tick = crate::controls::animation_tick(id);
+    callback(Event::OnTimerTick, EventData::OnAnimationTick(tick), ControlHandle::Timer(hwnd, id));
+}
+
+#[cfg(not(feature = "animation-timer"))]
+fn animation_timer_tick(hwnd: HWND, id: u32, callback: &Callback) {
+    callback(Event::OnTimerTick, NO_DATA, ControlHandle::Timer(hwnd, id));
+}
+
+#[cfg(feature = "animation-timer")]
+fn animation_timer_end(hwnd: HWND, id: u32, callback: &Callback) {
+    let tick = crate::controls::animation_end(id);
+    callback(Event::OnAnimationEnd, EventData::OnAnimationTick(tick), ControlHandle::Timer(hwnd, id));
+}
+
+#[cfg(not(feature = "animation-timer"))]
+fn animation_timer_end(_hwnd: HWND, _id: u32, _callback: &Callback) {
+}
+
+#[cfg(feature = "extern-canvas")]
+fn ruler_guide(step: WPARAM, callback: &Callback, handle: ControlHandle) {
+    use crate::controls::RULER_GUIDES_CHANGED;
+
+    match step {
+        RULER_GUIDES_CHANGED => callback(Event::OnRulerGuidesChanged, EventData::NoData, handle),
+        _ => callback(Event::OnRulerGuideMoved, EventData::NoData, handle),
+    }
+}
+
+#[cfg(not(feature = "extern-canvas"))]
+fn ruler_guide(_step: WPARAM, _callback: &Callback, _handle: ControlHandle) {
+}
+
+#[cfg(feature = "design-surface")]
+fn design_surface(change: WPARAM, l: LPARAM, callback: &Callback, handle: ControlHandle) {
+    use crate::controls::DESIGN_GEOMETRY_CHANGED;
+
+    match change {
+        DESIGN_GEOMETRY_CHANGED => {
+            // The geometry is owned by the design surface, which waits for the message to be handled
+            let geometry = unsafe { &*(l as *const Vec<(ControlHandle, [i32; 4])>) };
+            callback(Event::OnDesignGeometryChanged, EventData::OnDesignGeometryChanged(geometry.clone()), handle);
+        },
+        _ => callback(Event::OnDesignSelectionChanged, EventData::NoData, handle),
+    }
+}
+
+#[cfg(not(feature = "design-surface"))]
+fn design_surface(_change: WPARAM, _l: LPARAM, _callback: &Callback, _handle: ControlHandle) {
+}
+
+#[cfg(feature = "drag-drop")]
+fn drag_drop(step: WPARAM, l: LPARAM, callback: &Callback, handle: ControlHandle) {
+    use crate::controls::{DragData, DropText, DroppedFiles, DRAG_ENTER, DRAG_OVER, DROP_FILES, DROP_TEXT};
+
+    // The data is owned by the drop target, which waits for the message to be handled
+    match step {
+        DRAG_ENTER | DRAG_OVER => {
+            let data = unsafe { &*(l as *const DragData) };
+            let event = match step == DRAG_ENTER {
+                true => Event::OnDragEnter,
+                false => Event::OnDragOver
+            };
+
+            callback(event, EventData::OnDrag(data.clone()), handle);
+        },
+        DROP_FILES => {
+            let files = unsafe { &*(l as *const DroppedFiles) };
+            callback(Event::OnFileDrop, EventData::OnFileDrop(files.to_drop_files()), handle);
+        },
+        DROP_TEXT => {
+            let text = unsafe { &*(l as *const DropText) };
+            callback(Event::OnTextDrop, EventData::OnTextDrop(text.clone()), handle);
+        },
+        _ => callback(Event::OnDragLeave, NO_DATA, handle),
+    }
+}
+
+#[cfg(not(feature = "drag-drop"))]
+fn drag_drop(_step: WPARAM, _l: LPARAM, _callback: &Callback, _handle: ControlHandle) {
+}
+
+/// The keys held when files are dropped on a window that accepts files
+#[cfg(feature = "drag-drop")]
+fn drop_files_keys() -> u32 {
+    crate::controls::current_drag_keys()
+}
+
+#[cfg(not(feature = "drag-drop"))]
+fn drop_files_keys() -> u32 {
+    0
+}
+
+#[cfg(feature = "list-view")]
+fn list_view_header(change: WPARAM, column: LPARAM, callback: &Callback, handle: ControlHandle) {
+    match change {
+        LIST_VIEW_COLUMN_RESIZED => {
+            let data = EventData::OnListViewItemIndex { row_index: 0, column_index: column as usize };
+            callback(Event::OnListViewColumnResized, data, handle);
+        },
+        _ => callback(Event::OnListViewColumnReordered, NO_DATA, handle),
+    }
+}
+
+#[cfg(not(feature = "list-view"))]
+fn list_view_header(_change: WPARAM, _column: LPARAM, _callback: &Callback, _handle: ControlHandle) {
+}
+
+/// Return the handle sent with `OnMenuOpening`. Popup menus are identified using their menu data.
+#[cfg(feature = "menu")]
+unsafe fn menu_opening_handle(hwnd: HWND, menu: HMENU) -> ControlHandle {
+    use crate::win32::menu::is_popup_menu;
+
+    match is_popup_menu(menu) {
+        true => ControlHandle::PopMenu(hwnd, menu),
+        false => ControlHandle::Menu(ptr::null_mut(), menu)
+    }
+}
+
+#[cfg(not(feature = "menu"))]
+unsafe fn menu_opening_handle(_hwnd: HWND, menu: HMENU) -> ControlHandle {
+    ControlHandle::Menu(ptr::null_mut(), menu)
+}
+
+/// Sends `OnDatePickerTimeChanged` if the time part of a date picker value was changed by the user
+#[cfg(feature = "datetime-picker")]
+unsafe fn datetimepick_time_changed(m: u32, notif_raw: *const NMHDR, callback: &Callback) {
+    use winapi::um::commctrl::{DTN_DATETIMECHANGE, NMDATETIMECHANGE, GDT_VALID};
+    use crate::controls::{dtp_time_key, dtp_store_time_key, dtp_load_time_key};
+
+    if m != DTN_DATETIMECHANGE {
+        return;
+    }
+
+    let data = &*(notif_raw as *const NMDATETIMECHANGE);
+    let handle = data.nmhdr.hwndFrom;
+    let key = match data.dwFlags as LRESULT == GDT_VALID {
+        true => Some(dtp_time_key(&data.st)),
+        false => None
+    };
+
+    let previous = dtp_load_time_key(handle);
+    dtp_store_time_key(handle, key);
+
+    if let (Some(previous), Some(key)) = (previous, key) {
+        if previous != key {
+            callback(Event::OnDatePickerTimeChanged, NO_DATA, ControlHandle::Hwnd(handle));
+        }
+    }
+}
+
+#[cfg(not(feature = "datetime-picker"))]
+unsafe fn datetimepick_time_changed(_m: u32, _notif_raw: *const NMHDR, _callback: &Callback) {
+}
+
 fn tabs_commands(m: u32) -> Event {
     use winapi::um::commctrl::{TCN_SELCHANGE, TCN_SELCHANGING};
     match m {
@@ -809,7 +1255,7 @@ fn track_commands(m: u32) -> Event {
 fn tree_commands(m: u32) -> Event {
     use winapi::um::commctrl::{
         NM_CLICK, NM_DBLCLK, NM_KILLFOCUS, NM_RCLICK, NM_SETFOCUS, TVN_BEGINLABELEDITW,
-        TVN_DELETEITEMW, TVN_ENDLABELEDITW, TVN_ITEMCHANGEDW, TVN_ITEMEXPANDEDW, TVN_SELCHANGEDW,
+        TVN_DELETEITEMW, TVN_ENDLABELEDITW, TVN_ITEMCHANGEDW, TVN_ITEMEXPANDEDW, TVN_ITEMEXPANDINGW, TVN_SELCHANGEDW,
     };
 
     match m {
@@ -820,6 +1266,7 @@ fn tree_commands(m: u32) -> Event {
         NM_RCLICK => Event::OnTreeViewRightClick,
         TVN_DELETEITEMW => Event::OnTreeItemDelete,
         TVN_ITEMEXPANDEDW => Event::OnTreeItemExpanded,
+        TVN_ITEMEXPANDINGW => Event::OnTreeItemExpanding,
         TVN_SELCHANGEDW => Event::OnTreeItemSelectionChanged,
         TVN_ITEMCHANGEDW => Event::OnTreeItemChanged,
         TVN_BEGINLABELEDITW => Event::OnTreeViewBeginItemEdit,
@@ -849,6 +1296,28 @@ fn list_view_commands(m: u32) -> Event {
     }
 }
 
+/// Raise `OnButtonClick` on the default button (Enter) or the cancel button (Escape) of the window. Returns false if the window has none.
+fn dialog_button_command(hwnd: HWND, m: usize, callback: &Callback) -> bool {
+    use super::dialog_buttons::{DialogButton, dialog_button, can_click};
+
+    let role = match m as i32 {
+        IDOK => DialogButton::Default,
+        IDCANCEL => DialogButton::Cancel,
+        _ => { return false; }
+    };
+
+    match dialog_button(hwnd, role) {
+        Some(button) => {
+            if can_click(button) {
+                callback(Event::OnButtonClick, NO_DATA, ControlHandle::Hwnd(button));
+            }
+
+            true
+        },
+        None => false
+    }
+}
+
 fn no_class_name_commands(m: usize) -> Event {
     match m as i32 {
         IDOK => Event::OnKeyEnter,
@@ -862,7 +1331,7 @@ fn tree_data(m: u32, notif_raw: *const NMHDR) -> EventData {
     use crate::{ExpandState, TreeItem, TreeItemAction, TreeItemState};
     use winapi::um::commctrl::{
         NMTREEVIEWW, NMTVDISPINFOW, NMTVITEMCHANGE, TVE_COLLAPSE, TVE_EXPAND, TVN_DELETEITEMW,
-        TVN_ENDLABELEDITW, TVN_ITEMCHANGEDW, TVN_ITEMEXPANDEDW, TVN_SELCHANGEDW,
+        TVN_ENDLABELEDITW, TVN_ITEMCHANGEDW, TVN_ITEMEXPANDEDW, TVN_ITEMEXPANDINGW, TVN_SELCHANGEDW,
     };
 
     match m {
@@ -871,7 +1340,7 @@ fn tree_data(m: u32, notif_raw: *const NMHDR) -> EventData {
             let item = TreeItem { handle: data.itemOld.hItem };
             EventData::OnTreeItemDelete(item)
         },
-        TVN_ITEMEXPANDEDW => {
+        TVN_ITEMEXPANDEDW | TVN_ITEMEXPANDINGW => {
             let data = unsafe { &*(notif_raw as *const NMTREEVIEWW) };
             let item = TreeItem { handle: data.itemNew.hItem };
 
@@ -977,6 +1446,31 @@ fn list_view_data(_m: u32, _notif_raw: *const NMHDR) -> EventData {
     NO_DATA
 }
 
+#[cfg(feature="list-view")] const LIST_VIEW_COLUMN_REORDERED: WPARAM = 0;
+#[cfg(feature="list-view")] const LIST_VIEW_COLUMN_RESIZED: WPARAM = 1;
+
+/// The header notifications are sent before the list view applies the new order or the new width,
+/// so the event is posted back to the list view and raised once the change is done.
+#[cfg(feature="list-view")]
+unsafe fn list_view_header_notify(code: u32, notif_raw: *const NMHDR) {
+    use winapi::um::commctrl::{NMHEADERW, HDN_ENDDRAG, HDN_ENDTRACKW, HDN_DIVIDERDBLCLICKW};
+    use winapi::um::winuser::{GetParent, PostMessageW};
+
+    let change = match code {
+        HDN_ENDDRAG => LIST_VIEW_COLUMN_REORDERED,
+        HDN_ENDTRACKW | HDN_DIVIDERDBLCLICKW => LIST_VIEW_COLUMN_RESIZED,
+        _ => { return; }
+    };
+
+    let data = &*(notif_raw as *const NMHEADERW);
+    let list_view = GetParent(data.hdr.hwndFrom);
+    PostMessageW(list_view, NWG_LIST_VIEW_HEADER, change, data.iItem as LPARAM);
+}
+
+#[cfg(not(feature="list-view"))]
+unsafe fn list_view_header_notify(_code: u32, _notif_raw: *const NMHDR) {
+}
+
 
 unsafe fn static_commands(handle: HWND, m: u16) -> Event {
     use winapi::um::winuser::{STN_CLICKED, STN_DBLCLK, STM_GETIMAGE, IMAGE_BITMAP, IMAGE_ICON, IMAGE_CURSOR};
@@ -1034,12 +1528,19 @@ unsafe fn handle_default_notify_callback<'a>(notif_raw: *const NMHDR, callback:
     let code = notif.code;
 
     match &class_name as &str {
-        "SysDateTimePick32" => callback(datetimepick_commands(code), NO_DATA, handle),
+        "SysDateTimePick32" => {
+            callback(datetimepick_commands(code), NO_DATA, handle);
+            datetimepick_time_changed(code, notif_raw, callback);
+        },
+        "Button" => callback(button_notify_commands(code), NO_DATA, handle),
         "SysTabControl32" => callback(tabs_commands(code), NO_DATA, handle),
         "msctls_trackbar32" => callback(track_commands(code), NO_DATA, handle),
         winapi::um::commctrl::WC_TREEVIEW => callback(tree_commands(code), tree_data(code, notif_raw), handle),
         winapi::um::commctrl::WC_LISTVIEW => callback(list_view_commands(code), list_view_data(code, notif_raw), handle),
-        _ => {}
+        "SysHeader32" => list_view_header_notify(code, notif_raw),
+        _ => if let Some((evt, data)) = super::custom_control::notify_event(&class_name, notif) {
+            callback(evt, data, handle);
+        }
     }
 }
 
@@ -1,7 +1,7 @@
 use super::base_helper::{to_utf16, from_utf16};
 use super::high_dpi;
 use winapi::shared::windef::{HFONT, HWND, HMENU};
-use winapi::shared::minwindef::{UINT, WPARAM, LPARAM, LRESULT};
+use winapi::shared::minwindef::{UINT, DWORD, WPARAM, LPARAM, LRESULT};
 use winapi::um::winuser::WM_USER;
 use winapi::ctypes::c_int;
 use std::{ptr, mem};
@@ -14,6 +14,35 @@ pub const NWG_INIT: UINT = WM_USER + 101;
 pub const NWG_TRAY: UINT = WM_USER + 102;
 pub const NWG_TIMER_TICK: UINT = WM_USER + 103;
 pub const NWG_TIMER_STOP: UINT = WM_USER + 104;
+pub const NWG_LOG_APPEND: UINT = WM_USER + 105;
+pub const NWG_DATE_RANGE_CHANGED: UINT = WM_USER + 106;
+pub const NWG_WINDOW_TRAY: UINT = WM_USER + 107;
+pub const NWG_NETWORK_CHANGE: UINT = WM_USER + 108;
+pub const NWG_ELEVATION_RESULT: UINT = WM_USER + 109;
+pub const NWG_REGISTRY_CHANGE: UINT = WM_USER + 110;
+pub const NWG_PROCESS_EXITED: UINT = WM_USER + 111;
+pub const NWG_PRESENT_READY: UINT = WM_USER + 112;
+pub const NWG_COLOR_PICKED: UINT = WM_USER + 113;
+pub const NWG_FILE_PICKED: UINT = WM_USER + 114;
+pub const NWG_FILE_OPERATION_DONE: UINT = WM_USER + 115;
+pub const NWG_USER_IDLE: UINT = WM_USER + 116;
+pub const NWG_USER_RETURNED: UINT = WM_USER + 117;
+pub const NWG_RUBBER_BAND: UINT = WM_USER + 118;
+pub const NWG_VIEWPORT_CHANGED: UINT = WM_USER + 119;
+pub const NWG_RULER_GUIDE: UINT = WM_USER + 120;
+pub const NWG_DESIGN_SURFACE: UINT = WM_USER + 121;
+pub const NWG_LIST_VIEW_HEADER: UINT = WM_USER + 122;
+pub const NWG_ANIMATION_END: UINT = WM_USER + 123;
+pub const NWG_CHECK_GROUP_CHANGED: UINT = WM_USER + 124;
+pub const NWG_LIST_VIEW_SCROLLED: UINT = WM_USER + 125;
+pub const NWG_LIST_VIEW_END: UINT = WM_USER + 126;
+pub const NWG_SEARCH_PREFIX: UINT = WM_USER + 127;
+pub const NWG_ASYNC_WAKE: UINT = WM_USER + 128;
+pub const NWG_LAYOUT_MEASURE: UINT = WM_USER + 129;
+pub const NWG_DRAG_DROP: UINT = WM_USER + 130;
+pub const NWG_PRINT_PREVIEW_PAGE: UINT = WM_USER + 131;
+pub const NWG_MOUSE_ENTER: UINT = WM_USER + 132;
+pub const NWG_SHORTCUT: UINT = WM_USER + 133;
 
 
 /// Returns the class info of a hwnd handle
@@ -232,16 +261,94 @@ pub fn get_style(handle: HWND) -> UINT {
     get_window_long(handle, GWL_STYLE) as UINT
 }
 
-#[cfg(any(feature = "list-view", feature = "progress-bar"))]
 pub fn set_style(handle: HWND, style: u32) {
     use ::winapi::um::winuser::GWL_STYLE;
     set_window_long(handle, GWL_STYLE, style as usize);
 }
 
+/// Draw a background bitmap in the client area of a window. The area that is not covered by the bitmap is filled with `fill`.
+pub unsafe fn draw_background_bitmap(hwnd: HWND, dc: winapi::shared::windef::HDC, bitmap: winapi::shared::windef::HBITMAP, mode: crate::BackgroundMode, fill: winapi::shared::windef::HBRUSH) {
+    use winapi::um::winuser::{GetClientRect, FillRect};
+    use winapi::um::wingdi::{CreateCompatibleDC, DeleteDC, SelectObject, GetObjectW, BitBlt, StretchBlt, SetStretchBltMode, SetBrushOrgEx,
+      BITMAP, SRCCOPY, HALFTONE};
+    use winapi::shared::windef::RECT;
+    use crate::BackgroundMode;
+
+    let mut client: RECT = mem::zeroed();
+    GetClientRect(hwnd, &mut client);
+    let (width, height) = (client.right, client.bottom);
+
+    let mut info: BITMAP = mem::zeroed();
+    if GetObjectW(bitmap as _, mem::size_of::<BITMAP>() as c_int, &mut info as *mut BITMAP as _) == 0 {
+        FillRect(dc, &client, fill);
+        return;
+    }
+
+    let (bw, bh) = (info.bmWidth, info.bmHeight.abs());
+    if bw == 0 || bh == 0 {
+        FillRect(dc, &client, fill);
+        return;
+    }
+
+    let src = CreateCompatibleDC(dc);
+    let old = SelectObject(src, bitmap as _);
+
+    match mode {
+        BackgroundMode::Tile => {
+            let mut y = 0;
+            while y < height {
+                let mut x = 0;
+                while x < width {
+                    BitBlt(dc, x, y, bw, bh, src, 0, 0, SRCCOPY);
+                    x += bw;
+                }
+                y += bh;
+            }
+        },
+        BackgroundMode::Center => {
+            FillRect(dc, &client, fill);
+            BitBlt(dc, (width - bw) / 2, (height - bh) / 2, bw, bh, src, 0, 0, SRCCOPY);
+        },
+        BackgroundMode::Stretch => {
+            SetStretchBltMode(dc, HALFTONE);
+            SetBrushOrgEx(dc, 0, 0, ptr::null_mut());
+            StretchBlt(dc, 0, 0, width, height, src, 0, 0, bw, bh, SRCCOPY);
+        },
+        BackgroundMode::Fit => {
+            FillRect(dc, &client, fill);
+
+            // Use the smallest scale so that the whole bitmap is visible
+            let (w, h) = match (width as i64) * (bh as i64) < (height as i64) * (bw as i64) {
+                true => (width, ((width as i64 * bh as i64) / bw as i64) as i32),
+                false => (((height as i64 * bw as i64) / bh as i64) as i32, height),
+            };
+
+            SetStretchBltMode(dc, HALFTONE);
+            SetBrushOrgEx(dc, 0, 0, ptr::null_mut());
+            StretchBlt(dc, (width - w) / 2, (height - h) / 2, w, h, src, 0, 0, bw, bh, SRCCOPY);
+        }
+    }
+
+    SelectObject(src, old);
+    DeleteDC(src);
+}
+
 pub fn send_message(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
     unsafe { ::winapi::um::winuser::SendMessageW(hwnd, msg, w, l) }
 }
 
+/// Change the UIPI message filter of a window. `action` is one of MSGFLT_RESET (0), MSGFLT_ALLOW (1), MSGFLT_DISALLOW (2).
+pub unsafe fn change_message_filter(hwnd: HWND, msg: UINT, action: DWORD) -> bool {
+    use winapi::um::winuser::{ChangeWindowMessageFilterEx, CHANGEFILTERSTRUCT};
+
+    let mut filter = CHANGEFILTERSTRUCT {
+        cbSize: mem::size_of::<CHANGEFILTERSTRUCT>() as DWORD,
+        ExtStatus: 0
+    };
+
+    ChangeWindowMessageFilterEx(hwnd, msg, action, &mut filter) != 0
+}
+
 pub fn post_message(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) {
     unsafe { ::winapi::um::winuser::PostMessageW(hwnd, msg, w, l) };
 }
@@ -277,10 +384,15 @@ pub unsafe fn set_window_text<'a>(handle: HWND, text: &'a str) {
 }
 
 pub unsafe fn set_window_position(handle: HWND, x: i32, y: i32) {
+    let (x, y) = high_dpi::logical_to_physical_for(handle, x, y);
+    set_window_physical_position(handle, x, y);
+}
+
+/// Moves the window in the client area of its parent, in physical pixels
+pub unsafe fn set_window_physical_position(handle: HWND, x: i32, y: i32) {
     use winapi::um::winuser::SetWindowPos;
     use winapi::um::winuser::{SWP_NOZORDER, SWP_NOSIZE, SWP_NOACTIVATE, SWP_NOOWNERZORDER};
 
-    let (x, y) = high_dpi::logical_to_physical(x, y);
     SetWindowPos(handle, ptr::null_mut(), x as c_int, y as c_int, 0, 0, SWP_NOZORDER|SWP_NOSIZE|SWP_NOACTIVATE|SWP_NOOWNERZORDER);
 }
 
@@ -298,6 +410,12 @@ pub unsafe fn set_window_after(handle: HWND, after: Option<HWND>) {
 }
 
 pub unsafe fn get_window_position(handle: HWND) -> (i32, i32) {
+    let (x, y) = get_window_physical_position(handle);
+    high_dpi::physical_to_logical_for(handle, x, y)
+}
+
+/// Returns the position of the window in the client area of its parent, in physical pixels
+pub unsafe fn get_window_physical_position(handle: HWND) -> (i32, i32) {
     use winapi::um::winuser::{GetWindowRect, ScreenToClient, GetParent};
     use winapi::shared::windef::{RECT, POINT};
     
@@ -305,23 +423,27 @@ pub unsafe fn get_window_position(handle: HWND) -> (i32, i32) {
     GetWindowRect(handle, &mut r);
 
     let parent = GetParent(handle);
-    let (x, y) = if !parent.is_null() {
+    if !parent.is_null() {
         let mut pt = POINT{x: r.left, y: r.top};
         ScreenToClient(parent, &mut pt);
         (pt.x as i32, pt.y as i32)
     } else {
         (r.left as i32, r.top as i32)
-    };
-
-    high_dpi::physical_to_logical(x, y)
+    }
 }
 
 pub unsafe fn set_window_size(handle: HWND, w: u32, h: u32, fix: bool) {
+    let (w, h) = high_dpi::logical_to_physical_for(handle, w as i32, h as i32);
+    set_window_physical_size(handle, w as u32, h as u32, fix);
+}
+
+/// Resizes the window, in physical pixels. See `set_window_size` for `fix`.
+pub unsafe fn set_window_physical_size(handle: HWND, w: u32, h: u32, fix: bool) {
     use winapi::um::winuser::{SetWindowPos, AdjustWindowRectEx, GetWindowLongW};
     use winapi::um::winuser::{SWP_NOZORDER, SWP_NOMOVE, SWP_NOACTIVATE, SWP_NOCOPYBITS, GWL_STYLE, GWL_EXSTYLE, SWP_NOOWNERZORDER};
     use winapi::shared::windef::RECT;
 
-    let (mut w, mut h) = high_dpi::logical_to_physical(w as i32, h as i32);
+    let (mut w, mut h) = (w as i32, h as i32);
 
     if fix {
         let flags = GetWindowLongW(handle, GWL_STYLE) as u32;
@@ -336,15 +458,158 @@ pub unsafe fn set_window_size(handle: HWND, w: u32, h: u32, fix: bool) {
     SetWindowPos(handle, ptr::null_mut(), 0, 0, w, h, SWP_NOZORDER|SWP_NOMOVE|SWP_NOACTIVATE|SWP_NOCOPYBITS|SWP_NOOWNERZORDER);
 }
 
+/**
+    Moves and resizes the window in a single call, in physical pixels. The size is the size of the client area if `fix` is true
+    (see `set_window_size`). Used by the animations to move a window without redrawing it twice per frame.
+*/
+pub unsafe fn set_window_physical_rect(handle: HWND, x: i32, y: i32, w: u32, h: u32, fix: bool) {
+    use winapi::um::winuser::{SetWindowPos, AdjustWindowRectEx, GetWindowLongW};
+    use winapi::um::winuser::{SWP_NOZORDER, SWP_NOACTIVATE, SWP_NOCOPYBITS, GWL_STYLE, GWL_EXSTYLE, SWP_NOOWNERZORDER};
+    use winapi::shared::windef::RECT;
+
+    let (mut w, mut h) = (w as i32, h as i32);
+
+    if fix {
+        let flags = GetWindowLongW(handle, GWL_STYLE) as u32;
+        let ex_flags = GetWindowLongW(handle, GWL_EXSTYLE) as u32;
+        let mut rect = RECT {left: 0, top: 0, right: w, bottom: h};
+        AdjustWindowRectEx(&mut rect, flags, 0, ex_flags);
+
+        w = rect.right - rect.left;
+        h = rect.bottom  - rect.top;
+    }
+
+    SetWindowPos(handle, ptr::null_mut(), x as c_int, y as c_int, w, h, SWP_NOZORDER|SWP_NOACTIVATE|SWP_NOCOPYBITS|SWP_NOOWNERZORDER);
+}
+
 pub unsafe fn get_window_size(handle: HWND) -> (u32, u32) {
     get_window_size_impl(handle, false)
 }
 
-#[allow(unused)]
 pub unsafe fn get_window_physical_size(handle: HWND) -> (u32, u32) {
     get_window_size_impl(handle, true)
 }
 
+/**
+    Capture the pixels of a window, in physical pixels. Returns `(width, height, pixels)` with the pixels in RGBA.
+    If `full_frame` is true, the visible frame of the window is included, without the invisible resize borders of the system.
+    Otherwise only the client area is captured. The window is drawn even if it is covered by other windows.
+*/
+#[cfg(feature = "clipboard")]
+pub unsafe fn capture_window(handle: HWND, full_frame: bool) -> Option<(u32, u32, Vec<u8>)> {
+    use winapi::um::winuser::{GetWindowRect, GetClientRect, PrintWindow, PW_CLIENTONLY, PW_RENDERFULLCONTENT};
+    use winapi::um::wingdi::{CreateCompatibleDC, CreateDIBSection, SelectObject, DeleteObject, DeleteDC, GdiFlush,
+      BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, RGBQUAD};
+    use winapi::um::dwmapi::{DwmGetWindowAttribute, DWMWA_EXTENDED_FRAME_BOUNDS};
+    use winapi::shared::windef::RECT;
+    use winapi::shared::winerror::S_OK;
+    use std::slice;
+
+    // `printed` is the area drawn by PrintWindow, `copied` is the area of the image, relative to `printed`
+    let mut printed: RECT = mem::zeroed();
+    let (copied, flags) = match full_frame {
+        true => {
+            GetWindowRect(handle, &mut printed);
+
+            let mut bounds: RECT = mem::zeroed();
+            let result = DwmGetWindowAttribute(handle, DWMWA_EXTENDED_FRAME_BOUNDS, &mut bounds as *mut RECT as _, mem::size_of::<RECT>() as DWORD);
+            if result != S_OK {
+                bounds = printed;
+            }
+
+            let copied = RECT {
+                left: (bounds.left - printed.left).max(0),
+                top: (bounds.top - printed.top).max(0),
+                right: (bounds.right - printed.left).min(printed.right - printed.left),
+                bottom: (bounds.bottom - printed.top).min(printed.bottom - printed.top),
+            };
+
+            (copied, PW_RENDERFULLCONTENT)
+        },
+        false => {
+            GetClientRect(handle, &mut printed);
+            (printed, PW_CLIENTONLY | PW_RENDERFULLCONTENT)
+        }
+    };
+
+    let (printed_width, printed_height) = (printed.right - printed.left, printed.bottom - printed.top);
+    let (width, height) = (copied.right - copied.left, copied.bottom - copied.top);
+    if width <= 0 || height <= 0 {
+        return None;
+    }
+
+    let header = BITMAPINFOHEADER {
+        biSize: mem::size_of::<BITMAPINFOHEADER>() as DWORD,
+        biWidth: printed_width, biHeight: -printed_height,
+        biPlanes: 1, biBitCount: 32, biCompression: BI_RGB,
+        biSizeImage: 0,
+        biXPelsPerMeter: 0, biYPelsPerMeter: 0,
+        biClrUsed: 0, biClrImportant: 0
+    };
+
+    let bitmap_info = BITMAPINFO {
+        bmiHeader: header,
+        bmiColors: [RGBQUAD { rgbBlue: 0, rgbGreen: 0, rgbRed: 0, rgbReserved: 0 }],
+    };
+
+    let dc = CreateCompatibleDC(ptr::null_mut());
+    let mut bits = ptr::null_mut();
+    let bitmap = CreateDIBSection(dc, &bitmap_info, DIB_RGB_COLORS, &mut bits, ptr::null_mut(), 0);
+    if bitmap.is_null() {
+        DeleteDC(dc);
+        return None;
+    }
+
+    let old = SelectObject(dc, bitmap as _);
+    let printed_ok = PrintWindow(handle, dc, flags) != 0;
+    GdiFlush();
+
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    if printed_ok {
+        let source = slice::from_raw_parts(bits as *const u8, (printed_width * printed_height * 4) as usize);
+        for y in copied.top..copied.bottom {
+            let start = ((y * printed_width + copied.left) * 4) as usize;
+            let row = &source[start..(start + (width * 4) as usize)];
+
+            // BGRX -> RGBA. PrintWindow does not write the alpha channel.
+            for p in row.chunks_exact(4) {
+                pixels.extend_from_slice(&[p[2], p[1], p[0], 255]);
+            }
+        }
+    }
+
+    SelectObject(dc, old);
+    DeleteObject(bitmap as _);
+    DeleteDC(dc);
+
+    match printed_ok {
+        true => Some((width as u32, height as u32, pixels)),
+        false => None
+    }
+}
+
+/// Returns the height (in logical pixels) of the status bar at the bottom of `parent`, or 0 if `parent` has no visible status bar.
+/// The layouts place their children above the status bar.
+pub fn get_status_bar_height(parent: HWND) -> u32 {
+    use winapi::um::winuser::{FindWindowExW, GetWindowRect, WS_VISIBLE};
+    use winapi::shared::windef::RECT;
+
+    let class_name = to_utf16("msctls_statusbar32");
+
+    unsafe {
+        let status_bar = FindWindowExW(parent, ptr::null_mut(), class_name.as_ptr(), ptr::null());
+        if status_bar.is_null() || get_style(status_bar) & WS_VISIBLE == 0 {
+            return 0;
+        }
+
+        let mut r: RECT = mem::zeroed();
+        GetWindowRect(status_bar, &mut r);
+
+        let (_, height) = high_dpi::physical_to_logical_for(parent, 0, r.bottom - r.top);
+        height.max(0) as u32
+    }
+}
+
 unsafe fn get_window_size_impl(handle: HWND, return_physical: bool) -> (u32, u32) {
     use winapi::um::winuser::GetClientRect;
     use winapi::shared::windef::RECT;
@@ -355,7 +620,7 @@ unsafe fn get_window_size_impl(handle: HWND, return_physical: bool) -> (u32, u32
     let (w, h) = if return_physical {
         (r.right, r.bottom)
     } else {
-        high_dpi::physical_to_logical(r.right, r.bottom)
+        high_dpi::physical_to_logical_for(handle, r.right, r.bottom)
     };
 
     (w as u32, h as u32)
@@ -0,0 +1,198 @@
+/*!
+    Locale aware formatting and parsing of numbers, dates and times.
+
+    Every function takes an optional locale. If `None` is used, the current user locale is used.
+*/
+use winapi::um::winnls::{GetNumberFormatEx, NUMBERFMTW};
+use winapi::um::datetimeapi::{GetDateFormatEx, GetTimeFormatEx};
+use winapi::um::minwinbase::SYSTEMTIME;
+use crate::win32::base_helper::{to_utf16, from_utf16};
+use crate::NwgError;
+use super::Locale;
+use std::ptr;
+
+const DATE_SHORTDATE: u32 = 0x01;
+const DATE_LONGDATE: u32 = 0x02;
+const TIME_NOSECONDS: u32 = 0x02;
+
+
+/**
+    Format a number using the locale decimal separator, digit grouping and negative number mode.
+    If `decimals` is `None`, the locale fractional digit count is used.
+
+    ```rust
+    use native_windows_gui as nwg;
+    let fr = nwg::Locale::from_str("fr-FR").unwrap();
+    let text = nwg::locale_format_number(1234.5, Some(2), Some(&fr)); // "1 234,50"
+    ```
+*/
+pub fn locale_format_number(value: f64, decimals: Option<u32>, locale: Option<&Locale>) -> Result<String, NwgError> {
+    if !value.is_finite() {
+        return Err(NwgError::bad_locale("Cannot format a non finite number"));
+    }
+
+    let user;
+    let locale = match locale {
+        Some(l) => l,
+        None => { user = Locale::user(); &user }
+    };
+
+    let decimals = decimals.unwrap_or(locale.fractional_digit().max(0) as u32);
+    let value = to_utf16(&format!("{:.*}", decimals as usize, value));
+    let name = to_utf16(locale.name());
+    let mut decimal_sep = to_utf16(&locale.decimal_separator());
+    let mut thousand_sep = to_utf16(&locale.thousand_separator());
+
+    let format = NUMBERFMTW {
+        NumDigits: decimals,
+        LeadingZero: locale.leading_zeros() as u32,
+        Grouping: parse_grouping(&locale.digit_grouping()),
+        lpDecimalSep: decimal_sep.as_mut_ptr(),
+        lpThousandSep: thousand_sep.as_mut_ptr(),
+        NegativeOrder: locale.negative_number_mode() as u32,
+    };
+
+    unsafe {
+        let size = GetNumberFormatEx(name.as_ptr(), 0, value.as_ptr(), &format, ptr::null_mut(), 0);
+        if size == 0 {
+            return Err(NwgError::bad_locale("Failed to format number"));
+        }
+
+        let mut buffer: Vec<u16> = vec![0; size as usize];
+        GetNumberFormatEx(name.as_ptr(), 0, value.as_ptr(), &format, buffer.as_mut_ptr(), size);
+
+        Ok(from_utf16(&buffer))
+    }
+}
+
+/**
+    Parse a number formatted using the locale rules (ex: "1 234,5" with the french locale).
+    Thousand separators are ignored and both a leading/trailing negative sign or parentheses are accepted.
+*/
+pub fn locale_parse_number(text: &str, locale: Option<&Locale>) -> Result<f64, NwgError> {
+    let user;
+    let locale = match locale {
+        Some(l) => l,
+        None => { user = Locale::user(); &user }
+    };
+
+    parse_number(text, &locale.decimal_separator(), &locale.thousand_separator())
+}
+
+/// Parse a number using the given separators. See `locale_parse_number`.
+pub(crate) fn parse_number(text: &str, decimal_sep: &str, thousand_sep: &str) -> Result<f64, NwgError> {
+    let mut text = text.trim().to_string();
+    let mut negative = false;
+
+    if text.starts_with('(') && text.ends_with(')') && text.len() > 1 {
+        negative = true;
+        text = text[1..text.len()-1].to_string();
+    }
+
+    if !thousand_sep.is_empty() && thousand_sep != decimal_sep {
+        text = text.replace(thousand_sep, "");
+
+        // Some locales use non-breaking spaces as the thousand separator. Users will likely type regular spaces
+        if thousand_sep.chars().all(|c| c.is_whitespace()) {
+            text.retain(|c| !c.is_whitespace());
+        }
+    }
+
+    if !decimal_sep.is_empty() && decimal_sep != "." {
+        text = text.replace(decimal_sep, ".");
+    }
+
+    let mut text = text.trim();
+    if text.ends_with('-') {
+        negative = !negative;
+        text = text[..text.len()-1].trim_end();
+    } else if text.starts_with('-') {
+        negative = !negative;
+        text = text[1..].trim_start();
+    }
+
+    match text.parse::<f64>() {
+        Ok(v) if negative => Ok(-v),
+        Ok(v) => Ok(v),
+        Err(_) => Err(NwgError::bad_locale("Text is not a valid number for the locale"))
+    }
+}
+
+/**
+    Format a date using the locale short date format (ex: 2020-12-31) or long date format (ex: Thursday, December 31, 2020).
+    `month` and `day` are 1-based.
+*/
+pub fn locale_format_date(year: u16, month: u16, day: u16, long: bool, locale: Option<&Locale>) -> Result<String, NwgError> {
+    let time = SYSTEMTIME {
+        wYear: year, wMonth: month, wDayOfWeek: 0, wDay: day,
+        wHour: 0, wMinute: 0, wSecond: 0, wMilliseconds: 0
+    };
+
+    let name = locale.map(|l| to_utf16(l.name()));
+    let name_ptr = name.as_ref().map(|n| n.as_ptr()).unwrap_or(ptr::null());
+    let flags = match long {
+        true => DATE_LONGDATE,
+        false => DATE_SHORTDATE
+    };
+
+    unsafe {
+        let size = GetDateFormatEx(name_ptr, flags, &time, ptr::null(), ptr::null_mut(), 0, ptr::null());
+        if size == 0 {
+            return Err(NwgError::bad_locale("Failed to format date"));
+        }
+
+        let mut buffer: Vec<u16> = vec![0; size as usize];
+        GetDateFormatEx(name_ptr, flags, &time, ptr::null(), buffer.as_mut_ptr(), size, ptr::null());
+
+        Ok(from_utf16(&buffer))
+    }
+}
+
+/**
+    Format a time using the locale time format (ex: 13:45:30 or 1:45:30 PM).
+    If `seconds` is false, the seconds are left out.
+*/
+pub fn locale_format_time(hour: u16, minute: u16, second: u16, seconds: bool, locale: Option<&Locale>) -> Result<String, NwgError> {
+    let time = SYSTEMTIME {
+        wYear: 2000, wMonth: 1, wDayOfWeek: 0, wDay: 1,
+        wHour: hour, wMinute: minute, wSecond: second, wMilliseconds: 0
+    };
+
+    let name = locale.map(|l| to_utf16(l.name()));
+    let name_ptr = name.as_ref().map(|n| n.as_ptr()).unwrap_or(ptr::null());
+    let flags = match seconds {
+        true => 0,
+        false => TIME_NOSECONDS
+    };
+
+    unsafe {
+        let size = GetTimeFormatEx(name_ptr, flags, &time, ptr::null(), ptr::null_mut(), 0);
+        if size == 0 {
+            return Err(NwgError::bad_locale("Failed to format time"));
+        }
+
+        let mut buffer: Vec<u16> = vec![0; size as usize];
+        GetTimeFormatEx(name_ptr, flags, &time, ptr::null(), buffer.as_mut_ptr(), size);
+
+        Ok(from_utf16(&buffer))
+    }
+}
+
+/// Convert a locale digit grouping string (ex: "3;2;0") into the value expected by `NUMBERFMTW` (ex: 32)
+pub(crate) fn parse_grouping(grouping: &str) -> u32 {
+    let mut value = 0;
+    let mut repeat = false;
+    for part in grouping.split(';') {
+        match part.trim().parse::<u32>() {
+            Ok(0) => { repeat = true; break; },
+            Ok(v) => { value = value * 10 + v; },
+            Err(_) => break,
+        }
+    }
+
+    // A grouping that does not end with 0 (ex: "3") means the group is not repeated
+    match repeat {
+        true => value,
+        false => value * 10
+    }
+}
@@ -3,3 +3,6 @@ pub use shared::*;
 
 mod locale;
 pub use locale::*;
+
+mod format;
+pub use format::*;